@@ -1,8 +1,12 @@
 use reqwest::blocking::Client;
+use reqwest::header::CONTENT_ENCODING;
 use reqwest::redirect::Policy;
+use std::io::{Cursor, Read};
 use std::time::Duration;
 use url::Url;
 
+use super::compression;
+use super::transport::{fnv1a, Fixture, Transport};
 use super::types::*;
 use crate::config::Config;
 use crate::error::{RepriseError, Result};
@@ -22,6 +26,12 @@ const ALLOWED_HOSTS: &[&str] = &[
 ];
 
 const DEFAULT_BASE_URL: &str = "https://api.bitrise.io/v0.1";
+
+/// Release Management API path, on the same host as the v0.1 API
+const RELEASE_MANAGEMENT_PATH: &str = "/release-management/v1";
+
+/// Most items the API returns per page; larger limits are fetched in pages
+pub const MAX_PAGE_SIZE: u32 = 50;
 const USER_AGENT: &str = concat!("reprise/", env!("CARGO_PKG_VERSION"));
 
 /// Bitrise API client
@@ -29,12 +39,21 @@ pub struct BitriseClient {
     client: Client,
     token: String,
     base_url: String,
+    transport: Transport,
 }
 
 impl BitriseClient {
     /// Create a new client from configuration
+    ///
+    /// Honors `BITRISE_RECORD` / `BITRISE_REPLAY` (see [`Transport`]). When
+    /// replaying, a missing token is not an error since no request is sent.
     pub fn new(config: &Config) -> Result<Self> {
-        let token = config.require_token()?.to_string();
+        let transport = Transport::from_env();
+        let token = if transport.is_replay() {
+            config.api.token.clone().unwrap_or_default()
+        } else {
+            config.require_token()?.to_string()
+        };
 
         let client = Client::builder()
             .user_agent(USER_AGENT)
@@ -46,6 +65,7 @@ impl BitriseClient {
             client,
             token,
             base_url: DEFAULT_BASE_URL.to_string(),
+            transport,
         })
     }
 
@@ -61,11 +81,11 @@ impl BitriseClient {
             client,
             token: token.into(),
             base_url: DEFAULT_BASE_URL.to_string(),
+            transport: Transport::from_env(),
         })
     }
 
-    /// Create a new client with custom base URL (for testing)
-    #[cfg(test)]
+    /// Create a new client with a custom base URL (e.g. a mock server or proxy)
     pub fn with_base_url(token: impl Into<String>, base_url: impl Into<String>) -> Result<Self> {
         let client = Client::builder()
             .user_agent(USER_AGENT)
@@ -77,25 +97,84 @@ impl BitriseClient {
             client,
             token: token.into(),
             base_url: base_url.into(),
+            transport: Transport::Live,
         })
     }
 
-    /// Make a GET request to the Bitrise API
-    fn get<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T> {
-        let url = format!("{}{path}", self.base_url);
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", &self.token)
-            .send()?;
+    /// Use a specific transport (record/replay) instead of the environment default
+    pub fn with_transport(mut self, transport: Transport) -> Self {
+        self.transport = transport;
+        self
+    }
 
-        let status = response.status();
-        if !status.is_success() {
-            let message = response.text().unwrap_or_default();
-            return Err(RepriseError::api(status.as_u16(), message));
+    /// API root URL, e.g. `https://api.bitrise.io/v0.1`
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Whether responses come from recorded fixtures instead of the network
+    pub fn is_replay(&self) -> bool {
+        self.transport.is_replay()
+    }
+
+    /// Send a request through the configured transport and return the body text.
+    ///
+    /// `key` identifies the request for recording/replay: the API path for
+    /// Bitrise API calls, the full URL for external downloads.
+    fn execute(
+        &self,
+        method: reqwest::Method,
+        url: &str,
+        key: &str,
+        authorized: bool,
+        body: Option<&serde_json::Value>,
+    ) -> Result<String> {
+        let (status, text) = match &self.transport {
+            Transport::Replay(dir) => {
+                let fixture = Transport::load(dir, method.as_str(), key)?;
+                (fixture.status, fixture.body)
+            }
+            Transport::Live | Transport::Record(_) => {
+                let mut request = self.client.request(method.clone(), url);
+                if authorized {
+                    request = request.header("Authorization", &self.token);
+                }
+                if let Some(b) = body {
+                    request = request.json(b);
+                }
+                let response = request.send()?;
+                let status = response.status().as_u16();
+                let text = if (200..300).contains(&status) {
+                    response.text()?
+                } else {
+                    response.text().unwrap_or_default()
+                };
+
+                if let Transport::Record(dir) = &self.transport {
+                    Transport::save(
+                        dir,
+                        &Fixture {
+                            method: method.to_string(),
+                            key: key.to_string(),
+                            status,
+                            body: text.clone(),
+                        },
+                    )?;
+                }
+                (status, text)
+            }
+        };
+
+        if !(200..300).contains(&status) {
+            return Err(RepriseError::api(status, text));
         }
+        Ok(text)
+    }
 
-        let body = response.text()?;
+    /// Make a GET request to the Bitrise API
+    fn get<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let url = format!("{}{path}", self.base_url);
+        let body = self.execute(reqwest::Method::GET, &url, path, true, None)?;
         serde_json::from_str(&body).map_err(|e| {
             RepriseError::Json(e)
         })
@@ -103,15 +182,7 @@ impl BitriseClient {
 
     /// Fetch raw content from a URL (for log files)
     fn get_raw(&self, url: &str) -> Result<String> {
-        let response = self.client.get(url).send()?;
-
-        let status = response.status();
-        if !status.is_success() {
-            let message = response.text().unwrap_or_default();
-            return Err(RepriseError::api(status.as_u16(), message));
-        }
-
-        Ok(response.text()?)
+        self.execute(reqwest::Method::GET, url, url, false, None)
     }
 
     /// Make a POST request to the Bitrise API
@@ -121,20 +192,35 @@ impl BitriseClient {
         body: &B,
     ) -> Result<T> {
         let url = format!("{}{path}", self.base_url);
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", &self.token)
-            .json(body)
-            .send()?;
+        let payload = serde_json::to_value(body)?;
+        let body = self.execute(reqwest::Method::POST, &url, path, true, Some(&payload))?;
+        serde_json::from_str(&body).map_err(RepriseError::Json)
+    }
 
-        let status = response.status();
-        if !status.is_success() {
-            let message = response.text().unwrap_or_default();
-            return Err(RepriseError::api(status.as_u16(), message));
-        }
+    /// Make a PATCH request to the Bitrise API
+    fn patch<T: serde::de::DeserializeOwned, B: serde::Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T> {
+        let url = format!("{}{path}", self.base_url);
+        let payload = serde_json::to_value(body)?;
+        let body = self.execute(reqwest::Method::PATCH, &url, path, true, Some(&payload))?;
+        serde_json::from_str(&body).map_err(RepriseError::Json)
+    }
 
-        let body = response.text()?;
+    /// Make a request to the Release Management API, which lives beside
+    /// rather than under the v0.1 API
+    fn release_request<T: serde::de::DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<T> {
+        let key = format!("{RELEASE_MANAGEMENT_PATH}{path}");
+        let root = self.base_url.strip_suffix("/v0.1").unwrap_or(&self.base_url);
+        let url = format!("{root}{key}");
+        let body = self.execute(method, &url, &key, true, body)?;
         serde_json::from_str(&body).map_err(RepriseError::Json)
     }
 
@@ -147,13 +233,50 @@ impl BitriseClient {
         self.get("/me")
     }
 
+    /// Fingerprint of the API token, for keying per-account cache entries
+    /// without writing the token itself to disk
+    ///
+    /// Stable across runs and builds of reprise, so the cache entries it
+    /// keys survive upgrades.
+    pub fn token_fingerprint(&self) -> String {
+        format!("{:016x}", fnv1a(&self.token))
+    }
+
+    /// List the organizations the current user belongs to
+    pub fn list_organizations(&self) -> Result<OrganizationListResponse> {
+        self.get("/organizations")
+    }
+
+    /// List the members of an organization and their roles
+    pub fn list_organization_members(&self, org_slug: &str) -> Result<MemberListResponse> {
+        self.get(&format!("/organizations/{org_slug}/members"))
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // App Operations
     // ─────────────────────────────────────────────────────────────────────────
 
-    /// List all accessible apps
+    /// List all accessible apps, following pages until `limit` apps
     pub fn list_apps(&self, limit: u32) -> Result<AppListResponse> {
-        self.get(&format!("/apps?limit={limit}"))
+        let (data, paging) = collect_pages(limit, |page_limit, next| {
+            let page = self.list_apps_page(page_limit, next)?;
+            Ok((page.data, page.paging))
+        })?;
+        Ok(AppListResponse { data, paging })
+    }
+
+    /// List apps starting at a paging cursor (`paging.next` of a previous page)
+    pub fn list_apps_page(&self, limit: u32, next: Option<&str>) -> Result<AppListResponse> {
+        match next {
+            Some(next) => {
+                let query: String = url::form_urlencoded::Serializer::new(String::new())
+                    .append_pair("limit", &limit.to_string())
+                    .append_pair("next", next)
+                    .finish();
+                self.get(&format!("/apps?{query}"))
+            }
+            None => self.get(&format!("/apps?limit={limit}")),
+        }
     }
 
     /// Get a specific app by slug
@@ -172,24 +295,81 @@ impl BitriseClient {
             .find(|app| app.title.to_lowercase().contains(&name_lower)))
     }
 
+    /// Get an app's bitrise.yml as stored on Bitrise
+    pub fn get_bitrise_yml(&self, app_slug: &str) -> Result<String> {
+        let path = format!("/apps/{app_slug}/bitrise.yml");
+        let url = format!("{}{path}", self.base_url);
+        self.execute(reqwest::Method::GET, &url, &path, true, None)
+    }
+
+    /// Get the bitrise.yml a build ran with
+    pub fn get_build_bitrise_yml(&self, app_slug: &str, build_slug: &str) -> Result<String> {
+        let path = format!("/apps/{app_slug}/builds/{build_slug}/bitrise.yml");
+        let url = format!("{}{path}", self.base_url);
+        self.execute(reqwest::Method::GET, &url, &path, true, None)
+    }
+
+    /// List the users with access to an app and their roles
+    pub fn list_app_members(&self, app_slug: &str) -> Result<MemberListResponse> {
+        self.get(&format!("/apps/{app_slug}/members"))
+    }
+
+    /// List the branches of an app's repository that Bitrise knows about
+    pub fn list_branches(&self, app_slug: &str) -> Result<BranchListResponse> {
+        self.get(&format!("/apps/{app_slug}/branches"))
+    }
+
+    /// List the stacks available to an app
+    pub fn list_stacks(&self, app_slug: &str) -> Result<StackListResponse> {
+        self.get(&format!("/apps/{app_slug}/stacks"))
+    }
+
+    /// List the machine types available to an app
+    pub fn list_machine_types(&self, app_slug: &str) -> Result<MachineTypeListResponse> {
+        self.get(&format!("/apps/{app_slug}/machine_types"))
+    }
+
+    /// List the addons of an app, enabled or not
+    pub fn list_addons(&self, app_slug: &str) -> Result<AddonListResponse> {
+        self.get(&format!("/apps/{app_slug}/addons"))
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // Build Operations
     // ─────────────────────────────────────────────────────────────────────────
 
-    /// List builds for an app with optional filters
+    /// List builds for an app with optional filters, following pages until
+    /// `limit` builds
     pub fn list_builds(
         &self,
         app_slug: &str,
-        status: Option<i32>,
+        status: Option<BuildStatus>,
         branch: Option<&str>,
         workflow: Option<&str>,
         limit: u32,
+    ) -> Result<BuildListResponse> {
+        let (data, paging) = collect_pages(limit, |page_limit, next| {
+            let page = self.list_builds_page(app_slug, status, branch, workflow, page_limit, next)?;
+            Ok((page.data, page.paging))
+        })?;
+        Ok(BuildListResponse { data, paging })
+    }
+
+    /// List one page of builds, continuing from a `paging.next` cursor
+    pub fn list_builds_page(
+        &self,
+        app_slug: &str,
+        status: Option<BuildStatus>,
+        branch: Option<&str>,
+        workflow: Option<&str>,
+        limit: u32,
+        next: Option<&str>,
     ) -> Result<BuildListResponse> {
         // Use proper URL encoding for query parameters
         let mut params: Vec<(&str, String)> = vec![("limit", limit.to_string())];
 
         if let Some(s) = status {
-            params.push(("status", s.to_string()));
+            params.push(("status", s.code().to_string()));
         }
         if let Some(b) = branch {
             params.push(("branch", b.to_string()));
@@ -197,6 +377,9 @@ impl BitriseClient {
         if let Some(w) = workflow {
             params.push(("workflow", w.to_string()));
         }
+        if let Some(n) = next {
+            params.push(("next", n.to_string()));
+        }
 
         let query: String = url::form_urlencoded::Serializer::new(String::new())
             .extend_pairs(params)
@@ -253,16 +436,65 @@ impl BitriseClient {
     ///
     /// Validates that the URL is from an allowed Bitrise domain to prevent SSRF.
     pub fn fetch_raw_log(&self, log_url: &str) -> Result<String> {
+        let mut bytes = Vec::new();
+        self.open_raw_log(log_url)?.read_to_end(&mut bytes)?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// Open the raw log for streaming, without reading it into memory
+    ///
+    /// Validates the URL like [`fetch_raw_log`](Self::fetch_raw_log).
+    /// Gzip/deflate-compressed archives are decompressed. Recorded fixtures
+    /// hold the decompressed text.
+    pub fn open_raw_log(&self, log_url: &str) -> Result<Box<dyn Read + Send>> {
         self.validate_external_url(log_url, "Log")?;
-        self.get_raw(log_url)
+        if self.transport.is_replay() {
+            return Ok(Box::new(Cursor::new(self.get_raw(log_url)?)));
+        }
+
+        let response = self.client.get(log_url).send()?;
+        let status = response.status().as_u16();
+        if !(200..300).contains(&status) {
+            return Err(RepriseError::api(status, response.text().unwrap_or_default()));
+        }
+        let encoding = response
+            .headers()
+            .get(CONTENT_ENCODING)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let mut log = compression::decode(Box::new(response), encoding.as_deref())?;
+
+        if let Transport::Record(dir) = &self.transport {
+            let mut bytes = Vec::new();
+            log.read_to_end(&mut bytes)?;
+            let body = String::from_utf8_lossy(&bytes).into_owned();
+            Transport::save(
+                dir,
+                &Fixture {
+                    method: "GET".to_string(),
+                    key: log_url.to_string(),
+                    status,
+                    body: body.clone(),
+                },
+            )?;
+            return Ok(Box::new(Cursor::new(body.into_bytes())));
+        }
+        Ok(log)
     }
 
     /// Get the full log for a build
     pub fn get_full_log(&self, app_slug: &str, build_slug: &str) -> Result<String> {
         let log_response = self.get_build_log(app_slug, build_slug)?;
+        let mut bytes = Vec::new();
+        self.open_log(&log_response)?.read_to_end(&mut bytes)?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
 
+    /// Open the log described by a log response: the raw log when Bitrise
+    /// provides a download URL, otherwise the joined chunks
+    pub fn open_log(&self, log_response: &LogResponse) -> Result<Box<dyn Read + Send>> {
         match log_response.expiring_raw_log_url {
-            Some(url) => self.fetch_raw_log(&url),
+            Some(ref url) => self.open_raw_log(url),
             None => {
                 // Fall back to log chunks if no raw URL available
                 let log = log_response
@@ -271,7 +503,7 @@ impl BitriseClient {
                     .map(|c| c.chunk.as_str())
                     .collect::<Vec<_>>()
                     .join("");
-                Ok(log)
+                Ok(Box::new(Cursor::new(log.into_bytes())))
             }
         }
     }
@@ -301,6 +533,72 @@ impl BitriseClient {
         ))
     }
 
+    /// Turn an artifact's public install page on or off
+    pub fn set_artifact_public_page(
+        &self,
+        app_slug: &str,
+        build_slug: &str,
+        artifact_slug: &str,
+        enabled: bool,
+    ) -> Result<ArtifactResponse> {
+        let body = serde_json::json!({ "is_public_page_enabled": enabled });
+        self.patch(
+            &format!("/apps/{app_slug}/builds/{build_slug}/artifacts/{artifact_slug}"),
+            &body,
+        )
+    }
+
+    /// Register an artifact on a build, returning where to upload its file
+    pub fn create_artifact_upload(
+        &self,
+        app_slug: &str,
+        build_slug: &str,
+        title: &str,
+        file_size_bytes: u64,
+        artifact_type: &str,
+    ) -> Result<ArtifactUploadResponse> {
+        let body = serde_json::json!({
+            "title": title,
+            "file_size_bytes": file_size_bytes,
+            "artifact_type": artifact_type,
+        });
+        self.post(&format!("/apps/{app_slug}/builds/{build_slug}/artifacts"), &body)
+    }
+
+    /// Upload a file to the URL from [`create_artifact_upload`](Self::create_artifact_upload)
+    ///
+    /// Validates that the URL is from an allowed host, like downloads.
+    pub fn upload_artifact_file(&self, upload_url: &str, path: &std::path::Path) -> Result<()> {
+        self.validate_external_url(upload_url, "Artifact upload")?;
+
+        if self.transport.is_replay() {
+            return Err(RepriseError::InvalidArgument(
+                "Artifact uploads are not available in replay mode".to_string(),
+            ));
+        }
+
+        let file = std::fs::File::open(path)?;
+        let response = self.client.put(upload_url).body(file).send()?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let message = response.text().unwrap_or_default();
+            return Err(RepriseError::api(status.as_u16(), message));
+        }
+        Ok(())
+    }
+
+    /// Mark an uploaded artifact as complete so it shows on the build
+    pub fn finish_artifact_upload(
+        &self,
+        app_slug: &str,
+        build_slug: &str,
+        artifact_slug: &str,
+    ) -> Result<ArtifactResponse> {
+        let path = format!("/apps/{app_slug}/builds/{build_slug}/artifacts/{artifact_slug}");
+        self.post(&format!("{path}/finish_upload"), &serde_json::json!({}))
+    }
+
     /// Download an artifact to a file
     ///
     /// Validates that the URL is from an allowed host to prevent SSRF attacks.
@@ -308,6 +606,12 @@ impl BitriseClient {
         // Validate URL is from allowed hosts (SSRF protection)
         self.validate_external_url(url, "Artifact")?;
 
+        if self.transport.is_replay() {
+            return Err(RepriseError::InvalidArgument(
+                "Artifact downloads are not available in replay mode".to_string(),
+            ));
+        }
+
         let response = self.client.get(url).send()?;
 
         let status = response.status();
@@ -350,22 +654,42 @@ impl BitriseClient {
     // Pipeline Operations
     // ─────────────────────────────────────────────────────────────────────────
 
-    /// List pipelines for an app with optional filters
+    /// List pipelines for an app with optional filters, following pages
+    /// until `limit` pipelines
     pub fn list_pipelines(
         &self,
         app_slug: &str,
-        status: Option<i32>,
+        status: Option<BuildStatus>,
         branch: Option<&str>,
         limit: u32,
+    ) -> Result<PipelineListResponse> {
+        let (data, paging) = collect_pages(limit, |page_limit, next| {
+            let page = self.list_pipelines_page(app_slug, status, branch, page_limit, next)?;
+            Ok((page.data, page.paging))
+        })?;
+        Ok(PipelineListResponse { data, paging })
+    }
+
+    /// List one page of pipelines, continuing from a `paging.next` cursor
+    pub fn list_pipelines_page(
+        &self,
+        app_slug: &str,
+        status: Option<BuildStatus>,
+        branch: Option<&str>,
+        limit: u32,
+        next: Option<&str>,
     ) -> Result<PipelineListResponse> {
         let mut params: Vec<(&str, String)> = vec![("limit", limit.to_string())];
 
         if let Some(s) = status {
-            params.push(("status", s.to_string()));
+            params.push(("status", s.code().to_string()));
         }
         if let Some(b) = branch {
             params.push(("branch", b.to_string()));
         }
+        if let Some(n) = next {
+            params.push(("next", n.to_string()));
+        }
 
         let query: String = url::form_urlencoded::Serializer::new(String::new())
             .extend_pairs(params)
@@ -403,6 +727,8 @@ impl BitriseClient {
             build_params["branch"] = serde_json::json!(branch);
         }
 
+        add_source_params(&mut build_params, &params.source);
+
         if !params.environments.is_empty() {
             let envs: Vec<_> = params
                 .environments
@@ -502,6 +828,8 @@ impl BitriseClient {
             build_params["branch"] = serde_json::json!(branch);
         }
 
+        add_source_params(&mut build_params, &params.source);
+
         if let Some(ref msg) = params.commit_message {
             build_params["commit_message"] = serde_json::json!(msg);
         }
@@ -521,6 +849,19 @@ impl BitriseClient {
             build_params["environments"] = serde_json::json!(envs);
         }
 
+        self.post_build(app_slug, build_params)
+    }
+
+    /// Trigger a build again with the parameters it originally ran with
+    ///
+    /// Env vars, tag and pull request context come from the build's
+    /// `original_build_params`; see [`Build::rebuild_params`].
+    pub fn rebuild_build(&self, app_slug: &str, build: &Build) -> Result<Build> {
+        self.post_build(app_slug, build.rebuild_params())
+    }
+
+    /// Start a build from `build_params` and fetch it
+    fn post_build(&self, app_slug: &str, build_params: serde_json::Value) -> Result<Build> {
         let body = serde_json::json!({
             "hook_info": {
                 "type": "bitrise",
@@ -541,6 +882,100 @@ impl BitriseClient {
             })
         }
     }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // Release Management Operations
+    // ─────────────────────────────────────────────────────────────────────────
+
+    /// List the Release Management connected apps built by a Bitrise app
+    pub fn list_connected_apps(&self, app_slug: &str) -> Result<ConnectedAppListResponse> {
+        let path = format!("/connected-apps?project_id={app_slug}");
+        self.release_request(reqwest::Method::GET, &path, None)
+    }
+
+    /// List the releases of a connected app, newest first
+    pub fn list_releases(&self, connected_app_id: &str) -> Result<ReleaseListResponse> {
+        let path = format!("/connected-apps/{connected_app_id}/releases");
+        self.release_request(reqwest::Method::GET, &path, None)
+    }
+
+    /// Get one release of a connected app
+    pub fn get_release(&self, connected_app_id: &str, release_id: &str) -> Result<Release> {
+        let path = format!("/connected-apps/{connected_app_id}/releases/{release_id}");
+        self.release_request(reqwest::Method::GET, &path, None)
+    }
+
+    /// Start a release of `version` for a connected app
+    pub fn create_release(
+        &self,
+        connected_app_id: &str,
+        version: &str,
+        name: Option<&str>,
+        description: Option<&str>,
+    ) -> Result<Release> {
+        let mut body = serde_json::json!({ "version": version });
+        if let Some(name) = name {
+            body["name"] = serde_json::json!(name);
+        }
+        if let Some(description) = description {
+            body["description"] = serde_json::json!(description);
+        }
+        let path = format!("/connected-apps/{connected_app_id}/releases");
+        self.release_request(reqwest::Method::POST, &path, Some(&body))
+    }
+
+    /// Make a build the release candidate of a release
+    pub fn add_release_build(
+        &self,
+        connected_app_id: &str,
+        release_id: &str,
+        build_slug: &str,
+    ) -> Result<Release> {
+        let body = serde_json::json!({ "build_slug": build_slug });
+        let path = format!("/connected-apps/{connected_app_id}/releases/{release_id}/build");
+        self.release_request(reqwest::Method::POST, &path, Some(&body))
+    }
+}
+
+/// Fetch up to `limit` items in pages of at most [`MAX_PAGE_SIZE`]
+///
+/// The API silently caps larger limits at one page, so `fetch` is called
+/// with each page's limit and the previous page's `next` cursor until
+/// enough items arrive or the list ends. The last page's paging is kept.
+fn collect_pages<T>(
+    limit: u32,
+    mut fetch: impl FnMut(u32, Option<&str>) -> Result<(Vec<T>, Paging)>,
+) -> Result<(Vec<T>, Paging)> {
+    let wanted = limit as usize;
+    let mut items = Vec::new();
+    let mut next: Option<String> = None;
+    loop {
+        let remaining = wanted.saturating_sub(items.len()) as u32;
+        let (data, paging) = fetch(remaining.min(MAX_PAGE_SIZE), next.as_deref())?;
+        let empty = data.is_empty();
+        items.extend(data);
+        if items.len() >= wanted || empty || paging.next.is_none() {
+            items.truncate(wanted);
+            return Ok((items, paging));
+        }
+        next = paging.next;
+    }
+}
+
+/// Add tag, commit and pull request fields to a trigger's `build_params`
+fn add_source_params(build_params: &mut serde_json::Value, source: &TriggerSource) {
+    if let Some(ref tag) = source.tag {
+        build_params["tag"] = serde_json::json!(tag);
+    }
+    if let Some(ref commit_hash) = source.commit_hash {
+        build_params["commit_hash"] = serde_json::json!(commit_hash);
+    }
+    if let Some(pull_request_id) = source.pull_request_id {
+        build_params["pull_request_id"] = serde_json::json!(pull_request_id);
+    }
+    if let Some(ref branch_dest) = source.branch_dest {
+        build_params["branch_dest"] = serde_json::json!(branch_dest);
+    }
 }
 
 #[cfg(test)]
@@ -646,6 +1081,54 @@ mod tests {
         assert_eq!(err.exit_code(), 77); // EX_NOPERM
     }
 
+    #[test]
+    fn test_token_fingerprint_is_stable() {
+        let client = BitriseClient::with_token("test-token").unwrap();
+        assert_eq!(client.token_fingerprint(), "4fb48288fe58118d");
+        let other = BitriseClient::with_token("other-token").unwrap();
+        assert_ne!(client.token_fingerprint(), other.token_fingerprint());
+    }
+
+    #[test]
+    fn test_list_organizations() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/organizations")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": [{"slug": "org1", "name": "Acme", "concurrency_count": 2}]}"#)
+            .create();
+
+        let client = BitriseClient::with_base_url("test-token", server.url()).unwrap();
+        let orgs = client.list_organizations().unwrap();
+
+        mock.assert();
+        assert_eq!(orgs.data.len(), 1);
+        assert_eq!(orgs.data[0].name, "Acme");
+    }
+
+    #[test]
+    fn test_list_organization_members() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/organizations/org1/members")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [{"user_slug": "u1", "username": "ana", "email": "ana@example.com",
+                    "role": "owner"}, {"slug": "u2", "username": "ben"}]}"#,
+            )
+            .create();
+
+        let client = BitriseClient::with_base_url("test-token", server.url()).unwrap();
+        let members = client.list_organization_members("org1").unwrap().data;
+
+        mock.assert();
+        assert_eq!(members[0].slug, "u1");
+        assert_eq!(members[0].role.as_deref(), Some("owner"));
+        assert_eq!(members[1].email, None);
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // App Operations Tests
     // ─────────────────────────────────────────────────────────────────────────
@@ -674,6 +1157,22 @@ mod tests {
         assert_eq!(response.data[0].title, "Test App");
     }
 
+    #[test]
+    fn test_list_apps_page_passes_cursor() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/apps?limit=50&next=cursor-2")
+            .with_status(200)
+            .with_body(r#"{"data": [], "paging": {"total_item_count": 0, "page_item_limit": 50, "next": null}}"#)
+            .create();
+
+        let client = BitriseClient::with_base_url("test-token", server.url()).unwrap();
+        let result = client.list_apps_page(50, Some("cursor-2"));
+
+        mock.assert();
+        assert!(result.unwrap().data.is_empty());
+    }
+
     #[test]
     fn test_list_apps_empty() {
         let mut server = Server::new();
@@ -710,6 +1209,109 @@ mod tests {
         assert_eq!(app.data.title, "My App");
     }
 
+    #[test]
+    fn test_get_bitrise_yml_returns_text() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/apps/my-app/bitrise.yml")
+            .match_header("Authorization", "test-token")
+            .with_status(200)
+            .with_body("format_version: \"13\"\n")
+            .create();
+
+        let client = BitriseClient::with_base_url("test-token", server.url()).unwrap();
+        let yml = client.get_bitrise_yml("my-app").unwrap();
+
+        mock.assert();
+        assert_eq!(yml, "format_version: \"13\"\n");
+    }
+
+    #[test]
+    fn test_get_build_bitrise_yml_returns_text() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/apps/my-app/builds/build-1/bitrise.yml")
+            .with_status(200)
+            .with_body("format_version: \"11\"\n")
+            .create();
+
+        let client = BitriseClient::with_base_url("test-token", server.url()).unwrap();
+        let yml = client.get_build_bitrise_yml("my-app", "build-1").unwrap();
+
+        mock.assert();
+        assert_eq!(yml, "format_version: \"11\"\n");
+    }
+
+    #[test]
+    fn test_list_stacks_and_machine_types() {
+        let mut server = Server::new();
+        let stacks = server
+            .mock("GET", "/apps/my-app/stacks")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "osx-xcode-15.4.x", "title": "Xcode 15.4.x",
+                    "status": "stable", "machine_types": ["g2.mac.medium"]}]}"#,
+            )
+            .create();
+        let machines = server
+            .mock("GET", "/apps/my-app/machine_types")
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "g2.mac.medium", "cpu_count": 4, "ram": "12 GB"}]}"#)
+            .create();
+
+        let client = BitriseClient::with_base_url("test-token", server.url()).unwrap();
+        let stack = &client.list_stacks("my-app").unwrap().data[0];
+        let machine = &client.list_machine_types("my-app").unwrap().data[0];
+
+        stacks.assert();
+        machines.assert();
+        assert_eq!(stack.machine_types, vec!["g2.mac.medium"]);
+        assert_eq!(machine.cpu_count.as_deref(), Some("4"));
+        assert_eq!(machine.ram.as_deref(), Some("12 GB"));
+    }
+
+    #[test]
+    fn test_list_addons() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/apps/my-app/addons")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "addons-testing", "title": "Test Reports",
+                    "is_enabled": true, "plan": {"id": "free", "name": "Free"}},
+                    {"id": "addons-ship", "title": "Release Management"}]}"#,
+            )
+            .create();
+
+        let client = BitriseClient::with_base_url("test-token", server.url()).unwrap();
+        let addons = client.list_addons("my-app").unwrap().data;
+
+        mock.assert();
+        assert!(addons[0].is_enabled);
+        assert_eq!(addons[0].plan.as_ref().and_then(|p| p.name.as_deref()), Some("Free"));
+        assert!(!addons[1].is_enabled);
+        assert_eq!(
+            addons[0].url("my-app"),
+            "https://app.bitrise.io/app/my-app/addon/addons-testing/login"
+        );
+    }
+
+    #[test]
+    fn test_list_branches() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/apps/my-app/branches")
+            .with_status(200)
+            .with_body(r#"{"data": ["main", "feature/login"]}"#)
+            .create();
+
+        let client = BitriseClient::with_base_url("test-token", server.url()).unwrap();
+        let result = client.list_branches("my-app");
+
+        mock.assert();
+        assert_eq!(result.unwrap().data, vec!["main", "feature/login"]);
+    }
+
     #[test]
     fn test_get_app_not_found() {
         let mut server = Server::new();
@@ -754,6 +1356,61 @@ mod tests {
         assert_eq!(response.data[0].slug, "build-123");
     }
 
+    #[test]
+    fn test_list_builds_above_page_size_follows_pages() {
+        let mut server = Server::new();
+        let builds = |range: std::ops::Range<i64>| -> String {
+            let items: Vec<String> =
+                range.map(|n| make_build_json(&format!("b{}", n), n, 1)).collect();
+            items.join(",")
+        };
+        let first = server
+            .mock("GET", "/apps/test-app/builds?limit=50")
+            .with_status(200)
+            .with_body(format!(
+                r#"{{"data": [{}], "paging": {{"total_item_count": 90, "page_item_limit": 50, "next": "c2"}}}}"#,
+                builds(0..50)
+            ))
+            .create();
+        let second = server
+            .mock("GET", "/apps/test-app/builds?limit=20&next=c2")
+            .with_status(200)
+            .with_body(format!(
+                r#"{{"data": [{}], "paging": {{"total_item_count": 90, "page_item_limit": 20, "next": "c3"}}}}"#,
+                builds(50..70)
+            ))
+            .create();
+
+        let client = BitriseClient::with_base_url("test-token", server.url()).unwrap();
+        let response = client.list_builds("test-app", None, None, None, 70).unwrap();
+
+        first.assert();
+        second.assert();
+        assert_eq!(response.data.len(), 70);
+        assert_eq!(response.data[69].slug, "b69");
+        assert_eq!(response.paging.next.as_deref(), Some("c3"));
+    }
+
+    #[test]
+    fn test_collect_pages_stops_at_end_of_list() {
+        let mut calls = Vec::new();
+        let paging = |next: Option<&str>| Paging {
+            total_item_count: 3,
+            page_item_limit: 50,
+            next: next.map(String::from),
+        };
+        let (items, _) = collect_pages(120, |limit, next| {
+            calls.push((limit, next.map(String::from)));
+            match next {
+                None => Ok((vec![1, 2], paging(Some("c2")))),
+                Some(_) => Ok((vec![3], paging(None))),
+            }
+        })
+        .unwrap();
+        assert_eq!(items, [1, 2, 3]);
+        assert_eq!(calls, [(50, None), (50, Some("c2".to_string()))]);
+    }
+
     #[test]
     fn test_list_builds_with_filters() {
         let mut server = Server::new();
@@ -764,7 +1421,8 @@ mod tests {
             .create();
 
         let client = BitriseClient::with_base_url("test-token", server.url()).unwrap();
-        let result = client.list_builds("test-app", Some(1), Some("main"), None, 10);
+        let success = Some(BuildStatus::Success);
+        let result = client.list_builds("test-app", success, Some("main"), None, 10);
 
         mock.assert();
         assert!(result.is_ok());
@@ -812,6 +1470,25 @@ mod tests {
         assert_eq!(log.log_chunks[0].chunk, "Hello");
     }
 
+    #[test]
+    fn test_get_full_log_joins_chunks() {
+        let mut server = Server::new();
+        let _mock = server
+            .mock("GET", "/apps/test-app/builds/build-slug/log")
+            .with_status(200)
+            .with_body(r#"{"log_chunks": [{"chunk": "Hello ", "position": 0}, {"chunk": "world", "position": 1}], "expiring_raw_log_url": null, "is_archived": true}"#)
+            .create();
+
+        let client = BitriseClient::with_base_url("test-token", server.url()).unwrap();
+        assert_eq!(client.get_full_log("test-app", "build-slug").unwrap(), "Hello world");
+    }
+
+    #[test]
+    fn test_open_raw_log_rejects_untrusted_host() {
+        let client = BitriseClient::with_token("test-token").unwrap();
+        assert!(client.open_raw_log("https://example.com/log.txt").is_err());
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // Pipeline Operations Tests
     // ─────────────────────────────────────────────────────────────────────────
@@ -856,6 +1533,84 @@ mod tests {
         assert_eq!(pipeline.into_pipeline().id, "pipeline-id");
     }
 
+    #[test]
+    fn test_trigger_pipeline_sends_tag_and_pr_params() {
+        let mut server = Server::new();
+        let trigger = server
+            .mock("POST", "/apps/test-app/pipelines")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "build_params": {
+                    "pipeline_id": "release",
+                    "tag": "v1.2.0",
+                    "commit_hash": "abc123",
+                    "pull_request_id": 42,
+                    "branch_dest": "main"
+                }
+            })))
+            .with_status(201)
+            .with_body(r#"{"status": "ok", "message": "triggered", "id": "pipeline-id"}"#)
+            .create();
+        let fetch = server
+            .mock("GET", "/apps/test-app/pipelines/pipeline-id")
+            .with_status(200)
+            .with_body(format!(r#"{{"data": {}}}"#, make_pipeline_json("pipeline-id", 0)))
+            .create();
+
+        let client = BitriseClient::with_base_url("test-token", server.url()).unwrap();
+        let params = PipelineTriggerParams {
+            pipeline_id: "release".to_string(),
+            source: TriggerSource {
+                tag: Some("v1.2.0".to_string()),
+                commit_hash: Some("abc123".to_string()),
+                pull_request_id: Some(42),
+                branch_dest: Some("main".to_string()),
+            },
+            ..Default::default()
+        };
+        let result = client.trigger_pipeline("test-app", params);
+
+        trigger.assert();
+        fetch.assert();
+        assert_eq!(result.unwrap().id, "pipeline-id");
+    }
+
+    #[test]
+    fn test_rebuild_build_sends_original_params() {
+        let mut server = Server::new();
+        let trigger = server
+            .mock("POST", "/apps/test-app/builds")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "build_params": {
+                    "branch": "main",
+                    "workflow_id": "primary",
+                    "pull_request_id": 7,
+                    "environments": [{"mapped_to": "FLAVOR", "value": "beta", "is_expand": true}]
+                }
+            })))
+            .with_status(201)
+            .with_body(r#"{"status": "ok", "message": "triggered", "build_slug": "new-build"}"#)
+            .create();
+        let fetch = server
+            .mock("GET", "/apps/test-app/builds/new-build")
+            .with_status(200)
+            .with_body(format!(r#"{{"data": {}}}"#, make_build_json("new-build", 2, 0)))
+            .create();
+
+        let client = BitriseClient::with_base_url("test-token", server.url()).unwrap();
+        let mut build: Build = serde_json::from_str(&make_build_json("old-build", 1, 2)).unwrap();
+        build.original_build_params = Some(serde_json::json!({
+            "branch": "main",
+            "workflow_id": "primary",
+            "pull_request_id": 7,
+            "environments": [{"mapped_to": "FLAVOR", "value": "beta", "is_expand": true}]
+        }));
+        let result = client.rebuild_build("test-app", &build);
+
+        trigger.assert();
+        fetch.assert();
+        assert_eq!(result.unwrap().slug, "new-build");
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // Artifact Operations Tests
     // ─────────────────────────────────────────────────────────────────────────
@@ -898,6 +1653,79 @@ mod tests {
         assert!(artifact.data.is_public_page_enabled);
     }
 
+    #[test]
+    fn test_set_artifact_public_page() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("PATCH", "/apps/test-app/builds/build-slug/artifacts/art-slug")
+            .match_body(Matcher::Json(serde_json::json!({"is_public_page_enabled": true})))
+            .with_status(200)
+            .with_body(
+                r#"{"data": {"title": "app.ipa", "slug": "art-slug", "is_public_page_enabled": true,
+                    "public_install_page_url": "https://i.bitrise.io/abc"}}"#,
+            )
+            .create();
+
+        let client = BitriseClient::with_base_url("test-token", server.url()).unwrap();
+        let artifact = client
+            .set_artifact_public_page("test-app", "build-slug", "art-slug", true)
+            .unwrap();
+
+        mock.assert();
+        assert!(artifact.data.is_public_page_enabled);
+        assert_eq!(
+            artifact.data.public_install_page_url.as_deref(),
+            Some("https://i.bitrise.io/abc")
+        );
+    }
+
+    #[test]
+    fn test_create_and_finish_artifact_upload() {
+        let mut server = Server::new();
+        let create = server
+            .mock("POST", "/apps/test-app/builds/build-slug/artifacts")
+            .match_body(Matcher::Json(serde_json::json!({
+                "title": "coverage.xml",
+                "file_size_bytes": 1234,
+                "artifact_type": "file",
+            })))
+            .with_status(201)
+            .with_body(
+                r#"{"data": {"slug": "art-slug",
+                    "upload_url": "https://bitrise-prod-build-storage.s3.amazonaws.com/x"}}"#,
+            )
+            .create();
+        let finish = server
+            .mock("POST", "/apps/test-app/builds/build-slug/artifacts/art-slug/finish_upload")
+            .with_status(200)
+            .with_body(
+                r#"{"data": {"title": "coverage.xml", "slug": "art-slug",
+                    "is_public_page_enabled": false}}"#,
+            )
+            .create();
+
+        let client = BitriseClient::with_base_url("test-token", server.url()).unwrap();
+        let upload = client
+            .create_artifact_upload("test-app", "build-slug", "coverage.xml", 1234, "file")
+            .unwrap();
+        let artifact = client
+            .finish_artifact_upload("test-app", "build-slug", &upload.data.slug)
+            .unwrap();
+
+        create.assert();
+        finish.assert();
+        assert_eq!(artifact.data.title, "coverage.xml");
+    }
+
+    #[test]
+    fn test_upload_artifact_file_rejects_untrusted_host() {
+        let client = BitriseClient::with_token("test-token").unwrap();
+        let err = client
+            .upload_artifact_file("https://evil.example.com/x", std::path::Path::new("f"))
+            .unwrap_err();
+        assert!(err.to_string().contains("untrusted host"));
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // Abort Operations Tests
     // ─────────────────────────────────────────────────────────────────────────
@@ -934,6 +1762,66 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    // ─────────────────────────────────────────────────────────────────────────
+    // Release Management Tests
+    // ─────────────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_list_connected_apps_and_releases() {
+        let mut server = Server::new();
+        let apps = server
+            .mock("GET", "/release-management/v1/connected-apps?project_id=my-app")
+            .with_status(200)
+            .with_body(r#"{"items": [{"id": "ca-1", "project_id": "my-app", "platform": "ios"}]}"#)
+            .create();
+        let releases = server
+            .mock("GET", "/release-management/v1/connected-apps/ca-1/releases")
+            .with_status(200)
+            .with_body(
+                r#"{"items": [{"id": "rel-1", "version": "2.4.0", "status": "in_progress",
+                    "release_candidate": {"build_slug": "b-1", "build_number": 812}}]}"#,
+            )
+            .create();
+
+        let client = BitriseClient::with_base_url("test-token", server.url()).unwrap();
+        let connected = client.list_connected_apps("my-app").unwrap().items;
+        let release = &client.list_releases(&connected[0].id).unwrap().items[0];
+
+        apps.assert();
+        releases.assert();
+        assert_eq!(release.version, "2.4.0");
+        let candidate = release.release_candidate.as_ref().unwrap();
+        assert_eq!(candidate.build_number.as_deref(), Some("812"));
+    }
+
+    #[test]
+    fn test_create_release_and_add_build() {
+        let mut server = Server::new();
+        let create = server
+            .mock("POST", "/release-management/v1/connected-apps/ca-1/releases")
+            .match_body(Matcher::Json(serde_json::json!({"version": "2.5.0"})))
+            .with_status(201)
+            .with_body(r#"{"id": "rel-2", "version": "2.5.0"}"#)
+            .create();
+        let add = server
+            .mock("POST", "/release-management/v1/connected-apps/ca-1/releases/rel-2/build")
+            .match_body(Matcher::Json(serde_json::json!({"build_slug": "b-2"})))
+            .with_status(200)
+            .with_body(
+                r#"{"id": "rel-2", "version": "2.5.0",
+                    "release_candidate": {"build_slug": "b-2"}}"#,
+            )
+            .create();
+
+        let client = BitriseClient::with_base_url("test-token", server.url()).unwrap();
+        let release = client.create_release("ca-1", "2.5.0", None, None).unwrap();
+        let release = client.add_release_build("ca-1", &release.id, "b-2").unwrap();
+
+        create.assert();
+        add.assert();
+        assert_eq!(release.release_candidate.unwrap().build_slug, "b-2");
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // URL Validation Tests (SSRF Protection)
     // ─────────────────────────────────────────────────────────────────────────
@@ -1010,4 +1898,53 @@ mod tests {
         mock.assert();
         assert!(result.is_err());
     }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // Record/Replay Tests
+    // ─────────────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_record_then_replay() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/apps/test-app/builds/build-1")
+            .with_status(200)
+            .with_body(format!(r#"{{"data": {}}}"#, make_build_json("build-1", 42, 1)))
+            .expect(1)
+            .create();
+
+        let recorder = BitriseClient::with_base_url("test-token", server.url())
+            .unwrap()
+            .with_transport(Transport::Record(temp.path().to_path_buf()));
+        let recorded = recorder.get_build("test-app", "build-1").unwrap();
+        assert_eq!(recorded.data.build_number, 42);
+
+        // Replay must not hit the server again (mock expects exactly one call)
+        let replayer = BitriseClient::with_base_url("", "http://127.0.0.1:1")
+            .unwrap()
+            .with_transport(Transport::Replay(temp.path().to_path_buf()));
+        let replayed = replayer.get_build("test-app", "build-1").unwrap();
+
+        mock.assert();
+        assert_eq!(replayed.data.build_number, 42);
+    }
+
+    #[test]
+    fn test_replay_recorded_error() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut server = Server::new();
+        let _mock = server.mock("GET", "/me").with_status(401).with_body("Unauthorized").create();
+
+        let recorder = BitriseClient::with_base_url("bad-token", server.url())
+            .unwrap()
+            .with_transport(Transport::Record(temp.path().to_path_buf()));
+        assert!(recorder.get_me().is_err());
+
+        let replayer = BitriseClient::with_base_url("", "http://127.0.0.1:1")
+            .unwrap()
+            .with_transport(Transport::Replay(temp.path().to_path_buf()));
+        let err = replayer.get_me().unwrap_err();
+        assert_eq!(err.exit_code(), 77); // EX_NOPERM
+    }
 }
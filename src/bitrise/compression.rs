@@ -0,0 +1,164 @@
+//! Decompression for archived build logs
+//!
+//! Bitrise stores finished build logs on S3/GCS, and some of them are served
+//! gzip-compressed, either announced by `Content-Encoding` or just as a
+//! `.gz`-style body. [`decode`] detects that from the header or the magic
+//! bytes and inflates the log as it is read, so the terminal never sees
+//! binary data and large logs are never held in memory.
+
+use std::io::{self, BufRead, BufReader, Read};
+
+use flate2::bufread::{DeflateDecoder, MultiGzDecoder, ZlibDecoder};
+
+/// Compression framing of a response body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Gzip,
+    Zlib,
+    Deflate,
+}
+
+/// Wrap a log body so it reads as plain text, inflating it when the
+/// `Content-Encoding` header or the leading bytes say it is compressed
+pub fn decode(
+    body: Box<dyn Read + Send>,
+    content_encoding: Option<&str>,
+) -> io::Result<Box<dyn Read + Send>> {
+    let mut body = BufReader::new(body);
+    let head = body.fill_buf()?;
+
+    let format = match content_encoding.map(|e| e.trim().to_ascii_lowercase()).as_deref() {
+        Some("gzip" | "x-gzip") if head.starts_with(&[0x1f, 0x8b]) => Some(Format::Gzip),
+        Some("deflate") if is_zlib_header(head) => Some(Format::Zlib),
+        Some("deflate") => Some(Format::Deflate),
+        _ => sniff(head),
+    };
+    Ok(match format {
+        None => Box::new(body),
+        // Concatenated gzip members are read as one log
+        Some(Format::Gzip) => Box::new(MultiGzDecoder::new(body)),
+        Some(Format::Zlib) => Box::new(ZlibDecoder::new(body)),
+        Some(Format::Deflate) => Box::new(DeflateDecoder::new(body)),
+    })
+}
+
+/// Recognize compressed data by its magic bytes
+fn sniff(head: &[u8]) -> Option<Format> {
+    if head.starts_with(&[0x1f, 0x8b]) {
+        Some(Format::Gzip)
+    } else if head.len() >= 2 && head[0] == 0x78 && matches!(head[1], 0x01 | 0x9c | 0xda) {
+        // Only the common zlib headers, whose second byte isn't printable text
+        Some(Format::Zlib)
+    } else {
+        None
+    }
+}
+
+fn is_zlib_header(head: &[u8]) -> bool {
+    head.len() >= 2
+        && head[0] & 0x0f == 8
+        && (u16::from(head[0]) << 8 | u16::from(head[1])) % 31 == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn decode_all(body: Vec<u8>, encoding: Option<&str>) -> io::Result<String> {
+        let mut out = String::new();
+        decode(Box::new(Cursor::new(body)), encoding)?.read_to_string(&mut out)?;
+        Ok(out)
+    }
+
+    #[test]
+    fn test_plain_text_passes_through() {
+        let log = "xcodebuild: ** BUILD SUCCEEDED **\n";
+        assert_eq!(decode_all(log.as_bytes().to_vec(), None).unwrap(), log);
+        // "x^" looks like a zlib header but is ordinary text
+        assert_eq!(decode_all(b"x^2 + 1\n".to_vec(), None).unwrap(), "x^2 + 1\n");
+    }
+
+    #[test]
+    fn test_gzip_by_magic_bytes() {
+        let body = hex("1f8b0800000000000203cbc9cc4b5530e4ca0151465c0001b43d850e000000");
+        assert_eq!(decode_all(body, None).unwrap(), "line 1\nline 2\n");
+    }
+
+    #[test]
+    fn test_gzip_concatenated_members() {
+        let member = hex("1f8b0800000000000203cbc9cc4b5530e4ca0151465c0001b43d850e000000");
+        let body = [member.clone(), member].concat();
+        assert_eq!(decode_all(body, Some("gzip")).unwrap(), "line 1\nline 2\n".repeat(2));
+    }
+
+    #[test]
+    fn test_gzip_header_on_plain_body() {
+        assert_eq!(decode_all(b"already text\n".to_vec(), Some("gzip")).unwrap(), "already text\n");
+    }
+
+    #[test]
+    fn test_gzip_checksum_mismatch() {
+        let mut body = hex("1f8b0800000000000203cbc9cc4b5530e4ca0151465c0001b43d850e000000");
+        let crc = body.len() - 8;
+        body[crc] ^= 0xff;
+        assert!(decode_all(body, None).is_err());
+    }
+
+    #[test]
+    fn test_zlib_by_magic_and_deflate_encoding() {
+        let body = hex("789cabcac94c5248ca4fa9e40200151e038a");
+        assert_eq!(decode_all(body.clone(), None).unwrap(), "zlib body\n");
+        assert_eq!(decode_all(body, Some("deflate")).unwrap(), "zlib body\n");
+    }
+
+    #[test]
+    fn test_raw_deflate_blocks() {
+        // Fixed Huffman codes
+        let out = decode_all(hex("cb48cdc9c957c84027b900"), Some("deflate")).unwrap();
+        assert_eq!(out, "hello hello hello hello\n");
+
+        // Stored (uncompressed) block
+        let out = decode_all(hex("010b00f4ff706c61696e20746578740a"), Some("deflate")).unwrap();
+        assert_eq!(out, "plain text\n");
+    }
+
+    #[test]
+    fn test_dynamic_huffman_block() {
+        let expected: String = (0..20)
+            .map(|i| {
+                format!(
+                    "[{:02}:{:02}] Step {}: compiling module_{}.swift (warning: unused variable 'x{}')\n",
+                    i % 60,
+                    (i * 7) % 60,
+                    i % 9,
+                    i * 13 % 97,
+                    i
+                )
+            })
+            .collect();
+        let body = hex(concat!(
+            "8dd23b4e03411084e19c534c66485057cfbbafe1d042c8e005ad64af2d3f30c707b7261c8d3aade05305ff",
+            "864888dedcfa3a9d1c89fb3c1e4ef37e5ebedde1b8bbeda7777abddce7afab7bbe6fcfcbff2eeeb6dc2ed3",
+            "cefd6ccff3f6633fb9d52fad5e9e3604a1dc2074207883049558109ac41d8993416295bc309ae43b92af06",
+            "c9ab14844b9342478a6c90824a517c6c52ec48291aa4a85292c04d4a1d2917839454ca126a937247aa3048",
+            "59a5223135a9f43e19a0a25015f2832ed912667d4820010dc2f4a630b57140900765064b99d0c8c1c26190",
+            "66b4a409ad1c5e3c066d664b9bd0cc11c497419cc51227b47344097150a7252968e748127950278285d2d0",
+            "9125d6419e6ce9135a3a8a501a041a2c81425b4715f841a1d154e823f63f"
+        ));
+        assert_eq!(decode_all(body, Some("deflate")).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_truncated_stream_is_an_error() {
+        let body = hex("1f8b0800000000000203cbc9cc4b55");
+        assert!(decode_all(body, None).is_err());
+    }
+}
@@ -0,0 +1,162 @@
+//! Deploy workflows in bitrise.yml
+//!
+//! A workflow deploys when one of its steps uploads to a store or
+//! distribution service, directly, through a step bundle or `with` group,
+//! or through a workflow in its `before_run`/`after_run` chain.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::yml::Node;
+
+/// How deep step bundles may nest before we stop following them
+const MAX_BUNDLE_DEPTH: usize = 8;
+
+/// Where a deploy step ships builds to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Destination {
+    AppStore,
+    GooglePlay,
+    FirebaseAppDistribution,
+    AppCenter,
+}
+
+impl Destination {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::AppStore => "App Store Connect",
+            Self::GooglePlay => "Google Play",
+            Self::FirebaseAppDistribution => "Firebase App Distribution",
+            Self::AppCenter => "App Center",
+        }
+    }
+
+    /// The destination of a step reference such as `google-play-deploy@3`
+    /// or `git::https://github.com/org/steps-google-play-deploy.git@main`
+    pub fn from_step(step: &str) -> Option<Self> {
+        let id = step.rsplit_once('@').map_or(step, |(id, _)| id);
+        let id = id.rsplit(['/', ':']).next().unwrap_or(id);
+        let id = id.trim_end_matches(".git");
+        let id = id.strip_prefix("steps-").unwrap_or(id);
+
+        if id.starts_with("deploy-to-itunesconnect")
+            || id.starts_with("app-store-connect")
+            || id.contains("testflight")
+        {
+            Some(Self::AppStore)
+        } else if id.starts_with("google-play-deploy") {
+            Some(Self::GooglePlay)
+        } else if id.starts_with("firebase-app-distribution") {
+            Some(Self::FirebaseAppDistribution)
+        } else if id.starts_with("appcenter-deploy") {
+            Some(Self::AppCenter)
+        } else {
+            None
+        }
+    }
+}
+
+/// Workflows of a parsed bitrise.yml that deploy, with where each ships to
+pub fn deploy_workflows(doc: &Node) -> HashMap<String, Vec<Destination>> {
+    doc.get("workflows")
+        .map(Node::entries)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|(name, _)| {
+            let found = workflow_destinations(doc, name, &mut Vec::new());
+            (!found.is_empty()).then(|| (name.clone(), found))
+        })
+        .collect()
+}
+
+fn workflow_destinations(doc: &Node, name: &str, seen: &mut Vec<String>) -> Vec<Destination> {
+    if seen.iter().any(|s| s == name) {
+        return Vec::new();
+    }
+    seen.push(name.to_string());
+    let Some(workflow) = doc.get("workflows").and_then(|w| w.get(name)) else {
+        return Vec::new();
+    };
+
+    let mut found = Vec::new();
+    let chain = |key: &str| workflow.get(key).map(Node::items).unwrap_or_default();
+    for item in chain("before_run") {
+        found.extend(workflow_destinations(doc, item.as_str().unwrap_or_default(), seen));
+    }
+    if let Some(steps) = workflow.get("steps") {
+        step_destinations(doc, steps, 0, &mut found);
+    }
+    for item in chain("after_run") {
+        found.extend(workflow_destinations(doc, item.as_str().unwrap_or_default(), seen));
+    }
+
+    let mut unique = Vec::new();
+    for destination in found {
+        if !unique.contains(&destination) {
+            unique.push(destination);
+        }
+    }
+    unique
+}
+
+fn step_destinations(doc: &Node, steps: &Node, depth: usize, found: &mut Vec<Destination>) {
+    if depth > MAX_BUNDLE_DEPTH {
+        return;
+    }
+    for step in steps.items() {
+        for (id, body) in step.entries() {
+            let nested = if let Some(bundle) = id.strip_prefix("bundle::") {
+                doc.get("step_bundles").and_then(|b| b.get(bundle)).and_then(|b| b.get("steps"))
+            } else if id == "with" {
+                body.get("steps")
+            } else {
+                found.extend(Destination::from_step(id));
+                None
+            };
+            if let Some(nested) = nested {
+                step_destinations(doc, nested, depth + 1, found);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitrise::yml::parse;
+
+    #[test]
+    fn test_destination_from_step() {
+        assert_eq!(Destination::from_step("google-play-deploy@3"), Some(Destination::GooglePlay));
+        assert_eq!(
+            Destination::from_step("deploy-to-itunesconnect-application-loader@1"),
+            Some(Destination::AppStore)
+        );
+        assert_eq!(
+            Destination::from_step("git::https://github.com/org/steps-google-play-deploy.git@main"),
+            Some(Destination::GooglePlay)
+        );
+        assert_eq!(Destination::from_step("deploy-to-bitrise-io@2"), None);
+        assert_eq!(Destination::from_step("script@1"), None);
+    }
+
+    #[test]
+    fn test_deploy_workflows_follow_chains_and_bundles() {
+        let doc = parse(
+            "workflows:\n  \
+             _upload:\n    steps:\n    - app-store-connect-deliver@1: {}\n  \
+             ios:\n    after_run:\n    - _upload\n    steps:\n    - xcode-archive@5: {}\n  \
+             android:\n    steps:\n    - bundle::ship: {}\n  \
+             test:\n    steps:\n    - xcode-test@5: {}\n\
+             step_bundles:\n  ship:\n    steps:\n    - google-play-deploy@3: {}\n",
+        )
+        .unwrap();
+        let found = deploy_workflows(&doc);
+        assert_eq!(found.get("ios"), Some(&vec![Destination::AppStore]));
+        assert_eq!(found.get("_upload"), Some(&vec![Destination::AppStore]));
+        assert_eq!(found.get("android"), Some(&vec![Destination::GooglePlay]));
+        assert!(!found.contains_key("test"));
+    }
+}
@@ -1,7 +1,17 @@
 mod client;
+mod compression;
+pub mod deploys;
+pub mod schema;
+pub mod transport;
+pub mod triggers;
 pub mod types;
 pub mod url_parser;
+pub mod validate;
+pub mod yml;
 
-pub use client::BitriseClient;
+pub use client::{BitriseClient, MAX_PAGE_SIZE};
+pub use transport::Transport;
 pub use types::*;
-pub use url_parser::{parse_bitrise_url, BitriseUrl};
+pub use url_parser::{
+    find_url, parse_bitrise_link, parse_bitrise_url, BitriseLink, BitriseUrl, UrlView,
+};
@@ -0,0 +1,146 @@
+//! JSON Schemas for the `-o json` payloads
+//!
+//! The schemas are generated from the types in [`super::types`] (they derive
+//! [`JsonSchema`]) as they serialize, so scripts can validate reprise output
+//! or generate bindings from it. The tests serialize sample values and
+//! compare their keys with the schemas as a check on the derives.
+
+use schemars::generate::SchemaSettings;
+use schemars::JsonSchema;
+use serde_json::{json, Value};
+
+use super::types::{App, Artifact, Build, Pipeline};
+
+/// JSON Schema dialect the schemas are written in
+pub const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Payload kinds with a published schema
+pub const SCHEMA_KINDS: [&str; 4] = ["builds", "apps", "pipelines", "artifacts"];
+
+/// Schema of a list output (`builds`, `apps`, `pipelines` or `artifacts`)
+/// in its versioned envelope
+pub fn list_schema(kind: &str) -> Option<Value> {
+    match kind {
+        "builds" => Some(list_of::<Build>(kind)),
+        "apps" => Some(list_of::<App>(kind)),
+        "pipelines" => Some(list_of::<Pipeline>(kind)),
+        "artifacts" => Some(list_of::<Artifact>(kind)),
+        _ => None,
+    }
+}
+
+/// The envelope around a list of `T`, with `T` and the types it refers to
+/// under `$defs`
+fn list_of<T: JsonSchema>(kind: &str) -> Value {
+    let mut generator = SchemaSettings::draft2020_12().for_serialize().into_generator();
+    let item = generator.subschema_for::<T>();
+    json!({
+        "$schema": SCHEMA_DIALECT,
+        "title": format!("reprise {} -o json", kind),
+        "type": "object",
+        "properties": {
+            "api_version": {"const": 1},
+            "kind": {"const": kind},
+            "items": {"type": "array", "items": item},
+        },
+        "required": ["api_version", "kind", "items"],
+        "$defs": generator.take_definitions(true),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Property names of a definition in a list schema
+    fn property_names(schema: &Value, def: &str) -> Vec<String> {
+        let mut names: Vec<String> = schema["$defs"][def]["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Keys of a value once serialized
+    fn keys_of(value: impl serde::Serialize) -> Vec<String> {
+        let mut keys: Vec<String> = serde_json::to_value(value)
+            .unwrap()
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn test_build_schema_matches_type() {
+        let build: Build = serde_json::from_value(json!({
+            "slug": "b", "triggered_at": "2024-01-01T12:00:00Z", "status": 1,
+            "status_text": "success", "branch": "main", "build_number": 1,
+            "triggered_workflow": "ci"
+        }))
+        .unwrap();
+        let schema = list_schema("builds").unwrap();
+        assert_eq!(property_names(&schema, "Build"), keys_of(&build));
+    }
+
+    #[test]
+    fn test_app_schema_matches_type() {
+        let app: App = serde_json::from_value(json!({
+            "slug": "a", "title": "App", "is_disabled": false, "status": 1,
+            "owner": {"account_type": "user", "name": "Me", "slug": "me"}
+        }))
+        .unwrap();
+        let schema = list_schema("apps").unwrap();
+        assert_eq!(property_names(&schema, "App"), keys_of(&app));
+        assert_eq!(property_names(&schema, "Owner"), keys_of(&app.owner));
+    }
+
+    #[test]
+    fn test_pipeline_schema_matches_type() {
+        let pipeline: Pipeline = serde_json::from_value(json!({
+            "id": "p", "status": 1, "app": {"slug": "a", "title": "App"},
+            "workflows": [{"id": "w", "name": "ci", "status": 1}],
+            "trigger_params": {"branch": "main"}
+        }))
+        .unwrap();
+        let schema = list_schema("pipelines").unwrap();
+        assert_eq!(property_names(&schema, "Pipeline"), keys_of(&pipeline));
+        assert_eq!(property_names(&schema, "PipelineApp"), keys_of(&pipeline.app));
+        assert_eq!(property_names(&schema, "PipelineWorkflow"), keys_of(&pipeline.workflows[0]));
+        assert_eq!(
+            property_names(&schema, "PipelineTriggerParams"),
+            keys_of(&pipeline.trigger_params)
+        );
+    }
+
+    #[test]
+    fn test_artifact_schema_matches_type() {
+        let artifact: Artifact = serde_json::from_value(json!({
+            "title": "app.ipa", "slug": "art", "is_public_page_enabled": false,
+            "artifact_meta": {"app_info": {"version": "1.0"}}
+        }))
+        .unwrap();
+        let schema = list_schema("artifacts").unwrap();
+        let meta = artifact.artifact_meta.as_ref().unwrap();
+        assert_eq!(property_names(&schema, "Artifact"), keys_of(&artifact));
+        assert_eq!(property_names(&schema, "ArtifactMeta"), keys_of(meta));
+        assert_eq!(property_names(&schema, "ArtifactAppInfo"), keys_of(&meta.app_info));
+    }
+
+    #[test]
+    fn test_every_kind_has_a_schema() {
+        for kind in SCHEMA_KINDS {
+            let schema = list_schema(kind).unwrap();
+            assert_eq!(schema["$schema"], SCHEMA_DIALECT);
+            assert_eq!(schema["properties"]["kind"]["const"], kind);
+            assert_eq!(schema["properties"]["items"]["type"], "array");
+        }
+        assert!(list_schema("unknown").is_none());
+    }
+}
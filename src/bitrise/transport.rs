@@ -0,0 +1,197 @@
+//! Record/replay transport for the Bitrise client
+//!
+//! Setting `BITRISE_RECORD=<dir>` saves every API response to a fixture file
+//! in `<dir>`. Setting `BITRISE_REPLAY=<dir>` serves responses from those
+//! fixtures instead of the network, which makes command output deterministic
+//! for integration tests and lets demos run offline.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::{RepriseError, Result};
+
+/// Environment variable that enables recording to a directory
+pub const RECORD_ENV: &str = "BITRISE_RECORD";
+
+/// Environment variable that enables replaying from a directory
+pub const REPLAY_ENV: &str = "BITRISE_REPLAY";
+
+/// How the client talks to the network
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Transport {
+    /// Send requests to the network (normal operation)
+    #[default]
+    Live,
+    /// Send requests to the network and save responses as fixtures
+    Record(PathBuf),
+    /// Serve responses from previously recorded fixtures, never touching the network
+    Replay(PathBuf),
+}
+
+/// A recorded HTTP response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fixture {
+    /// HTTP method of the recorded request
+    pub method: String,
+    /// API path (or full URL for external requests)
+    pub key: String,
+    /// HTTP status code returned
+    pub status: u16,
+    /// Response body
+    pub body: String,
+}
+
+impl Transport {
+    /// Determine the transport from `BITRISE_REPLAY` / `BITRISE_RECORD`.
+    ///
+    /// Replay takes precedence if both are set.
+    pub fn from_env() -> Self {
+        let dir = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+        };
+
+        if let Some(path) = dir(REPLAY_ENV) {
+            Transport::Replay(path)
+        } else if let Some(path) = dir(RECORD_ENV) {
+            Transport::Record(path)
+        } else {
+            Transport::Live
+        }
+    }
+
+    /// Whether responses are served from fixtures
+    pub fn is_replay(&self) -> bool {
+        matches!(self, Transport::Replay(_))
+    }
+
+    /// Load the fixture for a request (replay mode)
+    pub fn load(dir: &Path, method: &str, key: &str) -> Result<Fixture> {
+        let path = fixture_path(dir, method, key);
+        let content = fs::read_to_string(&path).map_err(|e| {
+            if e.kind() == io::ErrorKind::NotFound {
+                RepriseError::Io(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!(
+                        "No recorded response for {} {} (expected {})",
+                        method,
+                        key,
+                        path.display()
+                    ),
+                ))
+            } else {
+                RepriseError::Io(e)
+            }
+        })?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Save a fixture for a request (record mode)
+    pub fn save(dir: &Path, fixture: &Fixture) -> Result<()> {
+        fs::create_dir_all(dir)?;
+        let path = fixture_path(dir, &fixture.method, &fixture.key);
+        fs::write(path, serde_json::to_string_pretty(fixture)?)?;
+        Ok(())
+    }
+}
+
+/// Build the fixture file path for a request.
+///
+/// The name combines a readable slug of the request with a hash of the full
+/// key, so different query strings never collide.
+pub fn fixture_path(dir: &Path, method: &str, key: &str) -> PathBuf {
+    // Drop the scheme/host for external URLs and any query string for the readable part
+    let readable = key
+        .split_once("://")
+        .map(|(_, rest)| rest)
+        .unwrap_or(key)
+        .split('?')
+        .next()
+        .unwrap_or_default();
+
+    let mut slug: String = readable
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    slug = slug.trim_matches('_').to_string();
+    slug.truncate(80);
+
+    let hash = fnv1a(&format!("{} {}", method, key));
+    dir.join(format!("{}_{}_{:016x}.json", method.to_lowercase(), slug, hash))
+}
+
+/// FNV-1a hash (stable across runs and platforms, unlike `DefaultHasher`)
+pub(crate) fn fnv1a(input: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in input.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_fixture_path_is_readable_and_stable() {
+        let dir = Path::new("/fixtures");
+        let a = fixture_path(dir, "GET", "/apps/abc/builds?limit=25");
+        let b = fixture_path(dir, "GET", "/apps/abc/builds?limit=25");
+        assert_eq!(a, b);
+
+        let name = a.file_name().unwrap().to_string_lossy().to_string();
+        assert!(name.starts_with("get_apps_abc_builds_"));
+        assert!(name.ends_with(".json"));
+    }
+
+    #[test]
+    fn test_fixture_path_distinguishes_queries_and_methods() {
+        let dir = Path::new("/fixtures");
+        let a = fixture_path(dir, "GET", "/apps/abc/builds?limit=25");
+        let b = fixture_path(dir, "GET", "/apps/abc/builds?limit=10");
+        let c = fixture_path(dir, "POST", "/apps/abc/builds?limit=25");
+        assert_ne!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_fixture_path_strips_external_host() {
+        let path = fixture_path(
+            Path::new("/fixtures"),
+            "GET",
+            "https://storage.googleapis.com/logs/abc.log?sig=xyz",
+        );
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        assert!(name.starts_with("get_storage_googleapis_com_logs_abc_log_"));
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let temp = TempDir::new().unwrap();
+        let fixture = Fixture {
+            method: "GET".to_string(),
+            key: "/me".to_string(),
+            status: 200,
+            body: r#"{"data": {}}"#.to_string(),
+        };
+
+        Transport::save(temp.path(), &fixture).unwrap();
+        let loaded = Transport::load(temp.path(), "GET", "/me").unwrap();
+        assert_eq!(loaded.status, 200);
+        assert_eq!(loaded.body, fixture.body);
+    }
+
+    #[test]
+    fn test_load_missing_fixture() {
+        let temp = TempDir::new().unwrap();
+        let err = Transport::load(temp.path(), "GET", "/me").unwrap_err();
+        assert!(err.to_string().contains("No recorded response for GET /me"));
+    }
+}
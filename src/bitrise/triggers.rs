@@ -0,0 +1,253 @@
+//! Build triggers defined in bitrise.yml
+//!
+//! Reads both the app-wide `trigger_map` (checked in order, first match
+//! wins) and the `triggers` blocks of individual workflows and pipelines.
+
+use serde::Serialize;
+
+use super::yml::{Node, Value};
+
+/// The git event a trigger reacts to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerEvent {
+    Push,
+    PullRequest,
+    Tag,
+}
+
+impl TriggerEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Push => "push",
+            Self::PullRequest => "pull_request",
+            Self::Tag => "tag",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "push" => Some(Self::Push),
+            "pull_request" => Some(Self::PullRequest),
+            "tag" => Some(Self::Tag),
+            _ => None,
+        }
+    }
+}
+
+/// What a trigger starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetKind {
+    Workflow,
+    Pipeline,
+}
+
+impl TargetKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Workflow => "workflow",
+            Self::Pipeline => "pipeline",
+        }
+    }
+}
+
+/// One condition of a trigger, e.g. `branch` matching `release/*`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TriggerCondition {
+    pub name: String,
+    pub pattern: String,
+    /// Whether `pattern` is a regular expression rather than a glob
+    pub regex: bool,
+}
+
+/// A rule that starts a workflow or pipeline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Trigger {
+    pub event: TriggerEvent,
+    pub conditions: Vec<TriggerCondition>,
+    pub target_kind: TargetKind,
+    /// Workflow or pipeline name; empty when the rule names neither
+    pub target: String,
+    /// Whether the rule comes from the ordered `trigger_map`
+    pub trigger_map: bool,
+    pub enabled: bool,
+    /// 1-based line in bitrise.yml
+    pub line: usize,
+}
+
+/// Keys of a `trigger_map` item that aren't conditions
+const TRIGGER_MAP_KEYS: &[&str] = &["type", "workflow", "pipeline", "enabled"];
+
+/// Every trigger in a parsed bitrise.yml: the `trigger_map` first, in order,
+/// then the `triggers` of each pipeline and workflow
+pub fn triggers(doc: &Node) -> Vec<Trigger> {
+    let mut found: Vec<Trigger> = doc
+        .get("trigger_map")
+        .map(|map| map.items().iter().map(trigger_map_item).collect())
+        .unwrap_or_default();
+
+    for (section, kind) in [("pipelines", TargetKind::Pipeline), ("workflows", TargetKind::Workflow)]
+    {
+        for (name, target) in doc.get(section).map(Node::entries).unwrap_or_default() {
+            if let Some(block) = target.get("triggers") {
+                found.extend(target_triggers(block, kind, name));
+            }
+        }
+    }
+    found
+}
+
+fn trigger_map_item(item: &Node) -> Trigger {
+    let (target_kind, target) = match (item.get("pipeline"), item.get("workflow")) {
+        (Some(pipeline), _) => (TargetKind::Pipeline, pipeline.as_str()),
+        (None, workflow) => (TargetKind::Workflow, workflow.and_then(Node::as_str)),
+    };
+    let conditions: Vec<TriggerCondition> = item
+        .entries()
+        .iter()
+        .filter(|(key, _)| !TRIGGER_MAP_KEYS.contains(&key.as_str()))
+        .map(|(key, value)| condition(strip_event_prefix(key), value))
+        .collect();
+
+    let event = item
+        .get("type")
+        .and_then(Node::as_str)
+        .and_then(TriggerEvent::from_name)
+        .unwrap_or_else(|| infer_event(item));
+
+    Trigger {
+        event,
+        conditions,
+        target_kind,
+        target: target.unwrap_or_default().to_string(),
+        trigger_map: true,
+        enabled: !item.get("enabled").is_some_and(Node::is_false),
+        line: item.line,
+    }
+}
+
+/// The event of a legacy `trigger_map` item without a `type`, from its keys
+fn infer_event(item: &Node) -> TriggerEvent {
+    let keys: Vec<&str> = item.entries().iter().map(|(k, _)| k.as_str()).collect();
+    if keys.iter().any(|k| k.contains("pull_request")) {
+        TriggerEvent::PullRequest
+    } else if keys.contains(&"tag") {
+        TriggerEvent::Tag
+    } else {
+        TriggerEvent::Push
+    }
+}
+
+/// `push_branch` and `pull_request_target_branch` read as `branch` and `target_branch`
+fn strip_event_prefix(key: &str) -> &str {
+    key.strip_prefix("push_")
+        .or_else(|| key.strip_prefix("pull_request_"))
+        .unwrap_or(key)
+}
+
+/// Triggers of one workflow or pipeline (`triggers: {push: [...], ...}`)
+fn target_triggers(block: &Node, kind: TargetKind, name: &str) -> Vec<Trigger> {
+    let all_enabled = !block.get("enabled").is_some_and(Node::is_false);
+    block
+        .entries()
+        .iter()
+        .filter_map(|(event, items)| Some((TriggerEvent::from_name(event)?, items)))
+        .flat_map(|(event, items)| {
+            items.items().iter().map(move |item| Trigger {
+                event,
+                conditions: item
+                    .entries()
+                    .iter()
+                    .filter(|(key, _)| key != "enabled")
+                    .map(|(key, value)| condition(key, value))
+                    .collect(),
+                target_kind: kind,
+                target: name.to_string(),
+                trigger_map: false,
+                enabled: all_enabled && !item.get("enabled").is_some_and(Node::is_false),
+                line: item.line,
+            })
+        })
+        .collect()
+}
+
+/// A condition from `key: pattern` or `key: {regex: ...}` (or `{pattern: ...}`)
+fn condition(name: &str, value: &Node) -> TriggerCondition {
+    let (pattern, regex) = match &value.value {
+        Value::Map(_) => match (value.get("regex"), value.get("pattern")) {
+            (Some(re), _) => (re.as_str(), true),
+            (None, glob) => (glob.and_then(Node::as_str), false),
+        },
+        Value::List(items) => {
+            let joined: Vec<&str> = items.iter().filter_map(Node::as_str).collect();
+            return TriggerCondition {
+                name: name.to_string(),
+                pattern: joined.join(", "),
+                regex: false,
+            };
+        }
+        _ => (value.as_str(), false),
+    };
+    TriggerCondition {
+        name: name.to_string(),
+        pattern: pattern.unwrap_or_default().to_string(),
+        regex,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitrise::yml::parse;
+
+    #[test]
+    fn test_trigger_map_items() {
+        let doc = parse(
+            "trigger_map:\n\
+             - push_branch: main\n  workflow: deploy\n\
+             - pull_request_source_branch: '*'\n  pull_request_target_branch: main\n  pipeline: ci\n\
+             - type: tag\n  tag: {regex: '^v\\d+'}\n  workflow: release\n  enabled: false\n",
+        )
+        .unwrap();
+        let found = triggers(&doc);
+        assert_eq!(found.len(), 3);
+
+        assert_eq!(found[0].event, TriggerEvent::Push);
+        assert_eq!(found[0].conditions[0].name, "branch");
+        assert_eq!(found[0].target, "deploy");
+        assert_eq!(found[0].line, 2);
+
+        assert_eq!(found[1].event, TriggerEvent::PullRequest);
+        assert_eq!(found[1].target_kind, TargetKind::Pipeline);
+        let names: Vec<_> = found[1].conditions.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["source_branch", "target_branch"]);
+
+        assert_eq!(found[2].event, TriggerEvent::Tag);
+        assert!(found[2].conditions[0].regex);
+        assert_eq!(found[2].conditions[0].pattern, "^v\\d+");
+        assert!(!found[2].enabled);
+    }
+
+    #[test]
+    fn test_workflow_triggers() {
+        let doc = parse(
+            "workflows:\n  primary:\n    triggers:\n      push:\n      - branch: main\n      \
+             pull_request:\n      - target_branch: main\n        enabled: false\n  other: {}\n",
+        )
+        .unwrap();
+        let found = triggers(&doc);
+        assert_eq!(found.len(), 2);
+        assert!(found.iter().all(|t| t.target == "primary" && !t.trigger_map));
+        assert_eq!(found[0].event, TriggerEvent::Push);
+        assert!(found[0].enabled);
+        assert_eq!(found[1].event, TriggerEvent::PullRequest);
+        assert!(!found[1].enabled);
+        assert_eq!(found[1].line, 7);
+    }
+
+    #[test]
+    fn test_no_triggers() {
+        assert!(triggers(&parse("format_version: '13'\n").unwrap()).is_empty());
+    }
+}
@@ -1,32 +1,171 @@
+use std::fmt;
+
+use std::borrow::Cow;
+
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Deserializer, Serialize};
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Status of a build, pipeline or pipeline workflow
+///
+/// Serialized as the Bitrise API's integer code (0 running, 1 success,
+/// 2 failed, 3 aborted, 4 aborted with success). Pipelines report their
+/// status as a string, which is accepted when deserializing too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BuildStatus {
+    #[default]
+    Running,
+    Success,
+    Failed,
+    Aborted,
+    AbortedWithSuccess,
+    /// A code this version of reprise doesn't know
+    Unknown(i32),
+}
 
-/// Custom deserializer for pipeline status that handles both int and string formats
-fn deserialize_pipeline_status<'de, D>(deserializer: D) -> Result<i32, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    use serde::de::Error;
+impl BuildStatus {
+    /// Status for a Bitrise API status code
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            0 => Self::Running,
+            1 => Self::Success,
+            2 => Self::Failed,
+            3 => Self::Aborted,
+            4 => Self::AbortedWithSuccess,
+            other => Self::Unknown(other),
+        }
+    }
 
-    #[derive(Deserialize)]
-    #[serde(untagged)]
-    enum StatusValue {
-        Int(i32),
-        Str(String),
+    /// Bitrise API status code
+    pub fn code(self) -> i32 {
+        match self {
+            Self::Running => 0,
+            Self::Success => 1,
+            Self::Failed => 2,
+            Self::Aborted => 3,
+            Self::AbortedWithSuccess => 4,
+            Self::Unknown(code) => code,
+        }
     }
 
-    match StatusValue::deserialize(deserializer)? {
-        StatusValue::Int(n) => Ok(n),
-        StatusValue::Str(s) => {
-            // Convert string status to integer
-            match s.to_lowercase().as_str() {
-                "running" | "on_hold" | "initializing" | "waiting_to_be_triggered" => Ok(0),
-                "succeeded" | "success" => Ok(1),
-                "failed" | "error" => Ok(2),
-                "aborted" | "cancelled" => Ok(3),
-                "aborted_with_success" => Ok(4),
-                _ => Err(D::Error::custom(format!("unknown status: {}", s))),
+    /// Status for a pipeline API status name, e.g. `succeeded`
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "running" | "on_hold" | "initializing" | "waiting_to_be_triggered" => {
+                Some(Self::Running)
             }
+            "succeeded" | "success" => Some(Self::Success),
+            "failed" | "error" => Some(Self::Failed),
+            "aborted" | "cancelled" => Some(Self::Aborted),
+            "aborted_with_success" => Some(Self::AbortedWithSuccess),
+            _ => None,
+        }
+    }
+
+    /// Human-readable status, e.g. `aborted-success`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Success => "success",
+            Self::Failed => "failed",
+            Self::Aborted => "aborted",
+            Self::AbortedWithSuccess => "aborted-success",
+            Self::Unknown(_) => "unknown",
+        }
+    }
+
+    /// Single-character symbol for compact listings
+    pub fn icon(self) -> &'static str {
+        match self {
+            Self::Running => "●",
+            Self::Success => "✓",
+            Self::Failed => "✗",
+            Self::Aborted | Self::AbortedWithSuccess => "○",
+            Self::Unknown(_) => "?",
+        }
+    }
+
+    /// Still queued or in progress
+    pub fn is_running(self) -> bool {
+        self == Self::Running
+    }
+
+    /// No longer running, whatever the outcome
+    pub fn is_finished(self) -> bool {
+        !self.is_running()
+    }
+
+    /// Aborted, with or without success
+    pub fn is_aborted(self) -> bool {
+        matches!(self, Self::Aborted | Self::AbortedWithSuccess)
+    }
+
+    /// Style text in this status's color
+    #[cfg(feature = "cli")]
+    pub fn colorize(self, text: &str) -> colored::ColoredString {
+        use colored::Colorize;
+
+        match self {
+            Self::Running => text.yellow().bold(),
+            Self::Success => text.green(),
+            Self::Failed => text.red().bold(),
+            Self::Aborted | Self::AbortedWithSuccess => text.red(),
+            Self::Unknown(_) => text.dimmed(),
+        }
+    }
+}
+
+impl fmt::Display for BuildStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<i32> for BuildStatus {
+    fn from(code: i32) -> Self {
+        Self::from_code(code)
+    }
+}
+
+impl Serialize for BuildStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i32(self.code())
+    }
+}
+
+impl JsonSchema for BuildStatus {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        "BuildStatus".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "integer",
+            "enum": [0, 1, 2, 3, 4],
+            "description": "0 running, 1 success, 2 failed, 3 aborted, 4 aborted with success",
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for BuildStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum StatusValue {
+            Int(i32),
+            Str(String),
+        }
+
+        match StatusValue::deserialize(deserializer)? {
+            StatusValue::Int(code) => Ok(Self::from_code(code)),
+            StatusValue::Str(name) => Self::from_name(&name)
+                .ok_or_else(|| D::Error::custom(format!("unknown status: {}", name))),
         }
     }
 }
@@ -45,7 +184,7 @@ pub struct AppResponse {
 }
 
 /// Bitrise application
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct App {
     pub slug: String,
     pub title: String,
@@ -62,13 +201,19 @@ pub struct App {
 }
 
 /// App owner information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Owner {
     pub account_type: String,
     pub name: String,
     pub slug: String,
 }
 
+/// Response wrapper for an app's branch names
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchListResponse {
+    pub data: Vec<String>,
+}
+
 /// Response wrapper for build list
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuildListResponse {
@@ -83,13 +228,13 @@ pub struct BuildResponse {
 }
 
 /// Bitrise build
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Build {
     pub slug: String,
     pub triggered_at: DateTime<Utc>,
     pub started_on_worker_at: Option<DateTime<Utc>>,
     pub finished_at: Option<DateTime<Utc>>,
-    pub status: i32,
+    pub status: BuildStatus,
     pub status_text: String,
     pub abort_reason: Option<String>,
     pub branch: String,
@@ -104,19 +249,15 @@ pub struct Build {
     pub pull_request_id: Option<i64>,
     pub pull_request_target_branch: Option<String>,
     pub credit_cost: Option<i32>,
+    /// Parameters the build was triggered with (env vars, tag, PR, ...)
+    #[serde(default)]
+    pub original_build_params: Option<serde_json::Value>,
 }
 
 impl Build {
     /// Get a human-readable status string
     pub fn status_display(&self) -> &str {
-        match self.status {
-            0 => "running",
-            1 => "success",
-            2 => "failed",
-            3 => "aborted",
-            4 => "aborted-success",
-            _ => "unknown",
-        }
+        self.status.as_str()
     }
 
     /// Calculate build duration if available
@@ -146,12 +287,78 @@ impl Build {
 
     /// Check if build is still running
     pub fn is_running(&self) -> bool {
-        self.status == 0
+        self.status.is_running()
     }
 
     /// Check if build failed
     pub fn is_failed(&self) -> bool {
-        self.status == 2
+        self.status == BuildStatus::Failed
+    }
+
+    /// `build_params` that trigger this build again
+    ///
+    /// Uses the build's `original_build_params` when the API returned them,
+    /// so env vars, tag and pull request context carry over; otherwise
+    /// rebuilds them from the build's own fields.
+    pub fn rebuild_params(&self) -> serde_json::Value {
+        if let Some(params @ serde_json::Value::Object(_)) = &self.original_build_params {
+            return params.clone();
+        }
+
+        let mut params = serde_json::json!({
+            "branch": self.branch,
+            "workflow_id": self.triggered_workflow,
+        });
+        if let Some(ref commit_hash) = self.commit_hash {
+            params["commit_hash"] = serde_json::json!(commit_hash);
+        }
+        if let Some(ref commit_message) = self.commit_message {
+            params["commit_message"] = serde_json::json!(commit_message);
+        }
+        if let Some(ref tag) = self.tag {
+            params["tag"] = serde_json::json!(tag);
+        }
+        if let Some(pull_request_id) = self.pull_request_id {
+            params["pull_request_id"] = serde_json::json!(pull_request_id);
+        }
+        if let Some(ref target) = self.pull_request_target_branch {
+            params["branch_dest"] = serde_json::json!(target);
+        }
+        params
+    }
+}
+
+#[cfg(test)]
+impl Build {
+    /// A successful `ci` build on `main` that ran for 90 seconds
+    ///
+    /// Tests override the fields they care about with struct update syntax:
+    /// `Build { status: BuildStatus::Failed, ..Build::fixture() }`.
+    pub(crate) fn fixture() -> Self {
+        use chrono::TimeZone;
+        let triggered_at = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        Build {
+            slug: "build-1".to_string(),
+            triggered_at,
+            started_on_worker_at: Some(triggered_at),
+            finished_at: Some(triggered_at + chrono::Duration::seconds(90)),
+            status: BuildStatus::Success,
+            status_text: "success".to_string(),
+            abort_reason: None,
+            branch: "main".to_string(),
+            build_number: 42,
+            commit_hash: None,
+            commit_message: None,
+            tag: None,
+            triggered_workflow: "ci".to_string(),
+            triggered_by: None,
+            stack_identifier: None,
+            machine_type_id: None,
+            pull_request_id: None,
+            pull_request_target_branch: None,
+            credit_cost: None,
+            original_build_params: None,
+        }
     }
 }
 
@@ -184,9 +391,20 @@ pub struct TriggerParams {
     pub branch: Option<String>,
     pub workflow_id: String,
     pub commit_message: Option<String>,
+    pub source: TriggerSource,
     pub environments: Vec<(String, String)>,
 }
 
+/// Tag, commit and pull request context shared by build and pipeline triggers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TriggerSource {
+    pub tag: Option<String>,
+    pub commit_hash: Option<String>,
+    pub pull_request_id: Option<u64>,
+    /// Target branch of the pull request (`branch_dest` in the API)
+    pub branch_dest: Option<String>,
+}
+
 /// Response from triggering a build
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TriggerResponse {
@@ -212,8 +430,22 @@ pub struct ArtifactResponse {
     pub data: Artifact,
 }
 
-/// Build artifact
+/// Response wrapper for a newly registered artifact upload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactUploadResponse {
+    pub data: ArtifactUpload,
+}
+
+/// An artifact registered on a build, waiting for its file
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactUpload {
+    pub slug: String,
+    /// Pre-signed URL the file is PUT to
+    pub upload_url: String,
+}
+
+/// Build artifact
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Artifact {
     pub title: String,
     pub slug: String,
@@ -222,9 +454,73 @@ pub struct Artifact {
     pub is_public_page_enabled: bool,
     pub expiring_download_url: Option<String>,
     pub public_install_page_url: Option<String>,
+    #[serde(default)]
+    pub artifact_meta: Option<ArtifactMeta>,
+}
+
+/// Platform metadata attached to APK/IPA artifacts
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ArtifactMeta {
+    #[serde(default)]
+    pub app_info: Option<ArtifactAppInfo>,
+}
+
+/// App details extracted from an APK or IPA by Bitrise
+///
+/// Android and iOS artifacts use different field names; both are accepted.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ArtifactAppInfo {
+    #[serde(default, alias = "app_title")]
+    pub app_name: Option<String>,
+    #[serde(default, alias = "bundle_id")]
+    pub package_name: Option<String>,
+    #[serde(default, alias = "version")]
+    pub version_name: Option<String>,
+    #[serde(default, alias = "build_number", deserialize_with = "deserialize_opt_string")]
+    pub version_code: Option<String>,
+    #[serde(default, alias = "minimum_os", alias = "min_OS_version", deserialize_with = "deserialize_opt_string")]
+    pub min_sdk_version: Option<String>,
+}
+
+impl ArtifactAppInfo {
+    /// Version display, e.g. "1.4.0 (212)"
+    pub fn version_display(&self) -> Option<String> {
+        match (&self.version_name, &self.version_code) {
+            (Some(name), Some(code)) => Some(format!("{} ({})", name, code)),
+            (Some(name), None) => Some(name.clone()),
+            (None, Some(code)) => Some(format!("({})", code)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Accept either a string or a number for optional string fields
+fn deserialize_opt_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrNumber {
+        Str(String),
+        Int(i64),
+        Float(f64),
+    }
+
+    Ok(match Option::<StringOrNumber>::deserialize(deserializer)? {
+        Some(StringOrNumber::Str(s)) if !s.is_empty() => Some(s),
+        Some(StringOrNumber::Int(n)) => Some(n.to_string()),
+        Some(StringOrNumber::Float(f)) => Some(f.to_string()),
+        _ => None,
+    })
 }
 
 impl Artifact {
+    /// App details, if Bitrise extracted any for this artifact
+    pub fn app_info(&self) -> Option<&ArtifactAppInfo> {
+        self.artifact_meta.as_ref()?.app_info.as_ref()
+    }
+
     /// Get human-readable file size
     pub fn size_display(&self) -> String {
         match self.file_size_bytes {
@@ -234,6 +530,26 @@ impl Artifact {
             None => "-".to_string(),
         }
     }
+
+    /// When the expiring download URL stops working, read from its signed
+    /// query (S3 `X-Amz-Date` + `X-Amz-Expires` or `Expires`, or the GCS
+    /// `X-Goog-` equivalents)
+    pub fn download_url_expires_at(&self) -> Option<DateTime<Utc>> {
+        let url = url::Url::parse(self.expiring_download_url.as_deref()?).ok()?;
+        let query: std::collections::HashMap<String, String> =
+            url.query_pairs().map(|(k, v)| (k.to_lowercase(), v.into_owned())).collect();
+
+        for prefix in ["x-amz-", "x-goog-"] {
+            let signed = query.get(&format!("{prefix}date"));
+            let lifetime = query.get(&format!("{prefix}expires"));
+            if let (Some(signed), Some(lifetime)) = (signed, lifetime) {
+                let signed = chrono::NaiveDateTime::parse_from_str(signed, "%Y%m%dT%H%M%SZ").ok()?;
+                let lifetime = chrono::Duration::seconds(lifetime.parse().ok()?);
+                return Some(signed.and_utc() + lifetime);
+            }
+        }
+        DateTime::from_timestamp(query.get("expires")?.parse().ok()?, 0)
+    }
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -268,7 +584,7 @@ impl PipelineResponse {
 
 /// Bitrise pipeline
 /// Handles both list response format and single pipeline response format
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Pipeline {
     #[serde(alias = "uuid", default)]
     pub id: String,
@@ -277,8 +593,8 @@ pub struct Pipeline {
     /// App object (single pipeline response format)
     #[serde(default)]
     pub app: Option<PipelineApp>,
-    #[serde(default, deserialize_with = "deserialize_pipeline_status")]
-    pub status: i32,
+    #[serde(default)]
+    pub status: BuildStatus,
     #[serde(default)]
     pub status_text: Option<String>,
     #[serde(default)]
@@ -304,7 +620,7 @@ pub struct Pipeline {
 }
 
 /// App reference in pipeline response
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct PipelineApp {
     #[serde(default)]
     pub slug: String,
@@ -313,7 +629,8 @@ pub struct PipelineApp {
 }
 
 /// Trigger params in pipeline response (contains branch info)
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+#[schemars(rename = "PipelineTriggerParams")]
 pub struct PipelineTriggerParamsResponse {
     #[serde(default)]
     pub branch: Option<String>,
@@ -348,14 +665,7 @@ impl Pipeline {
 impl Pipeline {
     /// Get a human-readable status string
     pub fn status_display(&self) -> &str {
-        match self.status {
-            0 => "running",
-            1 => "success",
-            2 => "failed",
-            3 => "aborted",
-            4 => "aborted-success",
-            _ => "unknown",
-        }
+        self.status.as_str()
     }
 
     /// Calculate pipeline duration if available
@@ -385,24 +695,39 @@ impl Pipeline {
 
     /// Check if pipeline is still running
     pub fn is_running(&self) -> bool {
-        self.status == 0
+        self.status.is_running()
     }
 
     /// Check if pipeline failed
     pub fn is_failed(&self) -> bool {
-        self.status == 2
+        self.status == BuildStatus::Failed
+    }
+
+    /// Count successful workflows against the total, e.g. `(3, 5)`
+    pub fn workflow_progress(&self) -> (usize, usize) {
+        let succeeded = self
+            .workflows
+            .iter()
+            .filter(|wf| wf.status == BuildStatus::Success)
+            .count();
+        (succeeded, self.workflows.len())
+    }
+
+    /// Check if the pipeline contains a workflow with this name
+    pub fn has_workflow(&self, name: &str) -> bool {
+        self.workflows.iter().any(|wf| wf.name == name)
     }
 }
 
 /// Workflow within a pipeline
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PipelineWorkflow {
     #[serde(alias = "uuid", default)]
     pub id: String,
     #[serde(default)]
     pub name: String,
-    #[serde(default, deserialize_with = "deserialize_pipeline_status")]
-    pub status: i32,
+    #[serde(default)]
+    pub status: BuildStatus,
     #[serde(default)]
     pub status_text: Option<String>,
 }
@@ -410,13 +735,7 @@ pub struct PipelineWorkflow {
 impl PipelineWorkflow {
     /// Get a human-readable status string
     pub fn status_display(&self) -> &str {
-        match self.status {
-            0 => "running",
-            1 => "success",
-            2 => "failed",
-            3 => "aborted",
-            _ => "unknown",
-        }
+        self.status.as_str()
     }
 }
 
@@ -425,6 +744,7 @@ impl PipelineWorkflow {
 pub struct PipelineTriggerParams {
     pub pipeline_id: String,
     pub branch: Option<String>,
+    pub source: TriggerSource,
     pub environments: Vec<(String, String)>,
 }
 
@@ -459,6 +779,213 @@ pub struct User {
     pub avatar_url: Option<String>,
 }
 
+/// Response wrapper for organization list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganizationListResponse {
+    pub data: Vec<Organization>,
+}
+
+/// An organization (workspace) the current user belongs to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Organization {
+    pub slug: String,
+    pub name: String,
+}
+
+/// Response wrapper for the members of an app or organization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberListResponse {
+    pub data: Vec<Member>,
+}
+
+/// A user with access to an app or organization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Member {
+    #[serde(alias = "user_slug")]
+    pub slug: String,
+    pub username: String,
+    #[serde(default)]
+    pub email: Option<String>,
+    /// e.g. "owner", "admin", "developer" or "tester"
+    #[serde(default)]
+    pub role: Option<String>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Stack Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Response wrapper for the stacks available to an app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StackListResponse {
+    pub data: Vec<Stack>,
+}
+
+/// A build stack: an OS image with preinstalled tools
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stack {
+    /// Stack ID as used in bitrise.yml (e.g. "osx-xcode-15.4.x")
+    #[serde(alias = "stack_id")]
+    pub id: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Release channel (e.g. "stable", "edge", "frozen")
+    #[serde(default)]
+    pub status: Option<String>,
+    /// IDs of the machine types that can run the stack
+    #[serde(default)]
+    pub machine_types: Vec<String>,
+}
+
+impl Stack {
+    /// Xcode version in the stack ID, e.g. "15.4" for "osx-xcode-15.4.x"
+    pub fn xcode_version(&self) -> Option<&str> {
+        let (_, rest) = self.id.split_once("xcode-")?;
+        let end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let version = rest[..end].trim_end_matches('.');
+        (!version.is_empty()).then_some(version)
+    }
+
+    /// Whether the stack's Xcode matches `version`, compared on the parts
+    /// both give: "15" and "15.4.1" both match a "15.4.x" stack
+    pub fn provides_xcode(&self, version: &str) -> bool {
+        let Some(own) = self.xcode_version() else {
+            return false;
+        };
+        let wanted: Vec<&str> = version.trim().trim_end_matches(".x").split('.').collect();
+        !wanted.iter().any(|part| part.is_empty())
+            && own.split('.').zip(&wanted).all(|(have, want)| {
+                have == *want || have.parse::<u32>().ok() == want.parse::<u32>().ok()
+            })
+    }
+}
+
+/// Response wrapper for the machine types available to an app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MachineTypeListResponse {
+    pub data: Vec<MachineType>,
+}
+
+/// A machine type (hardware) builds can run on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MachineType {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_opt_string")]
+    pub cpu_count: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_opt_string")]
+    pub ram: Option<String>,
+    #[serde(default)]
+    pub credit_per_minute: Option<f64>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Addon Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Response wrapper for the addons of an app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddonListResponse {
+    pub data: Vec<Addon>,
+}
+
+/// A Bitrise addon (e.g. Test Reports) and whether an app has it enabled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Addon {
+    /// Addon ID (e.g. "addons-testing")
+    pub id: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub is_enabled: bool,
+    #[serde(default)]
+    pub plan: Option<AddonPlan>,
+    #[serde(default)]
+    pub documentation_url: Option<String>,
+}
+
+impl Addon {
+    /// Web UI URL of the addon for an app
+    pub fn url(&self, app_slug: &str) -> String {
+        format!("https://app.bitrise.io/app/{}/addon/{}/login", app_slug, self.id)
+    }
+}
+
+/// The plan an addon is enabled with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddonPlan {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Release Management Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Response wrapper for connected apps (Release Management's view of an app)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectedAppListResponse {
+    pub items: Vec<ConnectedApp>,
+}
+
+/// A store app connected to Release Management, tied to a Bitrise project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectedApp {
+    pub id: String,
+    /// Slug of the Bitrise app (project) that builds it
+    #[serde(default)]
+    pub project_id: Option<String>,
+    #[serde(default)]
+    pub platform: Option<String>,
+    #[serde(default)]
+    pub store_app_id: Option<String>,
+    #[serde(default)]
+    pub store_app_name: Option<String>,
+}
+
+/// Response wrapper for the releases of a connected app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseListResponse {
+    pub items: Vec<Release>,
+}
+
+/// A release: one version going through the release train
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Release {
+    pub id: String,
+    pub version: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Progress through the train (e.g. "in_progress", "released")
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub release_branch: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// The build picked to ship, once one has been added
+    #[serde(default)]
+    pub release_candidate: Option<ReleaseCandidate>,
+}
+
+/// The build selected as a release's candidate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseCandidate {
+    pub build_slug: String,
+    #[serde(default, deserialize_with = "deserialize_opt_string")]
+    pub build_number: Option<String>,
+    #[serde(default)]
+    pub build_version: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -471,24 +998,13 @@ mod tests {
     fn make_build(status: i32, started: Option<DateTime<Utc>>, finished: Option<DateTime<Utc>>) -> Build {
         Build {
             slug: "test-slug".to_string(),
-            triggered_at: Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
             started_on_worker_at: started,
             finished_at: finished,
-            status,
+            status: BuildStatus::from_code(status),
             status_text: "test".to_string(),
-            abort_reason: None,
-            branch: "main".to_string(),
             build_number: 1,
-            commit_hash: None,
-            commit_message: None,
-            tag: None,
             triggered_workflow: "primary".to_string(),
-            triggered_by: None,
-            stack_identifier: None,
-            machine_type_id: None,
-            pull_request_id: None,
-            pull_request_target_branch: None,
-            credit_cost: None,
+            ..Build::fixture()
         }
     }
 
@@ -497,7 +1013,7 @@ mod tests {
             id: "test-id".to_string(),
             app_slug: "test-app".to_string(),
             app: None,
-            status,
+            status: BuildStatus::from_code(status),
             status_text: Some("test".to_string()),
             triggered_at: Some(Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap()),
             started_at: started,
@@ -520,6 +1036,7 @@ mod tests {
             is_public_page_enabled: false,
             expiring_download_url: None,
             public_install_page_url: None,
+            artifact_meta: None,
         }
     }
 
@@ -641,6 +1158,38 @@ mod tests {
         assert!(!build.is_failed());
     }
 
+    #[test]
+    fn test_build_rebuild_params_uses_original_params() {
+        let mut build = make_build(2, None, None);
+        let original = serde_json::json!({
+            "branch": "main",
+            "workflow_id": "primary",
+            "environments": [{"mapped_to": "FLAVOR", "value": "beta", "is_expand": true}],
+        });
+        build.original_build_params = Some(original.clone());
+        assert_eq!(build.rebuild_params(), original);
+    }
+
+    #[test]
+    fn test_build_rebuild_params_falls_back_to_build_fields() {
+        let mut build = make_build(2, None, None);
+        build.commit_hash = Some("abc123".to_string());
+        build.tag = Some("v1.0".to_string());
+        build.pull_request_id = Some(42);
+        build.pull_request_target_branch = Some("develop".to_string());
+        assert_eq!(
+            build.rebuild_params(),
+            serde_json::json!({
+                "branch": "main",
+                "workflow_id": "primary",
+                "commit_hash": "abc123",
+                "tag": "v1.0",
+                "pull_request_id": 42,
+                "branch_dest": "develop",
+            })
+        );
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // Pipeline Tests
     // ─────────────────────────────────────────────────────────────────────────
@@ -739,6 +1288,23 @@ mod tests {
         assert!(!pipeline.is_running());
     }
 
+    #[test]
+    fn test_pipeline_workflow_progress() {
+        let mut pipeline = make_pipeline(0, None, None);
+        pipeline.workflows = [("build", 1), ("test", 1), ("deploy", 0)]
+            .iter()
+            .map(|(name, status)| PipelineWorkflow {
+                id: name.to_string(),
+                name: name.to_string(),
+                status: BuildStatus::from_code(*status),
+                status_text: None,
+            })
+            .collect();
+        assert_eq!(pipeline.workflow_progress(), (2, 3));
+        assert!(pipeline.has_workflow("deploy"));
+        assert!(!pipeline.has_workflow("lint"));
+    }
+
     #[test]
     fn test_pipeline_is_failed_true() {
         let pipeline = make_pipeline(2, None, None);
@@ -760,7 +1326,7 @@ mod tests {
         let wf = PipelineWorkflow {
             id: "wf-id".to_string(),
             name: "build".to_string(),
-            status: 0,
+            status: BuildStatus::Running,
             status_text: Some("running".to_string()),
         };
         assert_eq!(wf.status_display(), "running");
@@ -771,7 +1337,7 @@ mod tests {
         let wf = PipelineWorkflow {
             id: "wf-id".to_string(),
             name: "build".to_string(),
-            status: 1,
+            status: BuildStatus::Success,
             status_text: Some("success".to_string()),
         };
         assert_eq!(wf.status_display(), "success");
@@ -782,7 +1348,7 @@ mod tests {
         let wf = PipelineWorkflow {
             id: "wf-id".to_string(),
             name: "build".to_string(),
-            status: 2,
+            status: BuildStatus::Failed,
             status_text: Some("failed".to_string()),
         };
         assert_eq!(wf.status_display(), "failed");
@@ -793,7 +1359,7 @@ mod tests {
         let wf = PipelineWorkflow {
             id: "wf-id".to_string(),
             name: "build".to_string(),
-            status: 3,
+            status: BuildStatus::Aborted,
             status_text: Some("aborted".to_string()),
         };
         assert_eq!(wf.status_display(), "aborted");
@@ -804,12 +1370,64 @@ mod tests {
         let wf = PipelineWorkflow {
             id: "wf-id".to_string(),
             name: "build".to_string(),
-            status: 99,
+            status: BuildStatus::Unknown(99),
             status_text: Some("unknown".to_string()),
         };
         assert_eq!(wf.status_display(), "unknown");
     }
 
+    #[test]
+    fn test_pipeline_workflow_status_display_aborted_success() {
+        let wf = PipelineWorkflow {
+            id: "wf-id".to_string(),
+            name: "build".to_string(),
+            status: BuildStatus::AbortedWithSuccess,
+            status_text: None,
+        };
+        assert_eq!(wf.status_display(), "aborted-success");
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // BuildStatus Tests
+    // ─────────────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_build_status_code_roundtrip() {
+        for code in [0, 1, 2, 3, 4, 99] {
+            assert_eq!(BuildStatus::from_code(code).code(), code);
+        }
+        assert_eq!(BuildStatus::from_code(4), BuildStatus::AbortedWithSuccess);
+        assert_eq!(BuildStatus::from_code(99), BuildStatus::Unknown(99));
+    }
+
+    #[test]
+    fn test_build_status_serializes_as_code() {
+        let json = serde_json::to_string(&BuildStatus::Failed).unwrap();
+        assert_eq!(json, "2");
+        let status: BuildStatus = serde_json::from_str("4").unwrap();
+        assert_eq!(status, BuildStatus::AbortedWithSuccess);
+    }
+
+    #[test]
+    fn test_build_status_deserializes_pipeline_names() {
+        let status: BuildStatus = serde_json::from_str(r#""succeeded""#).unwrap();
+        assert_eq!(status, BuildStatus::Success);
+        let status: BuildStatus = serde_json::from_str(r#""aborted_with_success""#).unwrap();
+        assert_eq!(status, BuildStatus::AbortedWithSuccess);
+        let status: BuildStatus = serde_json::from_str(r#""on_hold""#).unwrap();
+        assert!(status.is_running());
+        assert!(serde_json::from_str::<BuildStatus>(r#""mystery""#).is_err());
+    }
+
+    #[test]
+    fn test_build_status_display_and_icon() {
+        assert_eq!(BuildStatus::AbortedWithSuccess.to_string(), "aborted-success");
+        assert_eq!(BuildStatus::Unknown(7).to_string(), "unknown");
+        assert_eq!(BuildStatus::Success.icon(), "✓");
+        assert!(BuildStatus::AbortedWithSuccess.is_aborted());
+        assert!(BuildStatus::Failed.is_finished());
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // Artifact Tests
     // ─────────────────────────────────────────────────────────────────────────
@@ -916,6 +1534,121 @@ mod tests {
         assert!(!app.is_public); // defaults to false
     }
 
+    #[test]
+    fn test_artifact_deserialize_apk_meta() {
+        let json = r#"{
+            "title": "app-release.apk",
+            "slug": "art1",
+            "artifact_type": "android-apk",
+            "file_size_bytes": 2048,
+            "is_public_page_enabled": false,
+            "artifact_meta": {
+                "app_info": {
+                    "app_name": "Demo",
+                    "package_name": "com.example.demo",
+                    "version_name": "1.4.0",
+                    "version_code": 212,
+                    "min_sdk_version": 24
+                }
+            }
+        }"#;
+
+        let artifact: Artifact = serde_json::from_str(json).unwrap();
+        let info = artifact.app_info().unwrap();
+        assert_eq!(info.package_name.as_deref(), Some("com.example.demo"));
+        assert_eq!(info.version_display().as_deref(), Some("1.4.0 (212)"));
+        assert_eq!(info.min_sdk_version.as_deref(), Some("24"));
+    }
+
+    #[test]
+    fn test_artifact_deserialize_ipa_meta() {
+        let json = r#"{
+            "title": "Demo.ipa",
+            "slug": "art2",
+            "artifact_type": "ios-ipa",
+            "is_public_page_enabled": true,
+            "artifact_meta": {
+                "app_info": {
+                    "app_title": "Demo",
+                    "bundle_id": "com.example.demo",
+                    "version": "2.0",
+                    "build_number": "87",
+                    "min_OS_version": "15.0"
+                }
+            }
+        }"#;
+
+        let artifact: Artifact = serde_json::from_str(json).unwrap();
+        let info = artifact.app_info().unwrap();
+        assert_eq!(info.app_name.as_deref(), Some("Demo"));
+        assert_eq!(info.package_name.as_deref(), Some("com.example.demo"));
+        assert_eq!(info.version_display().as_deref(), Some("2.0 (87)"));
+        assert_eq!(info.min_sdk_version.as_deref(), Some("15.0"));
+    }
+
+    #[test]
+    fn test_stack_xcode_version() {
+        let stack = |id: &str| Stack {
+            id: id.to_string(),
+            title: None,
+            status: None,
+            machine_types: Vec::new(),
+        };
+        assert_eq!(stack("osx-xcode-15.4.x").xcode_version(), Some("15.4"));
+        assert_eq!(stack("osx-xcode-16.0.x-edge").xcode_version(), Some("16.0"));
+        assert_eq!(stack("osx-xcode-edge").xcode_version(), None);
+        assert_eq!(stack("linux-docker-android-22.04").xcode_version(), None);
+    }
+
+    #[test]
+    fn test_stack_provides_xcode() {
+        let stack = Stack {
+            id: "osx-xcode-15.4.x".to_string(),
+            title: None,
+            status: None,
+            machine_types: Vec::new(),
+        };
+        assert!(stack.provides_xcode("15.4"));
+        assert!(stack.provides_xcode("15"));
+        assert!(stack.provides_xcode("15.4.1"));
+        assert!(stack.provides_xcode("15.4.x"));
+        assert!(!stack.provides_xcode("15.3"));
+        assert!(!stack.provides_xcode("16"));
+        assert!(!stack.provides_xcode("1"));
+        assert!(!stack.provides_xcode(""));
+    }
+
+    #[test]
+    fn test_artifact_deserialize_without_meta() {
+        let json = r#"{
+            "title": "logs.txt",
+            "slug": "art3",
+            "is_public_page_enabled": false,
+            "artifact_meta": null
+        }"#;
+
+        let artifact: Artifact = serde_json::from_str(json).unwrap();
+        assert!(artifact.app_info().is_none());
+    }
+
+    #[test]
+    fn test_artifact_download_url_expires_at() {
+        let mut artifact = make_artifact(None);
+        assert_eq!(artifact.download_url_expires_at(), None);
+
+        artifact.expiring_download_url = Some(
+            "https://bitrise-prod-build-storage.s3.amazonaws.com/a.ipa\
+             ?X-Amz-Algorithm=AWS4-HMAC-SHA256&X-Amz-Date=20240101T120000Z&X-Amz-Expires=3600"
+                .to_string(),
+        );
+        let expected = Utc.with_ymd_and_hms(2024, 1, 1, 13, 0, 0).unwrap();
+        assert_eq!(artifact.download_url_expires_at(), Some(expected));
+
+        artifact.expiring_download_url =
+            Some("https://storage.googleapis.com/a.ipa?Expires=1704114000".to_string());
+        assert_eq!(artifact.download_url_expires_at(), Some(expected));
+    }
+
     #[test]
     fn test_pipeline_deserialize_with_uuid_alias() {
         let json = r#"{
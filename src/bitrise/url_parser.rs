@@ -4,6 +4,12 @@
 //! - App URLs: `https://app.bitrise.io/app/{app-slug}`
 //! - Build URLs: `https://app.bitrise.io/build/{build-slug}`
 //! - Pipeline URLs: `https://app.bitrise.io/app/{app-slug}/pipelines/{pipeline-id}`
+//! - Workflow editor URLs: `https://app.bitrise.io/app/{app-slug}/workflow_editor`
+//! - Add-on URLs: `https://app.bitrise.io/app/{app-slug}/add-ons`
+//!
+//! Legacy `www.bitrise.io` links are accepted too. Fragments and query
+//! parameters such as `#artifacts` or `?workflow_id=...` are kept as a
+//! [`UrlView`] so the `url` command can open the matching view.
 
 use url::Url;
 
@@ -74,13 +80,49 @@ impl BitriseUrl {
     }
 }
 
+/// The page of an app or build a URL points at, beyond its overview
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlView {
+    /// A build's artifacts tab: `#artifacts` or `?tab=artifacts`
+    Artifacts,
+    /// A build's log tab: `#log` or `?tab=log`
+    Log,
+    /// An app's workflow editor, optionally opened on one workflow
+    WorkflowEditor { workflow: Option<String> },
+    /// An app's add-ons pages
+    Addons,
+}
+
+/// A parsed Bitrise URL with the view its path or fragment selects
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitriseLink {
+    pub target: BitriseUrl,
+    pub view: Option<UrlView>,
+}
+
+/// Hosts that serve the Bitrise web UI
+const BITRISE_HOSTS: [&str; 3] = ["app.bitrise.io", "www.bitrise.io", "bitrise.io"];
+
 /// Parse a Bitrise URL into its components
 ///
 /// Supports the following URL patterns:
 /// - `https://app.bitrise.io/app/{app-slug}`
 /// - `https://app.bitrise.io/build/{build-slug}`
 /// - `https://app.bitrise.io/app/{app-slug}/pipelines/{pipeline-id}`
+///
+/// See [`parse_bitrise_link`] for the other shapes and their views.
 pub fn parse_bitrise_url(input: &str) -> Result<BitriseUrl> {
+    Ok(parse_bitrise_link(input)?.target)
+}
+
+/// Parse a Bitrise URL along with the view it selects
+///
+/// On top of [`parse_bitrise_url`]'s patterns, this accepts:
+/// - `/build/{slug}#artifacts`, `/build/{slug}?tab=log` and similar tabs
+/// - `/app/{slug}/workflow_editor#!/workflows?workflow_id={id}`
+/// - `/app/{slug}/add-ons` and `/app/{slug}/addon/{id}/...`
+/// - any of them on `www.bitrise.io`
+pub fn parse_bitrise_link(input: &str) -> Result<BitriseLink> {
     let url = Url::parse(input).map_err(|_| {
         RepriseError::InvalidArgument(format!("Invalid URL: {}", input))
     })?;
@@ -90,42 +132,84 @@ pub fn parse_bitrise_url(input: &str) -> Result<BitriseUrl> {
         RepriseError::InvalidArgument(format!("URL has no host: {}", input))
     })?;
 
-    if host != "app.bitrise.io" {
+    if !BITRISE_HOSTS.contains(&host) {
         return Err(RepriseError::InvalidArgument(format!(
             "Not a Bitrise URL (expected app.bitrise.io, got {}): {}",
             host, input
         )));
     }
 
-    // Parse path segments
+    // Parse path segments, ignoring a trailing slash
     let segments: Vec<&str> = url
         .path_segments()
-        .map(|s| s.collect())
+        .map(|s| s.filter(|segment| !segment.is_empty()).collect())
         .unwrap_or_default();
 
-    match segments.as_slice() {
+    let (target, view) = match segments.as_slice() {
         // /app/{slug}
-        ["app", slug] if !slug.is_empty() => Ok(BitriseUrl::App {
-            slug: slug.to_string(),
-        }),
+        ["app", slug] => (BitriseUrl::App { slug: slug.to_string() }, None),
         // /app/{slug}/pipelines/{id}
-        ["app", app_slug, "pipelines", pipeline_id] if !app_slug.is_empty() && !pipeline_id.is_empty() => {
-            Ok(BitriseUrl::Pipeline {
+        ["app", app_slug, "pipelines", pipeline_id] => (
+            BitriseUrl::Pipeline {
                 app_slug: app_slug.to_string(),
                 pipeline_id: pipeline_id.to_string(),
-            })
+            },
+            None,
+        ),
+        // /app/{slug}/workflow_editor, with the workflow in the fragment
+        ["app", slug, "workflow_editor", ..] => {
+            let workflow = url_param(&url, "workflow_id").or_else(|| url_param(&url, "workflow"));
+            (
+                BitriseUrl::App { slug: slug.to_string() },
+                Some(UrlView::WorkflowEditor { workflow }),
+            )
+        }
+        // /app/{slug}/add-ons, /app/{slug}/addon/{id}/...
+        ["app", slug, "add-ons" | "addons" | "addon", ..] => (
+            BitriseUrl::App { slug: slug.to_string() },
+            Some(UrlView::Addons),
+        ),
+        // /build/{slug}, with the tab in the fragment or query
+        ["build", slug] => (BitriseUrl::Build { slug: slug.to_string() }, build_tab(&url)),
+        _ => {
+            return Err(RepriseError::InvalidArgument(format!(
+                "Unrecognized Bitrise URL pattern: {}. Expected /app/{{slug}}, /build/{{slug}}, or /app/{{slug}}/pipelines/{{id}}",
+                input
+            )))
         }
-        // /build/{slug}
-        ["build", slug] if !slug.is_empty() => Ok(BitriseUrl::Build {
-            slug: slug.to_string(),
-        }),
-        _ => Err(RepriseError::InvalidArgument(format!(
-            "Unrecognized Bitrise URL pattern: {}. Expected /app/{{slug}}, /build/{{slug}}, or /app/{{slug}}/pipelines/{{id}}",
-            input
-        ))),
+    };
+
+    Ok(BitriseLink { target, view })
+}
+
+/// The build tab a URL opens: `#artifacts`, `#?tab=artifacts` or `?tab=...`
+fn build_tab(url: &Url) -> Option<UrlView> {
+    let fragment = url.fragment().unwrap_or("").trim_start_matches(['!', '/', '?']);
+    let tab = url_param(url, "tab").unwrap_or_else(|| fragment.to_string());
+    match tab.to_lowercase().as_str() {
+        "artifacts" => Some(UrlView::Artifacts),
+        "log" | "logs" => Some(UrlView::Log),
+        _ => None,
     }
 }
 
+/// A query parameter, also looked up in a fragment such as `#!/path?key=value`
+fn url_param(url: &Url, name: &str) -> Option<String> {
+    let fragment_query = url.fragment().and_then(|f| f.split_once('?')).map(|(_, q)| q);
+    url.query_pairs()
+        .chain(fragment_query.into_iter().flat_map(|q| url::form_urlencoded::parse(q.as_bytes())))
+        .find(|(key, value)| key == name && !value.is_empty())
+        .map(|(_, value)| value.into_owned())
+}
+
+/// First `http(s)://` link in free text, such as piped output or the
+/// clipboard, without surrounding quotes or brackets
+pub fn find_url(text: &str) -> Option<&str> {
+    text.split_whitespace()
+        .map(|word| word.trim_matches(|c: char| matches!(c, '"' | '\'' | '<' | '>' | '(' | ')')))
+        .find(|word| word.starts_with("https://") || word.starts_with("http://"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -198,6 +282,77 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_build_artifacts_tab() {
+        for input in [
+            "https://app.bitrise.io/build/xyz789#artifacts",
+            "https://app.bitrise.io/build/xyz789#?tab=artifacts",
+            "https://app.bitrise.io/build/xyz789?tab=artifacts",
+        ] {
+            let link = parse_bitrise_link(input).unwrap();
+            assert_eq!(link.target, BitriseUrl::Build { slug: "xyz789".to_string() });
+            assert_eq!(link.view, Some(UrlView::Artifacts), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_build_log_tab() {
+        let link = parse_bitrise_link("https://app.bitrise.io/build/xyz789?tab=log").unwrap();
+        assert_eq!(link.view, Some(UrlView::Log));
+
+        let link = parse_bitrise_link("https://app.bitrise.io/build/xyz789#unknown").unwrap();
+        assert_eq!(link.view, None);
+    }
+
+    #[test]
+    fn test_parse_workflow_editor_url() {
+        let link = parse_bitrise_link(
+            "https://app.bitrise.io/app/abc123/workflow_editor#!/workflows?workflow_id=deploy",
+        )
+        .unwrap();
+        assert_eq!(link.target, BitriseUrl::App { slug: "abc123".to_string() });
+        assert_eq!(
+            link.view,
+            Some(UrlView::WorkflowEditor { workflow: Some("deploy".to_string()) })
+        );
+
+        let link = parse_bitrise_link("https://app.bitrise.io/app/abc123/workflow_editor").unwrap();
+        assert_eq!(link.view, Some(UrlView::WorkflowEditor { workflow: None }));
+    }
+
+    #[test]
+    fn test_parse_addon_urls() {
+        for input in [
+            "https://app.bitrise.io/app/abc123/add-ons",
+            "https://app.bitrise.io/app/abc123/addon/addons-testing/login",
+        ] {
+            let link = parse_bitrise_link(input).unwrap();
+            assert_eq!(link.target, BitriseUrl::App { slug: "abc123".to_string() });
+            assert_eq!(link.view, Some(UrlView::Addons), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_legacy_www_host() {
+        let url = parse_bitrise_url("https://www.bitrise.io/build/xyz789/").unwrap();
+        assert_eq!(url, BitriseUrl::Build { slug: "xyz789".to_string() });
+        let url = parse_bitrise_url("https://www.bitrise.io/app/abc123").unwrap();
+        assert_eq!(url, BitriseUrl::App { slug: "abc123".to_string() });
+    }
+
+    #[test]
+    fn test_find_url_in_text() {
+        assert_eq!(
+            find_url("  https://app.bitrise.io/build/abc123\n"),
+            Some("https://app.bitrise.io/build/abc123")
+        );
+        assert_eq!(
+            find_url("Build failed: <https://app.bitrise.io/build/abc123> (see log)"),
+            Some("https://app.bitrise.io/build/abc123")
+        );
+        assert_eq!(find_url("no link here"), None);
+    }
+
     #[test]
     fn test_to_url() {
         let app = BitriseUrl::App { slug: "abc".to_string() };
@@ -0,0 +1,357 @@
+//! Structural checks for bitrise.yml
+//!
+//! Catches what would make Bitrise reject the file or silently skip a
+//! build: syntax errors, malformed steps and env lists, and references
+//! to workflows, stages, pipelines or step bundles that don't exist.
+
+use super::triggers::{triggers, TargetKind};
+use super::yml::{self, Node, Value};
+use crate::config::lint::{LintIssue, Severity};
+
+/// Top-level keys Bitrise understands
+const TOP_LEVEL_KEYS: &[&str] = &[
+    "format_version",
+    "default_step_lib_source",
+    "project_type",
+    "title",
+    "summary",
+    "description",
+    "app",
+    "meta",
+    "include",
+    "tools",
+    "trigger_map",
+    "pipelines",
+    "stages",
+    "workflows",
+    "step_bundles",
+    "containers",
+    "services",
+];
+
+/// Keys Bitrise understands in a workflow
+const WORKFLOW_KEYS: &[&str] = &[
+    "title",
+    "summary",
+    "description",
+    "before_run",
+    "after_run",
+    "envs",
+    "steps",
+    "meta",
+    "triggers",
+    "status_report_name",
+    "priority",
+    "tools",
+];
+
+/// Check bitrise.yml contents; an empty list means no problems were found
+pub fn validate(contents: &str) -> Vec<LintIssue> {
+    let doc = match yml::parse(contents) {
+        Ok(doc) => doc,
+        Err(e) => return vec![issue(Severity::Error, "", e.line, e.message)],
+    };
+    if !matches!(doc.value, Value::Map(_)) {
+        return vec![issue(Severity::Error, "", doc.line, "expected a mapping of keys".into())];
+    }
+
+    let mut checker = Checker { doc: &doc, issues: Vec::new() };
+    checker.top_level();
+    checker.workflows();
+    checker.pipelines();
+    checker.stages();
+    checker.trigger_map();
+    checker.issues.sort_by_key(|i| i.line);
+    checker.issues
+}
+
+fn issue(severity: Severity, key: &str, line: usize, message: String) -> LintIssue {
+    LintIssue {
+        severity,
+        key: key.to_string(),
+        line: Some(line),
+        message,
+    }
+}
+
+struct Checker<'a> {
+    doc: &'a Node,
+    issues: Vec<LintIssue>,
+}
+
+impl Checker<'_> {
+    fn error(&mut self, key: &str, line: usize, message: String) {
+        self.issues.push(issue(Severity::Error, key, line, message));
+    }
+
+    fn warn(&mut self, key: &str, line: usize, message: String) {
+        self.issues.push(issue(Severity::Warning, key, line, message));
+    }
+
+    /// Whether `section` (e.g. "workflows") defines `name`
+    fn defines(&self, section: &str, name: &str) -> bool {
+        self.doc.get(section).and_then(|s| s.get(name)).is_some()
+    }
+
+    /// Entries of a top-level section that must be a mapping
+    fn section(&mut self, name: &str) -> Vec<(String, Node)> {
+        match self.doc.get(name) {
+            Some(node) if matches!(node.value, Value::Map(_)) => node.entries().to_vec(),
+            Some(node) if node.value == Value::Null => Vec::new(),
+            Some(node) => {
+                let message = format!("'{}' must be a mapping, not {}", name, node.kind());
+                self.error(name, node.line, message);
+                Vec::new()
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn top_level(&mut self) {
+        let doc = self.doc;
+        if doc.get("format_version").is_none() {
+            self.error("format_version", doc.line, "missing 'format_version'".into());
+        }
+        for (key, node) in doc.entries() {
+            if !TOP_LEVEL_KEYS.contains(&key.as_str()) {
+                self.warn(key, node.line, format!("unknown key '{}'", key));
+            }
+        }
+        if let Some(envs) = doc.get("app").and_then(|app| app.get("envs")) {
+            self.envs("app.envs", envs);
+        }
+    }
+
+    fn workflows(&mut self) {
+        for (name, workflow) in self.section("workflows") {
+            let path = format!("workflows.{}", name);
+            for (key, node) in workflow.entries() {
+                if !WORKFLOW_KEYS.contains(&key.as_str()) {
+                    self.warn(&path, node.line, format!("unknown key '{}' in workflow", key));
+                }
+            }
+            for key in ["before_run", "after_run"] {
+                let Some(list) = workflow.get(key) else { continue };
+                for item in list.items() {
+                    let target = item.as_str().unwrap_or_default();
+                    if !self.defines("workflows", target) {
+                        let message = format!("{} names unknown workflow '{}'", key, target);
+                        self.error(&format!("{}.{}", path, key), item.line, message);
+                    }
+                }
+            }
+            if let Some(envs) = workflow.get("envs") {
+                self.envs(&format!("{}.envs", path), envs);
+            }
+            let runs_others = ["before_run", "after_run"].iter().any(|k| workflow.get(k).is_some());
+            match workflow.get("steps") {
+                Some(steps) => self.steps(&format!("{}.steps", path), steps),
+                None if !runs_others => {
+                    self.warn(&path, workflow.line, format!("workflow '{}' has no steps", name));
+                }
+                None => {}
+            }
+        }
+    }
+
+    fn steps(&mut self, path: &str, steps: &Node) {
+        if !matches!(steps.value, Value::List(_) | Value::Null) {
+            self.error(path, steps.line, format!("steps must be a list, not {}", steps.kind()));
+            return;
+        }
+        for step in steps.items() {
+            let [(id, body)] = step.entries() else {
+                let message = "each step must be a single 'step-id: {...}' entry".to_string();
+                self.error(path, step.line, message);
+                continue;
+            };
+            if let Some(bundle) = id.strip_prefix("bundle::") {
+                if !self.defines("step_bundles", bundle) {
+                    self.error(path, step.line, format!("unknown step bundle '{}'", bundle));
+                }
+            } else if id == "with" {
+                if let Some(inner) = body.get("steps") {
+                    self.steps(path, inner);
+                }
+            } else if let Some(inputs) = body.get("inputs") {
+                self.envs(&format!("{}.{}.inputs", path, id), inputs);
+            }
+        }
+    }
+
+    /// Env and input lists: `- KEY: value`, optionally with `opts`
+    fn envs(&mut self, path: &str, envs: &Node) {
+        if !matches!(envs.value, Value::List(_) | Value::Null) {
+            self.error(path, envs.line, format!("must be a list, not {}", envs.kind()));
+            return;
+        }
+        for env in envs.items() {
+            let keys = env.entries().iter().filter(|(k, _)| k != "opts").count();
+            if keys != 1 {
+                self.error(path, env.line, "each item must be a single 'KEY: value' entry".into());
+            }
+        }
+    }
+
+    fn pipelines(&mut self) {
+        for (name, pipeline) in self.section("pipelines") {
+            let path = format!("pipelines.{}", name);
+            for stage in pipeline.get("stages").map(Node::items).unwrap_or_default() {
+                for (stage_name, _) in stage.entries() {
+                    if !self.defines("stages", stage_name) {
+                        let message = format!("unknown stage '{}'", stage_name);
+                        self.error(&format!("{}.stages", path), stage.line, message);
+                    }
+                }
+            }
+            // Graph pipelines list workflows directly
+            let graph = pipeline.get("workflows").map(Node::entries).unwrap_or_default();
+            for (workflow, node) in graph {
+                if !self.defines("workflows", workflow) && node.get("uses").is_none() {
+                    let message = format!("unknown workflow '{}'", workflow);
+                    self.error(&format!("{}.workflows", path), node.line, message);
+                }
+            }
+            if pipeline.get("stages").is_none() && pipeline.get("workflows").is_none() {
+                let message = format!("pipeline '{}' has no stages or workflows", name);
+                self.error(&path, pipeline.line, message);
+            }
+        }
+    }
+
+    fn stages(&mut self) {
+        for (name, stage) in self.section("stages") {
+            let path = format!("stages.{}.workflows", name);
+            for item in stage.get("workflows").map(Node::items).unwrap_or_default() {
+                for (workflow, _) in item.entries() {
+                    if !self.defines("workflows", workflow) {
+                        self.error(&path, item.line, format!("unknown workflow '{}'", workflow));
+                    }
+                }
+            }
+        }
+    }
+
+    fn trigger_map(&mut self) {
+        let Some(map) = self.doc.get("trigger_map") else { return };
+        if !matches!(map.value, Value::List(_) | Value::Null) {
+            self.error("trigger_map", map.line, format!("must be a list, not {}", map.kind()));
+            return;
+        }
+        for trigger in triggers(self.doc).into_iter().filter(|t| t.trigger_map) {
+            let kind = trigger.target_kind.as_str();
+            let section = match trigger.target_kind {
+                TargetKind::Workflow => "workflows",
+                TargetKind::Pipeline => "pipelines",
+            };
+            if trigger.target.is_empty() {
+                let message = "item names no workflow or pipeline".to_string();
+                self.error("trigger_map", trigger.line, message);
+            } else if !self.defines(section, &trigger.target) {
+                let message = format!("unknown {} '{}'", kind, trigger.target);
+                self.error("trigger_map", trigger.line, message);
+            } else if trigger.target.starts_with('_') {
+                let message = format!("utility workflow '{}' can't be triggered", trigger.target);
+                self.warn("trigger_map", trigger.line, message);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID: &str = "\
+format_version: '13'
+default_step_lib_source: https://github.com/bitrise-io/bitrise-steplib.git
+app:
+  envs:
+  - PROJECT: App.xcodeproj
+trigger_map:
+- push_branch: main
+  pipeline: ci
+pipelines:
+  ci:
+    stages:
+    - build: {}
+stages:
+  build:
+    workflows:
+    - primary: {}
+workflows:
+  _setup:
+    steps:
+    - git-clone@8: {}
+  primary:
+    before_run:
+    - _setup
+    steps:
+    - script@1:
+        inputs:
+        - content: echo hi
+    - bundle::notify: {}
+step_bundles:
+  notify:
+    steps:
+    - slack@4: {}
+";
+
+    fn messages(contents: &str) -> Vec<(Severity, Option<usize>, String)> {
+        validate(contents)
+            .into_iter()
+            .map(|i| (i.severity, i.line, i.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_valid_file() {
+        assert_eq!(messages(VALID), vec![]);
+    }
+
+    #[test]
+    fn test_validate_reports_syntax_errors() {
+        let found = messages("format_version: '13'\nworkflows:\n  a: {}\n  a: {}\n");
+        assert_eq!(found, vec![(Severity::Error, Some(4), "duplicate key 'a'".to_string())]);
+    }
+
+    #[test]
+    fn test_validate_unknown_references() {
+        let contents = VALID
+            .replace("  pipeline: ci", "  workflow: deploy")
+            .replace("- _setup", "- setup")
+            .replace("bundle::notify", "bundle::missing");
+        let found = messages(&contents);
+        assert_eq!(found.len(), 3, "{:?}", found);
+        assert_eq!(found[0], (Severity::Error, Some(7), "unknown workflow 'deploy'".into()));
+        assert_eq!(
+            found[1],
+            (Severity::Error, Some(23), "before_run names unknown workflow 'setup'".into())
+        );
+        assert_eq!(found[2], (Severity::Error, Some(28), "unknown step bundle 'missing'".into()));
+    }
+
+    #[test]
+    fn test_validate_structure_problems() {
+        let contents = "workflows:\n  primary:\n    stepz: []\n  \
+                        other:\n    steps:\n    - a: {}\n      b: {}\n";
+        let found = messages(contents);
+        assert_eq!(found[0].2, "missing 'format_version'");
+        let stepz = (Severity::Warning, Some(3), "unknown key 'stepz' in workflow".to_string());
+        assert!(found.contains(&stepz));
+        let no_steps = (Severity::Warning, Some(2), "workflow 'primary' has no steps".to_string());
+        assert!(found.contains(&no_steps));
+        assert!(found.contains(&(
+            Severity::Error,
+            Some(6),
+            "each step must be a single 'step-id: {...}' entry".into()
+        )));
+    }
+
+    #[test]
+    fn test_validate_trigger_to_utility_workflow_warns() {
+        let contents = VALID.replace("  pipeline: ci", "  workflow: _setup");
+        let message = "utility workflow '_setup' can't be triggered".to_string();
+        assert_eq!(messages(&contents), vec![(Severity::Warning, Some(7), message)]);
+    }
+}
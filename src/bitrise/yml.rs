@@ -0,0 +1,378 @@
+//! bitrise.yml documents as a tree of nodes with line numbers
+//!
+//! Parsing is done by `yaml-rust2`, so anchors, aliases, tags, flow and
+//! block styles all work as in any YAML tool. Merge keys (`<<: *defaults`)
+//! are applied, with keys written in the mapping itself taking precedence.
+//! Only the first document of a multi-document stream is read.
+//!
+//! Every node keeps the 1-based line it starts on, so problems can be
+//! reported where they are.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::{Marker, TScalarStyle};
+
+/// A parsed YAML value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Null,
+    /// Any scalar; numbers and booleans are kept as written
+    Scalar(String),
+    List(Vec<Node>),
+    /// Entries in file order
+    Map(Vec<(String, Node)>),
+}
+
+/// A value and the line it starts on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    pub value: Value,
+    pub line: usize,
+}
+
+impl Node {
+    fn new(value: Value, line: usize) -> Self {
+        Self { value, line }
+    }
+
+    /// Look up a key in a mapping
+    pub fn get(&self, key: &str) -> Option<&Node> {
+        self.entries().iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// The text of a scalar
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.value {
+            Value::Scalar(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The items of a sequence (empty for anything else)
+    pub fn items(&self) -> &[Node] {
+        match &self.value {
+            Value::List(items) => items,
+            _ => &[],
+        }
+    }
+
+    /// The entries of a mapping (empty for anything else)
+    pub fn entries(&self) -> &[(String, Node)] {
+        match &self.value {
+            Value::Map(entries) => entries,
+            _ => &[],
+        }
+    }
+
+    /// Whether this is a scalar spelling false (`false`, `no`, `off`)
+    pub fn is_false(&self) -> bool {
+        self.as_str()
+            .is_some_and(|s| matches!(s.to_lowercase().as_str(), "false" | "no" | "off"))
+    }
+
+    /// Name of the node's kind, for messages
+    pub fn kind(&self) -> &'static str {
+        match self.value {
+            Value::Null => "nothing",
+            Value::Scalar(_) => "a value",
+            Value::List(_) => "a list",
+            Value::Map(_) => "a mapping",
+        }
+    }
+}
+
+/// Why a document couldn't be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YmlError {
+    /// 1-based line of the problem
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for YmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for YmlError {}
+
+type Parsed<T> = std::result::Result<T, YmlError>;
+
+fn error(line: usize, message: impl Into<String>) -> YmlError {
+    YmlError {
+        line,
+        message: message.into(),
+    }
+}
+
+/// Parse a YAML document
+///
+/// An empty document parses to [`Value::Null`].
+pub fn parse(text: &str) -> Parsed<Node> {
+    let mut builder = Builder::default();
+    Parser::new_from_str(text)
+        .load(&mut builder, false)
+        .map_err(|e| error(e.marker().line(), e.info()))?;
+    if let Some(e) = builder.error {
+        return Err(e);
+    }
+    Ok(builder.root.unwrap_or(Node::new(Value::Null, 1)))
+}
+
+/// A mapping or sequence whose end hasn't been reached yet
+enum Open {
+    Map {
+        line: usize,
+        anchor: usize,
+        entries: Vec<(String, Node)>,
+        /// Entries from `<<` merge keys, added after the mapping's own
+        merged: Vec<(String, Node)>,
+        /// A key waiting for its value
+        key: Option<(String, usize)>,
+    },
+    List {
+        line: usize,
+        anchor: usize,
+        items: Vec<Node>,
+    },
+}
+
+/// Builds the first document's tree from parser events
+#[derive(Default)]
+struct Builder {
+    open: Vec<Open>,
+    anchors: HashMap<usize, Node>,
+    root: Option<Node>,
+    /// The first problem found; later events are ignored
+    error: Option<YmlError>,
+}
+
+impl MarkedEventReceiver for Builder {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Err(e) = self.event(event, mark.line()) {
+            self.error = Some(e);
+        }
+    }
+}
+
+impl Builder {
+    fn event(&mut self, event: Event, line: usize) -> Parsed<()> {
+        match event {
+            Event::Scalar(text, style, anchor, _tag) => {
+                let plain_null = style == TScalarStyle::Plain
+                    && matches!(text.as_str(), "" | "~" | "null" | "Null" | "NULL");
+                let value = if plain_null { Value::Null } else { Value::Scalar(text) };
+                self.finish(Node::new(value, line), anchor)
+            }
+            Event::Alias(id) => match self.anchors.get(&id) {
+                Some(node) => {
+                    let node = Node::new(node.value.clone(), line);
+                    self.finish(node, 0)
+                }
+                None => Err(error(line, "alias refers to an anchor that isn't complete")),
+            },
+            Event::MappingStart(anchor, _tag) => {
+                self.open.push(Open::Map {
+                    line,
+                    anchor,
+                    entries: Vec::new(),
+                    merged: Vec::new(),
+                    key: None,
+                });
+                Ok(())
+            }
+            Event::SequenceStart(anchor, _tag) => {
+                self.open.push(Open::List { line, anchor, items: Vec::new() });
+                Ok(())
+            }
+            Event::MappingEnd | Event::SequenceEnd => match self.open.pop() {
+                Some(Open::Map { line, anchor, mut entries, merged, .. }) => {
+                    for (key, value) in merged {
+                        if !entries.iter().any(|(k, _)| *k == key) {
+                            entries.push((key, value));
+                        }
+                    }
+                    self.finish(Node::new(Value::Map(entries), line), anchor)
+                }
+                Some(Open::List { line, anchor, items }) => {
+                    self.finish(Node::new(Value::List(items), line), anchor)
+                }
+                None => Ok(()),
+            },
+            Event::StreamStart
+            | Event::StreamEnd
+            | Event::DocumentStart
+            | Event::DocumentEnd
+            | Event::Nothing => Ok(()),
+        }
+    }
+
+    /// Place a complete node in its parent, or make it the document
+    fn finish(&mut self, node: Node, anchor: usize) -> Parsed<()> {
+        if anchor > 0 {
+            self.anchors.insert(anchor, node.clone());
+        }
+        match self.open.last_mut() {
+            None => {
+                self.root.get_or_insert(node);
+                Ok(())
+            }
+            Some(Open::List { items, .. }) => {
+                items.push(node);
+                Ok(())
+            }
+            Some(Open::Map { entries, merged, key, .. }) => match key.take() {
+                None => {
+                    let name = match node.value {
+                        Value::Scalar(name) => name,
+                        Value::Null => String::new(),
+                        _ => return Err(error(node.line, "mapping keys must be plain values")),
+                    };
+                    if name != "<<" && entries.iter().any(|(k, _)| *k == name) {
+                        return Err(error(node.line, format!("duplicate key '{}'", name)));
+                    }
+                    *key = Some((name, node.line));
+                    Ok(())
+                }
+                Some((name, _)) if name == "<<" => merge(merged, node),
+                Some((name, key_line)) => {
+                    // A nested block starts on the line of its key
+                    let node = match node.value {
+                        Value::Map(_) | Value::List(_) => Node { line: key_line, ..node },
+                        _ => node,
+                    };
+                    entries.push((name, node));
+                    Ok(())
+                }
+            },
+        }
+    }
+}
+
+/// Add the entries of a `<<` value (a mapping or a list of mappings), where
+/// earlier mappings win over later ones
+fn merge(merged: &mut Vec<(String, Node)>, value: Node) -> Parsed<()> {
+    let sources = match value.value {
+        Value::Map(_) => vec![value],
+        Value::List(items) => items,
+        _ => return Err(error(value.line, "'<<' must merge a mapping or a list of mappings")),
+    };
+    for source in sources {
+        let Value::Map(entries) = source.value else {
+            return Err(error(source.line, "'<<' must merge a mapping or a list of mappings"));
+        };
+        for (key, value) in entries {
+            if !merged.iter().any(|(k, _)| *k == key) {
+                merged.push((key, value));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scalar(node: Option<&Node>) -> Option<&str> {
+        node.and_then(Node::as_str)
+    }
+
+    #[test]
+    fn test_parse_nested_maps_and_lists() {
+        let doc = parse(
+            "format_version: \"13\"\n\
+             # a comment\n\
+             workflows:\n  primary:\n    steps:\n    - git-clone@8: {}\n    - script:\n        title: Run # trailing\n",
+        )
+        .unwrap();
+        assert_eq!(scalar(doc.get("format_version")), Some("13"));
+        let primary = doc.get("workflows").and_then(|w| w.get("primary")).unwrap();
+        assert_eq!(primary.line, 4);
+        let steps = primary.get("steps").unwrap().items();
+        assert_eq!(steps.len(), 2);
+        assert!(steps[0].get("git-clone@8").is_some());
+        let title = steps[1].get("script").and_then(|s| s.get("title"));
+        assert_eq!(scalar(title), Some("Run"));
+        assert_eq!(title.map(|t| t.line), Some(8));
+    }
+
+    #[test]
+    fn test_parse_list_items_as_maps() {
+        let doc = parse(
+            "trigger_map:\n- push_branch: main\n  workflow: deploy\n- tag: 'v*'\n  pipeline: release\n",
+        )
+        .unwrap();
+        let items = doc.get("trigger_map").unwrap().items();
+        assert_eq!(items.len(), 2);
+        assert_eq!(scalar(items[0].get("workflow")), Some("deploy"));
+        assert_eq!(scalar(items[1].get("tag")), Some("v*"));
+        assert_eq!(items[1].line, 4);
+    }
+
+    #[test]
+    fn test_parse_block_scalars() {
+        let doc = parse("content: |-\n  #!/bin/bash\n  echo \"a: b\"\n\nnext: >\n  one\n  two\n")
+            .unwrap();
+        assert_eq!(scalar(doc.get("content")), Some("#!/bin/bash\necho \"a: b\""));
+        assert_eq!(scalar(doc.get("next")), Some("one two\n"));
+    }
+
+    #[test]
+    fn test_parse_flow_collections() {
+        let doc = parse("a: [x, \"y, z\"]\nb: {regex: '^main$', other: 1}\n").unwrap();
+        let list: Vec<_> = doc.get("a").unwrap().items().iter().filter_map(Node::as_str).collect();
+        assert_eq!(list, vec!["x", "y, z"]);
+        assert_eq!(scalar(doc.get("b").and_then(|b| b.get("regex"))), Some("^main$"));
+    }
+
+    #[test]
+    fn test_parse_scalars() {
+        let doc = parse("a: ~\nb: it's\nc: \"tab\\there\"\nd: 'it''s'\ne: http://x.io/a#b\n").unwrap();
+        assert_eq!(doc.get("a").map(|n| &n.value), Some(&Value::Null));
+        assert_eq!(scalar(doc.get("b")), Some("it's"));
+        assert_eq!(scalar(doc.get("c")), Some("tab\there"));
+        assert_eq!(scalar(doc.get("d")), Some("it's"));
+        assert_eq!(scalar(doc.get("e")), Some("http://x.io/a#b"));
+    }
+
+    #[test]
+    fn test_parse_errors_have_lines() {
+        let err = parse("a: 1\n  b: 2\n").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert_eq!(parse("a: 1\na: 2\n").unwrap_err().to_string(), "line 2: duplicate key 'a'");
+        assert_eq!(parse("a: \"open\n").unwrap_err().line, 1);
+        assert_eq!(parse("a:\n\tb: 1\n").unwrap_err().line, 2);
+        assert_eq!(parse("- a\nb: 1\n").unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn test_parse_anchors_aliases_and_merge_keys() {
+        let doc = parse(
+            "app:\n  envs: &envs\n  - A: 1\n\
+             defaults: &defaults\n  stack: linux\n  meta: {tier: small}\n\
+             workflows:\n  test:\n    <<: *defaults\n    envs: *envs\n    stack: macos\n\
+             \x20 deploy: !!map\n    <<: [*defaults]\n---\nsecond: doc\n",
+        )
+        .unwrap();
+        let test = doc.get("workflows").and_then(|w| w.get("test")).unwrap();
+        assert_eq!(scalar(test.get("stack")), Some("macos"));
+        assert_eq!(scalar(test.get("meta").and_then(|m| m.get("tier"))), Some("small"));
+        assert_eq!(test.get("envs").unwrap().items().len(), 1);
+        assert_eq!(test.get("envs").unwrap().line, 10);
+        let deploy = doc.get("workflows").and_then(|w| w.get("deploy")).unwrap();
+        assert_eq!(scalar(deploy.get("stack")), Some("linux"));
+        assert!(doc.get("second").is_none());
+    }
+
+    #[test]
+    fn test_parse_empty_document() {
+        assert_eq!(parse("# nothing\n").unwrap().value, Value::Null);
+    }
+}
@@ -0,0 +1,224 @@
+//! On-disk cache for finished build logs
+//!
+//! Once a build finishes its log never changes, so repeated `log`, `build
+//! --log` or `url --logs` calls can read it from disk instead of downloading
+//! the archive again. Logs are stored as plain text under `<cache>/logs`,
+//! one file per build slug. When the directory grows past its size limit the
+//! least recently read logs are evicted.
+
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use super::Cache;
+use crate::config::CacheConfig;
+use crate::error::Result;
+
+/// Default size limit for cached logs
+pub const DEFAULT_LOG_LIMIT_MB: u64 = 200;
+
+/// File-backed cache of finished build logs
+#[derive(Debug, Clone)]
+pub struct LogCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl LogCache {
+    /// Create a log cache inside a cache directory, capped at `max_mb`
+    pub fn new(cache: &Cache, max_mb: u64) -> Self {
+        Self::with_dir(cache.dir().join("logs"), max_mb * 1024 * 1024)
+    }
+
+    /// Create the log cache for the configured cache directory and
+    /// `cache.log_limit_mb`; `None` when log caching is disabled (limit 0)
+    pub fn for_config(config: &CacheConfig) -> Option<Self> {
+        let max_mb = config.log_limit_mb.unwrap_or(DEFAULT_LOG_LIMIT_MB);
+        if max_mb == 0 {
+            return None;
+        }
+        let cache = Cache::for_config(config).ok()?;
+        Some(Self::new(&cache, max_mb))
+    }
+
+    /// Create a log cache rooted at a specific directory
+    pub fn with_dir(dir: impl Into<PathBuf>, max_bytes: u64) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes,
+        }
+    }
+
+    /// The directory logs are written to
+    pub fn dir(&self) -> &PathBuf {
+        &self.dir
+    }
+
+    /// Read a cached log, marking it as recently used
+    pub fn get(&self, build_slug: &str) -> Option<String> {
+        let mut log = String::new();
+        self.open(build_slug)?.read_to_string(&mut log).ok()?;
+        Some(log)
+    }
+
+    /// Open a cached log for reading, marking it as recently used
+    pub fn open(&self, build_slug: &str) -> Option<File> {
+        let path = self.path_for(build_slug);
+        let file = File::open(&path).ok()?;
+        if let Ok(file) = File::options().append(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(file)
+    }
+
+    /// Store a finished build's log
+    pub fn put(&self, build_slug: &str, log: &str) -> Result<()> {
+        self.store(build_slug, &mut log.as_bytes()).map(drop)
+    }
+
+    /// Stream a finished build's log into the cache and open the stored copy,
+    /// then evict old logs over the size limit
+    ///
+    /// A log larger than the whole limit is evicted right away; the returned
+    /// handle still reads it.
+    pub fn store(&self, build_slug: &str, log: &mut dyn Read) -> Result<File> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path_for(build_slug);
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+        let written = File::create(&tmp).and_then(|mut file| io::copy(log, &mut file));
+        if let Err(e) = written {
+            let _ = fs::remove_file(&tmp);
+            return Err(e.into());
+        }
+        fs::rename(&tmp, &path)?;
+
+        let file = File::open(&path)?;
+        // Eviction failures (e.g. a log open elsewhere) only cost disk space
+        let _ = self.evict();
+        Ok(file)
+    }
+
+    /// Number of cached logs and their total size in bytes
+    pub fn usage(&self) -> (usize, u64) {
+        let files = self.files();
+        (files.len(), files.iter().map(|(_, len, _)| len).sum())
+    }
+
+    /// Remove every cached log; returns how many were removed
+    pub fn clear(&self) -> Result<usize> {
+        let files = self.files();
+        for (path, _, _) in &files {
+            fs::remove_file(path)?;
+        }
+        Ok(files.len())
+    }
+
+    /// Remove least recently used logs until the total fits the limit
+    fn evict(&self) -> Result<()> {
+        let mut files = self.files();
+        let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+        files.sort_by_key(|(_, _, modified)| *modified);
+        for (path, len, _) in files {
+            if total <= self.max_bytes {
+                break;
+            }
+            fs::remove_file(&path)?;
+            total -= len;
+        }
+        Ok(())
+    }
+
+    /// Cached log files with their size and last use
+    fn files(&self) -> Vec<(PathBuf, u64, SystemTime)> {
+        let Ok(dir) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        dir.filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "log"))
+            .filter_map(|e| {
+                let meta = e.metadata().ok()?;
+                let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                Some((e.path(), meta.len(), modified))
+            })
+            .collect()
+    }
+
+    /// File path for a build slug; anything outside `[A-Za-z0-9-_]` becomes `_`
+    fn path_for(&self, build_slug: &str) -> PathBuf {
+        let name: String = build_slug
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        self.dir.join(format!("{}.log", name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_put_and_get() {
+        let temp = TempDir::new().unwrap();
+        let logs = LogCache::with_dir(temp.path(), 1024);
+
+        assert_eq!(logs.get("abc"), None);
+        logs.put("abc", "line 1\nline 2\n").unwrap();
+        assert_eq!(logs.get("abc").as_deref(), Some("line 1\nline 2\n"));
+        assert_eq!(logs.usage(), (1, 14));
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let temp = TempDir::new().unwrap();
+        let logs = LogCache::with_dir(temp.path(), 25);
+
+        logs.put("old", "0123456789").unwrap();
+        let hour_ago = SystemTime::now() - Duration::from_secs(3600);
+        File::options()
+            .append(true)
+            .open(logs.path_for("old"))
+            .unwrap()
+            .set_modified(hour_ago)
+            .unwrap();
+        logs.put("mid", "0123456789").unwrap();
+        logs.put("new", "0123456789").unwrap();
+
+        assert_eq!(logs.get("old"), None);
+        assert!(logs.get("mid").is_some());
+        assert!(logs.get("new").is_some());
+    }
+
+    #[test]
+    fn test_skips_logs_over_the_limit() {
+        let temp = TempDir::new().unwrap();
+        let logs = LogCache::with_dir(temp.path(), 4);
+        logs.put("big", "0123456789").unwrap();
+        assert_eq!(logs.get("big"), None);
+    }
+
+    #[test]
+    fn test_store_streams_and_returns_copy() {
+        let temp = TempDir::new().unwrap();
+        let logs = LogCache::with_dir(temp.path(), 1024);
+
+        let mut stored = logs.store("abc", &mut "streamed\n".as_bytes()).unwrap();
+        let mut contents = String::new();
+        stored.read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "streamed\n");
+        assert_eq!(logs.get("abc").as_deref(), Some("streamed\n"));
+    }
+
+    #[test]
+    fn test_clear() {
+        let temp = TempDir::new().unwrap();
+        let logs = LogCache::with_dir(temp.path(), 1024);
+        logs.put("a", "x").unwrap();
+        logs.put("b", "y").unwrap();
+        assert_eq!(logs.clear().unwrap(), 2);
+        assert_eq!(logs.usage(), (0, 0));
+    }
+}
@@ -0,0 +1,321 @@
+//! Local cache for API data
+//!
+//! Values are stored as JSON files under `~/.reprise/cache`, each wrapped with
+//! the time it was written so callers can decide how stale is too stale.
+//! Cache failures are never fatal: a missing or corrupt entry reads as `None`.
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::config::{CacheConfig, Paths};
+use crate::error::Result;
+
+pub mod logs;
+
+pub use logs::LogCache;
+
+/// A cached value with the time it was stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry<T> {
+    /// When the value was written
+    pub stored_at: DateTime<Utc>,
+    /// The cached value
+    pub value: T,
+}
+
+impl<T> CacheEntry<T> {
+    /// Time elapsed since the entry was stored
+    pub fn age(&self) -> Duration {
+        (Utc::now() - self.stored_at).to_std().unwrap_or_default()
+    }
+
+    /// Whether the entry is younger than `ttl`
+    pub fn is_fresh(&self, ttl: Duration) -> bool {
+        self.age() < ttl
+    }
+}
+
+/// Summary of one cache file, for `reprise cache status`
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheFileInfo {
+    /// Entry key (the file name without `.json`)
+    pub key: String,
+    /// Size on disk
+    pub bytes: u64,
+    /// When the entry was written, if the file is readable
+    pub stored_at: Option<DateTime<Utc>>,
+    /// Number of items when the cached value is a list
+    pub items: Option<usize>,
+}
+
+/// File-backed JSON cache
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    /// Create a cache rooted at the default location (~/.reprise/cache)
+    pub fn new() -> Result<Self> {
+        Ok(Self::with_dir(Paths::new()?.cache_dir()))
+    }
+
+    /// Create a cache in the configured `cache.dir`, or the default location
+    pub fn for_config(config: &CacheConfig) -> Result<Self> {
+        match config.dir.as_deref() {
+            Some(dir) => Ok(Self::with_dir(expand_home(dir)?)),
+            None => Self::new(),
+        }
+    }
+
+    /// Create a cache rooted at a specific directory
+    pub fn with_dir(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The directory this cache writes to
+    pub fn dir(&self) -> &PathBuf {
+        &self.dir
+    }
+
+    /// Read an entry regardless of age
+    pub fn read<T: DeserializeOwned>(&self, key: &str) -> Option<CacheEntry<T>> {
+        let content = fs::read_to_string(self.path_for(key)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Read an entry only if it is younger than `ttl`
+    pub fn get<T: DeserializeOwned>(&self, key: &str, ttl: Duration) -> Option<T> {
+        self.read(key)
+            .filter(|entry| entry.is_fresh(ttl))
+            .map(|entry| entry.value)
+    }
+
+    /// Store a value, stamping it with the current time
+    pub fn put<T: Serialize>(&self, key: &str, value: &T) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let entry = CacheEntry {
+            stored_at: Utc::now(),
+            value,
+        };
+        let path = self.path_for(key);
+
+        // Write to a temp file and rename so concurrent readers never see a partial file
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+        fs::write(&tmp, serde_json::to_string(&entry)?)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// Remove an entry (missing entries are not an error)
+    pub fn remove(&self, key: &str) -> Result<()> {
+        match fs::remove_file(self.path_for(key)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// All entries on disk, sorted by key (empty if the directory is missing)
+    pub fn entries(&self) -> Vec<CacheFileInfo> {
+        let Ok(dir) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+
+        let mut entries: Vec<CacheFileInfo> = dir
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                let path = e.path();
+                if path.extension().is_none_or(|ext| ext != "json") {
+                    return None;
+                }
+                let key = path.file_stem()?.to_string_lossy().into_owned();
+                let bytes = e.metadata().map(|m| m.len()).unwrap_or(0);
+                let entry = self.read::<serde_json::Value>(&key);
+                Some(CacheFileInfo {
+                    key,
+                    bytes,
+                    stored_at: entry.as_ref().map(|e| e.stored_at),
+                    items: entry.and_then(|e| e.value.as_array().map(Vec::len)),
+                })
+            })
+            .collect();
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        entries
+    }
+
+    /// Remove all entries, or only those named `name` or starting with
+    /// `name-` / `name.` (so `prompt-status` clears every prompt status).
+    /// Returns the removed keys.
+    pub fn clear(&self, name: Option<&str>) -> Result<Vec<String>> {
+        let mut removed = Vec::new();
+        for entry in self.entries() {
+            let matches = name.is_none_or(|name| {
+                entry.key == name
+                    || entry
+                        .key
+                        .strip_prefix(name)
+                        .is_some_and(|rest| rest.starts_with(['-', '.']))
+            });
+            if matches {
+                self.remove(&entry.key)?;
+                removed.push(entry.key);
+            }
+        }
+        Ok(removed)
+    }
+
+    /// File path for a key; anything outside `[A-Za-z0-9-_.]` becomes `_`
+    fn path_for(&self, key: &str) -> PathBuf {
+        let name: String = key
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(format!("{}.json", name))
+    }
+}
+
+/// Expand a leading `~/` to the home directory
+fn expand_home(dir: &str) -> Result<PathBuf> {
+    match dir.strip_prefix("~/") {
+        Some(rest) => Ok(PathBuf::from(std::env::var("HOME")?).join(rest)),
+        None => Ok(PathBuf::from(dir)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_put_and_get() {
+        let temp = TempDir::new().unwrap();
+        let cache = Cache::with_dir(temp.path());
+
+        cache.put("answer", &42u32).unwrap();
+        assert_eq!(cache.get::<u32>("answer", Duration::from_secs(60)), Some(42));
+    }
+
+    #[test]
+    fn test_get_missing_key() {
+        let temp = TempDir::new().unwrap();
+        let cache = Cache::with_dir(temp.path());
+        assert_eq!(cache.get::<u32>("missing", Duration::from_secs(60)), None);
+    }
+
+    #[test]
+    fn test_entries_report_size_and_items() {
+        let temp = TempDir::new().unwrap();
+        let cache = Cache::with_dir(temp.path());
+
+        cache.put("apps", &vec!["a", "b", "c"]).unwrap();
+        cache.put("notify_last", &"Build #1").unwrap();
+
+        let entries = cache.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].key, "apps");
+        assert_eq!(entries[0].items, Some(3));
+        assert!(entries[0].bytes > 0);
+        assert!(entries[0].stored_at.is_some());
+        assert_eq!(entries[1].items, None);
+    }
+
+    #[test]
+    fn test_entries_of_missing_dir() {
+        let temp = TempDir::new().unwrap();
+        let cache = Cache::with_dir(temp.path().join("nope"));
+        assert!(cache.entries().is_empty());
+    }
+
+    #[test]
+    fn test_clear_by_name() {
+        let temp = TempDir::new().unwrap();
+        let cache = Cache::with_dir(temp.path());
+
+        cache.put("apps", &1).unwrap();
+        cache.put("prompt-status-abc-main", &1).unwrap();
+        cache.put("prompt-status-abc-main.refreshing", &()).unwrap();
+        cache.put("prompt-statusx", &1).unwrap();
+
+        let removed = cache.clear(Some("prompt-status")).unwrap();
+        assert_eq!(removed.len(), 2);
+        let left: Vec<String> = cache.entries().into_iter().map(|e| e.key).collect();
+        assert_eq!(left, vec!["apps", "prompt-statusx"]);
+
+        assert_eq!(cache.clear(None).unwrap().len(), 2);
+        assert!(cache.entries().is_empty());
+    }
+
+    #[test]
+    fn test_for_config_uses_configured_dir() {
+        let temp = TempDir::new().unwrap();
+        let config = CacheConfig {
+            dir: Some(temp.path().display().to_string()),
+            ..Default::default()
+        };
+        let cache = Cache::for_config(&config).unwrap();
+        assert_eq!(cache.dir(), &temp.path().to_path_buf());
+    }
+
+    #[test]
+    fn test_expand_home() {
+        let home = std::env::var("HOME").unwrap();
+        assert_eq!(expand_home("~/tmp/c").unwrap(), PathBuf::from(home).join("tmp/c"));
+        assert_eq!(expand_home("/dev/shm/c").unwrap(), PathBuf::from("/dev/shm/c"));
+    }
+
+    #[test]
+    fn test_get_respects_ttl() {
+        let temp = TempDir::new().unwrap();
+        let cache = Cache::with_dir(temp.path());
+
+        cache.put("answer", &42u32).unwrap();
+        assert_eq!(cache.get::<u32>("answer", Duration::ZERO), None);
+        // Stale entries are still readable
+        assert_eq!(cache.read::<u32>("answer").unwrap().value, 42);
+    }
+
+    #[test]
+    fn test_keys_are_sanitized() {
+        let temp = TempDir::new().unwrap();
+        let cache = Cache::with_dir(temp.path());
+
+        cache.put("prompt/app/feature/x", &"v").unwrap();
+        assert!(temp.path().join("prompt_app_feature_x.json").exists());
+        assert_eq!(
+            cache.get::<String>("prompt/app/feature/x", Duration::from_secs(60)),
+            Some("v".to_string())
+        );
+    }
+
+    #[test]
+    fn test_corrupt_entry_reads_as_none() {
+        let temp = TempDir::new().unwrap();
+        let cache = Cache::with_dir(temp.path());
+
+        fs::write(temp.path().join("bad.json"), "not json").unwrap();
+        assert!(cache.read::<u32>("bad").is_none());
+    }
+
+    #[test]
+    fn test_remove() {
+        let temp = TempDir::new().unwrap();
+        let cache = Cache::with_dir(temp.path());
+
+        cache.put("k", &1u8).unwrap();
+        cache.remove("k").unwrap();
+        assert!(cache.read::<u8>("k").is_none());
+        // Removing again is fine
+        cache.remove("k").unwrap();
+    }
+}
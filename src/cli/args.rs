@@ -1,11 +1,13 @@
-use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum, ValueHint};
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum, ValueHint};
 use clap_complete::Shell;
 use serde::{Deserialize, Serialize};
 
+use crate::bitrise::{BuildStatus, TriggerSource};
+
 /// A fast, feature-rich CLI for interacting with Bitrise CI/CD
 #[derive(Parser)]
 #[command(name = "reprise")]
-#[command(version, propagate_version = true)]
+#[command(version, propagate_version = true, disable_help_subcommand = true)]
 #[command(about = "A fast, feature-rich CLI for Bitrise")]
 #[command(long_about = "A fast, feature-rich CLI for Bitrise.\n\n\
 Written in Rust, reprise makes it easy to interact with Bitrise CI/CD \
@@ -27,11 +29,16 @@ Quick Start:
 Environment Variables:
   BITRISE_TOKEN    API token (can also use --token flag)
   NO_COLOR         Disable colored output when set
+  REPRISE_*        Override config keys, e.g. REPRISE_DEFAULT_APP,
+                   REPRISE_OUTPUT_FORMAT, REPRISE_CACHE_DIR (see README)
 
 Aliases:
   Many commands have short aliases: builds (b), log (l, logs),
   app (a), pipelines (pl), pipeline (p), artifacts (art)
 
+More help: 'reprise help <topic>' (authentication, caching, exit-codes)
+and 'reprise man <dir>' for man pages
+
 Documentation: https://github.com/dan-hart/reprise")]
 pub struct Cli {
     /// Bitrise API token (overrides config file and BITRISE_TOKEN env var)
@@ -39,8 +46,9 @@ pub struct Cli {
     pub token: Option<String>,
 
     /// Output format: 'pretty' for human-readable, 'json' for scripting
-    #[arg(short, long, value_enum, default_value = "pretty", global = true)]
-    pub output: OutputFormat,
+    /// (default: output.format from config, else pretty)
+    #[arg(short, long, value_enum, global = true)]
+    pub output: Option<OutputFormat>,
 
     /// Quiet mode - suppress non-essential output (progress indicators, hints)
     #[arg(short, long, global = true, conflicts_with = "verbose")]
@@ -50,6 +58,35 @@ pub struct Cli {
     #[arg(short, long, global = true, conflicts_with = "quiet")]
     pub verbose: bool,
 
+    /// Don't pipe long output (log, builds) through a pager
+    #[arg(long, global = true)]
+    pub no_pager: bool,
+
+    /// Never prompt: confirmations fail with a hint to pass --yes instead
+    /// of waiting on stdin (also on when the CI environment variable is set)
+    #[arg(long, global = true)]
+    pub non_interactive: bool,
+
+    /// Lay out pretty output for this many columns instead of the
+    /// terminal's width (e.g. when piping into a file)
+    #[arg(
+        long,
+        global = true,
+        value_name = "COLUMNS",
+        value_parser = clap::value_parser!(u16).range(20..)
+    )]
+    pub width: Option<u16>,
+
+    /// Leave the title and column header rows out of list output, so
+    /// `grep` and `awk` see only the items
+    #[arg(long, global = true)]
+    pub no_header: bool,
+
+    /// JSON layout: 'v1' wraps output in a versioned envelope (default),
+    /// 'legacy' prints the bare payload as before
+    #[arg(long, value_enum, global = true, env = "REPRISE_JSON_COMPAT", value_name = "LAYOUT")]
+    pub json_compat: Option<JsonCompat>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -65,6 +102,27 @@ pub enum OutputFormat {
     Json,
 }
 
+impl OutputFormat {
+    /// Parse the `output.format` config value, falling back to pretty
+    pub fn from_config(value: &str) -> Self {
+        if value.eq_ignore_ascii_case("json") {
+            Self::Json
+        } else {
+            Self::Pretty
+        }
+    }
+}
+
+/// Layout of `-o json` output
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum JsonCompat {
+    /// `{"api_version": 1, "kind": ..., "items": [...]}`
+    #[default]
+    V1,
+    /// The bare payload, without the envelope
+    Legacy,
+}
+
 /// Available commands
 #[derive(Subcommand)]
 pub enum Commands {
@@ -75,8 +133,16 @@ Examples:
   reprise apps --filter ios       Filter apps containing 'ios'
   reprise apps --filter \"My App\"  Filter by partial name match
   reprise apps --limit 10         Show only first 10 apps
+  reprise apps --owner acme       Apps owned by a matching account
+  reprise apps --mine             Apps owned by your personal account
+  reprise apps --type flutter     Only Flutter apps
+  reprise apps --enabled          Hide disabled apps
+  reprise apps --sort activity    Most recently built apps first
   reprise apps -o json            Output as JSON for scripting
-  reprise apps -o json | jq '.[0].slug'  Get first app's slug")]
+  reprise apps -o json | jq '.items[0].slug'  Get first app's slug
+
+Sorting by activity looks up each app's latest build (8 apps at a time)
+and caches the result for 15 minutes, or cache.ttl if set.")]
     Apps(AppsArgs),
 
     /// Show or set the default app
@@ -94,6 +160,12 @@ when the --app flag is not specified. The slug is the unique identifier
 found in your Bitrise app URL: app.bitrise.io/app/<slug>")]
     App(AppArgs),
 
+    /// Show organization details such as members
+    #[command(after_help = "\
+Example:
+  reprise org members acme           Members and roles, for access audits")]
+    Org(OrgArgs),
+
     /// List builds for the default or specified app
     #[command(alias = "b", after_help = "\
 Examples:
@@ -106,16 +178,39 @@ Examples:
   reprise builds --me             Show only my builds
   reprise builds --triggered-by alice  Show builds triggered by 'alice'
   reprise builds --pr 1234        Show builds for PR #1234
+  reprise builds --commit HEAD    Show builds for the checked-out commit
+  reprise builds --commit a1b2c3d Show builds for a commit hash
+  reprise builds --stale-branches Builds of branches that were deleted
   reprise builds --limit 50       Show more builds
+  reprise builds --since 2h       Builds from the last two hours
+  reprise builds --since 2024-05-01 --until 2024-05-07  A date range
+  reprise builds --deltas         Show duration vs. workflow average
+  reprise builds --no-git         Skip the local checkout markers
+  reprise builds --sort duration  Longest builds first
+  reprise builds --group-by branch  Section the list by branch
   reprise builds --app other-app  Use different app
   reprise builds -o json          Output as JSON
 
 Filtering:
-  Use --me to show only builds you triggered (requires API auth).
+  Use --me to show only builds you triggered (requires API auth; your
+  username is cached for a day, see 'reprise cache clear me').
   Use --triggered-by for partial username match (case-insensitive).
   Use --pr to filter by pull request number.
+  Use --commit to filter by commit (searches several pages of history).
+  Use --stale-branches to audit builds of deleted branches. Inside the app's
+  repository, branches come from origin (run 'git fetch --prune' first);
+  elsewhere, from the branches Bitrise reports. Tag builds are skipped.
+  These filters page through history until --limit builds match.
+  Use --since/--until for a time window; dates like 2024-05-01 include the whole day.
   Combine multiple filters: --status failed --branch main --me
 
+Local Checkout:
+  Inside a git repository, builds are marked against your HEAD:
+  ● HEAD           the build ran on your checked-out commit
+  ↑ includes HEAD  the build's commit descends from HEAD (your change is built)
+  ↓ behind HEAD    the build's commit is an ancestor of HEAD (predates it)
+  Commits not fetched locally are left unmarked.
+
 Status Icons (in pretty output):
   [running]  Build is currently in progress
   [success]  Build completed successfully
@@ -134,10 +229,17 @@ Examples:
   reprise build abc123 --logs     Dump the full build log
   reprise build abc123 --artifacts  List build artifacts
 
+Trigger Parameters:
+  The details end with the parameters the build was triggered with
+  (tag, PR, stack, ...) and its custom env vars. Values of env vars
+  that look like credentials (TOKEN, SECRET, PASSWORD, KEY, ...) or are
+  marked sensitive are shown as ****.
+
 Following Builds:
   Use --follow (-f) to stream live log output for running builds.
+  On a terminal, a status line below the log shows the running step.
   Add --notify (-n) to receive a desktop notification when complete.
-  Adjust --interval to change polling frequency (default: 3 seconds).
+  Polls every 3s at first, then backs off to --interval (default: 30 seconds).
 
 Finding Build Slugs:
   The build slug is the unique ID shown in the Bitrise URL after /build/
@@ -152,15 +254,45 @@ Examples:
   reprise l abc123                Short alias
   reprise log abc123 --tail 100   Show last 100 lines
   reprise log abc123 --tail 50 --follow  Follow with context
-  reprise log abc123 --save build.log  Save log to file
+  reprise log abc123 --save build.log  Print the log and save it to a file
+  reprise log abc123 --save build.log --tail 20  Save, then show the end
+  reprise log abc123 --save-dir ./logs  Save as ./logs/app-<app>-build-<n>.log
+  reprise log --branch main --status failed --save-dir ./logs
+                                  Save logs of the last failed builds on main
   reprise log abc123 --follow     Stream live log output
   reprise log abc123 -f --notify  Follow with desktop notification
   reprise log abc123 --app other  View log from different app
+  reprise log abc123 --strip-ansi Plain text, even in a terminal
+  reprise log abc123 --raw | less -R  Keep Bitrise's colors when piping
 
 Output:
-  Logs include ANSI color codes from Bitrise. Colors display in
-  terminals that support them. Use --save to capture raw output.
-  Pipe to 'less -R' for scrollable colored output.")]
+  Logs include ANSI color codes from Bitrise. In a terminal they are
+  shown as-is; when piped or redirected they are stripped so tools like
+  grep see plain text. Use --raw to pass the log through untouched.
+
+  Logs are streamed: --save writes to the file as the log downloads and
+  --tail keeps only the last lines, so even very large logs stay cheap.
+  The log is still printed when saved; add --tail to print only its end.
+
+Saving Several Logs:
+  --save-dir names each file app-<app-slug>-build-<number>.log. Without a
+  build slug it saves the logs of the latest finished builds matching
+  --branch, --status and --workflow (up to --limit, default: 10).
+
+Annotations:
+  reprise log abc123 --annotations github   ::error file=..,line=..:: commands
+  reprise log abc123 --annotations sarif > build.sarif
+
+  Compiler and test diagnostics with a file and line (clang, swiftc,
+  XCTest, Kotlin) are printed instead of the log. Paths under Bitrise's
+  checkout (/Users/vagrant/git, /bitrise/src) or --source-root are made
+  relative to the repository.
+
+Sharing:
+  reprise log abc123 --html build.html   One file that opens in any browser
+
+  Bitrise's colors and your highlight rules are kept, and each step is a
+  collapsible section. Failed steps start expanded.")]
     Log(LogArgs),
 
     /// Manage configuration
@@ -182,6 +314,34 @@ The config file is stored in your system's config directory.
 Use 'reprise config path' to see the exact location.")]
     Config(ConfigArgs),
 
+    /// Inspect or clear locally cached API data
+    #[command(after_help = "\
+Examples:
+  reprise cache status                Show cache location, entries and size
+  reprise cache clear                 Remove everything
+  reprise cache clear apps            Remove only the cached app list
+  reprise cache clear logs            Remove cached build logs
+  reprise cache clear prompt-status   Remove all cached prompt statuses
+  reprise cache clear build-apps      Forget which app each looked-up build is in
+
+The cache lives in ~/.reprise/cache unless cache.dir (or REPRISE_CACHE_DIR)
+points elsewhere. Clearing it is always safe; entries
+are fetched again when needed.")]
+    Cache(CacheArgs),
+
+    /// Show recorded triggers, aborts, rebuilds and downloads
+    #[command(after_help = "\
+Examples:
+  reprise history                     Last 50 actions, newest first
+  reprise history --limit 200         Further back
+  reprise history -o json | jq '.items[] | select(.action == \"abort\")'
+                                      Who aborted what
+
+Every trigger, abort, rebuild and artifact download made with reprise is
+appended to ~/.reprise/history.jsonl with the time, the command line (the
+token masked), app, build or pipeline slug, result and local user.")]
+    History(HistoryArgs),
+
     /// Trigger a new build
     #[command(after_help = "\
 Examples:
@@ -193,11 +353,25 @@ Examples:
   reprise trigger -w primary --wait -n    Wait with desktop notification
   reprise trigger -w primary --app xyz    Trigger for specific app
   reprise trigger -w deploy -m \"Deploy v1.0\"  Add commit message
+  reprise trigger -w release --tag v1.2.0  Build a tag
+  reprise trigger -w ci --commit abc123   Build a specific commit
+  reprise trigger -w ci -b feature/x --pr 42 --pr-target main  Build as a PR
+  reprise trigger -w ci -b main --skip-if-running --wait  Reuse a running build
+  reprise trigger -w nightly --at 02:00   Trigger at 2am (next occurrence)
+  reprise trigger -w ci --in 45m          Trigger in 45 minutes
+  reprise trigger -w ci -b main --then-pipeline release  Run a pipeline after a green build
 
 Options:
-  If --branch is not specified, the repository's default branch is used.
+  If --workflow or --branch is not specified, the app's [apps.\"<slug>\"]
+  config is used; without a branch the repository's default branch is used.
   Use --wait to block until the build completes. Combine with --notify
   for a desktop notification when done. Adjust --interval for polling.
+  With --skip-if-running, a running build of the same workflow and branch
+  (and --commit) is reported, and waited on with --wait, instead.
+  With --at or --in, reprise counts down in the terminal and triggers when
+  the time comes; press Ctrl+C to cancel before then.
+  --then-pipeline waits for the build and then triggers the pipeline on the
+  build's branch and commit when --when matches (default: success).
 
 Environment Variables:
   Use --env KEY=VALUE to pass environment variables to the build.
@@ -205,7 +379,11 @@ Environment Variables:
     Trigger(TriggerArgs),
 
     /// List or download build artifacts
-    #[command(alias = "art", after_help = "\
+    #[command(
+        alias = "art",
+        args_conflicts_with_subcommands = true,
+        subcommand_negates_reqs = true,
+        after_help = "\
 Examples:
   reprise artifacts abc123                List artifacts for build
   reprise art abc123                      Short alias
@@ -221,10 +399,35 @@ Filtering:
   reprise artifacts abc123 -f \"*.ipa\" -d .        Download only IPAs
   reprise artifacts abc123 -f \"*.apk\" --exclude \"*-debug*\"  APKs except debug
 
+Sorting:
+  reprise artifacts abc123 --sort size             Largest first
+  reprise artifacts abc123 --sort name             Alphabetical
+
+Latest Build:
+  reprise artifacts latest                          Newest successful build
+  reprise artifacts latest --branch main -f \"*.ipa\" -d ./out
+  reprise artifacts latest -b release -w deploy     Filter by branch and workflow
+
 Downloading:
   Without -d/--download, artifacts are listed but not downloaded.
   With -d, matching artifacts are downloaded to the specified directory
-  (or current directory if no path given). Existing files are overwritten.")]
+  (or current directory if no path given). Existing files are overwritten.
+
+Expiry:
+  reprise artifacts abc123 --warn-expiring 7d      Warn if deleted within 7 days
+  reprise artifacts abc123 --warn-expiring 7d --retention 90d   Longer plan retention
+
+  Listings show whether each artifact has a public install page and, for
+  signed download links, when the link stops working. --warn-expiring
+  adds when the build's artifacts are deleted (build finish plus
+  --retention) and warns on stderr if that is within the given time.
+
+Sharing:
+  reprise artifacts publish abc123 app.ipa --on    Public install page on
+  reprise artifacts publish abc123 app.ipa --off   And off again
+
+Uploading:
+  reprise artifacts upload coverage.xml --build abc123   Attach a local file")]
     Artifacts(ArtifactsArgs),
 
     /// Abort a running build
@@ -237,10 +440,26 @@ Examples:
 
 Confirmation:
   By default, you'll be prompted to confirm before aborting.
-  Use -y/--yes to skip the confirmation (useful for scripts).
+  Use -y/--yes to skip the confirmation (useful for scripts). Without a
+  terminal to answer on (piped stdin, CI, --non-interactive) the prompt
+  fails instead of waiting.
   The abort reason is optional but helps with debugging.")]
     Abort(AbortArgs),
 
+    /// Run a build again with its original parameters
+    #[command(after_help = "\
+Examples:
+  reprise rebuild abc123                  Rebuild with the same parameters
+  reprise rebuild abc123 --wait           Rebuild and wait for completion
+  reprise rebuild abc123 --wait -n        Wait and notify when done
+  reprise rebuild abc123 --app xyz        Specify app explicitly
+
+Parameters:
+  The new build reuses everything the original was triggered with:
+  branch, workflow, commit, tag, pull request and custom env vars.
+  Same as 'reprise url <build-url> --retry'.")]
+    Rebuild(RebuildArgs),
+
     /// Parse a Bitrise URL or generate URLs from slugs
     #[command(after_help = "\
 Parse URL Examples:
@@ -249,6 +468,8 @@ Parse URL Examples:
   reprise url https://app.bitrise.io/app/xyz/pipelines/123  Show pipeline status
   reprise url <url> --browser                                Open URL in browser
   reprise url <url> --watch                                  Watch build/pipeline progress
+  echo <url> | reprise url -                                 Read the URL from stdin
+  reprise url --clipboard                                    Read the URL from the clipboard
 
 Generate URL Examples:
   reprise url --build abc123                  Generate build URL
@@ -272,6 +493,12 @@ Build URL Actions (Modify):
 App URL Actions:
   reprise url <app-url> --set-default    Set this app as your default
 
+Other URL Shapes:
+  Links to a build's tab open it: <build-url>#artifacts lists artifacts,
+  <build-url>?tab=log dumps the log. Workflow editor links on a workflow
+  list its recent builds; add-on links show the app. Legacy
+  www.bitrise.io links work too.
+
 Tips:
   Copy a URL from Bitrise and paste it here to quickly view status,
   check logs, abort, retry, or download artifacts without setting up app context.
@@ -286,16 +513,23 @@ Examples:
   reprise pipelines --status running Show running pipelines
   reprise pipelines --status failed  Show failed pipelines
   reprise pipelines --branch main    Filter by branch
+  reprise pipelines --workflow deploy  Pipelines that run 'deploy'
   reprise pipelines --me             Show only my pipelines
   reprise pipelines --triggered-by bob  Show pipelines triggered by 'bob'
   reprise pipelines --limit 50       Show more pipelines
+  reprise pipelines --since yesterday --until yesterday  Yesterday's pipelines
   reprise pipelines -o json          Output as JSON
 
 Filtering:
   Use --me to show only pipelines you triggered (requires API auth).
   Use --triggered-by for partial username match (case-insensitive).
+  Use --workflow to keep pipelines that contain a workflow (exact name).
+  Use --since/--until for a time window (searches several pages of history).
   Combine multiple filters: --status running --branch main
 
+Each pipeline shows its workflow progress, e.g. 3/5 ✓ for three of five
+workflows succeeded.
+
 Pipelines vs Builds:
   Pipelines orchestrate multiple workflows in stages. Use 'builds'
   to see individual workflow executions within a pipeline.")]
@@ -327,6 +561,426 @@ Subcommands:
 Use 'reprise pipeline <subcommand> --help' for subcommand details.")]
     Pipeline(PipelineArgs),
 
+    /// Download a build's app artifact and install it on a device or simulator
+    #[command(after_help = "\
+Examples:
+  reprise install abc123                      Install the build's .apk/.ipa
+  reprise install abc123 --filter \"*-debug.apk\"  Pick a specific artifact
+  reprise install abc123 --device emulator-5554  Target a specific device
+  reprise install abc123 --filter \"*.app.zip\"  Install on the booted simulator
+
+Installers:
+  .apk       adb install -r (Android device or emulator)
+  .ipa       xcrun devicectl device install app (iOS device, needs --device)
+  .app.zip   unzipped, then xcrun simctl install (iOS simulator)
+
+Exactly one installable artifact must match; use --filter to choose.")]
+    Install(InstallArgs),
+
+    /// Download a build's dSYMs for crash symbolication
+    #[command(after_help = "\
+Examples:
+  reprise dsyms abc123 -d ./symbols           dSYM zips of a build
+  reprise dsyms latest -w deploy --unzip      Newest successful deploy, unzipped
+  reprise dsyms abc123 --filter \"*symbols*\"   Pick artifacts by name instead
+
+Artifacts with 'dSYM' in their name (e.g. App.app.dSYM.zip) are picked
+unless --filter is given. --unzip extracts each zip next to it with
+'unzip'; the zips are kept. Files go to --dir, the app's download_dir,
+or the current directory.")]
+    Dsyms(DsymsArgs),
+
+    /// Show code coverage from a build's coverage reports
+    #[command(after_help = "\
+Examples:
+  reprise coverage                            Newest successful build
+  reprise coverage abc123                     A specific build
+  reprise coverage latest -b main             Newest successful build on main
+  reprise coverage --trend --since 30d -b main   Coverage over the last 30 days
+
+Reports are artifacts named like lcov.info, *.lcov, cobertura*.xml or
+coverage*.xml (use --filter to pick others) in LCOV or Cobertura format.
+Several reports in one build are added up. --trend charts successful
+builds that have a report, oldest first.")]
+    Coverage(CoverageArgs),
+
+    /// Wait for the build of a commit to finish (for merge gating)
+    #[command(after_help = "\
+Examples:
+  reprise wait --commit HEAD                  Wait for CI on the checked-out commit
+  reprise wait --commit a1b2c3d --timeout 1h  Wait up to an hour
+  reprise wait --commit HEAD --workflow ci    Only consider the 'ci' workflow
+  reprise wait --commit HEAD && git merge ... Gate a merge on CI
+
+Polls until a build for the commit exists and finishes. When several
+workflows build the commit, the latest build of each must succeed.
+
+Exit codes:
+  0    All builds succeeded
+  1    A build failed or was aborted
+  75   Timed out (no finished build before --timeout)
+  130  Interrupted with Ctrl+C")]
+    Wait(WaitArgs),
+
+    /// Wait for one build to meet a condition, printing only the result
+    #[command(after_help = "\
+Examples:
+  reprise wait-build abc123 --until finished              Any outcome
+  reprise wait-build abc123 --until status=success        Exit 0 only on success
+  reprise wait-build abc123 --until status=failed --timeout 2h
+  reprise wait-build \"$slug\" -q --until status=success && ./deploy.sh
+
+Conditions:
+  finished          The build stopped running, whatever the outcome
+  status=success    The build succeeded
+  status=failed     The build failed
+  status=aborted    The build was aborted
+
+Prints nothing while waiting and one line (or one JSON object) at the end,
+so it composes in shell scripts; use 'build --follow' to watch a build.
+
+Exit codes:
+  0    The condition was met
+  1    The build finished without meeting it (e.g. failed for status=success)
+  2    Invalid --until or --timeout
+  66   No such build
+  75   Timed out (the build is still running after --timeout)
+  130  Interrupted with Ctrl+C")]
+    WaitBuild(WaitBuildArgs),
+
+    /// Keep watching apps and branches, notifying when their status changes
+    #[command(after_help = "\
+Examples:
+  reprise watch-daemon ios:main android:main    Watch two branches
+  reprise watch-daemon                          Watch daemon.targets from the config
+  reprise watch-daemon ios --interval 600       Any branch of ios, every 10 minutes
+  reprise watch-daemon ios:main --once          Check once (for cron or launchd)
+  reprise watch-daemon -o json >> status.jsonl  Log every finished build
+
+Targets are APP or APP:BRANCH, where APP is a slug, alias or name. Each
+poll looks at the newest finished build of every target. A build that
+ends differently from the target's previous one (main broke, or was
+fixed) shows a desktop notification and, with a webhook configured, a
+Slack message. Other new builds are only printed. The configured hooks
+run for every new build.
+
+The last build per target is kept in the cache, so a restarted daemon
+reports what changed while it was stopped. Run it in the background with
+your service manager, tmux or nohup; Ctrl+C stops it.
+
+Config:
+  [daemon]
+  targets = [\"ios:main\", \"android:main\"]
+  interval = 300
+  slack_webhook = \"https://hooks.slack.com/services/...\"")]
+    WatchDaemon(WatchDaemonArgs),
+
+    /// Show the latest build status in the system tray / menu bar
+    #[cfg(feature = "tray")]
+    #[command(after_help = "\
+Examples:
+  reprise tray                      Default app, any branch
+  reprise tray -a ios -b main       The ios app's main branch
+  reprise tray --interval 60        Poll every minute
+
+The icon is green, red or gray for the newest finished build's outcome,
+and its menu shows that build with Open Build, Rebuild and Pause Polling.
+Polling uses the same engine and daemon.interval as watch-daemon, and a
+status change also shows a desktop notification. Choose Quit to stop.
+
+Only available in builds with the 'tray' feature; on Linux it needs GTK
+and libappindicator.")]
+    Tray(TrayArgs),
+
+    /// Summarize builds and pipelines for a pull request
+    #[command(after_help = "\
+Examples:
+  reprise pr 1234                 Builds and pipelines for PR #1234
+  reprise pr 1234 --app ios       Use a specific app or alias
+  reprise pr 1234 -o json         Output as JSON for scripting
+
+Builds are matched by pull request number (searching recent history).
+Pipelines are matched by the PR's source branch.")]
+    Pr(PrArgs),
+
+    /// Show recent builds and pipelines together, newest first
+    #[command(after_help = "\
+Examples:
+  reprise runs                    Recent builds and pipelines
+  reprise runs --status running   What CI is doing right now
+  reprise runs --branch main      Only runs on main
+  reprise runs --app ios -o json  Output as JSON for scripting
+
+Each row is marked 'build' or 'pipeline'. Builds started by a pipeline's
+workflows also appear as their own rows.")]
+    Runs(RunsArgs),
+
+    /// Show a CI overview for the current git repository and branch
+    #[command(alias = "st", after_help = "\
+Examples:
+  reprise status                  Overview for the current branch
+  reprise st                      Short alias
+  reprise status --branch main    Overview for another branch
+  reprise status --app ios        Use a specific app or alias
+  reprise status -o json          Output as JSON for scripting
+
+Shows whether HEAD has been built, the latest builds on the branch,
+and any pipelines currently running for the app.
+
+The app is detected from the 'origin' remote when --app is not given,
+falling back to the default app.")]
+    Status(StatusArgs),
+
+    /// Show running and latest builds across all your apps
+    #[command(alias = "ov", after_help = "\
+Examples:
+  reprise overview                Latest build for every app
+  reprise ov                      Short alias
+  reprise overview --running      Only apps with builds in progress
+  reprise overview --branch main  Latest build on main for each app
+  reprise overview --refresh      Re-fetch the app list
+  reprise overview -o json        Output as JSON
+
+Apps are queried concurrently. The app list is cached for an hour;
+use --refresh after adding or removing apps on Bitrise.")]
+    Overview(OverviewArgs),
+
+    /// Grid of the latest build status per branch and workflow
+    #[command(after_help = "\
+Examples:
+  reprise matrix                                       Every recently built branch
+  reprise matrix --branches main,develop,release/*     Pick rows; globs allowed
+  reprise matrix -b main,develop -w unit,ui            Pick columns too
+  reprise matrix -b main -w unit,ui -o json            Cells as JSON
+
+Each cell is the newest build of that workflow on that branch, colored by
+status, with how long ago it was triggered. Globs such as release/* match
+branches among the last --limit builds; branches named outright are also
+looked up further back. Without --workflows, the columns are the
+workflows seen on the chosen branches.")]
+    Matrix(MatrixArgs),
+
+    /// Show who triggered builds in a period, with failures and credits
+    #[command(after_help = "\
+Examples:
+  reprise activity                    Builds per user over the last 7 days
+  reprise activity --since 1d         Since yesterday, e.g. for standup
+  reprise activity --since this-week --branch main
+  reprise activity --since 30d -o json
+
+Columns:
+  FAIL %   Failed share of finished builds (aborted builds don't count)
+  RERUNS   Builds of a commit and workflow the same user had already built
+  CREDITS  Credits the user's builds cost
+
+Every build in the period is fetched, so long periods on busy apps take
+a few requests.")]
+    Activity(ActivityArgs),
+
+    /// Write a Markdown or HTML digest of recent builds, e.g. nightly
+    #[command(after_help = "\
+Examples:
+  reprise report                                 Last 24 hours as Markdown
+  reprise report --since 24h --out report.md     Write to a file
+  reprise report --since 7d --out weekly.html    HTML, e.g. for email
+  reprise report -b main --format html > main.html
+
+The report lists the number of builds and how they ended, the failed
+builds with links, workflows whose successful builds got slower than in
+the 7 days before (by 20% and at least a minute), build time and credits.
+The format defaults to HTML for a .html --out path and Markdown
+otherwise. With -o json and no --out, prints the report's data instead.
+
+Nightly from cron:
+  0 7 * * * reprise report --since 24h --out /tmp/ci.md && post-to-slack /tmp/ci.md")]
+    Report(ReportArgs),
+
+    /// Most common causes of failed builds, from their logs
+    #[command(after_help = "\
+Examples:
+  reprise failures                      Top failure causes of the last 7 days
+  reprise failures --since 30d -b main  On main over a month
+  reprise failures -w primary --top 5   Five most common, one workflow
+
+Error lines (error, fatal, exception, panic) of each failed build's log
+are grouped by signature: paths cut to file names and numbers, hashes and
+IDs replaced, so the same failure matches across builds. Each build counts
+once per signature. Logs are read from the log cache when possible; at
+most --max-builds logs are read, newest first.")]
+    Failures(FailuresArgs),
+
+    /// Show the trigger map and workflow triggers from bitrise.yml
+    #[command(after_help = "\
+Examples:
+  reprise triggers                    Triggers of the default app
+  reprise triggers --app ios          Triggers of another app
+  reprise triggers --file bitrise.yml Read a local bitrise.yml instead
+  reprise triggers -o json
+
+Lists the push, pull request and tag rules that start builds, with the
+line each is defined on: the app-wide trigger_map (checked in order, the
+first match wins), then the triggers of each pipeline and workflow.
+Apps that keep bitrise.yml in their repository need --file.")]
+    Triggers(TriggersArgs),
+
+    /// List the build stacks and machine types available to an app
+    #[command(after_help = "\
+Examples:
+  reprise stacks                      Stacks and machine types of the default app
+  reprise stacks --app ios            Of another app
+  reprise stacks osx-xcode-15.4.x     Check a stack is available (exits 2 if not)
+  reprise stacks --find-xcode 15.4    Stacks with Xcode 15.4 (15 matches any 15.x)
+  reprise stacks -o json
+
+The XCODE column is read from the stack ID. Use a stack ID as the
+'stack' of a workflow's meta in bitrise.yml.")]
+    Stacks(StacksArgs),
+
+    /// List the Bitrise addons enabled for an app (Test Reports, Insights, ...)
+    #[command(after_help = "\
+Examples:
+  reprise addons                      Enabled addons of the default app
+  reprise addons --app ios            Of another app
+  reprise addons --all                Include addons that aren't enabled
+  reprise addons -o json
+
+Each addon's URL opens it in the Bitrise web UI for the app.")]
+    Addons(AddonsArgs),
+
+    /// Work with Release Management releases (list, show, create, add a build)
+    #[command(after_help = "\
+Examples:
+  reprise release list                      Releases of the default app
+  reprise release show 2.4.0                A release by version or ID
+  reprise release create 2.5.0              Start a release
+  reprise release add-build 2.5.0 abc123    Make build abc123 the release candidate
+
+Releases belong to the app's Release Management connected app. When a
+Bitrise app has several (e.g. an iOS and an Android store app), choose
+one with --connected-app.")]
+    Release(ReleaseArgs),
+
+    /// Show which versions were deployed where, and when
+    #[command(after_help = "\
+Examples:
+  reprise deploys                         Recent deploys of the default app
+  reprise deploys --limit 200             Look further back
+  reprise deploys --workflow ship-ios     Treat only this workflow as a deploy
+  reprise deploys -o json
+
+A successful build is a deploy when its workflow runs a store or
+distribution step (App Store Connect, Google Play, Firebase App
+Distribution, App Center), found by reading the app's bitrise.yml
+on Bitrise. When bitrise.yml is kept in the repository, workflows are
+picked by name (deploy, release, store, ...) instead. Versions come
+from the build's IPA, APK or AAB artifact.")]
+    Deploys(DeploysArgs),
+
+    /// Check a local bitrise.yml for errors before committing it
+    #[command(name = "validate-yml", after_help = "\
+Examples:
+  reprise validate-yml bitrise.yml            Check a file
+  reprise validate-yml --strict bitrise.yml   Fail on warnings too
+  git show :bitrise.yml | reprise validate-yml -   Check the staged version
+
+Checks, with line numbers:
+  Syntax errors and duplicate keys
+  Missing format_version and unknown keys
+  Steps, envs and inputs that aren't single 'key: value' entries
+  before_run/after_run, stages, pipelines, step bundles and trigger_map
+  entries that name something the file doesn't define
+
+Runs locally and needs no API token. Exits with code 65 when errors
+are found (or warnings, with --strict), so it can gate a pre-commit hook.")]
+    ValidateYml(ValidateYmlArgs),
+
+    /// Compare the bitrise.yml of builds
+    #[command(after_help = "\
+Examples:
+  reprise yml diff abc123 def456        What changed in CI config between runs
+  reprise yml diff abc123 def456 | less -R
+  reprise yml diff abc123 def456 -o json
+
+Each build's bitrise.yml is the snapshot Bitrise stored when it ran, so
+the diff shows the config the builds actually used. Builds started before
+Bitrise kept snapshots have none.")]
+    Yml(YmlArgs),
+
+    /// Export build history to a file for offline analysis
+    #[command(after_help = "\
+Examples:
+  reprise export builds --since 90d --out builds.json
+  reprise export builds --since 90d --format csv --out builds.csv
+  reprise export builds --branch main --status failed --out failed.csv
+  reprise export builds --since 2024-01-01 --until 2024-03-31 --out q1.json
+  reprise export builds --format csv | grep failed   Write to stdout
+
+Exports page through the app's full build history (or everything since
+--since), so builds can be archived beyond Bitrise's retention period.
+The format defaults to CSV for a .csv --out path and JSON otherwise.")]
+    Export(ExportArgs),
+
+    /// Measure the latency of key Bitrise API endpoints
+    #[command(after_help = "\
+Examples:
+  reprise benchmark                 10 requests per endpoint, default app
+  reprise benchmark -n 50 -a ios    More requests, another app
+  reprise benchmark -o json
+
+Times listing apps, listing builds and fetching a build's log metadata,
+and reports p50/p95 latency per endpoint. A TCP connect to the API host
+is timed too: it is the network round trip alone, so slow connects point
+at the network and slow requests over fast connects point at Bitrise.")]
+    Benchmark(BenchmarkArgs),
+
+    /// Send builds to an OpenTelemetry tracing backend
+    #[command(after_help = "\
+Examples:
+  reprise otel export <slug> --endpoint http://localhost:4318
+  reprise otel export latest -b main --endpoint https://otlp.example.com
+  reprise otel export <slug> --header x-api-key=... --service-name ios-ci
+  reprise otel export <slug> --dry-run              Print the OTLP JSON
+
+A build becomes one trace: a span for the build, one for its time in the
+queue, and one per step, with step times read from the build log. Spans
+are sent as OTLP/HTTP JSON to <endpoint>/v1/traces. The endpoint and
+headers default to OTEL_EXPORTER_OTLP_ENDPOINT and
+OTEL_EXPORTER_OTLP_HEADERS. Trace IDs come from the build slug, so
+exporting a build again doesn't duplicate it.")]
+    Otel(OtelArgs),
+
+    /// Print a compact status of the latest build on the current branch
+    #[command(name = "prompt-status", after_help = "\
+Examples:
+  reprise prompt-status                   e.g. '✓ #4211 3m'
+  reprise prompt-status --app ios         Use a specific app or alias
+  reprise prompt-status --ttl 300         Refresh at most every 5 minutes
+
+Designed for shell prompts (starship, PS1). Output comes from a local
+cache so it returns instantly; when the cache is stale a background
+refresh is started. Prints nothing (and exits 0) outside a git branch
+or when no app/token is configured.
+
+Starship:
+  [custom.bitrise]
+  command = \"reprise prompt-status\"
+  when = \"git rev-parse --git-dir\"")]
+    PromptStatus(PromptStatusArgs),
+
+    /// Print the JSON Schema of a JSON output
+    #[command(after_help = "\
+Examples:
+  reprise schema builds                   Schema of 'builds -o json'
+  reprise schema apps > apps.schema.json  Save for validation or codegen
+  reprise schema pipelines
+  reprise schema artifacts
+
+The schemas (JSON Schema 2020-12) describe the envelope printed by
+'builds', 'apps', 'pipelines' and 'artifacts' with -o json; the items are
+the bare array printed with --json-compat legacy. Optional fields are
+always present and null when unset.")]
+    Schema(SchemaArgs),
+
     /// Generate shell completions
     #[command(after_help = "\
 Examples:
@@ -339,8 +993,35 @@ Installation:
   Bash:   Source the file in your .bashrc
   Zsh:    Place in a directory in your $fpath, then run 'compinit'
   Fish:   Place in ~/.config/fish/completions/
-  PowerShell: Add 'Import-Module ./reprise.ps1' to your profile")]
+  PowerShell: Add 'Import-Module ./reprise.ps1' to your profile
+
+Dynamic values (bash, zsh, fish):
+  --app completes aliases and cached apps, --workflow the workflows in
+  ./bitrise.yml and recent builds, and 'config alias' alias names. They are
+  read from the config and cache only, so completion never waits on the API.")]
     Completions(CompletionsArgs),
+
+    /// Show help for a command, or a long-form help topic
+    #[command(after_help = crate::cli::topics::list())]
+    Help(HelpArgs),
+
+    /// Write man pages for reprise and all its commands
+    #[command(after_help = "\
+Examples:
+  reprise man ./man                                   Write the pages
+  sudo reprise man /usr/local/share/man/man1          Install them
+  man -l ./man/reprise-builds.1                       Read one
+
+One page per command and subcommand, named like reprise-config-alias.1.")]
+    Man(ManArgs),
+
+    /// Show a build notification and handle its actions (started by reprise)
+    #[command(name = "notify-handler", hide = true)]
+    NotifyHandler(NotifyHandlerArgs),
+
+    /// Print completion candidates (called by the shell completion scripts)
+    #[command(name = "__complete", hide = true)]
+    Complete(CompleteArgs),
 }
 
 /// Arguments for the apps command
@@ -350,9 +1031,73 @@ pub struct AppsArgs {
     #[arg(short, long, value_name = "TEXT")]
     pub filter: Option<String>,
 
+    /// Filter by owner (account name or slug, case-insensitive partial match)
+    #[arg(long, value_name = "NAME")]
+    pub owner: Option<String>,
+
+    /// Only apps owned by your personal account
+    #[arg(long, conflicts_with = "owner")]
+    pub mine: bool,
+
+    /// Filter by project type
+    #[arg(long = "type", value_enum, value_name = "TYPE")]
+    pub project_type: Option<ProjectType>,
+
+    /// Only disabled apps
+    #[arg(long, conflicts_with = "enabled")]
+    pub disabled: bool,
+
+    /// Only enabled apps
+    #[arg(long)]
+    pub enabled: bool,
+
     /// Maximum number of apps to return
-    #[arg(short, long, default_value = "50", value_name = "N")]
+    #[arg(short, long, default_value = "50", value_name = "N", value_parser = parse_limit)]
     pub limit: u32,
+
+    /// Sort apps (name: alphabetical, activity: most recently built first)
+    #[arg(long, value_enum)]
+    pub sort: Option<AppSort>,
+}
+
+/// Sort order for app listings
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AppSort {
+    /// Alphabetical by name
+    Name,
+    /// Most recently built first
+    Activity,
+}
+
+/// Bitrise project types
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProjectType {
+    Ios,
+    Android,
+    Flutter,
+    ReactNative,
+    Macos,
+    Xamarin,
+    Cordova,
+    Ionic,
+    Other,
+}
+
+impl ProjectType {
+    /// The `project_type` value Bitrise reports
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ios => "ios",
+            Self::Android => "android",
+            Self::Flutter => "flutter",
+            Self::ReactNative => "react-native",
+            Self::Macos => "macos",
+            Self::Xamarin => "xamarin",
+            Self::Cordova => "cordova",
+            Self::Ionic => "ionic",
+            Self::Other => "other",
+        }
+    }
 }
 
 /// Arguments for the app command
@@ -393,6 +1138,66 @@ Example:
 Shows the app slug and name. If no default is set, you'll be
 prompted to set one. Use 'reprise app set' to change it.")]
     Show,
+
+    /// Remove the default app
+    #[command(after_help = "\
+Example:
+  reprise app unset                  Commands then need --app
+
+The app stays in 'reprise app recent' so it can be restored later.")]
+    Unset,
+
+    /// List recent default apps, or switch back to one
+    #[command(after_help = "\
+Examples:
+  reprise app recent                 List the last 5 default apps
+  reprise app recent 2               Make the second one the default again
+
+Every 'reprise app set' is remembered; the current default is marked.")]
+    Recent {
+        /// Number of the app to make the default again (from the list)
+        #[arg(value_name = "N")]
+        number: Option<usize>,
+    },
+
+    /// List the users with access to an app and their roles
+    #[command(after_help = "\
+Examples:
+  reprise app members                List members of the default app
+  reprise app members --app ios      Another app
+  reprise app members -o json | jq -r '.items[] | select(.role == \"admin\") | .email'
+
+Shows who can see and change the app, for access reviews. Listing
+members needs a token with admin access to the app.")]
+    Members {
+        /// App slug (overrides default app)
+        #[arg(short, long)]
+        app: Option<String>,
+    },
+}
+
+/// Arguments for the org command
+#[derive(Args)]
+pub struct OrgArgs {
+    #[command(subcommand)]
+    pub command: OrgCommands,
+}
+
+/// Org subcommands
+#[derive(Subcommand)]
+pub enum OrgCommands {
+    /// List the members of an organization and their roles
+    #[command(after_help = "\
+Examples:
+  reprise org members acme           By organization name
+  reprise org members 1a2b3c4d5e6f   By organization slug
+
+A name is matched (case-insensitively, first partial match) against the
+organizations your token belongs to.")]
+    Members {
+        /// Organization slug or name
+        org: String,
+    },
 }
 
 /// Arguments for the builds command
@@ -430,21 +1235,81 @@ pub struct BuildsArgs {
     #[arg(long, value_name = "DURATION")]
     pub since: Option<String>,
 
+    /// Show builds before a time (e.g., 2h, yesterday, 2025-01-15 includes that whole day)
+    #[arg(long, value_name = "DURATION")]
+    pub until: Option<String>,
+
     /// Filter by pull request number
     #[arg(long, value_name = "NUMBER")]
     pub pr: Option<i64>,
 
+    /// Filter by commit (full/short hash, or a git revision like HEAD)
+    #[arg(long, value_name = "SHA")]
+    pub commit: Option<String>,
+
+    /// Show only builds of branches that no longer exist
+    #[arg(long)]
+    pub stale_branches: bool,
+
     /// Maximum number of builds to return
-    #[arg(short, long, default_value = "25", value_name = "N")]
+    #[arg(short, long, default_value = "25", value_name = "N", value_parser = parse_limit)]
     pub limit: u32,
 
+    /// Show each build's duration delta vs. the average of recent successful
+    /// builds of the same workflow (e.g., +2m 10s), and a sparkline of the
+    /// listed builds' durations
+    #[arg(long)]
+    pub deltas: bool,
+
+    /// Don't mark builds whose commit is, includes or is behind the local git HEAD
+    #[arg(long)]
+    pub no_git: bool,
+
+    /// Sort builds (duration: longest first, number, finished: most recent first)
+    #[arg(long, value_enum)]
+    pub sort: Option<BuildSort>,
+
+    /// Group builds into sections
+    #[arg(long, value_enum, value_name = "KEY")]
+    pub group_by: Option<BuildGroupBy>,
+
     /// Watch mode - continuously refresh the build list
     #[arg(long)]
     pub watch: bool,
 
     /// Refresh interval in seconds for watch mode (default: 10)
-    #[arg(long, default_value = "10", value_name = "SECS", requires = "watch")]
-    pub interval: u64,
+    #[arg(long, value_name = "SECS", requires = "watch")]
+    pub interval: Option<u64>,
+}
+
+/// Sort order for build listings
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuildSort {
+    /// Longest first
+    Duration,
+    /// Highest build number first
+    Number,
+    /// Most recently finished first
+    Finished,
+}
+
+/// Grouping key for build listings
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuildGroupBy {
+    Branch,
+    Workflow,
+    Status,
+}
+
+impl BuildGroupBy {
+    /// Name used as the grouping key in JSON output
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Branch => "branch",
+            Self::Workflow => "workflow",
+            Self::Status => "status",
+        }
+    }
 }
 
 /// Build status filter options
@@ -461,13 +1326,13 @@ pub enum BuildStatusFilter {
 }
 
 impl BuildStatusFilter {
-    /// Convert to Bitrise API status code
-    pub fn to_api_code(self) -> i32 {
+    /// The build status this filter selects
+    pub fn to_status(self) -> BuildStatus {
         match self {
-            Self::Running => 0,
-            Self::Success => 1,
-            Self::Failed => 2,
-            Self::Aborted => 3,
+            Self::Running => BuildStatus::Running,
+            Self::Success => BuildStatus::Success,
+            Self::Failed => BuildStatus::Failed,
+            Self::Aborted => BuildStatus::Aborted,
         }
     }
 }
@@ -495,8 +1360,8 @@ pub struct BuildArgs {
     #[arg(long, conflicts_with_all = ["follow", "logs"])]
     pub artifacts: bool,
 
-    /// Polling interval in seconds when following (1-60 recommended)
-    #[arg(long, default_value = "3", value_name = "SECS")]
+    /// Longest wait between polls in seconds (polls every 3s at first)
+    #[arg(long, default_value = "30", value_name = "SECS")]
     pub interval: u64,
 
     /// Send desktop notification when build completes (with --follow)
@@ -508,8 +1373,8 @@ pub struct BuildArgs {
 #[derive(Args)]
 pub struct LogArgs {
     /// Build slug (unique ID from Bitrise URL or 'builds' output)
-    #[arg(value_name = "SLUG")]
-    pub slug: String,
+    #[arg(value_name = "SLUG", required_unless_present = "save_dir")]
+    pub slug: Option<String>,
 
     /// App slug (overrides default)
     #[arg(short, long)]
@@ -519,21 +1384,92 @@ pub struct LogArgs {
     #[arg(short, long, value_name = "LINES")]
     pub tail: Option<usize>,
 
-    /// Save log to file (creates or overwrites)
+    /// Also save the log to a file (creates or overwrites); with --tail,
+    /// the whole log is saved and only the last lines are printed
     #[arg(long, value_hint = ValueHint::FilePath, value_name = "PATH")]
     pub save: Option<String>,
 
+    /// Save logs into a directory as app-<app-slug>-build-<number>.log
+    #[arg(
+        long,
+        value_hint = ValueHint::DirPath,
+        value_name = "DIR",
+        conflicts_with_all = ["save", "tail", "follow"]
+    )]
+    pub save_dir: Option<String>,
+
+    /// With --save-dir and no slug: only builds on this branch
+    #[arg(short, long, requires = "save_dir", conflicts_with = "slug")]
+    pub branch: Option<String>,
+
+    /// With --save-dir and no slug: only builds with this status
+    #[arg(short, long, value_enum, requires = "save_dir", conflicts_with = "slug")]
+    pub status: Option<BuildStatusFilter>,
+
+    /// With --save-dir and no slug: only builds of this workflow
+    #[arg(short, long, requires = "save_dir", conflicts_with = "slug")]
+    pub workflow: Option<String>,
+
+    /// With --save-dir and no slug: number of builds to save (default: 10)
+    #[arg(
+        short,
+        long,
+        requires = "save_dir",
+        conflicts_with = "slug",
+        value_parser = parse_limit
+    )]
+    pub limit: Option<u32>,
+
     /// Follow log output (stream live for running builds)
     #[arg(short, long)]
     pub follow: bool,
 
-    /// Polling interval in seconds when following (1-60 recommended)
-    #[arg(long, default_value = "3", value_name = "SECS")]
-    pub interval: u64,
+    /// Longest wait between polls in seconds (polls every 3s at first; default: 30)
+    #[arg(long, value_name = "SECS")]
+    pub interval: Option<u64>,
 
     /// Send desktop notification when build completes (with --follow)
     #[arg(short, long)]
     pub notify: bool,
+
+    /// Remove ANSI escape codes from the log (default when piped)
+    #[arg(long, conflicts_with = "raw")]
+    pub strip_ansi: bool,
+
+    /// Print the log exactly as received: keep ANSI codes, no highlighting
+    #[arg(long)]
+    pub raw: bool,
+
+    /// Print compiler and test diagnostics as annotations instead of the log
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        conflicts_with_all = ["save", "save_dir", "tail", "follow"]
+    )]
+    pub annotations: Option<AnnotationFormat>,
+
+    /// Write the log as a standalone HTML page with collapsible steps
+    #[arg(
+        long,
+        value_hint = ValueHint::FilePath,
+        value_name = "PATH",
+        conflicts_with_all = ["save", "save_dir", "tail", "follow", "annotations"]
+    )]
+    pub html: Option<String>,
+
+    /// Checkout directory to strip from annotation paths
+    #[arg(long, value_name = "PATH", requires = "annotations")]
+    pub source_root: Option<String>,
+}
+
+/// Annotation formats for `log --annotations`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnnotationFormat {
+    /// GitHub Actions workflow commands (::error file=..::message)
+    Github,
+    /// SARIF 2.1.0, for code scanning tools
+    Sarif,
 }
 
 /// Arguments for the config command
@@ -562,12 +1498,39 @@ Examples:
   reprise config set defaults.app_slug abc123def456
   reprise config set defaults.app_name \"My iOS App\"
   reprise config set output.format json
+  reprise config set output.pager \"less -RS\"
+  reprise config set output.pager \"\"   Disable paging
+  reprise config set hooks.on_build_failure \"./page-me.sh {slug}\"
+  reprise config set theme.success \"bright green\"
 
 Available Keys:
-  api.token           Your Bitrise personal access token
+  api.token           Your Bitrise personal access token (verified via /me)
   defaults.app_slug   Default app slug for commands
   defaults.app_name   Display name for default app
   output.format       Default output format (pretty or json)
+  output.pager        Pager for long output (default: $PAGER or less -R)
+  log.default_rules   Apply built-in log highlighting (true or false)
+  cache.ttl           Seconds cached API data stays fresh (default: 3600;
+                      cache.ttl_secs is accepted too)
+  cache.dir           Cache directory (default: ~/.reprise/cache)
+  cache.log_limit_mb  Size limit for cached build logs (default: 200, 0 disables)
+  notify.min_interval Minimum seconds between desktop notifications
+  notify.quiet_hours  Local time range without notifications (e.g. 22:00-08:00)
+  notify.webhook_url  URL that finished watched builds are POSTed to as JSON
+  notify.ntfy_topic   ntfy topic that finished watched builds are pushed to
+  notify.ntfy_server  ntfy server for the topic (default: https://ntfy.sh)
+  hooks.on_build_success  Command run when a watched build succeeds
+  hooks.on_build_failure  Command run when a watched build fails
+  hooks.on_build_abort    Command run when a watched build is aborted
+  hooks.on_build_finish   Command run after any watched build finishes
+  daemon.interval     Seconds between watch-daemon polls (default: 300)
+  daemon.slack_webhook  Slack incoming webhook for watch-daemon status changes
+  theme.running       Color of running statuses (e.g. yellow, bright blue)
+  theme.success       Color of successful statuses
+  theme.failed        Color of failed statuses
+  theme.aborted       Color of aborted statuses
+  theme.ascii         ASCII status icons instead of Unicode (true or false)
+  update.check        Check daily for newer releases (true or false)
 
 Get your API token from: https://app.bitrise.io/me/profile#/security")]
     Set {
@@ -594,13 +1557,60 @@ Example:
   reprise config init                Start interactive setup
 
 Walks you through setting up:
-  1. Your Bitrise API token
-  2. Default app selection
+  1. Your Bitrise API token (checked against your account)
+  2. Default app, picked from your apps
   3. Output format preference
 
+Everything is saved together once the wizard finishes.
+
 This is the recommended way to get started with reprise.")]
     Init,
 
+    /// Check the config file for unknown keys and invalid values
+    #[command(after_help = "\
+Examples:
+  reprise config lint                Report problems with line numbers
+  reprise config lint -o json        Machine-readable report
+
+Unknown keys (often typos) are warnings; values reprise can't use, such as
+an output.format other than pretty or json, are errors. Exits non-zero
+when any errors are found.")]
+    Lint,
+
+    /// Print the configuration as TOML for sharing or moving to another machine
+    #[command(after_help = "\
+Examples:
+  reprise config export > reprise.toml           Back up everything
+  reprise config export --no-secrets > team.toml Share without your token
+
+Exports the config file as written, including aliases and per-app settings,
+always as TOML so 'config import' can read it back. Values from REPRISE_*
+environment variables are not included.")]
+    Export {
+        /// Leave out the API token and Slack webhook
+        #[arg(long)]
+        no_secrets: bool,
+    },
+
+    /// Import settings from a file written by 'config export'
+    #[command(after_help = "\
+Examples:
+  reprise config import team.toml     Apply shared team defaults
+
+Sections in the file replace yours, except [aliases] and [apps], which are
+merged by name. Your API token, default app and webhooks are kept unless the
+file sets them. [hooks] runs shell commands, so it is only imported with
+--with-hooks.")]
+    Import {
+        /// TOML file to import
+        #[arg(value_hint = ValueHint::FilePath)]
+        file: String,
+
+        /// Also import the file's [hooks] (shell commands run after builds)
+        #[arg(long)]
+        with_hooks: bool,
+    },
+
     /// Manage app aliases (shortcuts for app slugs)
     #[command(after_help = "\
 Examples:
@@ -608,13 +1618,19 @@ Examples:
   reprise config alias ignite-ios                Show alias value
   reprise config alias ignite-ios abc123def456   Set alias
   reprise config alias ignite-ios --remove       Remove alias
+  reprise config alias export aliases.toml       Share your aliases
+  reprise config alias import aliases.toml       Merge a teammate's aliases
 
 Aliases allow you to use short names instead of long app slugs:
   reprise builds --app ignite-ios    # Uses alias
   reprise builds --app abc123def456  # Uses slug directly
 
 Aliases are stored in ~/.reprise/config.toml under [aliases].")]
+    #[command(args_conflicts_with_subcommands = true)]
     Alias {
+        #[command(subcommand)]
+        command: Option<AliasCommands>,
+
         /// Alias name (e.g., \"ignite-ios\"). Omit to list all aliases.
         name: Option<String>,
 
@@ -625,28 +1641,280 @@ Aliases are stored in ~/.reprise/config.toml under [aliases].")]
         #[arg(short, long)]
         remove: bool,
     },
+
+    /// Revert the last config change
+    #[command(after_help = "\
+Examples:
+  reprise config undo                Put back what the last change replaced
+  reprise config undo && reprise config undo   Walk back two changes
+
+Every change reprise saves (config set, default app, aliases, output
+format, import) is journaled in ~/.reprise/undo.json, up to the last 20.
+Keys changed again since are left alone. The API token is never journaled,
+so token changes can't be undone.")]
+    Undo,
 }
 
-/// Arguments for the trigger command
-#[derive(Args)]
-pub struct TriggerArgs {
-    /// Workflow name to run (as defined in bitrise.yml)
-    #[arg(short, long)]
-    pub workflow: String,
+/// Alias subcommands for sharing aliases between people
+#[derive(Subcommand)]
+pub enum AliasCommands {
+    /// Write your aliases to a TOML file to share with your team
+    #[command(after_help = "\
+Examples:
+  reprise config alias export aliases.toml   Write to a file
+  reprise config alias export                Print to stdout")]
+    Export {
+        /// File to write (creates or overwrites); prints to stdout when omitted
+        #[arg(value_hint = ValueHint::FilePath)]
+        file: Option<String>,
+    },
 
-    /// Branch to build (defaults to repo's default branch)
-    #[arg(short, long)]
-    pub branch: Option<String>,
+    /// Import aliases from a file written by 'config alias export'
+    #[command(after_help = "\
+Examples:
+  reprise config alias import aliases.toml                        Merge
+  reprise config alias import aliases.toml --strategy overwrite   Replace yours
+
+The file may be an alias export or a full 'config export'; only its
+[aliases] are imported.
+
+Strategies:
+  merge      Add the file's aliases and update ones with the same name;
+             your other aliases are kept (default)
+  overwrite  Replace all of your aliases with the file's")]
+    Import {
+        /// TOML file to import
+        #[arg(value_hint = ValueHint::FilePath)]
+        file: String,
+
+        /// How to combine the file's aliases with yours
+        #[arg(long, value_enum, default_value = "merge")]
+        strategy: AliasImportStrategy,
+    },
+}
 
-    /// App slug (overrides default)
-    #[arg(short, long)]
-    pub app: Option<String>,
+/// How imported aliases are combined with existing ones
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AliasImportStrategy {
+    /// Add new aliases and update same-named ones, keeping the rest
+    Merge,
+    /// Replace all existing aliases
+    Overwrite,
+}
 
-    /// Commit message for the build (shown in Bitrise UI)
-    #[arg(short, long)]
-    pub message: Option<String>,
+/// Arguments for the history command
+#[derive(Args)]
+pub struct HistoryArgs {
+    /// Maximum number of actions to show
+    #[arg(short, long, default_value = "50", value_name = "N", value_parser = parse_limit)]
+    pub limit: u32,
+}
 
-    /// Environment variables in KEY=VALUE format (repeatable)
+/// Arguments for the cache command
+#[derive(Args)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub command: CacheCommands,
+}
+
+/// Cache subcommands
+#[derive(Subcommand)]
+pub enum CacheCommands {
+    /// Show the cache location, its entries and their size on disk
+    Status,
+
+    /// Remove cached entries
+    Clear {
+        /// Entry to remove (e.g. apps, logs, prompt-status); omit to clear everything
+        entry: Option<String>,
+    },
+}
+
+/// Arguments for the yml command
+#[derive(Args)]
+pub struct YmlArgs {
+    #[command(subcommand)]
+    pub command: YmlCommands,
+}
+
+/// Yml subcommands
+#[derive(Subcommand)]
+pub enum YmlCommands {
+    /// Show a unified diff of the bitrise.yml two builds ran with
+    Diff(YmlDiffArgs),
+}
+
+/// Arguments for yml diff
+#[derive(Args)]
+pub struct YmlDiffArgs {
+    /// Build to compare from (slug)
+    #[arg(value_name = "BUILD1")]
+    pub from: String,
+
+    /// Build to compare to (slug)
+    #[arg(value_name = "BUILD2")]
+    pub to: String,
+
+    /// App slug (overrides default app)
+    #[arg(short, long)]
+    pub app: Option<String>,
+}
+
+/// Arguments for the export command
+#[derive(Args)]
+pub struct ExportArgs {
+    #[command(subcommand)]
+    pub command: ExportCommands,
+}
+
+/// Export subcommands
+#[derive(Subcommand)]
+pub enum ExportCommands {
+    /// Export build history as JSON or CSV
+    Builds(ExportBuildsArgs),
+}
+
+/// Arguments for export builds
+#[derive(Args)]
+pub struct ExportBuildsArgs {
+    /// App slug (overrides default app)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Only builds with this status (running, success, failed, aborted)
+    #[arg(short, long, value_enum)]
+    pub status: Option<BuildStatusFilter>,
+
+    /// Only builds on this branch (exact match)
+    #[arg(short, long)]
+    pub branch: Option<String>,
+
+    /// Only builds of this workflow (exact match)
+    #[arg(short, long)]
+    pub workflow: Option<String>,
+
+    /// Export builds since a time (e.g., 90d, 1w, 2025-01-15); default: all history
+    #[arg(long, value_name = "DURATION")]
+    pub since: Option<String>,
+
+    /// Export builds before a time (e.g., yesterday, 2025-01-15 includes that whole day)
+    #[arg(long, value_name = "DURATION")]
+    pub until: Option<String>,
+
+    /// File format (default: csv for a .csv --out path, otherwise json)
+    #[arg(long, value_enum)]
+    pub format: Option<ExportFormat>,
+
+    /// File to write (creates or overwrites); prints to stdout when omitted
+    #[arg(long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    pub out: Option<String>,
+}
+
+/// File format for exports
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+/// Arguments for the benchmark command
+#[derive(Args)]
+pub struct BenchmarkArgs {
+    /// App slug (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Requests per endpoint
+    #[arg(
+        short = 'n',
+        long,
+        default_value = "10",
+        value_parser = clap::value_parser!(u64).range(1..=100)
+    )]
+    pub requests: u64,
+}
+
+/// Arguments for the otel command
+#[derive(Args)]
+pub struct OtelArgs {
+    #[command(subcommand)]
+    pub command: OtelCommands,
+}
+
+/// OpenTelemetry subcommands
+#[derive(Subcommand)]
+pub enum OtelCommands {
+    /// Export a finished build and its steps as trace spans
+    Export(OtelExportArgs),
+}
+
+/// Arguments for otel export
+#[derive(Args)]
+pub struct OtelExportArgs {
+    /// Build slug (unique ID from Bitrise URL or 'builds' output), or 'latest'
+    /// for the newest successful build
+    #[arg(value_name = "SLUG")]
+    pub slug: String,
+
+    /// App slug (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Branch to pick the latest build from (with 'latest')
+    #[arg(short, long)]
+    pub branch: Option<String>,
+
+    /// Workflow to pick the latest build from (with 'latest')
+    #[arg(short, long)]
+    pub workflow: Option<String>,
+
+    /// OTLP/HTTP endpoint, e.g. http://localhost:4318 (/v1/traces is appended)
+    #[arg(
+        long,
+        env = "OTEL_EXPORTER_OTLP_ENDPOINT",
+        value_hint = ValueHint::Url,
+        value_name = "URL",
+        required_unless_present = "dry_run"
+    )]
+    pub endpoint: Option<String>,
+
+    /// Request header for the endpoint, e.g. for authentication (repeatable)
+    #[arg(long = "header", value_name = "KEY=VALUE")]
+    pub headers: Vec<String>,
+
+    /// service.name of the exported trace
+    #[arg(long, env = "OTEL_SERVICE_NAME", default_value = crate::otel::DEFAULT_SERVICE_NAME)]
+    pub service_name: String,
+
+    /// Print the OTLP JSON instead of sending it
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Arguments for the trigger command
+#[derive(Args)]
+pub struct TriggerArgs {
+    /// Workflow name to run (as defined in bitrise.yml; defaults to the
+    /// app's configured workflow)
+    #[arg(short, long)]
+    pub workflow: Option<String>,
+
+    /// Branch to build (defaults to repo's default branch)
+    #[arg(short, long)]
+    pub branch: Option<String>,
+
+    /// App slug (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Commit message for the build (shown in Bitrise UI)
+    #[arg(short, long)]
+    pub message: Option<String>,
+
+    #[command(flatten)]
+    pub source: TriggerSourceArgs,
+
+    /// Environment variables in KEY=VALUE format (repeatable)
     #[arg(long, value_name = "KEY=VALUE", value_parser = parse_env_var)]
     pub env: Vec<(String, String)>,
 
@@ -658,22 +1926,118 @@ pub struct TriggerArgs {
     #[arg(short, long)]
     pub notify: bool,
 
-    /// Polling interval in seconds when waiting (1-60 recommended)
-    #[arg(long, default_value = "10", value_name = "SECS")]
-    pub interval: u64,
+    /// Longest wait between polls in seconds (polls every 3s at first; default: 30)
+    #[arg(long, value_name = "SECS")]
+    pub interval: Option<u64>,
+
+    /// Don't trigger if a build of the same workflow and branch (and
+    /// --commit, if given) is already running; reuse that build instead
+    #[arg(long)]
+    pub skip_if_running: bool,
+
+    /// Wait until this time of day (24-hour, e.g. 02:00) before triggering
+    #[arg(long, value_name = "TIME", conflicts_with = "in_")]
+    pub at: Option<String>,
+
+    /// Wait this long (e.g. 45m, 2h) before triggering
+    #[arg(long = "in", value_name = "DURATION")]
+    pub in_: Option<String>,
+
+    /// Pipeline to trigger once the build finishes (implies waiting for it)
+    #[arg(long, value_name = "PIPELINE")]
+    pub then_pipeline: Option<String>,
+
+    /// Build outcome that triggers --then-pipeline
+    #[arg(long, value_enum, default_value = "success", requires = "then_pipeline")]
+    pub when: ChainWhen,
+}
+
+/// Build outcome that triggers a chained pipeline
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChainWhen {
+    /// Build completed successfully
+    Success,
+    /// Build failed (not aborted)
+    Failure,
+    /// Build finished with any outcome
+    Always,
+}
+
+impl ChainWhen {
+    /// Whether a finished build with this status satisfies the condition
+    pub fn matches(self, status: BuildStatus) -> bool {
+        match self {
+            Self::Success => status == BuildStatus::Success,
+            Self::Failure => status == BuildStatus::Failed,
+            Self::Always => true,
+        }
+    }
+
+    /// Name as given on the command line
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Failure => "failure",
+            Self::Always => "always",
+        }
+    }
+}
+
+/// Tag, commit and pull request options shared by build and pipeline triggers
+#[derive(Args, Clone, Default)]
+pub struct TriggerSourceArgs {
+    /// Git tag to build (e.g., v1.2.0)
+    #[arg(long)]
+    pub tag: Option<String>,
+
+    /// Commit hash to build
+    #[arg(long, value_name = "SHA")]
+    pub commit: Option<String>,
+
+    /// Pull request number to build as
+    #[arg(long, value_name = "NUMBER")]
+    pub pr: Option<u64>,
+
+    /// Target branch of the pull request (with --pr)
+    #[arg(long, value_name = "BRANCH", requires = "pr")]
+    pub pr_target: Option<String>,
+}
+
+impl TriggerSourceArgs {
+    /// Convert to the client's trigger source parameters
+    pub fn to_source(&self) -> TriggerSource {
+        TriggerSource {
+            tag: self.tag.clone(),
+            commit_hash: self.commit.clone(),
+            pull_request_id: self.pr,
+            branch_dest: self.pr_target.clone(),
+        }
+    }
 }
 
 /// Arguments for the artifacts command
 #[derive(Args)]
 pub struct ArtifactsArgs {
-    /// Build slug (unique ID from Bitrise URL or 'builds' output)
-    #[arg(value_name = "SLUG")]
-    pub slug: String,
+    /// Build slug (unique ID from Bitrise URL or 'builds' output), or 'latest'
+    /// for the newest successful build
+    #[arg(value_name = "SLUG", required = true)]
+    pub slug: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<ArtifactsCommands>,
 
     /// App slug (overrides default)
     #[arg(short, long)]
     pub app: Option<String>,
 
+    /// Branch to pick the latest build from (with 'latest')
+    #[arg(short, long)]
+    pub branch: Option<String>,
+
+    /// Workflow to pick the latest build from (with 'latest')
+    #[arg(short, long)]
+    pub workflow: Option<String>,
+
     /// Download artifacts to directory (current dir if no path given)
     #[arg(short, long, value_hint = ValueHint::DirPath, value_name = "DIR")]
     pub download: Option<Option<String>>,
@@ -685,6 +2049,171 @@ pub struct ArtifactsArgs {
     /// Exclude artifacts matching glob pattern (e.g., "*.dSYM*")
     #[arg(long, value_name = "PATTERN")]
     pub exclude: Option<String>,
+
+    /// Sort artifacts (size: largest first, name, or type)
+    #[arg(long, value_enum)]
+    pub sort: Option<ArtifactSort>,
+
+    /// Warn when the build's artifacts are deleted within this long (e.g. 7d)
+    #[arg(long, value_name = "DURATION")]
+    pub warn_expiring: Option<String>,
+
+    /// How long your Bitrise plan keeps artifacts (with --warn-expiring)
+    #[arg(long, value_name = "DURATION", default_value = "30d", requires = "warn_expiring")]
+    pub retention: String,
+}
+
+/// Artifacts subcommands
+#[derive(Subcommand)]
+pub enum ArtifactsCommands {
+    /// Turn an artifact's public install page on or off
+    #[command(after_help = "\
+Examples:
+  reprise artifacts publish abc123 app.ipa --on        Share a test build
+  reprise artifacts publish latest \"*.apk\" --on --qr   With a QR code to scan
+  reprise artifacts publish abc123 app.ipa --off       Stop sharing
+
+The artifact is matched by slug, title or glob pattern and must be
+unique. --qr renders the install URL with 'qrencode', if installed.")]
+    Publish(ArtifactPublishArgs),
+
+    /// Attach a local file (coverage report, screenshots) to a build
+    #[command(after_help = "\
+Examples:
+  reprise artifacts upload coverage.xml --build abc123
+  reprise artifacts upload shots.zip --build abc123 --title screenshots.zip
+  reprise artifacts upload report.html --build latest --type file
+
+Bitrise must allow artifact uploads for your token and build; a refusal
+is reported with the HTTP status.")]
+    Upload(ArtifactUploadArgs),
+}
+
+/// Arguments for the artifacts publish command
+#[derive(Args)]
+pub struct ArtifactPublishArgs {
+    /// Build slug, or 'latest' for the newest successful build
+    #[arg(value_name = "SLUG")]
+    pub build: String,
+
+    /// Artifact slug, title or glob pattern (e.g. "*.ipa")
+    pub artifact: String,
+
+    /// Enable the public install page
+    #[arg(long, conflicts_with = "off", required_unless_present = "off")]
+    pub on: bool,
+
+    /// Disable the public install page
+    #[arg(long)]
+    pub off: bool,
+
+    /// Print a QR code of the install URL (needs 'qrencode')
+    #[arg(long, requires = "on")]
+    pub qr: bool,
+
+    /// App slug (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+}
+
+/// Arguments for the artifacts upload command
+#[derive(Args)]
+pub struct ArtifactUploadArgs {
+    /// File to upload
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub file: String,
+
+    /// Build slug, or 'latest' for the newest successful build
+    #[arg(long, value_name = "SLUG")]
+    pub build: String,
+
+    /// Artifact title shown on Bitrise (default: the file name)
+    #[arg(long)]
+    pub title: Option<String>,
+
+    /// Artifact type, e.g. file, ios-ipa, android-apk
+    #[arg(long = "type", value_name = "TYPE", default_value = "file")]
+    pub artifact_type: String,
+
+    /// App slug (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+}
+
+/// Sort order for artifact listings
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArtifactSort {
+    /// Largest first
+    Size,
+    /// Alphabetical by title
+    Name,
+    /// Grouped by artifact type, then title
+    Type,
+}
+
+/// Arguments for the dsyms command
+#[derive(Args)]
+pub struct DsymsArgs {
+    /// Build slug (unique ID from Bitrise URL or 'builds' output), or 'latest'
+    /// for the newest successful build
+    #[arg(value_name = "SLUG")]
+    pub slug: String,
+
+    /// App slug (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Branch to pick the latest build from (with 'latest')
+    #[arg(short, long)]
+    pub branch: Option<String>,
+
+    /// Workflow to pick the latest build from (with 'latest')
+    #[arg(short, long)]
+    pub workflow: Option<String>,
+
+    /// Directory to download to
+    #[arg(short, long, value_hint = ValueHint::DirPath, value_name = "DIR")]
+    pub dir: Option<String>,
+
+    /// Pick artifacts by glob pattern instead of by 'dSYM' in their name
+    #[arg(short, long, value_name = "PATTERN")]
+    pub filter: Option<String>,
+
+    /// Extract downloaded zips (keeps the zips)
+    #[arg(long)]
+    pub unzip: bool,
+}
+
+/// Arguments for the coverage command
+#[derive(Args)]
+pub struct CoverageArgs {
+    /// Build slug, or 'latest' for the newest successful build
+    #[arg(value_name = "SLUG", default_value = "latest", conflicts_with = "trend")]
+    pub slug: String,
+
+    /// App slug (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Only builds on this branch
+    #[arg(short, long)]
+    pub branch: Option<String>,
+
+    /// Only builds of this workflow
+    #[arg(short, long)]
+    pub workflow: Option<String>,
+
+    /// Pick report artifacts by glob pattern instead of by name
+    #[arg(short, long, value_name = "PATTERN")]
+    pub filter: Option<String>,
+
+    /// Chart coverage of successful builds over time
+    #[arg(long)]
+    pub trend: bool,
+
+    /// Start of the trend (e.g., 30d, 1w, 2025-01-15)
+    #[arg(long, default_value = "30d", value_name = "DURATION", requires = "trend")]
+    pub since: String,
 }
 
 /// Arguments for the abort command
@@ -707,13 +2236,41 @@ pub struct AbortArgs {
     pub yes: bool,
 }
 
+/// Arguments for the rebuild command
+#[derive(Args)]
+pub struct RebuildArgs {
+    /// Build slug (unique ID from Bitrise URL or 'builds' output)
+    #[arg(value_name = "SLUG")]
+    pub slug: String,
+
+    /// App slug (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Wait for the new build to complete
+    #[arg(short, long)]
+    pub wait: bool,
+
+    /// Longest wait between polls in seconds (polls every 3s at first)
+    #[arg(long, default_value = "30", value_name = "SECS", requires = "wait")]
+    pub interval: u64,
+
+    /// Send desktop notification when the build completes (with --wait)
+    #[arg(short, long, requires = "wait")]
+    pub notify: bool,
+}
+
 /// Arguments for the url command
 #[derive(Args)]
 pub struct UrlArgs {
-    /// Bitrise URL to parse (app, build, or pipeline URL)
-    #[arg(value_hint = ValueHint::Url, required_unless_present_any = ["gen_build", "gen_app", "gen_pipeline"])]
+    /// Bitrise URL to parse (app, build, or pipeline URL); '-' reads it from stdin
+    #[arg(value_hint = ValueHint::Url, required_unless_present_any = ["gen_build", "gen_app", "gen_pipeline", "clipboard"])]
     pub url: Option<String>,
 
+    /// Read the URL from the system clipboard
+    #[arg(long, conflicts_with_all = ["url", "gen_build", "gen_app", "gen_pipeline"])]
+    pub clipboard: bool,
+
     /// Generate URL for a build slug (instead of parsing a URL)
     #[arg(long = "build", value_name = "SLUG", conflicts_with_all = ["url", "gen_app", "gen_pipeline"])]
     pub gen_build: Option<String>,
@@ -738,8 +2295,8 @@ pub struct UrlArgs {
     #[arg(short, long)]
     pub watch: bool,
 
-    /// Polling interval in seconds when watching/following (default: 5)
-    #[arg(long, default_value = "5", value_name = "SECS")]
+    /// Longest wait between polls in seconds (polls every 3s at first)
+    #[arg(long, default_value = "30", value_name = "SECS")]
     pub interval: u64,
 
     /// Send desktop notification when build/pipeline completes
@@ -806,6 +2363,10 @@ pub struct PipelinesArgs {
     #[arg(short, long)]
     pub branch: Option<String>,
 
+    /// Show only pipelines containing this workflow (exact name)
+    #[arg(short, long)]
+    pub workflow: Option<String>,
+
     /// Filter by user who triggered (partial match, case-insensitive)
     #[arg(long, value_name = "USER")]
     pub triggered_by: Option<String>,
@@ -818,8 +2379,12 @@ pub struct PipelinesArgs {
     #[arg(long, value_name = "DURATION")]
     pub since: Option<String>,
 
-    /// Maximum number of pipelines to return
-    #[arg(short, long, default_value = "25", value_name = "N")]
+    /// Show pipelines before a time (e.g., 2h, yesterday, 2025-01-15 includes that whole day)
+    #[arg(long, value_name = "DURATION")]
+    pub until: Option<String>,
+
+    /// Maximum number of pipelines to return
+    #[arg(short, long, default_value = "25", value_name = "N", value_parser = parse_limit)]
     pub limit: u32,
 }
 
@@ -843,12 +2408,13 @@ Examples:
   reprise pipeline show abc123       Show pipeline details
   reprise pipeline show abc123 -o json  Output as JSON
   reprise pipeline show abc123 --app xyz  Specify app
+  reprise pipeline show abc123 --follow-failed  Show the failed workflow's log
 
 Displays pipeline information including:
   - Pipeline name and ID
   - Current status and duration
   - Branch and commit info
-  - Stage breakdown with individual workflow status")]
+  - Stage breakdown with each workflow's build number, duration and link")]
     Show {
         /// Pipeline ID (from 'pipelines' command or Bitrise URL)
         id: String,
@@ -856,6 +2422,10 @@ Displays pipeline information including:
         /// App slug (overrides default)
         #[arg(short, long)]
         app: Option<String>,
+
+        /// Print the log of the first failed workflow's build instead
+        #[arg(long)]
+        follow_failed: bool,
     },
 
     /// Trigger a new pipeline run
@@ -867,9 +2437,14 @@ Examples:
   reprise pipeline trigger release --env VERSION=1.0.0
   reprise pipeline trigger ci --env A=1 --env B=2
   reprise pipeline trigger deploy --wait --notify
+  reprise pipeline trigger release --tag v1.2.0
+  reprise pipeline trigger ci --commit abc123 --branch main
+  reprise pipeline trigger ci -b feature/x --pr 42 --pr-target main
 
 Options:
   If --branch is not specified, the repository's default branch is used.
+  Use --tag or --commit to build a specific tag or commit, and --pr with
+  --pr-target to run with pull request context.
   Use --wait to block until the pipeline completes.
   Add --notify for a desktop notification when done.
 
@@ -887,6 +2462,9 @@ Environment Variables:
         #[arg(short, long)]
         app: Option<String>,
 
+        #[command(flatten)]
+        source: TriggerSourceArgs,
+
         /// Environment variables in KEY=VALUE format (repeatable)
         #[arg(long, value_name = "KEY=VALUE", value_parser = parse_env_var)]
         env: Vec<(String, String)>,
@@ -899,8 +2477,8 @@ Environment Variables:
         #[arg(short, long)]
         notify: bool,
 
-        /// Polling interval in seconds when waiting (default: 10)
-        #[arg(long, default_value = "10", value_name = "SECS")]
+        /// Longest wait between polls in seconds (polls every 3s at first)
+        #[arg(long, default_value = "30", value_name = "SECS")]
         interval: u64,
     },
 
@@ -965,8 +2543,8 @@ Partial rebuilds are faster and preserve successful work.")]
         #[arg(short, long)]
         notify: bool,
 
-        /// Polling interval in seconds when waiting (default: 10)
-        #[arg(long, default_value = "10", value_name = "SECS")]
+        /// Longest wait between polls in seconds (polls every 3s at first)
+        #[arg(long, default_value = "30", value_name = "SECS")]
         interval: u64,
     },
 
@@ -990,8 +2568,8 @@ pipeline completes (success, failure, or abort).")]
         #[arg(short, long)]
         app: Option<String>,
 
-        /// Polling interval in seconds (default: 5)
-        #[arg(long, default_value = "5", value_name = "SECS")]
+        /// Longest wait between polls in seconds (polls every 3s at first)
+        #[arg(long, default_value = "30", value_name = "SECS")]
         interval: u64,
 
         /// Send desktop notification when pipeline completes
@@ -1000,6 +2578,522 @@ pipeline completes (success, failure, or abort).")]
     },
 }
 
+/// Arguments for the install command
+#[derive(Args)]
+pub struct InstallArgs {
+    /// Build slug (unique ID from Bitrise URL or 'builds' output)
+    #[arg(value_name = "SLUG")]
+    pub slug: String,
+
+    /// App slug (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Only consider artifacts matching glob pattern (e.g., "*.apk")
+    #[arg(short, long, value_name = "PATTERN")]
+    pub filter: Option<String>,
+
+    /// Device to install on (adb serial, iOS device UDID, or simulator UDID)
+    #[arg(short, long, value_name = "ID")]
+    pub device: Option<String>,
+}
+
+/// Arguments for the wait command
+#[derive(Args)]
+pub struct WaitArgs {
+    /// Commit to wait for (full/short hash, or a git revision like HEAD)
+    #[arg(long, value_name = "SHA")]
+    pub commit: String,
+
+    /// Only consider builds of this workflow
+    #[arg(short, long)]
+    pub workflow: Option<String>,
+
+    /// App slug (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Give up after this long (e.g., 90s, 30m, 2h)
+    #[arg(long, default_value = "30m", value_name = "DURATION")]
+    pub timeout: String,
+
+    /// Longest wait between polls in seconds (polls every 3s at first)
+    #[arg(long, default_value = "30", value_name = "SECS")]
+    pub interval: u64,
+
+    /// Send desktop notification when the wait finishes
+    #[arg(short, long)]
+    pub notify: bool,
+}
+
+/// Arguments for the wait-build command
+#[derive(Args)]
+pub struct WaitBuildArgs {
+    /// Build slug to wait for
+    pub slug: String,
+
+    /// Condition to wait for: finished, or status=success|failed|aborted
+    #[arg(long, value_name = "CONDITION", value_parser = parse_until)]
+    pub until: UntilCondition,
+
+    /// App slug (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Give up after this long (e.g., 90s, 30m, 2h)
+    #[arg(long, default_value = "30m", value_name = "DURATION")]
+    pub timeout: String,
+
+    /// Longest wait between polls in seconds (polls every 3s at first)
+    #[arg(long, default_value = "30", value_name = "SECS")]
+    pub interval: u64,
+}
+
+/// What wait-build waits for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UntilCondition {
+    /// The build stopped running
+    Finished,
+    /// The build finished with this status
+    Status(BuildStatusFilter),
+}
+
+impl UntilCondition {
+    /// Whether a finished build meets the condition
+    pub fn met_by(self, status: BuildStatus) -> bool {
+        match self {
+            Self::Finished => status.is_finished(),
+            Self::Status(BuildStatusFilter::Aborted) => status.is_aborted(),
+            Self::Status(filter) => status == filter.to_status(),
+        }
+    }
+}
+
+impl std::fmt::Display for UntilCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Finished => f.write_str("finished"),
+            Self::Status(filter) => write!(f, "status={}", filter.to_status()),
+        }
+    }
+}
+
+/// Arguments for the watch-daemon command
+#[derive(Args)]
+pub struct WatchDaemonArgs {
+    /// Apps or branches to watch, as APP or APP:BRANCH (default: daemon.targets)
+    #[arg(value_name = "TARGET")]
+    pub targets: Vec<String>,
+
+    /// Seconds between polls (default: daemon.interval, or 300)
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(10..))]
+    pub interval: Option<u64>,
+
+    /// Post status changes to this Slack incoming webhook (default: daemon.slack_webhook)
+    #[arg(long, value_name = "URL")]
+    pub slack_webhook: Option<String>,
+
+    /// Don't show desktop notifications
+    #[arg(long)]
+    pub no_desktop: bool,
+
+    /// Poll once and exit
+    #[arg(long)]
+    pub once: bool,
+}
+
+/// Arguments for the tray command
+#[cfg(feature = "tray")]
+#[derive(Args)]
+pub struct TrayArgs {
+    /// App slug or alias (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Only builds on this branch
+    #[arg(short, long)]
+    pub branch: Option<String>,
+
+    /// Seconds between polls (default: daemon.interval, or 300)
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(10..))]
+    pub interval: Option<u64>,
+}
+
+/// Arguments for the pr command
+#[derive(Args)]
+pub struct PrArgs {
+    /// Pull request number
+    #[arg(value_name = "NUMBER")]
+    pub number: i64,
+
+    /// App slug (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Maximum number of builds to show
+    #[arg(short, long, default_value = "25", value_name = "N", value_parser = parse_limit)]
+    pub limit: u32,
+}
+
+/// Arguments for the status command
+#[derive(Args)]
+pub struct StatusArgs {
+    /// App slug or alias (defaults to the app matching the git remote)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Branch to summarize (defaults to the current git branch)
+    #[arg(short, long)]
+    pub branch: Option<String>,
+
+    /// Number of recent builds to show
+    #[arg(short, long, default_value = "5", value_parser = parse_limit)]
+    pub limit: u32,
+}
+
+/// Arguments for the overview command
+#[derive(Args)]
+pub struct OverviewArgs {
+    /// Only consider builds on this branch
+    #[arg(short, long)]
+    pub branch: Option<String>,
+
+    /// Only show apps with running builds
+    #[arg(long)]
+    pub running: bool,
+
+    /// Re-fetch the app list instead of using the cached one
+    #[arg(long)]
+    pub refresh: bool,
+}
+
+/// Arguments for the matrix command
+#[derive(Args)]
+pub struct MatrixArgs {
+    /// App slug or alias (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Branches for the rows, comma-separated; globs like release/* allowed
+    #[arg(short, long, value_name = "BRANCHES", value_delimiter = ',')]
+    pub branches: Vec<String>,
+
+    /// Workflows for the columns, comma-separated
+    #[arg(
+        short,
+        long = "workflow",
+        visible_alias = "workflows",
+        value_name = "WORKFLOWS",
+        value_delimiter = ','
+    )]
+    pub workflows: Vec<String>,
+
+    /// Number of recent builds to look through
+    #[arg(short, long, default_value = "100", value_name = "N", value_parser = parse_limit)]
+    pub limit: u32,
+}
+
+/// Arguments for the activity command
+#[derive(Args)]
+pub struct ActivityArgs {
+    /// App slug or alias (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Start of the period (e.g., 7d, 1w, today, this-week, 2025-01-15)
+    #[arg(long, default_value = "7d", value_name = "DURATION")]
+    pub since: String,
+
+    /// End of the period (e.g., yesterday, 2025-01-15 includes that whole day)
+    #[arg(long, value_name = "DURATION")]
+    pub until: Option<String>,
+
+    /// Only builds on this branch (exact match)
+    #[arg(short, long)]
+    pub branch: Option<String>,
+
+    /// Only builds of this workflow (exact match)
+    #[arg(short, long)]
+    pub workflow: Option<String>,
+}
+
+/// Arguments for the report command
+#[derive(Args)]
+pub struct ReportArgs {
+    /// App slug or alias (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Start of the period (e.g., 24h, 7d, yesterday, 2025-01-15)
+    #[arg(long, default_value = "24h", value_name = "DURATION")]
+    pub since: String,
+
+    /// End of the period (e.g., today, 2025-01-15 includes that whole day)
+    #[arg(long, value_name = "DURATION")]
+    pub until: Option<String>,
+
+    /// Only builds on this branch (exact match)
+    #[arg(short, long)]
+    pub branch: Option<String>,
+
+    /// Report format (default: html for a .html --out path, otherwise markdown)
+    #[arg(long, value_enum)]
+    pub format: Option<ReportFormat>,
+
+    /// File to write (creates or overwrites); prints to stdout when omitted
+    #[arg(long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    pub out: Option<String>,
+}
+
+/// File format for reports
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+/// Arguments for the failures command
+#[derive(Args)]
+pub struct FailuresArgs {
+    /// App slug or alias (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Start of the period (e.g., 7d, 1w, today, this-week, 2025-01-15)
+    #[arg(long, default_value = "7d", value_name = "DURATION")]
+    pub since: String,
+
+    /// End of the period (e.g., yesterday, 2025-01-15 includes that whole day)
+    #[arg(long, value_name = "DURATION")]
+    pub until: Option<String>,
+
+    /// Only builds on this branch (exact match)
+    #[arg(short, long)]
+    pub branch: Option<String>,
+
+    /// Only builds of this workflow (exact match)
+    #[arg(short, long)]
+    pub workflow: Option<String>,
+
+    /// Number of failure causes to show
+    #[arg(short = 'n', long, default_value = "10")]
+    pub top: usize,
+
+    /// Read at most this many failed builds' logs
+    #[arg(long, default_value = "50", value_name = "N")]
+    pub max_builds: usize,
+}
+
+/// Arguments for the triggers command
+#[derive(Args)]
+pub struct TriggersArgs {
+    /// App slug or alias (overrides default)
+    #[arg(short, long, conflicts_with = "file")]
+    pub app: Option<String>,
+
+    /// Read a local bitrise.yml instead of the app's
+    #[arg(short, long, value_name = "PATH")]
+    pub file: Option<String>,
+}
+
+/// Arguments for the stacks command
+#[derive(Args)]
+pub struct StacksArgs {
+    /// Only this stack, failing if the app can't use it
+    pub stack: Option<String>,
+
+    /// Only stacks providing this Xcode version (e.g. 15.4, or 15 for any 15.x)
+    #[arg(long, value_name = "VERSION", conflicts_with = "stack")]
+    pub find_xcode: Option<String>,
+
+    /// App slug or alias (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+}
+
+/// Arguments for the addons command
+#[derive(Args)]
+pub struct AddonsArgs {
+    /// Include addons that aren't enabled for the app
+    #[arg(long)]
+    pub all: bool,
+
+    /// App slug or alias (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+}
+
+/// Arguments for the release command
+#[derive(Args)]
+pub struct ReleaseArgs {
+    #[command(subcommand)]
+    pub command: ReleaseCommands,
+}
+
+/// Release subcommands
+#[derive(Subcommand)]
+pub enum ReleaseCommands {
+    /// List the app's releases
+    List {
+        #[command(flatten)]
+        target: ReleaseAppArgs,
+    },
+
+    /// Show a release and its release candidate
+    Show {
+        /// Release version (e.g. 2.4.0) or ID
+        release: String,
+
+        #[command(flatten)]
+        target: ReleaseAppArgs,
+    },
+
+    /// Start a release of a new version
+    Create {
+        /// Version to release (e.g. 2.5.0)
+        #[arg(id = "release_version", value_name = "VERSION")]
+        version: String,
+
+        /// Release name (defaults to the version)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Release description
+        #[arg(long)]
+        description: Option<String>,
+
+        #[command(flatten)]
+        target: ReleaseAppArgs,
+    },
+
+    /// Make a build the release candidate of a release
+    #[command(name = "add-build")]
+    AddBuild {
+        /// Release version (e.g. 2.4.0) or ID
+        release: String,
+
+        /// Build slug (from 'builds' output or a Bitrise URL)
+        #[arg(value_name = "SLUG")]
+        build: String,
+
+        /// Replace an existing release candidate without asking
+        #[arg(short, long)]
+        yes: bool,
+
+        #[command(flatten)]
+        target: ReleaseAppArgs,
+    },
+}
+
+/// Which app's releases a release subcommand works on
+#[derive(Args)]
+pub struct ReleaseAppArgs {
+    /// App slug or alias (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Release Management connected app ID, when the app has several
+    #[arg(long, value_name = "ID")]
+    pub connected_app: Option<String>,
+}
+
+/// Arguments for the deploys command
+#[derive(Args)]
+pub struct DeploysArgs {
+    /// App slug or alias (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Number of recent successful builds to look through
+    #[arg(short, long, default_value = "50", value_name = "N", value_parser = parse_limit)]
+    pub limit: u32,
+
+    /// Workflow that deploys (repeatable); skips reading bitrise.yml
+    #[arg(short, long, value_name = "NAME")]
+    pub workflow: Vec<String>,
+}
+
+/// Arguments for the validate-yml command
+#[derive(Args)]
+pub struct ValidateYmlArgs {
+    /// Path to bitrise.yml ('-' reads stdin)
+    #[arg(default_value = "bitrise.yml")]
+    pub file: String,
+
+    /// Treat warnings as errors
+    #[arg(long)]
+    pub strict: bool,
+}
+
+/// Arguments for the runs command
+#[derive(Args)]
+pub struct RunsArgs {
+    /// App slug or alias (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Filter by branch name (exact match)
+    #[arg(short, long)]
+    pub branch: Option<String>,
+
+    /// Filter by status (running, success, failed, aborted)
+    #[arg(short, long, value_enum)]
+    pub status: Option<BuildStatusFilter>,
+
+    /// Maximum number of runs to show
+    #[arg(short, long, default_value = "25", value_name = "N", value_parser = parse_limit)]
+    pub limit: u32,
+}
+
+/// Arguments for the prompt-status command
+#[derive(Args)]
+pub struct PromptStatusArgs {
+    /// App slug or alias (overrides default)
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Branch to report on (defaults to the current git branch)
+    #[arg(short, long)]
+    pub branch: Option<String>,
+
+    /// Seconds before the cached status is refreshed in the background
+    #[arg(long, default_value = "60", value_name = "SECS")]
+    pub ttl: u64,
+
+    /// Fetch from the API and update the cache (used by the background refresh)
+    #[arg(long, hide = true)]
+    pub refresh: bool,
+}
+
+/// Arguments for the schema command
+#[derive(Args)]
+pub struct SchemaArgs {
+    /// JSON output to describe
+    #[arg(value_enum)]
+    pub kind: SchemaKind,
+}
+
+/// JSON outputs with a published schema
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SchemaKind {
+    Builds,
+    Apps,
+    Pipelines,
+    Artifacts,
+}
+
+impl SchemaKind {
+    /// Name of the output, as used by [`crate::bitrise::schema::list_schema`]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Builds => "builds",
+            Self::Apps => "apps",
+            Self::Pipelines => "pipelines",
+            Self::Artifacts => "artifacts",
+        }
+    }
+}
+
 /// Arguments for the completions command
 #[derive(Args)]
 pub struct CompletionsArgs {
@@ -1008,15 +3102,110 @@ pub struct CompletionsArgs {
     pub shell: Shell,
 }
 
+/// Values completed from the config and cache rather than the command line
+/// definitions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompletionKind {
+    /// Aliases and cached app slugs, for `--app`
+    AppSlugs,
+    /// Alias names, for `config alias`
+    Aliases,
+    /// Workflows in ./bitrise.yml and seen in cached builds, for `--workflow`
+    Workflows,
+}
+
+/// Arguments for the hidden __complete command
+#[derive(Args)]
+pub struct CompleteArgs {
+    /// Values to list
+    #[arg(value_enum)]
+    pub kind: CompletionKind,
+
+    /// Format the candidates for this shell's completion system
+    #[arg(long, value_enum)]
+    pub shell: Option<Shell>,
+}
+
+/// Arguments for the help command
+#[derive(Args)]
+pub struct HelpArgs {
+    /// A help topic (listed below), or a command such as 'builds' or 'config alias'
+    #[arg(value_name = "TOPIC|COMMAND")]
+    pub topic: Vec<String>,
+}
+
+/// Arguments for the man command
+#[derive(Args)]
+pub struct ManArgs {
+    /// Directory to write the pages to (created if missing)
+    #[arg(value_hint = ValueHint::DirPath, value_name = "DIR")]
+    pub dir: String,
+}
+
+/// Arguments for the hidden notify-handler command
+#[derive(Args)]
+pub struct NotifyHandlerArgs {
+    #[arg(long)]
+    pub summary: String,
+
+    #[arg(long, default_value = "")]
+    pub body: String,
+
+    #[arg(long, default_value = "dialog-information")]
+    pub icon: String,
+
+    /// Build page opened by the notification
+    #[arg(long)]
+    pub url: String,
+
+    /// Offer a "Rebuild" action
+    #[arg(long)]
+    pub rebuild: bool,
+}
+
 impl Cli {
+    /// Parse the command line, also returning the subcommand path (e.g.
+    /// `config.alias.import`), which is the `kind` of JSON output
+    pub fn parse_with_kind() -> (Self, String) {
+        let matches = Self::command().get_matches();
+        let cli = Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+        let mut names = Vec::new();
+        let mut current = &matches;
+        while let Some((name, sub)) = current.subcommand() {
+            names.push(name);
+            current = sub;
+        }
+        (cli, names.join("."))
+    }
+
     /// Generate shell completions to stdout
     pub fn print_completions(shell: Shell) {
-        clap_complete::generate(
-            shell,
-            &mut Cli::command(),
-            "reprise",
-            &mut std::io::stdout(),
-        );
+        print!("{}", crate::cli::completions::script(shell));
+    }
+}
+
+/// Parse a `--limit`, which must ask for at least one item
+fn parse_limit(s: &str) -> std::result::Result<u32, String> {
+    match s.parse::<u32>() {
+        Ok(0) => Err("must be at least 1".to_string()),
+        Ok(limit) => Ok(limit),
+        Err(_) => Err(format!("'{}' is not a positive number", s)),
+    }
+}
+
+/// Parse a wait-build `--until`, e.g. `status=success`
+fn parse_until(s: &str) -> std::result::Result<UntilCondition, String> {
+    let expected = "expected finished, or status=success|failed|aborted";
+    let status = match s.split_once('=') {
+        None if s == "finished" => return Ok(UntilCondition::Finished),
+        Some(("status", "finished")) => return Ok(UntilCondition::Finished),
+        Some(("status", status)) => status,
+        _ => return Err(format!("'{}': {}", s, expected)),
+    };
+    match BuildStatusFilter::from_str(status, true) {
+        Ok(BuildStatusFilter::Running) | Err(_) => Err(format!("'{}': {}", s, expected)),
+        Ok(filter) => Ok(UntilCondition::Status(filter)),
     }
 }
 
@@ -2,10 +2,13 @@
 
 use colored::Colorize;
 
+use super::common::{confirm, resolve_app};
 use crate::bitrise::BitriseClient;
 use crate::cli::args::{AbortArgs, OutputFormat};
 use crate::config::Config;
 use crate::error::Result;
+use crate::history;
+use crate::output::json;
 
 /// Handle the abort command
 pub fn abort(
@@ -15,15 +18,7 @@ pub fn abort(
     format: OutputFormat,
 ) -> Result<String> {
     // Get app slug from args or default
-    let app_slug = args
-        .app
-        .as_deref()
-        .or(config.defaults.app_slug.as_deref())
-        .ok_or_else(|| {
-            crate::error::RepriseError::Config(
-                "No app specified. Use --app or set a default with 'reprise app set'".to_string(),
-            )
-        })?;
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
 
     // Get the build first to show info
     let build = client.get_build(app_slug, &args.slug)?;
@@ -43,13 +38,26 @@ pub fn abort(
                     "build_number": build.data.build_number,
                     "status": build.data.status_text,
                 });
-                Ok(serde_json::to_string_pretty(&json)?)
+                json::format_json(&json)
             }
         };
     }
 
+    // Confirm unless --yes flag is set
+    if !args.yes && format == OutputFormat::Pretty {
+        let question = format!(
+            "Abort build #{} on branch '{}'?",
+            build.data.build_number, build.data.branch
+        );
+        if !confirm(&question, "--yes")? {
+            return Ok("Aborted.".to_string());
+        }
+    }
+
     // Abort the build
-    client.abort_build(app_slug, &args.slug, args.reason.as_deref())?;
+    let result = client.abort_build(app_slug, &args.slug, args.reason.as_deref());
+    history::record("abort", app_slug, Some(&args.slug), &result);
+    result?;
 
     match format {
         OutputFormat::Pretty => {
@@ -73,7 +81,7 @@ pub fn abort(
                 "build_slug": args.slug,
                 "reason": args.reason,
             });
-            Ok(serde_json::to_string_pretty(&json)?)
+            json::format_json(&json)
         }
     }
 }
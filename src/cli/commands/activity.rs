@@ -0,0 +1,154 @@
+//! Activity command (who triggered builds in a period, and how they went)
+
+use std::collections::{HashMap, HashSet};
+
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::bitrise::{BitriseClient, Build, BuildStatus};
+use crate::cli::args::{ActivityArgs, OutputFormat};
+use crate::config::Config;
+use crate::duration::TimeWindow;
+use crate::error::Result;
+use crate::output::progress::StatusLine;
+use crate::output::table::{self, Table};
+use crate::output::{json, pretty, theme};
+
+use super::common::{build_history, resolve_app, show_progress};
+
+/// Shown for builds without a `triggered_by`
+const UNKNOWN_USER: &str = "(unknown)";
+
+/// One user's builds in the period
+#[derive(Debug, Serialize)]
+struct UserActivity {
+    user: String,
+    builds: usize,
+    succeeded: usize,
+    failed: usize,
+    aborted: usize,
+    /// Failed share of finished (succeeded or failed) builds
+    failure_rate: Option<f64>,
+    /// Builds of a commit and workflow the user had already built
+    reruns: usize,
+    credits: i64,
+}
+
+/// Handle the activity command
+pub fn activity(
+    client: &BitriseClient,
+    config: &Config,
+    args: &ActivityArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    let window = TimeWindow::from_args(Some(&args.since), args.until.as_deref())?;
+
+    let mut status_line = show_progress(format).then(|| StatusLine::start("Fetching builds"));
+    let builds = build_history(
+        client,
+        app_slug,
+        None,
+        args.branch.as_deref(),
+        args.workflow.as_deref(),
+        window.as_ref(),
+        |count| {
+            if let Some(ref mut status) = status_line {
+                status.set_message(&format!("Fetching builds ({} so far)", count));
+            }
+        },
+    )?;
+    if let Some(ref mut status) = status_line {
+        status.finish();
+    }
+
+    let users = user_activity(&builds);
+    match format {
+        OutputFormat::Pretty => Ok(format_activity(&users, &args.since, builds.len())),
+        OutputFormat::Json => json::format_json(&users),
+    }
+}
+
+/// Group builds by who triggered them, busiest first
+fn user_activity(builds: &[Build]) -> Vec<UserActivity> {
+    let mut by_user: HashMap<&str, UserActivity> = HashMap::new();
+    let mut built: HashSet<(&str, &str, &str)> = HashSet::new();
+
+    // Oldest first, so the first build of a commit isn't counted as a rerun
+    for build in builds.iter().rev() {
+        let user = build.triggered_by.as_deref().unwrap_or(UNKNOWN_USER);
+        let entry = by_user.entry(user).or_insert_with(|| UserActivity {
+            user: user.to_string(),
+            builds: 0,
+            succeeded: 0,
+            failed: 0,
+            aborted: 0,
+            failure_rate: None,
+            reruns: 0,
+            credits: 0,
+        });
+        entry.builds += 1;
+        match build.status {
+            BuildStatus::Success => entry.succeeded += 1,
+            BuildStatus::Failed => entry.failed += 1,
+            BuildStatus::Aborted | BuildStatus::AbortedWithSuccess => entry.aborted += 1,
+            _ => {}
+        }
+        entry.credits += i64::from(build.credit_cost.unwrap_or(0));
+        if let Some(ref commit) = build.commit_hash {
+            if !built.insert((user, commit, &build.triggered_workflow)) {
+                entry.reruns += 1;
+            }
+        }
+    }
+
+    let mut users: Vec<UserActivity> = by_user.into_values().collect();
+    for user in &mut users {
+        let finished = user.succeeded + user.failed;
+        user.failure_rate = (finished > 0).then(|| user.failed as f64 / finished as f64);
+    }
+    users.sort_by(|a, b| b.builds.cmp(&a.builds).then_with(|| a.user.cmp(&b.user)));
+    users
+}
+
+/// Render the per-user table
+fn format_activity(users: &[UserActivity], since: &str, total: usize) -> String {
+    if users.is_empty() {
+        return format!("No builds since {}.", since).dimmed().to_string();
+    }
+
+    let mut table = Table::new(&["USER", "BUILDS", "FAILED", "FAIL %", "RERUNS", "CREDITS"]);
+    for user in users {
+        let failed = if user.failed > 0 {
+            theme::paint(BuildStatus::Failed, &user.failed.to_string()).to_string()
+        } else {
+            "0".to_string()
+        };
+        let rate = user
+            .failure_rate
+            .map_or("-".to_string(), |r| format!("{:.0}%", r * 100.0));
+        table.row(vec![
+            user.user.clone(),
+            user.builds.to_string(),
+            failed,
+            rate,
+            user.reruns.to_string(),
+            user.credits.to_string(),
+        ]);
+    }
+
+    let mut output = String::new();
+    if table::headers_shown() {
+        output.push_str(&format!(
+            "{} since {} ({} builds, {} users)\n",
+            "Activity".bold(),
+            since,
+            total,
+            users.len()
+        ));
+        output.push_str(&pretty::separator(pretty::MAX_RULE_WIDTH));
+        output.push('\n');
+    }
+    output.push_str(&table.render());
+    output.trim_end().to_string()
+}
@@ -0,0 +1,88 @@
+//! Addons command (Bitrise addons such as Test Reports, enabled per app)
+
+use colored::Colorize;
+
+use crate::bitrise::{Addon, BitriseClient};
+use crate::cli::args::{AddonsArgs, OutputFormat};
+use crate::config::Config;
+use crate::error::Result;
+use crate::output::json;
+use crate::output::pretty;
+use crate::output::table::{self, Table};
+
+use super::common::resolve_app;
+
+/// Handle the addons command
+pub fn addons(
+    client: &BitriseClient,
+    config: &Config,
+    args: &AddonsArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    let mut addons = client.list_addons(app_slug)?.data;
+    let disabled = addons.iter().filter(|a| !a.is_enabled).count();
+    if !args.all {
+        addons.retain(|a| a.is_enabled);
+    }
+    // Enabled first, keeping the API's order within each group
+    addons.sort_by_key(|a| !a.is_enabled);
+
+    match format {
+        OutputFormat::Pretty => Ok(format_addons(&addons, app_slug, args.all, disabled)),
+        OutputFormat::Json => {
+            let json = addons
+                .iter()
+                .map(|a| {
+                    let mut value = serde_json::to_value(a)?;
+                    value["url"] = serde_json::json!(a.url(app_slug));
+                    Ok(value)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            json::format_json(&json)
+        }
+    }
+}
+
+/// Render the addons as a table, with a hint about addons left out
+fn format_addons(addons: &[Addon], app_slug: &str, all: bool, disabled: usize) -> String {
+    let hint = if !all && disabled > 0 {
+        format!("{} more available but not enabled; use --all to list them.", disabled)
+    } else {
+        String::new()
+    };
+    if addons.is_empty() {
+        let message = format!("No addons enabled for {}.", app_slug);
+        return format!("{} {}", message, hint).trim_end().dimmed().to_string();
+    }
+
+    let mut output = String::new();
+    if table::headers_shown() {
+        output.push_str(&format!("{} for {}\n", "Addons".bold(), app_slug));
+        output.push_str(&pretty::separator(pretty::MAX_RULE_WIDTH));
+        output.push('\n');
+    }
+    let mut table = Table::new(&["ADDON", "STATUS", "PLAN", "URL"]);
+    for addon in addons {
+        let status = if addon.is_enabled {
+            "enabled".green().to_string()
+        } else {
+            "not enabled".dimmed().to_string()
+        };
+        table.row(vec![
+            addon.title.as_deref().unwrap_or(&addon.id).bold().to_string(),
+            status,
+            addon
+                .plan
+                .as_ref()
+                .map(|p| p.name.clone().unwrap_or_else(|| p.id.clone()))
+                .unwrap_or_else(|| "-".to_string()),
+            addon.url(app_slug).dimmed().to_string(),
+        ]);
+    }
+    output.push_str(table.render().trim_end());
+    if !hint.is_empty() {
+        output.push_str(&format!("\n\n{}", hint.dimmed()));
+    }
+    output
+}
@@ -1,9 +1,12 @@
 use colored::Colorize;
 
+use super::common::resolve_app;
+use super::org::format_members;
 use crate::bitrise::BitriseClient;
 use crate::cli::args::{AppArgs, AppCommands, OutputFormat};
 use crate::config::Config;
 use crate::error::{RepriseError, Result};
+use crate::output::json;
 
 /// Handle the app set command
 pub fn app_set(
@@ -46,7 +49,7 @@ pub fn app_set(
                 "app_slug": app.slug,
                 "app_name": app.title
             });
-            Ok(serde_json::to_string_pretty(&result)?)
+            json::format_json(&result)
         }
     }
 }
@@ -66,7 +69,7 @@ pub fn app_show(config: &Config, format: OutputFormat) -> Result<String> {
                     "app_slug": slug,
                     "app_name": name
                 });
-                Ok(serde_json::to_string_pretty(&result)?)
+                json::format_json(&result)
             }
         },
         (Some(slug), None) => match format {
@@ -76,7 +79,7 @@ pub fn app_show(config: &Config, format: OutputFormat) -> Result<String> {
                     "app_slug": slug,
                     "app_name": null
                 });
-                Ok(serde_json::to_string_pretty(&result)?)
+                json::format_json(&result)
             }
         },
         _ => match format {
@@ -90,8 +93,130 @@ pub fn app_show(config: &Config, format: OutputFormat) -> Result<String> {
                     "app_slug": null,
                     "app_name": null
                 });
-                Ok(serde_json::to_string_pretty(&result)?)
+                json::format_json(&result)
             }
         },
     }
 }
+
+/// Remove the default app
+pub fn app_unset(config: &mut Config, format: OutputFormat) -> Result<String> {
+    let previous = config.defaults.app_name.clone();
+    let Some(slug) = config.unset_default_app() else {
+        return match format {
+            OutputFormat::Pretty => Ok("No default app set.".dimmed().to_string()),
+            OutputFormat::Json => json::format_json(
+                &serde_json::json!({ "success": true, "previous_app_slug": null }),
+            ),
+        };
+    };
+    config.save()?;
+
+    match format {
+        OutputFormat::Pretty => Ok(format!(
+            "{} Removed default app: {} ({})",
+            "✓".green(),
+            previous.as_deref().unwrap_or(&slug).bold(),
+            slug
+        )),
+        OutputFormat::Json => {
+            let result = serde_json::json!({
+                "success": true,
+                "previous_app_slug": slug,
+                "previous_app_name": previous
+            });
+            json::format_json(&result)
+        }
+    }
+}
+
+/// List the users with access to an app
+pub fn app_members(
+    client: &BitriseClient,
+    config: &Config,
+    app: Option<&str>,
+    format: OutputFormat,
+) -> Result<String> {
+    let app_slug = &resolve_app(client, app, config)?;
+    let members = client.list_app_members(app_slug)?.data;
+    match format {
+        OutputFormat::Pretty => Ok(format_members(&members, app_slug)),
+        OutputFormat::Json => json::format_json(&members),
+    }
+}
+
+/// List recent default apps, or make one of them the default again
+pub fn app_recent(
+    config: &mut Config,
+    number: Option<usize>,
+    format: OutputFormat,
+) -> Result<String> {
+    if let Some(number) = number {
+        let app = number
+            .checked_sub(1)
+            .and_then(|i| config.defaults.recent.get(i))
+            .cloned()
+            .ok_or_else(|| {
+                RepriseError::InvalidArgument(format!(
+                    "No recent app #{}. Run 'reprise app recent' to list them.",
+                    number
+                ))
+            })?;
+        config.set_default_app(app.slug.clone(), app.name.clone());
+        config.save()?;
+
+        return match format {
+            OutputFormat::Pretty => Ok(format!(
+                "{} Default app set to: {} ({})",
+                "✓".green(),
+                app.name.as_deref().unwrap_or(&app.slug).bold(),
+                app.slug
+            )),
+            OutputFormat::Json => {
+                let result = serde_json::json!({
+                    "success": true,
+                    "app_slug": app.slug,
+                    "app_name": app.name
+                });
+                json::format_json(&result)
+            }
+        };
+    }
+
+    let current = config.defaults.app_slug.as_deref();
+    match format {
+        OutputFormat::Pretty => {
+            if config.defaults.recent.is_empty() {
+                return Ok("No recent apps. Set one with: reprise app set <slug>"
+                    .dimmed()
+                    .to_string());
+            }
+            let mut output = format!("{}\n", "Recent Default Apps".bold());
+            for (i, app) in config.defaults.recent.iter().enumerate() {
+                let marker = if Some(app.slug.as_str()) == current {
+                    "*".green()
+                } else {
+                    " ".normal()
+                };
+                let name = app.name.as_deref().unwrap_or(&app.slug);
+                output.push_str(&format!("{} {}. {} {}\n", marker, i + 1, name, app.slug.dimmed()));
+            }
+            Ok(output.trim_end().to_string())
+        }
+        OutputFormat::Json => {
+            let apps: Vec<_> = config
+                .defaults
+                .recent
+                .iter()
+                .map(|app| {
+                    serde_json::json!({
+                        "app_slug": app.slug,
+                        "app_name": app.name,
+                        "current": Some(app.slug.as_str()) == current
+                    })
+                })
+                .collect();
+            json::format_json(&apps)
+        }
+    }
+}
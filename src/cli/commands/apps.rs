@@ -1,24 +1,158 @@
-use crate::bitrise::BitriseClient;
-use crate::cli::args::{AppsArgs, OutputFormat};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::bitrise::{App, BitriseClient, MAX_PAGE_SIZE};
+use crate::cache::Cache;
+use crate::cli::args::{AppSort, AppsArgs, OutputFormat};
+use crate::config::Config;
 use crate::error::Result;
 use crate::output;
 
+use super::common::{current_user, warn_paged_limit, MAX_SEARCH_PAGES};
+
+/// Cache key for each app's latest build time
+const ACTIVITY_CACHE_KEY: &str = "app-activity";
+
+/// How long a cached build time is trusted, unless `cache.ttl` is set
+const ACTIVITY_CACHE_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Maximum number of apps queried at the same time
+const MAX_CONCURRENT: usize = 8;
+
+/// An app's latest build time and when it was looked up
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Activity {
+    checked_at: DateTime<Utc>,
+    last_build_at: Option<DateTime<Utc>>,
+}
+
 /// Handle the apps command
-pub fn apps(client: &BitriseClient, args: &AppsArgs, format: OutputFormat) -> Result<String> {
-    let response = client.list_apps(args.limit)?;
-
-    // Apply filter if provided
-    let apps: Vec<_> = if let Some(ref filter) = args.filter {
-        let filter_lower = filter.to_lowercase();
-        response
-            .data
-            .into_iter()
-            .filter(|app| app.title.to_lowercase().contains(&filter_lower))
-            .collect()
-    } else {
-        // Apply limit
-        response.data.into_iter().take(args.limit as usize).collect()
-    };
+pub fn apps(
+    client: &BitriseClient,
+    config: &Config,
+    args: &AppsArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    warn_paged_limit(args.limit);
+    let limit = args.limit as usize;
+    let my_slug = if args.mine { Some(current_user(client, config)?.slug) } else { None };
+
+    let filtered = args.filter.is_some()
+        || args.owner.is_some()
+        || my_slug.is_some()
+        || args.project_type.is_some()
+        || args.disabled
+        || args.enabled;
+    if !filtered {
+        let response = client.list_apps(args.limit)?;
+        let mut apps: Vec<_> = response.data.into_iter().take(limit).collect();
+        sort_apps(client, config, &mut apps, args.sort);
+        return output::format_apps(&apps, format);
+    }
+
+    // Filters run locally, so walk the pages until enough apps match
+    let mut apps = Vec::new();
+    let mut next: Option<String> = None;
+    for _ in 0..MAX_SEARCH_PAGES {
+        let page = client.list_apps_page(MAX_PAGE_SIZE, next.as_deref())?;
+        apps.extend(page.data.into_iter().filter(|app| matches(app, args, my_slug.as_deref())));
+
+        next = page.paging.next;
+        if apps.len() >= limit || next.is_none() {
+            break;
+        }
+    }
+    apps.truncate(limit);
+    sort_apps(client, config, &mut apps, args.sort);
 
     output::format_apps(&apps, format)
 }
+
+/// Reorder apps for `--sort`; without it the API's order is kept
+fn sort_apps(client: &BitriseClient, config: &Config, apps: &mut [App], sort: Option<AppSort>) {
+    match sort {
+        Some(AppSort::Name) => apps.sort_by_key(|app| app.title.to_lowercase()),
+        Some(AppSort::Activity) => {
+            let activity = latest_activity(client, config, apps);
+            // Most recently built first; apps that never built go last
+            apps.sort_by_key(|app| Reverse(activity.get(&app.slug).copied().flatten()));
+        }
+        None => {}
+    }
+}
+
+/// Latest build time of each app, from the cache or fetched concurrently
+fn latest_activity(
+    client: &BitriseClient,
+    config: &Config,
+    apps: &[App],
+) -> HashMap<String, Option<DateTime<Utc>>> {
+    let ttl = config.cache.ttl.map_or(ACTIVITY_CACHE_TTL, Duration::from_secs);
+    let max_age = chrono::Duration::from_std(ttl).unwrap_or(chrono::Duration::MAX);
+    let cache = Cache::for_config(&config.cache).ok();
+    let mut cached: HashMap<String, Activity> = cache
+        .as_ref()
+        .and_then(|c| c.read(ACTIVITY_CACHE_KEY))
+        .map(|entry| entry.value)
+        .unwrap_or_default();
+    let now = Utc::now();
+    cached.retain(|_, a| now - a.checked_at < max_age);
+
+    let missing: Vec<&App> = apps.iter().filter(|a| !cached.contains_key(&a.slug)).collect();
+    for chunk in missing.chunks(MAX_CONCURRENT) {
+        let fetched: Vec<Option<Activity>> = thread::scope(|scope| {
+            let handles: Vec<_> = chunk
+                .iter()
+                .map(|app| scope.spawn(move || fetch_activity(client, &app.slug)))
+                .collect();
+            handles.into_iter().map(|h| h.join().ok().flatten()).collect()
+        });
+        for (app, activity) in chunk.iter().zip(fetched) {
+            // Failed lookups aren't cached, so they are retried next time
+            if let Some(activity) = activity {
+                cached.insert(app.slug.clone(), activity);
+            }
+        }
+    }
+    if let (Some(cache), false) = (cache, missing.is_empty()) {
+        // A cache write failure only costs a refetch next time
+        let _ = cache.put(ACTIVITY_CACHE_KEY, &cached);
+    }
+
+    cached
+        .into_iter()
+        .map(|(slug, activity)| (slug, activity.last_build_at))
+        .collect()
+}
+
+/// Look up an app's most recent build; `None` if the request fails
+fn fetch_activity(client: &BitriseClient, app_slug: &str) -> Option<Activity> {
+    let builds = client.list_builds(app_slug, None, None, None, 1).ok()?;
+    Some(Activity {
+        checked_at: Utc::now(),
+        last_build_at: builds.data.first().map(|b| b.triggered_at),
+    })
+}
+
+/// Whether an app passes the name, owner, type and enabled filters
+fn matches(app: &App, args: &AppsArgs, my_slug: Option<&str>) -> bool {
+    let contains = |value: &str, part: &str| value.to_lowercase().contains(&part.to_lowercase());
+
+    args.filter.as_deref().is_none_or(|name| contains(&app.title, name))
+        && args.owner.as_deref().is_none_or(|owner| {
+            contains(&app.owner.name, owner) || contains(&app.owner.slug, owner)
+        })
+        && my_slug.is_none_or(|slug| app.owner.slug == slug)
+        && args.project_type.is_none_or(|kind| {
+            app.project_type
+                .as_deref()
+                .is_some_and(|t| t.eq_ignore_ascii_case(kind.as_str()))
+        })
+        && (!args.disabled || app.is_disabled)
+        && (!args.enabled || !app.is_disabled)
+}
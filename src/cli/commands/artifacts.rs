@@ -1,13 +1,23 @@
 //! Artifacts command
 
 use std::path::{Path, PathBuf};
+use std::process::Command;
 
+use chrono::Utc;
 use colored::Colorize;
 
-use crate::bitrise::{Artifact, BitriseClient};
-use crate::cli::args::{ArtifactsArgs, OutputFormat};
+use super::common::{resolve_app, show_progress};
+use crate::bitrise::{Artifact, BitriseClient, BuildStatus};
+use crate::cli::args::{
+    ArtifactPublishArgs, ArtifactSort, ArtifactUploadArgs, ArtifactsArgs, ArtifactsCommands,
+    OutputFormat,
+};
 use crate::config::Config;
+use crate::duration::parse_duration;
 use crate::error::{RepriseError, Result};
+use crate::history;
+use crate::output::json;
+use crate::output::pretty::{self, ArtifactRetention};
 
 /// Match a filename against a simple glob pattern.
 ///
@@ -23,7 +33,7 @@ use crate::error::{RepriseError, Result};
 /// assert!(matches_glob("App.dSYM.zip", "*.dSYM*"));
 /// assert!(!matches_glob("app.apk", "*.ipa"));
 /// ```
-fn matches_glob(name: &str, pattern: &str) -> bool {
+pub(super) fn matches_glob(name: &str, pattern: &str) -> bool {
     let name_lower = name.to_lowercase();
     let pattern_lower = pattern.to_lowercase();
 
@@ -110,7 +120,7 @@ fn simple_regex_match(text: &str, pattern: &str) -> bool {
 }
 
 /// Filter artifacts based on filter and exclude patterns
-fn filter_artifacts<'a>(
+pub(super) fn filter_artifacts<'a>(
     artifacts: &'a [Artifact],
     filter: Option<&str>,
     exclude: Option<&str>,
@@ -128,11 +138,22 @@ fn filter_artifacts<'a>(
         .collect()
 }
 
+/// Sort artifacts in place by the requested order
+fn sort_artifacts(artifacts: &mut [&Artifact], sort: ArtifactSort) {
+    match sort {
+        ArtifactSort::Size => artifacts.sort_by_key(|a| std::cmp::Reverse(a.file_size_bytes.unwrap_or(0))),
+        ArtifactSort::Name => artifacts.sort_by_key(|a| a.title.to_lowercase()),
+        ArtifactSort::Type => artifacts.sort_by_key(|a| {
+            (a.artifact_type.clone().unwrap_or_default(), a.title.to_lowercase())
+        }),
+    }
+}
+
 /// Sanitize a filename to prevent path traversal attacks
 ///
 /// Removes path separators and parent directory references,
 /// keeping only the base filename with safe characters.
-fn sanitize_filename(name: &str) -> Result<String> {
+pub(super) fn sanitize_filename(name: &str) -> Result<String> {
     // Get just the filename, stripping any path components
     // Explicitly reject if we can't extract a valid filename (don't fall back to original)
     let base_name = Path::new(name)
@@ -164,6 +185,293 @@ fn sanitize_filename(name: &str) -> Result<String> {
     Ok(base_name.to_string())
 }
 
+/// Resolve a build slug argument, expanding `latest` to the newest
+/// successful build (optionally on a branch/workflow)
+pub(super) fn resolve_build_slug(
+    client: &BitriseClient,
+    app_slug: &str,
+    slug: &str,
+    branch: Option<&str>,
+    workflow: Option<&str>,
+    format: OutputFormat,
+) -> Result<String> {
+    if !slug.eq_ignore_ascii_case("latest") {
+        if branch.is_some() || workflow.is_some() {
+            return Err(RepriseError::InvalidArgument(
+                "--branch and --workflow can only be used with 'latest'".to_string(),
+            ));
+        }
+        return Ok(slug.to_string());
+    }
+
+    let response = client.list_builds(app_slug, Some(BuildStatus::Success), branch, workflow, 1)?;
+
+    let build = response.data.into_iter().next().ok_or_else(|| {
+        let mut scope = String::new();
+        if let Some(b) = branch {
+            scope.push_str(&format!(" on branch '{}'", b));
+        }
+        if let Some(w) = workflow {
+            scope.push_str(&format!(" for workflow '{}'", w));
+        }
+        RepriseError::BuildNotFound(format!("no successful build{}", scope))
+    })?;
+
+    if show_progress(format) {
+        eprintln!(
+            "{} Using build #{} ({})",
+            "->".cyan(),
+            build.build_number,
+            build.slug.dimmed()
+        );
+    }
+
+    Ok(build.slug)
+}
+
+/// Download artifacts of a build into `dir`, returning the saved filenames
+pub(super) fn download_artifacts(
+    client: &BitriseClient,
+    app_slug: &str,
+    build_slug: &str,
+    artifacts: &[&Artifact],
+    dir: &Path,
+    format: OutputFormat,
+) -> Result<Vec<String>> {
+    let result = download_each(client, app_slug, build_slug, artifacts, dir, format);
+    history::record("download", app_slug, Some(build_slug), &result);
+    result
+}
+
+fn download_each(
+    client: &BitriseClient,
+    app_slug: &str,
+    build_slug: &str,
+    artifacts: &[&Artifact],
+    dir: &Path,
+    format: OutputFormat,
+) -> Result<Vec<String>> {
+    std::fs::create_dir_all(dir)?;
+
+    let mut downloaded = Vec::new();
+    for artifact in artifacts {
+        // Get artifact with download URL
+        let artifact_detail = client.get_artifact(app_slug, build_slug, &artifact.slug)?;
+
+        if let Some(ref url) = artifact_detail.data.expiring_download_url {
+            // Sanitize filename to prevent path traversal
+            let safe_filename = sanitize_filename(&artifact.title)?;
+            let file_path = dir.join(&safe_filename);
+
+            if show_progress(format) {
+                eprint!("Downloading {}... ", safe_filename);
+            }
+
+            client.download_artifact(url, &file_path)?;
+
+            if show_progress(format) {
+                eprintln!("{}", "done".green());
+            }
+
+            downloaded.push(safe_filename);
+        }
+    }
+    Ok(downloaded)
+}
+
+/// When the build's artifacts are deleted, warning on stderr when that is
+/// within `--warn-expiring`
+fn check_retention(
+    client: &BitriseClient,
+    app_slug: &str,
+    build_slug: &str,
+    args: &ArtifactsArgs,
+) -> Result<Option<ArtifactRetention>> {
+    let Some(ref warn) = args.warn_expiring else {
+        return Ok(None);
+    };
+    let warn = parse_duration(warn)?;
+    let retention = parse_duration(&args.retention)?;
+
+    let build = client.get_build(app_slug, build_slug)?.data;
+    let until = build.finished_at.unwrap_or(build.triggered_at) + retention;
+    let now = Utc::now();
+    let expiring = until - now <= warn;
+    if expiring {
+        let when = if until > now {
+            format!("in {}", pretty::format_span(until - now))
+        } else {
+            "already".to_string()
+        };
+        eprintln!(
+            "warning: artifacts of build #{} expire {} ({} under a {} retention); \
+             download what you need to keep",
+            build.build_number,
+            when,
+            until.format("%Y-%m-%d"),
+            args.retention
+        );
+    }
+    Ok(Some(ArtifactRetention { until, expiring }))
+}
+
+/// Turn an artifact's public install page on or off (`artifacts publish`)
+fn publish(
+    client: &BitriseClient,
+    config: &Config,
+    args: &ArtifactPublishArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let ArtifactPublishArgs { build, artifact, on, qr, app, .. } = args;
+    let app_slug = &resolve_app(client, app.as_deref(), config)?;
+    let build_slug = resolve_build_slug(client, app_slug, build, None, None, format)?;
+
+    let response = client.list_artifacts(app_slug, &build_slug)?;
+    let by_slug: Vec<&Artifact> = response.data.iter().filter(|a| a.slug == *artifact).collect();
+    let matches = if by_slug.is_empty() {
+        filter_artifacts(&response.data, Some(artifact), None)
+    } else {
+        by_slug
+    };
+    let target = match matches.as_slice() {
+        [one] => *one,
+        [] => {
+            return Err(RepriseError::InvalidArgument(format!(
+                "No artifact matching '{}' in build {}",
+                artifact, build_slug
+            )));
+        }
+        many => {
+            let names: Vec<&str> = many.iter().map(|a| a.title.as_str()).collect();
+            return Err(RepriseError::InvalidArgument(format!(
+                "'{}' matches {} artifacts: {}. Use a slug or a narrower pattern.",
+                artifact,
+                many.len(),
+                names.join(", ")
+            )));
+        }
+    };
+
+    let mut updated =
+        client.set_artifact_public_page(app_slug, &build_slug, &target.slug, *on)?.data;
+    if *on && updated.public_install_page_url.is_none() {
+        updated = client.get_artifact(app_slug, &build_slug, &target.slug)?.data;
+    }
+
+    match format {
+        OutputFormat::Pretty => {
+            let state = if *on { "on".green() } else { "off".dimmed() };
+            let mut output = format!(
+                "{} Public install page {} for {}",
+                "✓".green(),
+                state,
+                updated.title.bold()
+            );
+            if let Some(ref url) = updated.public_install_page_url.as_ref().filter(|_| *on) {
+                output.push_str(&format!("\n  {} {}", "Install URL:".cyan(), url));
+                if *qr {
+                    output.push_str(&format!("\n\n{}", qr_code(url)?.trim_end()));
+                }
+            }
+            Ok(output)
+        }
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "build_slug": build_slug,
+                "artifact_slug": updated.slug,
+                "title": updated.title,
+                "is_public_page_enabled": updated.is_public_page_enabled,
+                "public_install_page_url": updated.public_install_page_url,
+            });
+            json::format_json(&json)
+        }
+    }
+}
+
+/// Attach a local file to a build (`artifacts upload`)
+fn upload(
+    client: &BitriseClient,
+    config: &Config,
+    args: &ArtifactUploadArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let path = Path::new(&args.file);
+    let metadata = std::fs::metadata(path).map_err(|e| {
+        RepriseError::InvalidArgument(format!("Cannot read {}: {}", args.file, e))
+    })?;
+    if !metadata.is_file() {
+        return Err(RepriseError::InvalidArgument(format!("{} is not a file", args.file)));
+    }
+    let title = match args.title {
+        Some(ref title) => title.clone(),
+        None => path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| args.file.clone()),
+    };
+
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    let build_slug = resolve_build_slug(client, app_slug, &args.build, None, None, format)?;
+
+    let upload = client
+        .create_artifact_upload(app_slug, &build_slug, &title, metadata.len(), &args.artifact_type)
+        .map_err(|e| match e {
+            RepriseError::Api { status: status @ (401 | 403 | 404 | 405), .. } => {
+                RepriseError::InvalidArgument(format!(
+                    "Bitrise doesn't allow artifact uploads to build {} with this token \
+                     (HTTP {})",
+                    build_slug, status
+                ))
+            }
+            e => e,
+        })?
+        .data;
+    if show_progress(format) {
+        eprintln!("{} Uploading {} ({} bytes)", "->".cyan(), title, metadata.len());
+    }
+    client.upload_artifact_file(&upload.upload_url, path)?;
+    let artifact = client.finish_artifact_upload(app_slug, &build_slug, &upload.slug)?.data;
+
+    match format {
+        OutputFormat::Pretty => Ok(format!(
+            "{} Uploaded {} to build {} ({})",
+            "✓".green(),
+            artifact.title.bold(),
+            build_slug,
+            artifact.slug.dimmed()
+        )),
+        OutputFormat::Json => json::format_json(&serde_json::json!({
+            "build_slug": build_slug,
+            "artifact": artifact,
+        })),
+    }
+}
+
+/// A terminal QR code of `text`, rendered by `qrencode`
+fn qr_code(text: &str) -> Result<String> {
+    let output = Command::new("qrencode")
+        .args(["-t", "ansiutf8"])
+        .arg(text)
+        .output()
+        .map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                RepriseError::Io(std::io::Error::new(
+                    e.kind(),
+                    "'qrencode' not found on PATH (needed for --qr)",
+                ))
+            } else {
+                RepriseError::Io(e)
+            }
+        })?;
+    if !output.status.success() {
+        return Err(RepriseError::Io(std::io::Error::other(format!(
+            "'qrencode' exited with {}",
+            output.status
+        ))));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
 /// Handle the artifacts command
 pub fn artifacts(
     client: &BitriseClient,
@@ -171,34 +479,51 @@ pub fn artifacts(
     args: &ArtifactsArgs,
     format: OutputFormat,
 ) -> Result<String> {
+    match args.command {
+        Some(ArtifactsCommands::Publish(ref publish_args)) => {
+            return publish(client, config, publish_args, format);
+        }
+        Some(ArtifactsCommands::Upload(ref upload_args)) => {
+            return upload(client, config, upload_args, format);
+        }
+        None => {}
+    }
+
     // Get app slug from args or default
-    let app_slug = args
-        .app
-        .as_deref()
-        .or(config.defaults.app_slug.as_deref())
-        .ok_or_else(|| {
-            crate::error::RepriseError::Config(
-                "No app specified. Use --app or set a default with 'reprise app set'".to_string(),
-            )
-        })?;
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+
+    let build_slug = resolve_build_slug(
+        client,
+        app_slug,
+        args.slug.as_deref().unwrap_or_default(),
+        args.branch.as_deref(),
+        args.workflow.as_deref(),
+        format,
+    )?;
+
+    let retention = check_retention(client, app_slug, &build_slug, args)?;
 
     // List artifacts
-    let response = client.list_artifacts(app_slug, &args.slug)?;
+    let response = client.list_artifacts(app_slug, &build_slug)?;
 
     if response.data.is_empty() {
         return match format {
             OutputFormat::Pretty => Ok("No artifacts found for this build.".dimmed().to_string()),
-            OutputFormat::Json => Ok(serde_json::to_string_pretty(&response.data)?),
+            OutputFormat::Json => json::format_json(&response.data),
         };
     }
 
     // Apply filtering
-    let filtered_artifacts = filter_artifacts(
+    let mut filtered_artifacts = filter_artifacts(
         &response.data,
         args.filter.as_deref(),
         args.exclude.as_deref(),
     );
 
+    if let Some(sort) = args.sort {
+        sort_artifacts(&mut filtered_artifacts, sort);
+    }
+
     if filtered_artifacts.is_empty() {
         let filter_msg = match (&args.filter, &args.exclude) {
             (Some(f), Some(e)) => format!("filter '{}' and exclude '{}'", f, e),
@@ -212,7 +537,7 @@ pub fn artifacts(
                 filter_msg,
                 response.data.len()
             ).dimmed().to_string()),
-            OutputFormat::Json => Ok(serde_json::to_string_pretty(&Vec::<&Artifact>::new())?),
+            OutputFormat::Json => json::format_json(&Vec::<&Artifact>::new()),
         };
     }
 
@@ -220,37 +545,20 @@ pub fn artifacts(
     if let Some(ref dir_opt) = args.download {
         let download_dir = match dir_opt {
             Some(path) => PathBuf::from(path),
-            None => std::env::current_dir()?,
+            None => match config.app_config(app_slug).download_dir {
+                Some(ref dir) => PathBuf::from(dir),
+                None => std::env::current_dir()?,
+            },
         };
 
-        // Create directory if it doesn't exist
-        std::fs::create_dir_all(&download_dir)?;
-
-        let mut downloaded = Vec::new();
-
-        for artifact in &filtered_artifacts {
-            // Get artifact with download URL
-            let artifact_detail =
-                client.get_artifact(app_slug, &args.slug, &artifact.slug)?;
-
-            if let Some(ref url) = artifact_detail.data.expiring_download_url {
-                // Sanitize filename to prevent path traversal
-                let safe_filename = sanitize_filename(&artifact.title)?;
-                let file_path = download_dir.join(&safe_filename);
-
-                if format == OutputFormat::Pretty {
-                    eprint!("Downloading {}... ", safe_filename);
-                }
-
-                client.download_artifact(url, &file_path)?;
-
-                if format == OutputFormat::Pretty {
-                    eprintln!("{}", "done".green());
-                }
-
-                downloaded.push(safe_filename);
-            }
-        }
+        let downloaded = download_artifacts(
+            client,
+            app_slug,
+            &build_slug,
+            &filtered_artifacts,
+            &download_dir,
+            format,
+        )?;
 
         return match format {
             OutputFormat::Pretty => {
@@ -269,11 +577,12 @@ pub fn artifacts(
             }
             OutputFormat::Json => {
                 let json = serde_json::json!({
+                    "build_slug": build_slug,
                     "downloaded": downloaded,
                     "directory": download_dir.to_string_lossy(),
                     "total_artifacts": response.data.len(),
                 });
-                Ok(serde_json::to_string_pretty(&json)?)
+                json::format_json(&json)
             }
         };
     }
@@ -312,6 +621,8 @@ pub fn artifacts(
                 if let Some(ref artifact_type) = artifact.artifact_type {
                     output.push_str(&format!("    Type: {}\n", artifact_type));
                 }
+                output.push_str(&pretty::format_artifact_meta(artifact));
+                output.push_str(&pretty::format_artifact_expiry(artifact, retention, Utc::now()));
                 output.push('\n');
             }
 
@@ -319,7 +630,7 @@ pub fn artifacts(
         }
         OutputFormat::Json => {
             let artifacts_data: Vec<_> = filtered_artifacts.to_vec();
-            Ok(serde_json::to_string_pretty(&artifacts_data)?)
+            json::format_json(&artifacts_data)
         }
     }
 }
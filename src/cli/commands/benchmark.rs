@@ -0,0 +1,256 @@
+//! Benchmark command (latency of key Bitrise API endpoints)
+//!
+//! Each endpoint is called a number of times over one kept-alive
+//! connection, so its times are Bitrise's response time plus a network
+//! round trip. A TCP connect to the API host measures the round trip alone:
+//! slow connects point at the network, slow requests over fast connects
+//! point at Bitrise.
+
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::bitrise::BitriseClient;
+use crate::cli::args::{BenchmarkArgs, OutputFormat};
+use crate::config::Config;
+use crate::error::{RepriseError, Result};
+use crate::output::json;
+use crate::output::progress::StatusLine;
+use crate::output::pretty;
+use crate::output::table::{self, Table};
+
+use super::common::{resolve_app, show_progress};
+
+/// Give up on a TCP connect after this long
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Name of the network baseline measurement
+const CONNECT: &str = "TCP connect";
+
+/// Builds listed per request, as `reprise builds` does by default
+const BUILDS_LIMIT: u32 = 10;
+
+/// Latency of one endpoint over all its requests
+#[derive(Debug, Serialize)]
+struct Measurement {
+    endpoint: &'static str,
+    requests: usize,
+    errors: usize,
+    /// The first error, to tell a bad token from a flaky network
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    min_ms: Option<f64>,
+    p50_ms: Option<f64>,
+    p95_ms: Option<f64>,
+    max_ms: Option<f64>,
+}
+
+impl Measurement {
+    /// Time `requests` calls of `call`
+    fn run<T>(
+        endpoint: &'static str,
+        requests: usize,
+        mut call: impl FnMut() -> Result<T>,
+    ) -> Self {
+        let mut times = Vec::with_capacity(requests);
+        let mut errors = 0;
+        let mut error = None;
+        for _ in 0..requests {
+            let start = Instant::now();
+            match call() {
+                Ok(_) => times.push(start.elapsed()),
+                Err(e) => {
+                    errors += 1;
+                    error.get_or_insert_with(|| e.to_string());
+                }
+            }
+        }
+        times.sort();
+        Self {
+            endpoint,
+            requests,
+            errors,
+            error,
+            min_ms: times.first().copied().map(millis),
+            p50_ms: percentile(&times, 50.0).map(millis),
+            p95_ms: percentile(&times, 95.0).map(millis),
+            max_ms: times.last().copied().map(millis),
+        }
+    }
+}
+
+/// Handle the benchmark command
+pub fn benchmark(
+    client: &BitriseClient,
+    config: &Config,
+    args: &BenchmarkArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    let requests = args.requests as usize;
+
+    let mut status_line = show_progress(format).then(|| StatusLine::start("Connecting"));
+    let mut progress = |endpoint: &str| {
+        if let Some(ref mut status) = status_line {
+            status.set_message(&format!("Measuring {} ({} requests)", endpoint, requests));
+        }
+    };
+
+    // Open the kept-alive connection first, so TLS setup isn't in the times
+    let builds = client.list_builds(app_slug, None, None, None, BUILDS_LIMIT)?;
+    let build_slug = builds.data.iter().find(|b| b.status.is_finished()).map(|b| b.slug.clone());
+
+    let mut measurements = Vec::new();
+    // Replayed requests never touch the network, so there is nothing to connect to
+    if !client.is_replay() {
+        progress(CONNECT);
+        let address = api_address(client.base_url())?;
+        measurements.push(Measurement::run(CONNECT, requests, || {
+            Ok(TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)?)
+        }));
+    }
+    progress("list apps");
+    measurements.push(Measurement::run("list apps", requests, || {
+        client.list_apps_page(BUILDS_LIMIT, None)
+    }));
+    progress("list builds");
+    measurements.push(Measurement::run("list builds", requests, || {
+        client.list_builds(app_slug, None, None, None, BUILDS_LIMIT)
+    }));
+    if let Some(ref build_slug) = build_slug {
+        progress("log metadata");
+        measurements.push(Measurement::run("log metadata", requests, || {
+            client.get_build_log(app_slug, build_slug)
+        }));
+    }
+    if let Some(ref mut status) = status_line {
+        status.finish();
+    }
+
+    match format {
+        OutputFormat::Pretty => Ok(format_measurements(&measurements, app_slug, requests)),
+        OutputFormat::Json => json::format_json(&serde_json::json!({
+            "app_slug": app_slug,
+            "requests": requests,
+            "measurements": measurements,
+        })),
+    }
+}
+
+/// The API host's first address, for the TCP connect baseline
+fn api_address(base_url: &str) -> Result<std::net::SocketAddr> {
+    let url = url::Url::parse(base_url)
+        .map_err(|e| RepriseError::InvalidArgument(format!("Invalid API URL: {}", e)))?;
+    let host = url.host_str().unwrap_or_default();
+    let port = url.port_or_known_default().unwrap_or(443);
+    (host, port).to_socket_addrs()?.next().ok_or_else(|| {
+        RepriseError::Io(std::io::Error::other(format!("{} has no addresses", host)))
+    })
+}
+
+/// Nearest-rank percentile of sorted times
+fn percentile(sorted: &[Duration], p: f64) -> Option<Duration> {
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.clamp(1, sorted.len().max(1)) - 1).copied()
+}
+
+fn millis(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 10_000.0).round() / 10.0
+}
+
+fn format_measurements(measurements: &[Measurement], app_slug: &str, requests: usize) -> String {
+    let mut output = String::new();
+    if table::headers_shown() {
+        output.push_str(&format!(
+            "{} for {} ({} requests each)\n",
+            "API Latency".bold(),
+            app_slug,
+            requests
+        ));
+        output.push_str(&pretty::separator(pretty::MAX_RULE_WIDTH));
+        output.push('\n');
+    }
+    let ms = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{:.0} ms", v));
+    let mut table = Table::new(&["ENDPOINT", "P50", "P95", "MIN", "MAX", "ERRORS"]);
+    for m in measurements {
+        let errors = match m.errors {
+            0 => "0".dimmed().to_string(),
+            n => n.to_string().red().to_string(),
+        };
+        table.row(vec![
+            m.endpoint.bold().to_string(),
+            ms(m.p50_ms),
+            ms(m.p95_ms),
+            ms(m.min_ms),
+            ms(m.max_ms),
+            errors,
+        ]);
+        if let Some(ref error) = m.error {
+            table.text(format!("  {}", error.dimmed()));
+        }
+    }
+    output.push_str(table.render().trim_end());
+    if let Some(verdict) = verdict(measurements) {
+        output.push_str(&format!("\n\n{}", verdict));
+    }
+    output
+}
+
+/// A one-line reading of the numbers, when the TCP baseline was measured
+fn verdict(measurements: &[Measurement]) -> Option<String> {
+    let connect = measurements.iter().find(|m| m.endpoint == CONNECT)?.p50_ms?;
+    let slowest = measurements
+        .iter()
+        .filter(|m| m.endpoint != CONNECT)
+        .filter_map(|m| Some((m.endpoint, m.p50_ms?)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    let verdict = if connect >= 250.0 {
+        format!(
+            "The network to Bitrise is slow: a TCP connect alone takes {:.0} ms.",
+            connect
+        )
+    } else if slowest.1 >= 1000.0 {
+        format!(
+            "Bitrise is slow to respond: {} takes {:.0} ms over a {:.0} ms round trip.",
+            slowest.0, slowest.1, connect
+        )
+    } else {
+        format!("Latency looks normal ({:.0} ms round trip).", connect)
+    };
+    Some(verdict.dimmed().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile() {
+        let times: Vec<Duration> = (1..=20).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&times, 50.0), Some(Duration::from_millis(10)));
+        assert_eq!(percentile(&times, 95.0), Some(Duration::from_millis(19)));
+        assert_eq!(percentile(&times[..1], 95.0), Some(Duration::from_millis(1)));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn test_verdict() {
+        let measurement = |endpoint, p50| Measurement {
+            endpoint,
+            requests: 1,
+            errors: 0,
+            error: None,
+            min_ms: Some(p50),
+            p50_ms: Some(p50),
+            p95_ms: Some(p50),
+            max_ms: Some(p50),
+        };
+        let slow_api = [measurement(CONNECT, 40.0), measurement("list builds", 2400.0)];
+        assert!(verdict(&slow_api).unwrap().contains("Bitrise is slow"));
+        let slow_network = [measurement(CONNECT, 400.0), measurement("list apps", 900.0)];
+        assert!(verdict(&slow_network).unwrap().contains("The network"));
+        assert_eq!(verdict(&[measurement("list apps", 90.0)]), None);
+    }
+}
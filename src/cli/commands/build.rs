@@ -1,15 +1,21 @@
 use std::io::{self, Write};
-use std::thread;
-use std::time::Duration;
 
 use colored::Colorize;
 
-use super::common::{is_interrupted, resolve_app_slug, setup_interrupt_handler};
+use super::common::{
+    build_progress_message, fetch_full_log, finished_message, is_interrupted, resolve_app,
+    setup_interrupt_handler,
+};
 use crate::bitrise::BitriseClient;
 use crate::cli::args::{BuildArgs, OutputFormat};
 use crate::config::Config;
 use crate::error::{RepriseError, Result};
+use crate::eta;
+use crate::logparse::{Highlighter, StepTracker};
 use crate::output;
+use crate::output::json;
+use crate::output::progress::StatusLine;
+use crate::watch::PollSchedule;
 
 /// Handle the build command (show details)
 pub fn build(
@@ -19,16 +25,24 @@ pub fn build(
     format: OutputFormat,
 ) -> Result<String> {
     // Resolve app slug from args or config default
-    let app_slug = resolve_app_slug(args.app.as_deref(), config)?;
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
 
     // Handle --follow: stream live log output
     if args.follow {
-        return follow_log(client, app_slug, &args.slug, args.interval, args.notify, format);
+        return follow_log(
+            client,
+            &config.log_highlighter()?,
+            app_slug,
+            &args.slug,
+            args.interval,
+            args.notify,
+            format,
+        );
     }
 
     // Handle --logs: dump full log
     if args.logs {
-        return dump_log(client, app_slug, &args.slug, format);
+        return dump_log(client, config, app_slug, &args.slug, format);
     }
 
     // Handle --artifacts: list artifacts
@@ -42,13 +56,14 @@ pub fn build(
 }
 
 /// Dump the full build log
-fn dump_log(
+pub(super) fn dump_log(
     client: &BitriseClient,
+    config: &Config,
     app_slug: &str,
     build_slug: &str,
     format: OutputFormat,
 ) -> Result<String> {
-    let log_content = client.get_full_log(app_slug, build_slug)?;
+    let log_content = fetch_full_log(client, config, app_slug, build_slug)?;
 
     if log_content.is_empty() {
         return Err(RepriseError::LogNotAvailable(
@@ -57,14 +72,14 @@ fn dump_log(
     }
 
     match format {
-        OutputFormat::Pretty => Ok(highlight_log_content(&log_content)),
+        OutputFormat::Pretty => Ok(config.log_highlighter()?.highlight(&log_content)),
         OutputFormat::Json => {
             let result = serde_json::json!({
                 "build_slug": build_slug,
                 "log": log_content,
                 "lines": log_content.lines().count()
             });
-            Ok(serde_json::to_string_pretty(&result)?)
+            json::format_json(&result)
         }
     }
 }
@@ -83,6 +98,7 @@ fn list_artifacts(
 /// Follow log output for a running build
 fn follow_log(
     client: &BitriseClient,
+    highlighter: &Highlighter,
     app_slug: &str,
     build_slug: &str,
     interval_secs: u64,
@@ -95,17 +111,16 @@ fn follow_log(
     // Set up signal handler for graceful Ctrl+C handling
     let interrupted = setup_interrupt_handler();
 
-    if format == OutputFormat::Pretty {
-        eprintln!(
-            "{} Following build log (Ctrl+C to stop)...\n",
-            "->".cyan()
-        );
-    }
+    let mut status_line = (format == OutputFormat::Pretty)
+        .then(|| StatusLine::start("Following build log (Ctrl+C to stop)"));
+    let mut steps = StepTracker::new();
 
+    let schedule = PollSchedule::start_secs(interval_secs);
     loop {
         // Check for interrupt
         if is_interrupted(&interrupted) {
-            if format == OutputFormat::Pretty {
+            if let Some(ref mut status) = status_line {
+                status.finish();
                 eprintln!("\n{} Interrupted by user", "!".yellow());
             }
             break;
@@ -120,7 +135,7 @@ fn follow_log(
             Err(_) => {
                 // Log may not be available yet
                 if build.data.is_running() {
-                    thread::sleep(Duration::from_secs(interval_secs));
+                    schedule.sleep();
                     continue;
                 }
                 return Err(RepriseError::LogNotAvailable(
@@ -135,33 +150,54 @@ fn follow_log(
 
         // Print new lines
         if !new_lines.is_empty() {
-            for line in new_lines {
-                match format {
-                    OutputFormat::Pretty => {
-                        writeln!(stdout, "{}", highlight_log_line(line))?;
-                    }
-                    OutputFormat::Json => {
-                        let json = serde_json::json!({ "line": line });
-                        writeln!(stdout, "{}", serde_json::to_string(&json)?)?;
+            let mut print = || -> Result<()> {
+                for line in new_lines {
+                    match format {
+                        OutputFormat::Pretty => {
+                            writeln!(stdout, "{}", highlighter.highlight_line(line))?;
+                        }
+                        OutputFormat::Json => {
+                            let json = serde_json::json!({ "line": line });
+                            writeln!(stdout, "{}", serde_json::to_string(&json)?)?;
+                        }
                     }
                 }
+                stdout.flush()?;
+                Ok(())
+            };
+            match status_line {
+                Some(ref status) => status.hide_while(print)?,
+                None => print()?,
+            }
+            for line in new_lines {
+                steps.feed(line);
             }
-            stdout.flush()?;
             last_line_count = lines.len();
         }
 
+        // Show where the build is while it runs
+        if build.data.is_running() {
+            if let Some(ref mut status) = status_line {
+                if let Some(started_at) = build.data.started_on_worker_at {
+                    status.set_started_at(started_at);
+                    let workflow = &build.data.triggered_workflow;
+                    status.set_expected(eta::expected_duration(client, app_slug, workflow));
+                }
+                status.set_message(&build_progress_message(&build.data));
+                status.set_step(steps.current());
+            }
+        }
+
         // Check if build is done
         if !build.data.is_running() {
-            if format == OutputFormat::Pretty {
-                let status_msg = match build.data.status {
-                    1 => format!("\n{} Build completed successfully", "✓".green()),
-                    2 => format!("\n{} Build failed", "✗".red()),
-                    3 => format!("\n{} Build aborted", "!".yellow()),
-                    _ => format!("\n{} Build finished", "->".cyan()),
-                };
+            if let Some(ref mut status) = status_line {
+                status.finish();
+                let status_msg = format!("\n{}", finished_message("Build", build.data.status));
                 eprintln!("{}", status_msg);
             }
 
+            crate::hooks::build_finished(app_slug, &build.data);
+
             // Send desktop notification if requested
             if send_notification {
                 crate::notify::build_completed(&build.data, None);
@@ -171,59 +207,10 @@ fn follow_log(
         }
 
         // Wait before next poll
-        thread::sleep(Duration::from_secs(interval_secs));
+        schedule.sleep();
     }
 
     // Return empty string since we've already printed everything
     Ok(String::new())
 }
 
-/// Highlight log lines based on content
-fn highlight_log_line(line: &str) -> String {
-    let line_lower = line.to_lowercase();
-
-    // Error patterns (red)
-    if line_lower.contains("error")
-        || line_lower.contains("failed")
-        || line_lower.contains("failure")
-        || line_lower.contains("fatal")
-        || line_lower.contains("exception")
-        || line_lower.contains("panic")
-        || line.starts_with("E ")
-        || line.contains("[ERROR]")
-        || line.contains("[error]")
-    {
-        return line.red().to_string();
-    }
-
-    // Warning patterns (yellow)
-    if line_lower.contains("warning")
-        || line_lower.contains("warn")
-        || line.starts_with("W ")
-        || line.contains("[WARN]")
-        || line.contains("[warn]")
-    {
-        return line.yellow().to_string();
-    }
-
-    // Success patterns (green)
-    if line_lower.contains("success")
-        || line_lower.contains("passed")
-        || line_lower.contains("completed")
-        || line.contains("[OK]")
-        || line.contains("BUILD SUCCESSFUL")
-    {
-        return line.green().to_string();
-    }
-
-    line.to_string()
-}
-
-/// Apply highlighting to full log content
-fn highlight_log_content(content: &str) -> String {
-    content
-        .lines()
-        .map(highlight_log_line)
-        .collect::<Vec<_>>()
-        .join("\n")
-}
@@ -1,3 +1,4 @@
+use std::collections::{HashMap, HashSet};
 use std::io::{self, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -7,13 +8,17 @@ use std::time::Duration;
 use chrono::Local;
 use colored::Colorize;
 
-use super::common::{get_github_username, matches_user, resolve_app_slug};
-use crate::bitrise::BitriseClient;
-use crate::cli::args::{BuildsArgs, OutputFormat};
+use super::common::{
+    matches_user, me_filter, remember_workflows, resolve_app, resolve_app_slug, search_builds,
+    search_builds_in_window, show_progress, warn_paged_limit,
+};
+use crate::bitrise::{BitriseClient, Build};
+use crate::cli::args::{BuildGroupBy, BuildSort, BuildsArgs, OutputFormat};
 use crate::config::Config;
-use crate::duration::parse_since;
-use crate::error::{RepriseError, Result};
-use crate::output;
+use crate::duration::TimeWindow;
+use crate::error::Result;
+use crate::git::{self, HeadRelation};
+use crate::output::{self, json, pretty};
 
 /// Handle the builds command
 pub fn builds(
@@ -39,6 +44,14 @@ fn watch_builds(
     format: OutputFormat,
 ) -> Result<String> {
     let mut stdout = io::stdout();
+    let interval = args
+        .interval
+        .or_else(|| {
+            resolve_app_slug(args.app.as_deref(), config)
+                .ok()
+                .and_then(|app| config.app_config(app).interval)
+        })
+        .unwrap_or(10);
 
     // Set up signal handler for graceful Ctrl+C handling
     let interrupted = Arc::new(AtomicBool::new(false));
@@ -49,11 +62,11 @@ fn watch_builds(
     })
     .ok();
 
-    if format == OutputFormat::Pretty {
+    if show_progress(format) {
         eprintln!(
             "{} Watching builds (Ctrl+C to stop, refreshing every {}s)...\n",
             "->".cyan(),
-            args.interval
+            interval
         );
     }
 
@@ -90,14 +103,14 @@ fn watch_builds(
                 "\n{} Last updated: {} (refreshing every {}s)",
                 "->".dimmed(),
                 Local::now().format("%H:%M:%S"),
-                args.interval
+                interval
             );
         }
 
         stdout.flush()?;
 
         // Wait before next poll
-        thread::sleep(Duration::from_secs(args.interval));
+        thread::sleep(Duration::from_secs(interval));
     }
 
     Ok(String::new())
@@ -111,130 +124,200 @@ fn fetch_and_format_builds(
     format: OutputFormat,
 ) -> Result<String> {
     // Resolve app slug from args or config default
-    let app_slug = resolve_app_slug(args.app.as_deref(), config)?;
-
-    // Resolve triggered_by filter (--me uses API to get current user + GitHub username)
-    let me_filter: Option<(String, Option<String>)> = if args.me {
-        let user = client.get_me().map_err(|e| {
-            RepriseError::Config(format!(
-                "Cannot determine current user for --me flag: {}. Use --triggered-by <username> instead.",
-                e
-            ))
-        })?;
-        let github_username = get_github_username();
-
-        // Warn if GitHub username not configured (webhook-triggered builds won't match)
-        if github_username.is_none() && format != OutputFormat::Json {
-            eprintln!(
-                "hint: GitHub username not configured. Webhook-triggered builds may not be matched.\n\
-                 hint: Run: git config --global github.user YOUR_GITHUB_USERNAME\n"
-            );
-        }
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
 
-        Some((user.data.username, github_username))
-    } else {
-        None
-    };
+    // Fall back to the app's configured branch and workflow filters
+    let app_config = config.app_config(app_slug);
+    let branch = args.branch.as_deref().or(app_config.branch.as_deref());
+    let workflow = args.workflow.as_deref().or(app_config.workflow.as_deref());
 
-    let triggered_by_filter = args.triggered_by.clone();
+    // Resolve triggered_by filter (--me uses the cached current user + GitHub username)
+    let me_filter = if args.me { Some(me_filter(client, config, format)?) } else { None };
+    let triggered_by_lower = args.triggered_by.as_ref().map(|s| s.to_lowercase());
 
-    // Convert status filter to API code
-    let status = args.status.map(|s| s.to_api_code());
+    let status = args.status.map(|s| s.to_status());
 
-    // Fetch extra builds when filtering client-side to ensure we have enough results
-    // Cap at 50 (API maximum)
-    let fetch_limit = if me_filter.is_some() || triggered_by_filter.is_some() {
-        args.limit.saturating_mul(4).min(50)
-    } else {
-        args.limit.min(50)
-    };
+    warn_paged_limit(args.limit);
+
+    // Parse --since/--until window if provided
+    let window = TimeWindow::from_args(args.since.as_deref(), args.until.as_deref())?;
 
-    let response = client.list_builds(
-        app_slug,
-        status,
-        args.branch.as_deref(),
-        args.workflow.as_deref(),
-        fetch_limit,
-    )?;
-
-    // Parse --since threshold if provided
-    let since_threshold = args
-        .since
-        .as_ref()
-        .map(|s| parse_since(s))
-        .transpose()?;
-
-    // Apply client-side filters
     let workflow_contains_lower = args.workflow_contains.as_ref().map(|s| s.to_lowercase());
+    let live = if args.stale_branches { Some(live_branches(client, app_slug)?) } else { None };
+    let client_filtered = args.commit.is_some()
+        || args.pr.is_some()
+        || me_filter.is_some()
+        || triggered_by_lower.is_some()
+        || workflow_contains_lower.is_some()
+        || live.is_some();
 
-    // PR number filter
-    let pr_filter = args.pr;
+    // Commit, PR, user, branch existence and time window filters aren't supported by the API;
+    // search history until enough builds match
+    let mut builds = if client_filtered || window.is_some() {
+        let sha = args.commit.as_deref().map(git::resolve_commit_arg).transpose()?;
+        let predicate = |b: &Build| {
+            let commit_ok = sha.as_deref().is_none_or(|sha| {
+                b.commit_hash
+                    .as_deref()
+                    .is_some_and(|hash| git::commit_matches(hash, sha))
+            });
+            let user_ok = match (&me_filter, &triggered_by_lower) {
+                // --me: match both Bitrise username and webhook-github/<github-username>
+                (Some((bitrise_username, github_username)), _) => {
+                    b.triggered_by.as_deref().is_some_and(|t| {
+                        matches_user(t, bitrise_username, github_username.as_deref())
+                    })
+                }
+                // --triggered-by: case-insensitive partial match
+                (None, Some(user)) => b
+                    .triggered_by
+                    .as_deref()
+                    .is_some_and(|t| t.to_lowercase().contains(user)),
+                (None, None) => true,
+            };
+            let workflow_ok = workflow_contains_lower
+                .as_ref()
+                .is_none_or(|pattern| b.triggered_workflow.to_lowercase().contains(pattern));
+            // Tag builds have no branch to go stale
+            let stale_ok = live.as_ref().is_none_or(|live| {
+                b.tag.is_none() && !b.branch.is_empty() && !live.contains(&b.branch)
+            });
+            commit_ok
+                && user_ok
+                && workflow_ok
+                && stale_ok
+                && args.pr.is_none_or(|pr_num| b.pull_request_id == Some(pr_num))
+        };
 
-    let builds: Vec<_> = if let Some((ref bitrise_username, ref github_username)) = me_filter {
-        // --me flag: match both Bitrise username and webhook-github/<github-username>
-        response
-            .data
-            .into_iter()
-            .filter(|b| {
-                b.triggered_by
-                    .as_ref()
-                    .map(|t| matches_user(t, bitrise_username, github_username.as_deref()))
-                    .unwrap_or(false)
-            })
-            .filter(|b| {
-                workflow_contains_lower.as_ref().is_none_or(|pattern| {
-                    b.triggered_workflow.to_lowercase().contains(pattern)
-                })
-            })
-            .filter(|b| {
-                since_threshold.is_none_or(|threshold| b.triggered_at >= threshold)
-            })
-            .filter(|b| {
-                pr_filter.is_none_or(|pr_num| b.pull_request_id == Some(pr_num))
-            })
-            .take(args.limit as usize)
-            .collect()
-    } else if let Some(ref user) = triggered_by_filter {
-        // --triggered-by flag: case-insensitive partial match (existing behavior)
-        let user_lower = user.to_lowercase();
-        response
-            .data
-            .into_iter()
-            .filter(|b| {
-                b.triggered_by
-                    .as_ref()
-                    .map(|t| t.to_lowercase().contains(&user_lower))
-                    .unwrap_or(false)
-            })
-            .filter(|b| {
-                workflow_contains_lower.as_ref().is_none_or(|pattern| {
-                    b.triggered_workflow.to_lowercase().contains(pattern)
-                })
-            })
-            .filter(|b| {
-                since_threshold.is_none_or(|threshold| b.triggered_at >= threshold)
-            })
-            .filter(|b| {
-                pr_filter.is_none_or(|pr_num| b.pull_request_id == Some(pr_num))
-            })
-            .take(args.limit as usize)
-            .collect()
+        let limit = args.limit as usize;
+        match window {
+            Some(ref w) => search_builds_in_window(
+                client, app_slug, status, branch, workflow, limit, w, predicate,
+            )?,
+            None => search_builds(client, app_slug, status, branch, workflow, limit, predicate)?,
+        }
     } else {
-        response.data.into_iter()
-            .filter(|b| {
-                workflow_contains_lower.as_ref().is_none_or(|pattern| {
-                    b.triggered_workflow.to_lowercase().contains(pattern)
+        client
+            .list_builds(app_slug, status, branch, workflow, args.limit)?
+            .data
+    };
+    builds.truncate(args.limit as usize);
+    remember_workflows(config, app_slug, &builds);
+
+    // Deltas compare against older builds, so compute them before re-sorting
+    let mut notes = pretty::BuildNotes::default();
+    if format == OutputFormat::Pretty {
+        if args.deltas {
+            notes.deltas = pretty::delta_map(&builds);
+        }
+        if !args.no_git {
+            notes.head = head_relations(&builds);
+        }
+    }
+
+    if let Some(sort) = args.sort {
+        sort_builds(&mut builds, sort);
+    }
+
+    match (args.group_by, format) {
+        (Some(key), OutputFormat::Pretty) => Ok(pretty::format_build_groups(
+            &group_builds(builds, key),
+            &notes,
+        )),
+        (Some(key), OutputFormat::Json) => {
+            let groups: Vec<_> = group_builds(builds, key)
+                .into_iter()
+                .map(|(name, builds)| {
+                    serde_json::json!({
+                        "key": name,
+                        "count": builds.len(),
+                        "builds": builds,
+                    })
                 })
-            })
-            .filter(|b| {
-                since_threshold.is_none_or(|threshold| b.triggered_at >= threshold)
-            })
-            .filter(|b| {
-                pr_filter.is_none_or(|pr_num| b.pull_request_id == Some(pr_num))
-            })
-            .take(args.limit as usize)
-            .collect()
+                .collect();
+            let json = serde_json::json!({
+                "group_by": key.as_str(),
+                "groups": groups,
+            });
+            json::format_json(&json)
+        }
+        (None, OutputFormat::Pretty) => {
+            let mut output = pretty::format_builds_annotated(&builds, &notes);
+            if let Some(trend) = pretty::format_duration_trend(&builds).filter(|_| args.deltas) {
+                output.push_str(&format!("\n\n{}", trend));
+            }
+            Ok(output)
+        }
+        (None, OutputFormat::Json) => output::format_builds(&builds, format),
+    }
+}
+
+/// Branches that still exist in the app's repository
+///
+/// Uses `origin`'s remote-tracking branches when the current checkout is the
+/// app's repository, otherwise the branches Bitrise reports for the app.
+fn live_branches(client: &BitriseClient, app_slug: &str) -> Result<HashSet<String>> {
+    let remote = git::origin_url().and_then(|u| git::normalize_repo_url(&u));
+    if let Some(remote) = remote {
+        // Best effort: if the app can't be fetched, ask Bitrise instead
+        let same_repo = client.get_app(app_slug).ok().is_some_and(|app| {
+            app.data
+                .repo_url
+                .as_deref()
+                .and_then(git::normalize_repo_url)
+                .is_some_and(|url| url == remote)
+        });
+        if let Some(branches) = same_repo.then(git::remote_branches).flatten() {
+            return Ok(branches.into_iter().collect());
+        }
+    }
+    Ok(client.list_branches(app_slug)?.data.into_iter().collect())
+}
+
+/// How each build's commit relates to the local checkout, keyed by build slug
+///
+/// Empty outside a git repository; builds of commits git doesn't know are skipped.
+fn head_relations(builds: &[Build]) -> HashMap<String, HeadRelation> {
+    let Some(head) = git::head_commit() else {
+        return HashMap::new();
     };
+    let mut by_commit: HashMap<&str, Option<HeadRelation>> = HashMap::new();
+    builds
+        .iter()
+        .filter_map(|build| {
+            let commit = build.commit_hash.as_deref()?;
+            let relation = *by_commit
+                .entry(commit)
+                .or_insert_with(|| git::head_relation(commit, &head));
+            relation.map(|r| (build.slug.clone(), r))
+        })
+        .collect()
+}
+
+/// Reorder builds; ties and missing values keep the API's newest-first order
+fn sort_builds(builds: &mut [Build], sort: BuildSort) {
+    match sort {
+        BuildSort::Duration => {
+            builds.sort_by_key(|b| std::cmp::Reverse(b.duration().map(|d| d.num_seconds())))
+        }
+        BuildSort::Number => builds.sort_by_key(|b| std::cmp::Reverse(b.build_number)),
+        BuildSort::Finished => builds.sort_by_key(|b| std::cmp::Reverse(b.finished_at)),
+    }
+}
 
-    output::format_builds(&builds, format)
+/// Split builds into sections, in order of first appearance
+fn group_builds(builds: Vec<Build>, key: BuildGroupBy) -> Vec<(String, Vec<Build>)> {
+    let mut groups: Vec<(String, Vec<Build>)> = Vec::new();
+    for build in builds {
+        let name = match key {
+            BuildGroupBy::Branch => build.branch.clone(),
+            BuildGroupBy::Workflow => build.triggered_workflow.clone(),
+            BuildGroupBy::Status => build.status_display().to_string(),
+        };
+        match groups.iter_mut().find(|(existing, _)| *existing == name) {
+            Some((_, members)) => members.push(build),
+            None => groups.push((name, vec![build])),
+        }
+    }
+    groups
 }
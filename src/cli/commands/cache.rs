@@ -0,0 +1,136 @@
+//! Cache command (inspect and clear locally cached API data)
+
+use chrono::Utc;
+use colored::Colorize;
+
+use crate::cache::logs::DEFAULT_LOG_LIMIT_MB;
+use crate::cache::{Cache, CacheFileInfo, LogCache};
+use crate::cli::args::{CacheArgs, CacheCommands, OutputFormat};
+use crate::config::Config;
+use crate::error::Result;
+use crate::output::json;
+use crate::output::progress::format_elapsed;
+
+/// Handle the cache command
+pub fn cache(config: &Config, args: &CacheArgs, format: OutputFormat) -> Result<String> {
+    let cache = Cache::for_config(&config.cache)?;
+    let logs = LogCache::new(
+        &cache,
+        config.cache.log_limit_mb.unwrap_or(DEFAULT_LOG_LIMIT_MB),
+    );
+    match &args.command {
+        CacheCommands::Status => cache_status(&cache, &logs, format),
+        CacheCommands::Clear { entry } => cache_clear(&cache, &logs, entry.as_deref(), format),
+    }
+}
+
+/// Show the cache location, entries and size on disk
+fn cache_status(cache: &Cache, logs: &LogCache, format: OutputFormat) -> Result<String> {
+    let entries = cache.entries();
+    let (log_count, log_bytes) = logs.usage();
+    let total_bytes: u64 = entries.iter().map(|e| e.bytes).sum::<u64>() + log_bytes;
+
+    match format {
+        OutputFormat::Pretty => {
+            let mut output = String::new();
+            output.push_str(&format!("{}\n", "Cache".bold()));
+            output.push_str(&format!("  Location: {}\n", cache.dir().display()));
+            output.push_str(&format!(
+                "  Entries:  {} ({})\n",
+                entries.len(),
+                format_bytes(total_bytes)
+            ));
+
+            output.push_str(&format!(
+                "  Logs:     {} builds ({})\n",
+                log_count,
+                format_bytes(log_bytes)
+            ));
+
+            if !entries.is_empty() {
+                let width = entries.iter().map(|e| e.key.len()).max().unwrap_or(0);
+                output.push('\n');
+                for entry in &entries {
+                    output.push_str(&format!("  {}\n", format_entry(entry, width)));
+                }
+            }
+            Ok(output.trim_end().to_string())
+        }
+        OutputFormat::Json => {
+            let result = serde_json::json!({
+                "location": cache.dir().display().to_string(),
+                "total_bytes": total_bytes,
+                "entries": entries,
+                "logs": { "count": log_count, "bytes": log_bytes },
+            });
+            json::format_json(&result)
+        }
+    }
+}
+
+/// Remove all entries, or those matching one name (`logs` clears cached build logs)
+fn cache_clear(
+    cache: &Cache,
+    logs: &LogCache,
+    entry: Option<&str>,
+    format: OutputFormat,
+) -> Result<String> {
+    let mut removed = Vec::new();
+    if entry.is_none_or(|name| name == "logs") {
+        let count = logs.clear()?;
+        if count > 0 {
+            let plural = if count == 1 { "" } else { "s" };
+            removed.push(format!("logs ({} build{})", count, plural));
+        }
+    }
+    if entry != Some("logs") {
+        removed.extend(cache.clear(entry)?);
+    }
+
+    match format {
+        OutputFormat::Pretty => Ok(match (entry, removed.len()) {
+            (Some(name), 0) => format!("{} No cache entry named '{}'", "!".yellow(), name),
+            (_, 0) => "Cache is already empty.".to_string(),
+            (_, 1) => format!("{} Removed {}", "✓".green(), removed[0]),
+            (_, n) => format!("{} Removed {} entries", "✓".green(), n),
+        }),
+        OutputFormat::Json => {
+            let result = serde_json::json!({ "removed": removed });
+            json::format_json(&result)
+        }
+    }
+}
+
+/// One line of `cache status`: key, item count, size and age
+fn format_entry(entry: &CacheFileInfo, width: usize) -> String {
+    let items = entry
+        .items
+        .map(|n| format!("{} items", n))
+        .unwrap_or_default();
+    let age = entry
+        .stored_at
+        .map(|t| {
+            let secs = (Utc::now() - t).num_seconds().max(0) as u64;
+            format!("{} ago", format_elapsed(secs))
+        })
+        .unwrap_or_else(|| "unreadable".to_string());
+    format!(
+        "{:<width$}  {:>9}  {:>8}  {}",
+        entry.key,
+        items,
+        format_bytes(entry.bytes),
+        age.dimmed(),
+        width = width
+    )
+}
+
+/// Human-readable size, e.g. `512 B` or `8.1 KB`
+fn format_bytes(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
@@ -3,11 +3,23 @@
 //! This module contains helper functions that are used by multiple commands
 //! to avoid code duplication.
 
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
+use colored::Colorize;
+
+use crate::bitrise::{App, BitriseClient, Build, BuildStatus, Pipeline, User, MAX_PAGE_SIZE};
+use crate::cache::{Cache, LogCache};
 use crate::config::Config;
-use crate::error::Result;
+use crate::duration::TimeWindow;
+use crate::error::{RepriseError, Result};
+use crate::git;
+use crate::cli::args::OutputFormat;
+use crate::output::{terminal, theme};
 
 /// Get GitHub username from git config, if available.
 ///
@@ -86,6 +98,58 @@ pub fn matches_user(triggered_by: &str, bitrise_username: &str, github_username:
     false
 }
 
+/// Cache key prefix for the account behind the API token
+const ME_CACHE_KEY: &str = "me";
+
+/// How long the token's account is trusted, unless `cache.ttl` is set
+pub const ME_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The account the API token belongs to, from the cache or `/me`
+///
+/// Entries are keyed by a fingerprint of the token, so switching tokens
+/// never matches builds against another account's username.
+pub fn current_user(client: &BitriseClient, config: &Config) -> Result<User> {
+    let key = format!("{}-{}", ME_CACHE_KEY, client.token_fingerprint());
+    let ttl = config.cache.ttl.map_or(ME_CACHE_TTL, Duration::from_secs);
+    let cache = Cache::for_config(&config.cache).ok();
+    if let Some(user) = cache.as_ref().and_then(|c| c.get::<User>(&key, ttl)) {
+        return Ok(user);
+    }
+
+    let user = client.get_me()?.data;
+    if let Some(cache) = cache {
+        // A cache write failure only costs a `/me` call next time
+        let _ = cache.put(&key, &user);
+    }
+    Ok(user)
+}
+
+/// Bitrise and GitHub usernames that `--me` matches `triggered_by` against
+/// (see [`matches_user`])
+pub fn me_filter(
+    client: &BitriseClient,
+    config: &Config,
+    format: OutputFormat,
+) -> Result<(String, Option<String>)> {
+    let user = current_user(client, config).map_err(|e| {
+        RepriseError::Config(format!(
+            "Cannot determine current user for --me flag: {}. Use --triggered-by <username> instead.",
+            e
+        ))
+    })?;
+    let github_username = get_github_username();
+
+    // Warn if GitHub username not configured (webhook-triggered builds won't match)
+    if github_username.is_none() && show_progress(format) {
+        eprintln!(
+            "hint: GitHub username not configured. Webhook-triggered builds may not be matched.\n\
+             hint: Run: git config --global github.user YOUR_GITHUB_USERNAME\n"
+        );
+    }
+
+    Ok((user.username, github_username))
+}
+
 /// Resolve the app slug from command args or config default.
 ///
 /// This is a common pattern used across many commands where the app
@@ -119,6 +183,418 @@ pub fn resolve_app_slug<'a>(app_arg: Option<&'a str>, config: &'a Config) -> Res
     }
 }
 
+/// Cache key for the account's app list
+pub const APPS_CACHE_KEY: &str = "apps";
+
+/// How long the cached app list is trusted, unless `cache.ttl` is set
+pub const APPS_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// App list from the cache, refreshing it from the API when stale
+pub fn load_apps(
+    client: &BitriseClient,
+    cache: Option<&Cache>,
+    ttl: Duration,
+    refresh: bool,
+) -> Result<Vec<App>> {
+    if !refresh {
+        if let Some(apps) = cache.and_then(|c| c.get::<Vec<App>>(APPS_CACHE_KEY, ttl))
+        {
+            return Ok(apps);
+        }
+    }
+
+    let apps = client.list_apps(100)?.data;
+    if let Some(cache) = cache {
+        // A cache write failure only costs a refetch next time
+        let _ = cache.put(APPS_CACHE_KEY, &apps);
+    }
+    Ok(apps)
+}
+
+/// Cache key for the workflows seen in each app's builds
+pub const WORKFLOWS_CACHE_KEY: &str = "workflows";
+
+/// Remember the workflows of listed builds, so `--workflow` can be completed
+/// without a request
+pub fn remember_workflows(config: &Config, app_slug: &str, builds: &[Build]) {
+    let Ok(cache) = Cache::for_config(&config.cache) else {
+        return;
+    };
+    let mut known: HashMap<String, Vec<String>> =
+        cache.read(WORKFLOWS_CACHE_KEY).map(|entry| entry.value).unwrap_or_default();
+    let workflows = known.entry(app_slug.to_string()).or_default();
+    let before = workflows.len();
+    for build in builds.iter().filter(|b| !b.triggered_workflow.is_empty()) {
+        if !workflows.contains(&build.triggered_workflow) {
+            workflows.push(build.triggered_workflow.clone());
+        }
+    }
+    if workflows.len() != before {
+        workflows.sort();
+        // Completion works without it, so a failed write isn't worth a warning
+        let _ = cache.put(WORKFLOWS_CACHE_KEY, &known);
+    }
+}
+
+/// Resolve an `--app` value to an app slug, falling back to the default app.
+///
+/// Used by every command that takes `--app`. The value is tried, in order, as:
+/// 1. a configured alias
+/// 2. the slug of an app in the cached app list
+/// 3. an app name in the cached list (exact, then partial; case-insensitive)
+/// 4. a slug or name in a freshly fetched app list
+///
+/// A name matching several apps is an error listing them. A value matching
+/// nothing is used as a slug as-is, so apps missing from the list (or an
+/// unreachable API) still work.
+///
+/// # Example
+/// ```ignore
+/// // With an app titled "Ignite iOS" (slug abc123def456) on the account:
+/// let app_slug = resolve_app(client, Some("ignite"), config)?;
+/// // Returns "abc123def456"
+/// ```
+pub fn resolve_app(
+    client: &BitriseClient,
+    app_arg: Option<&str>,
+    config: &Config,
+) -> Result<String> {
+    let Some(input) = app_arg else {
+        return config.require_default_app().map(str::to_string);
+    };
+    if let Some(slug) = config.get_alias(input) {
+        return Ok(slug.to_string());
+    }
+
+    let ttl = config.cache.ttl.map_or(APPS_CACHE_TTL, Duration::from_secs);
+    let cache = Cache::for_config(&config.cache).ok();
+    let cached = cache.as_ref().and_then(|c| c.get::<Vec<App>>(APPS_CACHE_KEY, ttl));
+    if let Some(apps) = cached {
+        if let Some(slug) = match_app(&apps, input)? {
+            return Ok(slug);
+        }
+    }
+
+    match load_apps(client, cache.as_ref(), ttl, true) {
+        Ok(apps) => Ok(match_app(&apps, input)?.unwrap_or_else(|| input.to_string())),
+        Err(_) => Ok(input.to_string()),
+    }
+}
+
+/// Find an app by slug, then by name; `None` when nothing matches
+fn match_app(apps: &[App], input: &str) -> Result<Option<String>> {
+    if apps.iter().any(|app| app.slug == input) {
+        return Ok(Some(input.to_string()));
+    }
+
+    let needle = input.to_lowercase();
+    let exact: Vec<&App> = apps.iter().filter(|app| app.title.to_lowercase() == needle).collect();
+    let matches = if exact.is_empty() {
+        apps.iter().filter(|app| app.title.to_lowercase().contains(&needle)).collect()
+    } else {
+        exact
+    };
+
+    match matches.as_slice() {
+        [] => Ok(None),
+        [app] => Ok(Some(app.slug.clone())),
+        several => {
+            let names: Vec<String> =
+                several.iter().map(|app| format!("{} ({})", app.title, app.slug)).collect();
+            Err(RepriseError::InvalidArgument(format!(
+                "'{}' matches {} apps: {}. Use the app slug or an alias instead.",
+                input,
+                several.len(),
+                names.join(", ")
+            )))
+        }
+    }
+}
+
+/// Detect the app slug for the current git repository.
+///
+/// Like [`resolve_app`], but when `--app` is not given it first looks
+/// for a Bitrise app whose repository matches the `origin` remote of the
+/// current directory, falling back to the configured default app.
+///
+/// # Arguments
+/// * `client` - Bitrise API client (used to list apps when matching remotes)
+/// * `app_arg` - Optional app slug or alias from command line argument
+/// * `config` - Application configuration
+///
+/// # Returns
+/// - The resolved app slug from args (after alias lookup) if provided
+/// - The slug of the app whose `repo_url` matches `origin`, if any
+/// - The default app slug from config otherwise
+pub fn detect_app_slug(
+    client: &BitriseClient,
+    app_arg: Option<&str>,
+    config: &Config,
+) -> Result<String> {
+    if app_arg.is_some() {
+        return resolve_app(client, app_arg, config);
+    }
+
+    if let Some(remote) = git::origin_url().and_then(|u| git::normalize_repo_url(&u)) {
+        // Best effort: if listing apps fails, fall back to the default app
+        if let Ok(apps) = client.list_apps(100) {
+            let matched = apps.data.into_iter().find(|app| {
+                app.repo_url
+                    .as_deref()
+                    .and_then(git::normalize_repo_url)
+                    .is_some_and(|url| url == remote)
+            });
+            if let Some(app) = matched {
+                return Ok(app.slug);
+            }
+        }
+    }
+
+    config.require_default_app().map(str::to_string)
+}
+
+/// Maximum pages (of 50 builds) to walk when searching build history
+pub const MAX_SEARCH_PAGES: usize = 10;
+
+/// Whether to print progress and hints on stderr ("Downloading...",
+/// "Watching..."): pretty output without `--quiet`
+///
+/// Final results and errors are printed either way.
+pub fn show_progress(format: OutputFormat) -> bool {
+    format == OutputFormat::Pretty && !terminal::quiet()
+}
+
+/// Ask a yes/no question on stderr; only "y" confirms
+///
+/// Without anyone to answer (`--non-interactive`, `CI` set, or stdin not a
+/// terminal) this fails instead of waiting on stdin, naming `confirm_flag`
+/// (e.g. `--yes`) as the way to confirm up front.
+pub fn confirm(question: &str, confirm_flag: &str) -> Result<bool> {
+    let reason = if !terminal::interactive() {
+        Some("reprise is running non-interactively (--non-interactive or CI)")
+    } else if !terminal::stdin_is_tty() {
+        Some("stdin is not a terminal")
+    } else {
+        None
+    };
+    if let Some(reason) = reason {
+        return Err(RepriseError::InvalidArgument(format!(
+            "{} needs confirmation, but {}; pass {} to confirm",
+            question.trim_end_matches('?'),
+            reason,
+            confirm_flag
+        )));
+    }
+
+    eprint!("{} {} [y/N] ", "?".yellow(), question);
+    io::stderr().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().eq_ignore_ascii_case("y"))
+}
+
+/// Warn that a `--limit` above the API's page size is fetched page by page
+pub fn warn_paged_limit(limit: u32) {
+    if let Some(message) = paged_limit_warning(limit) {
+        eprintln!("warning: {}", message);
+    }
+}
+
+fn paged_limit_warning(limit: u32) -> Option<String> {
+    (limit > MAX_PAGE_SIZE).then(|| {
+        format!(
+            "--limit {} is above the API's maximum of {} per page; fetching {} pages",
+            limit,
+            MAX_PAGE_SIZE,
+            limit.div_ceil(MAX_PAGE_SIZE)
+        )
+    })
+}
+
+/// Search build history for builds matching a predicate.
+///
+/// Used for filters the Bitrise API doesn't support server-side (commit,
+/// pull request). Walks up to [`MAX_SEARCH_PAGES`] pages, newest first,
+/// and stops early once `limit` matches are found.
+///
+/// # Arguments
+/// * `client` - Bitrise API client
+/// * `app_slug` - App to search
+/// * `status` / `branch` / `workflow` - Server-side filters to narrow the search
+/// * `limit` - Stop after this many matches
+/// * `predicate` - Client-side filter
+pub fn search_builds<F>(
+    client: &BitriseClient,
+    app_slug: &str,
+    status: Option<BuildStatus>,
+    branch: Option<&str>,
+    workflow: Option<&str>,
+    limit: usize,
+    predicate: F,
+) -> Result<Vec<Build>>
+where
+    F: FnMut(&Build) -> bool,
+{
+    search_build_pages(client, app_slug, status, branch, workflow, limit, None, predicate)
+}
+
+/// Search build history for builds inside a `--since`/`--until` window.
+///
+/// Like [`search_builds`], but only keeps builds triggered inside the window
+/// and stops paging once builds are older than its start.
+#[allow(clippy::too_many_arguments)]
+pub fn search_builds_in_window<F>(
+    client: &BitriseClient,
+    app_slug: &str,
+    status: Option<BuildStatus>,
+    branch: Option<&str>,
+    workflow: Option<&str>,
+    limit: usize,
+    window: &TimeWindow,
+    predicate: F,
+) -> Result<Vec<Build>>
+where
+    F: FnMut(&Build) -> bool,
+{
+    search_build_pages(client, app_slug, status, branch, workflow, limit, Some(window), predicate)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn search_build_pages<F>(
+    client: &BitriseClient,
+    app_slug: &str,
+    status: Option<BuildStatus>,
+    branch: Option<&str>,
+    workflow: Option<&str>,
+    limit: usize,
+    window: Option<&TimeWindow>,
+    mut predicate: F,
+) -> Result<Vec<Build>>
+where
+    F: FnMut(&Build) -> bool,
+{
+    let mut matches = Vec::new();
+    let mut next: Option<String> = None;
+
+    for _ in 0..MAX_SEARCH_PAGES {
+        let page = client.list_builds_page(
+            app_slug,
+            status,
+            branch,
+            workflow,
+            MAX_PAGE_SIZE,
+            next.as_deref(),
+        )?;
+
+        // Newest first: once a build predates the window, the rest do too
+        let past_window = page
+            .data
+            .last()
+            .is_some_and(|b| window.is_some_and(|w| w.is_before(b.triggered_at)));
+
+        matches.extend(page.data.into_iter().filter(|b| {
+            window.is_none_or(|w| w.contains(b.triggered_at)) && predicate(b)
+        }));
+
+        next = page.paging.next;
+        if matches.len() >= limit || next.is_none() || past_window {
+            break;
+        }
+    }
+
+    matches.truncate(limit);
+    Ok(matches)
+}
+
+/// Every build matching the server-side filters, paging through the full
+/// history or, with a `window`, until builds predate it.
+///
+/// Unlike [`search_builds`] there is no page cap, so this suits exports and
+/// reports that need the whole period. `progress` gets the running count
+/// after each page.
+pub fn build_history(
+    client: &BitriseClient,
+    app_slug: &str,
+    status: Option<BuildStatus>,
+    branch: Option<&str>,
+    workflow: Option<&str>,
+    window: Option<&TimeWindow>,
+    mut progress: impl FnMut(usize),
+) -> Result<Vec<Build>> {
+    let mut builds = Vec::new();
+    let mut next: Option<String> = None;
+    loop {
+        let page = client.list_builds_page(
+            app_slug,
+            status,
+            branch,
+            workflow,
+            MAX_PAGE_SIZE,
+            next.as_deref(),
+        )?;
+
+        // Newest first: once a build predates the window, the rest do too
+        let past_window = page
+            .data
+            .last()
+            .is_some_and(|b| window.is_some_and(|w| w.is_before(b.triggered_at)));
+        builds.extend(
+            page.data
+                .into_iter()
+                .filter(|b| window.is_none_or(|w| w.contains(b.triggered_at))),
+        );
+        progress(builds.len());
+
+        next = page.paging.next;
+        if next.is_none() || past_window {
+            return Ok(builds);
+        }
+    }
+}
+
+/// Search pipeline history for pipelines inside a time window.
+///
+/// Pipelines without a trigger time are skipped. Paging stops after
+/// [`MAX_SEARCH_PAGES`] pages, `limit` matches, or once pipelines are older
+/// than the window.
+pub fn search_pipelines_in_window<F>(
+    client: &BitriseClient,
+    app_slug: &str,
+    branch: Option<&str>,
+    limit: usize,
+    window: &TimeWindow,
+    mut predicate: F,
+) -> Result<Vec<Pipeline>>
+where
+    F: FnMut(&Pipeline) -> bool,
+{
+    let mut matches = Vec::new();
+    let mut next: Option<String> = None;
+
+    for _ in 0..MAX_SEARCH_PAGES {
+        let page =
+            client.list_pipelines_page(app_slug, None, branch, MAX_PAGE_SIZE, next.as_deref())?;
+
+        let past_window = page
+            .data
+            .last()
+            .and_then(|p| p.triggered_at)
+            .is_some_and(|at| window.is_before(at));
+
+        matches.extend(page.data.into_iter().filter(|p| {
+            p.triggered_at.is_some_and(|at| window.contains(at)) && predicate(p)
+        }));
+
+        next = page.paging.next;
+        if matches.len() >= limit || next.is_none() || past_window {
+            break;
+        }
+    }
+
+    matches.truncate(limit);
+    Ok(matches)
+}
+
 /// Set up a Ctrl+C interrupt handler for graceful cancellation.
 ///
 /// Creates an atomic boolean that will be set to `true` when the user
@@ -172,6 +648,73 @@ pub fn is_interrupted(interrupted: &AtomicBool) -> bool {
     interrupted.load(Ordering::SeqCst)
 }
 
+/// Status line text for a build being watched, e.g. `Build #42 running · primary`
+pub fn build_progress_message(build: &Build) -> String {
+    format!(
+        "Build #{} {} · {}",
+        build.build_number,
+        build.status_display(),
+        build.triggered_workflow
+    )
+}
+
+/// Pretty line for a build or pipeline that stopped running, e.g.
+/// `✓ Build completed successfully`
+pub fn finished_message(what: &str, status: BuildStatus) -> String {
+    match status {
+        BuildStatus::Success => format!("{} {} completed successfully", theme::icon(status), what),
+        BuildStatus::Failed => format!("{} {} failed", theme::icon(status), what),
+        BuildStatus::Aborted | BuildStatus::AbortedWithSuccess => {
+            format!("{} {} aborted", theme::icon(status), what)
+        }
+        BuildStatus::Running | BuildStatus::Unknown(_) => {
+            format!("{} {} finished", "->".cyan(), what)
+        }
+    }
+}
+
+/// Open a build's full log as a stream, so large logs never have to be held
+/// in memory at once.
+///
+/// Finished builds' logs are read from the local log cache (see
+/// [`LogCache`]), or streamed into it on first download and read back.
+pub fn open_full_log(
+    client: &BitriseClient,
+    config: &Config,
+    app_slug: &str,
+    build_slug: &str,
+) -> Result<Box<dyn BufRead>> {
+    let log_cache = LogCache::for_config(&config.cache);
+    if let Some(file) = log_cache.as_ref().and_then(|c| c.open(build_slug)) {
+        return Ok(Box::new(BufReader::new(file)));
+    }
+
+    let response = client.get_build_log(app_slug, build_slug)?;
+    let mut log = client.open_log(&response)?;
+
+    // Archived logs belong to finished builds and won't change. An unusable
+    // cache directory just means streaming straight from the download.
+    match log_cache {
+        Some(log_cache) if response.is_archived && fs::create_dir_all(log_cache.dir()).is_ok() => {
+            let file = log_cache.store(build_slug, &mut log)?;
+            Ok(Box::new(BufReader::new(file)))
+        }
+        _ => Ok(Box::new(BufReader::new(log))),
+    }
+}
+
+/// Fetch a build's full log into memory (see [`open_full_log`])
+pub fn fetch_full_log(
+    client: &BitriseClient,
+    config: &Config,
+    app_slug: &str,
+    build_slug: &str,
+) -> Result<String> {
+    let mut bytes = Vec::new();
+    open_full_log(client, config, app_slug, build_slug)?.read_to_end(&mut bytes)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -294,4 +837,69 @@ mod tests {
         interrupted.store(true, Ordering::SeqCst);
         assert!(is_interrupted(&interrupted));
     }
+
+    #[test]
+    fn test_build_progress_message() {
+        let build: Build = serde_json::from_str(
+            r#"{"slug": "b1", "build_number": 42, "status": 0, "status_text": "in-progress",
+                "triggered_at": "2024-01-01T12:00:00Z", "branch": "main",
+                "triggered_workflow": "primary"}"#,
+        )
+        .unwrap();
+        assert_eq!(build_progress_message(&build), "Build #42 running · primary");
+    }
+
+    #[test]
+    fn test_paged_limit_warning_above_page_size() {
+        assert_eq!(paged_limit_warning(50), None);
+        let warning = paged_limit_warning(120).unwrap();
+        assert!(warning.contains("--limit 120"));
+        assert!(warning.ends_with("fetching 3 pages"));
+    }
+
+    #[test]
+    fn test_finished_message_treats_aborted_with_success_as_aborted() {
+        let message = finished_message("Build", BuildStatus::AbortedWithSuccess);
+        assert!(message.ends_with("Build aborted"));
+        let message = finished_message("Pipeline", BuildStatus::Success);
+        assert!(message.ends_with("Pipeline completed successfully"));
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // match_app Tests
+    // ─────────────────────────────────────────────────────────────────────────
+
+    fn app(slug: &str, title: &str) -> App {
+        serde_json::from_value(serde_json::json!({
+            "slug": slug,
+            "title": title,
+            "is_disabled": false,
+            "status": 1,
+            "owner": {"account_type": "organization", "name": "Team", "slug": "team"}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_match_app_by_slug_and_name() {
+        let apps = vec![app("ios-slug", "Ignite iOS"), app("android-slug", "Ignite Android")];
+        assert_eq!(match_app(&apps, "ios-slug").unwrap().as_deref(), Some("ios-slug"));
+        assert_eq!(match_app(&apps, "ignite ios").unwrap().as_deref(), Some("ios-slug"));
+        assert_eq!(match_app(&apps, "andr").unwrap().as_deref(), Some("android-slug"));
+        assert_eq!(match_app(&apps, "web").unwrap(), None);
+    }
+
+    #[test]
+    fn test_match_app_exact_name_beats_partial() {
+        let apps = vec![app("a", "Ignite"), app("b", "Ignite Android")];
+        assert_eq!(match_app(&apps, "ignite").unwrap().as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn test_match_app_ambiguous_name() {
+        let apps = vec![app("ios-slug", "Ignite iOS"), app("android-slug", "Ignite Android")];
+        let err = match_app(&apps, "ignite").unwrap_err().to_string();
+        assert!(err.contains("matches 2 apps"));
+        assert!(err.contains("Ignite iOS (ios-slug)"));
+    }
 }
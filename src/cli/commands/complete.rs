@@ -0,0 +1,109 @@
+//! Hidden __complete command (candidates for the shell completion scripts)
+//!
+//! Runs on every Tab press, so it only reads the config, the cache and
+//! ./bitrise.yml: no requests, and nothing printed but the candidates.
+
+use std::collections::HashMap;
+
+use clap_complete::Shell;
+
+use super::common::{APPS_CACHE_KEY, WORKFLOWS_CACHE_KEY};
+use crate::bitrise::{yml, App};
+use crate::cache::Cache;
+use crate::cli::args::{CompleteArgs, CompletionKind};
+use crate::config::Config;
+
+/// A completion value and what it stands for
+type Candidate = (String, String);
+
+/// Handle the __complete command
+///
+/// A broken config or cache leaves the list short rather than failing.
+pub fn complete(args: &CompleteArgs) -> String {
+    let config = Config::load().unwrap_or_default();
+    let cache = Cache::for_config(&config.cache).ok();
+    let candidates = match args.kind {
+        CompletionKind::AppSlugs => app_candidates(&config, cache.as_ref()),
+        CompletionKind::Aliases => alias_candidates(&config),
+        CompletionKind::Workflows => workflow_candidates(&config, cache.as_ref()),
+    };
+    format_candidates(&candidates, args.shell)
+}
+
+fn alias_candidates(config: &Config) -> Vec<Candidate> {
+    let mut aliases: Vec<Candidate> = config
+        .aliases
+        .iter()
+        .map(|(name, slug)| (name.clone(), slug.clone()))
+        .collect();
+    aliases.sort();
+    aliases
+}
+
+/// Aliases, then the cached app list, however old
+fn app_candidates(config: &Config, cache: Option<&Cache>) -> Vec<Candidate> {
+    let mut candidates = alias_candidates(config);
+    let apps: Vec<App> = cache
+        .and_then(|c| c.read(APPS_CACHE_KEY))
+        .map(|entry| entry.value)
+        .unwrap_or_default();
+    candidates.extend(apps.into_iter().map(|app| (app.slug, app.title)));
+    candidates
+}
+
+/// Workflows in ./bitrise.yml, then those seen in the default app's builds
+/// (every app's, without a default app)
+fn workflow_candidates(config: &Config, cache: Option<&Cache>) -> Vec<Candidate> {
+    let mut candidates: Vec<Candidate> = Vec::new();
+    let local = std::fs::read_to_string("bitrise.yml")
+        .ok()
+        .and_then(|text| yml::parse(&text).ok());
+    if let Some(workflows) = local.as_ref().and_then(|root| root.get("workflows")) {
+        for (name, _) in workflows.entries() {
+            candidates.push((name.clone(), "bitrise.yml".to_string()));
+        }
+    }
+    let seen: HashMap<String, Vec<String>> = cache
+        .and_then(|c| c.read(WORKFLOWS_CACHE_KEY))
+        .map(|entry| entry.value)
+        .unwrap_or_default();
+    let recent: Vec<&String> = match config.defaults.app_slug {
+        Some(ref slug) => seen.get(slug).into_iter().flatten().collect(),
+        None => seen.values().flatten().collect(),
+    };
+    for workflow in recent {
+        if !candidates.iter().any(|(name, _)| name == workflow) {
+            candidates.push((workflow.clone(), "recent builds".to_string()));
+        }
+    }
+    candidates
+}
+
+/// One candidate per line, as each shell's script reads them
+///
+/// bash takes bare values; zsh's `_describe` takes `value:description`;
+/// fish (and a human) gets `value<TAB>description`.
+fn format_candidates(candidates: &[Candidate], shell: Option<Shell>) -> String {
+    candidates
+        .iter()
+        .map(|(value, description)| match shell {
+            Some(Shell::Bash) => format!("{}\n", value),
+            Some(Shell::Zsh) => format!("{}:{}\n", value.replace(':', "\\:"), description),
+            _ => format!("{}\t{}\n", value, description),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_candidates() {
+        let candidates = vec![("ios:beta".to_string(), "abc123".to_string())];
+        assert_eq!(format_candidates(&candidates, Some(Shell::Bash)), "ios:beta\n");
+        assert_eq!(format_candidates(&candidates, Some(Shell::Zsh)), "ios\\:beta:abc123\n");
+        assert_eq!(format_candidates(&candidates, Some(Shell::Fish)), "ios:beta\tabc123\n");
+        assert_eq!(format_candidates(&[], None), "");
+    }
+}
@@ -1,11 +1,19 @@
+use std::collections::HashMap;
 use std::io::{self, Write};
 
 use colored::Colorize;
 use rpassword::read_password;
 
-use crate::cli::args::{ConfigArgs, ConfigCommands, OutputFormat};
-use crate::config::{Config, Paths};
+use crate::bitrise::{App, BitriseClient, User};
+use crate::cli::args::{
+    AliasCommands, AliasImportStrategy, ConfigArgs, ConfigCommands, OutputFormat,
+};
+use crate::config::lint::{self, LintIssue, Severity};
+use crate::config::undo::{self, KeyChange};
+use crate::config::{canonical_key, Config, Paths, ENV_OVERRIDES};
 use crate::error::{RepriseError, Result};
+use crate::output::json;
+use crate::output::terminal;
 
 /// Safely truncate a string to show first and last n characters
 /// Works correctly with multi-byte UTF-8 characters
@@ -31,14 +39,34 @@ pub fn config(
         ConfigCommands::Set { key, value } => config_set(config, key, value, format),
         ConfigCommands::Path => config_path(format),
         ConfigCommands::Init => config_init(config, format),
-        ConfigCommands::Alias { name, slug, remove } => {
+        ConfigCommands::Lint => config_lint(format),
+        ConfigCommands::Export { no_secrets } => config_export(*no_secrets),
+        ConfigCommands::Import { file, with_hooks } => {
+            config_import(config, file, *with_hooks, format)
+        }
+        ConfigCommands::Alias { command: Some(AliasCommands::Export { file }), .. } => {
+            alias_export(config, file.as_deref(), format)
+        }
+        ConfigCommands::Alias { command: Some(AliasCommands::Import { file, strategy }), .. } => {
+            alias_import(config, file, *strategy, format)
+        }
+        ConfigCommands::Alias { command: None, name, slug, remove } => {
             config_alias(config, name.as_deref(), slug.as_deref(), *remove, format)
         }
+        ConfigCommands::Undo => config_undo(format),
     }
 }
 
 /// Show current configuration
 fn config_show(config: &Config, format: OutputFormat) -> Result<String> {
+    // Marks values that come from a REPRISE_* environment variable
+    let source = |key: &str| -> String {
+        config
+            .env_override(key)
+            .map(|var| format!(" {}", format!("(from {})", var).dimmed()))
+            .unwrap_or_default()
+    };
+
     match format {
         OutputFormat::Pretty => {
             let mut output = String::new();
@@ -54,30 +82,171 @@ fn config_show(config: &Config, format: OutputFormat) -> Result<String> {
                 .as_ref()
                 .map(|t| mask_token(t, 4))
                 .unwrap_or_else(|| "(not set)".dimmed().to_string());
-            output.push_str(&format!("  token = {}\n", token_display));
+            output.push_str(&format!("  token = {}{}\n", token_display, source("api.token")));
 
             // Defaults section
             output.push_str(&format!("\n{}\n", "[defaults]".cyan()));
             output.push_str(&format!(
-                "  app_slug = {}\n",
+                "  app_slug = {}{}\n",
                 config
                     .defaults
                     .app_slug
                     .as_deref()
-                    .unwrap_or("(not set)")
+                    .unwrap_or("(not set)"),
+                source("defaults.app_slug")
             ));
             output.push_str(&format!(
-                "  app_name = {}\n",
+                "  app_name = {}{}\n",
                 config
                     .defaults
                     .app_name
                     .as_deref()
-                    .unwrap_or("(not set)")
+                    .unwrap_or("(not set)"),
+                source("defaults.app_name")
             ));
 
             // Output section
             output.push_str(&format!("\n{}\n", "[output]".cyan()));
-            output.push_str(&format!("  format = {}\n", config.output.format));
+            output.push_str(&format!(
+                "  format = {}{}\n",
+                config.output.format,
+                source("output.format")
+            ));
+            output.push_str(&format!(
+                "  pager = {}{}\n",
+                config.output.pager.as_deref().unwrap_or("(not set)"),
+                source("output.pager")
+            ));
+
+            // Cache section
+            output.push_str(&format!("\n{}\n", "[cache]".cyan()));
+            output.push_str(&format!(
+                "  ttl = {}{}\n",
+                config
+                    .cache
+                    .ttl
+                    .map(|secs| format!("{}s", secs))
+                    .unwrap_or_else(|| "(not set)".to_string()),
+                source("cache.ttl")
+            ));
+            output.push_str(&format!(
+                "  dir = {}{}\n",
+                config.cache.dir.as_deref().unwrap_or("(default)"),
+                source("cache.dir")
+            ));
+            output.push_str(&format!(
+                "  log_limit_mb = {}{}\n",
+                config
+                    .cache
+                    .log_limit_mb
+                    .map(|mb| format!("{} MB", mb))
+                    .unwrap_or_else(|| "(not set)".to_string()),
+                source("cache.log_limit_mb")
+            ));
+
+            // Notify section
+            output.push_str(&format!("\n{}\n", "[notify]".cyan()));
+            output.push_str(&format!(
+                "  min_interval = {}{}\n",
+                config
+                    .notify
+                    .min_interval
+                    .map(|secs| format!("{}s", secs))
+                    .unwrap_or_else(|| "(not set)".to_string()),
+                source("notify.min_interval")
+            ));
+            output.push_str(&format!(
+                "  quiet_hours = {}{}\n",
+                config.notify.quiet_hours.as_deref().unwrap_or("(not set)"),
+                source("notify.quiet_hours")
+            ));
+            output.push_str(&format!(
+                "  webhook_url = {}{}\n",
+                if config.notify.webhook_url.is_some() { "(set)" } else { "(not set)" },
+                source("notify.webhook_url")
+            ));
+            output.push_str(&format!(
+                "  ntfy_topic = {}{}\n",
+                if config.notify.ntfy_topic.is_some() { "(set)" } else { "(not set)" },
+                source("notify.ntfy_topic")
+            ));
+            output.push_str(&format!(
+                "  ntfy_server = {}{}\n",
+                config.notify.ntfy_server.as_deref().unwrap_or(crate::ntfy::DEFAULT_SERVER),
+                source("notify.ntfy_server")
+            ));
+
+            // Hooks section
+            output.push_str(&format!("\n{}\n", "[hooks]".cyan()));
+            let hooks = [
+                ("on_build_success", &config.hooks.on_build_success),
+                ("on_build_failure", &config.hooks.on_build_failure),
+                ("on_build_abort", &config.hooks.on_build_abort),
+                ("on_build_finish", &config.hooks.on_build_finish),
+            ];
+            for (name, command) in hooks {
+                output.push_str(&format!(
+                    "  {} = {}{}\n",
+                    name,
+                    command.as_deref().unwrap_or("(not set)"),
+                    source(&format!("hooks.{}", name))
+                ));
+            }
+
+            // Daemon section
+            output.push_str(&format!("\n{}\n", "[daemon]".cyan()));
+            output.push_str(&format!(
+                "  targets = {}\n",
+                if config.daemon.targets.is_empty() {
+                    "(not set)".to_string()
+                } else {
+                    config.daemon.targets.join(", ")
+                }
+            ));
+            output.push_str(&format!(
+                "  interval = {}{}\n",
+                config
+                    .daemon
+                    .interval
+                    .map(|secs| format!("{}s", secs))
+                    .unwrap_or_else(|| "(default)".to_string()),
+                source("daemon.interval")
+            ));
+            output.push_str(&format!(
+                "  slack_webhook = {}{}\n",
+                if config.daemon.slack_webhook.is_some() { "(set)" } else { "(not set)" },
+                source("daemon.slack_webhook")
+            ));
+
+            // Theme section
+            output.push_str(&format!("\n{}\n", "[theme]".cyan()));
+            let colors = [
+                ("running", &config.theme.running),
+                ("success", &config.theme.success),
+                ("failed", &config.theme.failed),
+                ("aborted", &config.theme.aborted),
+            ];
+            for (name, color) in colors {
+                output.push_str(&format!(
+                    "  {} = {}{}\n",
+                    name,
+                    color.as_deref().unwrap_or("(default)"),
+                    source(&format!("theme.{}", name))
+                ));
+            }
+            output.push_str(&format!(
+                "  ascii = {}{}\n",
+                config.theme.ascii,
+                source("theme.ascii")
+            ));
+
+            // Update section
+            output.push_str(&format!("\n{}\n", "[update]".cyan()));
+            output.push_str(&format!(
+                "  check = {}{}\n",
+                config.update.check,
+                source("update.check")
+            ));
 
             // Aliases section (if any exist)
             if !config.aliases.is_empty() {
@@ -97,42 +266,37 @@ fn config_show(config: &Config, format: OutputFormat) -> Result<String> {
             if let Some(ref token) = safe_config.api.token {
                 safe_config.api.token = Some(mask_token(token, 4));
             }
-            Ok(serde_json::to_string_pretty(&safe_config)?)
+            if safe_config.daemon.slack_webhook.is_some() {
+                safe_config.daemon.slack_webhook = Some("****".to_string());
+            }
+            if safe_config.notify.webhook_url.is_some() {
+                safe_config.notify.webhook_url = Some("****".to_string());
+            }
+            // Anyone who knows an ntfy topic can read its pushes
+            if safe_config.notify.ntfy_topic.is_some() {
+                safe_config.notify.ntfy_topic = Some("****".to_string());
+            }
+            json::format_json(&safe_config)
         }
     }
 }
 
 /// Set a configuration value
 fn config_set(config: &mut Config, key: &str, value: &str, format: OutputFormat) -> Result<String> {
-    match key {
-        "api.token" => {
-            config.set_token(value.to_string());
-            config.save()?;
-        }
-        "defaults.app_slug" => {
-            config.defaults.app_slug = Some(value.to_string());
-            config.save()?;
-        }
-        "defaults.app_name" => {
-            config.defaults.app_name = Some(value.to_string());
-            config.save()?;
-        }
-        "output.format" => {
-            if value != "pretty" && value != "json" {
-                return Err(RepriseError::InvalidArgument(
-                    "output.format must be 'pretty' or 'json'".to_string(),
-                ));
-            }
-            config.output.format = value.to_string();
-            config.save()?;
-        }
-        _ => {
-            return Err(RepriseError::InvalidArgument(format!(
-                "Unknown config key: {}. Valid keys: api.token, defaults.app_slug, defaults.app_name, output.format",
-                key
-            )));
-        }
+    let key = canonical_key(key);
+    if !ENV_OVERRIDES.iter().any(|(_, k)| *k == key) {
+        let keys: Vec<&str> = ENV_OVERRIDES.iter().map(|(_, k)| *k).collect();
+        return Err(RepriseError::InvalidArgument(format!(
+            "Unknown config key: {}. Valid keys: {}",
+            key,
+            keys.join(", ")
+        )));
+    }
+    if key == "api.token" {
+        return config_set_token(config, value, format);
     }
+    config.set(key, value)?;
+    config.save()?;
 
     match format {
         OutputFormat::Pretty => Ok(format!("{} Set {} = {}", "✓".green(), key, value)),
@@ -142,7 +306,64 @@ fn config_set(config: &mut Config, key: &str, value: &str, format: OutputFormat)
                 "key": key,
                 "value": value
             });
-            Ok(serde_json::to_string_pretty(&result)?)
+            json::format_json(&result)
+        }
+    }
+}
+
+/// Set the API token after checking it with Bitrise
+///
+/// A rejected token is never saved. If Bitrise can't be reached the token is
+/// saved anyway, with a warning, so setup works offline.
+fn config_set_token(config: &mut Config, token: &str, format: OutputFormat) -> Result<String> {
+    let token = token.trim();
+    if token.is_empty() || token.chars().any(char::is_whitespace) {
+        return Err(RepriseError::InvalidArgument(
+            "api.token must be a single word without spaces".to_string(),
+        ));
+    }
+
+    let account = match verify_token(token) {
+        Ok((client, user)) => {
+            // Organizations are informational only
+            let orgs: Vec<String> = client
+                .list_organizations()
+                .map(|r| r.data.into_iter().map(|o| o.name).collect())
+                .unwrap_or_default();
+            Some((user, orgs))
+        }
+        Err(e @ RepriseError::Config(_)) => return Err(e),
+        Err(e) => {
+            eprintln!("{} Couldn't verify token: {}", "!".yellow(), e);
+            None
+        }
+    };
+
+    config.set("api.token", token)?;
+    config.save()?;
+
+    let masked = mask_token(token, 4);
+    match format {
+        OutputFormat::Pretty => {
+            let mut output = format!("{} Set api.token = {}", "✓".green(), masked);
+            if let Some((ref user, ref orgs)) = account {
+                output.push_str(&format!("\n  Account:       {}", user.username.bold()));
+                if !orgs.is_empty() {
+                    output.push_str(&format!("\n  Organizations: {}", orgs.join(", ")));
+                }
+            }
+            Ok(output)
+        }
+        OutputFormat::Json => {
+            let result = serde_json::json!({
+                "success": true,
+                "key": "api.token",
+                "value": masked,
+                "verified": account.is_some(),
+                "username": account.as_ref().map(|(u, _)| &u.username),
+                "organizations": account.as_ref().map(|(_, o)| o),
+            });
+            json::format_json(&result)
         }
     }
 }
@@ -170,7 +391,294 @@ fn config_path(format: OutputFormat) -> Result<String> {
                 "path": paths.config_file.display().to_string(),
                 "exists": paths.config_exists()
             });
-            Ok(serde_json::to_string_pretty(&result)?)
+            json::format_json(&result)
+        }
+    }
+}
+
+/// Format a lint issue as `line N: message`
+fn describe_issue(issue: &LintIssue) -> String {
+    match issue.line {
+        Some(line) => format!("line {}: {}", line, issue.message),
+        None => issue.message.clone(),
+    }
+}
+
+/// Check the config file and report problems
+///
+/// Works on the raw file, so it runs even when the config fails to parse.
+pub fn config_lint(format: OutputFormat) -> Result<String> {
+    let paths = Paths::new()?;
+    let issues = lint::lint_file(&paths)?;
+    let errors = issues.iter().filter(|i| i.severity == Severity::Error).count();
+    let report = lint_report(&paths.config_file.display().to_string(), &issues, format)?;
+
+    if errors > 0 {
+        println!("{}", report);
+        return Err(RepriseError::Config(format!(
+            "config file has {} error(s)",
+            errors
+        )));
+    }
+    Ok(report)
+}
+
+/// Render lint issues for the file at `path`, like `reprise config lint`
+pub(super) fn lint_report(
+    path: &str,
+    issues: &[LintIssue],
+    format: OutputFormat,
+) -> Result<String> {
+    let errors = issues.iter().filter(|i| i.severity == Severity::Error).count();
+    Ok(match format {
+        OutputFormat::Pretty => {
+            let mut output = String::new();
+            if issues.is_empty() {
+                output.push_str(&format!("{} {} looks good", "✓".green(), path));
+            } else {
+                output.push_str(&format!("{}\n", path.bold()));
+                for issue in issues {
+                    let label = match issue.severity {
+                        Severity::Error => "error".red().bold(),
+                        Severity::Warning => "warning".yellow().bold(),
+                    };
+                    output.push_str(&format!("  {}: {}\n", label, describe_issue(issue)));
+                }
+                output.push_str(&format!(
+                    "\n{} error(s), {} warning(s)",
+                    errors,
+                    issues.len() - errors
+                ));
+            }
+            output
+        }
+        OutputFormat::Json => {
+            let result = serde_json::json!({
+                "path": path,
+                "valid": errors == 0,
+                "issues": issues,
+            });
+            json::format_json(&result)?
+        }
+    })
+}
+
+/// Print config file problems to stderr before running a command
+///
+/// Parse failures are reported by `Config::load` itself; this covers what
+/// still parses, like misspelled keys, which would otherwise be ignored.
+pub fn warn_config_issues() {
+    let Ok(issues) = Paths::new().and_then(|paths| lint::lint_file(&paths)) else {
+        return;
+    };
+    if issues.is_empty() {
+        return;
+    }
+
+    for issue in &issues {
+        eprintln!("{}: config {}", "warning".yellow(), describe_issue(issue));
+    }
+    if !terminal::quiet() {
+        eprintln!("hint: run 'reprise config lint' for details\n");
+    }
+}
+
+/// Export the config file, optionally without the API token and webhooks
+fn config_export(no_secrets: bool) -> Result<String> {
+    // Export what's on disk, not values from REPRISE_* variables
+    let mut config = Config::load_from(&Paths::new()?)?;
+    if no_secrets {
+        config.api.token = None;
+        config.daemon.slack_webhook = None;
+        config.notify.webhook_url = None;
+        config.notify.ntfy_topic = None;
+    }
+
+    // Always TOML, whatever the output format, so `config import` reads it back
+    Ok(toml::to_string_pretty(&config)?.trim_end().to_string())
+}
+
+/// Revert the last journaled config change
+fn config_undo(format: OutputFormat) -> Result<String> {
+    let undone = undo::undo(&Paths::new()?)?;
+    if let Some(ref undone) = undone {
+        let reverted: Vec<String> =
+            shown(&undone.reverted).map(|change| change.reversed().describe()).collect();
+        crate::history::record_config("config undo", &reverted.join(", "));
+    }
+
+    match format {
+        OutputFormat::Pretty => {
+            let Some(undone) = undone else {
+                return Ok("Nothing to undo".dimmed().to_string());
+            };
+            let mut output = String::new();
+            if undone.reverted.is_empty() {
+                output.push_str(&format!("{} Nothing reverted\n", "!".yellow()));
+            } else {
+                output.push_str(&format!("{} Reverted the last config change\n", "✓".green()));
+            }
+            for change in shown(&undone.reverted) {
+                output.push_str(&format!("  {}\n", change.reversed().describe()));
+            }
+            for change in shown(&undone.skipped) {
+                output.push_str(&format!(
+                    "  {} {} (changed since, kept)\n",
+                    "skipped".yellow(),
+                    change.key()
+                ));
+            }
+            Ok(output.trim_end().to_string())
+        }
+        OutputFormat::Json => {
+            let result = match undone {
+                Some(undone) => serde_json::json!({
+                    "success": true,
+                    "reverted": redacted(&undone.reverted),
+                    "skipped": redacted(&undone.skipped),
+                }),
+                None => serde_json::json!({ "success": false, "reverted": [], "skipped": [] }),
+            };
+            json::format_json(&result)
+        }
+    }
+}
+
+/// Changes with secret values masked, for JSON output
+fn redacted(changes: &[KeyChange]) -> Vec<KeyChange> {
+    changes.iter().map(KeyChange::redacted).collect()
+}
+
+/// Changes worth listing, leaving out bookkeeping like the recent apps
+fn shown(changes: &[KeyChange]) -> impl Iterator<Item = &KeyChange> {
+    changes.iter().filter(|change| !change.is_bookkeeping())
+}
+
+/// Import settings from an exported config file
+fn config_import(
+    config: &mut Config,
+    file: &str,
+    with_hooks: bool,
+    format: OutputFormat,
+) -> Result<String> {
+    let contents = std::fs::read_to_string(file)?;
+
+    let errors: Vec<String> = lint::lint(&contents)
+        .iter()
+        .filter(|i| i.severity == Severity::Error)
+        .map(describe_issue)
+        .collect();
+    if !errors.is_empty() {
+        return Err(RepriseError::Config(format!(
+            "{} is not a valid config file:\n  {}",
+            file,
+            errors.join("\n  ")
+        )));
+    }
+
+    let sections = config.import_toml(&contents, with_hooks)?;
+    config.save()?;
+    let skipped_hooks = !with_hooks
+        && contents.parse::<toml::Table>().is_ok_and(|table| table.contains_key("hooks"));
+    if skipped_hooks {
+        eprintln!(
+            "{} Skipped [hooks] from {}: it runs shell commands. \
+             Re-run with --with-hooks to import it.",
+            "!".yellow(),
+            file
+        );
+    }
+
+    match format {
+        OutputFormat::Pretty => {
+            if sections.is_empty() {
+                return Ok(format!("No settings found in {}", file).dimmed().to_string());
+            }
+            Ok(format!(
+                "{} Imported [{}] from {}",
+                "✓".green(),
+                sections.join("], ["),
+                file
+            ))
+        }
+        OutputFormat::Json => {
+            let result = serde_json::json!({
+                "success": true,
+                "file": file,
+                "sections": sections,
+                "skipped": if skipped_hooks { vec!["hooks"] } else { vec![] },
+            });
+            json::format_json(&result)
+        }
+    }
+}
+
+/// Check a token against `/me`, returning a client for it and its account
+fn verify_token(token: &str) -> Result<(BitriseClient, User)> {
+    let client = BitriseClient::with_token(token)?;
+    match client.get_me() {
+        Ok(me) => Ok((client, me.data)),
+        Err(RepriseError::Api { status: 401 | 403, .. }) => Err(RepriseError::Config(
+            "Bitrise rejected this API token. Create a new one at https://app.bitrise.io/me/profile#/security"
+                .to_string(),
+        )),
+        Err(e) => Err(e),
+    }
+}
+
+/// Print a prompt and read one trimmed line from stdin
+fn prompt_line(prompt: &str) -> Result<String> {
+    print!("{}", prompt);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().to_string())
+}
+
+/// Let the user pick a default app from their account (Enter skips)
+fn choose_default_app(client: &BitriseClient) -> Result<Option<App>> {
+    let mut apps: Vec<App> = client
+        .list_apps(100)?
+        .data
+        .into_iter()
+        .filter(|a| !a.is_disabled)
+        .collect();
+    if apps.is_empty() {
+        println!("{}", "No apps found for this account.".dimmed());
+        return Ok(None);
+    }
+    apps.sort_by_key(|a| a.title.to_lowercase());
+
+    println!("\n{}", "Your apps:".bold());
+    for (i, app) in apps.iter().enumerate() {
+        println!(
+            "  {:>3}. {} {}",
+            i + 1,
+            app.title,
+            format!("({}, {})", app.owner.name, app.slug).dimmed()
+        );
+    }
+
+    loop {
+        let input = prompt_line(&format!("\nDefault app [1-{}, Enter to skip]: ", apps.len()))?;
+        if input.is_empty() {
+            return Ok(None);
+        }
+        match input.parse::<usize>() {
+            Ok(n) if (1..=apps.len()).contains(&n) => return Ok(Some(apps.swap_remove(n - 1))),
+            _ => println!("{} Enter a number from 1 to {}", "!".yellow(), apps.len()),
+        }
+    }
+}
+
+/// Ask for the preferred output format (Enter keeps the current one)
+fn choose_output_format(current: &str) -> Result<String> {
+    loop {
+        let input = prompt_line(&format!("Output format [pretty/json] ({}): ", current))?;
+        match input.to_lowercase().as_str() {
+            "" => return Ok(current.to_string()),
+            "pretty" | "json" => return Ok(input.to_lowercase()),
+            _ => println!("{} Enter 'pretty' or 'json'", "!".yellow()),
         }
     }
 }
@@ -182,6 +690,13 @@ fn config_init(config: &mut Config, format: OutputFormat) -> Result<String> {
             "config init requires interactive mode (--output pretty)".to_string(),
         ));
     }
+    if !terminal::interactive() || !terminal::stdin_is_tty() {
+        return Err(RepriseError::InvalidArgument(
+            "config init asks for input, but there is no one to answer (--non-interactive, \
+             CI, or stdin not a terminal); use 'reprise config set api.token <TOKEN>' instead"
+                .to_string(),
+        ));
+    }
 
     println!("{}", "Reprise Configuration".bold());
     println!("{}", "─".repeat(40));
@@ -203,16 +718,36 @@ fn config_init(config: &mut Config, format: OutputFormat) -> Result<String> {
         ));
     }
 
+    // Validate the token before asking anything else
+    let (client, user) = verify_token(&token)?;
+    println!("{} Authenticated as {}", "✓".green(), user.username.bold());
     config.set_token(token);
+
+    // A failed app listing shouldn't throw away a valid token
+    match choose_default_app(&client) {
+        Ok(Some(app)) => config.set_default_app(app.slug, Some(app.title)),
+        Ok(None) => {}
+        Err(e) => eprintln!("{} Couldn't list apps: {}", "!".yellow(), e),
+    }
+
+    let output_format = choose_output_format(&config.output.format)?;
+    config.output.format = output_format;
+
+    // Everything is written in one go at the end of the wizard
     config.save()?;
 
     let paths = Paths::new()?;
+    let next_step = if config.defaults.app_slug.is_some() {
+        "reprise builds"
+    } else {
+        "reprise apps"
+    };
 
     Ok(format!(
-        "\n{} Configuration saved to: {}\n\nRun '{}' to see your apps.",
+        "\n{} Configuration saved to: {}\n\nRun '{}' to get started.",
         "✓".green(),
         paths.config_file.display(),
-        "reprise apps".cyan()
+        next_step.cyan()
     ))
 }
 
@@ -230,7 +765,7 @@ fn config_alias(
             if config.aliases.is_empty() {
                 return match format {
                     OutputFormat::Pretty => Ok("No aliases configured.\n\nSet one with: reprise config alias <name> <slug>".dimmed().to_string()),
-                    OutputFormat::Json => Ok(serde_json::to_string_pretty(&config.aliases)?),
+                    OutputFormat::Json => json::format_json(&config.aliases),
                 };
             }
 
@@ -253,7 +788,7 @@ fn config_alias(
                     }
                     Ok(output)
                 }
-                OutputFormat::Json => Ok(serde_json::to_string_pretty(&config.aliases)?),
+                OutputFormat::Json => json::format_json(&config.aliases),
             }
         }
 
@@ -272,7 +807,7 @@ fn config_alias(
                             "name": alias_name,
                             "slug": alias_slug
                         });
-                        Ok(serde_json::to_string_pretty(&result)?)
+                        json::format_json(&result)
                     }
                 },
                 None => Err(RepriseError::Config(format!(
@@ -300,7 +835,7 @@ fn config_alias(
                                 "name": alias_name,
                                 "previous_slug": old_slug
                             });
-                            Ok(serde_json::to_string_pretty(&result)?)
+                            json::format_json(&result)
                         }
                     }
                 }
@@ -335,7 +870,7 @@ fn config_alias(
                         "name": alias_name,
                         "slug": alias_slug
                     });
-                    Ok(serde_json::to_string_pretty(&result)?)
+                    json::format_json(&result)
                 }
             }
         }
@@ -351,3 +886,103 @@ fn config_alias(
         )),
     }
 }
+
+/// Write aliases as an `[aliases]` TOML table to a file, or return it
+fn alias_export(config: &Config, file: Option<&str>, format: OutputFormat) -> Result<String> {
+    let mut table = toml::Table::new();
+    table.insert("aliases".to_string(), toml::Value::try_from(&config.aliases)?);
+    let contents = toml::to_string_pretty(&table)?;
+
+    let Some(file) = file else {
+        return match format {
+            OutputFormat::Pretty => Ok(contents.trim_end().to_string()),
+            OutputFormat::Json => json::format_json(&config.aliases),
+        };
+    };
+    std::fs::write(file, &contents)?;
+
+    match format {
+        OutputFormat::Pretty => Ok(format!(
+            "{} Exported {} alias{} to {}",
+            "✓".green(),
+            config.aliases.len(),
+            if config.aliases.len() == 1 { "" } else { "es" },
+            file
+        )),
+        OutputFormat::Json => {
+            let result = serde_json::json!({
+                "success": true,
+                "file": file,
+                "aliases": config.aliases.len(),
+            });
+            json::format_json(&result)
+        }
+    }
+}
+
+/// Import aliases from a file, merging with or replacing the current ones
+fn alias_import(
+    config: &mut Config,
+    file: &str,
+    strategy: AliasImportStrategy,
+    format: OutputFormat,
+) -> Result<String> {
+    let aliases = parse_alias_file(&std::fs::read_to_string(file)?)
+        .map_err(|e| RepriseError::Config(format!("{} is not a valid alias file: {}", file, e)))?;
+    let changes = config.import_aliases(aliases, strategy == AliasImportStrategy::Overwrite);
+    config.save()?;
+
+    match format {
+        OutputFormat::Pretty => {
+            let mut output = format!("{} Imported aliases from {}\n", "✓".green(), file);
+            let groups = [
+                ("Added", &changes.added),
+                ("Updated", &changes.updated),
+                ("Removed", &changes.removed),
+            ];
+            for (label, names) in groups.into_iter().filter(|(_, names)| !names.is_empty()) {
+                output.push_str(&format!("  {:<9}{}\n", format!("{}:", label), names.join(", ")));
+            }
+            if !changes.unchanged.is_empty() {
+                output.push_str(&format!(
+                    "  {}\n",
+                    format!("{} unchanged", changes.unchanged.len()).dimmed()
+                ));
+            }
+            Ok(output.trim_end().to_string())
+        }
+        OutputFormat::Json => {
+            let result = serde_json::json!({
+                "success": true,
+                "file": file,
+                "added": changes.added,
+                "updated": changes.updated,
+                "removed": changes.removed,
+                "unchanged": changes.unchanged,
+            });
+            json::format_json(&result)
+        }
+    }
+}
+
+/// Read aliases from an `[aliases]` table, or from top-level `name = "slug"`
+/// pairs when the file has no such table
+fn parse_alias_file(contents: &str) -> std::result::Result<HashMap<String, String>, String> {
+    let mut table: toml::Table =
+        contents.parse().map_err(|e: toml::de::Error| e.message().to_string())?;
+    let aliases = match table.remove("aliases") {
+        Some(toml::Value::Table(aliases)) => aliases,
+        Some(_) => return Err("[aliases] must be a table".to_string()),
+        None if table.values().any(|v| v.is_table()) => {
+            return Err("no [aliases] table found".to_string())
+        }
+        None => table,
+    };
+    aliases
+        .into_iter()
+        .map(|(name, slug)| match slug {
+            toml::Value::String(slug) => Ok((name, slug)),
+            other => Err(format!("alias '{}' must be an app slug string, found {}", name, other)),
+        })
+        .collect()
+}
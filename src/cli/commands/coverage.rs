@@ -0,0 +1,266 @@
+//! Coverage command (code coverage of a build, or its trend over time)
+
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::bitrise::{Artifact, BitriseClient, Build, BuildStatus};
+use crate::cli::args::{CoverageArgs, OutputFormat};
+use crate::config::Config;
+use crate::coverage::{self, Coverage, CoverageFormat};
+use crate::duration::TimeWindow;
+use crate::error::{RepriseError, Result};
+use crate::output::progress::StatusLine;
+use crate::output::table::{self, Table};
+use crate::output::{chart, json, pretty};
+
+use super::artifacts::{filter_artifacts, resolve_build_slug};
+use super::common::{build_history, resolve_app, show_progress};
+
+/// Cells of a full (100%) bar in the trend chart
+const BAR_WIDTH: usize = 30;
+
+/// One coverage report artifact of a build
+#[derive(Debug, Serialize)]
+struct Report {
+    artifact: String,
+    format: CoverageFormat,
+    #[serde(flatten)]
+    coverage: Coverage,
+}
+
+/// Coverage of one build in a trend
+#[derive(Debug, Serialize)]
+struct TrendPoint {
+    build_number: i64,
+    build_slug: String,
+    triggered_at: DateTime<Utc>,
+    branch: String,
+    #[serde(flatten)]
+    coverage: Coverage,
+    percent: Option<f64>,
+}
+
+/// Handle the coverage command
+pub fn coverage(
+    client: &BitriseClient,
+    config: &Config,
+    args: &CoverageArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    if args.trend {
+        return trend(client, app_slug, args, format);
+    }
+
+    let build_slug = resolve_build_slug(
+        client,
+        app_slug,
+        &args.slug,
+        args.branch.as_deref(),
+        args.workflow.as_deref(),
+        format,
+    )?;
+    let artifacts = client.list_artifacts(app_slug, &build_slug)?.data;
+    let reports = read_reports(client, app_slug, &build_slug, &artifacts, args)?;
+    if reports.is_empty() {
+        return Err(RepriseError::InvalidArgument(format!(
+            "No coverage reports in build {} ({} artifacts); {}",
+            build_slug,
+            artifacts.len(),
+            match args.filter {
+                Some(ref pattern) => format!("none matching '{}' could be parsed", pattern),
+                None => "pick them by name with --filter".to_string(),
+            }
+        )));
+    }
+    let total = Coverage::combine(reports.iter().map(|r| r.coverage));
+
+    match format {
+        OutputFormat::Pretty => Ok(format_coverage(&build_slug, total, &reports)),
+        OutputFormat::Json => json::format_json(&serde_json::json!({
+            "build_slug": build_slug,
+            "lines_covered": total.lines_covered,
+            "lines_valid": total.lines_valid,
+            "percent": total.percent(),
+            "reports": reports,
+        })),
+    }
+}
+
+/// Coverage of each successful build in the window that has a report
+fn trend(
+    client: &BitriseClient,
+    app_slug: &str,
+    args: &CoverageArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let window = TimeWindow::from_args(Some(&args.since), None)?;
+    let mut status_line = show_progress(format).then(|| StatusLine::start("Fetching builds"));
+    let builds = build_history(
+        client,
+        app_slug,
+        Some(BuildStatus::Success),
+        args.branch.as_deref(),
+        args.workflow.as_deref(),
+        window.as_ref(),
+        |_| {},
+    )?;
+
+    let mut points = Vec::new();
+    let total = builds.len();
+    // Oldest first, so the chart reads left to right in time
+    for (i, build) in builds.into_iter().rev().enumerate() {
+        if let Some(ref mut status) = status_line {
+            status.set_message(&format!("Reading coverage ({}/{} builds)", i + 1, total));
+        }
+        let artifacts = client.list_artifacts(app_slug, &build.slug)?.data;
+        let reports = read_reports(client, app_slug, &build.slug, &artifacts, args)?;
+        if !reports.is_empty() {
+            points.push(trend_point(build, &reports));
+        }
+    }
+    if let Some(ref mut status) = status_line {
+        status.finish();
+    }
+
+    match format {
+        OutputFormat::Pretty => Ok(format_trend(&points, &args.since, total)),
+        OutputFormat::Json => json::format_json(&points),
+    }
+}
+
+fn trend_point(build: Build, reports: &[Report]) -> TrendPoint {
+    let coverage = Coverage::combine(reports.iter().map(|r| r.coverage));
+    TrendPoint {
+        build_number: build.build_number,
+        build_slug: build.slug,
+        triggered_at: build.triggered_at,
+        branch: build.branch,
+        coverage,
+        percent: coverage.percent(),
+    }
+}
+
+/// Download and parse the build's coverage reports, skipping artifacts
+/// that turn out not to be LCOV or Cobertura
+fn read_reports(
+    client: &BitriseClient,
+    app_slug: &str,
+    build_slug: &str,
+    artifacts: &[Artifact],
+    args: &CoverageArgs,
+) -> Result<Vec<Report>> {
+    let candidates: Vec<&Artifact> = match args.filter {
+        Some(ref pattern) => filter_artifacts(artifacts, Some(pattern), None),
+        None => artifacts.iter().filter(|a| coverage::is_coverage_report(&a.title)).collect(),
+    };
+
+    let mut reports = Vec::new();
+    for artifact in candidates {
+        let detail = client.get_artifact(app_slug, build_slug, &artifact.slug)?.data;
+        let Some(ref url) = detail.expiring_download_url else {
+            continue;
+        };
+        let path = std::env::temp_dir().join(format!(
+            "reprise-coverage-{}-{}",
+            std::process::id(),
+            artifact.slug
+        ));
+        client.download_artifact(url, &path)?;
+        let contents = std::fs::read(&path);
+        let _ = std::fs::remove_file(&path);
+        if let Some((format, coverage)) = coverage::parse(&String::from_utf8_lossy(&contents?)) {
+            reports.push(Report { artifact: artifact.title.clone(), format, coverage });
+        }
+    }
+    Ok(reports)
+}
+
+/// "82.4%", or "-" without any lines
+fn percent_label(coverage: Coverage) -> String {
+    coverage.percent().map_or("-".to_string(), |p| format!("{:.1}%", p))
+}
+
+/// Render a build's total coverage and, with several, each report
+fn format_coverage(build_slug: &str, total: Coverage, reports: &[Report]) -> String {
+    let mut output = format!(
+        "{} {} ({} of {} lines) in build {}",
+        "Coverage:".cyan(),
+        percent_label(total).bold(),
+        total.lines_covered,
+        total.lines_valid,
+        build_slug
+    );
+    if reports.len() > 1 {
+        for report in reports {
+            output.push_str(&format!(
+                "\n  {:>6}  {} {}",
+                percent_label(report.coverage),
+                report.artifact,
+                format!("({})", report.format.as_str()).dimmed()
+            ));
+        }
+    }
+    output
+}
+
+/// Render a trend as a table with a bar per build and the overall change
+fn format_trend(points: &[TrendPoint], since: &str, scanned: usize) -> String {
+    if points.is_empty() {
+        return format!(
+            "No coverage reports in {} successful builds since {}.",
+            scanned, since
+        )
+        .dimmed()
+        .to_string();
+    }
+
+    let mut output = String::new();
+    if table::headers_shown() {
+        output.push_str(&format!("{} since {}\n", "Coverage".bold(), since));
+        output.push_str(&pretty::separator(pretty::MAX_RULE_WIDTH));
+        output.push('\n');
+    }
+    let mut table = Table::new(&["DATE", "BUILD", "COVERAGE", "CHANGE", ""]);
+    let mut previous: Option<f64> = None;
+    for point in points {
+        let change = match (previous, point.percent) {
+            (Some(before), Some(now)) => change_label(now - before),
+            _ => String::new(),
+        };
+        table.row(vec![
+            point.triggered_at.format("%Y-%m-%d").to_string(),
+            format!("#{}", point.build_number),
+            percent_label(point.coverage),
+            change,
+            chart::bar(point.percent.unwrap_or_default() / 100.0, BAR_WIDTH),
+        ]);
+        previous = point.percent.or(previous);
+    }
+    output.push_str(table.render().trim_end());
+
+    let percents: Vec<f64> = points.iter().filter_map(|p| p.percent).collect();
+    if let (Some(first), Some(last)) = (percents.first(), percents.last()) {
+        output.push_str(&format!(
+            "\n\n{} {:.1}% → {:.1}% ({}) over {} builds",
+            chart::sparkline(&percents),
+            first,
+            last,
+            change_label(last - first),
+            points.len()
+        ));
+    }
+    output
+}
+
+/// "+1.2", "-0.4" or "±0.0" percentage points, colored by direction
+fn change_label(points: f64) -> String {
+    if points >= 0.05 {
+        format!("+{:.1}", points).green().to_string()
+    } else if points <= -0.05 {
+        format!("{:.1}", points).red().to_string()
+    } else {
+        "±0.0".dimmed().to_string()
+    }
+}
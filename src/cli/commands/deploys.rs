@@ -0,0 +1,163 @@
+//! Deploys command (which versions went to which store, and when)
+
+use std::collections::HashMap;
+
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::bitrise::deploys::{deploy_workflows, Destination};
+use crate::bitrise::{yml, ArtifactAppInfo, BitriseClient, Build, BuildStatus};
+use crate::cli::args::{DeploysArgs, OutputFormat};
+use crate::config::Config;
+use crate::error::{RepriseError, Result};
+use crate::output::json;
+use crate::output::pretty;
+use crate::output::table::{self, Table};
+
+use super::common::{resolve_app, show_progress, warn_paged_limit};
+
+/// Workflow name fragments that suggest a deploy, when bitrise.yml can't be read
+const DEPLOY_NAME_HINTS: &[&str] = &["deploy", "release", "store", "testflight", "publish"];
+
+/// A successful build of a deploy workflow
+#[derive(Debug, Serialize)]
+struct Deploy {
+    build_number: i64,
+    build_slug: String,
+    finished_at: Option<chrono::DateTime<chrono::Utc>>,
+    workflow: String,
+    branch: String,
+    /// Empty when the destinations aren't known (bitrise.yml unavailable)
+    destinations: Vec<Destination>,
+    app_info: Option<ArtifactAppInfo>,
+}
+
+/// Handle the deploys command
+pub fn deploys(
+    client: &BitriseClient,
+    config: &Config,
+    args: &DeploysArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    warn_paged_limit(args.limit);
+
+    let known = if args.workflow.is_empty() {
+        known_deploy_workflows(client, app_slug, format)?
+    } else {
+        Some(args.workflow.iter().map(|w| (w.clone(), Vec::new())).collect())
+    };
+    let is_deploy = |workflow: &str| match known {
+        Some(ref known) => known.contains_key(workflow),
+        None => {
+            let name = workflow.to_lowercase();
+            DEPLOY_NAME_HINTS.iter().any(|hint| name.contains(hint))
+        }
+    };
+
+    let success = Some(BuildStatus::Success);
+    let builds = client.list_builds(app_slug, success, None, None, args.limit)?.data;
+    let deploys = builds
+        .into_iter()
+        .filter(|b| is_deploy(&b.triggered_workflow))
+        .map(|build| {
+            let destinations = known
+                .as_ref()
+                .and_then(|k| k.get(&build.triggered_workflow).cloned())
+                .unwrap_or_default();
+            deploy(client, app_slug, build, destinations)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    match format {
+        OutputFormat::Pretty => Ok(format_deploys(&deploys, app_slug, args.limit)),
+        OutputFormat::Json => json::format_json(&deploys),
+    }
+}
+
+/// Deploy workflows from the app's bitrise.yml on Bitrise, or `None` when
+/// it isn't stored there (e.g. kept in the repository) and names must do
+fn known_deploy_workflows(
+    client: &BitriseClient,
+    app_slug: &str,
+    format: OutputFormat,
+) -> Result<Option<HashMap<String, Vec<Destination>>>> {
+    let contents = match client.get_bitrise_yml(app_slug) {
+        Ok(contents) => contents,
+        Err(RepriseError::Api { status, .. }) if (400..500).contains(&status) => {
+            if show_progress(format) {
+                eprintln!(
+                    "warning: bitrise.yml isn't stored on Bitrise (HTTP {}); guessing deploy \
+                     workflows from their names (name them with --workflow)",
+                    status
+                );
+            }
+            return Ok(None);
+        }
+        Err(e) => return Err(e),
+    };
+    Ok(Some(deploy_workflows(&yml::parse(&contents)?)))
+}
+
+/// A deploy, with the version read from the build's app artifact
+fn deploy(
+    client: &BitriseClient,
+    app_slug: &str,
+    build: Build,
+    destinations: Vec<Destination>,
+) -> Result<Deploy> {
+    let artifacts = client.list_artifacts(app_slug, &build.slug)?.data;
+    let app_info = artifacts
+        .into_iter()
+        .filter_map(|a| a.artifact_meta.and_then(|m| m.app_info))
+        .find(|info| info.version_name.is_some() || info.version_code.is_some());
+    Ok(Deploy {
+        build_number: build.build_number,
+        build_slug: build.slug,
+        finished_at: build.finished_at,
+        workflow: build.triggered_workflow,
+        branch: build.branch,
+        destinations,
+        app_info,
+    })
+}
+
+/// Render deploys newest first as a table
+fn format_deploys(deploys: &[Deploy], app_slug: &str, scanned: u32) -> String {
+    if deploys.is_empty() {
+        return format!("No deploys in the last {} successful builds of {}.", scanned, app_slug)
+            .dimmed()
+            .to_string();
+    }
+
+    let mut output = String::new();
+    if table::headers_shown() {
+        output.push_str(&format!("{} of {}\n", "Deploys".bold(), app_slug));
+        output.push_str(&pretty::separator(pretty::MAX_RULE_WIDTH));
+        output.push('\n');
+    }
+    let mut table = Table::new(&["FINISHED", "BUILD", "VERSION", "WHERE", "WORKFLOW", "BRANCH"]);
+    for deploy in deploys {
+        let destinations: Vec<&str> = deploy.destinations.iter().map(|d| d.as_str()).collect();
+        table.row(vec![
+            deploy
+                .finished_at
+                .map_or("-".to_string(), |t| t.format("%Y-%m-%d %H:%M").to_string()),
+            format!("#{}", deploy.build_number),
+            deploy
+                .app_info
+                .as_ref()
+                .and_then(ArtifactAppInfo::version_display)
+                .map_or("-".to_string(), |v| v.bold().to_string()),
+            if destinations.is_empty() {
+                "-".to_string()
+            } else {
+                destinations.join(", ")
+            },
+            deploy.workflow.clone(),
+            deploy.branch.dimmed().to_string(),
+        ]);
+    }
+    output.push_str(table.render().trim_end());
+    output
+}
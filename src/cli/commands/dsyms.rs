@@ -0,0 +1,116 @@
+//! dSYMs command (download a build's debug symbols for crash symbolication)
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use colored::Colorize;
+
+use super::artifacts::{download_artifacts, filter_artifacts, resolve_build_slug};
+use super::common::{resolve_app, show_progress};
+use super::install::run;
+use crate::bitrise::{Artifact, BitriseClient};
+use crate::cli::args::{DsymsArgs, OutputFormat};
+use crate::config::Config;
+use crate::error::Result;
+use crate::output::json;
+
+/// Whether an artifact looks like zipped dSYMs (e.g. `App.app.dSYM.zip`,
+/// `dSYMs.zip`)
+fn is_dsym(artifact: &Artifact) -> bool {
+    let title = artifact.title.to_lowercase();
+    let kind = artifact.artifact_type.as_deref().unwrap_or_default();
+    title.contains("dsym") || kind.to_lowercase().contains("dsym")
+}
+
+/// Handle the dsyms command
+pub fn dsyms(
+    client: &BitriseClient,
+    config: &Config,
+    args: &DsymsArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    let build_slug = resolve_build_slug(
+        client,
+        app_slug,
+        &args.slug,
+        args.branch.as_deref(),
+        args.workflow.as_deref(),
+        format,
+    )?;
+
+    let response = client.list_artifacts(app_slug, &build_slug)?;
+    let dsyms: Vec<&Artifact> = match args.filter {
+        Some(ref pattern) => filter_artifacts(&response.data, Some(pattern), None),
+        None => response.data.iter().filter(|a| is_dsym(a)).collect(),
+    };
+    if dsyms.is_empty() {
+        let how = match args.filter {
+            Some(ref pattern) => format!("matching '{}'", pattern),
+            None => "named like *dSYM*; use --filter to pick them by name".to_string(),
+        };
+        return match format {
+            OutputFormat::Pretty => Ok(format!(
+                "No dSYM artifacts {} in build {} ({} artifacts in total).",
+                how,
+                build_slug,
+                response.data.len()
+            )
+            .dimmed()
+            .to_string()),
+            OutputFormat::Json => json::format_json(&serde_json::json!({
+                "build_slug": build_slug,
+                "downloaded": Vec::<String>::new(),
+            })),
+        };
+    }
+
+    let dir = match args.dir {
+        Some(ref dir) => PathBuf::from(dir),
+        None => match config.app_config(app_slug).download_dir {
+            Some(ref dir) => PathBuf::from(dir),
+            None => std::env::current_dir()?,
+        },
+    };
+    let downloaded = download_artifacts(client, app_slug, &build_slug, &dsyms, &dir, format)?;
+
+    let mut unzipped = Vec::new();
+    if args.unzip {
+        for name in downloaded.iter().filter(|n| n.to_lowercase().ends_with(".zip")) {
+            if show_progress(format) {
+                eprintln!("{} Unzipping {}", "->".cyan(), name);
+            }
+            let mut cmd = Command::new("unzip");
+            cmd.arg("-q").arg("-o").arg(dir.join(name)).arg("-d").arg(&dir);
+            run(cmd, "unzip")?;
+            unzipped.push(name.clone());
+        }
+    }
+
+    match format {
+        OutputFormat::Pretty => {
+            let mut output = format!(
+                "{} Downloaded {} dSYM archive(s) to {}",
+                "✓".green(),
+                downloaded.len(),
+                dir.display()
+            );
+            if !unzipped.is_empty() {
+                output.push_str(&format!(" and unzipped {}", unzipped.len()));
+            }
+            for name in &downloaded {
+                output.push_str(&format!("\n  {}", name));
+            }
+            Ok(output)
+        }
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "build_slug": build_slug,
+                "downloaded": downloaded,
+                "unzipped": unzipped,
+                "directory": dir.to_string_lossy(),
+            });
+            json::format_json(&json)
+        }
+    }
+}
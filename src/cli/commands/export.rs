@@ -0,0 +1,155 @@
+//! Export command (write build history to a file for offline analysis)
+
+use std::fs;
+use std::path::Path;
+
+use crate::bitrise::{BitriseClient, Build};
+use crate::cli::args::{
+    ExportArgs, ExportBuildsArgs, ExportCommands, ExportFormat, OutputFormat,
+};
+use crate::config::Config;
+use crate::duration::TimeWindow;
+use crate::error::Result;
+use crate::output::json;
+use crate::output::progress::StatusLine;
+
+use super::common::{build_history, resolve_app};
+
+/// Handle the export command
+pub fn export(
+    client: &BitriseClient,
+    config: &Config,
+    args: &ExportArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    match &args.command {
+        ExportCommands::Builds(args) => export_builds(client, config, args, format),
+    }
+}
+
+/// Export every build matching the filters, paging through the full history
+fn export_builds(
+    client: &BitriseClient,
+    config: &Config,
+    args: &ExportBuildsArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    let window = TimeWindow::from_args(args.since.as_deref(), args.until.as_deref())?;
+    let file_format = args.format.unwrap_or_else(|| match args.out {
+        Some(ref out) if out.to_lowercase().ends_with(".csv") => ExportFormat::Csv,
+        _ => ExportFormat::Json,
+    });
+
+    let mut status_line = (format == OutputFormat::Pretty && args.out.is_some())
+        .then(|| StatusLine::start("Exporting builds"));
+    let builds = build_history(
+        client,
+        app_slug,
+        args.status.map(|s| s.to_status()),
+        args.branch.as_deref(),
+        args.workflow.as_deref(),
+        window.as_ref(),
+        |count| {
+            if let Some(ref mut status) = status_line {
+                status.set_message(&format!("Exporting builds ({} so far)", count));
+            }
+        },
+    )?;
+    if let Some(ref mut status) = status_line {
+        status.finish();
+    }
+
+    let contents = match file_format {
+        ExportFormat::Json => serde_json::to_string_pretty(&builds)? + "\n",
+        ExportFormat::Csv => builds_to_csv(&builds),
+    };
+
+    let Some(ref out) = args.out else {
+        // No file: the export itself is the output, e.g. for piping
+        return Ok(contents.trim_end().to_string());
+    };
+    let path = Path::new(out);
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, &contents)?;
+
+    match format {
+        OutputFormat::Pretty => Ok(format!(
+            "Exported {} build{} to {}",
+            builds.len(),
+            if builds.len() == 1 { "" } else { "s" },
+            out
+        )),
+        OutputFormat::Json => {
+            let result = serde_json::json!({
+                "app_slug": app_slug,
+                "builds": builds.len(),
+                "saved_to": out,
+                "bytes": contents.len(),
+            });
+            json::format_json(&result)
+        }
+    }
+}
+
+/// Column headers of the CSV export
+const CSV_COLUMNS: [&str; 17] = [
+    "build_number",
+    "slug",
+    "status",
+    "branch",
+    "workflow",
+    "triggered_at",
+    "started_at",
+    "finished_at",
+    "duration_secs",
+    "triggered_by",
+    "commit_hash",
+    "commit_message",
+    "tag",
+    "pull_request_id",
+    "stack",
+    "machine_type",
+    "credit_cost",
+];
+
+/// Render builds as CSV, one row per build
+fn builds_to_csv(builds: &[Build]) -> String {
+    let mut csv = CSV_COLUMNS.join(",") + "\n";
+    for build in builds {
+        let fields = [
+            build.build_number.to_string(),
+            build.slug.clone(),
+            build.status_display().to_string(),
+            build.branch.clone(),
+            build.triggered_workflow.clone(),
+            build.triggered_at.to_rfc3339(),
+            build.started_on_worker_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            build.finished_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            build.duration().map(|d| d.num_seconds().to_string()).unwrap_or_default(),
+            build.triggered_by.clone().unwrap_or_default(),
+            build.commit_hash.clone().unwrap_or_default(),
+            build.commit_message.clone().unwrap_or_default(),
+            build.tag.clone().unwrap_or_default(),
+            build.pull_request_id.map(|id| id.to_string()).unwrap_or_default(),
+            build.stack_identifier.clone().unwrap_or_default(),
+            build.machine_type_id.clone().unwrap_or_default(),
+            build.credit_cost.map(|c| c.to_string()).unwrap_or_default(),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,211 @@
+//! Failures command (recurring causes of failed builds, from their logs)
+
+use std::collections::HashMap;
+
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::bitrise::{BitriseClient, Build, BuildStatus};
+use crate::cli::args::{FailuresArgs, OutputFormat};
+use crate::config::Config;
+use crate::duration::TimeWindow;
+use crate::error::Result;
+use crate::logparse::failures::error_lines;
+use crate::output::json;
+use crate::output::pretty;
+use crate::output::progress::StatusLine;
+use crate::output::table;
+
+use super::common::{build_history, open_full_log, resolve_app, show_progress};
+
+/// Example builds listed per failure cause
+const MAX_EXAMPLES: usize = 3;
+
+/// A failed build a cause was seen in
+#[derive(Debug, Clone, Serialize)]
+struct Example {
+    build_number: i64,
+    build_slug: String,
+    workflow: String,
+    branch: String,
+}
+
+/// Failed builds sharing an error signature
+#[derive(Debug, Serialize)]
+struct Cause {
+    signature: String,
+    /// The error line as it appeared in the newest build
+    example_line: String,
+    builds: usize,
+    /// Steps that printed it, most frequent first
+    steps: Vec<String>,
+    examples: Vec<Example>,
+}
+
+/// Everything the report is based on
+#[derive(Debug, Serialize)]
+struct Report {
+    failed_builds: usize,
+    logs_read: usize,
+    /// Logs that had no recognizable error line
+    without_errors: usize,
+    causes: Vec<Cause>,
+}
+
+/// Handle the failures command
+pub fn failures(
+    client: &BitriseClient,
+    config: &Config,
+    args: &FailuresArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    let window = TimeWindow::from_args(Some(&args.since), args.until.as_deref())?;
+
+    let mut status_line = show_progress(format).then(|| StatusLine::start("Fetching builds"));
+    let builds = build_history(
+        client,
+        app_slug,
+        Some(BuildStatus::Failed),
+        args.branch.as_deref(),
+        args.workflow.as_deref(),
+        window.as_ref(),
+        |count| {
+            if let Some(ref mut status) = status_line {
+                status.set_message(&format!("Fetching builds ({} so far)", count));
+            }
+        },
+    )?;
+
+    let to_read = builds.len().min(args.max_builds);
+    let mut causes: HashMap<String, (Cause, HashMap<String, usize>)> = HashMap::new();
+    let mut without_errors = 0;
+    // Newest first, so example lines and builds are the most recent ones
+    for (i, build) in builds.iter().take(to_read).enumerate() {
+        if let Some(ref mut status) = status_line {
+            status.set_message(&format!("Reading logs ({}/{})", i + 1, to_read));
+        }
+        let log = open_full_log(client, config, app_slug, &build.slug)?;
+        let lines = error_lines(log)?;
+        if lines.is_empty() {
+            without_errors += 1;
+        }
+        for line in lines {
+            let (cause, steps) = causes.entry(line.signature.clone()).or_insert_with(|| {
+                let cause = Cause {
+                    signature: line.signature,
+                    example_line: line.line,
+                    builds: 0,
+                    steps: Vec::new(),
+                    examples: Vec::new(),
+                };
+                (cause, HashMap::new())
+            });
+            cause.builds += 1;
+            if cause.examples.len() < MAX_EXAMPLES {
+                cause.examples.push(example(build));
+            }
+            if let Some(step) = line.step {
+                *steps.entry(step).or_default() += 1;
+            }
+        }
+    }
+    if let Some(ref mut status) = status_line {
+        status.finish();
+    }
+
+    let mut causes: Vec<Cause> = causes
+        .into_values()
+        .map(|(mut cause, steps)| {
+            let mut steps: Vec<(String, usize)> = steps.into_iter().collect();
+            steps.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            cause.steps = steps.into_iter().map(|(step, _)| step).collect();
+            cause
+        })
+        .collect();
+    // Most builds first; ties go to the most recent
+    causes.sort_by(|a, b| {
+        b.builds
+            .cmp(&a.builds)
+            .then_with(|| b.examples[0].build_number.cmp(&a.examples[0].build_number))
+    });
+    causes.truncate(args.top);
+
+    let report = Report {
+        failed_builds: builds.len(),
+        logs_read: to_read,
+        without_errors,
+        causes,
+    };
+    match format {
+        OutputFormat::Pretty => Ok(format_report(&report, &args.since)),
+        OutputFormat::Json => json::format_json(&report),
+    }
+}
+
+fn example(build: &Build) -> Example {
+    Example {
+        build_number: build.build_number,
+        build_slug: build.slug.clone(),
+        workflow: build.triggered_workflow.clone(),
+        branch: build.branch.clone(),
+    }
+}
+
+/// Render the causes as a ranked list
+fn format_report(report: &Report, since: &str) -> String {
+    if report.failed_builds == 0 {
+        return format!("No failed builds since {}.", since).dimmed().to_string();
+    }
+
+    let mut output = String::new();
+    if table::headers_shown() {
+        output.push_str(&format!(
+            "{} since {} ({} failed builds)\n",
+            "Failure causes".bold(),
+            since,
+            report.failed_builds
+        ));
+        output.push_str(&pretty::separator(pretty::MAX_RULE_WIDTH));
+        output.push('\n');
+    }
+    if report.causes.is_empty() {
+        output.push_str(&"No error lines found in the failed builds' logs.".dimmed().to_string());
+        return output;
+    }
+
+    let width = report.causes.len().to_string().len();
+    for (i, cause) in report.causes.iter().enumerate() {
+        let builds = if cause.builds == 1 { "build" } else { "builds" };
+        output.push_str(&format!(
+            "{:>width$}. {} {}  {}\n",
+            i + 1,
+            cause.builds.to_string().bold(),
+            builds,
+            cause.example_line.red(),
+        ));
+        let examples: Vec<String> =
+            cause.examples.iter().map(|e| format!("#{}", e.build_number)).collect();
+        let mut detail = format!("e.g. {}", examples.join(", "));
+        if let Some(step) = cause.steps.first() {
+            detail = format!("in {} · {}", step, detail);
+        }
+        output.push_str(&format!("{:width$}  {}\n", "", detail.dimmed()));
+    }
+
+    let mut notes = Vec::new();
+    if report.logs_read < report.failed_builds {
+        notes.push(format!(
+            "read the newest {} of {} logs (raise --max-builds for more)",
+            report.logs_read, report.failed_builds
+        ));
+    }
+    if report.without_errors > 0 {
+        let logs = if report.without_errors == 1 { "log has" } else { "logs have" };
+        notes.push(format!("{} {} no error lines", report.without_errors, logs));
+    }
+    if !notes.is_empty() {
+        output.push_str(&format!("\n{}", notes.join("; ").dimmed()));
+    }
+    output.trim_end().to_string()
+}
@@ -0,0 +1,49 @@
+//! Help command (help for a command, or a long-form help topic)
+
+use clap::CommandFactory;
+
+use crate::cli::args::{Cli, HelpArgs};
+use crate::cli::topics;
+use crate::error::{RepriseError, Result};
+use crate::output::terminal;
+
+/// Handle the help command
+///
+/// A single word naming a topic prints the topic; otherwise the words are
+/// a command path (`config alias`) whose `--help` is printed.
+pub fn help(args: &HelpArgs) -> Result<String> {
+    if let [name] = &args.topic[..] {
+        if let Some(topic) = topics::find(name) {
+            return Ok(topic.text.to_string());
+        }
+    }
+
+    let mut cli = Cli::command();
+    cli.build();
+    let mut command = &mut cli;
+    for name in &args.topic {
+        command = command
+            .find_subcommand_mut(name)
+            .filter(|c| !c.is_hide_set())
+            .ok_or_else(|| {
+                let names: Vec<&str> = topics::TOPICS.iter().map(|t| t.name).collect();
+                RepriseError::InvalidArgument(format!(
+                    "No help topic or command '{}' (topics: {}; commands: reprise --help)",
+                    args.topic.join(" "),
+                    names.join(", ")
+                ))
+            })?;
+    }
+
+    let help = command.render_long_help();
+    let mut output = if terminal::colors_enabled() {
+        help.ansi().to_string()
+    } else {
+        help.to_string()
+    };
+    if args.topic.is_empty() {
+        output.push('\n');
+        output.push_str(&topics::list());
+    }
+    Ok(output)
+}
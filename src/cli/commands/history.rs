@@ -0,0 +1,61 @@
+//! History command (review recorded triggers, aborts, rebuilds and downloads)
+
+use chrono::Local;
+use colored::Colorize;
+
+use crate::cli::args::{HistoryArgs, OutputFormat};
+use crate::config::Paths;
+use crate::error::Result;
+use crate::history::{self, Entry};
+use crate::output::json;
+use crate::output::pretty;
+use crate::output::table::{self, Table};
+
+/// Handle the history command
+pub fn history(args: &HistoryArgs, format: OutputFormat) -> Result<String> {
+    let file = history::file(&Paths::new()?);
+    let mut entries = history::read(&file)?;
+    entries.reverse();
+    entries.truncate(args.limit as usize);
+
+    match format {
+        OutputFormat::Pretty => Ok(format_history(&entries)),
+        OutputFormat::Json => json::format_json(&entries),
+    }
+}
+
+fn format_history(entries: &[Entry]) -> String {
+    if entries.is_empty() {
+        return "No recorded actions yet.".dimmed().to_string();
+    }
+    let mut output = String::new();
+    if table::headers_shown() {
+        output.push_str(&format!("{}\n", "History".bold()));
+        output.push_str(&pretty::separator(pretty::MAX_RULE_WIDTH));
+        output.push('\n');
+    }
+    let mut table = Table::new(&["TIME", "ACTION", "RESULT", "APP", "SLUG", "USER"]);
+    for entry in entries {
+        let result = match entry.error {
+            None => entry.result.green().to_string(),
+            Some(_) => entry.result.red().to_string(),
+        };
+        table.row(vec![
+            entry.time.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string(),
+            entry.action.bold().to_string(),
+            result,
+            if entry.app_slug.is_empty() { "-".to_string() } else { entry.app_slug.clone() },
+            entry.slug.clone().unwrap_or_else(|| "-".to_string()),
+            entry.user.clone().unwrap_or_else(|| "-".to_string()),
+        ]);
+        table.text(format!("  {}", entry.command.dimmed()));
+        if let Some(ref detail) = entry.detail {
+            table.text(format!("  {}", detail));
+        }
+        if let Some(ref error) = entry.error {
+            table.text(format!("  {}", error.red()));
+        }
+    }
+    output.push_str(table.render().trim_end());
+    output
+}
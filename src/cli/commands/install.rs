@@ -0,0 +1,211 @@
+//! Install command (download an app artifact and install it on a device)
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use colored::Colorize;
+
+use super::artifacts::{filter_artifacts, sanitize_filename};
+use super::common::{resolve_app, show_progress};
+use crate::bitrise::{Artifact, BitriseClient};
+use crate::cli::args::{InstallArgs, OutputFormat};
+use crate::config::Config;
+use crate::error::{RepriseError, Result};
+use crate::history;
+use crate::output::json;
+
+/// How an artifact gets onto a device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Installer {
+    /// Android package via `adb install`
+    Adb,
+    /// iOS device build via `xcrun devicectl`
+    Devicectl,
+    /// Zipped simulator `.app` via `xcrun simctl`
+    Simctl,
+}
+
+impl Installer {
+    /// Pick an installer from an artifact filename
+    fn for_file(name: &str) -> Option<Self> {
+        let lower = name.to_lowercase();
+        if lower.ends_with(".apk") {
+            Some(Self::Adb)
+        } else if lower.ends_with(".ipa") {
+            Some(Self::Devicectl)
+        } else if lower.ends_with(".app.zip") {
+            Some(Self::Simctl)
+        } else {
+            None
+        }
+    }
+
+    fn tool(self) -> &'static str {
+        match self {
+            Self::Adb => "adb",
+            Self::Devicectl | Self::Simctl => "xcrun",
+        }
+    }
+}
+
+/// Handle the install command
+pub fn install(
+    client: &BitriseClient,
+    config: &Config,
+    args: &InstallArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+
+    let response = client.list_artifacts(app_slug, &args.slug)?;
+    let candidates: Vec<&Artifact> = filter_artifacts(&response.data, args.filter.as_deref(), None)
+        .into_iter()
+        .filter(|a| Installer::for_file(&a.title).is_some())
+        .collect();
+
+    let artifact = match candidates.as_slice() {
+        [one] => *one,
+        [] => {
+            return Err(RepriseError::InvalidArgument(format!(
+                "No installable artifact (.apk, .ipa, .app.zip) found in build {}{}",
+                args.slug,
+                args.filter
+                    .as_ref()
+                    .map(|f| format!(" matching '{}'", f))
+                    .unwrap_or_default()
+            )));
+        }
+        many => {
+            let names: Vec<&str> = many.iter().map(|a| a.title.as_str()).collect();
+            return Err(RepriseError::InvalidArgument(format!(
+                "Multiple installable artifacts found: {}. Use --filter to pick one.",
+                names.join(", ")
+            )));
+        }
+    };
+
+    let installer = Installer::for_file(&artifact.title).ok_or_else(|| {
+        RepriseError::InvalidArgument(format!("Unsupported artifact: {}", artifact.title))
+    })?;
+    if installer == Installer::Devicectl && args.device.is_none() {
+        return Err(RepriseError::InvalidArgument(
+            "Installing an .ipa requires --device <UDID> (see 'xcrun devicectl list devices')"
+                .to_string(),
+        ));
+    }
+
+    // Download to a scratch directory
+    let detail = client.get_artifact(app_slug, &args.slug, &artifact.slug)?;
+    let url = detail.data.expiring_download_url.ok_or_else(|| {
+        RepriseError::InvalidArgument(format!("Artifact has no download URL: {}", artifact.title))
+    })?;
+    let work_dir = std::env::temp_dir().join(format!("reprise-install-{}", args.slug));
+    std::fs::create_dir_all(&work_dir)?;
+    let file_path = work_dir.join(sanitize_filename(&artifact.title)?);
+
+    if show_progress(format) {
+        eprint!("Downloading {}... ", artifact.title);
+    }
+    let downloaded = client.download_artifact(&url, &file_path);
+    history::record("download", app_slug, Some(&args.slug), &downloaded);
+    downloaded?;
+    if show_progress(format) {
+        eprintln!("{}", "done".green());
+        eprintln!("{} Installing with {}...", "->".cyan(), installer.tool());
+    }
+
+    let result = run_installer(installer, &file_path, &work_dir, args.device.as_deref());
+    let _ = std::fs::remove_dir_all(&work_dir);
+    result?;
+
+    match format {
+        OutputFormat::Pretty => Ok(format!(
+            "{} Installed {}{}",
+            "✓".green(),
+            artifact.title.bold(),
+            args.device
+                .as_ref()
+                .map(|d| format!(" on {}", d))
+                .unwrap_or_default()
+        )),
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "installed": artifact.title,
+                "build_slug": args.slug,
+                "installer": installer.tool(),
+                "device": args.device,
+            });
+            json::format_json(&json)
+        }
+    }
+}
+
+/// Run the platform installer for a downloaded artifact
+fn run_installer(
+    installer: Installer,
+    file: &Path,
+    work_dir: &Path,
+    device: Option<&str>,
+) -> Result<()> {
+    let mut cmd = Command::new(installer.tool());
+    match installer {
+        Installer::Adb => {
+            if let Some(serial) = device {
+                cmd.args(["-s", serial]);
+            }
+            cmd.arg("install").arg("-r").arg(file);
+        }
+        Installer::Devicectl => {
+            cmd.args(["devicectl", "device", "install", "app", "--device"])
+                .arg(device.unwrap_or_default())
+                .arg(file);
+        }
+        Installer::Simctl => {
+            let app = unzip_app(file, work_dir)?;
+            cmd.args(["simctl", "install", device.unwrap_or("booted")])
+                .arg(app);
+        }
+    }
+
+    run(cmd, installer.tool())
+}
+
+/// Extract a zipped `.app` bundle and return its path
+fn unzip_app(zip: &Path, work_dir: &Path) -> Result<PathBuf> {
+    let out_dir = work_dir.join("unzipped");
+    let mut cmd = Command::new("unzip");
+    cmd.arg("-q").arg("-o").arg(zip).arg("-d").arg(&out_dir);
+    run(cmd, "unzip")?;
+
+    std::fs::read_dir(&out_dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .find(|path| path.extension().is_some_and(|ext| ext == "app"))
+        .ok_or_else(|| {
+            RepriseError::InvalidArgument(format!(
+                "No .app bundle found inside {}",
+                zip.display()
+            ))
+        })
+}
+
+/// Run an external tool, mapping "not installed" and non-zero exits to errors
+pub(super) fn run(mut cmd: Command, tool: &str) -> Result<()> {
+    let status = cmd.status().map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            RepriseError::Io(std::io::Error::new(
+                e.kind(),
+                format!("'{}' not found on PATH", tool),
+            ))
+        } else {
+            RepriseError::Io(e)
+        }
+    })?;
+
+    if !status.success() {
+        return Err(RepriseError::Io(std::io::Error::other(format!(
+            "'{}' exited with {}",
+            tool, status
+        ))));
+    }
+    Ok(())
+}
@@ -1,15 +1,26 @@
+use std::collections::VecDeque;
 use std::fs;
-use std::io::{self, Write};
-use std::thread;
-use std::time::Duration;
+use std::io::{self, BufRead, BufWriter, Write};
+use std::path::Path;
 
 use colored::Colorize;
 
-use super::common::{is_interrupted, resolve_app_slug, setup_interrupt_handler};
-use crate::bitrise::BitriseClient;
-use crate::cli::args::{LogArgs, OutputFormat};
+use super::common::{
+    build_progress_message, finished_message, is_interrupted, open_full_log, resolve_app,
+    setup_interrupt_handler, show_progress, warn_paged_limit,
+};
+use crate::bitrise::{BitriseClient, Build};
+use crate::cli::args::{AnnotationFormat, LogArgs, OutputFormat};
 use crate::config::Config;
 use crate::error::{RepriseError, Result};
+use crate::eta;
+use crate::logparse::annotations::{self, Level, DEFAULT_SOURCE_ROOTS};
+use crate::logparse::html;
+use crate::logparse::{Highlighter, StepTracker};
+use crate::output::json;
+use crate::output::progress::StatusLine;
+use crate::output::terminal::{strip_ansi, AnsiMode};
+use crate::watch::{PollSchedule, DEFAULT_MAX_INTERVAL_SECS};
 
 /// Handle the log command
 pub fn log(
@@ -19,49 +30,301 @@ pub fn log(
     format: OutputFormat,
 ) -> Result<String> {
     // Resolve app slug from args or config default
-    let app_slug = resolve_app_slug(args.app.as_deref(), config)?;
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    let highlighter = config.log_highlighter()?;
+
+    if let Some(ref dir) = args.save_dir {
+        return save_logs_to_dir(client, config, app_slug, args, Path::new(dir), format);
+    }
+    let Some(build_slug) = args.slug.as_deref() else {
+        return Err(RepriseError::InvalidArgument(
+            "A build slug is required unless --save-dir is given".to_string(),
+        ));
+    };
 
     // Handle follow mode
     if args.follow {
-        return follow_log(client, app_slug, &args.slug, args.interval, args.notify, format);
+        let interval = args
+            .interval
+            .or(config.app_config(app_slug).interval)
+            .unwrap_or(DEFAULT_MAX_INTERVAL_SECS);
+        return follow_log(client, &highlighter, app_slug, build_slug, args, interval, format);
+    }
+
+    if let Some(annotation_format) = args.annotations {
+        return annotate(client, config, app_slug, build_slug, args, annotation_format, format);
+    }
+
+    if let Some(ref path) = args.html {
+        return export_html(client, config, &highlighter, app_slug, build_slug, path, format);
     }
 
-    // Fetch the full log
-    let log_content = client.get_full_log(app_slug, &args.slug)?;
+    // Stream the log: --save writes it to the file as it arrives and --tail
+    // keeps only the last lines, so only a printed full log is held in memory
+    let mut reader = open_full_log(client, config, app_slug, build_slug)?;
+    let mut save = match args.save {
+        Some(ref path) => Some(BufWriter::new(fs::File::create(path)?)),
+        None => None,
+    };
+    let mut tail = VecDeque::new();
+    let mut full = String::new();
 
-    if log_content.is_empty() {
+    let bytes = stream_lines(
+        &mut reader,
+        save.as_mut().map(|file| file as &mut dyn Write),
+        args.strip_ansi,
+        |text| {
+            if let Some(max) = args.tail {
+                if tail.len() == max {
+                    tail.pop_front();
+                }
+                if max > 0 {
+                    tail.push_back(text.trim_end_matches(['\n', '\r']).to_string());
+                }
+            } else {
+                full.push_str(text);
+            }
+        },
+    )?;
+
+    if bytes == 0 {
         return Err(RepriseError::LogNotAvailable(
             "Log content is empty or not yet available.".to_string(),
         ));
     }
 
-    // Apply --tail if specified
-    let output = if let Some(tail_lines) = args.tail {
-        let lines: Vec<&str> = log_content.lines().collect();
-        let start = lines.len().saturating_sub(tail_lines);
-        lines[start..].join("\n")
-    } else {
-        log_content.clone()
-    };
-
-    // Save to file if --save specified
-    if let Some(ref path) = args.save {
-        fs::write(path, &log_content)?;
+    if let (Some(mut file), Some(path)) = (save, args.save.as_ref()) {
+        file.flush()?;
         if format == OutputFormat::Pretty {
             eprintln!("Log saved to: {}", path);
         }
     }
 
+    let output = match args.tail {
+        Some(_) => Vec::from(tail).join("\n"),
+        None => full,
+    };
+    let ansi = AnsiMode::for_log(args.strip_ansi, args.raw);
+
     // Return appropriate output
     match format {
-        OutputFormat::Pretty => Ok(highlight_log_content(&output)),
+        OutputFormat::Pretty => Ok(render(&output, &highlighter, ansi, args.raw)),
         OutputFormat::Json => {
+            let output = ansi.apply(&output);
             let result = serde_json::json!({
-                "build_slug": args.slug,
+                "build_slug": build_slug,
                 "log": output,
                 "lines": output.lines().count()
             });
-            Ok(serde_json::to_string_pretty(&result)?)
+            json::format_json(&result)
+        }
+    }
+}
+
+/// Compiler and test diagnostics of a build's log as GitHub Actions
+/// workflow commands or SARIF (`--annotations`)
+fn annotate(
+    client: &BitriseClient,
+    config: &Config,
+    app_slug: &str,
+    build_slug: &str,
+    args: &LogArgs,
+    annotation_format: AnnotationFormat,
+    format: OutputFormat,
+) -> Result<String> {
+    let root = args.source_root.as_deref().map(|root| format!("{}/", root.trim_end_matches('/')));
+    let roots: Vec<&str> = match root {
+        Some(ref root) => vec![root.as_str()],
+        None => DEFAULT_SOURCE_ROOTS.to_vec(),
+    };
+    let reader = open_full_log(client, config, app_slug, build_slug)?;
+    let diagnostics = annotations::diagnostics(reader, &roots)?;
+    if show_progress(format) {
+        let errors = diagnostics.iter().filter(|d| d.level == Level::Error).count();
+        eprintln!(
+            "{} {} errors and {} warnings in build {}",
+            "->".cyan(),
+            errors,
+            diagnostics.len() - errors,
+            build_slug
+        );
+    }
+
+    match annotation_format {
+        AnnotationFormat::Github => Ok(annotations::github_commands(&diagnostics)),
+        AnnotationFormat::Sarif => {
+            Ok(serde_json::to_string_pretty(&annotations::sarif(&diagnostics))?)
+        }
+    }
+}
+
+/// Write a build's log as a standalone HTML page (`--html`)
+fn export_html(
+    client: &BitriseClient,
+    config: &Config,
+    highlighter: &Highlighter,
+    app_slug: &str,
+    build_slug: &str,
+    path: &str,
+    format: OutputFormat,
+) -> Result<String> {
+    let build = client.get_build(app_slug, build_slug)?.data;
+    let title = format!(
+        "Build #{} · {} · {}",
+        build.build_number, build.triggered_workflow, build.branch
+    );
+    let reader = open_full_log(client, config, app_slug, build_slug)?;
+    let mut file = BufWriter::new(fs::File::create(path)?);
+    let stats = html::write_html(reader, &mut file, &title, highlighter)?;
+    file.flush()?;
+    if stats.lines == 0 {
+        return Err(RepriseError::LogNotAvailable(
+            "Log content is empty or not yet available.".to_string(),
+        ));
+    }
+
+    match format {
+        OutputFormat::Pretty => Ok(format!(
+            "{} Log saved to: {} ({} steps, {} failed)",
+            "✓".green(),
+            path,
+            stats.steps,
+            stats.failed_steps
+        )),
+        OutputFormat::Json => {
+            let result = serde_json::json!({
+                "build_slug": build_slug,
+                "saved_to": path,
+                "lines": stats.lines,
+                "steps": stats.steps,
+                "failed_steps": stats.failed_steps,
+            });
+            json::format_json(&result)
+        }
+    }
+}
+
+/// Read a log line by line, writing each line to `save` (ANSI codes removed
+/// if `strip` is set) and handing it to `on_line`; returns the bytes read
+fn stream_lines(
+    reader: &mut dyn BufRead,
+    mut save: Option<&mut dyn Write>,
+    strip: bool,
+    mut on_line: impl FnMut(&str),
+) -> Result<usize> {
+    let mut bytes = 0;
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let n = reader.read_until(b'\n', &mut line)?;
+        if n == 0 {
+            return Ok(bytes);
+        }
+        bytes += n;
+        let text = String::from_utf8_lossy(&line);
+
+        // Saved raw unless --strip-ansi was given
+        if let Some(ref mut file) = save {
+            if strip {
+                file.write_all(strip_ansi(&text).as_bytes())?;
+            } else {
+                file.write_all(&line)?;
+            }
+        }
+        on_line(&text);
+    }
+}
+
+/// Save logs into `dir` as `app-<app-slug>-build-<number>.log`: the given
+/// build's, or those of the latest finished builds matching the filters
+fn save_logs_to_dir(
+    client: &BitriseClient,
+    config: &Config,
+    app_slug: &str,
+    args: &LogArgs,
+    dir: &Path,
+    format: OutputFormat,
+) -> Result<String> {
+    if let Some(limit) = args.limit {
+        warn_paged_limit(limit);
+    }
+    let builds: Vec<Build> = match args.slug {
+        Some(ref slug) => vec![client.get_build(app_slug, slug)?.data],
+        None => client
+            .list_builds(
+                app_slug,
+                args.status.map(|s| s.to_status()),
+                args.branch.as_deref(),
+                args.workflow.as_deref(),
+                args.limit.unwrap_or(10),
+            )?
+            .data
+            .into_iter()
+            // A running build's log is still growing
+            .filter(|b| !b.is_running())
+            .collect(),
+    };
+    if builds.is_empty() {
+        return Err(RepriseError::BuildNotFound(
+            "No finished builds match the given filters".to_string(),
+        ));
+    }
+    fs::create_dir_all(dir)?;
+
+    let mut saved = Vec::new();
+    let mut failed = Vec::new();
+    for build in &builds {
+        let path = dir.join(format!("app-{}-build-{}.log", app_slug, build.build_number));
+        let result = open_full_log(client, config, app_slug, &build.slug).and_then(|mut reader| {
+            let mut file = BufWriter::new(fs::File::create(&path)?);
+            let bytes = stream_lines(&mut reader, Some(&mut file), args.strip_ansi, |_| {})?;
+            file.flush()?;
+            Ok(bytes)
+        });
+        match result {
+            Ok(bytes) => {
+                if format == OutputFormat::Pretty {
+                    eprintln!("Log saved to: {}", path.display());
+                }
+                saved.push(serde_json::json!({
+                    "build_slug": build.slug,
+                    "build_number": build.build_number,
+                    "saved_to": path.display().to_string(),
+                    "bytes": bytes,
+                }));
+            }
+            Err(e) => {
+                if format == OutputFormat::Pretty {
+                    eprintln!("{} Build #{}: {}", "!".yellow(), build.build_number, e);
+                }
+                failed.push(serde_json::json!({
+                    "build_slug": build.slug,
+                    "build_number": build.build_number,
+                    "error": e.to_string(),
+                }));
+            }
+        }
+    }
+
+    if saved.is_empty() {
+        return Err(RepriseError::LogNotAvailable(
+            "None of the selected builds' logs could be saved.".to_string(),
+        ));
+    }
+    match format {
+        OutputFormat::Pretty => Ok(format!(
+            "Saved {} log{} to {}",
+            saved.len(),
+            if saved.len() == 1 { "" } else { "s" },
+            dir.display()
+        )),
+        OutputFormat::Json => {
+            let result = serde_json::json!({
+                "dir": dir.display().to_string(),
+                "saved": saved,
+                "failed": failed,
+            });
+            json::format_json(&result)
         }
     }
 }
@@ -69,29 +332,30 @@ pub fn log(
 /// Follow log output for a running build
 fn follow_log(
     client: &BitriseClient,
+    highlighter: &Highlighter,
     app_slug: &str,
     build_slug: &str,
+    args: &LogArgs,
     interval_secs: u64,
-    send_notification: bool,
     format: OutputFormat,
 ) -> Result<String> {
+    let ansi = AnsiMode::for_log(args.strip_ansi, args.raw);
     let mut last_line_count = 0;
     let mut stdout = io::stdout();
 
     // Set up signal handler for graceful Ctrl+C handling
     let interrupted = setup_interrupt_handler();
 
-    if format == OutputFormat::Pretty {
-        eprintln!(
-            "{} Following build log (Ctrl+C to stop)...\n",
-            "->".cyan()
-        );
-    }
+    let mut status_line = (format == OutputFormat::Pretty)
+        .then(|| StatusLine::start("Following build log (Ctrl+C to stop)"));
+    let mut steps = StepTracker::new();
 
+    let schedule = PollSchedule::start_secs(interval_secs);
     loop {
         // Check for interrupt
         if is_interrupted(&interrupted) {
-            if format == OutputFormat::Pretty {
+            if let Some(ref mut status) = status_line {
+                status.finish();
                 eprintln!("\n{} Interrupted by user", "!".yellow());
             }
             break;
@@ -106,7 +370,7 @@ fn follow_log(
             Err(_) => {
                 // Log may not be available yet
                 if build.data.is_running() {
-                    thread::sleep(Duration::from_secs(interval_secs));
+                    schedule.sleep();
                     continue;
                 }
                 return Err(RepriseError::LogNotAvailable(
@@ -121,35 +385,56 @@ fn follow_log(
 
         // Print new lines
         if !new_lines.is_empty() {
-            for line in new_lines {
-                match format {
-                    OutputFormat::Pretty => {
-                        writeln!(stdout, "{}", highlight_log_line(line))?;
-                    }
-                    OutputFormat::Json => {
-                        let json = serde_json::json!({ "line": line });
-                        writeln!(stdout, "{}", serde_json::to_string(&json)?)?;
+            let mut print = || -> Result<()> {
+                for line in new_lines {
+                    match format {
+                        OutputFormat::Pretty => {
+                            writeln!(stdout, "{}", render(line, highlighter, ansi, args.raw))?;
+                        }
+                        OutputFormat::Json => {
+                            let json = serde_json::json!({ "line": ansi.apply(line) });
+                            writeln!(stdout, "{}", serde_json::to_string(&json)?)?;
+                        }
                     }
                 }
+                stdout.flush()?;
+                Ok(())
+            };
+            match status_line {
+                Some(ref status) => status.hide_while(print)?,
+                None => print()?,
+            }
+            for line in new_lines {
+                steps.feed(line);
             }
-            stdout.flush()?;
             last_line_count = lines.len();
         }
 
+        // Show where the build is while it runs
+        if build.data.is_running() {
+            if let Some(ref mut status) = status_line {
+                if let Some(started_at) = build.data.started_on_worker_at {
+                    status.set_started_at(started_at);
+                    let workflow = &build.data.triggered_workflow;
+                    status.set_expected(eta::expected_duration(client, app_slug, workflow));
+                }
+                status.set_message(&build_progress_message(&build.data));
+                status.set_step(steps.current());
+            }
+        }
+
         // Check if build is done
         if !build.data.is_running() {
-            if format == OutputFormat::Pretty {
-                let status_msg = match build.data.status {
-                    1 => format!("\n{} Build completed successfully", "✓".green()),
-                    2 => format!("\n{} Build failed", "✗".red()),
-                    3 => format!("\n{} Build aborted", "!".yellow()),
-                    _ => format!("\n{} Build finished", "->".cyan()),
-                };
+            if let Some(ref mut status) = status_line {
+                status.finish();
+                let status_msg = format!("\n{}", finished_message("Build", build.data.status));
                 eprintln!("{}", status_msg);
             }
 
+            crate::hooks::build_finished(app_slug, &build.data);
+
             // Send desktop notification if requested
-            if send_notification {
+            if args.notify {
                 crate::notify::build_completed(&build.data, None);
             }
 
@@ -157,59 +442,19 @@ fn follow_log(
         }
 
         // Wait before next poll
-        thread::sleep(Duration::from_secs(interval_secs));
+        schedule.sleep();
     }
 
     // Return empty string since we've already printed everything
     Ok(String::new())
 }
 
-/// Highlight log lines based on content
-fn highlight_log_line(line: &str) -> String {
-    let line_lower = line.to_lowercase();
-
-    // Error patterns (red)
-    if line_lower.contains("error")
-        || line_lower.contains("failed")
-        || line_lower.contains("failure")
-        || line_lower.contains("fatal")
-        || line_lower.contains("exception")
-        || line_lower.contains("panic")
-        || line.starts_with("E ")
-        || line.contains("[ERROR]")
-        || line.contains("[error]")
-    {
-        return line.red().to_string();
-    }
-
-    // Warning patterns (yellow)
-    if line_lower.contains("warning")
-        || line_lower.contains("warn")
-        || line.starts_with("W ")
-        || line.contains("[WARN]")
-        || line.contains("[warn]")
-    {
-        return line.yellow().to_string();
-    }
-
-    // Success patterns (green)
-    if line_lower.contains("success")
-        || line_lower.contains("passed")
-        || line_lower.contains("completed")
-        || line.contains("[OK]")
-        || line.contains("BUILD SUCCESSFUL")
-    {
-        return line.green().to_string();
-    }
-
-    line.to_string()
-}
-
-/// Apply highlighting to full log content
-fn highlight_log_content(content: &str) -> String {
-    content
-        .lines()
-        .map(highlight_log_line)
-        .collect::<Vec<_>>()
-        .join("\n")
+/// Prepare log text for pretty output: raw passes through untouched,
+/// otherwise ANSI codes are handled per `ansi` and lines are highlighted
+fn render(text: &str, highlighter: &Highlighter, ansi: AnsiMode, raw: bool) -> String {
+    if raw {
+        text.to_string()
+    } else {
+        highlighter.highlight(&ansi.apply(text))
+    }
 }
@@ -0,0 +1,42 @@
+//! Man command (write man pages generated from the command definitions)
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::CommandFactory;
+use colored::Colorize;
+
+use crate::cli::args::{Cli, ManArgs, OutputFormat};
+use crate::error::Result;
+use crate::output::json;
+
+/// Handle the man command
+pub fn man(args: &ManArgs, format: OutputFormat) -> Result<String> {
+    let dir = Path::new(&args.dir);
+    fs::create_dir_all(dir)?;
+
+    let mut cli = Cli::command();
+    cli.build();
+    let mut pages = Vec::new();
+    write_pages(cli, dir, &mut pages)?;
+
+    match format {
+        OutputFormat::Pretty => Ok(format!(
+            "{} Wrote {} man pages to {}",
+            "✓".green(),
+            pages.len(),
+            dir.display()
+        )),
+        OutputFormat::Json => json::format_json(&pages),
+    }
+}
+
+/// Write the page of a command and, depth first, of its visible subcommands
+fn write_pages(command: clap::Command, dir: &Path, pages: &mut Vec<PathBuf>) -> Result<()> {
+    for sub in command.get_subcommands().filter(|c| !c.is_hide_set()) {
+        write_pages(sub.clone(), dir, pages)?;
+    }
+    let man = clap_mangen::Man::new(command).source(concat!("reprise ", env!("CARGO_PKG_VERSION")));
+    pages.push(man.generate_to(dir)?);
+    Ok(())
+}
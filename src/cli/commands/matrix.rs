@@ -0,0 +1,179 @@
+//! Matrix command (latest build status per branch and workflow)
+
+use chrono::Utc;
+use colored::Colorize;
+use serde::Serialize;
+
+use super::artifacts::matches_glob;
+use super::common::resolve_app;
+use crate::bitrise::{BitriseClient, Build};
+use crate::cli::args::{MatrixArgs, OutputFormat};
+use crate::config::Config;
+use crate::error::Result;
+use crate::output::table::{self, Table};
+use crate::output::{json, pretty, theme};
+
+/// One branch and workflow's newest build
+#[derive(Debug, Serialize)]
+struct Cell {
+    branch: String,
+    workflow: String,
+    build: Option<Build>,
+}
+
+/// Handle the matrix command
+pub fn matrix(
+    client: &BitriseClient,
+    config: &Config,
+    args: &MatrixArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    let recent = client.list_builds(app_slug, None, None, None, args.limit)?.data;
+
+    let branches = expand_branches(&args.branches, &recent);
+    let workflows = if args.workflows.is_empty() {
+        let mut seen: Vec<String> = recent
+            .iter()
+            .filter(|b| branches.contains(&b.branch))
+            .map(|b| b.triggered_workflow.clone())
+            .collect();
+        seen.sort();
+        seen.dedup();
+        seen
+    } else {
+        args.workflows.clone()
+    };
+
+    let mut cells = Vec::with_capacity(branches.len() * workflows.len());
+    for branch in &branches {
+        for workflow in &workflows {
+            let newest = recent
+                .iter()
+                .find(|b| &b.branch == branch && &b.triggered_workflow == workflow)
+                .cloned();
+            // Branches named outright are looked up past the recent builds
+            let build = match newest {
+                None if !is_pattern(branch) && args.branches.contains(branch) => client
+                    .list_builds(app_slug, None, Some(branch), Some(workflow), 1)?
+                    .data
+                    .into_iter()
+                    .next(),
+                newest => newest,
+            };
+            cells.push(Cell { branch: branch.clone(), workflow: workflow.clone(), build });
+        }
+    }
+
+    match format {
+        OutputFormat::Pretty => Ok(format_matrix(&branches, &workflows, &cells)),
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "branches": branches,
+                "workflows": workflows,
+                "cells": cells,
+            });
+            json::format_json(&json)
+        }
+    }
+}
+
+fn is_pattern(branch: &str) -> bool {
+    branch.contains(['*', '?'])
+}
+
+/// Branch names for the rows: names as given, patterns replaced by the
+/// matching branches of recent builds, and without `--branches` every
+/// branch built recently (most recently built first)
+fn expand_branches(patterns: &[String], recent: &[Build]) -> Vec<String> {
+    let mut built: Vec<&String> = Vec::new();
+    for build in recent {
+        if !built.contains(&&build.branch) {
+            built.push(&build.branch);
+        }
+    }
+    if patterns.is_empty() {
+        return built.into_iter().cloned().collect();
+    }
+
+    let mut branches: Vec<String> = Vec::new();
+    for pattern in patterns {
+        let matched: Vec<String> = if is_pattern(pattern) {
+            let mut matched: Vec<String> = built
+                .iter()
+                .filter(|branch| matches_glob(branch, pattern))
+                .map(|branch| branch.to_string())
+                .collect();
+            matched.sort();
+            matched
+        } else {
+            vec![pattern.clone()]
+        };
+        for branch in matched {
+            if !branches.contains(&branch) {
+                branches.push(branch);
+            }
+        }
+    }
+    branches
+}
+
+/// The grid: a row per branch, a column per workflow
+fn format_matrix(branches: &[String], workflows: &[String], cells: &[Cell]) -> String {
+    if branches.is_empty() || workflows.is_empty() {
+        return "No builds match these branches and workflows.".dimmed().to_string();
+    }
+
+    let mut output = String::new();
+    if table::headers_shown() {
+        output.push_str(&format!(
+            "{} ({} branches × {} workflows)\n",
+            "Build Matrix".bold(),
+            branches.len(),
+            workflows.len()
+        ));
+        output.push_str(&pretty::separator(pretty::MAX_RULE_WIDTH));
+        output.push('\n');
+    }
+
+    let headers: Vec<&str> =
+        std::iter::once("BRANCH").chain(workflows.iter().map(String::as_str)).collect();
+    let mut grid = Table::new(&headers);
+    for (branch, row) in branches.iter().zip(cells.chunks(workflows.len())) {
+        let mut line = vec![branch.bold().to_string()];
+        line.extend(row.iter().map(|cell| format_cell(cell.build.as_ref())));
+        grid.row(line);
+    }
+    output.push_str(grid.render().trim_end());
+    output
+}
+
+/// e.g. `✓ success 3h`, or a dimmed `-` without a build
+fn format_cell(build: Option<&Build>) -> String {
+    let Some(build) = build else {
+        return "-".dimmed().to_string();
+    };
+    let status = format!("{} {}", theme::symbol(build.status), build.status);
+    let age = pretty::format_span(Utc::now() - build.triggered_at);
+    format!("{} {}", theme::paint(build.status, &status), age.dimmed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(branch: &str) -> Build {
+        Build { branch: branch.to_string(), ..Build::fixture() }
+    }
+
+    #[test]
+    fn test_expand_branches() {
+        let recent = vec![build("release/2.1"), build("main"), build("release/2.0"), build("main")];
+        let all = expand_branches(&[], &recent);
+        assert_eq!(all, ["release/2.1", "main", "release/2.0"]);
+
+        let patterns = vec!["main".to_string(), "release/*".to_string(), "develop".to_string()];
+        let rows = expand_branches(&patterns, &recent);
+        assert_eq!(rows, ["main", "release/2.0", "release/2.1", "develop"]);
+    }
+}
@@ -1,26 +1,96 @@
 mod abort;
+mod activity;
+mod addons;
 mod app;
 mod apps;
 mod artifacts;
+mod benchmark;
 mod build;
 mod builds;
+mod cache;
 pub mod common;
+mod complete;
 mod config;
+mod coverage;
+mod deploys;
+mod dsyms;
+mod export;
+mod failures;
+mod help;
+mod history;
+mod install;
 mod log;
+mod man;
+mod matrix;
+mod org;
+mod otel;
+mod overview;
 mod pipeline;
 mod pipelines;
+mod pr;
+mod prompt_status;
+mod rebuild;
+mod release;
+mod report;
+mod runs;
+mod schema;
+mod stacks;
+mod status;
+#[cfg(feature = "tray")]
+mod tray;
 mod trigger;
+mod triggers;
 mod url;
+mod validate_yml;
+mod wait;
+mod wait_build;
+mod watch_daemon;
+mod yml;
 
 pub use self::abort::abort;
-pub use self::app::{app_set, app_show};
+pub use self::activity::activity;
+pub use self::addons::addons;
+pub use self::app::{app_members, app_recent, app_set, app_show, app_unset};
 pub use self::apps::apps;
 pub use self::artifacts::artifacts;
+pub use self::benchmark::benchmark;
 pub use self::build::build;
 pub use self::builds::builds;
-pub use self::config::config;
+pub use self::cache::cache;
+pub use self::complete::complete;
+pub use self::config::{config, config_lint, warn_config_issues};
+pub use self::coverage::coverage;
+pub use self::deploys::deploys;
+pub use self::dsyms::dsyms;
+pub use self::export::export;
+pub use self::failures::failures;
+pub use self::help::help;
+pub use self::history::history;
+pub use self::install::install;
 pub use self::log::log;
+pub use self::man::man;
+pub use self::matrix::matrix;
+pub use self::org::org;
+pub use self::otel::otel;
+pub use self::overview::overview;
 pub use self::pipeline::pipeline;
 pub use self::pipelines::pipelines;
-pub use self::trigger::trigger;
+pub use self::pr::pr;
+pub use self::prompt_status::prompt_status;
+pub use self::rebuild::rebuild;
+pub use self::release::release;
+pub use self::report::report;
+pub use self::runs::runs;
+pub use self::schema::schema;
+pub use self::stacks::stacks;
+pub use self::status::status;
+#[cfg(feature = "tray")]
+pub use self::tray::tray;
+pub use self::trigger::{resolve_workflow, trigger};
+pub use self::triggers::{triggers, triggers_file};
 pub use self::url::{is_generation_mode, url, url_generate};
+pub use self::validate_yml::validate_yml;
+pub use self::wait::wait;
+pub use self::wait_build::wait_build;
+pub use self::watch_daemon::watch_daemon;
+pub use self::yml::yml;
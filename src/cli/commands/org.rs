@@ -0,0 +1,114 @@
+//! Org command (organization members and roles)
+
+use colored::Colorize;
+
+use crate::bitrise::{BitriseClient, Member, Organization};
+use crate::cli::args::{OrgArgs, OrgCommands, OutputFormat};
+use crate::error::{RepriseError, Result};
+use crate::output::json;
+use crate::output::pretty;
+use crate::output::table::{self, Table};
+
+/// Handle the org command
+pub fn org(client: &BitriseClient, args: &OrgArgs, format: OutputFormat) -> Result<String> {
+    match &args.command {
+        OrgCommands::Members { org } => {
+            let org = resolve_org(client, org)?;
+            let members = client.list_organization_members(&org.slug)?.data;
+            match format {
+                OutputFormat::Pretty => Ok(format_members(&members, &org.name)),
+                OutputFormat::Json => json::format_json(&members),
+            }
+        }
+    }
+}
+
+/// Find an organization by slug or name among the user's organizations
+fn resolve_org(client: &BitriseClient, org: &str) -> Result<Organization> {
+    let orgs = client.list_organizations()?.data;
+    let needle = org.to_lowercase();
+    let found = orgs
+        .iter()
+        .find(|o| o.slug == org || o.name.to_lowercase() == needle)
+        .or_else(|| orgs.iter().find(|o| o.name.to_lowercase().contains(&needle)));
+    match found {
+        Some(found) => Ok(found.clone()),
+        // Not one of the user's: it may still be a slug they can read
+        None if !org.contains(' ') => {
+            Ok(Organization { slug: org.to_string(), name: org.to_string() })
+        }
+        None => {
+            let names: Vec<&str> = orgs.iter().map(|o| o.name.as_str()).collect();
+            Err(RepriseError::InvalidArgument(format!(
+                "No organization matches '{}'. Yours: {}",
+                org,
+                if names.is_empty() { "none".to_string() } else { names.join(", ") }
+            )))
+        }
+    }
+}
+
+/// A table of members, e.g. for an access review of `owner`
+pub(super) fn format_members(members: &[Member], owner: &str) -> String {
+    if members.is_empty() {
+        return format!("No members found for {}.", owner).dimmed().to_string();
+    }
+
+    let mut output = String::new();
+    if table::headers_shown() {
+        output.push_str(&format!("{} of {} ({})\n", "Members".bold(), owner, role_counts(members)));
+        output.push_str(&pretty::separator(pretty::MAX_RULE_WIDTH));
+        output.push('\n');
+    }
+    let mut rows = Table::new(&["USER", "ROLE", "EMAIL"]);
+    for member in members {
+        let role = member.role.as_deref().unwrap_or("-");
+        let role = match role {
+            "owner" | "admin" => role.yellow().to_string(),
+            _ => role.to_string(),
+        };
+        rows.row(vec![
+            member.username.bold().to_string(),
+            role,
+            member.email.clone().unwrap_or_else(|| "-".to_string()).dimmed().to_string(),
+        ]);
+    }
+    output.push_str(rows.render().trim_end());
+    output
+}
+
+/// e.g. `1 owner, 3 developers`, in order of first appearance
+fn role_counts(members: &[Member]) -> String {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for member in members {
+        let role = member.role.as_deref().unwrap_or("member");
+        match counts.iter_mut().find(|(r, _)| *r == role) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((role, 1)),
+        }
+    }
+    counts
+        .iter()
+        .map(|(role, count)| format!("{} {}{}", count, role, if *count == 1 { "" } else { "s" }))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_counts() {
+        let member = |role: Option<&str>| Member {
+            slug: "u".to_string(),
+            username: "user".to_string(),
+            email: None,
+            role: role.map(str::to_string),
+        };
+        let members =
+            vec![member(Some("developer")), member(Some("owner")), member(Some("developer"))];
+        assert_eq!(role_counts(&members), "2 developers, 1 owner");
+        assert_eq!(role_counts(&[member(None)]), "1 member");
+    }
+}
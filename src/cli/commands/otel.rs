@@ -0,0 +1,91 @@
+//! Otel command (send builds to an OpenTelemetry tracing backend)
+
+use colored::Colorize;
+
+use crate::bitrise::BitriseClient;
+use crate::cli::args::{OtelArgs, OtelCommands, OtelExportArgs, OutputFormat};
+use crate::config::Config;
+use crate::error::{RepriseError, Result};
+use crate::logparse::steps::step_timings;
+use crate::otel;
+use crate::output::json;
+
+use super::artifacts::resolve_build_slug;
+use super::common::{open_full_log, resolve_app, show_progress};
+
+/// Handle the otel command
+pub fn otel(
+    client: &BitriseClient,
+    config: &Config,
+    args: &OtelArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    match &args.command {
+        OtelCommands::Export(args) => otel_export(client, config, args, format),
+    }
+}
+
+/// Export a build as a trace, with a span per step from its log
+fn otel_export(
+    client: &BitriseClient,
+    config: &Config,
+    args: &OtelExportArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let mut headers = Vec::new();
+    if let Ok(spec) = std::env::var("OTEL_EXPORTER_OTLP_HEADERS") {
+        headers = otel::parse_headers(&spec).map_err(|e| {
+            RepriseError::InvalidArgument(format!("OTEL_EXPORTER_OTLP_HEADERS: {}", e))
+        })?;
+    }
+    for header in &args.headers {
+        headers.extend(otel::parse_headers(header).map_err(RepriseError::InvalidArgument)?);
+    }
+
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    let build_slug = &resolve_build_slug(
+        client,
+        app_slug,
+        &args.slug,
+        args.branch.as_deref(),
+        args.workflow.as_deref(),
+        format,
+    )?;
+    let build = client.get_build(app_slug, build_slug)?.data;
+    if build.finished_at.is_none() {
+        return Err(RepriseError::InvalidArgument(format!(
+            "Build #{} hasn't finished yet",
+            build.build_number
+        )));
+    }
+    let steps = step_timings(open_full_log(client, config, app_slug, build_slug)?)?;
+    let payload = otel::trace_payload(app_slug, &build, &steps, &args.service_name)?;
+
+    let endpoint = match args.endpoint {
+        Some(ref endpoint) if !args.dry_run => endpoint,
+        _ => return Ok(serde_json::to_string_pretty(&payload)?),
+    };
+    let url = otel::traces_url(endpoint);
+    if show_progress(format) {
+        eprintln!("{} Sending build #{} to {}", "->".cyan(), build.build_number, url);
+    }
+    otel::send(&url, &headers, &payload)?;
+
+    let trace_id = otel::trace_id(&build.slug);
+    match format {
+        OutputFormat::Pretty => Ok(format!(
+            "{} Exported build #{} with {} steps (trace {})",
+            "✓".green(),
+            build.build_number,
+            steps.len(),
+            trace_id.dimmed()
+        )),
+        OutputFormat::Json => json::format_json(&serde_json::json!({
+            "build_slug": build.slug,
+            "build_number": build.build_number,
+            "trace_id": trace_id,
+            "steps": steps.len(),
+            "url": url,
+        })),
+    }
+}
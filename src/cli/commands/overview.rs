@@ -0,0 +1,167 @@
+//! Overview command (latest and running builds across all apps)
+
+use std::thread;
+use std::time::Duration;
+
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::bitrise::{App, BitriseClient, Build, BuildStatus};
+use crate::cache::Cache;
+use crate::cli::args::{OutputFormat, OverviewArgs};
+use crate::config::Config;
+use crate::error::Result;
+use crate::output::{json, pretty, terminal, theme};
+
+use super::common::{load_apps, show_progress, APPS_CACHE_TTL};
+
+/// Maximum number of apps queried at the same time
+const MAX_CONCURRENT: usize = 8;
+
+/// Builds fetched per app (enough to spot a few concurrent runs)
+const BUILDS_PER_APP: u32 = 10;
+
+/// One app's row in the overview
+#[derive(Debug, Serialize)]
+struct AppOverview {
+    app_slug: String,
+    app_title: String,
+    running: Vec<Build>,
+    latest: Option<Build>,
+    error: Option<String>,
+}
+
+/// Handle the overview command
+pub fn overview(
+    client: &BitriseClient,
+    config: &Config,
+    args: &OverviewArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let ttl = config.cache.ttl.map_or(APPS_CACHE_TTL, Duration::from_secs);
+    let cache = Cache::for_config(&config.cache).ok();
+    let apps: Vec<App> = load_apps(client, cache.as_ref(), ttl, args.refresh)?
+        .into_iter()
+        .filter(|a| !a.is_disabled)
+        .collect();
+
+    if show_progress(format) && apps.len() > MAX_CONCURRENT {
+        eprintln!("{} Checking {} apps...", "->".cyan(), apps.len());
+    }
+
+    let mut rows = Vec::with_capacity(apps.len());
+    for chunk in apps.chunks(MAX_CONCURRENT) {
+        let chunk_rows: Vec<AppOverview> = thread::scope(|scope| {
+            let handles: Vec<_> = chunk
+                .iter()
+                .map(|app| scope.spawn(|| app_overview(client, app, args.branch.as_deref())))
+                .collect();
+            handles
+                .into_iter()
+                .zip(chunk)
+                .map(|(handle, app)| {
+                    handle.join().unwrap_or_else(|_| AppOverview {
+                        app_slug: app.slug.clone(),
+                        app_title: app.title.clone(),
+                        running: Vec::new(),
+                        latest: None,
+                        error: Some("worker thread panicked".to_string()),
+                    })
+                })
+                .collect()
+        });
+        rows.extend(chunk_rows);
+    }
+
+    if args.running {
+        rows.retain(|r| !r.running.is_empty());
+    }
+
+    // Busy apps first, then most recently built
+    rows.sort_by(|a, b| {
+        b.running
+            .len()
+            .cmp(&a.running.len())
+            .then_with(|| {
+                let a_at = a.latest.as_ref().map(|l| l.triggered_at);
+                let b_at = b.latest.as_ref().map(|l| l.triggered_at);
+                b_at.cmp(&a_at)
+            })
+    });
+
+    match format {
+        OutputFormat::Pretty => Ok(format_overview(&rows)),
+        OutputFormat::Json => json::format_json(&rows),
+    }
+}
+
+/// Fetch recent builds for one app; errors are reported in the row
+fn app_overview(client: &BitriseClient, app: &App, branch: Option<&str>) -> AppOverview {
+    let mut row = AppOverview {
+        app_slug: app.slug.clone(),
+        app_title: app.title.clone(),
+        running: Vec::new(),
+        latest: None,
+        error: None,
+    };
+
+    match client.list_builds(&app.slug, None, branch, None, BUILDS_PER_APP) {
+        Ok(response) => {
+            let (running, finished): (Vec<Build>, Vec<Build>) =
+                response.data.into_iter().partition(|b| b.is_running());
+            row.running = running;
+            row.latest = finished.into_iter().next();
+        }
+        Err(e) => row.error = Some(e.to_string()),
+    }
+
+    row
+}
+
+/// Render the overview table
+fn format_overview(rows: &[AppOverview]) -> String {
+    if rows.is_empty() {
+        return "No apps to show.".dimmed().to_string();
+    }
+
+    // App names get a quarter of the terminal, so wide ones truncate less
+    let width = rows
+        .iter()
+        .map(|r| r.app_title.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min((terminal::width() / 4).clamp(16, 40));
+
+    let running_total: usize = rows.iter().map(|r| r.running.len()).sum();
+    let mut output = format!(
+        "{} ({} apps, {} running)\n",
+        "Overview".bold(),
+        rows.len(),
+        running_total
+    );
+    output.push_str(&pretty::separator(pretty::MAX_RULE_WIDTH));
+    output.push('\n');
+
+    for row in rows {
+        let title: String = row.app_title.chars().take(width).collect();
+        let title = format!("{:<width$}", title, width = width);
+
+        let running = if row.running.is_empty() {
+            format!("{:10}", "")
+        } else {
+            let icon = theme::symbol(BuildStatus::Running);
+            let running = format!("{} {} running", icon, row.running.len());
+            theme::paint(BuildStatus::Running, &format!("{:10}", running)).to_string()
+        };
+
+        let latest = match (&row.error, &row.latest) {
+            (Some(err), _) => format!("error: {}", err).red().to_string(),
+            (None, Some(build)) => pretty::format_build_line(build),
+            (None, None) => "no builds".dimmed().to_string(),
+        };
+
+        output.push_str(&format!("{}  {}  {}\n", title.bold(), running, latest));
+    }
+
+    output.trim_end().to_string()
+}
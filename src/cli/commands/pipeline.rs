@@ -1,18 +1,22 @@
 //! Pipeline command with subcommands
 
-use std::io::{self, Write};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::thread;
-use std::time::Duration;
-
 use colored::Colorize;
 
-use crate::bitrise::{BitriseClient, Pipeline, PipelineTriggerParams};
-use crate::cli::args::{OutputFormat, PipelineArgs, PipelineCommands};
+use super::build::dump_log;
+use super::common::{
+    confirm, finished_message, is_interrupted, resolve_app, setup_interrupt_handler,
+    show_progress,
+};
+use crate::bitrise::{BitriseClient, Build, Pipeline, PipelineTriggerParams};
+use crate::cli::args::{OutputFormat, PipelineArgs, PipelineCommands, TriggerSourceArgs};
 use crate::config::Config;
 use crate::error::{RepriseError, Result};
+use crate::history;
 use crate::output;
+use crate::output::json;
+use crate::output::progress::StatusLine;
+use crate::output::theme;
+use crate::watch::{self, WatchOptions};
 
 /// Handle the pipeline command
 pub fn pipeline(
@@ -22,13 +26,16 @@ pub fn pipeline(
     format: OutputFormat,
 ) -> Result<String> {
     match &args.command {
-        Some(PipelineCommands::Show { id, app }) => {
-            pipeline_show(client, config, id, app.as_deref(), format)
-        }
+        Some(PipelineCommands::Show {
+            id,
+            app,
+            follow_failed,
+        }) => pipeline_show(client, config, id, app.as_deref(), *follow_failed, format),
         Some(PipelineCommands::Trigger {
             name,
             branch,
             app,
+            source,
             env,
             wait,
             notify,
@@ -39,6 +46,7 @@ pub fn pipeline(
             name,
             branch.as_deref(),
             app.as_deref(),
+            source,
             env,
             *wait,
             *notify,
@@ -86,7 +94,7 @@ pub fn pipeline(
         None => {
             // If no subcommand but ID provided, show pipeline details
             if let Some(ref id) = args.id {
-                pipeline_show(client, config, id, None, format)
+                pipeline_show(client, config, id, None, false, format)
             } else {
                 Err(RepriseError::InvalidArgument(
                     "Please provide a pipeline ID or use a subcommand (trigger, abort, rebuild, watch)".to_string(),
@@ -102,14 +110,92 @@ fn pipeline_show(
     config: &Config,
     pipeline_id: &str,
     app: Option<&str>,
+    follow_failed: bool,
     format: OutputFormat,
 ) -> Result<String> {
-    let app_slug = app
-        .map(Ok)
-        .unwrap_or_else(|| config.require_default_app())?;
+    let app_slug = &resolve_app(client, app, config)?;
+
+    let pipeline = client.get_pipeline(app_slug, pipeline_id)?.into_pipeline();
+    let builds = resolve_workflow_builds(client, app_slug, &pipeline);
+
+    if follow_failed {
+        let (wf, build) = pipeline
+            .workflows
+            .iter()
+            .zip(&builds)
+            .find_map(|(wf, b)| b.as_ref().filter(|b| b.is_failed()).map(|b| (wf, b)))
+            .ok_or_else(|| {
+                RepriseError::BuildNotFound(format!(
+                    "no failed workflow build in pipeline {}",
+                    pipeline.id
+                ))
+            })?;
+        if show_progress(format) {
+            eprintln!(
+                "{} Log for failed workflow {} (build #{})\n",
+                "->".cyan(),
+                wf.name.bold(),
+                build.build_number
+            );
+        }
+        return dump_log(client, config, app_slug, &build.slug, format);
+    }
 
-    let response = client.get_pipeline(app_slug, pipeline_id)?;
-    output::format_pipeline(&response.into_pipeline(), format)
+    match format {
+        OutputFormat::Pretty => Ok(output::pretty::format_pipeline_with_builds(&pipeline, &builds)),
+        OutputFormat::Json => {
+            let mut value = serde_json::to_value(&pipeline)?;
+            value["workflow_builds"] = pipeline
+                .workflows
+                .iter()
+                .zip(&builds)
+                .map(|(wf, build)| {
+                    serde_json::json!({
+                        "workflow": wf.name,
+                        "status": wf.status_display(),
+                        "build_slug": build.as_ref().map(|b| &b.slug),
+                        "build_number": build.as_ref().map(|b| b.build_number),
+                        "duration_secs": build.as_ref().and_then(|b| b.duration()).map(|d| d.num_seconds()),
+                        "url": build.as_ref().map(|b| format!("https://app.bitrise.io/build/{}", b.slug)),
+                    })
+                })
+                .collect();
+            json::format_json(&value)
+        }
+    }
+}
+
+/// Find the build behind each pipeline workflow, in workflow order
+///
+/// A pipeline workflow's ID is the slug of the build that ran it. If that
+/// lookup fails, fall back to the oldest build of the workflow on the
+/// pipeline's branch triggered after the pipeline was. Workflows that haven't
+/// started yet have no build.
+fn resolve_workflow_builds(
+    client: &BitriseClient,
+    app_slug: &str,
+    pipeline: &Pipeline,
+) -> Vec<Option<Build>> {
+    let branch = Some(pipeline.get_branch()).filter(|b| !b.is_empty());
+
+    pipeline
+        .workflows
+        .iter()
+        .map(|wf| {
+            if !wf.id.is_empty() {
+                if let Ok(response) = client.get_build(app_slug, &wf.id) {
+                    return Some(response.data);
+                }
+            }
+            client
+                .list_builds(app_slug, None, branch, Some(&wf.name), 10)
+                .ok()?
+                .data
+                .into_iter()
+                .rev()
+                .find(|b| pipeline.triggered_at.is_none_or(|t| b.triggered_at >= t))
+        })
+        .collect()
 }
 
 /// Trigger a new pipeline
@@ -120,23 +206,26 @@ fn pipeline_trigger(
     name: &str,
     branch: Option<&str>,
     app: Option<&str>,
+    source: &TriggerSourceArgs,
     env: &[(String, String)],
     wait: bool,
     send_notification: bool,
     interval_secs: u64,
     format: OutputFormat,
 ) -> Result<String> {
-    let app_slug = app
-        .map(Ok)
-        .unwrap_or_else(|| config.require_default_app())?;
+    let app_slug = &resolve_app(client, app, config)?;
 
     let params = PipelineTriggerParams {
         pipeline_id: name.to_string(),
         branch: branch.map(String::from),
+        source: source.to_source(),
         environments: env.to_vec(),
     };
 
-    let pipeline = client.trigger_pipeline(app_slug, params)?;
+    let result = client.trigger_pipeline(app_slug, params);
+    let id = result.as_ref().ok().map(|p| p.id.as_str());
+    history::record("pipeline trigger", app_slug, id, &result);
+    let pipeline = result?;
 
     // Print initial status (to stderr so stdout can be piped)
     if format == OutputFormat::Pretty {
@@ -171,7 +260,7 @@ fn pipeline_trigger(
     match format {
         OutputFormat::Pretty => Ok(String::new()), // Already printed above
         OutputFormat::Json => {
-            let json = serde_json::to_string_pretty(&pipeline)?;
+            let json = json::format_json(&pipeline)?;
             Ok(json)
         }
     }
@@ -187,27 +276,16 @@ fn pipeline_abort(
     skip_confirmation: bool,
     format: OutputFormat,
 ) -> Result<String> {
-    let app_slug = app
-        .map(Ok)
-        .unwrap_or_else(|| config.require_default_app())?;
+    let app_slug = &resolve_app(client, app, config)?;
 
     // Confirm unless --yes flag is provided
-    if !skip_confirmation {
-        eprint!(
-            "Are you sure you want to abort pipeline {}? [y/N] ",
-            pipeline_id
-        );
-        io::stderr().flush()?;
-
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-
-        if !input.trim().eq_ignore_ascii_case("y") {
-            return Ok("Aborted.".to_string());
-        }
+    if !skip_confirmation && !confirm(&format!("Abort pipeline {}?", pipeline_id), "--yes")? {
+        return Ok("Aborted.".to_string());
     }
 
-    client.abort_pipeline(app_slug, pipeline_id, reason)?;
+    let result = client.abort_pipeline(app_slug, pipeline_id, reason);
+    history::record("pipeline abort", app_slug, Some(pipeline_id), &result);
+    result?;
 
     match format {
         OutputFormat::Pretty => {
@@ -222,7 +300,7 @@ fn pipeline_abort(
                 "status": "aborted",
                 "pipeline_id": pipeline_id,
             });
-            Ok(serde_json::to_string_pretty(&result)?)
+            json::format_json(&result)
         }
     }
 }
@@ -240,11 +318,11 @@ fn pipeline_rebuild(
     interval_secs: u64,
     format: OutputFormat,
 ) -> Result<String> {
-    let app_slug = app
-        .map(Ok)
-        .unwrap_or_else(|| config.require_default_app())?;
+    let app_slug = &resolve_app(client, app, config)?;
 
-    let pipeline = client.rebuild_pipeline(app_slug, pipeline_id, partial)?;
+    let result = client.rebuild_pipeline(app_slug, pipeline_id, partial);
+    history::record("pipeline rebuild", app_slug, Some(pipeline_id), &result);
+    let pipeline = result?;
 
     // Print initial status (to stderr so stdout can be piped)
     if format == OutputFormat::Pretty {
@@ -277,7 +355,7 @@ fn pipeline_rebuild(
     match format {
         OutputFormat::Pretty => Ok(String::new()), // Already printed above
         OutputFormat::Json => {
-            let json = serde_json::to_string_pretty(&pipeline)?;
+            let json = json::format_json(&pipeline)?;
             Ok(json)
         }
     }
@@ -293,12 +371,10 @@ fn pipeline_watch(
     send_notification: bool,
     format: OutputFormat,
 ) -> Result<String> {
-    let app_slug = app
-        .map(Ok)
-        .unwrap_or_else(|| config.require_default_app())?;
+    let app_slug = &resolve_app(client, app, config)?;
 
     // Initial display
-    if format == OutputFormat::Pretty {
+    if show_progress(format) {
         eprintln!("{} Watching pipeline {} (Ctrl+C to stop)...", "->".cyan(), pipeline_id);
     }
 
@@ -312,34 +388,6 @@ fn pipeline_watch(
     )
 }
 
-/// Fetch pipeline with retry logic for transient server errors
-fn get_pipeline_with_retry(
-    client: &BitriseClient,
-    app_slug: &str,
-    pipeline_id: &str,
-    max_retries: u32,
-) -> Result<Pipeline> {
-    let mut attempt = 0;
-    loop {
-        match client.get_pipeline(app_slug, pipeline_id) {
-            Ok(response) => return Ok(response.into_pipeline()),
-            Err(e) => {
-                // Only retry on 5xx server errors
-                let should_retry =
-                    matches!(&e, RepriseError::Api { status, .. } if *status >= 500);
-
-                if should_retry && attempt < max_retries {
-                    attempt += 1;
-                    let backoff = Duration::from_secs(1 << (attempt - 1)); // 1, 2, 4, 8, 16s
-                    thread::sleep(backoff);
-                    continue;
-                }
-                return Err(e);
-            }
-        }
-    }
-}
-
 /// Wait for a pipeline to complete
 fn wait_for_pipeline(
     client: &BitriseClient,
@@ -350,108 +398,90 @@ fn wait_for_pipeline(
     format: OutputFormat,
 ) -> Result<String> {
     // Set up signal handler for graceful Ctrl+C handling
-    let interrupted = Arc::new(AtomicBool::new(false));
-    let interrupted_clone = Arc::clone(&interrupted);
+    let interrupted = setup_interrupt_handler();
 
-    ctrlc::set_handler(move || {
-        interrupted_clone.store(true, Ordering::SeqCst);
-    })
-    .ok(); // Ignore error if handler already set
+    let mut status_line = (format == OutputFormat::Pretty)
+        .then(|| StatusLine::start("Waiting for pipeline to complete (Ctrl+C to stop)"));
 
-    if format == OutputFormat::Pretty {
-        eprintln!(
-            "\n{} Waiting for pipeline to complete (Ctrl+C to stop)...",
-            "->".cyan()
-        );
+    let options = WatchOptions::new(interval_secs);
+    let finished = watch::watch_pipeline(client, app_slug, pipeline_id, &options, |pipeline| {
+        // Still running - show progress
+        if let Some(ref mut line) = status_line {
+            if let Some(started_at) = pipeline.started_at {
+                line.set_started_at(started_at);
+            }
+            line.set_message(&pipeline_progress_message(pipeline));
+        }
+        !is_interrupted(&interrupted)
+    })?;
+    if let Some(ref mut line) = status_line {
+        line.finish();
     }
 
-    loop {
-        // Check for interrupt
-        if interrupted.load(Ordering::SeqCst) {
-            if format == OutputFormat::Pretty {
-                eprintln!(
-                    "\n{} Interrupted - pipeline continues in background",
-                    "!".yellow()
-                );
-                eprintln!(
-                    "  View at: https://app.bitrise.io/app/{}/pipelines/{}",
-                    app_slug, pipeline_id
-                );
-            }
-            return Ok(String::new());
+    let Some(pipeline) = finished else {
+        if format == OutputFormat::Pretty {
+            eprintln!(
+                "{} Interrupted - pipeline continues in background",
+                "!".yellow()
+            );
+            eprintln!(
+                "  View at: https://app.bitrise.io/app/{}/pipelines/{}",
+                app_slug, pipeline_id
+            );
         }
+        return Ok(String::new());
+    };
 
-        thread::sleep(Duration::from_secs(interval_secs));
+    // Pipeline finished
+    if send_notification {
+        crate::notify::pipeline_completed(&pipeline);
+    }
 
-        let pipeline = get_pipeline_with_retry(client, app_slug, pipeline_id, 5)?;
+    match format {
+        OutputFormat::Pretty => {
+            let status_msg = format!("\n{}", finished_message("Pipeline", pipeline.status));
 
-        if !pipeline.is_running() {
-            // Pipeline finished
-            if send_notification {
-                notify_pipeline_completed(&pipeline);
+            let mut output = status_msg;
+            output.push_str(&format!("\n  Duration: {}", pipeline.duration_display()));
+
+            if let Some(ref reason) = pipeline.abort_reason {
+                output.push_str(&format!("\n  Reason:   {}", reason));
             }
 
-            return match format {
-                OutputFormat::Pretty => {
-                    let status_msg = match pipeline.status {
-                        1 => format!("\n{} Pipeline completed successfully!", "✓".green()),
-                        2 => format!("\n{} Pipeline failed", "✗".red()),
-                        3 => format!("\n{} Pipeline aborted", "!".yellow()),
-                        _ => format!("\n{} Pipeline finished", "->".cyan()),
-                    };
-
-                    let mut output = status_msg;
-                    output.push_str(&format!("\n  Duration: {}", pipeline.duration_display()));
-
-                    if let Some(ref reason) = pipeline.abort_reason {
-                        output.push_str(&format!("\n  Reason:   {}", reason));
-                    }
-
-                    // Show workflow statuses
-                    if !pipeline.workflows.is_empty() {
-                        output.push_str("\n\n  Workflows:");
-                        for wf in &pipeline.workflows {
-                            let wf_status = match wf.status {
-                                1 => "✓".green(),
-                                2 => "✗".red(),
-                                3 => "○".dimmed(),
-                                _ => "?".dimmed(),
-                            };
-                            output.push_str(&format!("\n    {} {}", wf_status, wf.name));
-                        }
-                    }
-
-                    Ok(output)
-                }
-                OutputFormat::Json => {
-                    let json = serde_json::to_string_pretty(&pipeline)?;
-                    Ok(json)
+            // Show workflow statuses
+            if !pipeline.workflows.is_empty() {
+                output.push_str("\n\n  Workflows:");
+                for wf in &pipeline.workflows {
+                    let wf_status = theme::icon(wf.status);
+                    output.push_str(&format!("\n    {} {}", wf_status, wf.name));
                 }
-            };
-        }
+            }
 
-        // Still running - show progress
-        if format == OutputFormat::Pretty {
-            eprint!(".");
+            Ok(output)
+        }
+        OutputFormat::Json => {
+            let json = json::format_json(&pipeline)?;
+            Ok(json)
         }
     }
 }
 
-/// Send desktop notification when pipeline completes
-fn notify_pipeline_completed(pipeline: &crate::bitrise::Pipeline) {
-    let (title, body) = match pipeline.status {
-        1 => ("Pipeline Succeeded", format!("Pipeline {} completed successfully", pipeline.pipeline_id)),
-        2 => ("Pipeline Failed", format!("Pipeline {} failed", pipeline.pipeline_id)),
-        3 => ("Pipeline Aborted", format!("Pipeline {} was aborted", pipeline.pipeline_id)),
-        _ => ("Pipeline Finished", format!("Pipeline {} finished", pipeline.pipeline_id)),
-    };
+/// Status line text for a running pipeline: progress and the workflows in flight
+fn pipeline_progress_message(pipeline: &Pipeline) -> String {
+    let (succeeded, total) = pipeline.workflow_progress();
+    let mut message = format!("Pipeline {} {}", pipeline.pipeline_id, pipeline.status_display());
+    if total > 0 {
+        message.push_str(&format!(" · {}/{} workflows", succeeded, total));
+    }
 
-    if let Err(e) = notify_rust::Notification::new()
-        .summary(title)
-        .body(&body)
-        .timeout(notify_rust::Timeout::Milliseconds(5000))
-        .show()
-    {
-        eprintln!("Failed to send notification: {}", e);
+    let running: Vec<&str> = pipeline
+        .workflows
+        .iter()
+        .filter(|wf| wf.status.is_running())
+        .map(|wf| wf.name.as_str())
+        .collect();
+    if !running.is_empty() {
+        message.push_str(&format!(" · {}", running.join(", ")));
     }
+    message
 }
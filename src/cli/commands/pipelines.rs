@@ -1,11 +1,13 @@
 //! List pipelines command
 
-use super::common::{get_github_username, matches_user, resolve_app_slug};
-use crate::bitrise::BitriseClient;
+use super::common::{
+    matches_user, me_filter, resolve_app, search_pipelines_in_window, warn_paged_limit,
+};
+use crate::bitrise::{BitriseClient, MAX_PAGE_SIZE};
 use crate::cli::args::{OutputFormat, PipelinesArgs};
 use crate::config::Config;
-use crate::duration::parse_since;
-use crate::error::{RepriseError, Result};
+use crate::duration::TimeWindow;
+use crate::error::Result;
 use crate::output;
 
 /// Handle the pipelines command
@@ -16,63 +18,63 @@ pub fn pipelines(
     format: OutputFormat,
 ) -> Result<String> {
     // Resolve app slug from args or config default
-    let app_slug = resolve_app_slug(args.app.as_deref(), config)?;
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
 
     // Resolve triggered_by filter (--me uses API to get current user + GitHub username)
-    let me_filter: Option<(String, Option<String>)> = if args.me {
-        let user = client.get_me().map_err(|e| {
-            RepriseError::Config(format!(
-                "Cannot determine current user for --me flag: {}. Use --triggered-by <username> instead.",
-                e
-            ))
-        })?;
-        let github_username = get_github_username();
-
-        // Warn if GitHub username not configured (webhook-triggered builds won't match)
-        if github_username.is_none() && format != OutputFormat::Json {
-            eprintln!(
-                "hint: GitHub username not configured. Webhook-triggered builds may not be matched.\n\
-                 hint: Run: git config --global github.user YOUR_GITHUB_USERNAME\n"
-            );
-        }
-
-        Some((user.data.username, github_username))
-    } else {
-        None
-    };
+    let me_filter = if args.me { Some(me_filter(client, config, format)?) } else { None };
 
     let triggered_by_filter = args.triggered_by.clone();
 
     // Status filter needs to be applied client-side (API doesn't support it)
-    let status_filter = args.status.map(|s| s.to_api_code());
-
-    // Fetch extra pipelines when filtering client-side to ensure we have enough results
-    // Cap at 50 (API maximum)
-    let needs_client_filter =
-        me_filter.is_some() || triggered_by_filter.is_some() || status_filter.is_some();
+    let status_filter = args.status.map(|s| s.to_status());
+
+    // Fetch extra pipelines when filtering client-side to ensure we have
+    // enough results, up to a page beyond the limit
+    warn_paged_limit(args.limit);
+    let needs_client_filter = me_filter.is_some()
+        || triggered_by_filter.is_some()
+        || status_filter.is_some()
+        || args.workflow.is_some();
     let fetch_limit = if needs_client_filter {
-        args.limit.saturating_mul(4).min(50)
+        args.limit.saturating_mul(4).min(args.limit.max(MAX_PAGE_SIZE))
     } else {
-        args.limit.min(50)
+        args.limit
     };
 
-    let response = client.list_pipelines(
-        app_slug,
-        None, // Status filtering not supported by API, filter client-side
-        args.branch.as_deref(),
-        fetch_limit,
-    )?;
-
-    // Parse --since threshold if provided
-    let since_threshold = args
-        .since
-        .as_ref()
-        .map(|s| parse_since(s))
-        .transpose()?;
+    // Parse --since/--until window if provided
+    let window = TimeWindow::from_args(args.since.as_deref(), args.until.as_deref())?;
+
+    // A time window may reach past the first page, so search history for it
+    let fetched = match window {
+        Some(ref w) => {
+            let search_limit = if needs_client_filter {
+                args.limit.saturating_mul(4)
+            } else {
+                args.limit
+            };
+            search_pipelines_in_window(
+                client,
+                app_slug,
+                args.branch.as_deref(),
+                search_limit as usize,
+                w,
+                |_| true,
+            )?
+        }
+        None => {
+            client
+                .list_pipelines(
+                    app_slug,
+                    None, // Status filtering not supported by API, filter client-side
+                    args.branch.as_deref(),
+                    fetch_limit,
+                )?
+                .data
+        }
+    };
 
     // Apply filters client-side
-    let pipelines: Vec<_> = response
-        .data
+    let pipelines: Vec<_> = fetched
         .into_iter()
         .filter(|p| {
             // Filter by status if specified
@@ -82,6 +84,13 @@ pub fn pipelines(
                 }
             }
 
+            // Filter by --workflow (pipelines containing that workflow)
+            if let Some(ref workflow) = args.workflow {
+                if !p.has_workflow(workflow) {
+                    return false;
+                }
+            }
+
             // Filter by --me flag (match both Bitrise username and webhook-github/<github-username>)
             if let Some((ref bitrise_username, ref github_username)) = me_filter {
                 if !p
@@ -107,17 +116,6 @@ pub fn pipelines(
                 }
             }
 
-            // Filter by --since threshold
-            if let Some(threshold) = since_threshold {
-                if let Some(triggered_at) = p.triggered_at {
-                    if triggered_at < threshold {
-                        return false;
-                    }
-                } else {
-                    return false; // No triggered_at, exclude
-                }
-            }
-
             true
         })
         .take(args.limit as usize)
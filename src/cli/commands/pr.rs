@@ -0,0 +1,124 @@
+//! Pull request summary command
+
+use colored::Colorize;
+
+use super::common::{resolve_app, search_builds};
+use crate::bitrise::{BitriseClient, Build, BuildStatus, Pipeline};
+use crate::cli::args::{OutputFormat, PrArgs};
+use crate::config::Config;
+use crate::error::Result;
+use crate::output::{json, pretty, theme};
+
+/// Handle the pr command
+pub fn pr(
+    client: &BitriseClient,
+    config: &Config,
+    args: &PrArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+
+    let builds = search_builds(client, app_slug, None, None, None, args.limit as usize, |b| {
+        b.pull_request_id == Some(args.number)
+    })?;
+
+    // Pipelines don't carry a PR number; match them by the PR's source branch
+    let source_branch = builds.first().map(|b| b.branch.clone());
+    let pipelines: Vec<Pipeline> = match source_branch {
+        Some(ref branch) => client
+            .list_pipelines(app_slug, None, Some(branch), 50)
+            .map(|r| r.data)
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
+
+    let target_branch = builds
+        .iter()
+        .find_map(|b| b.pull_request_target_branch.clone());
+
+    match format {
+        OutputFormat::Pretty => {
+            if builds.is_empty() {
+                return Ok(format!(
+                    "No builds found for PR #{} in recent history.",
+                    args.number
+                ));
+            }
+
+            let mut output = format!("{}", format!("PR #{}", args.number).bold());
+            if let Some(ref source) = source_branch {
+                output.push_str(&format!(
+                    "  {} -> {}",
+                    source,
+                    target_branch.as_deref().unwrap_or("?")
+                ));
+            }
+            output.push('\n');
+            output.push_str(&format!("{}\n\n", summarize(&builds)));
+
+            output.push_str(&format!("{} ({})\n", "Builds".bold(), builds.len()));
+            for build in &builds {
+                output.push_str(&format!(
+                    "  {}  {}\n",
+                    pretty::format_build_line(build),
+                    build.slug.dimmed()
+                ));
+            }
+
+            if !pipelines.is_empty() {
+                output.push_str(&format!("\n{} ({})\n", "Pipelines".bold(), pipelines.len()));
+                for p in &pipelines {
+                    let status = theme::label(p.status);
+                    output.push_str(&format!(
+                        "  {:12} {} {} {}\n",
+                        status,
+                        p.pipeline_id,
+                        p.duration_display().dimmed(),
+                        p.id.dimmed()
+                    ));
+                }
+            }
+
+            Ok(output.trim_end().to_string())
+        }
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "pull_request": args.number,
+                "source_branch": source_branch,
+                "target_branch": target_branch,
+                "builds": builds,
+                "pipelines": pipelines,
+            });
+            json::format_json(&json)
+        }
+    }
+}
+
+/// Summarize the latest result per workflow (e.g. "2 passing, 1 failing, 1 running")
+fn summarize(builds: &[Build]) -> String {
+    // Builds are newest first, so the first one seen per workflow is the latest
+    let mut seen = std::collections::HashSet::new();
+    let (mut passing, mut failing, mut running) = (0, 0, 0);
+    for build in builds {
+        if !seen.insert(build.triggered_workflow.as_str()) {
+            continue;
+        }
+        match build.status {
+            BuildStatus::Running => running += 1,
+            BuildStatus::Success => passing += 1,
+            _ => failing += 1,
+        }
+    }
+
+    let mut parts = Vec::new();
+    if passing > 0 {
+        parts.push(format!("{} passing", passing).green().to_string());
+    }
+    if failing > 0 {
+        parts.push(format!("{} failing", failing).red().to_string());
+    }
+    if running > 0 {
+        parts.push(format!("{} running", running).yellow().to_string());
+    }
+    format!("Latest per workflow: {}", parts.join(", "))
+}
@@ -0,0 +1,135 @@
+//! Prompt status command (compact, cache-backed build status for shell prompts)
+
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use chrono::Utc;
+
+use super::common::resolve_app_slug;
+use crate::bitrise::{BitriseClient, Build};
+use crate::cache::Cache;
+use crate::cli::args::{OutputFormat, PromptStatusArgs};
+use crate::config::Config;
+use crate::error::Result;
+use crate::git;
+use crate::output::{json, theme};
+
+/// Minimum delay between background refreshes for the same branch
+const REFRESH_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Handle the prompt-status command
+///
+/// Never blocks on the network unless `--refresh` is given. Callers should
+/// treat errors as "print nothing" so a broken setup never breaks the prompt.
+pub fn prompt_status(
+    token: Option<&str>,
+    args: &PromptStatusArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let config = Config::load()?;
+    let app_slug = resolve_app_slug(args.app.as_deref(), &config)?;
+    let branch = match args.branch.clone().or_else(git::current_branch) {
+        Some(b) => b,
+        None => return Ok(String::new()),
+    };
+
+    let cache = Cache::for_config(&config.cache)?;
+    let key = format!("prompt-status-{}-{}", app_slug, branch);
+
+    if args.refresh {
+        let client = match token {
+            Some(t) => BitriseClient::with_token(t)?,
+            None => BitriseClient::new(&config)?,
+        };
+        let latest = client
+            .list_builds(app_slug, None, Some(&branch), None, 1)?
+            .data
+            .into_iter()
+            .next();
+        cache.put(&key, &latest)?;
+        return Ok(String::new());
+    }
+
+    let entry = cache.read::<Option<Build>>(&key);
+    let stale = !entry
+        .as_ref()
+        .is_some_and(|e| e.is_fresh(Duration::from_secs(args.ttl)));
+
+    if stale {
+        spawn_refresh(&cache, &key, token, app_slug, &branch);
+    }
+
+    let build = entry.and_then(|e| e.value);
+
+    match format {
+        OutputFormat::Pretty => Ok(build.as_ref().map(format_compact).unwrap_or_default()),
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "app_slug": app_slug,
+                "branch": branch,
+                "stale": stale,
+                "build": build,
+            });
+            json::format_json(&json)
+        }
+    }
+}
+
+/// Start a detached `prompt-status --refresh` unless one ran recently
+fn spawn_refresh(cache: &Cache, key: &str, token: Option<&str>, app_slug: &str, branch: &str) {
+    let marker = format!("{}.refreshing", key);
+    if cache.get::<()>(&marker, REFRESH_BACKOFF).is_some() {
+        return;
+    }
+    if cache.put(&marker, &()).is_err() {
+        return;
+    }
+
+    let Ok(exe) = std::env::current_exe() else {
+        return;
+    };
+
+    let mut cmd = Command::new(exe);
+    cmd.args(["prompt-status", "--refresh", "--app", app_slug, "--branch", branch])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    // Pass the token through the environment so it never appears in `ps`
+    if let Some(t) = token {
+        cmd.env("BITRISE_TOKEN", t);
+    }
+
+    let _ = cmd.spawn();
+}
+
+/// Format a build as e.g. `✓ #4211 3m`
+fn format_compact(build: &Build) -> String {
+    let symbol = theme::icon(build.status);
+
+    // Running builds show elapsed time so far
+    let secs = match build.duration() {
+        Some(d) => Some(d.num_seconds()),
+        None if build.is_running() => build
+            .started_on_worker_at
+            .map(|start| (Utc::now() - start).num_seconds()),
+        None => None,
+    };
+
+    match secs {
+        Some(s) => format!("{} #{} {}", symbol, build.build_number, compact_duration(s)),
+        None => format!("{} #{}", symbol, build.build_number),
+    }
+}
+
+/// Format seconds as a short duration (`45s`, `3m`, `1h2m`)
+fn compact_duration(secs: i64) -> String {
+    let secs = secs.max(0);
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}h{}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
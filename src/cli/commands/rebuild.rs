@@ -0,0 +1,86 @@
+//! Rebuild command
+
+use colored::Colorize;
+
+use super::common::{resolve_app, show_progress};
+use super::url::watch_build_with_app;
+use crate::bitrise::{BitriseClient, Build};
+use crate::cli::args::{OutputFormat, RebuildArgs};
+use crate::config::Config;
+use crate::error::Result;
+use crate::history;
+use crate::output::json;
+
+/// Handle the rebuild command
+pub fn rebuild(
+    client: &BitriseClient,
+    config: &Config,
+    args: &RebuildArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    let build = client.get_build(app_slug, &args.slug)?.data;
+    let wait = args.wait.then_some((args.interval, args.notify));
+    rebuild_and_report(client, app_slug, &build, wait, format)
+}
+
+/// Trigger `build` again with its original parameters, then report the new
+/// build or, with `wait` as `(interval, notify)`, watch it to completion
+pub(super) fn rebuild_and_report(
+    client: &BitriseClient,
+    app_slug: &str,
+    build: &Build,
+    wait: Option<(u64, bool)>,
+    format: OutputFormat,
+) -> Result<String> {
+    let result = client.rebuild_build(app_slug, build);
+    let slug = result.as_ref().map_or(&build.slug, |b| &b.slug);
+    history::record("rebuild", app_slug, Some(slug), &result);
+    let new_build = result?;
+
+    let new_build_slug = &new_build.slug;
+    let new_build_number = new_build.build_number;
+
+    if let Some((interval, notify)) = wait {
+        if show_progress(format) {
+            eprintln!(
+                "{} Triggered rebuild #{}, waiting for completion...\n",
+                "✓".green(),
+                new_build_number
+            );
+        }
+        return watch_build_with_app(client, app_slug, new_build_slug, interval, notify, format);
+    }
+
+    match format {
+        OutputFormat::Pretty => {
+            let mut output = String::new();
+            output.push_str(&format!("{} Triggered rebuild\n", "✓".green()));
+            output.push_str(&format!(
+                "  Original: #{} ({}, {})\n",
+                build.build_number, build.triggered_workflow, build.branch
+            ));
+            output.push_str(&format!(
+                "  New:      #{} (slug: {})\n",
+                new_build_number, new_build_slug
+            ));
+            output.push_str(&format!(
+                "\n  URL: https://app.bitrise.io/build/{}",
+                new_build_slug
+            ));
+            Ok(output)
+        }
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "status": "triggered",
+                "original_build_number": build.build_number,
+                "new_build_number": new_build_number,
+                "new_build_slug": new_build_slug,
+                "workflow": build.triggered_workflow,
+                "branch": build.branch,
+                "url": format!("https://app.bitrise.io/build/{}", new_build_slug)
+            });
+            json::format_json(&json)
+        }
+    }
+}
@@ -0,0 +1,226 @@
+//! Release command (Release Management releases of an app)
+
+use colored::Colorize;
+
+use crate::bitrise::{BitriseClient, Release};
+use crate::cli::args::{OutputFormat, ReleaseAppArgs, ReleaseArgs, ReleaseCommands};
+use crate::config::Config;
+use crate::error::{RepriseError, Result};
+use crate::output::json;
+use crate::output::pretty;
+use crate::output::table::{self, Table};
+
+use super::common::{confirm, resolve_app};
+
+/// Handle the release command
+pub fn release(
+    client: &BitriseClient,
+    config: &Config,
+    args: &ReleaseArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    match &args.command {
+        ReleaseCommands::List { target } => {
+            let connected_app = connected_app(client, config, target)?;
+            let releases = client.list_releases(&connected_app)?.items;
+            match format {
+                OutputFormat::Pretty => Ok(format_releases(&releases, &connected_app)),
+                OutputFormat::Json => json::format_json(&releases),
+            }
+        }
+        ReleaseCommands::Show { release, target } => {
+            let connected_app = connected_app(client, config, target)?;
+            let release = find_release(client, &connected_app, release)?;
+            match format {
+                OutputFormat::Pretty => Ok(format_release(&release)),
+                OutputFormat::Json => json::format_json(&release),
+            }
+        }
+        ReleaseCommands::Create { version, name, description, target } => {
+            let connected_app = connected_app(client, config, target)?;
+            let release = client.create_release(
+                &connected_app,
+                version,
+                name.as_deref(),
+                description.as_deref(),
+            )?;
+            match format {
+                OutputFormat::Pretty => Ok(format!(
+                    "{} Release {} created\n\n{}",
+                    "✓".green(),
+                    release.version.bold(),
+                    format_release(&release)
+                )),
+                OutputFormat::Json => json::format_json(&release),
+            }
+        }
+        ReleaseCommands::AddBuild { release, build, yes, target } => {
+            let app_slug = resolve_app(client, target.app.as_deref(), config)?;
+            let connected_app = connected_app(client, config, target)?;
+            let release = find_release(client, &connected_app, release)?;
+            // Check the build exists before touching the release
+            let build = client.get_build(&app_slug, build)?.data;
+
+            if let Some(ref candidate) = release.release_candidate {
+                if candidate.build_slug != build.slug && !yes && format == OutputFormat::Pretty {
+                    let question = format!(
+                        "Replace the release candidate of {} with build #{}?",
+                        release.version, build.build_number
+                    );
+                    if !confirm(&question, "--yes")? {
+                        return Ok("Aborted.".to_string());
+                    }
+                }
+            }
+
+            let release = client.add_release_build(&connected_app, &release.id, &build.slug)?;
+            match format {
+                OutputFormat::Pretty => Ok(format!(
+                    "{} Build #{} is the release candidate of {}",
+                    "✓".green(),
+                    build.build_number.to_string().bold(),
+                    release.version.bold()
+                )),
+                OutputFormat::Json => json::format_json(&release),
+            }
+        }
+    }
+}
+
+/// The connected app releases are read from: `--connected-app`, or the one
+/// Release Management connected to the Bitrise app
+fn connected_app(
+    client: &BitriseClient,
+    config: &Config,
+    target: &ReleaseAppArgs,
+) -> Result<String> {
+    if let Some(ref id) = target.connected_app {
+        return Ok(id.clone());
+    }
+    let app_slug = resolve_app(client, target.app.as_deref(), config)?;
+    let connected = client.list_connected_apps(&app_slug)?.items;
+    match connected.as_slice() {
+        [] => Err(RepriseError::AppNotFound(format!(
+            "{} is not connected to Release Management",
+            app_slug
+        ))),
+        [only] => Ok(only.id.clone()),
+        several => {
+            let choices: Vec<String> = several
+                .iter()
+                .map(|app| {
+                    let label = app.store_app_name.as_deref().or(app.platform.as_deref());
+                    match label {
+                        Some(label) => format!("{} ({})", app.id, label),
+                        None => app.id.clone(),
+                    }
+                })
+                .collect();
+            Err(RepriseError::InvalidArgument(format!(
+                "{} has {} connected apps; choose one with --connected-app: {}",
+                app_slug,
+                several.len(),
+                choices.join(", ")
+            )))
+        }
+    }
+}
+
+/// A release by ID, or by version among the connected app's releases
+fn find_release(client: &BitriseClient, connected_app: &str, wanted: &str) -> Result<Release> {
+    let releases = client.list_releases(connected_app)?.items;
+    releases
+        .into_iter()
+        .find(|r| r.id == wanted || r.version == wanted)
+        .ok_or_else(|| {
+            RepriseError::InvalidArgument(format!(
+                "No release '{}'; run 'reprise release list' to see them",
+                wanted
+            ))
+        })
+}
+
+/// Build number of a release's candidate, e.g. "#812", or "-" without one
+fn candidate_label(release: &Release) -> String {
+    match release.release_candidate {
+        Some(ref candidate) => match candidate.build_number {
+            Some(ref number) => format!("#{}", number),
+            None => candidate.build_slug.clone(),
+        },
+        None => "-".to_string(),
+    }
+}
+
+/// Render releases as a table
+fn format_releases(releases: &[Release], connected_app: &str) -> String {
+    if releases.is_empty() {
+        return format!("No releases for connected app {}.", connected_app)
+            .dimmed()
+            .to_string();
+    }
+
+    let mut output = String::new();
+    if table::headers_shown() {
+        output.push_str(&format!("{} of {}\n", "Releases".bold(), connected_app));
+        output.push_str(&pretty::separator(pretty::MAX_RULE_WIDTH));
+        output.push('\n');
+    }
+    let mut table = Table::new(&["VERSION", "NAME", "STATUS", "CANDIDATE", "CREATED", "ID"]);
+    for release in releases {
+        table.row(vec![
+            release.version.bold().to_string(),
+            release.name.clone().unwrap_or_default(),
+            release.status.clone().unwrap_or_else(|| "-".to_string()),
+            candidate_label(release),
+            release
+                .created_at
+                .map_or("-".to_string(), |t| t.format("%Y-%m-%d").to_string()),
+            release.id.dimmed().to_string(),
+        ]);
+    }
+    output.push_str(table.render().trim_end());
+    output
+}
+
+/// Render one release
+fn format_release(release: &Release) -> String {
+    let mut output = String::new();
+    output.push_str(&format!("Release {}\n", release.version.bold()));
+    output.push_str(&pretty::separator(60));
+    output.push('\n');
+    output.push_str(&format!("{} {}\n", "ID:".cyan(), release.id));
+    if let Some(ref name) = release.name {
+        output.push_str(&format!("{} {}\n", "Name:".cyan(), name));
+    }
+    if let Some(ref status) = release.status {
+        output.push_str(&format!("{} {}\n", "Status:".cyan(), status));
+    }
+    if let Some(ref branch) = release.release_branch {
+        output.push_str(&format!("{} {}\n", "Branch:".cyan(), branch));
+    }
+    if let Some(created_at) = release.created_at {
+        let created = created_at.format("%Y-%m-%d %H:%M");
+        output.push_str(&format!("{} {}\n", "Created:".cyan(), created));
+    }
+    match release.release_candidate {
+        Some(ref candidate) => {
+            output.push_str(&format!("{} {}", "Candidate:".cyan(), candidate_label(release)));
+            if let Some(ref version) = candidate.build_version {
+                output.push_str(&format!(" (version {})", version));
+            }
+            output.push_str(&format!(
+                "\n  {}\n",
+                format!("https://app.bitrise.io/build/{}", candidate.build_slug).dimmed()
+            ));
+        }
+        None => output.push_str(&format!(
+            "{} {}\n",
+            "Candidate:".cyan(),
+            "none yet; add one with 'reprise release add-build'".dimmed()
+        )),
+    }
+    if let Some(ref description) = release.description {
+        output.push_str(&format!("\n{}\n", description));
+    }
+    output.trim_end().to_string()
+}
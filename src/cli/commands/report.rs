@@ -0,0 +1,467 @@
+//! Report command (a Markdown or HTML digest of a period's builds)
+//!
+//! Made for a nightly cron job: `reprise report --since 24h --out
+//! report.html` writes a file to post to Slack or mail, covering how many
+//! builds ran, which failed (with links), which workflows got slower and
+//! the credits spent.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::bitrise::{BitriseClient, Build, BuildStatus};
+use crate::cli::args::{OutputFormat, ReportArgs, ReportFormat};
+use crate::config::Config;
+use crate::duration::TimeWindow;
+use crate::error::{RepriseError, Result};
+use crate::logparse::html::escape as html_escape;
+use crate::output::json;
+use crate::output::progress::{format_elapsed, StatusLine};
+
+use super::common::{build_history, resolve_app};
+
+/// Days before the period whose builds are the duration baseline
+const BASELINE_DAYS: i64 = 7;
+
+/// A workflow is slower when its average grew by this share...
+const REGRESSION_RATIO: f64 = 0.2;
+
+/// ...and by at least this many seconds
+const REGRESSION_MIN_SECS: i64 = 60;
+
+/// Everything in a report, also its `-o json` form without `--out`
+#[derive(Debug, Serialize)]
+struct Report {
+    app_slug: String,
+    app_title: String,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    builds: usize,
+    succeeded: usize,
+    failed: usize,
+    aborted: usize,
+    running: usize,
+    /// Time spent building, over finished builds
+    build_secs: i64,
+    credits: i64,
+    failures: Vec<Failure>,
+    regressions: Vec<Regression>,
+}
+
+/// A failed build, with a link to it
+#[derive(Debug, Serialize)]
+struct Failure {
+    build_number: i64,
+    url: String,
+    workflow: String,
+    branch: String,
+    triggered_by: Option<String>,
+    /// First line of the commit message
+    commit: Option<String>,
+}
+
+/// A workflow whose successful builds got slower than in the baseline
+#[derive(Debug, Serialize)]
+struct Regression {
+    workflow: String,
+    baseline_secs: i64,
+    average_secs: i64,
+    builds: usize,
+}
+
+/// Handle the report command
+pub fn report(
+    client: &BitriseClient,
+    config: &Config,
+    args: &ReportArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    let window = TimeWindow::from_args(Some(&args.since), args.until.as_deref())?
+        .ok_or_else(|| RepriseError::InvalidArgument("--since is required".to_string()))?;
+    let since = window.since.unwrap_or_else(Utc::now);
+    let until = window.until.unwrap_or_else(Utc::now);
+    let file_format = args.format.unwrap_or_else(|| match args.out {
+        Some(ref out) if out.to_lowercase().ends_with(".html") => ReportFormat::Html,
+        _ => ReportFormat::Markdown,
+    });
+
+    let mut status_line = (format == OutputFormat::Pretty && args.out.is_some())
+        .then(|| StatusLine::start("Fetching builds"));
+    let app = client.get_app(app_slug)?.data;
+    // The baseline's builds come along, for spotting slower workflows
+    let fetched = TimeWindow {
+        since: Some(since - Duration::days(BASELINE_DAYS)),
+        until: Some(until),
+    };
+    let builds = build_history(
+        client,
+        app_slug,
+        None,
+        args.branch.as_deref(),
+        None,
+        Some(&fetched),
+        |count| {
+            if let Some(ref mut status) = status_line {
+                status.set_message(&format!("Fetching builds ({} so far)", count));
+            }
+        },
+    )?;
+    if let Some(ref mut status) = status_line {
+        status.finish();
+    }
+
+    let (period, baseline): (Vec<Build>, Vec<Build>) =
+        builds.into_iter().partition(|b| window.contains(b.triggered_at));
+    let report = summarize(app_slug, &app.title, since, until, &period, &baseline);
+
+    let Some(ref out) = args.out else {
+        // No file: the report itself is the output, e.g. for piping
+        return match format {
+            OutputFormat::Json => json::format_json(&report),
+            OutputFormat::Pretty => Ok(render(&report, file_format).trim_end().to_string()),
+        };
+    };
+    let contents = render(&report, file_format);
+    let path = Path::new(out);
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, &contents)?;
+
+    match format {
+        OutputFormat::Pretty => Ok(format!(
+            "Wrote report of {} build{} to {}",
+            report.builds,
+            if report.builds == 1 { "" } else { "s" },
+            out
+        )),
+        OutputFormat::Json => {
+            let result = serde_json::json!({
+                "app_slug": app_slug,
+                "builds": report.builds,
+                "failed": report.failed,
+                "saved_to": out,
+                "bytes": contents.len(),
+            });
+            json::format_json(&result)
+        }
+    }
+}
+
+/// Tally the period's builds (newest first) against the baseline's
+fn summarize(
+    app_slug: &str,
+    app_title: &str,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    period: &[Build],
+    baseline: &[Build],
+) -> Report {
+    let count =
+        |matches: fn(BuildStatus) -> bool| period.iter().filter(|b| matches(b.status)).count();
+    let failures = period
+        .iter()
+        .filter(|b| b.status == BuildStatus::Failed)
+        .map(|b| Failure {
+            build_number: b.build_number,
+            url: format!("https://app.bitrise.io/build/{}", b.slug),
+            workflow: b.triggered_workflow.clone(),
+            branch: b.branch.clone(),
+            triggered_by: b.triggered_by.clone(),
+            commit: b
+                .commit_message
+                .as_deref()
+                .and_then(|m| m.lines().next())
+                .map(str::to_string),
+        })
+        .collect();
+
+    Report {
+        app_slug: app_slug.to_string(),
+        app_title: app_title.to_string(),
+        since,
+        until,
+        builds: period.len(),
+        succeeded: count(|s| s == BuildStatus::Success),
+        failed: count(|s| s == BuildStatus::Failed),
+        aborted: count(BuildStatus::is_aborted),
+        running: count(BuildStatus::is_running),
+        build_secs: period.iter().filter_map(|b| b.duration()).map(|d| d.num_seconds()).sum(),
+        credits: period.iter().map(|b| i64::from(b.credit_cost.unwrap_or(0))).sum(),
+        failures,
+        regressions: regressions(period, baseline),
+    }
+}
+
+/// Average successful build time per workflow, with the number of builds
+fn average_secs(builds: &[Build]) -> BTreeMap<&str, (i64, usize)> {
+    let mut totals: BTreeMap<&str, (i64, usize)> = BTreeMap::new();
+    for build in builds.iter().filter(|b| b.status == BuildStatus::Success) {
+        if let Some(duration) = build.duration() {
+            let total = totals.entry(&build.triggered_workflow).or_default();
+            total.0 += duration.num_seconds();
+            total.1 += 1;
+        }
+    }
+    totals.values_mut().for_each(|(secs, count)| *secs /= *count as i64);
+    totals
+}
+
+/// Workflows whose successful builds took noticeably longer than in the
+/// baseline, slowest down first
+fn regressions(period: &[Build], baseline: &[Build]) -> Vec<Regression> {
+    let before = average_secs(baseline);
+    let mut slower: Vec<Regression> = average_secs(period)
+        .into_iter()
+        .filter_map(|(workflow, (average, builds))| {
+            let (baseline_secs, _) = *before.get(workflow)?;
+            let growth = average - baseline_secs;
+            let slower = growth >= REGRESSION_MIN_SECS
+                && growth as f64 >= baseline_secs as f64 * REGRESSION_RATIO;
+            slower.then(|| Regression {
+                workflow: workflow.to_string(),
+                baseline_secs,
+                average_secs: average,
+                builds,
+            })
+        })
+        .collect();
+    slower.sort_by_key(|r| r.baseline_secs - r.average_secs);
+    slower
+}
+
+fn render(report: &Report, format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Markdown => render_markdown(report),
+        ReportFormat::Html => render_html(report),
+    }
+}
+
+/// e.g. `2024-05-01 22:00 UTC`
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d %H:%M UTC").to_string()
+}
+
+/// Summary lines shared by both formats, e.g. `Builds: 12 (10 succeeded, ...)`
+fn summary_lines(report: &Report) -> Vec<String> {
+    let rate = (report.succeeded + report.failed > 0).then(|| {
+        report.succeeded as f64 * 100.0 / (report.succeeded + report.failed) as f64
+    });
+    let mut lines = vec![
+        format!(
+            "Builds: {} ({} succeeded, {} failed, {} aborted, {} running)",
+            report.builds, report.succeeded, report.failed, report.aborted, report.running
+        ),
+        format!("Build time: {}", format_elapsed(report.build_secs.max(0) as u64)),
+        format!("Credits: {}", report.credits),
+    ];
+    if let Some(rate) = rate {
+        lines.insert(1, format!("Success rate: {:.0}%", rate));
+    }
+    lines
+}
+
+/// One failure's description after its link, e.g. `ci on main by alice: Fix login`
+fn failure_detail(failure: &Failure) -> String {
+    let mut detail = format!("{} on {}", failure.workflow, failure.branch);
+    if let Some(ref user) = failure.triggered_by {
+        detail.push_str(&format!(" by {}", user));
+    }
+    if let Some(ref commit) = failure.commit {
+        detail.push_str(&format!(": {}", commit));
+    }
+    detail
+}
+
+fn regression_cells(regression: &Regression) -> [String; 4] {
+    let growth = regression.average_secs - regression.baseline_secs;
+    [
+        regression.workflow.clone(),
+        format_elapsed(regression.baseline_secs.max(0) as u64),
+        format_elapsed(regression.average_secs.max(0) as u64),
+        format!(
+            "+{} ({} build{})",
+            format_elapsed(growth.max(0) as u64),
+            regression.builds,
+            if regression.builds == 1 { "" } else { "s" }
+        ),
+    ]
+}
+
+fn render_markdown(report: &Report) -> String {
+    let mut md = format!("# Bitrise report: {}\n\n", markdown_escape(&report.app_title));
+    md.push_str(&format!(
+        "_{} to {}_\n\n",
+        timestamp(report.since),
+        timestamp(report.until)
+    ));
+    for line in summary_lines(report) {
+        md.push_str(&format!("- {}\n", line));
+    }
+
+    md.push_str("\n## Failures\n\n");
+    if report.failures.is_empty() {
+        md.push_str("No failed builds.\n");
+    }
+    for failure in &report.failures {
+        md.push_str(&format!(
+            "- [#{}]({}) {}\n",
+            failure.build_number,
+            failure.url,
+            markdown_escape(&failure_detail(failure))
+        ));
+    }
+
+    md.push_str("\n## Slower Workflows\n\n");
+    if report.regressions.is_empty() {
+        md.push_str(&format!(
+            "No workflow got slower than in the {} days before.\n",
+            BASELINE_DAYS
+        ));
+    } else {
+        md.push_str(&format!(
+            "Average successful build time against the {} days before.\n\n",
+            BASELINE_DAYS
+        ));
+        md.push_str("| Workflow | Before | Now | Change |\n|---|---|---|---|\n");
+        for regression in &report.regressions {
+            let cells = regression_cells(regression).map(|c| markdown_escape(&c));
+            md.push_str(&format!("| {} |\n", cells.join(" | ")));
+        }
+    }
+    md
+}
+
+/// Keep build text from being read as Markdown (or breaking table cells)
+fn markdown_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '#') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn render_html(report: &Report) -> String {
+    let title = format!("Bitrise report: {}", html_escape(&report.app_title));
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>body {{ font-family: sans-serif; max-width: 50em; margin: 2em auto; }} \
+         table {{ border-collapse: collapse; }} \
+         th, td {{ border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; }}</style>\n\
+         </head>\n<body>\n<h1>{}</h1>\n",
+        title, title
+    );
+    html.push_str(&format!(
+        "<p><em>{} to {}</em></p>\n<ul>\n",
+        timestamp(report.since),
+        timestamp(report.until)
+    ));
+    for line in summary_lines(report) {
+        html.push_str(&format!("<li>{}</li>\n", html_escape(&line)));
+    }
+    html.push_str("</ul>\n\n<h2>Failures</h2>\n");
+    if report.failures.is_empty() {
+        html.push_str("<p>No failed builds.</p>\n");
+    } else {
+        html.push_str("<ul>\n");
+        for failure in &report.failures {
+            html.push_str(&format!(
+                "<li><a href=\"{}\">#{}</a> {}</li>\n",
+                html_escape(&failure.url),
+                failure.build_number,
+                html_escape(&failure_detail(failure))
+            ));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    html.push_str("\n<h2>Slower Workflows</h2>\n");
+    if report.regressions.is_empty() {
+        html.push_str(&format!(
+            "<p>No workflow got slower than in the {} days before.</p>\n",
+            BASELINE_DAYS
+        ));
+    } else {
+        html.push_str(&format!(
+            "<p>Average successful build time against the {} days before.</p>\n",
+            BASELINE_DAYS
+        ));
+        html.push_str("<table>\n<tr><th>Workflow</th><th>Before</th><th>Now</th>");
+        html.push_str("<th>Change</th></tr>\n");
+        for regression in &report.regressions {
+            let cells: Vec<String> = regression_cells(regression)
+                .iter()
+                .map(|c| format!("<td>{}</td>", html_escape(c)))
+                .collect();
+            html.push_str(&format!("<tr>{}</tr>\n", cells.concat()));
+        }
+        html.push_str("</table>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(number: i64, status: i32, workflow: &str, minutes: i64) -> Build {
+        let fixture = Build::fixture();
+        Build {
+            slug: format!("b{}", number),
+            build_number: number,
+            status: BuildStatus::from_code(status),
+            finished_at: Some(fixture.triggered_at + Duration::minutes(minutes)),
+            triggered_workflow: workflow.to_string(),
+            triggered_by: Some("alice".to_string()),
+            commit_message: Some("Fix *login*\n\nLonger description".to_string()),
+            credit_cost: Some(3),
+            ..fixture
+        }
+    }
+
+    fn report() -> Report {
+        let period = vec![build(5, 2, "ci", 12), build(4, 1, "ci", 12), build(3, 1, "ui", 10)];
+        let baseline = vec![build(2, 1, "ci", 8), build(1, 1, "ui", 10)];
+        let now = Utc::now();
+        summarize("app", "My <App>", now - Duration::days(1), now, &period, &baseline)
+    }
+
+    #[test]
+    fn test_summarize_counts_and_regressions() {
+        let report = report();
+        assert_eq!((report.builds, report.succeeded, report.failed), (3, 2, 1));
+        assert_eq!(report.credits, 9);
+        assert_eq!(report.failures[0].commit.as_deref(), Some("Fix *login*"));
+        // ci went from 8 to 12 minutes; ui is unchanged
+        assert_eq!(report.regressions.len(), 1);
+        assert_eq!(report.regressions[0].workflow, "ci");
+        assert_eq!(report.regressions[0].average_secs, 720);
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let md = render_markdown(&report());
+        assert!(md.starts_with("# Bitrise report: My \\<App\\>\n"));
+        assert!(md.contains("- Success rate: 67%\n"));
+        assert!(md.contains(
+            "- [#5](https://app.bitrise.io/build/b5) ci on main by alice: Fix \\*login\\*\n"
+        ));
+        assert!(md.contains("| ci | 8m 00s | 12m 00s | +4m 00s (1 build) |\n"));
+    }
+
+    #[test]
+    fn test_render_html_escapes_text() {
+        let html = render_html(&report());
+        assert!(html.contains("<h1>Bitrise report: My &lt;App&gt;</h1>"));
+        assert!(html.contains("<a href=\"https://app.bitrise.io/build/b5\">#5</a>"));
+        assert!(html.contains("<td>+4m 00s (1 build)</td>"));
+    }
+}
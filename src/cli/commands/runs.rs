@@ -0,0 +1,127 @@
+//! Runs command (builds and pipelines in one feed)
+
+use std::cmp::Reverse;
+
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+
+use super::common::{resolve_app, warn_paged_limit};
+use crate::bitrise::{BitriseClient, Build, Pipeline};
+use crate::cli::args::{OutputFormat, RunsArgs};
+use crate::config::Config;
+use crate::error::Result;
+use crate::output::json;
+use crate::output::pretty;
+
+/// A build or pipeline in the feed
+enum Run<'a> {
+    Build(&'a Build),
+    Pipeline(&'a Pipeline),
+}
+
+impl Run<'_> {
+    fn triggered_at(&self) -> Option<DateTime<Utc>> {
+        match self {
+            Run::Build(build) => Some(build.triggered_at),
+            Run::Pipeline(pipeline) => pipeline.triggered_at,
+        }
+    }
+}
+
+/// Handle the runs command
+pub fn runs(
+    client: &BitriseClient,
+    config: &Config,
+    args: &RunsArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    let status_filter = args.status.map(|s| s.to_status());
+    warn_paged_limit(args.limit);
+    let limit = args.limit;
+
+    let builds = client
+        .list_builds(
+            app_slug,
+            status_filter,
+            args.branch.as_deref(),
+            None,
+            limit,
+        )?
+        .data;
+
+    // Pipelines are optional for an app; don't fail the feed if unavailable.
+    // Status is filtered client-side, as in 'pipelines'.
+    let pipelines: Vec<Pipeline> = client
+        .list_pipelines(app_slug, None, args.branch.as_deref(), limit)
+        .map(|r| {
+            r.data
+                .into_iter()
+                .filter(|p| status_filter.is_none_or(|s| p.status == s))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // Newest first; pipelines without a trigger time sort last
+    let mut feed: Vec<Run> = builds
+        .iter()
+        .map(Run::Build)
+        .chain(pipelines.iter().map(Run::Pipeline))
+        .collect();
+    feed.sort_by_key(|run| Reverse(run.triggered_at()));
+    feed.truncate(args.limit as usize);
+
+    match format {
+        OutputFormat::Pretty => {
+            if feed.is_empty() {
+                return Ok("No builds or pipelines found.".to_string());
+            }
+
+            let mut output = String::new();
+            output.push_str(&format!("{}\n", "Runs".bold()));
+            for run in &feed {
+                let when = run
+                    .triggered_at()
+                    .map(|t| t.format("%m-%d %H:%M").to_string())
+                    .unwrap_or_else(|| "-".to_string());
+                let line = match run {
+                    Run::Build(build) => format!(
+                        "{} {} {} {}",
+                        "build   ".cyan(),
+                        when.dimmed(),
+                        pretty::format_build_line(build),
+                        build.branch
+                    ),
+                    Run::Pipeline(pipeline) => format!(
+                        "{} {} {} {}",
+                        "pipeline".magenta(),
+                        when.dimmed(),
+                        pretty::format_pipeline_line(pipeline),
+                        pipeline.get_branch()
+                    ),
+                };
+                output.push_str(&format!("  {}\n", line));
+            }
+
+            Ok(output.trim_end().to_string())
+        }
+        OutputFormat::Json => {
+            let entries: Vec<_> = feed
+                .iter()
+                .map(|run| match run {
+                    Run::Build(build) => serde_json::json!({
+                        "type": "build",
+                        "triggered_at": build.triggered_at,
+                        "build": build,
+                    }),
+                    Run::Pipeline(pipeline) => serde_json::json!({
+                        "type": "pipeline",
+                        "triggered_at": pipeline.triggered_at,
+                        "pipeline": pipeline,
+                    }),
+                })
+                .collect();
+            json::format_json(&entries)
+        }
+    }
+}
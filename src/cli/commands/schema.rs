@@ -0,0 +1,16 @@
+//! Schema command (JSON Schema of the -o json outputs)
+
+use crate::bitrise::schema;
+use crate::cli::args::SchemaArgs;
+use crate::error::{RepriseError, Result};
+
+/// Handle the schema command
+///
+/// The schema is JSON whatever the output format, so it can be redirected
+/// straight into a file.
+pub fn schema(args: &SchemaArgs) -> Result<String> {
+    let kind = args.kind.as_str();
+    let schema = schema::list_schema(kind)
+        .ok_or_else(|| RepriseError::InvalidArgument(format!("No schema for '{}'", kind)))?;
+    Ok(serde_json::to_string_pretty(&schema)?)
+}
@@ -0,0 +1,142 @@
+//! Stacks command (build stacks and machine types available to an app)
+
+use colored::Colorize;
+
+use crate::bitrise::{BitriseClient, MachineType, Stack};
+use crate::cli::args::{OutputFormat, StacksArgs};
+use crate::config::Config;
+use crate::error::{RepriseError, Result};
+use crate::output::json;
+use crate::output::pretty;
+use crate::output::table::{self, Table};
+
+use super::common::resolve_app;
+
+/// Handle the stacks command
+pub fn stacks(
+    client: &BitriseClient,
+    config: &Config,
+    args: &StacksArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    let mut stacks = client.list_stacks(app_slug)?.data;
+    let mut machine_types = client.list_machine_types(app_slug)?.data;
+
+    // Checking one stack: fail when it's unavailable, so scripts can rely on it
+    if let Some(ref id) = args.stack {
+        let Some(stack) = stacks.iter().find(|s| s.id == *id).cloned() else {
+            return Err(unknown_stack(id, &stacks));
+        };
+        machine_types.retain(|m| stack.machine_types.contains(&m.id));
+        stacks = vec![stack];
+    }
+
+    if let Some(ref version) = args.find_xcode {
+        let (matching, others): (Vec<Stack>, Vec<Stack>) =
+            stacks.into_iter().partition(|s| s.provides_xcode(version));
+        if matching.is_empty() && format == OutputFormat::Pretty {
+            return Ok(no_xcode_match(version, &others));
+        }
+        machine_types.retain(|m| matching.iter().any(|s| s.machine_types.contains(&m.id)));
+        stacks = matching;
+    }
+
+    match format {
+        OutputFormat::Pretty => Ok(format_stacks(&stacks, &machine_types, app_slug)),
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "stacks": stacks,
+                "machine_types": machine_types,
+            });
+            json::format_json(&json)
+        }
+    }
+}
+
+/// Error for a stack the app can't use, naming similar ones
+fn unknown_stack(id: &str, stacks: &[Stack]) -> RepriseError {
+    let needle = id.to_lowercase();
+    let similar: Vec<&str> = stacks
+        .iter()
+        .map(|s| s.id.as_str())
+        .filter(|s| s.contains(&needle) || needle.contains(*s))
+        .collect();
+    let hint = if similar.is_empty() {
+        "run 'reprise stacks' to list them".to_string()
+    } else {
+        format!("did you mean {}?", similar.join(", "))
+    };
+    RepriseError::InvalidArgument(format!(
+        "Stack '{}' is not available for this app; {}",
+        id, hint
+    ))
+}
+
+/// Message when no stack has the wanted Xcode, naming the versions there are
+fn no_xcode_match(version: &str, stacks: &[Stack]) -> String {
+    let mut versions: Vec<&str> = stacks.iter().filter_map(Stack::xcode_version).collect();
+    versions.sort_by_key(|v| {
+        v.split('.')
+            .map(|part| part.parse::<u32>().unwrap_or(0))
+            .collect::<Vec<_>>()
+    });
+    versions.dedup();
+
+    let mut message = format!("No stack provides Xcode {}.", version);
+    if !versions.is_empty() {
+        message.push_str(&format!(" Available: {}", versions.join(", ")));
+    }
+    message.dimmed().to_string()
+}
+
+/// Render the stack and machine type tables
+fn format_stacks(stacks: &[Stack], machine_types: &[MachineType], app_slug: &str) -> String {
+    if stacks.is_empty() {
+        return "No stacks found.".to_string();
+    }
+
+    let mut output = String::new();
+    if table::headers_shown() {
+        output.push_str(&format!("{} for {}\n", "Stacks".bold(), app_slug));
+        output.push_str(&pretty::separator(pretty::MAX_RULE_WIDTH));
+        output.push('\n');
+    }
+    let mut table = Table::new(&["STACK", "XCODE", "STATUS", "MACHINE TYPES"]);
+    for stack in stacks {
+        let status = match stack.status.as_deref() {
+            Some("stable") | None => stack.status.clone().unwrap_or_default(),
+            Some(other) => other.yellow().to_string(),
+        };
+        table.row(vec![
+            stack.id.bold().to_string(),
+            stack.xcode_version().unwrap_or("-").to_string(),
+            status,
+            stack.machine_types.join(", ").dimmed().to_string(),
+        ]);
+    }
+    output.push_str(table.render().trim_end());
+
+    if !machine_types.is_empty() {
+        output.push_str("\n\n");
+        if table::headers_shown() {
+            output.push_str(&format!("{}\n", "Machine Types".bold()));
+            output.push_str(&pretty::separator(pretty::MAX_RULE_WIDTH));
+            output.push('\n');
+        }
+        let mut table = Table::new(&["MACHINE TYPE", "NAME", "CPU", "RAM", "CREDITS/MIN"]);
+        for machine in machine_types {
+            table.row(vec![
+                machine.id.bold().to_string(),
+                machine.name.clone().unwrap_or_default(),
+                machine.cpu_count.clone().unwrap_or_else(|| "-".to_string()),
+                machine.ram.clone().unwrap_or_else(|| "-".to_string()),
+                machine
+                    .credit_per_minute
+                    .map_or("-".to_string(), |c| format!("{}", c)),
+            ]);
+        }
+        output.push_str(table.render().trim_end());
+    }
+    output
+}
@@ -0,0 +1,133 @@
+//! Status command (repo-aware CI overview)
+
+use colored::Colorize;
+
+use super::common::{detect_app_slug, warn_paged_limit};
+use crate::bitrise::{BitriseClient, Build, BuildStatus};
+use crate::cli::args::{OutputFormat, StatusArgs};
+use crate::config::Config;
+use crate::error::{RepriseError, Result};
+use crate::git;
+use crate::output::{json, pretty, theme};
+
+/// Handle the status command
+pub fn status(
+    client: &BitriseClient,
+    config: &Config,
+    args: &StatusArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let app_slug = detect_app_slug(client, args.app.as_deref(), config)?;
+    let branch = args.branch.clone().or_else(git::current_branch).ok_or_else(|| {
+        RepriseError::InvalidArgument(
+            "Not on a git branch. Use --branch to choose one.".to_string(),
+        )
+    })?;
+
+    // HEAD only makes sense when looking at the checked-out branch
+    let head = if args.branch.is_none() {
+        git::head_commit()
+    } else {
+        None
+    };
+
+    // Fetch extra builds so a slightly older HEAD build is still found
+    warn_paged_limit(args.limit);
+    let fetch_limit = args.limit.max(20);
+    let builds = client
+        .list_builds(&app_slug, None, Some(&branch), None, fetch_limit)?
+        .data;
+
+    let head_build = head.as_deref().and_then(|sha| {
+        builds.iter().find(|b| {
+            b.commit_hash
+                .as_deref()
+                .is_some_and(|hash| git::commit_matches(hash, sha))
+        })
+    });
+
+    // Pipelines are optional for an app; don't fail the overview if unavailable
+    let running_pipelines: Vec<_> = client
+        .list_pipelines(&app_slug, None, None, 50)
+        .map(|r| r.data.into_iter().filter(|p| p.is_running()).collect())
+        .unwrap_or_default();
+
+    let recent: Vec<&Build> = builds.iter().take(args.limit as usize).collect();
+
+    match format {
+        OutputFormat::Pretty => {
+            let mut output = String::new();
+
+            output.push_str(&format!(
+                "{} {}  {} {}",
+                "App:".cyan(),
+                app_slug,
+                "Branch:".cyan(),
+                branch.bold()
+            ));
+            if let Some(ref sha) = head {
+                output.push_str(&format!("  {} {}", "HEAD:".cyan(), short_sha(sha)));
+            }
+            output.push_str("\n\n");
+
+            // HEAD build
+            if head.is_some() {
+                match head_build {
+                    Some(build) => {
+                        output.push_str(&format!("{} {}\n\n", "HEAD build:".bold(), pretty::format_build_line(build)));
+                    }
+                    None => {
+                        output.push_str(&format!(
+                            "{} {}\n\n",
+                            "HEAD build:".bold(),
+                            "none yet (push to trigger CI?)".dimmed()
+                        ));
+                    }
+                }
+            }
+
+            // Recent builds on branch
+            output.push_str(&format!("{}\n", "Recent builds:".bold()));
+            if recent.is_empty() {
+                output.push_str(&format!("  {}\n", "No builds on this branch.".dimmed()));
+            }
+            for build in &recent {
+                output.push_str(&format!("  {}\n", pretty::format_build_line(build)));
+            }
+
+            // Running pipelines
+            output.push_str(&format!("\n{}\n", "Running pipelines:".bold()));
+            if running_pipelines.is_empty() {
+                output.push_str(&format!("  {}", "None".dimmed()));
+            }
+            for p in &running_pipelines {
+                output.push_str(&format!(
+                    "  {} {} {} {} {}\n",
+                    theme::icon(BuildStatus::Running),
+                    p.pipeline_id,
+                    p.get_branch(),
+                    p.duration_display().dimmed(),
+                    p.id.dimmed()
+                ));
+            }
+
+            Ok(output.trim_end().to_string())
+        }
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "app_slug": app_slug,
+                "branch": branch,
+                "head_commit": head,
+                "head_build": head_build,
+                "recent_builds": recent,
+                "running_pipelines": running_pipelines,
+            });
+            json::format_json(&json)
+        }
+    }
+}
+
+/// First 7 characters of a commit hash
+fn short_sha(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
@@ -0,0 +1,289 @@
+//! Tray command (latest build status in the system tray / menu bar)
+//!
+//! The tray icon lives on the main thread's event loop, as macOS requires.
+//! A worker thread polls with the watch-daemon engine and makes every API
+//! call; the two talk through a command channel one way and the event
+//! loop's proxy the other.
+
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+use tao::event::{Event, StartCause};
+use tao::event_loop::{ControlFlow, EventLoopBuilder, EventLoopProxy};
+use tray_icon::menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tray_icon::{Icon, TrayIcon, TrayIconBuilder};
+
+use super::common::resolve_app;
+use super::url::open_url_in_browser;
+use crate::bitrise::{BitriseClient, Build, BuildStatus};
+use crate::cli::args::{OutputFormat, TrayArgs};
+use crate::config::Config;
+use crate::daemon::{self, DaemonState, Observation, DEFAULT_INTERVAL};
+use crate::error::{RepriseError, Result};
+
+/// Width and height of the generated status icon, in pixels
+const ICON_SIZE: u32 = 32;
+
+/// Sent from the worker (and the menu) to the event loop
+enum UserEvent {
+    /// The newest finished build, or why it couldn't be fetched
+    Status(std::result::Result<Option<Build>, String>),
+    /// The build started by "Rebuild", or why it couldn't be
+    Rebuilt(std::result::Result<Build, String>),
+    Menu(MenuEvent),
+}
+
+/// Sent from the event loop to the worker
+enum Request {
+    Pause(bool),
+    Rebuild(Box<Build>),
+}
+
+/// The tray's menu items, kept to update them and match clicks
+struct TrayMenu {
+    menu: Menu,
+    status: MenuItem,
+    open: MenuItem,
+    rebuild: MenuItem,
+    pause: CheckMenuItem,
+    quit: MenuItem,
+}
+
+impl TrayMenu {
+    fn new() -> std::result::Result<Self, tray_icon::menu::Error> {
+        let tray_menu = Self {
+            menu: Menu::new(),
+            status: MenuItem::new("Checking builds…", false, None),
+            open: MenuItem::new("Open Build", false, None),
+            rebuild: MenuItem::new("Rebuild", false, None),
+            pause: CheckMenuItem::new("Pause Polling", true, false, None),
+            quit: MenuItem::new("Quit", true, None),
+        };
+        let separator = PredefinedMenuItem::separator();
+        let menu = &tray_menu.menu;
+        menu.append(&tray_menu.status)?;
+        menu.append(&separator)?;
+        menu.append(&tray_menu.open)?;
+        menu.append(&tray_menu.rebuild)?;
+        menu.append(&tray_menu.pause)?;
+        menu.append(&PredefinedMenuItem::separator())?;
+        menu.append(&tray_menu.quit)?;
+        Ok(tray_menu)
+    }
+}
+
+/// Handle the tray command
+///
+/// Runs until "Quit" is chosen; the event loop then exits the process.
+pub fn tray(
+    client: &BitriseClient,
+    config: &Config,
+    args: &TrayArgs,
+    _format: OutputFormat,
+) -> Result<String> {
+    let app_slug = resolve_app(client, args.app.as_deref(), config)?;
+    let label = args
+        .app
+        .clone()
+        .or_else(|| config.defaults.app_name.clone())
+        .unwrap_or_else(|| app_slug.clone());
+    let interval = args.interval.or(config.daemon.interval).unwrap_or(DEFAULT_INTERVAL);
+
+    #[allow(unused_mut)] // Only macOS changes the event loop
+    let mut event_loop = EventLoopBuilder::<UserEvent>::with_user_event().build();
+    #[cfg(target_os = "macos")]
+    {
+        // A menu bar item, without a Dock icon
+        use tao::platform::macos::{ActivationPolicy, EventLoopExtMacOS};
+        event_loop.set_activation_policy(ActivationPolicy::Accessory);
+    }
+    // After the event loop, which initializes GTK on Linux
+    let tray_menu = TrayMenu::new().map_err(|e| tray_error(&e))?;
+    let menu_proxy = event_loop.create_proxy();
+    MenuEvent::set_event_handler(Some(move |event| {
+        let _ = menu_proxy.send_event(UserEvent::Menu(event));
+    }));
+
+    let worker_proxy = event_loop.create_proxy();
+    let (requests, received) = mpsc::channel();
+    let branch = args.branch.as_deref();
+    thread::scope(|scope| -> Result<String> {
+        scope.spawn(|| poll(client, &app_slug, branch, interval, received, worker_proxy));
+        run(event_loop, tray_menu, requests, label)
+    })
+}
+
+/// Poll for the newest finished build until the event loop goes away,
+/// serving menu requests in between
+fn poll(
+    client: &BitriseClient,
+    app_slug: &str,
+    branch: Option<&str>,
+    interval: u64,
+    requests: Receiver<Request>,
+    proxy: EventLoopProxy<UserEvent>,
+) {
+    let mut state = DaemonState::default();
+    let mut paused = false;
+    loop {
+        if !paused {
+            let status = daemon::latest_finished(client, app_slug, branch);
+            if let Ok(Some(ref build)) = status {
+                if let Some(Observation::Changed { .. }) = state.observe(app_slug, build) {
+                    crate::notify::build_completed(build, None);
+                }
+            }
+            let status = status.map_err(|e| e.to_string());
+            if proxy.send_event(UserEvent::Status(status)).is_err() {
+                return;
+            }
+        }
+        match requests.recv_timeout(Duration::from_secs(interval)) {
+            Ok(Request::Pause(pause)) => paused = pause,
+            Ok(Request::Rebuild(build)) => {
+                let started = client.rebuild_build(app_slug, &build);
+                let slug = started.as_ref().map_or(&build.slug, |b| &b.slug);
+                crate::history::record("rebuild", app_slug, Some(slug), &started);
+                let started = started.map_err(|e| e.to_string());
+                let _ = proxy.send_event(UserEvent::Rebuilt(started));
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
+
+/// Run the event loop: show the tray icon and react to statuses and clicks
+fn run(
+    event_loop: tao::event_loop::EventLoop<UserEvent>,
+    tray_menu: TrayMenu,
+    requests: Sender<Request>,
+    label: String,
+) -> ! {
+    let mut tray: Option<TrayIcon> = None;
+    let mut latest: Option<Build> = None;
+    event_loop.run(move |event, _, control_flow| {
+        *control_flow = ControlFlow::Wait;
+        match event {
+            // macOS needs the event loop running before the icon is created
+            Event::NewEvents(StartCause::Init) => {
+                let mut builder = TrayIconBuilder::new()
+                    .with_menu(Box::new(tray_menu.menu.clone()))
+                    .with_tooltip(format!("reprise · {}", label));
+                if let Some(icon) = status_icon(None) {
+                    builder = builder.with_icon(icon);
+                }
+                match builder.build() {
+                    Ok(icon) => tray = Some(icon),
+                    Err(e) => {
+                        eprintln!("error: {}", tray_error(&e));
+                        *control_flow = ControlFlow::ExitWithCode(1);
+                    }
+                }
+            }
+            Event::UserEvent(UserEvent::Status(Ok(build))) => {
+                let text = match build {
+                    Some(ref build) => status_text(&label, build),
+                    None => format!("{}: no finished builds", label),
+                };
+                tray_menu.status.set_text(&text);
+                tray_menu.open.set_enabled(build.is_some());
+                tray_menu.rebuild.set_enabled(build.is_some());
+                if let Some(ref tray) = tray {
+                    let _ = tray.set_icon(status_icon(build.as_ref().map(|b| b.status)));
+                    let _ = tray.set_tooltip(Some(format!("reprise · {}", text)));
+                }
+                latest = build;
+            }
+            Event::UserEvent(UserEvent::Status(Err(message))) => {
+                tray_menu.status.set_text(format!("{}: {}", label, message));
+            }
+            Event::UserEvent(UserEvent::Rebuilt(result)) => match result {
+                Ok(build) => crate::notify::send(
+                    "Rebuild started",
+                    &format!("{} #{} on {}", label, build.build_number, build.branch),
+                ),
+                Err(message) => crate::notify::send("Rebuild failed", &message),
+            },
+            Event::UserEvent(UserEvent::Menu(event)) => {
+                if event.id == *tray_menu.quit.id() {
+                    *control_flow = ControlFlow::Exit;
+                } else if event.id == *tray_menu.pause.id() {
+                    let _ = requests.send(Request::Pause(tray_menu.pause.is_checked()));
+                } else if let Some(ref build) = latest {
+                    if event.id == *tray_menu.open.id() {
+                        let url = format!("https://app.bitrise.io/build/{}", build.slug);
+                        let _ = open_url_in_browser(&url);
+                    } else if event.id == *tray_menu.rebuild.id() {
+                        let _ = requests.send(Request::Rebuild(Box::new(build.clone())));
+                    }
+                }
+            }
+            _ => {}
+        }
+    })
+}
+
+/// Menu and tooltip text for a build, e.g. `ios #42 failed · ci · main`
+fn status_text(label: &str, build: &Build) -> String {
+    format!(
+        "{} #{} {} · {} · {}",
+        label,
+        build.build_number,
+        build.status_display(),
+        build.triggered_workflow,
+        build.branch
+    )
+}
+
+/// A filled circle in the status's color; gray before the first status
+fn status_icon(status: Option<BuildStatus>) -> Option<Icon> {
+    let rgba = circle_rgba(status_color(status), ICON_SIZE);
+    Icon::from_rgba(rgba, ICON_SIZE, ICON_SIZE).ok()
+}
+
+fn status_color(status: Option<BuildStatus>) -> [u8; 3] {
+    match status {
+        Some(BuildStatus::Success) => [0x2e, 0xa0, 0x43],
+        Some(BuildStatus::Failed) => [0xd7, 0x3a, 0x49],
+        Some(BuildStatus::Running) => [0xdb, 0xab, 0x09],
+        _ => [0x8b, 0x94, 0x9e],
+    }
+}
+
+/// RGBA pixels of a `size`×`size` image with a centered circle of `color`
+fn circle_rgba(color: [u8; 3], size: u32) -> Vec<u8> {
+    let center = (size as f64 - 1.0) / 2.0;
+    let radius = size as f64 / 2.0 - 1.0;
+    let mut rgba = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let distance = (x as f64 - center).hypot(y as f64 - center);
+            // Anti-aliased edge: fade out over the last pixel
+            let alpha = (radius + 0.5 - distance).clamp(0.0, 1.0);
+            rgba.extend_from_slice(&color);
+            rgba.push((alpha * 255.0).round() as u8);
+        }
+    }
+    rgba
+}
+
+fn tray_error(error: &impl std::fmt::Display) -> RepriseError {
+    RepriseError::Io(std::io::Error::other(format!("Couldn't create the tray icon: {}", error)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circle_rgba() {
+        let rgba = circle_rgba([1, 2, 3], 8);
+        assert_eq!(rgba.len(), 8 * 8 * 4);
+        let alpha = |x: usize, y: usize| rgba[(y * 8 + x) * 4 + 3];
+        assert_eq!(alpha(0, 0), 0);
+        assert_eq!(alpha(4, 4), 255);
+        assert_eq!(&rgba[(4 * 8 + 4) * 4..][..3], &[1, 2, 3]);
+    }
+}
@@ -1,15 +1,31 @@
 //! Trigger build command
 
+use std::io;
+use std::sync::atomic::AtomicBool;
 use std::thread;
-use std::time::Duration;
 
+use chrono::{DateTime, Local};
 use colored::Colorize;
+use is_terminal::IsTerminal;
 
-use super::common::{is_interrupted, setup_interrupt_handler};
-use crate::bitrise::BitriseClient;
-use crate::cli::args::{OutputFormat, TriggerArgs};
+use super::common::{
+    build_progress_message, finished_message, is_interrupted, resolve_app,
+    setup_interrupt_handler,
+};
+use crate::bitrise::{
+    BitriseClient, Build, BuildStatus, Pipeline, PipelineTriggerParams, TriggerSource,
+};
+use crate::cli::args::{ChainWhen, OutputFormat, TriggerArgs};
 use crate::config::Config;
-use crate::error::Result;
+use crate::duration;
+use crate::error::{RepriseError, Result};
+use crate::eta;
+use crate::git;
+use crate::history;
+use crate::hooks;
+use crate::output::json;
+use crate::output::progress::{format_elapsed, StatusLine};
+use crate::watch::{self, WatchOptions, DEFAULT_MAX_INTERVAL_SECS};
 
 /// Handle the trigger command
 pub fn trigger(
@@ -18,35 +34,69 @@ pub fn trigger(
     args: &TriggerArgs,
     format: OutputFormat,
 ) -> Result<String> {
+    let workflow = resolve_workflow(config, args)?;
+    let start_at = scheduled_start(args)?;
+
     // Get app slug from args or default
-    let app_slug = args
-        .app
-        .as_ref()
-        .or(config.defaults.app_slug.as_ref())
-        .ok_or_else(|| {
-            crate::error::RepriseError::Config(
-                "No app specified. Use --app or set a default with 'reprise app set'".to_string(),
-            )
-        })?;
-
-    // Build trigger params
-    let params = crate::bitrise::TriggerParams {
-        branch: args.branch.clone(),
-        workflow_id: args.workflow.clone(),
-        commit_message: args.message.clone(),
-        environments: args.env.clone(),
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    let app_config = config.app_config(app_slug);
+
+    let branch = args.branch.clone().or_else(|| app_config.branch.clone());
+
+    // One handler serves both the countdown and the wait for the build
+    let waiting = args.wait || args.then_pipeline.is_some();
+    let interrupted = (start_at.is_some() || waiting).then(setup_interrupt_handler);
+    if let (Some(start_at), Some(flag)) = (start_at, &interrupted) {
+        if !wait_until(start_at, workflow, flag, format) {
+            if format == OutputFormat::Pretty {
+                eprintln!("{} Cancelled - build not triggered", "!".yellow());
+            }
+            return Ok(String::new());
+        }
+    }
+
+    // Reuse a matching build that is already running instead of stacking another
+    let running = if args.skip_if_running {
+        let commit = args.source.commit.as_deref();
+        find_running_build(client, app_slug, workflow, branch.as_deref(), commit)?
+    } else {
+        None
     };
+    let skipped = running.is_some();
 
-    // Trigger the build
-    let build = client.trigger_build(app_slug, params)?;
+    let build = match running {
+        Some(build) => build,
+        None => {
+            // Build trigger params
+            let params = crate::bitrise::TriggerParams {
+                branch,
+                workflow_id: workflow.to_string(),
+                commit_message: args.message.clone(),
+                source: args.source.to_source(),
+                environments: args.env.clone(),
+            };
+            let result = client.trigger_build(app_slug, params);
+            let slug = result.as_ref().ok().map(|b| b.slug.as_str());
+            history::record("trigger", app_slug, slug, &result);
+            result?
+        }
+    };
 
     // Print initial status (to stderr so stdout can be piped)
     if format == OutputFormat::Pretty {
-        eprintln!(
-            "{} Build #{} triggered",
-            "✓".green(),
-            build.build_number.to_string().bold()
-        );
+        if skipped {
+            eprintln!(
+                "{} Build #{} is already running - not triggering another",
+                "!".yellow(),
+                build.build_number.to_string().bold()
+            );
+        } else {
+            eprintln!(
+                "{} Build #{} triggered",
+                "✓".green(),
+                build.build_number.to_string().bold()
+            );
+        }
         eprintln!("  Slug:     {}", build.slug.dimmed());
         eprintln!("  Branch:   {}", build.branch);
         eprintln!("  Workflow: {}", build.triggered_workflow);
@@ -56,20 +106,135 @@ pub fn trigger(
         );
     }
 
-    // Wait for build to complete if requested
-    if args.wait {
-        return wait_for_build(client, app_slug, &build.slug, args.interval, args.notify, format);
+    // Wait for build to complete if requested; a chained pipeline needs the outcome
+    if waiting {
+        let interval = args.interval.or(app_config.interval).unwrap_or(DEFAULT_MAX_INTERVAL_SECS);
+        let interrupted = interrupted.unwrap_or_else(setup_interrupt_handler);
+        let Some(build) = wait_for_build(
+            client,
+            app_slug,
+            &build.slug,
+            interval,
+            args.notify,
+            &interrupted,
+            format,
+        )?
+        else {
+            return Ok(String::new());
+        };
+
+        let Some(ref pipeline_name) = args.then_pipeline else {
+            return finished_output(&build, format);
+        };
+        let pipeline = args
+            .when
+            .matches(build.status)
+            .then(|| trigger_next_pipeline(client, app_slug, pipeline_name, &build, args))
+            .transpose()?;
+        let pipeline = pipeline.as_ref();
+        return chained_output(app_slug, &build, pipeline_name, args.when, pipeline, format);
     }
 
     match format {
         OutputFormat::Pretty => Ok(String::new()), // Already printed above
         OutputFormat::Json => {
-            let json = serde_json::to_string_pretty(&build)?;
+            let json = json::format_json(&build)?;
             Ok(json)
         }
     }
 }
 
+/// Resolve the workflow to trigger from `--workflow` or the app's config
+///
+/// Needs only the config, so `main` calls it before creating the API client
+/// to report a missing workflow without requiring a token first.
+pub fn resolve_workflow<'a>(config: &'a Config, args: &'a TriggerArgs) -> Result<&'a str> {
+    if let Some(ref workflow) = args.workflow {
+        return Ok(workflow);
+    }
+
+    let app = args.app.as_deref().or(config.defaults.app_slug.as_deref());
+    app.and_then(|slug| config.app_config(slug).workflow.as_deref())
+        .ok_or_else(|| {
+            RepriseError::InvalidArgument(format!(
+                "No workflow specified. Use --workflow or set 'workflow' under [apps.\"{}\"] in config",
+                app.unwrap_or("<slug>")
+            ))
+        })
+}
+
+/// When `--at` or `--in` asks to delay the trigger, the time to trigger at
+fn scheduled_start(args: &TriggerArgs) -> Result<Option<DateTime<Local>>> {
+    let now = Local::now();
+    if let Some(ref at) = args.at {
+        return duration::next_time_of_day(at, now).map(Some);
+    }
+    match args.in_ {
+        Some(ref delay) => Ok(Some(now + duration::parse_duration(delay)?)),
+        None => Ok(None),
+    }
+}
+
+/// Sleep until `start_at` with a countdown; `false` if cancelled with Ctrl+C
+fn wait_until(
+    start_at: DateTime<Local>,
+    workflow: &str,
+    interrupted: &AtomicBool,
+    format: OutputFormat,
+) -> bool {
+    let message = |left: Option<u64>| {
+        let left = left.map(|secs| format!(" - {} left", format_elapsed(secs)));
+        format!(
+            "Triggering {} at {}{} (Ctrl+C to cancel)",
+            workflow,
+            start_at.format("%a %H:%M:%S"),
+            left.unwrap_or_default()
+        )
+    };
+    // Without a terminal the countdown would print a line every second
+    let live = io::stderr().is_terminal();
+    let mut status_line =
+        (format == OutputFormat::Pretty).then(|| StatusLine::start(&message(None)));
+
+    loop {
+        if is_interrupted(interrupted) {
+            return false;
+        }
+        let Ok(left) = (start_at - Local::now()).to_std() else {
+            break;
+        };
+        if left.is_zero() {
+            break;
+        }
+        if let (Some(line), true) = (status_line.as_mut(), live) {
+            line.set_message(&message(Some(left.as_secs() + 1)));
+        }
+        thread::sleep(left.min(std::time::Duration::from_secs(1)));
+    }
+    if let Some(ref mut line) = status_line {
+        line.finish();
+    }
+    !is_interrupted(interrupted)
+}
+
+/// Find a running build of `workflow`, on `branch` and at `commit` when
+/// those are given
+fn find_running_build(
+    client: &BitriseClient,
+    app_slug: &str,
+    workflow: &str,
+    branch: Option<&str>,
+    commit: Option<&str>,
+) -> Result<Option<Build>> {
+    let status = Some(BuildStatus::Running);
+    let running = client.list_builds(app_slug, status, branch, Some(workflow), 50)?.data;
+    Ok(running.into_iter().find(|build| {
+        commit.is_none_or(|sha| {
+            build.commit_hash.as_deref().is_some_and(|full| git::commit_matches(full, sha))
+        })
+    }))
+}
+
 /// Wait for a build to complete
 fn wait_for_build(
     client: &BitriseClient,
@@ -77,63 +242,133 @@ fn wait_for_build(
     build_slug: &str,
     interval_secs: u64,
     send_notification: bool,
+    interrupted: &AtomicBool,
     format: OutputFormat,
-) -> Result<String> {
-    // Set up signal handler for graceful Ctrl+C handling
-    let interrupted = setup_interrupt_handler();
+) -> Result<Option<Build>> {
+    let mut status_line = (format == OutputFormat::Pretty)
+        .then(|| StatusLine::start("Waiting for build to complete (Ctrl+C to stop)"));
 
-    if format == OutputFormat::Pretty {
-        eprintln!("\n{} Waiting for build to complete (Ctrl+C to stop)...", "->".cyan());
+    let options = WatchOptions {
+        on_build_finished: Some(hooks::build_finished),
+        ..WatchOptions::new(interval_secs)
+    };
+    let finished = watch::watch_build(client, app_slug, build_slug, &options, |build| {
+        // Still running - show progress
+        if let Some(ref mut line) = status_line {
+            if let Some(started_at) = build.started_on_worker_at {
+                line.set_started_at(started_at);
+                let workflow = &build.triggered_workflow;
+                line.set_expected(eta::expected_duration(client, app_slug, workflow));
+            }
+            line.set_message(&build_progress_message(build));
+        }
+        !is_interrupted(interrupted)
+    })?;
+    if let Some(ref mut line) = status_line {
+        line.finish();
     }
 
-    loop {
-        // Check for interrupt
-        if is_interrupted(&interrupted) {
-            if format == OutputFormat::Pretty {
-                eprintln!("\n{} Interrupted - build continues in background", "!".yellow());
-                eprintln!("  View at: https://app.bitrise.io/build/{}", build_slug);
-            }
-            return Ok(String::new());
+    let Some(build) = finished else {
+        if format == OutputFormat::Pretty {
+            eprintln!("{} Interrupted - build continues in background", "!".yellow());
+            eprintln!("  View at: https://app.bitrise.io/build/{}", build_slug);
         }
+        return Ok(None);
+    };
 
-        thread::sleep(Duration::from_secs(interval_secs));
+    // Build finished
+    if send_notification {
+        crate::notify::build_completed(&build, None);
+    }
+    Ok(Some(build))
+}
 
-        let build = client.get_build(app_slug, build_slug)?;
+/// Trigger `pipeline_name` on the branch and commit the build ran
+fn trigger_next_pipeline(
+    client: &BitriseClient,
+    app_slug: &str,
+    pipeline_name: &str,
+    build: &Build,
+    args: &TriggerArgs,
+) -> Result<Pipeline> {
+    let source = TriggerSource {
+        commit_hash: build.commit_hash.clone().or_else(|| args.source.commit.clone()),
+        ..args.source.to_source()
+    };
+    let params = PipelineTriggerParams {
+        pipeline_id: pipeline_name.to_string(),
+        branch: Some(build.branch.clone()).filter(|b| !b.is_empty()),
+        source,
+        environments: Vec::new(),
+    };
+    let result = client.trigger_pipeline(app_slug, params);
+    let id = result.as_ref().ok().map(|p| p.id.as_str());
+    history::record("pipeline trigger", app_slug, id, &result);
+    result
+}
 
-        if !build.data.is_running() {
-            // Build finished
-            if send_notification {
-                crate::notify::build_completed(&build.data, None);
+/// Output for a build followed by `--then-pipeline`
+fn chained_output(
+    app_slug: &str,
+    build: &Build,
+    pipeline_name: &str,
+    when: ChainWhen,
+    pipeline: Option<&Pipeline>,
+    format: OutputFormat,
+) -> Result<String> {
+    match format {
+        OutputFormat::Pretty => {
+            let mut output = finished_output(build, format)?;
+            match pipeline {
+                Some(pipeline) => {
+                    output.push_str(&format!(
+                        "\n\n{} Pipeline {} triggered",
+                        "✓".green(),
+                        pipeline_name.bold()
+                    ));
+                    output.push_str(&format!("\n  ID:       {}", pipeline.id.dimmed()));
+                    output.push_str(&format!(
+                        "\n  View at:  https://app.bitrise.io/app/{}/pipelines/{}",
+                        app_slug, pipeline.id
+                    ));
+                }
+                None => output.push_str(&format!(
+                    "\n\n{} Pipeline {} not triggered (--when {})",
+                    "-".dimmed(),
+                    pipeline_name,
+                    when.as_str()
+                )),
             }
+            Ok(output)
+        }
+        OutputFormat::Json => {
+            let result = serde_json::json!({
+                "build": build,
+                "pipeline": pipeline,
+            });
+            json::format_json(&result)
+        }
+    }
+}
 
-            return match format {
-                OutputFormat::Pretty => {
-                    let status_msg = match build.data.status {
-                        1 => format!("\n{} Build completed successfully!", "✓".green()),
-                        2 => format!("\n{} Build failed", "✗".red()),
-                        3 => format!("\n{} Build aborted", "!".yellow()),
-                        _ => format!("\n{} Build finished", "->".cyan()),
-                    };
+/// Output for a finished build
+fn finished_output(build: &Build, format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Pretty => {
+            let status_msg = format!("\n{}", finished_message("Build", build.status));
 
-                    let mut output = status_msg;
-                    output.push_str(&format!("\n  Duration: {}", build.data.duration_display()));
+            let mut output = status_msg;
+            output.push_str(&format!("\n  Duration: {}", build.duration_display()));
 
-                    if let Some(ref reason) = build.data.abort_reason {
-                        output.push_str(&format!("\n  Reason:   {}", reason));
-                    }
+            if let Some(ref reason) = build.abort_reason {
+                output.push_str(&format!("\n  Reason:   {}", reason));
+            }
 
-                    Ok(output)
-                }
-                OutputFormat::Json => {
-                    let json = serde_json::to_string_pretty(&build.data)?;
-                    Ok(json)
-                }
-            };
+            Ok(output)
         }
-
-        // Still running - show progress
-        if format == OutputFormat::Pretty {
-            eprint!(".");
+        OutputFormat::Json => {
+            let json = json::format_json(&build)?;
+            Ok(json)
         }
     }
 }
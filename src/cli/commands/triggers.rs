@@ -0,0 +1,105 @@
+//! Triggers command (the trigger map and workflow triggers in bitrise.yml)
+
+use std::fs;
+
+use colored::Colorize;
+
+use crate::bitrise::triggers::{self, Trigger};
+use crate::bitrise::{yml, BitriseClient};
+use crate::cli::args::{OutputFormat, TriggersArgs};
+use crate::config::Config;
+use crate::error::Result;
+use crate::output::json;
+use crate::output::pretty;
+use crate::output::table::{self, Table};
+
+use super::common::resolve_app;
+
+/// Handle the triggers command for an app's bitrise.yml on Bitrise
+pub fn triggers(
+    client: &BitriseClient,
+    config: &Config,
+    args: &TriggersArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    let contents = client.get_bitrise_yml(app_slug)?;
+    report(&contents, app_slug, format)
+}
+
+/// Handle the triggers command for a local bitrise.yml (no API client needed)
+pub fn triggers_file(path: &str, format: OutputFormat) -> Result<String> {
+    let contents = fs::read_to_string(path)?;
+    report(&contents, path, format)
+}
+
+fn report(contents: &str, source: &str, format: OutputFormat) -> Result<String> {
+    let found = triggers::triggers(&yml::parse(contents)?);
+    match format {
+        OutputFormat::Pretty => Ok(format_triggers(&found, source)),
+        OutputFormat::Json => json::format_json(&found),
+    }
+}
+
+/// Render the triggers as a table, trigger map first
+fn format_triggers(found: &[Trigger], source: &str) -> String {
+    if found.is_empty() {
+        return format!("No triggers in the bitrise.yml of {}.", source)
+            .dimmed()
+            .to_string();
+    }
+
+    let mut table = Table::new(&["LINE", "EVENT", "WHEN", "STARTS"]);
+    for trigger in found {
+        let target = if trigger.target.is_empty() {
+            "(nothing)".yellow().to_string()
+        } else {
+            format!("{} {}", trigger.target_kind.as_str(), trigger.target.bold())
+        };
+        let mut row = vec![
+            trigger.line.to_string(),
+            trigger.event.as_str().to_string(),
+            conditions(trigger),
+            target,
+        ];
+        if !trigger.enabled {
+            row[3] = format!("{} {}", row[3], "(disabled)".dimmed());
+            row[0] = row[0].dimmed().to_string();
+        }
+        table.row(row);
+    }
+
+    let mut output = String::new();
+    if table::headers_shown() {
+        output.push_str(&format!("{} in {}\n", "Triggers".bold(), source));
+        output.push_str(&pretty::separator(pretty::MAX_RULE_WIDTH));
+        output.push('\n');
+    }
+    output.push_str(table.render().trim_end());
+    if found.iter().any(|t| t.trigger_map) {
+        output.push_str(&format!(
+            "\n\n{}",
+            "trigger_map items are checked in order; the first match starts a build.".dimmed()
+        ));
+    }
+    output.trim_end().to_string()
+}
+
+/// Conditions like "branch=main, source_branch=/^feat/"
+fn conditions(trigger: &Trigger) -> String {
+    if trigger.conditions.is_empty() {
+        return "any".to_string();
+    }
+    trigger
+        .conditions
+        .iter()
+        .map(|c| {
+            if c.regex {
+                format!("{}=/{}/", c.name, c.pattern)
+            } else {
+                format!("{}={}", c.name, c.pattern)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -1,5 +1,6 @@
 //! URL command - parse and interact with Bitrise URLs
 
+use std::collections::HashMap;
 use std::io::{self, Write};
 use std::process::Command;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -7,13 +8,30 @@ use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 
+use chrono::{DateTime, Utc};
 use colored::Colorize;
-
-use crate::bitrise::{parse_bitrise_url, BitriseClient, BitriseUrl, Build};
+use serde::{Deserialize, Serialize};
+
+use super::common::{
+    build_progress_message, confirm, fetch_full_log, finished_message, load_apps, show_progress,
+    APPS_CACHE_TTL,
+};
+use super::rebuild::rebuild_and_report;
+use crate::bitrise::{
+    find_url, parse_bitrise_link, BitriseClient, BitriseLink, BitriseUrl, Build, UrlView,
+};
+use crate::cache::Cache;
 use crate::cli::args::{OutputFormat, UrlArgs};
 use crate::config::Config;
 use crate::error::{RepriseError, Result};
+use crate::eta;
+use crate::history;
+use crate::logparse::{Highlighter, StepTracker};
 use crate::output;
+use crate::output::json;
+use crate::output::progress::StatusLine;
+use crate::output::theme;
+use crate::watch::PollSchedule;
 
 /// Check if the URL args are in generation mode (--build, --app, or --pipeline)
 pub fn is_generation_mode(args: &UrlArgs) -> bool {
@@ -70,11 +88,8 @@ pub fn url(
     }
 
     // Parse the URL (required when not in generation mode)
-    let url_str = args.url.as_ref()
-        .ok_or_else(|| RepriseError::InvalidArgument(
-            "Either a URL or one of --build, --app, --pipeline is required".to_string()
-        ))?;
-    let parsed = parse_bitrise_url(url_str)?;
+    let url_str = input_url(args)?;
+    let BitriseLink { target: parsed, view } = parse_bitrise_link(&url_str)?;
 
     // Validate flags for URL type
     validate_flags_for_url_type(&parsed, args)?;
@@ -90,10 +105,10 @@ pub fn url(
     // Handle based on URL type
     match parsed {
         BitriseUrl::Build { slug } => {
-            handle_build_url(client, config, &slug, args, format)
+            handle_build_url(client, config, &slug, view.as_ref(), args, format)
         }
         BitriseUrl::App { slug } => {
-            handle_app_url(client, config, &slug, args, format)
+            handle_app_url(client, config, &slug, view.as_ref(), args, format)
         }
         BitriseUrl::Pipeline { app_slug, pipeline_id } => {
             handle_pipeline_url(client, &app_slug, &pipeline_id, args, format)
@@ -140,7 +155,7 @@ fn handle_url_generation(
                     "url": url
                 }),
             };
-            Ok(serde_json::to_string_pretty(&json)?)
+            json::format_json(&json)
         }
     }
 }
@@ -233,6 +248,7 @@ fn handle_build_url(
     client: &BitriseClient,
     config: &Config,
     build_slug: &str,
+    view: Option<&UrlView>,
     args: &UrlArgs,
     format: OutputFormat,
 ) -> Result<String> {
@@ -246,26 +262,40 @@ fn handle_build_url(
 
     // Handle --retry action: rebuild with same parameters
     if args.retry {
-        return retry_build_action(client, &app_slug, &build, args, format);
+        let wait = args.retry_wait.then_some((args.interval, args.notify));
+        return rebuild_and_report(client, &app_slug, &build, wait, format);
     }
 
     // Handle --download action: download artifacts
     if let Some(ref dir) = args.download_dir {
-        return download_artifacts_action(client, &app_slug, build_slug, dir, format);
+        let result = download_artifacts_action(client, &app_slug, build_slug, dir, format);
+        history::record("download", &app_slug, Some(build_slug), &result);
+        return result;
     }
 
+    // A #log or #artifacts link opens that tab unless a view flag is given
+    let tab = view.filter(|_| !(args.logs || args.follow || args.artifacts || args.watch));
+
     // Handle --logs flag: dump the full build log
-    if args.logs {
-        return dump_build_log(client, &app_slug, build_slug, format);
+    if args.logs || tab == Some(&UrlView::Log) {
+        return dump_build_log(client, config, &app_slug, build_slug, format);
     }
 
     // Handle --follow flag: stream live log output
     if args.follow {
-        return follow_build_log(client, &app_slug, build_slug, args.interval, args.notify, format);
+        return follow_build_log(
+            client,
+            &config.log_highlighter()?,
+            &app_slug,
+            build_slug,
+            args.interval,
+            args.notify,
+            format,
+        );
     }
 
     // Handle --artifacts flag: list build artifacts
-    if args.artifacts {
+    if args.artifacts || tab == Some(&UrlView::Artifacts) {
         return list_build_artifacts(client, &app_slug, build_slug, format);
     }
 
@@ -279,7 +309,7 @@ fn handle_build_url(
 
     // Add URL to output in pretty mode
     if format == OutputFormat::Pretty {
-        if let Some(ref url) = args.url {
+        if let Some(url) = typed_url(args) {
             output.push_str(&format!("\n{} {}\n", "URL:".dimmed(), url));
         }
     }
@@ -287,41 +317,129 @@ fn handle_build_url(
     Ok(output)
 }
 
+/// Cache key for the build slug → app slug index
+const BUILD_APPS_CACHE_KEY: &str = "build-apps";
+
+/// Most builds kept in the index; the least recently resolved go first
+const BUILD_APPS_MAX: usize = 1000;
+
+/// Maximum number of apps probed for a build at the same time
+const MAX_CONCURRENT_PROBES: usize = 8;
+
+/// The app a build belongs to, and when that was last confirmed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BuildApp {
+    app_slug: String,
+    resolved_at: DateTime<Utc>,
+}
+
 /// Find a build and return both the build and its app_slug
+///
+/// Builds never move between apps, so every resolved build is kept in a
+/// cached index that's tried first. Otherwise the default app is tried,
+/// then every other app is probed, several at a time.
 fn find_build_with_app(
     client: &BitriseClient,
     config: &Config,
     build_slug: &str,
 ) -> Result<(Build, String)> {
-    // First try the default app if configured
-    if let Some(app_slug) = config.defaults.app_slug.as_deref() {
-        if let Ok(response) = client.get_build(app_slug, build_slug) {
-            return Ok((response.data, app_slug.to_string()));
+    let cache = Cache::for_config(&config.cache).ok();
+    let mut index: HashMap<String, BuildApp> = cache
+        .as_ref()
+        .and_then(|c| c.read(BUILD_APPS_CACHE_KEY))
+        .map(|entry| entry.value)
+        .unwrap_or_default();
+
+    let indexed = index.get(build_slug).map(|b| b.app_slug.clone());
+    let default_app = config.defaults.app_slug.clone();
+    let mut tried: Vec<String> = Vec::new();
+    let mut found = None;
+    for app_slug in indexed.into_iter().chain(default_app) {
+        if tried.contains(&app_slug) {
+            continue;
         }
+        if let Ok(response) = client.get_build(&app_slug, build_slug) {
+            found = Some((response.data, app_slug));
+            break;
+        }
+        tried.push(app_slug);
+    }
+
+    if found.is_none() {
+        let ttl = config.cache.ttl.map_or(APPS_CACHE_TTL, Duration::from_secs);
+        let apps = load_apps(client, cache.as_ref(), ttl, false)?;
+        let candidates: Vec<&str> = apps
+            .iter()
+            .map(|app| app.slug.as_str())
+            .filter(|slug| !tried.iter().any(|t| t == slug))
+            .collect();
+        found = probe_apps(client, &candidates, build_slug);
     }
 
-    // Search through all accessible apps
-    let apps = client.list_apps(50)?;
-    for app in &apps.data {
-        if let Ok(response) = client.get_build(&app.slug, build_slug) {
-            return Ok((response.data, app.slug.clone()));
+    let Some((build, app_slug)) = found else {
+        return Err(RepriseError::BuildNotFound(format!(
+            "Build {} not found in any accessible app. Try setting a default app with 'reprise app set'.",
+            build_slug
+        )));
+    };
+
+    index.insert(
+        build_slug.to_string(),
+        BuildApp { app_slug: app_slug.clone(), resolved_at: Utc::now() },
+    );
+    if index.len() > BUILD_APPS_MAX {
+        let mut by_age: Vec<(String, DateTime<Utc>)> =
+            index.iter().map(|(slug, b)| (slug.clone(), b.resolved_at)).collect();
+        by_age.sort_by_key(|(_, resolved_at)| *resolved_at);
+        for (slug, _) in by_age.into_iter().take(index.len() - BUILD_APPS_MAX) {
+            index.remove(&slug);
         }
     }
+    if let Some(cache) = cache {
+        // A cache write failure only costs a probe next time
+        let _ = cache.put(BUILD_APPS_CACHE_KEY, &index);
+    }
 
-    Err(RepriseError::BuildNotFound(format!(
-        "Build {} not found in any accessible app. Try setting a default app with 'reprise app set'.",
-        build_slug
-    )))
+    Ok((build, app_slug))
+}
+
+/// Look for a build in each of `app_slugs`, a few apps at a time; the
+/// first app in list order that has it wins
+fn probe_apps(
+    client: &BitriseClient,
+    app_slugs: &[&str],
+    build_slug: &str,
+) -> Option<(Build, String)> {
+    for chunk in app_slugs.chunks(MAX_CONCURRENT_PROBES) {
+        let found: Vec<Option<Build>> = thread::scope(|scope| {
+            let handles: Vec<_> = chunk
+                .iter()
+                .map(|app_slug| {
+                    scope.spawn(move || client.get_build(app_slug, build_slug).ok().map(|r| r.data))
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().ok().flatten()).collect()
+        });
+        let hit = chunk
+            .iter()
+            .zip(found)
+            .find_map(|(app_slug, build)| Some((build?, app_slug.to_string())));
+        if hit.is_some() {
+            return hit;
+        }
+    }
+    None
 }
 
 /// Dump the full build log
 fn dump_build_log(
     client: &BitriseClient,
+    config: &Config,
     app_slug: &str,
     build_slug: &str,
     format: OutputFormat,
 ) -> Result<String> {
-    let log_content = client.get_full_log(app_slug, build_slug)?;
+    let log_content = fetch_full_log(client, config, app_slug, build_slug)?;
 
     if log_content.is_empty() {
         return Err(RepriseError::LogNotAvailable(
@@ -330,7 +448,7 @@ fn dump_build_log(
     }
 
     match format {
-        OutputFormat::Pretty => Ok(highlight_log_content(&log_content)),
+        OutputFormat::Pretty => Ok(config.log_highlighter()?.highlight(&log_content)),
         OutputFormat::Json => {
             let result = serde_json::json!({
                 "build_slug": build_slug,
@@ -338,7 +456,7 @@ fn dump_build_log(
                 "log": log_content,
                 "lines": log_content.lines().count()
             });
-            Ok(serde_json::to_string_pretty(&result)?)
+            json::format_json(&result)
         }
     }
 }
@@ -346,6 +464,7 @@ fn dump_build_log(
 /// Stream live log output for a running build
 fn follow_build_log(
     client: &BitriseClient,
+    highlighter: &Highlighter,
     app_slug: &str,
     build_slug: &str,
     interval_secs: u64,
@@ -364,17 +483,16 @@ fn follow_build_log(
     })
     .ok();
 
-    if format == OutputFormat::Pretty {
-        eprintln!(
-            "{} Following build log (Ctrl+C to stop)...\n",
-            "->".cyan()
-        );
-    }
+    let mut status_line = (format == OutputFormat::Pretty)
+        .then(|| StatusLine::start("Following build log (Ctrl+C to stop)"));
+    let mut steps = StepTracker::new();
 
+    let schedule = PollSchedule::start_secs(interval_secs);
     loop {
         // Check for interrupt
         if interrupted.load(Ordering::SeqCst) {
-            if format == OutputFormat::Pretty {
+            if let Some(ref mut status) = status_line {
+                status.finish();
                 eprintln!("\n{} Interrupted by user", "!".yellow());
             }
             break;
@@ -389,7 +507,7 @@ fn follow_build_log(
             Err(_) => {
                 // Log may not be available yet
                 if build.data.is_running() {
-                    thread::sleep(Duration::from_secs(interval_secs));
+                    schedule.sleep();
                     continue;
                 }
                 return Err(RepriseError::LogNotAvailable(
@@ -404,33 +522,54 @@ fn follow_build_log(
 
         // Print new lines
         if !new_lines.is_empty() {
-            for line in new_lines {
-                match format {
-                    OutputFormat::Pretty => {
-                        writeln!(stdout, "{}", highlight_log_line(line))?;
-                    }
-                    OutputFormat::Json => {
-                        let json = serde_json::json!({ "line": line });
-                        writeln!(stdout, "{}", serde_json::to_string(&json)?)?;
+            let mut print = || -> Result<()> {
+                for line in new_lines {
+                    match format {
+                        OutputFormat::Pretty => {
+                            writeln!(stdout, "{}", highlighter.highlight_line(line))?;
+                        }
+                        OutputFormat::Json => {
+                            let json = serde_json::json!({ "line": line });
+                            writeln!(stdout, "{}", serde_json::to_string(&json)?)?;
+                        }
                     }
                 }
+                stdout.flush()?;
+                Ok(())
+            };
+            match status_line {
+                Some(ref status) => status.hide_while(print)?,
+                None => print()?,
+            }
+            for line in new_lines {
+                steps.feed(line);
             }
-            stdout.flush()?;
             last_line_count = lines.len();
         }
 
+        // Show where the build is while it runs
+        if build.data.is_running() {
+            if let Some(ref mut status) = status_line {
+                if let Some(started_at) = build.data.started_on_worker_at {
+                    status.set_started_at(started_at);
+                    let workflow = &build.data.triggered_workflow;
+                    status.set_expected(eta::expected_duration(client, app_slug, workflow));
+                }
+                status.set_message(&build_progress_message(&build.data));
+                status.set_step(steps.current());
+            }
+        }
+
         // Check if build is done
         if !build.data.is_running() {
-            if format == OutputFormat::Pretty {
-                let status_msg = match build.data.status {
-                    1 => format!("\n{} Build completed successfully", "✓".green()),
-                    2 => format!("\n{} Build failed", "✗".red()),
-                    3 => format!("\n{} Build aborted", "!".yellow()),
-                    _ => format!("\n{} Build finished", "->".cyan()),
-                };
+            if let Some(ref mut status) = status_line {
+                status.finish();
+                let status_msg = format!("\n{}", finished_message("Build", build.data.status));
                 eprintln!("{}", status_msg);
             }
 
+            crate::hooks::build_finished(app_slug, &build.data);
+
             // Send desktop notification if requested
             if send_notification {
                 crate::notify::build_completed(&build.data, None);
@@ -440,7 +579,7 @@ fn follow_build_log(
         }
 
         // Wait before next poll
-        thread::sleep(Duration::from_secs(interval_secs));
+        schedule.sleep();
     }
 
     Ok(String::new())
@@ -458,65 +597,15 @@ fn list_build_artifacts(
     if response.data.is_empty() {
         return match format {
             OutputFormat::Pretty => Ok(format!("{} No artifacts found for this build.", "!".yellow())),
-            OutputFormat::Json => Ok(serde_json::to_string_pretty(&response.data)?),
+            OutputFormat::Json => json::format_json(&response.data),
         };
     }
 
     output::format_artifacts(&response.data, format)
 }
 
-/// Highlight a single log line based on content
-fn highlight_log_line(line: &str) -> String {
-    let line_lower = line.to_lowercase();
-
-    // Error patterns (red)
-    if line_lower.contains("error")
-        || line_lower.contains("failed")
-        || line_lower.contains("failure")
-        || line_lower.contains("fatal")
-        || line_lower.contains("exception")
-        || line_lower.contains("panic")
-        || line.starts_with("E ")
-        || line.contains("[ERROR]")
-        || line.contains("[error]")
-    {
-        return line.red().to_string();
-    }
-
-    // Warning patterns (yellow)
-    if line_lower.contains("warning")
-        || line_lower.contains("warn")
-        || line.starts_with("W ")
-        || line.contains("[WARN]")
-        || line.contains("[warn]")
-    {
-        return line.yellow().to_string();
-    }
-
-    // Success patterns (green)
-    if line_lower.contains("success")
-        || line_lower.contains("passed")
-        || line_lower.contains("completed")
-        || line.contains("[OK]")
-        || line.contains("BUILD SUCCESSFUL")
-    {
-        return line.green().to_string();
-    }
-
-    line.to_string()
-}
-
-/// Apply highlighting to full log content
-fn highlight_log_content(content: &str) -> String {
-    content
-        .lines()
-        .map(highlight_log_line)
-        .collect::<Vec<_>>()
-        .join("\n")
-}
-
 /// Watch a build until it completes (with known app_slug)
-fn watch_build_with_app(
+pub(super) fn watch_build_with_app(
     client: &BitriseClient,
     app_slug: &str,
     build_slug: &str,
@@ -535,20 +624,18 @@ fn watch_build_with_app(
     })
     .ok();
 
-    if format == OutputFormat::Pretty {
-        eprintln!(
-            "{} Watching build (Ctrl+C to stop)...\n",
-            "->".cyan()
-        );
-    }
+    let mut status_line =
+        (format == OutputFormat::Pretty).then(|| StatusLine::start("Watching build (Ctrl+C to stop)"));
 
-    let mut last_status = -1;
+    let mut last_status = None;
 
+    let schedule = PollSchedule::start_secs(interval_secs);
     loop {
         // Check for interrupt
         if interrupted.load(Ordering::SeqCst) {
-            if format == OutputFormat::Pretty {
-                eprintln!("\n{} Interrupted by user", "!".yellow());
+            if let Some(ref mut line) = status_line {
+                line.finish();
+                eprintln!("{} Interrupted by user", "!".yellow());
             }
             break;
         }
@@ -556,49 +643,34 @@ fn watch_build_with_app(
         // Get build status
         let build = client.get_build(app_slug, build_slug)?.data;
 
-        // Print status update if changed
-        if build.status != last_status {
-            let status_str = match build.status {
-                0 => "RUNNING".yellow().bold(),
-                1 => "SUCCESS".green().bold(),
-                2 => "FAILED".red().bold(),
-                3 => "ABORTED".red(),
-                _ => "UNKNOWN".dimmed(),
-            };
-
-            match format {
-                OutputFormat::Pretty => {
-                    writeln!(
-                        stdout,
-                        "{} Build #{} - {} ({})",
-                        "->".cyan(),
-                        build.build_number,
-                        status_str,
-                        build.duration_display()
-                    )?;
-                }
-                OutputFormat::Json => {
-                    let json = serde_json::json!({
-                        "build_number": build.build_number,
-                        "status": build.status_text,
-                        "duration": build.duration_display()
-                    });
-                    writeln!(stdout, "{}", serde_json::to_string(&json)?)?;
+        match status_line {
+            Some(ref mut line) => {
+                if let Some(started_at) = build.started_on_worker_at {
+                    line.set_started_at(started_at);
+                    let workflow = &build.triggered_workflow;
+                    line.set_expected(eta::expected_duration(client, app_slug, workflow));
                 }
+                line.set_message(&build_progress_message(&build));
             }
-            stdout.flush()?;
-            last_status = build.status;
+            // JSON: one line per status change
+            None if last_status != Some(build.status) => {
+                let json = serde_json::json!({
+                    "build_number": build.build_number,
+                    "status": build.status_text,
+                    "duration": build.duration_display()
+                });
+                writeln!(stdout, "{}", serde_json::to_string(&json)?)?;
+                stdout.flush()?;
+            }
+            None => {}
         }
+        last_status = Some(build.status);
 
         // Check if build is done
         if !build.is_running() {
-            if format == OutputFormat::Pretty {
-                let final_msg = match build.status {
-                    1 => format!("\n{} Build completed successfully!", "✓".green()),
-                    2 => format!("\n{} Build failed", "✗".red()),
-                    3 => format!("\n{} Build aborted", "!".yellow()),
-                    _ => format!("\n{} Build finished", "->".cyan()),
-                };
+            if let Some(ref mut line) = status_line {
+                line.finish();
+                let final_msg = format!("\n{}", finished_message("Build", build.status));
                 eprintln!("{}", final_msg);
 
                 // Show build URL
@@ -609,6 +681,8 @@ fn watch_build_with_app(
                 );
             }
 
+            crate::hooks::build_finished(app_slug, &build);
+
             // Send desktop notification if requested
             if send_notification {
                 crate::notify::build_completed(&build, None);
@@ -618,7 +692,7 @@ fn watch_build_with_app(
         }
 
         // Wait before next poll
-        thread::sleep(Duration::from_secs(interval_secs));
+        schedule.sleep();
     }
 
     Ok(String::new())
@@ -629,9 +703,18 @@ fn handle_app_url(
     client: &BitriseClient,
     config: &mut Config,
     app_slug: &str,
+    view: Option<&UrlView>,
     args: &UrlArgs,
     format: OutputFormat,
 ) -> Result<String> {
+    // A workflow editor link on one workflow lists that workflow's builds
+    if let Some(UrlView::WorkflowEditor { workflow: Some(workflow) }) = view {
+        if !args.set_default {
+            let builds = client.list_builds(app_slug, None, None, Some(workflow), 10)?;
+            return output::format_builds(&builds.data, format);
+        }
+    }
+
     let app = client.get_app(app_slug)?;
 
     // Handle --set-default flag
@@ -655,7 +738,7 @@ fn handle_app_url(
 
     // Add URL to output in pretty mode
     if format == OutputFormat::Pretty && !args.browser {
-        if let Some(ref url) = args.url {
+        if let Some(url) = typed_url(args) {
             output.push_str(&format!("\n{} {}\n", "URL:".dimmed(), url));
         }
     }
@@ -685,7 +768,7 @@ fn handle_pipeline_url(
 
     // Add URL to output in pretty mode
     if format == OutputFormat::Pretty && !args.browser {
-        if let Some(ref url) = args.url {
+        if let Some(url) = typed_url(args) {
             output.push_str(&format!("\n{} {}\n", "URL:".dimmed(), url));
         }
     }
@@ -713,15 +796,16 @@ fn watch_pipeline(
     })
     .ok();
 
-    if format == OutputFormat::Pretty {
+    if show_progress(format) {
         eprintln!(
             "{} Watching pipeline (Ctrl+C to stop)...\n",
             "->".cyan()
         );
     }
 
-    let mut last_status = -1;
+    let mut last_status = None;
 
+    let schedule = PollSchedule::start_secs(interval_secs);
     loop {
         // Check for interrupt
         if interrupted.load(Ordering::SeqCst) {
@@ -736,14 +820,8 @@ fn watch_pipeline(
         let pipeline = response.into_pipeline();
 
         // Print status update if changed
-        if pipeline.status != last_status {
-            let status_str = match pipeline.status {
-                0 => "RUNNING".yellow().bold(),
-                1 => "SUCCESS".green().bold(),
-                2 => "FAILED".red().bold(),
-                3 => "ABORTED".red(),
-                _ => "UNKNOWN".dimmed(),
-            };
+        if last_status != Some(pipeline.status) {
+            let status_str = theme::heading(pipeline.status);
 
             match format {
                 OutputFormat::Pretty => {
@@ -758,13 +836,7 @@ fn watch_pipeline(
 
                     // Show workflow statuses
                     for wf in &pipeline.workflows {
-                        let wf_status = match wf.status {
-                            0 => "●".yellow(),
-                            1 => "✓".green(),
-                            2 => "✗".red(),
-                            3 => "○".dimmed(),
-                            _ => "?".dimmed(),
-                        };
+                        let wf_status = theme::icon(wf.status);
                         writeln!(stdout, "   {} {}", wf_status, wf.name)?;
                     }
                 }
@@ -784,18 +856,13 @@ fn watch_pipeline(
                 }
             }
             stdout.flush()?;
-            last_status = pipeline.status;
+            last_status = Some(pipeline.status);
         }
 
         // Check if pipeline is done
         if !pipeline.is_running() {
             if format == OutputFormat::Pretty {
-                let final_msg = match pipeline.status {
-                    1 => format!("\n{} Pipeline completed successfully!", "✓".green()),
-                    2 => format!("\n{} Pipeline failed", "✗".red()),
-                    3 => format!("\n{} Pipeline aborted", "!".yellow()),
-                    _ => format!("\n{} Pipeline finished", "->".cyan()),
-                };
+                let final_msg = format!("\n{}", finished_message("Pipeline", pipeline.status));
                 eprintln!("{}", final_msg);
 
                 // Show pipeline URL
@@ -809,40 +876,78 @@ fn watch_pipeline(
 
             // Send desktop notification if requested
             if send_notification {
-                notify_pipeline_completed(&pipeline);
+                crate::notify::pipeline_completed(&pipeline);
             }
 
             break;
         }
 
         // Wait before next poll
-        thread::sleep(Duration::from_secs(interval_secs));
+        schedule.sleep();
     }
 
     Ok(String::new())
 }
 
-/// Send desktop notification when pipeline completes
-fn notify_pipeline_completed(pipeline: &crate::bitrise::Pipeline) {
-    let (title, body) = match pipeline.status {
-        1 => ("Pipeline Succeeded", format!("Pipeline {} completed successfully", pipeline.pipeline_id)),
-        2 => ("Pipeline Failed", format!("Pipeline {} failed", pipeline.pipeline_id)),
-        3 => ("Pipeline Aborted", format!("Pipeline {} was aborted", pipeline.pipeline_id)),
-        _ => ("Pipeline Finished", format!("Pipeline {} finished", pipeline.pipeline_id)),
+/// The URL to act on: the argument, the first link piped on stdin (`-`)
+/// or the first link on the clipboard (`--clipboard`)
+fn input_url(args: &UrlArgs) -> Result<String> {
+    let (text, source) = if args.clipboard {
+        (read_clipboard()?, "the clipboard")
+    } else {
+        match args.url.as_deref() {
+            Some("-") => (io::read_to_string(io::stdin())?, "stdin"),
+            Some(url) => return Ok(url.to_string()),
+            None => {
+                return Err(RepriseError::InvalidArgument(
+                    "Either a URL or one of --build, --app, --pipeline is required".to_string(),
+                ))
+            }
+        }
     };
+    find_url(&text).map(str::to_string).ok_or_else(|| {
+        RepriseError::InvalidArgument(format!("No URL found on {}", source))
+    })
+}
 
-    if let Err(e) = notify_rust::Notification::new()
-        .summary(title)
-        .body(&body)
-        .timeout(notify_rust::Timeout::Milliseconds(5000))
-        .show()
-    {
-        eprintln!("Failed to send notification: {}", e);
+/// The URL as typed on the command line, not `-` for stdin
+fn typed_url(args: &UrlArgs) -> Option<&str> {
+    args.url.as_deref().filter(|url| *url != "-")
+}
+
+/// Read the system clipboard's text
+fn read_clipboard() -> Result<String> {
+    #[cfg(target_os = "macos")]
+    let candidates: &[(&str, &[&str])] = &[("pbpaste", &[])];
+
+    #[cfg(target_os = "windows")]
+    let candidates: &[(&str, &[&str])] =
+        &[("powershell", &["-NoProfile", "-Command", "Get-Clipboard"])];
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let candidates: &[(&str, &[&str])] = &[
+        ("wl-paste", &["--no-newline"]),
+        ("xclip", &["-selection", "clipboard", "-o"]),
+        ("xsel", &["--clipboard", "--output"]),
+    ];
+
+    for (program, program_args) in candidates {
+        if let Ok(output) = Command::new(program).args(*program_args).output() {
+            if output.status.success() {
+                return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+            }
+        }
     }
+
+    let tools: Vec<&str> = candidates.iter().map(|(program, _)| *program).collect();
+    Err(RepriseError::InvalidArgument(format!(
+        "Could not read the clipboard (tried {})",
+        tools.join(", ")
+    )))
 }
 
 /// Open a URL in the default browser
-fn open_url_in_browser(url: &str) -> Result<()> {
+pub(super) fn open_url_in_browser(url: &str) -> Result<()> {
     #[cfg(target_os = "macos")]
     {
         Command::new("open")
@@ -898,31 +1003,24 @@ fn abort_build_action(
                     "build_number": build.build_number,
                     "status": build.status_text,
                 });
-                Ok(serde_json::to_string_pretty(&json)?)
+                json::format_json(&json)
             }
         };
     }
 
     // Confirm unless --yes flag is set
     if !args.yes && format == OutputFormat::Pretty {
-        eprint!(
-            "{} Abort build #{} on branch '{}'? [y/N] ",
-            "?".yellow(),
-            build.build_number,
-            build.branch
-        );
-        io::stdout().flush()?;
-
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-
-        if !input.trim().eq_ignore_ascii_case("y") {
+        let question =
+            format!("Abort build #{} on branch '{}'?", build.build_number, build.branch);
+        if !confirm(&question, "--yes")? {
             return Ok("Aborted.".to_string());
         }
     }
 
     // Abort the build
-    client.abort_build(app_slug, build_slug, args.abort_reason.as_deref())?;
+    let result = client.abort_build(app_slug, build_slug, args.abort_reason.as_deref());
+    history::record("abort", app_slug, Some(build_slug), &result);
+    result?;
 
     match format {
         OutputFormat::Pretty => {
@@ -946,88 +1044,7 @@ fn abort_build_action(
                 "build_slug": build_slug,
                 "reason": args.abort_reason,
             });
-            Ok(serde_json::to_string_pretty(&json)?)
-        }
-    }
-}
-
-/// Retry/rebuild a build from URL
-fn retry_build_action(
-    client: &BitriseClient,
-    app_slug: &str,
-    build: &Build,
-    args: &UrlArgs,
-    format: OutputFormat,
-) -> Result<String> {
-    use crate::bitrise::TriggerParams;
-
-    // Trigger a new build with the same parameters
-    let params = TriggerParams {
-        branch: Some(build.branch.clone()),
-        workflow_id: build.triggered_workflow.clone(),
-        commit_message: build.commit_message.clone(),
-        environments: vec![],
-    };
-
-    let new_build = client.trigger_build(app_slug, params)?;
-
-    let new_build_slug = &new_build.slug;
-    let new_build_number = new_build.build_number;
-
-    // If --wait flag, watch the build until completion
-    if args.retry_wait {
-        if format == OutputFormat::Pretty {
-            eprintln!(
-                "{} Triggered rebuild #{}, waiting for completion...\n",
-                "✓".green(),
-                new_build_number
-            );
-        }
-        return watch_build_with_app(
-            client,
-            app_slug,
-            new_build_slug,
-            args.interval,
-            args.notify,
-            format,
-        );
-    }
-
-    match format {
-        OutputFormat::Pretty => {
-            let mut output = String::new();
-            output.push_str(&format!(
-                "{} Triggered rebuild\n",
-                "✓".green()
-            ));
-            output.push_str(&format!(
-                "  Original: #{} ({}, {})\n",
-                build.build_number,
-                build.triggered_workflow,
-                build.branch
-            ));
-            output.push_str(&format!(
-                "  New:      #{} (slug: {})\n",
-                new_build_number,
-                new_build_slug
-            ));
-            output.push_str(&format!(
-                "\n  URL: https://app.bitrise.io/build/{}",
-                new_build_slug
-            ));
-            Ok(output)
-        }
-        OutputFormat::Json => {
-            let json = serde_json::json!({
-                "status": "triggered",
-                "original_build_number": build.build_number,
-                "new_build_number": new_build_number,
-                "new_build_slug": new_build_slug,
-                "workflow": build.triggered_workflow,
-                "branch": build.branch,
-                "url": format!("https://app.bitrise.io/build/{}", new_build_slug)
-            });
-            Ok(serde_json::to_string_pretty(&json)?)
+            json::format_json(&json)
         }
     }
 }
@@ -1053,7 +1070,7 @@ fn download_artifacts_action(
                     "directory": dir,
                     "message": "No artifacts found"
                 });
-                Ok(serde_json::to_string_pretty(&json)?)
+                json::format_json(&json)
             }
         };
     }
@@ -1078,13 +1095,13 @@ fn download_artifacts_action(
             let safe_filename = sanitize_artifact_filename(&artifact.title)?;
             let file_path = download_dir.join(&safe_filename);
 
-            if format == OutputFormat::Pretty {
+            if show_progress(format) {
                 eprint!("Downloading {}... ", safe_filename);
             }
 
             client.download_artifact(url, &file_path)?;
 
-            if format == OutputFormat::Pretty {
+            if show_progress(format) {
                 eprintln!("{}", "done".green());
             }
 
@@ -1106,7 +1123,7 @@ fn download_artifacts_action(
                 "downloaded": downloaded,
                 "directory": download_dir.to_string_lossy(),
             });
-            Ok(serde_json::to_string_pretty(&json)?)
+            json::format_json(&json)
         }
     }
 }
@@ -0,0 +1,40 @@
+//! Validate-yml command (check a local bitrise.yml before committing it)
+
+use std::fs;
+use std::io;
+
+use crate::bitrise::validate;
+use crate::cli::args::{OutputFormat, ValidateYmlArgs};
+use crate::config::lint::Severity;
+use crate::error::{RepriseError, Result};
+
+use super::config::lint_report;
+
+/// Handle the validate-yml command (no API client needed)
+///
+/// Returns the report along with the error to exit with when the file has
+/// problems, so the report is printed like any other output (JSON envelope
+/// included) before the command fails.
+pub fn validate_yml(
+    args: &ValidateYmlArgs,
+    format: OutputFormat,
+) -> Result<(String, Option<RepriseError>)> {
+    let (contents, path) = match args.file.as_str() {
+        "-" => (io::read_to_string(io::stdin())?, "stdin"),
+        path => (fs::read_to_string(path)?, path),
+    };
+    let issues = validate::validate(&contents);
+    let report = lint_report(path, &issues, format)?;
+
+    let errors = issues.iter().filter(|i| i.severity == Severity::Error).count();
+    let failing = if args.strict { issues.len() } else { errors };
+    let failure = (failing > 0).then(|| {
+        RepriseError::YmlInvalid(format!(
+            "{} has {} error(s), {} warning(s)",
+            path,
+            errors,
+            issues.len() - errors
+        ))
+    });
+    Ok((report, failure))
+}
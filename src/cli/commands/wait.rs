@@ -0,0 +1,177 @@
+//! Wait command (block until CI for a commit finishes)
+
+use std::collections::HashSet;
+use std::time::Instant;
+
+use chrono::Utc;
+
+use super::common::{
+    is_interrupted, resolve_app, search_builds, search_builds_in_window, setup_interrupt_handler,
+};
+use crate::bitrise::{BitriseClient, Build, BuildStatus};
+use crate::cli::args::{OutputFormat, WaitArgs};
+use crate::config::Config;
+use crate::duration::{parse_duration, TimeWindow};
+use crate::error::{RepriseError, Result};
+use crate::git;
+use crate::output::{json, pretty, theme};
+use crate::output::progress::StatusLine;
+use crate::watch::PollSchedule;
+
+/// Allowance for the local clock being ahead of Bitrise's
+const CLOCK_SKEW_MINUTES: i64 = 5;
+
+/// Handle the wait command
+///
+/// Returns the report along with the error to exit with when a build failed,
+/// so the report is printed like any other output before the command fails.
+/// Timeouts and interrupts are plain errors; either way the exit code can
+/// gate scripts.
+pub fn wait(
+    client: &BitriseClient,
+    config: &Config,
+    args: &WaitArgs,
+    format: OutputFormat,
+) -> Result<(String, Option<RepriseError>)> {
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    let sha = git::resolve_commit_arg(&args.commit)?;
+    let timeout = parse_duration(&args.timeout)?
+        .to_std()
+        .map_err(|_| RepriseError::InvalidArgument(format!("Invalid timeout: {}", args.timeout)))?;
+    let short = &sha[..sha.len().min(7)];
+
+    let interrupted = setup_interrupt_handler();
+    let started = Instant::now();
+    let schedule = PollSchedule::start_secs(args.interval);
+
+    let mut status_line = (format == OutputFormat::Pretty).then(|| {
+        StatusLine::start(&format!(
+            "Waiting for CI on {} (timeout {}, Ctrl+C to stop)",
+            short, args.timeout
+        ))
+    });
+
+    // The first poll searches the build history for the commit; later ones
+    // only look back to its oldest build (or to when waiting started), which
+    // is usually a single page
+    let for_commit = |b: &Build| {
+        b.commit_hash
+            .as_deref()
+            .is_some_and(|hash| git::commit_matches(hash, &sha))
+    };
+    let workflow = args.workflow.as_deref();
+    let mut window: Option<TimeWindow> = None;
+
+    let builds = loop {
+        if is_interrupted(&interrupted) {
+            return Err(RepriseError::Interrupted);
+        }
+
+        let found = match window {
+            Some(ref w) => {
+                search_builds_in_window(client, app_slug, None, None, workflow, 20, w, for_commit)?
+            }
+            None => {
+                let found = search_builds(client, app_slug, None, None, workflow, 20, for_commit)?;
+                let waiting_since = Utc::now() - chrono::Duration::minutes(CLOCK_SKEW_MINUTES);
+                let since = found.iter().map(|b| b.triggered_at).fold(waiting_since, Ord::min);
+                window = Some(TimeWindow { since: Some(since), until: None });
+                found
+            }
+        };
+        let latest = latest_per_workflow(found);
+
+        if !latest.is_empty() && latest.iter().all(|b| !b.is_running()) {
+            break latest;
+        }
+
+        if started.elapsed() >= timeout {
+            let reason = if latest.is_empty() {
+                format!("no build for {} after {}", short, args.timeout)
+            } else {
+                format!("build for {} still running after {}", short, args.timeout)
+            };
+            if args.notify {
+                crate::notify::send("Bitrise wait timed out", &reason);
+            }
+            return Err(RepriseError::Timeout(reason));
+        }
+
+        if let Some(ref mut line) = status_line {
+            let running: Vec<&str> = latest
+                .iter()
+                .filter(|b| b.is_running())
+                .map(|b| b.triggered_workflow.as_str())
+                .collect();
+            if running.is_empty() {
+                line.set_message(&format!("Waiting for a build of {}", short));
+            } else {
+                line.set_message(&format!("CI on {} running · {}", short, running.join(", ")));
+            }
+        }
+        schedule.sleep();
+    };
+
+    if let Some(ref mut line) = status_line {
+        line.finish();
+    }
+    for build in &builds {
+        crate::hooks::build_finished(app_slug, build);
+    }
+    let failed: Vec<&Build> = builds.iter().filter(|b| b.status != BuildStatus::Success).collect();
+
+    if args.notify {
+        match failed.first() {
+            Some(b) => crate::notify::build_completed(b, None),
+            None => crate::notify::send(
+                "Bitrise Build Succeeded",
+                &format!("All builds for {} passed", short),
+            ),
+        }
+    }
+
+    let report = match format {
+        OutputFormat::Pretty => {
+            let mut output = String::new();
+            for build in &builds {
+                output.push_str(&format!("  {}\n", pretty::format_build_line(build)));
+            }
+            if failed.is_empty() {
+                let icon = theme::icon(BuildStatus::Success);
+                output.push_str(&format!("{} All builds for {} passed", icon, short));
+            } else {
+                let icon = theme::icon(BuildStatus::Failed);
+                output.push_str(&format!("{} CI failed for {}", icon, short));
+            }
+            format!("\n{}", output)
+        }
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "commit": sha,
+                "success": failed.is_empty(),
+                "builds": builds,
+            });
+            json::format_json(&json)?
+        }
+    };
+
+    if failed.is_empty() {
+        return Ok((report, None));
+    }
+
+    let summary = failed
+        .iter()
+        .map(|b| format!("#{} {} ({})", b.build_number, b.triggered_workflow, b.status_display()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Ok((report, Some(RepriseError::BuildFailed(summary))))
+}
+
+/// Keep only the newest build of each workflow (input is newest first)
+fn latest_per_workflow(builds: Vec<Build>) -> Vec<Build> {
+    let mut seen = HashSet::new();
+    builds
+        .into_iter()
+        .filter(|b| seen.insert(b.triggered_workflow.clone()))
+        .collect()
+}
@@ -0,0 +1,87 @@
+//! Wait-build command (block until a build meets a condition, for scripts)
+
+use std::time::Instant;
+
+use super::common::{is_interrupted, resolve_app, setup_interrupt_handler};
+use crate::bitrise::{BitriseClient, Build};
+use crate::cli::args::{OutputFormat, WaitBuildArgs};
+use crate::config::Config;
+use crate::duration::parse_duration;
+use crate::error::{RepriseError, Result};
+use crate::output::json;
+use crate::output::theme;
+use crate::watch::PollSchedule;
+
+/// Handle the wait-build command
+///
+/// Nothing is printed while polling. Returns the report along with the error
+/// to exit with when the build finished without meeting the condition, so the
+/// report is printed like any other output before the command fails. A
+/// timeout and an interrupt are plain errors, each with its own exit code.
+pub fn wait_build(
+    client: &BitriseClient,
+    config: &Config,
+    args: &WaitBuildArgs,
+    format: OutputFormat,
+) -> Result<(String, Option<RepriseError>)> {
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    let timeout = parse_duration(&args.timeout)?
+        .to_std()
+        .map_err(|_| RepriseError::InvalidArgument(format!("Invalid timeout: {}", args.timeout)))?;
+
+    let interrupted = setup_interrupt_handler();
+    let started = Instant::now();
+    let schedule = PollSchedule::start_secs(args.interval);
+
+    let build = loop {
+        if is_interrupted(&interrupted) {
+            return Err(RepriseError::Interrupted);
+        }
+        let build = client.get_build(app_slug, &args.slug)?.data;
+        if build.status.is_finished() {
+            break build;
+        }
+        if started.elapsed() >= timeout {
+            return Err(RepriseError::Timeout(format!(
+                "build #{} still running after {}",
+                build.build_number, args.timeout
+            )));
+        }
+        schedule.sleep();
+    };
+
+    let met = args.until.met_by(build.status);
+    let report = match format {
+        OutputFormat::Pretty => format_result(&build),
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "until": args.until.to_string(),
+                "met": met,
+                "build": build,
+            });
+            json::format_json(&json)?
+        }
+    };
+    if met {
+        return Ok((report, None));
+    }
+
+    let unmet = RepriseError::ConditionUnmet(format!(
+        "build #{} finished {}, waited for {}",
+        build.build_number,
+        build.status_display(),
+        args.until
+    ));
+    Ok((report, Some(unmet)))
+}
+
+/// e.g. `✓ Build #42 success · primary`
+fn format_result(build: &Build) -> String {
+    format!(
+        "{} Build #{} {} · {}",
+        theme::icon(build.status),
+        build.build_number,
+        build.status_display(),
+        build.triggered_workflow
+    )
+}
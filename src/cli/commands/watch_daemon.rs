@@ -0,0 +1,196 @@
+//! Watch-daemon command (poll apps and branches, report status changes)
+
+use std::thread;
+use std::time::Duration;
+
+use chrono::Local;
+use colored::Colorize;
+
+use super::common::{is_interrupted, resolve_app, setup_interrupt_handler, show_progress};
+use crate::bitrise::{BitriseClient, Build, BuildStatus};
+use crate::cache::Cache;
+use crate::cli::args::{OutputFormat, WatchDaemonArgs};
+use crate::config::Config;
+use crate::daemon::{self, DaemonState, Observation, Target, DEFAULT_INTERVAL, STATE_KEY};
+use crate::error::{RepriseError, Result};
+use crate::notify::slack;
+use crate::output::theme;
+
+/// A target with its app resolved
+struct Watched {
+    target: Target,
+    app_slug: String,
+}
+
+/// Handle the watch-daemon command
+pub fn watch_daemon(
+    client: &BitriseClient,
+    config: &Config,
+    args: &WatchDaemonArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let specs = if args.targets.is_empty() { &config.daemon.targets } else { &args.targets };
+    if specs.is_empty() {
+        return Err(RepriseError::InvalidArgument(
+            "No targets to watch; pass APP or APP:BRANCH, or set daemon.targets in the config"
+                .to_string(),
+        ));
+    }
+    let mut watched = Vec::new();
+    for spec in specs {
+        let target = Target::parse(spec).map_err(RepriseError::InvalidArgument)?;
+        let app_slug = resolve_app(client, Some(&target.app), config)?;
+        watched.push(Watched { target, app_slug });
+    }
+    let interval = args.interval.or(config.daemon.interval).unwrap_or(DEFAULT_INTERVAL);
+    let webhook = args.slack_webhook.as_deref().or(config.daemon.slack_webhook.as_deref());
+
+    let cache = Cache::for_config(&config.cache)?;
+    let mut state: DaemonState = cache.read(STATE_KEY).map(|entry| entry.value).unwrap_or_default();
+
+    let interrupted = setup_interrupt_handler();
+    if show_progress(format) && !args.once {
+        eprintln!(
+            "{} Watching {} (every {}s, Ctrl+C to stop)",
+            "->".cyan(),
+            specs.join(", "),
+            interval
+        );
+    }
+
+    loop {
+        for entry in &watched {
+            let branch = entry.target.branch.as_deref();
+            let latest = match daemon::latest_finished(client, &entry.app_slug, branch) {
+                Ok(latest) => latest,
+                // A daemon outlives network hiccups; a single check reports them
+                Err(e) if !args.once => {
+                    eprintln!("warning: {}: {}", entry.target.label(), e);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            let Some(build) = latest else {
+                continue;
+            };
+            let key = entry.target.key(&entry.app_slug);
+            if let Some(observation) = state.observe(&key, &build) {
+                report(entry, &build, observation, args, webhook, format)?;
+            }
+        }
+        if let Err(e) = cache.put(STATE_KEY, &state) {
+            eprintln!("warning: couldn't save daemon state: {}", e);
+        }
+
+        if args.once {
+            break;
+        }
+        for _ in 0..interval {
+            if is_interrupted(&interrupted) {
+                break;
+            }
+            thread::sleep(Duration::from_secs(1));
+        }
+        if is_interrupted(&interrupted) {
+            if format == OutputFormat::Pretty {
+                eprintln!("\n{} Stopped watching", "!".yellow());
+            }
+            break;
+        }
+    }
+
+    Ok(String::new())
+}
+
+/// Print a newly seen build, and notify when it changed the target's status
+fn report(
+    entry: &Watched,
+    build: &Build,
+    observation: Observation,
+    args: &WatchDaemonArgs,
+    webhook: Option<&str>,
+    format: OutputFormat,
+) -> Result<()> {
+    let label = entry.target.label();
+    let previous = match observation {
+        Observation::Changed { from } => Some(from),
+        Observation::First | Observation::Unchanged => None,
+    };
+
+    match format {
+        OutputFormat::Pretty => {
+            let mut line = format!(
+                "{} {} {} #{} {} · {}",
+                Local::now().format("%H:%M").to_string().dimmed(),
+                theme::icon(build.status),
+                label.bold(),
+                build.build_number,
+                theme::label(build.status),
+                build.triggered_workflow
+            );
+            match observation {
+                Observation::First => line.push_str(&format!(" {}", "(now watching)".dimmed())),
+                Observation::Changed { from } => {
+                    line.push_str(&format!(" (was {})", theme::label(from)));
+                }
+                Observation::Unchanged => {}
+            }
+            println!("{}", line);
+        }
+        OutputFormat::Json => {
+            let event = match observation {
+                Observation::First => "first",
+                Observation::Unchanged => "unchanged",
+                Observation::Changed { .. } => "changed",
+            };
+            let json = serde_json::json!({
+                "time": Local::now().to_rfc3339(),
+                "target": label,
+                "app_slug": entry.app_slug,
+                "event": event,
+                "previous_status": previous.map(BuildStatus::as_str),
+                "build": build,
+            });
+            println!("{}", serde_json::to_string(&json)?);
+        }
+    }
+
+    // The first build may be long finished; only new ones count as events
+    if observation == Observation::First {
+        return Ok(());
+    }
+    crate::hooks::build_finished(&entry.app_slug, build);
+
+    let Some(from) = previous else {
+        return Ok(());
+    };
+    if !args.no_desktop {
+        crate::notify::build_completed(build, Some(&label));
+    }
+    if let Some(webhook) = webhook {
+        if let Err(e) = slack::post(webhook, &slack_message(&label, build, from)) {
+            eprintln!("warning: couldn't post to Slack: {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// Slack text for a status change, e.g. `ios:main is failing: build #42 ...`
+fn slack_message(label: &str, build: &Build, from: BuildStatus) -> String {
+    let (emoji, state) = match build.status {
+        BuildStatus::Success => (":white_check_mark:", "is fixed"),
+        BuildStatus::Failed => (":x:", "is failing"),
+        _ => (":warning:", "was aborted"),
+    };
+    format!(
+        "{} *{}* {}: <https://app.bitrise.io/build/{}|build #{}> of {} {} (was {})",
+        emoji,
+        slack::escape(label),
+        state,
+        build.slug,
+        build.build_number,
+        slack::escape(&build.triggered_workflow),
+        build.status.as_str(),
+        from.as_str()
+    )
+}
@@ -0,0 +1,81 @@
+//! Yml command (compare the bitrise.yml snapshots of builds)
+
+use colored::Colorize;
+
+use super::common::resolve_app;
+use crate::bitrise::{BitriseClient, Build};
+use crate::cli::args::{OutputFormat, YmlArgs, YmlCommands, YmlDiffArgs};
+use crate::config::Config;
+use crate::error::{RepriseError, Result};
+use crate::output::diff;
+use crate::output::json;
+
+/// Handle the yml command
+pub fn yml(
+    client: &BitriseClient,
+    config: &Config,
+    args: &YmlArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    match &args.command {
+        YmlCommands::Diff(args) => yml_diff(client, config, args, format),
+    }
+}
+
+/// Diff the bitrise.yml two builds ran with
+fn yml_diff(
+    client: &BitriseClient,
+    config: &Config,
+    args: &YmlDiffArgs,
+    format: OutputFormat,
+) -> Result<String> {
+    let app_slug = &resolve_app(client, args.app.as_deref(), config)?;
+    let (from, from_yml) = snapshot(client, app_slug, &args.from)?;
+    let (to, to_yml) = snapshot(client, app_slug, &args.to)?;
+
+    let changes = diff::unified(&from_yml, &to_yml, &label(&from), &label(&to));
+    match format {
+        OutputFormat::Pretty => Ok(match changes {
+            Some(changes) => diff::paint(&changes),
+            None => format!(
+                "bitrise.yml is the same in builds #{} and #{}",
+                from.build_number, to.build_number
+            )
+            .dimmed()
+            .to_string(),
+        }),
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "from": { "build_slug": from.slug, "build_number": from.build_number },
+                "to": { "build_slug": to.slug, "build_number": to.build_number },
+                "changed": changes.is_some(),
+                "diff": changes.unwrap_or_default(),
+            });
+            json::format_json(&json)
+        }
+    }
+}
+
+/// A build and the bitrise.yml Bitrise stored for it
+fn snapshot(client: &BitriseClient, app_slug: &str, build_slug: &str) -> Result<(Build, String)> {
+    let build = client.get_build(app_slug, build_slug)?.data;
+    match client.get_build_bitrise_yml(app_slug, build_slug) {
+        Ok(yml) => Ok((build, yml)),
+        // Only "not stored" answers; auth and rate-limit errors pass through as is
+        Err(RepriseError::Api { status: status @ (400 | 404), .. }) => {
+            Err(RepriseError::api(
+                status,
+                format!("build #{} has no stored bitrise.yml", build.build_number),
+            ))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// e.g. `bitrise.yml (build #42, primary on main)`
+fn label(build: &Build) -> String {
+    format!(
+        "bitrise.yml (build #{}, {} on {})",
+        build.build_number, build.triggered_workflow, build.branch
+    )
+}
@@ -0,0 +1,259 @@
+//! Shell completion scripts
+//!
+//! clap_complete knows the commands and flags; app slugs, workflows and
+//! alias names only exist in the user's config and cache. The bash, zsh
+//! and fish scripts are patched to ask `reprise __complete <kind>` for
+//! those values when the shell completes them.
+
+use clap::{Arg, Command, CommandFactory, ValueEnum};
+use clap_complete::Shell;
+
+use super::args::{Cli, CompletionKind};
+
+/// Start of every [`marker`]
+const MARKER: &str = "REPRISE-DYNAMIC-";
+
+/// zsh function offering a kind's candidates, with their descriptions
+const ZSH_HELPER: &str = r#"
+_reprise_complete() {
+    local -a candidates
+    candidates=(${(f)"$(reprise __complete "$1" --shell zsh 2>/dev/null)"})
+    _describe -t "$1" "$1" candidates
+}
+"#;
+
+/// The completion script for a shell
+pub fn script(shell: Shell) -> String {
+    let mut cmd = Cli::command();
+    match shell {
+        Shell::Zsh => cmd = mark_dynamic(cmd, &mut Vec::new()),
+        // The bash generator joins command paths with "__", so the name
+        // would break it; the hidden command is never completed anyway
+        Shell::Bash => cmd = cmd.mut_subcommand("__complete", |sub| sub.name("complete")),
+        _ => {}
+    }
+    let mut buf = Vec::new();
+    clap_complete::generate(shell, &mut cmd, "reprise", &mut buf);
+    let script = String::from_utf8_lossy(&buf).into_owned();
+    match shell {
+        Shell::Bash => patch_bash(&script),
+        Shell::Zsh => patch_zsh(&script),
+        Shell::Fish => patch_fish(&script),
+        _ => script,
+    }
+}
+
+/// Which values an argument takes, for those completed dynamically
+///
+/// `path` holds the subcommand names leading to the argument.
+fn dynamic_kind(path: &[String], arg: &Arg) -> Option<CompletionKind> {
+    if !arg.get_action().takes_values() {
+        return None;
+    }
+    match (arg.get_long(), arg.get_id().as_str()) {
+        (Some("app"), _) => Some(CompletionKind::AppSlugs),
+        (Some("workflow"), _) => Some(CompletionKind::Workflows),
+        (None, "name") if path == ["config", "alias"] => Some(CompletionKind::Aliases),
+        (None, "slug") if path == ["config", "alias"] => Some(CompletionKind::AppSlugs),
+        _ => None,
+    }
+}
+
+fn kind_name(kind: CompletionKind) -> String {
+    kind.to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+/// Value name (or, for positionals, help) marking an argument in the zsh
+/// script, replaced after generation
+fn marker(kind: CompletionKind) -> &'static str {
+    match kind {
+        CompletionKind::AppSlugs => "REPRISE-DYNAMIC-app-slugs",
+        CompletionKind::Workflows => "REPRISE-DYNAMIC-workflows",
+        CompletionKind::Aliases => "REPRISE-DYNAMIC-aliases",
+    }
+}
+
+/// What zsh shows for a dynamic value, in place of the marker
+fn display_name(kind: CompletionKind) -> &'static str {
+    match kind {
+        CompletionKind::AppSlugs => "APP",
+        CompletionKind::Workflows => "WORKFLOW",
+        CompletionKind::Aliases => "ALIAS",
+    }
+}
+
+/// The shell command printing a kind's candidates
+fn callback(kind: CompletionKind, shell: &str) -> String {
+    format!("reprise __complete {} --shell {}", kind_name(kind), shell)
+}
+
+/// Name every dynamic argument's value after its kind, so the zsh script
+/// can be patched where each one is completed
+fn mark_dynamic(mut cmd: Command, path: &mut Vec<String>) -> Command {
+    let marked: Vec<_> = cmd
+        .get_arguments()
+        .filter_map(|arg| Some((arg.get_id().clone(), dynamic_kind(path, arg)?)))
+        .collect();
+    for (id, kind) in marked {
+        // zsh names positionals by id, so their help carries the marker
+        cmd = cmd.mut_arg(id, |arg| match arg.is_positional() {
+            true => arg.help(marker(kind)),
+            false => arg.value_name(marker(kind)),
+        });
+    }
+    let names: Vec<String> = cmd.get_subcommands().map(|s| s.get_name().to_string()).collect();
+    for name in names {
+        path.push(name.clone());
+        cmd = cmd.mut_subcommand(&name, |sub| mark_dynamic(sub, path));
+        path.pop();
+    }
+    cmd
+}
+
+/// Complete marked values with `_reprise_complete <kind>` instead of files
+fn patch_zsh(script: &str) -> String {
+    let mut out = String::with_capacity(script.len() + ZSH_HELPER.len());
+    let mut rest = script;
+    while let Some(start) = rest.find(MARKER) {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let kind = CompletionKind::value_variants()
+            .iter()
+            .copied()
+            .find(|&kind| rest.starts_with(marker(kind)));
+        let Some(kind) = kind else {
+            out.push_str(MARKER);
+            rest = &rest[MARKER.len()..];
+            continue;
+        };
+        rest = &rest[marker(kind).len()..];
+        out.push_str(display_name(kind));
+        // The action follows on the same line: `:APP:_default'`, `-- APP:_default'`
+        let line_end = rest.find('\n').unwrap_or(rest.len());
+        if let Some(end) = rest[..line_end].find(":_default") {
+            out.push_str(&rest[..end]);
+            out.push_str(&format!(":_reprise_complete {}", kind_name(kind)));
+            rest = &rest[end + ":_default".len()..];
+        }
+    }
+    out.push_str(rest);
+    match out.split_once('\n') {
+        Some((first, body)) => format!("{}\n{}{}", first, ZSH_HELPER, body),
+        None => out,
+    }
+}
+
+/// Complete `--app` and `--workflow` values, and alias names after
+/// `config alias`, from `reprise __complete`
+fn patch_bash(script: &str) -> String {
+    let mut out = String::with_capacity(script.len());
+    let mut kind = None;
+    for line in script.lines() {
+        let trimmed = line.trim();
+        match (kind.take(), trimmed) {
+            (Some(kind), "COMPREPLY=($(compgen -f \"${cur}\"))") => {
+                let indent = &line[..line.len() - line.trim_start().len()];
+                out.push_str(&format!(
+                    "{}COMPREPLY=($(compgen -W \"$({} 2>/dev/null)\" -- \"${{cur}}\"))\n",
+                    indent,
+                    callback(kind, "bash")
+                ));
+                continue;
+            }
+            (_, "--app)" | "-a)") => kind = Some(CompletionKind::AppSlugs),
+            (_, "--workflow)" | "-w)") => kind = Some(CompletionKind::Workflows),
+            _ => {}
+        }
+        out.push_str(&line.replace("complete -F _reprise ", "complete -F _reprise_dynamic "));
+        out.push('\n');
+    }
+    out.push_str(&format!(
+        "\n_reprise_dynamic() {{\n    \
+        if [[ ${{COMP_CWORD}} -eq 3 && ${{COMP_WORDS[1]}} == config \
+        && ${{COMP_WORDS[2]}} == alias ]]; then\n        \
+        local names=\"$({} 2>/dev/null) export import\"\n        \
+        COMPREPLY=($(compgen -W \"${{names}}\" -- \"${{COMP_WORDS[3]}}\"))\n        \
+        return 0\n    \
+        fi\n    \
+        _reprise \"$@\"\n}}\n",
+        callback(CompletionKind::Aliases, "bash")
+    ));
+    out
+}
+
+/// Add the dynamic values to the `--app` and `--workflow` lines, and
+/// alias names after `config alias`
+fn patch_fish(script: &str) -> String {
+    let mut out = String::with_capacity(script.len());
+    for line in script.lines() {
+        out.push_str(line);
+        let kind = if !line.ends_with(" -r") {
+            None
+        } else if line.contains(" -l app ") {
+            Some(CompletionKind::AppSlugs)
+        } else if line.contains(" -l workflow ") {
+            Some(CompletionKind::Workflows)
+        } else {
+            None
+        };
+        if let Some(kind) = kind {
+            out.push_str(&format!(" -f -a \"({})\"", callback(kind, "fish")));
+        }
+        out.push('\n');
+    }
+    out.push_str(&format!(
+        "complete -c reprise -n \"__fish_reprise_using_subcommand config; \
+        and __fish_seen_subcommand_from alias; \
+        and not __fish_seen_subcommand_from export import\" -f -a \"({})\"\n",
+        callback(CompletionKind::Aliases, "fish")
+    ));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scripts_call_back_for_dynamic_values() {
+        for (shell, call) in [
+            (Shell::Bash, "reprise __complete"),
+            (Shell::Zsh, "_reprise_complete"),
+            (Shell::Fish, "reprise __complete"),
+        ] {
+            let script = script(shell);
+            for kind in ["app-slugs", "workflows", "aliases"] {
+                assert!(script.contains(&format!("{} {}", call, kind)), "{shell} {kind}");
+            }
+            assert!(!script.contains(MARKER), "{shell}");
+        }
+    }
+
+    #[test]
+    fn test_zsh_completes_alias_names() {
+        let script = script(Shell::Zsh);
+        assert!(script.starts_with("#compdef reprise\n\n_reprise_complete() {"));
+        assert!(script.contains(":_reprise_complete aliases'"));
+        assert!(script.contains("APP:_reprise_complete app-slugs'"));
+    }
+
+    /// The bash script matches `-a` and `-w` by their short names alone
+    #[test]
+    fn test_short_app_and_workflow_flags_are_unambiguous() {
+        fn check(cmd: &Command) {
+            for arg in cmd.get_arguments().filter(|a| a.get_action().takes_values()) {
+                match arg.get_short() {
+                    Some('a') => assert_eq!(arg.get_long(), Some("app"), "{}", cmd.get_name()),
+                    Some('w') => {
+                        assert_eq!(arg.get_long(), Some("workflow"), "{}", cmd.get_name())
+                    }
+                    _ => {}
+                }
+            }
+            cmd.get_subcommands().for_each(check);
+        }
+        check(&Cli::command());
+    }
+}
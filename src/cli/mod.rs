@@ -1,4 +1,6 @@
 pub mod args;
 pub mod commands;
+pub mod completions;
+pub mod topics;
 
 pub use args::{Cli, Commands, OutputFormat};
@@ -0,0 +1,45 @@
+//! Long-form help topics (`reprise help <topic>`)
+//!
+//! Topics explain behavior that spans commands, which no single command's
+//! `--help` is the place for. The text is compiled into the binary.
+
+/// A help topic
+pub struct Topic {
+    pub name: &'static str,
+    /// One line for the topic list
+    pub summary: &'static str,
+    pub text: &'static str,
+}
+
+/// All help topics, in the order they are listed
+pub const TOPICS: &[Topic] = &[
+    Topic {
+        name: "authentication",
+        summary: "Where the API token comes from, and how to store it",
+        text: include_str!("authentication.txt"),
+    },
+    Topic {
+        name: "caching",
+        summary: "What is cached on disk, for how long, and how to clear it",
+        text: include_str!("caching.txt"),
+    },
+    Topic {
+        name: "exit-codes",
+        summary: "What each exit code means, for scripts",
+        text: include_str!("exit-codes.txt"),
+    },
+];
+
+/// Find a topic by name
+pub fn find(name: &str) -> Option<&'static Topic> {
+    TOPICS.iter().find(|topic| topic.name.eq_ignore_ascii_case(name))
+}
+
+/// The topic list shown by `reprise help`
+pub fn list() -> String {
+    let mut list = String::from("Help topics (reprise help <topic>):\n");
+    for topic in TOPICS {
+        list.push_str(&format!("  {:<16}{}\n", topic.name, topic.summary));
+    }
+    list
+}
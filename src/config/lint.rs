@@ -0,0 +1,497 @@
+//! Config file validation
+//!
+//! Checks the raw TOML against the known config layout so typos and bad
+//! values are reported with their line instead of being silently ignored
+//! or failing later in the middle of a command.
+
+use std::fs;
+
+use serde::Serialize;
+
+use super::paths::Paths;
+use super::settings::{Config, QuietHours, ThemeConfig};
+use crate::daemon::Target;
+use crate::logparse::{HighlightRule, Highlighter};
+
+/// Keys accepted in each fixed config section
+const SECTIONS: &[(&str, &[&str])] = &[
+    ("api", &["token"]),
+    ("defaults", &["app_slug", "app_name", "recent"]),
+    ("output", &["format", "pager"]),
+    ("log", &["default_rules", "highlight"]),
+    ("cache", &["ttl", "ttl_secs", "dir", "log_limit_mb"]),
+    ("notify", &["min_interval", "quiet_hours", "webhook_url", "ntfy_topic", "ntfy_server"]),
+    ("hooks", &["on_build_success", "on_build_failure", "on_build_abort", "on_build_finish"]),
+    ("daemon", &["targets", "interval", "slack_webhook"]),
+    ("theme", &["running", "success", "failed", "aborted", "ascii"]),
+    ("update", &["check"]),
+];
+
+/// Keys accepted in an `[apps."<slug>"]` section
+const APP_KEYS: &[&str] = &["workflow", "branch", "interval", "download_dir"];
+
+/// Valid values for `output.format`
+const OUTPUT_FORMATS: &[&str] = &["pretty", "json"];
+
+/// How serious a config problem is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Ignored by reprise, but probably a mistake (e.g., a misspelled key)
+    Warning,
+    /// A value reprise can't use
+    Error,
+}
+
+/// A single problem found in the config file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintIssue {
+    pub severity: Severity,
+    /// Dotted key path (e.g., "output.format")
+    pub key: String,
+    /// 1-based line in the config file, when it can be located
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl LintIssue {
+    fn new(severity: Severity, key: &str, line: Option<usize>, message: String) -> Self {
+        Self {
+            severity,
+            key: key.to_string(),
+            line,
+            message,
+        }
+    }
+}
+
+/// Lint the config file at the given paths (no issues if it doesn't exist)
+pub fn lint_file(paths: &Paths) -> crate::error::Result<Vec<LintIssue>> {
+    if !paths.config_exists() {
+        return Ok(Vec::new());
+    }
+    Ok(lint(&fs::read_to_string(&paths.config_file)?))
+}
+
+/// Lint config file contents
+pub fn lint(contents: &str) -> Vec<LintIssue> {
+    let table = match contents.parse::<toml::Table>() {
+        Ok(table) => table,
+        Err(e) => {
+            let line = e.span().map(|span| line_of_offset(contents, span.start));
+            return vec![LintIssue::new(
+                Severity::Error,
+                "",
+                line,
+                e.message().to_string(),
+            )];
+        }
+    };
+
+    let mut issues = Vec::new();
+    for (key, value) in &table {
+        match key.as_str() {
+            "aliases" => lint_aliases(contents, value, &mut issues),
+            "apps" => lint_apps(contents, value, &mut issues),
+            section => match SECTIONS.iter().find(|(name, _)| *name == section) {
+                Some((_, keys)) => lint_section(contents, section, keys, value, &mut issues),
+                None => issues.push(LintIssue::new(
+                    Severity::Warning,
+                    section,
+                    locate(contents, None, section),
+                    format!("unknown section '{}'", section),
+                )),
+            },
+        }
+    }
+
+    // Catch type errors the walk above doesn't check individually
+    if issues.iter().all(|i| i.severity != Severity::Error) {
+        if let Err(e) = toml::from_str::<Config>(contents) {
+            let line = e.span().map(|span| line_of_offset(contents, span.start));
+            issues.push(LintIssue::new(Severity::Error, "", line, e.message().to_string()));
+        }
+    }
+
+    issues
+}
+
+fn lint_section(
+    contents: &str,
+    section: &str,
+    keys: &[&str],
+    value: &toml::Value,
+    issues: &mut Vec<LintIssue>,
+) {
+    let Some(table) = value.as_table() else {
+        issues.push(LintIssue::new(
+            Severity::Error,
+            section,
+            locate(contents, None, section),
+            format!("'{}' must be a table", section),
+        ));
+        return;
+    };
+
+    for (key, value) in table {
+        let path = format!("{}.{}", section, key);
+        let line = locate(contents, Some(section), key);
+
+        if !keys.contains(&key.as_str()) {
+            issues.push(LintIssue::new(
+                Severity::Warning,
+                &path,
+                line,
+                format!("unknown key '{}' (expected one of: {})", path, keys.join(", ")),
+            ));
+            continue;
+        }
+
+        match path.as_str() {
+            "output.format" => {
+                let valid = value.as_str().is_some_and(|f| OUTPUT_FORMATS.contains(&f));
+                if !valid {
+                    issues.push(LintIssue::new(
+                        Severity::Error,
+                        &path,
+                        line,
+                        format!("output.format must be 'pretty' or 'json', found {}", value),
+                    ));
+                }
+            }
+            "notify.quiet_hours" => {
+                let parsed = match value.as_str() {
+                    Some(range) => QuietHours::parse(range).map(|_| ()),
+                    None => Err(format!("expected a string like '22:00-08:00', found {}", value)),
+                };
+                if let Err(message) = parsed {
+                    issues.push(LintIssue::new(
+                        Severity::Error,
+                        &path,
+                        line,
+                        format!("notify.quiet_hours: {}", message),
+                    ));
+                }
+            }
+            "theme.running" | "theme.success" | "theme.failed" | "theme.aborted" => {
+                let checked = match value.as_str() {
+                    Some(color) => ThemeConfig::check_color(color),
+                    None => Err(format!("expected a color name, found {}", value)),
+                };
+                if let Err(message) = checked {
+                    issues.push(LintIssue::new(
+                        Severity::Error,
+                        &path,
+                        line,
+                        format!("{}: {}", path, message),
+                    ));
+                }
+            }
+            "daemon.targets" => {
+                let specs = value.as_array().map(|specs| specs.iter().map(toml::Value::as_str));
+                let invalid = specs.into_iter().flatten().find_map(|spec| match spec {
+                    Some(spec) => Target::parse(spec).err(),
+                    None => Some("expected strings like \"ios:main\"".to_string()),
+                });
+                if let Some(message) = invalid {
+                    issues.push(LintIssue::new(
+                        Severity::Error,
+                        &path,
+                        line,
+                        format!("daemon.targets: {}", message),
+                    ));
+                }
+            }
+            "log.highlight" => lint_highlight_rules(contents, value, issues),
+            _ => {}
+        }
+    }
+}
+
+fn lint_highlight_rules(contents: &str, value: &toml::Value, issues: &mut Vec<LintIssue>) {
+    let Some(rules) = value.as_array() else {
+        return;
+    };
+
+    for (index, rule) in rules.iter().enumerate() {
+        let path = format!("log.highlight[{}]", index);
+        let line = locate_nth_array_table(contents, "log.highlight", index);
+
+        if let Some(table) = rule.as_table() {
+            for key in table.keys().filter(|k| !["pattern", "color"].contains(&k.as_str())) {
+                issues.push(LintIssue::new(
+                    Severity::Warning,
+                    &format!("{}.{}", path, key),
+                    line,
+                    format!("unknown key '{}' in highlight rule (expected pattern, color)", key),
+                ));
+            }
+        }
+
+        let parsed = rule
+            .clone()
+            .try_into::<HighlightRule>()
+            .map_err(|e| e.message().to_string())
+            .and_then(|rule| {
+                Highlighter::new(&[rule], false)
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            });
+        if let Err(message) = parsed {
+            issues.push(LintIssue::new(Severity::Error, &path, line, message));
+        }
+    }
+}
+
+fn lint_aliases(contents: &str, value: &toml::Value, issues: &mut Vec<LintIssue>) {
+    let Some(table) = value.as_table() else {
+        issues.push(LintIssue::new(
+            Severity::Error,
+            "aliases",
+            locate(contents, None, "aliases"),
+            "'aliases' must be a table of name = \"slug\"".to_string(),
+        ));
+        return;
+    };
+
+    for (name, slug) in table.iter().filter(|(_, slug)| !slug.is_str()) {
+        issues.push(LintIssue::new(
+            Severity::Error,
+            &format!("aliases.{}", name),
+            locate(contents, Some("aliases"), name),
+            format!("alias '{}' must be an app slug string, found {}", name, slug),
+        ));
+    }
+}
+
+fn lint_apps(contents: &str, value: &toml::Value, issues: &mut Vec<LintIssue>) {
+    let Some(apps) = value.as_table() else {
+        issues.push(LintIssue::new(
+            Severity::Error,
+            "apps",
+            locate(contents, None, "apps"),
+            "'apps' must contain [apps.\"<slug>\"] tables".to_string(),
+        ));
+        return;
+    };
+
+    for (slug, app) in apps {
+        let section = format!("apps.{}", slug);
+        let Some(table) = app.as_table() else {
+            continue; // reported by the typed parse
+        };
+        for key in table.keys().filter(|k| !APP_KEYS.contains(&k.as_str())) {
+            issues.push(LintIssue::new(
+                Severity::Warning,
+                &format!("{}.{}", section, key),
+                locate(contents, Some(&section), key),
+                format!(
+                    "unknown key '{}' for app '{}' (expected one of: {})",
+                    key,
+                    slug,
+                    APP_KEYS.join(", ")
+                ),
+            ));
+        }
+    }
+}
+
+/// 1-based line number of a byte offset
+fn line_of_offset(contents: &str, offset: usize) -> usize {
+    contents[..offset.min(contents.len())].matches('\n').count() + 1
+}
+
+/// Normalize a table header (`[apps."abc"]` -> `apps.abc`)
+fn normalize_header(header: &str) -> String {
+    header
+        .split('.')
+        .map(|part| part.trim().trim_matches('"').trim_matches('\''))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Find the line of `key` inside `[section]` (or the top level), falling back
+/// to the `[section.key]` header itself
+fn locate(contents: &str, section: Option<&str>, key: &str) -> Option<usize> {
+    let header_for_key = match section {
+        Some(section) => format!("{}.{}", section, key),
+        None => key.to_string(),
+    };
+    let mut current: Option<String> = None;
+
+    for (index, line) in contents.lines().enumerate() {
+        let trimmed = line.trim();
+        if let Some(header) = trimmed.strip_prefix('[') {
+            let header = header.trim_start_matches('[');
+            let header = header.split(']').next().unwrap_or_default();
+            let header = normalize_header(header);
+            if header == header_for_key || header.starts_with(&format!("{}.", header_for_key)) {
+                return Some(index + 1);
+            }
+            current = Some(header);
+            continue;
+        }
+
+        if current.as_deref() == section {
+            let name = trimmed.split('=').next().unwrap_or_default().trim();
+            if trimmed.contains('=') && normalize_header(name) == key {
+                return Some(index + 1);
+            }
+        }
+    }
+
+    None
+}
+
+/// Find the line of the `index`-th `[[name]]` header
+fn locate_nth_array_table(contents: &str, name: &str, index: usize) -> Option<usize> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            line.trim()
+                .strip_prefix("[[")
+                .and_then(|rest| rest.split("]]").next())
+                .is_some_and(|header| normalize_header(header) == name)
+        })
+        .nth(index)
+        .map(|(line, _)| line + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_valid_config_has_no_issues() {
+        let contents = r#"
+[api]
+token = "secret"
+
+[defaults]
+app_slug = "abc-123"
+
+[output]
+format = "json"
+
+[aliases]
+ios = "abc-123"
+
+[apps."abc-123"]
+workflow = "deploy"
+interval = 30
+
+[[log.highlight]]
+pattern = "^error"
+color = "red"
+"#;
+        assert_eq!(lint(contents), vec![]);
+    }
+
+    #[test]
+    fn test_lint_empty_config_has_no_issues() {
+        assert!(lint("").is_empty());
+    }
+
+    #[test]
+    fn test_lint_unknown_key_is_warning_with_line() {
+        let contents = "[output]\nformat = \"pretty\"\nformt = \"json\"\n";
+        let issues = lint(contents);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Warning);
+        assert_eq!(issues[0].key, "output.formt");
+        assert_eq!(issues[0].line, Some(3));
+    }
+
+    #[test]
+    fn test_lint_unknown_section() {
+        let contents = "[defaults]\napp_slug = \"x\"\n\n[ouput]\nformat = \"json\"\n";
+        let issues = lint(contents);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].key, "ouput");
+        assert_eq!(issues[0].line, Some(4));
+    }
+
+    #[test]
+    fn test_lint_invalid_output_format() {
+        let contents = "[output]\nformat = \"yaml\"\n";
+        let issues = lint(contents);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert_eq!(issues[0].line, Some(2));
+        assert!(issues[0].message.contains("pretty"));
+    }
+
+    #[test]
+    fn test_lint_invalid_quiet_hours() {
+        let contents = "[notify]\nmin_interval = 60\nquiet_hours = \"10pm-8am\"\n";
+        let issues = lint(contents);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].key, "notify.quiet_hours");
+        assert_eq!(issues[0].line, Some(3));
+    }
+
+    #[test]
+    fn test_lint_invalid_daemon_target() {
+        let contents = "[daemon]\ninterval = 300\ntargets = [\"ios:main\", \"android:\"]\n";
+        let issues = lint(contents);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].key, "daemon.targets");
+        assert_eq!(issues[0].line, Some(3));
+        assert!(issues[0].message.contains("android:"));
+    }
+
+    #[test]
+    fn test_lint_invalid_theme_color() {
+        let contents = "[theme]\nsuccess = \"green\"\nfailed = \"crimson\"\n";
+        let issues = lint(contents);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].key, "theme.failed");
+        assert_eq!(issues[0].line, Some(3));
+        assert!(issues[0].message.contains("crimson"));
+    }
+
+    #[test]
+    fn test_lint_unknown_app_key() {
+        let contents = "[apps.\"abc-123\"]\nworkflow = \"ci\"\nbrnach = \"main\"\n";
+        let issues = lint(contents);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].key, "apps.abc-123.brnach");
+        assert_eq!(issues[0].line, Some(3));
+    }
+
+    #[test]
+    fn test_lint_invalid_highlight_rule() {
+        let contents = "[[log.highlight]]\npattern = \"ok\"\ncolor = \"red\"\n\n[[log.highlight]]\npattern = \"[bad\"\ncolor = \"red\"\n";
+        let issues = lint(contents);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].key, "log.highlight[1]");
+        assert_eq!(issues[0].line, Some(5));
+    }
+
+    #[test]
+    fn test_lint_type_error_reports_line() {
+        let contents = "[apps.\"abc\"]\ninterval = \"fast\"\n";
+        let issues = lint(contents);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert_eq!(issues[0].line, Some(2));
+    }
+
+    #[test]
+    fn test_lint_syntax_error_reports_line() {
+        let contents = "[api]\ntoken = \"abc\"\n[output\n";
+        let issues = lint(contents);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert_eq!(issues[0].line, Some(3));
+    }
+
+    #[test]
+    fn test_lint_non_string_alias() {
+        let issues = lint("[aliases]\nios = 42\n");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].key, "aliases.ios");
+        assert_eq!(issues[0].line, Some(2));
+    }
+}
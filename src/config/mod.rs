@@ -1,5 +1,11 @@
+pub mod lint;
 mod paths;
 mod settings;
+pub mod undo;
 
 pub use paths::Paths;
-pub use settings::Config;
+pub use settings::{
+    canonical_key, AliasChanges, AppConfig, CacheConfig, Config, DaemonConfig, HooksConfig,
+    NotifyConfig, QuietHours, RecentApp, ThemeConfig, UpdateConfig, ENV_OVERRIDES,
+    RECENT_APPS_LIMIT, SECRET_KEYS, THEME_COLORS,
+};
@@ -1,5 +1,5 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 
@@ -40,10 +40,34 @@ impl Paths {
         Ok(())
     }
 
+    /// Directory for cached API data (~/.reprise/cache)
+    pub fn cache_dir(&self) -> PathBuf {
+        self.root.join("cache")
+    }
+
     /// Check if the config file exists
     pub fn config_exists(&self) -> bool {
         self.config_file.exists()
     }
+
+    /// Journal of config changes for `config undo` (~/.reprise/undo.json)
+    pub fn undo_file(&self) -> PathBuf {
+        self.root.join("undo.json")
+    }
+}
+
+/// Write a file only its owner can read, as it may hold the API token
+pub(crate) fn write_private(path: &Path, contents: &str) -> Result<()> {
+    fs::write(path, contents)?;
+
+    // Set restrictive permissions (600 = owner read/write only)
+    #[cfg(unix)]
+    {
+        let perms = fs::Permissions::from_mode(0o600);
+        fs::set_permissions(path, perms)?;
+    }
+
+    Ok(())
 }
 
 impl Default for Paths {
@@ -1,11 +1,13 @@
+use chrono::NaiveTime;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-#[cfg(unix)]
-use std::os::unix::fs::PermissionsExt;
 
-use super::paths::Paths;
+use super::paths::{write_private, Paths};
+use super::undo;
+use crate::bitrise::BuildStatus;
 use crate::error::{RepriseError, Result};
+use crate::logparse::{HighlightRule, Highlighter};
 
 /// Main configuration structure
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -22,11 +24,111 @@ pub struct Config {
     #[serde(default)]
     pub output: OutputConfig,
 
+    /// Build log display settings
+    #[serde(default)]
+    pub log: LogConfig,
+
     /// App aliases (short name -> app slug)
     #[serde(default)]
     pub aliases: HashMap<String, String>,
+
+    /// Per-app overrides (`[apps."<slug>"]`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub apps: HashMap<String, AppConfig>,
+
+    /// Local cache settings
+    #[serde(default, skip_serializing_if = "CacheConfig::is_default")]
+    pub cache: CacheConfig,
+
+    /// Desktop notification throttling
+    #[serde(default, skip_serializing_if = "NotifyConfig::is_default")]
+    pub notify: NotifyConfig,
+
+    /// Local commands run when a watched build finishes
+    #[serde(default, skip_serializing_if = "HooksConfig::is_default")]
+    pub hooks: HooksConfig,
+
+    /// Apps and branches polled by `reprise watch-daemon`
+    #[serde(default, skip_serializing_if = "DaemonConfig::is_default")]
+    pub daemon: DaemonConfig,
+
+    /// Status colors and icons in pretty output
+    #[serde(default, skip_serializing_if = "ThemeConfig::is_default")]
+    pub theme: ThemeConfig,
+
+    /// Checking for newer reprise releases
+    #[serde(default, skip_serializing_if = "UpdateConfig::is_default")]
+    pub update: UpdateConfig,
+
+    /// Keys currently overridden by environment variables (never saved)
+    #[serde(skip)]
+    env_keys: Vec<&'static str>,
 }
 
+/// Environment variables that override config keys, as (variable, key)
+pub const ENV_OVERRIDES: &[(&str, &str)] = &[
+    ("REPRISE_API_TOKEN", "api.token"),
+    ("REPRISE_DEFAULT_APP", "defaults.app_slug"),
+    ("REPRISE_DEFAULT_APP_NAME", "defaults.app_name"),
+    ("REPRISE_OUTPUT_FORMAT", "output.format"),
+    ("REPRISE_PAGER", "output.pager"),
+    ("REPRISE_LOG_DEFAULT_RULES", "log.default_rules"),
+    ("REPRISE_CACHE_TTL", "cache.ttl"),
+    ("REPRISE_CACHE_DIR", "cache.dir"),
+    ("REPRISE_CACHE_LOG_LIMIT_MB", "cache.log_limit_mb"),
+    ("REPRISE_NOTIFY_MIN_INTERVAL", "notify.min_interval"),
+    ("REPRISE_NOTIFY_QUIET_HOURS", "notify.quiet_hours"),
+    ("REPRISE_NOTIFY_WEBHOOK_URL", "notify.webhook_url"),
+    ("REPRISE_NOTIFY_NTFY_TOPIC", "notify.ntfy_topic"),
+    ("REPRISE_NOTIFY_NTFY_SERVER", "notify.ntfy_server"),
+    ("REPRISE_HOOKS_ON_BUILD_SUCCESS", "hooks.on_build_success"),
+    ("REPRISE_HOOKS_ON_BUILD_FAILURE", "hooks.on_build_failure"),
+    ("REPRISE_HOOKS_ON_BUILD_ABORT", "hooks.on_build_abort"),
+    ("REPRISE_HOOKS_ON_BUILD_FINISH", "hooks.on_build_finish"),
+    ("REPRISE_DAEMON_INTERVAL", "daemon.interval"),
+    ("REPRISE_DAEMON_SLACK_WEBHOOK", "daemon.slack_webhook"),
+    ("REPRISE_THEME_RUNNING", "theme.running"),
+    ("REPRISE_THEME_SUCCESS", "theme.success"),
+    ("REPRISE_THEME_FAILED", "theme.failed"),
+    ("REPRISE_THEME_ABORTED", "theme.aborted"),
+    ("REPRISE_THEME_ASCII", "theme.ascii"),
+    ("REPRISE_UPDATE_CHECK", "update.check"),
+];
+
+/// Keys holding secrets: left out by `config export --no-secrets` and never
+/// shown with their values in the history or `config undo`
+pub const SECRET_KEYS: &[&str] =
+    &["api.token", "notify.webhook_url", "notify.ntfy_topic", "daemon.slack_webhook"];
+
+/// Other spellings accepted for config keys, as (alias, key)
+const KEY_ALIASES: &[(&str, &str)] = &[("cache.ttl_secs", "cache.ttl")];
+
+/// The config key an alias stands for, or `key` itself
+pub fn canonical_key(key: &str) -> &str {
+    KEY_ALIASES.iter().find(|(alias, _)| *alias == key).map_or(key, |(_, k)| *k)
+}
+
+/// Color names accepted by the `[theme]` keys
+pub const THEME_COLORS: &[&str] = &[
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "purple",
+    "cyan",
+    "white",
+    "bright black",
+    "bright red",
+    "bright green",
+    "bright yellow",
+    "bright blue",
+    "bright magenta",
+    "bright cyan",
+    "bright white",
+];
+
 /// API-related configuration
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ApiConfig {
@@ -41,6 +143,20 @@ pub struct DefaultsConfig {
     pub app_slug: Option<String>,
     /// Default app name (for display)
     pub app_name: Option<String>,
+    /// Apps recently set as the default, most recent first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recent: Vec<RecentApp>,
+}
+
+/// Maximum number of recent default apps remembered
+pub const RECENT_APPS_LIMIT: usize = 5;
+
+/// An app that was set as the default
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentApp {
+    pub slug: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
 }
 
 /// Output formatting preferences
@@ -49,6 +165,10 @@ pub struct OutputConfig {
     /// Default output format
     #[serde(default = "default_format")]
     pub format: String,
+
+    /// Pager for long output (empty disables paging; unset uses $PAGER or less -R)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pager: Option<String>,
 }
 
 fn default_format() -> String {
@@ -59,15 +179,476 @@ impl Default for OutputConfig {
     fn default() -> Self {
         Self {
             format: default_format(),
+            pager: None,
         }
     }
 }
 
+/// Build log display settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogConfig {
+    /// Whether the built-in highlight rules apply after the user rules
+    #[serde(default = "default_true")]
+    pub default_rules: bool,
+
+    /// User highlight rules (`[[log.highlight]]` with `pattern` and `color`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub highlight: Vec<HighlightRule>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            default_rules: true,
+            highlight: Vec::new(),
+        }
+    }
+}
+
+/// Local cache settings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheConfig {
+    /// How long cached API data (e.g., the app list) stays fresh, in seconds
+    #[serde(default, alias = "ttl_secs", skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u64>,
+
+    /// Cache directory (default: ~/.reprise/cache); `~/` is expanded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+
+    /// Size limit for cached build logs in MB (default: 200; 0 disables)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_limit_mb: Option<u64>,
+}
+
+impl CacheConfig {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Desktop notification throttling
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotifyConfig {
+    /// Minimum seconds between two notifications; later ones are dropped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_interval: Option<u64>,
+
+    /// Local time range without notifications, e.g. "22:00-08:00"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet_hours: Option<String>,
+
+    /// URL that finished watched builds are POSTed to as JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+
+    /// ntfy topic that finished watched builds are pushed to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ntfy_topic: Option<String>,
+
+    /// ntfy server for `ntfy_topic` (default: https://ntfy.sh)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ntfy_server: Option<String>,
+}
+
+impl NotifyConfig {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// The parsed quiet hours; an invalid range is ignored
+    pub fn quiet_hours(&self) -> Option<QuietHours> {
+        self.quiet_hours
+            .as_deref()
+            .and_then(|range| QuietHours::parse(range).ok())
+    }
+}
+
+/// Shell commands run when a watched build finishes (see the `hooks` module)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HooksConfig {
+    /// Run when a build succeeds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_build_success: Option<String>,
+
+    /// Run when a build fails
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_build_failure: Option<String>,
+
+    /// Run when a build is aborted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_build_abort: Option<String>,
+
+    /// Run after any finished build, following the outcome's own hook
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_build_finish: Option<String>,
+}
+
+impl HooksConfig {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// The hooks to run, in order, for a finished build's status
+    pub fn for_status(&self, status: BuildStatus) -> Vec<(&'static str, &str)> {
+        let outcome = match status {
+            BuildStatus::Success => Some(("on_build_success", &self.on_build_success)),
+            BuildStatus::Failed => Some(("on_build_failure", &self.on_build_failure)),
+            BuildStatus::Aborted | BuildStatus::AbortedWithSuccess => {
+                Some(("on_build_abort", &self.on_build_abort))
+            }
+            BuildStatus::Running | BuildStatus::Unknown(_) => None,
+        };
+        outcome
+            .into_iter()
+            .chain([("on_build_finish", &self.on_build_finish)])
+            .filter_map(|(event, command)| Some((event, command.as_deref()?)))
+            .filter(|(_, command)| !command.trim().is_empty())
+            .collect()
+    }
+}
+
+/// Targets and delivery settings of `reprise watch-daemon`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaemonConfig {
+    /// Apps or branches to poll, as "APP" or "APP:BRANCH"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<String>,
+
+    /// Seconds between polls
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval: Option<u64>,
+
+    /// Slack incoming webhook URL that status changes are posted to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slack_webhook: Option<String>,
+}
+
+impl DaemonConfig {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Checking for newer reprise releases (see the `update` module)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateConfig {
+    /// Look for a newer release once a day and mention it after a command
+    #[serde(default)]
+    pub check: bool,
+}
+
+impl UpdateConfig {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// A daily local time range, which may wrap past midnight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    /// Parse "HH:MM-HH:MM"
+    pub fn parse(range: &str) -> std::result::Result<Self, String> {
+        let invalid = || format!("expected a range like '22:00-08:00', got '{}'", range);
+        let (start, end) = range.split_once('-').ok_or_else(invalid)?;
+        let parse = |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M").map_err(|_| invalid());
+        Ok(Self {
+            start: parse(start)?,
+            end: parse(end)?,
+        })
+    }
+
+    /// Whether a time of day falls inside the range (start inclusive, end exclusive)
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// Status colors and icons in pretty output (see `output::theme`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThemeConfig {
+    /// Color of running builds (default: yellow)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub running: Option<String>,
+
+    /// Color of successful builds (default: green)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success: Option<String>,
+
+    /// Color of failed builds (default: red)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failed: Option<String>,
+
+    /// Color of aborted builds (default: red)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aborted: Option<String>,
+
+    /// Use ASCII status icons, for terminals without Unicode symbols
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ascii: bool,
+}
+
+impl ThemeConfig {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Check a color name against [`THEME_COLORS`]
+    pub fn check_color(color: &str) -> std::result::Result<(), String> {
+        if THEME_COLORS.contains(&color.to_lowercase().as_str()) {
+            Ok(())
+        } else {
+            Err(format!("unknown color '{}' (e.g. 'green' or 'bright blue')", color))
+        }
+    }
+}
+
+/// Per-app preferences used when the matching flag isn't given
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppConfig {
+    /// Workflow to trigger, and to filter `builds` by
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workflow: Option<String>,
+
+    /// Branch to trigger, and to filter `builds` by
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+
+    /// Longest polling interval in seconds for --wait, --watch and --follow
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval: Option<u64>,
+
+    /// Directory for `artifacts --download` without a path
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download_dir: Option<String>,
+}
+
+/// What importing a set of aliases changed, with names in sorted order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AliasChanges {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+    pub unchanged: Vec<String>,
+}
+
+const EMPTY_APP_CONFIG: AppConfig = AppConfig {
+    workflow: None,
+    branch: None,
+    interval: None,
+    download_dir: None,
+};
+
 impl Config {
-    /// Load configuration from the default path
+    /// Get the overrides for an app (slug or alias); empty if none are configured
+    pub fn app_config(&self, app: &str) -> &AppConfig {
+        self.apps
+            .get(self.resolve_alias(app))
+            .unwrap_or(&EMPTY_APP_CONFIG)
+    }
+
+    /// Compile the configured log highlight rules
+    pub fn log_highlighter(&self) -> Result<Highlighter> {
+        Highlighter::new(&self.log.highlight, self.log.default_rules)
+    }
+
+    /// Load configuration from the default path, with environment overrides
     pub fn load() -> Result<Self> {
         let paths = Paths::new()?;
-        Self::load_from(&paths)
+        let mut config = Self::load_from(&paths)?;
+        config.apply_env_overrides(|var| std::env::var(var).ok())?;
+        Ok(config)
+    }
+
+    /// Merge `REPRISE_*` environment variables (see [`ENV_OVERRIDES`]) over
+    /// the file values. Empty variables are ignored.
+    pub fn apply_env_overrides(&mut self, lookup: impl Fn(&str) -> Option<String>) -> Result<()> {
+        for &(var, key) in ENV_OVERRIDES {
+            let Some(value) = lookup(var).filter(|v| !v.is_empty()) else {
+                continue;
+            };
+            self.parse_value(key, &value)
+                .map_err(|e| RepriseError::Config(format!("Invalid {}: {}", var, e)))?;
+            if !self.env_keys.contains(&key) {
+                self.env_keys.push(key);
+            }
+        }
+        Ok(())
+    }
+
+    /// Environment variable overriding a config key, if one is set
+    pub fn env_override(&self, key: &str) -> Option<&'static str> {
+        if !self.env_keys.contains(&key) {
+            return None;
+        }
+        ENV_OVERRIDES.iter().find(|(_, k)| *k == key).map(|(var, _)| *var)
+    }
+
+    /// Set a scalar config key (e.g., "output.format") from its string form
+    ///
+    /// The key stops being overridden by its environment variable, so the
+    /// new value is what `save` writes.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let key = canonical_key(key);
+        self.parse_value(key, value)
+            .map_err(|e| RepriseError::InvalidArgument(format!("{}: {}", key, e)))?;
+        self.env_keys.retain(|k| *k != key);
+        Ok(())
+    }
+
+    fn parse_value(&mut self, key: &str, value: &str) -> std::result::Result<(), String> {
+        match key {
+            "api.token" => self.api.token = Some(value.to_string()),
+            "defaults.app_slug" => self.defaults.app_slug = Some(value.to_string()),
+            "defaults.app_name" => self.defaults.app_name = Some(value.to_string()),
+            "output.format" => {
+                if value != "pretty" && value != "json" {
+                    return Err(format!("expected 'pretty' or 'json', got '{}'", value));
+                }
+                self.output.format = value.to_string();
+            }
+            "output.pager" => self.output.pager = Some(value.to_string()),
+            "log.default_rules" => {
+                self.log.default_rules = value
+                    .parse()
+                    .map_err(|_| format!("expected 'true' or 'false', got '{}'", value))?;
+            }
+            "cache.ttl" => {
+                let secs = value
+                    .parse()
+                    .map_err(|_| format!("expected seconds, got '{}'", value))?;
+                self.cache.ttl = Some(secs);
+            }
+            "cache.dir" => self.cache.dir = Some(value.to_string()),
+            "cache.log_limit_mb" => {
+                let mb = value
+                    .parse()
+                    .map_err(|_| format!("expected megabytes, got '{}'", value))?;
+                self.cache.log_limit_mb = Some(mb);
+            }
+            "notify.min_interval" => {
+                let secs = value
+                    .parse()
+                    .map_err(|_| format!("expected seconds, got '{}'", value))?;
+                self.notify.min_interval = Some(secs);
+            }
+            "notify.quiet_hours" => {
+                QuietHours::parse(value)?;
+                self.notify.quiet_hours = Some(value.to_string());
+            }
+            "notify.webhook_url" => {
+                if !value.starts_with("https://") && !value.starts_with("http://") {
+                    return Err(format!("expected an http(s):// URL, got '{}'", value));
+                }
+                self.notify.webhook_url = Some(value.to_string());
+            }
+            "notify.ntfy_topic" => {
+                let valid = value.chars().all(|c| c.is_ascii_alphanumeric() || "-_".contains(c));
+                if value.is_empty() || value.len() > 64 || !valid {
+                    return Err(format!(
+                        "expected a topic of up to 64 letters, digits, - or _, got '{}'",
+                        value
+                    ));
+                }
+                self.notify.ntfy_topic = Some(value.to_string());
+            }
+            "notify.ntfy_server" => {
+                if !value.starts_with("https://") && !value.starts_with("http://") {
+                    return Err(format!("expected an http(s):// URL, got '{}'", value));
+                }
+                self.notify.ntfy_server = Some(value.trim_end_matches('/').to_string());
+            }
+            "hooks.on_build_success" => self.hooks.on_build_success = Some(value.to_string()),
+            "hooks.on_build_failure" => self.hooks.on_build_failure = Some(value.to_string()),
+            "hooks.on_build_abort" => self.hooks.on_build_abort = Some(value.to_string()),
+            "hooks.on_build_finish" => self.hooks.on_build_finish = Some(value.to_string()),
+            "daemon.interval" => {
+                let secs = value
+                    .parse()
+                    .map_err(|_| format!("expected seconds, got '{}'", value))?;
+                self.daemon.interval = Some(secs);
+            }
+            "daemon.slack_webhook" => {
+                if !value.starts_with("https://") {
+                    return Err(format!("expected an https:// webhook URL, got '{}'", value));
+                }
+                self.daemon.slack_webhook = Some(value.to_string());
+            }
+            "theme.running" | "theme.success" | "theme.failed" | "theme.aborted" => {
+                ThemeConfig::check_color(value)?;
+                let color = Some(value.to_string());
+                match key {
+                    "theme.running" => self.theme.running = color,
+                    "theme.success" => self.theme.success = color,
+                    "theme.failed" => self.theme.failed = color,
+                    _ => self.theme.aborted = color,
+                }
+            }
+            "theme.ascii" => {
+                self.theme.ascii = value
+                    .parse()
+                    .map_err(|_| format!("expected 'true' or 'false', got '{}'", value))?;
+            }
+            "update.check" => {
+                self.update.check = value
+                    .parse()
+                    .map_err(|_| format!("expected 'true' or 'false', got '{}'", value))?;
+            }
+            _ => return Err(format!("unknown key '{}'", key)),
+        }
+        Ok(())
+    }
+
+    /// Copy a scalar config key's value from another config
+    fn copy_value(&mut self, from: &Config, key: &str) {
+        match key {
+            "api.token" => self.api.token = from.api.token.clone(),
+            "defaults.app_slug" => self.defaults.app_slug = from.defaults.app_slug.clone(),
+            "defaults.app_name" => self.defaults.app_name = from.defaults.app_name.clone(),
+            "output.format" => self.output.format = from.output.format.clone(),
+            "output.pager" => self.output.pager = from.output.pager.clone(),
+            "log.default_rules" => self.log.default_rules = from.log.default_rules,
+            "cache.ttl" => self.cache.ttl = from.cache.ttl,
+            "cache.dir" => self.cache.dir = from.cache.dir.clone(),
+            "cache.log_limit_mb" => self.cache.log_limit_mb = from.cache.log_limit_mb,
+            "notify.min_interval" => self.notify.min_interval = from.notify.min_interval,
+            "notify.quiet_hours" => self.notify.quiet_hours = from.notify.quiet_hours.clone(),
+            "notify.webhook_url" => self.notify.webhook_url = from.notify.webhook_url.clone(),
+            "notify.ntfy_topic" => self.notify.ntfy_topic = from.notify.ntfy_topic.clone(),
+            "notify.ntfy_server" => self.notify.ntfy_server = from.notify.ntfy_server.clone(),
+            "hooks.on_build_success" => {
+                self.hooks.on_build_success = from.hooks.on_build_success.clone()
+            }
+            "hooks.on_build_failure" => {
+                self.hooks.on_build_failure = from.hooks.on_build_failure.clone()
+            }
+            "hooks.on_build_abort" => self.hooks.on_build_abort = from.hooks.on_build_abort.clone(),
+            "hooks.on_build_finish" => {
+                self.hooks.on_build_finish = from.hooks.on_build_finish.clone()
+            }
+            "daemon.interval" => self.daemon.interval = from.daemon.interval,
+            "daemon.slack_webhook" => self.daemon.slack_webhook = from.daemon.slack_webhook.clone(),
+            "theme.running" => self.theme.running = from.theme.running.clone(),
+            "theme.success" => self.theme.success = from.theme.success.clone(),
+            "theme.failed" => self.theme.failed = from.theme.failed.clone(),
+            "theme.aborted" => self.theme.aborted = from.theme.aborted.clone(),
+            "theme.ascii" => self.theme.ascii = from.theme.ascii,
+            "update.check" => self.update.check = from.update.check,
+            _ => {}
+        }
     }
 
     /// Load configuration from a specific paths instance
@@ -88,21 +669,107 @@ impl Config {
     }
 
     /// Save configuration to a specific paths instance
+    ///
+    /// Keys overridden by environment variables keep their file values, so a
+    /// token from `REPRISE_API_TOKEN` is never written to disk.
+    ///
+    /// The keys that changed are journaled for [`undo`](super::undo) and,
+    /// with the `cli` feature, recorded in the command history.
     pub fn save_to(&self, paths: &Paths) -> Result<()> {
         paths.ensure_dirs()?;
-        let contents = toml::to_string_pretty(self)?;
-        fs::write(&paths.config_file, &contents)?;
-
-        // Set restrictive permissions on config file (contains API token)
-        #[cfg(unix)]
-        {
-            let perms = fs::Permissions::from_mode(0o600);
-            fs::set_permissions(&paths.config_file, perms)?;
+        let previous = fs::read_to_string(&paths.config_file).ok();
+        let contents = if self.env_keys.is_empty() {
+            toml::to_string_pretty(self)?
+        } else {
+            let on_disk = Self::load_from(paths)?;
+            let mut config = self.clone();
+            for key in &self.env_keys {
+                config.copy_value(&on_disk, key);
+            }
+            toml::to_string_pretty(&config)?
+        };
+        write_private(&paths.config_file, &contents)?;
+
+        // The change is saved either way; only undoing it would be lost
+        let step = undo::record(paths, previous.as_deref(), &contents);
+        #[cfg(feature = "cli")]
+        if let Ok(Some(step)) = step {
+            crate::history::record_config("config change", &step.summary());
         }
-
+        #[cfg(not(feature = "cli"))]
+        let _ = step;
         Ok(())
     }
 
+    /// Import settings from TOML (as written by `config export`)
+    ///
+    /// Sections in the imported file replace the current ones, except
+    /// `[aliases]` and `[apps]`, whose entries are merged by name, and `[api]`
+    /// and `[defaults]`, which only set the keys the file gives a value (so a
+    /// file exported with `--no-secrets` keeps the token). `[hooks]` holds
+    /// shell commands and is skipped unless `with_hooks` is set. Returns the
+    /// names of the imported sections.
+    pub fn import_toml(&mut self, contents: &str, with_hooks: bool) -> Result<Vec<String>> {
+        let table: toml::Table = contents.parse()?;
+        let imported: Config = toml::from_str(contents)?;
+
+        let mut sections = Vec::new();
+        for section in table.keys() {
+            match section.as_str() {
+                "api" => {
+                    let Some(token) = non_empty(&imported.api.token) else {
+                        continue;
+                    };
+                    self.api.token = Some(token);
+                    self.env_keys.retain(|k| *k != "api.token");
+                    sections.push(section.clone());
+                    continue;
+                }
+                "defaults" => {
+                    // The name describes the slug, so they are imported together
+                    let Some(slug) = non_empty(&imported.defaults.app_slug) else {
+                        continue;
+                    };
+                    self.defaults.app_slug = Some(slug);
+                    self.defaults.app_name = non_empty(&imported.defaults.app_name);
+                    self.env_keys
+                        .retain(|k| !matches!(*k, "defaults.app_slug" | "defaults.app_name"));
+                    sections.push(section.clone());
+                    continue;
+                }
+                "output" => self.output = imported.output.clone(),
+                "log" => self.log = imported.log.clone(),
+                "cache" => self.cache = imported.cache.clone(),
+                "notify" => {
+                    // Secrets left out by `--no-secrets` keep their local values
+                    let notify = imported.notify.clone();
+                    self.notify = NotifyConfig {
+                        webhook_url: notify.webhook_url.or(self.notify.webhook_url.take()),
+                        ntfy_topic: notify.ntfy_topic.or(self.notify.ntfy_topic.take()),
+                        ..notify
+                    };
+                }
+                "hooks" if with_hooks => self.hooks = imported.hooks.clone(),
+                "daemon" => {
+                    let daemon = imported.daemon.clone();
+                    self.daemon = DaemonConfig {
+                        slack_webhook: daemon.slack_webhook.or(self.daemon.slack_webhook.take()),
+                        ..daemon
+                    };
+                }
+                "theme" => self.theme = imported.theme.clone(),
+                "update" => self.update = imported.update.clone(),
+                "aliases" => self.aliases.extend(imported.aliases.clone()),
+                "apps" => self.apps.extend(imported.apps.clone()),
+                _ => continue,
+            }
+            let prefix = format!("{}.", section);
+            self.env_keys.retain(|k| !k.starts_with(&prefix));
+            sections.push(section.clone());
+        }
+        Ok(sections)
+    }
+
     /// Get the API token or return an error with instructions
     pub fn require_token(&self) -> Result<&str> {
         self.api.token.as_deref().ok_or_else(|| {
@@ -118,11 +785,24 @@ impl Config {
     }
 
     /// Set the default app
+    ///
+    /// The app also moves to the front of the recent default apps.
     pub fn set_default_app(&mut self, slug: String, name: Option<String>) {
+        let recent = &mut self.defaults.recent;
+        recent.retain(|app| app.slug != slug);
+        recent.insert(0, RecentApp { slug: slug.clone(), name: name.clone() });
+        recent.truncate(RECENT_APPS_LIMIT);
+
         self.defaults.app_slug = Some(slug);
         self.defaults.app_name = name;
     }
 
+    /// Remove the default app, returning its slug; recent apps are kept
+    pub fn unset_default_app(&mut self) -> Option<String> {
+        self.defaults.app_name = None;
+        self.defaults.app_slug.take()
+    }
+
     /// Set the API token
     pub fn set_token(&mut self, token: String) {
         self.api.token = Some(token);
@@ -143,6 +823,41 @@ impl Config {
         self.aliases.get(name).map(|s| s.as_str())
     }
 
+    /// Import aliases shared by someone else
+    ///
+    /// Same-named aliases take the imported slug. With `overwrite`, aliases
+    /// missing from `aliases` are removed; otherwise they are kept.
+    pub fn import_aliases(
+        &mut self,
+        aliases: HashMap<String, String>,
+        overwrite: bool,
+    ) -> AliasChanges {
+        let mut changes = AliasChanges::default();
+        if overwrite {
+            changes.removed = self
+                .aliases
+                .keys()
+                .filter(|name| !aliases.contains_key(*name))
+                .cloned()
+                .collect();
+            for name in &changes.removed {
+                self.aliases.remove(name);
+            }
+        }
+        for (name, slug) in aliases {
+            match self.aliases.insert(name.clone(), slug.clone()) {
+                None => changes.added.push(name),
+                Some(old) if old == slug => changes.unchanged.push(name),
+                Some(_) => changes.updated.push(name),
+            }
+        }
+        changes.added.sort();
+        changes.updated.sort();
+        changes.removed.sort();
+        changes.unchanged.sort();
+        changes
+    }
+
     /// Resolve an app slug, checking aliases first
     ///
     /// If the input matches an alias, returns the corresponding slug.
@@ -152,6 +867,11 @@ impl Config {
     }
 }
 
+/// A value that is set and not blank
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value.as_ref().filter(|v| !v.trim().is_empty()).cloned()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -321,6 +1041,29 @@ token = "partial-token"
         assert_eq!(config.defaults.app_name, Some("App Name".to_string()));
     }
 
+    #[test]
+    fn test_set_default_app_tracks_recent() {
+        let mut config = Config::default();
+        for slug in ["a", "b", "c", "d", "e", "f", "b"] {
+            config.set_default_app(slug.to_string(), None);
+        }
+
+        let recent: Vec<&str> = config.defaults.recent.iter().map(|r| r.slug.as_str()).collect();
+        assert_eq!(recent, vec!["b", "f", "e", "d", "c"]);
+    }
+
+    #[test]
+    fn test_unset_default_app_keeps_recent() {
+        let mut config = Config::default();
+        config.set_default_app("app-slug".to_string(), Some("App".to_string()));
+
+        assert_eq!(config.unset_default_app(), Some("app-slug".to_string()));
+        assert!(config.defaults.app_slug.is_none());
+        assert!(config.defaults.app_name.is_none());
+        assert_eq!(config.defaults.recent.len(), 1);
+        assert_eq!(config.unset_default_app(), None);
+    }
+
     #[test]
     fn test_set_default_app_without_name() {
         let mut config = Config::default();
@@ -383,6 +1126,432 @@ format = "json"
         assert_eq!(config.output.format, "json");
     }
 
+    #[test]
+    fn test_config_deserializes_output_pager() {
+        let config: Config = toml::from_str("[output]\npager = \"\"\n").unwrap();
+        assert_eq!(config.output.pager.as_deref(), Some(""));
+        assert_eq!(config.output.format, "pretty");
+    }
+
+    #[test]
+    fn test_config_deserializes_log_highlight_rules() {
+        let toml_str = r#"
+[log]
+default_rules = false
+
+[[log.highlight]]
+pattern = "^xcodebuild: error"
+color = "red"
+
+[[log.highlight]]
+pattern = "Retrying"
+color = "cyan"
+"#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(!config.log.default_rules);
+        assert_eq!(config.log.highlight.len(), 2);
+        assert_eq!(config.log.highlight[1].pattern, "Retrying");
+
+        let highlighter = config.log_highlighter().unwrap();
+        assert!(highlighter.classify("error: unrelated").is_none());
+        assert!(highlighter.classify("xcodebuild: error: oops").is_some());
+    }
+
+    #[test]
+    fn test_log_config_default_keeps_builtin_rules() {
+        let config = Config::default();
+        assert!(config.log.default_rules);
+        assert!(config.log.highlight.is_empty());
+        assert!(config.log_highlighter().unwrap().classify("error: oops").is_some());
+    }
+
+    #[test]
+    fn test_log_highlighter_rejects_invalid_pattern() {
+        let toml_str = r#"
+[[log.highlight]]
+pattern = "[unclosed"
+color = "red"
+"#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(config.log_highlighter().is_err());
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // Per-App Override Tests
+    // ─────────────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_config_deserializes_app_overrides() {
+        let toml_str = r#"
+[apps."abc-123"]
+workflow = "deploy"
+branch = "develop"
+interval = 30
+download_dir = "/tmp/builds/ios"
+"#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let app = config.app_config("abc-123");
+        assert_eq!(app.workflow, Some("deploy".to_string()));
+        assert_eq!(app.branch, Some("develop".to_string()));
+        assert_eq!(app.interval, Some(30));
+        assert_eq!(app.download_dir, Some("/tmp/builds/ios".to_string()));
+    }
+
+    #[test]
+    fn test_app_config_resolves_alias() {
+        let mut config = Config::default();
+        config.set_alias("ios".to_string(), "abc-123".to_string());
+        config.apps.insert(
+            "abc-123".to_string(),
+            AppConfig {
+                workflow: Some("deploy".to_string()),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(config.app_config("ios").workflow.as_deref(), Some("deploy"));
+    }
+
+    #[test]
+    fn test_app_config_missing_is_empty() {
+        let config = Config::default();
+        assert_eq!(config.app_config("unknown"), &AppConfig::default());
+    }
+
+    #[test]
+    fn test_empty_app_overrides_not_serialized() {
+        let toml_str = toml::to_string_pretty(&Config::default()).unwrap();
+        assert!(!toml_str.contains("[apps"));
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // Environment Override Tests
+    // ─────────────────────────────────────────────────────────────────────────
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_env_overrides_file_values() {
+        let mut config = Config::default();
+        config.set_default_app("file-app".to_string(), None);
+
+        config
+            .apply_env_overrides(env(&[
+                ("REPRISE_DEFAULT_APP", "env-app"),
+                ("REPRISE_OUTPUT_FORMAT", "json"),
+                ("REPRISE_CACHE_TTL", "120"),
+                ("REPRISE_LOG_DEFAULT_RULES", "false"),
+            ]))
+            .unwrap();
+
+        assert_eq!(config.defaults.app_slug, Some("env-app".to_string()));
+        assert_eq!(config.output.format, "json");
+        assert_eq!(config.cache.ttl, Some(120));
+        assert!(!config.log.default_rules);
+        assert_eq!(config.env_override("defaults.app_slug"), Some("REPRISE_DEFAULT_APP"));
+        assert_eq!(config.env_override("api.token"), None);
+    }
+
+    #[test]
+    fn test_env_empty_value_is_ignored() {
+        let mut config = Config::default();
+        config.set_default_app("file-app".to_string(), None);
+
+        config
+            .apply_env_overrides(env(&[("REPRISE_DEFAULT_APP", "")]))
+            .unwrap();
+
+        assert_eq!(config.defaults.app_slug, Some("file-app".to_string()));
+        assert_eq!(config.env_override("defaults.app_slug"), None);
+    }
+
+    #[test]
+    fn test_env_invalid_value_names_variable() {
+        let mut config = Config::default();
+        let err = config
+            .apply_env_overrides(env(&[("REPRISE_CACHE_TTL", "soon")]))
+            .unwrap_err();
+        assert!(err.to_string().contains("REPRISE_CACHE_TTL"));
+
+        let err = config
+            .apply_env_overrides(env(&[("REPRISE_OUTPUT_FORMAT", "yaml")]))
+            .unwrap_err();
+        assert!(err.to_string().contains("REPRISE_OUTPUT_FORMAT"));
+    }
+
+    #[test]
+    fn test_save_keeps_file_value_for_env_overridden_keys() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = make_test_paths(&temp_dir);
+
+        let mut config = Config::default();
+        config.set_token("file-token".to_string());
+        config.save_to(&paths).unwrap();
+
+        config
+            .apply_env_overrides(env(&[("REPRISE_API_TOKEN", "env-token")]))
+            .unwrap();
+        config.set_default_app("new-app".to_string(), None);
+        config.save_to(&paths).unwrap();
+
+        let loaded = Config::load_from(&paths).unwrap();
+        assert_eq!(loaded.api.token, Some("file-token".to_string()));
+        assert_eq!(loaded.defaults.app_slug, Some("new-app".to_string()));
+    }
+
+    #[test]
+    fn test_set_replaces_env_override() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = make_test_paths(&temp_dir);
+
+        let mut config = Config::default();
+        config
+            .apply_env_overrides(env(&[("REPRISE_OUTPUT_FORMAT", "json")]))
+            .unwrap();
+        config.set("output.format", "json").unwrap();
+        config.save_to(&paths).unwrap();
+
+        assert_eq!(config.env_override("output.format"), None);
+        assert_eq!(Config::load_from(&paths).unwrap().output.format, "json");
+    }
+
+    #[test]
+    fn test_set_rejects_invalid_values() {
+        let mut config = Config::default();
+        assert!(config.set("output.format", "yaml").is_err());
+        assert!(config.set("cache.ttl", "-5").is_err());
+        assert!(config.set("nope", "x").is_err());
+        assert!(config.set("cache.ttl", "60").is_ok());
+        assert_eq!(config.cache.ttl, Some(60));
+        assert!(config.set("cache.ttl_secs", "90").is_ok());
+        assert_eq!(config.cache.ttl, Some(90));
+    }
+
+    #[test]
+    fn test_cache_ttl_secs_alias_and_dir() {
+        let config: Config = toml::from_str("[cache]\nttl_secs = 86400\ndir = \"/tmp/reprise\"\n").unwrap();
+        assert_eq!(config.cache.ttl, Some(86400));
+        assert_eq!(config.cache.dir, Some("/tmp/reprise".to_string()));
+
+        let mut config = Config::default();
+        config
+            .apply_env_overrides(env(&[("REPRISE_CACHE_DIR", "/dev/shm/reprise")]))
+            .unwrap();
+        assert_eq!(config.cache.dir, Some("/dev/shm/reprise".to_string()));
+    }
+
+    #[test]
+    fn test_set_notify_keys() {
+        let mut config = Config::default();
+        assert!(config.set("notify.quiet_hours", "late").is_err());
+        assert!(config.set("notify.quiet_hours", "25:00-08:00").is_err());
+        config.set("notify.quiet_hours", "22:00-08:00").unwrap();
+        config.set("notify.min_interval", "300").unwrap();
+        assert!(config.set("notify.webhook_url", "ci.example.com/hook").is_err());
+        config.set("notify.webhook_url", "http://localhost:8080/hook").unwrap();
+        assert!(config.set("notify.ntfy_topic", "my builds").is_err());
+        config.set("notify.ntfy_topic", "reprise-builds_7f3a").unwrap();
+        config.set("notify.ntfy_server", "https://ntfy.example.com/").unwrap();
+        assert_eq!(config.notify.ntfy_server.as_deref(), Some("https://ntfy.example.com"));
+        assert_eq!(config.notify.min_interval, Some(300));
+        assert!(config.notify.quiet_hours().is_some());
+    }
+
+    #[test]
+    fn test_set_daemon_keys() {
+        let mut config = Config::default();
+        assert!(config.set("daemon.interval", "often").is_err());
+        assert!(config.set("daemon.slack_webhook", "hooks.slack.com/x").is_err());
+        config.set("daemon.interval", "600").unwrap();
+        config.set("daemon.slack_webhook", "https://hooks.slack.com/services/T/B/x").unwrap();
+        assert_eq!(config.daemon.interval, Some(600));
+
+        let contents = "[daemon]\ntargets = [\"ios\", \"ios:main\"]\n";
+        let parsed: Config = toml::from_str(contents).unwrap();
+        assert_eq!(parsed.daemon.targets, vec!["ios", "ios:main"]);
+    }
+
+    #[test]
+    fn test_update_check_is_opt_in() {
+        let mut config = Config::default();
+        assert!(!config.update.check);
+        assert!(!toml::to_string(&config).unwrap().contains("[update]"));
+        assert!(config.set("update.check", "yes").is_err());
+        config.set("update.check", "true").unwrap();
+        assert!(toml::to_string(&config).unwrap().contains("[update]\ncheck = true"));
+    }
+
+    #[test]
+    fn test_quiet_hours_wrapping_midnight() {
+        let quiet = QuietHours::parse("22:00-08:00").unwrap();
+        let at = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        assert!(quiet.contains(at(23, 30)));
+        assert!(quiet.contains(at(22, 0)));
+        assert!(quiet.contains(at(7, 59)));
+        assert!(!quiet.contains(at(8, 0)));
+        assert!(!quiet.contains(at(12, 0)));
+    }
+
+    #[test]
+    fn test_quiet_hours_same_day() {
+        let quiet = QuietHours::parse("12:00 - 13:30").unwrap();
+        let at = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        assert!(quiet.contains(at(12, 45)));
+        assert!(!quiet.contains(at(13, 30)));
+        assert!(!quiet.contains(at(9, 0)));
+    }
+
+    #[test]
+    fn test_hooks_for_status() {
+        let mut config = Config::default();
+        config.set("hooks.on_build_success", "./deploy.sh {slug}").unwrap();
+        config.set("hooks.on_build_finish", "./log.sh").unwrap();
+        config.set("hooks.on_build_failure", " ").unwrap();
+
+        let success = config.hooks.for_status(BuildStatus::Success);
+        assert_eq!(
+            success,
+            vec![("on_build_success", "./deploy.sh {slug}"), ("on_build_finish", "./log.sh")]
+        );
+        // A blank command counts as unset
+        let failed = config.hooks.for_status(BuildStatus::Failed);
+        assert_eq!(failed, vec![("on_build_finish", "./log.sh")]);
+        assert!(Config::default().hooks.for_status(BuildStatus::Aborted).is_empty());
+    }
+
+    #[test]
+    fn test_set_theme_keys() {
+        let mut config = Config::default();
+        config.set("theme.success", "Bright Blue").unwrap();
+        config.set("theme.ascii", "true").unwrap();
+        assert_eq!(config.theme.success.as_deref(), Some("Bright Blue"));
+        assert!(config.theme.ascii);
+
+        let err = config.set("theme.failed", "crimson").unwrap_err().to_string();
+        assert!(err.contains("unknown color 'crimson'"));
+        assert!(config.set("theme.ascii", "yes").is_err());
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // Import Tests
+    // ─────────────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_import_replaces_sections_and_merges_aliases() {
+        let mut config = Config::default();
+        config.set_token("my-token".to_string());
+        config.set_default_app("mine".to_string(), Some("Mine".to_string()));
+        config.set_alias("ios".to_string(), "old-ios".to_string());
+        config.set_alias("web".to_string(), "web-app".to_string());
+
+        let sections = config
+            .import_toml(
+                r#"
+[defaults]
+app_slug = "team-app"
+
+[aliases]
+ios = "new-ios"
+android = "android-app"
+
+[apps."team-app"]
+workflow = "ci"
+"#,
+                false,
+            )
+            .unwrap();
+
+        assert_eq!(sections, vec!["aliases", "apps", "defaults"]);
+        assert_eq!(config.api.token, Some("my-token".to_string()));
+        assert_eq!(config.defaults.app_slug, Some("team-app".to_string()));
+        assert_eq!(config.defaults.app_name, None);
+        assert_eq!(config.get_alias("ios"), Some("new-ios"));
+        assert_eq!(config.get_alias("web"), Some("web-app"));
+        assert_eq!(config.get_alias("android"), Some("android-app"));
+        assert_eq!(config.app_config("team-app").workflow.as_deref(), Some("ci"));
+    }
+
+    #[test]
+    fn test_import_keeps_secrets_left_out_of_the_file() {
+        let mut config = Config::default();
+        config.set_token("my-token".to_string());
+        config.set_default_app("mine".to_string(), Some("Mine".to_string()));
+        config.notify.webhook_url = Some("https://hooks.example.com/ci".to_string());
+        config.hooks.on_build_failure = Some("say failed".to_string());
+
+        let mut exported = config.clone();
+        exported.api.token = None;
+        exported.notify.webhook_url = None;
+        exported.notify.min_interval = Some(60);
+        exported.defaults.app_slug = Some(String::new());
+        exported.hooks.on_build_failure = Some("rm -rf ~".to_string());
+        let contents = toml::to_string_pretty(&exported).unwrap();
+
+        let sections = config.import_toml(&contents, false).unwrap();
+        assert!(!sections.contains(&"hooks".to_string()));
+        assert_eq!(config.api.token.as_deref(), Some("my-token"));
+        assert_eq!(config.defaults.app_slug.as_deref(), Some("mine"));
+        assert_eq!(config.defaults.app_name.as_deref(), Some("Mine"));
+        assert_eq!(config.notify.webhook_url.as_deref(), Some("https://hooks.example.com/ci"));
+        assert_eq!(config.notify.min_interval, Some(60));
+        assert_eq!(config.hooks.on_build_failure.as_deref(), Some("say failed"));
+
+        config.import_toml(&contents, true).unwrap();
+        assert_eq!(config.hooks.on_build_failure.as_deref(), Some("rm -rf ~"));
+    }
+
+    fn aliases(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_import_aliases_merge() {
+        let mut config = Config::default();
+        config.set_alias("ios".to_string(), "old-ios".to_string());
+        config.set_alias("web".to_string(), "web-app".to_string());
+
+        let changes = config.import_aliases(
+            aliases(&[("ios", "new-ios"), ("web", "web-app"), ("android", "android-app")]),
+            false,
+        );
+
+        assert_eq!(changes.added, vec!["android"]);
+        assert_eq!(changes.updated, vec!["ios"]);
+        assert_eq!(changes.unchanged, vec!["web"]);
+        assert!(changes.removed.is_empty());
+        assert_eq!(config.get_alias("ios"), Some("new-ios"));
+        assert_eq!(config.aliases.len(), 3);
+    }
+
+    #[test]
+    fn test_import_aliases_overwrite() {
+        let mut config = Config::default();
+        config.set_alias("ios".to_string(), "ios-app".to_string());
+        config.set_alias("mine".to_string(), "my-app".to_string());
+
+        let changes = config.import_aliases(aliases(&[("ios", "ios-app")]), true);
+
+        assert_eq!(changes.removed, vec!["mine"]);
+        assert_eq!(changes.unchanged, vec!["ios"]);
+        assert_eq!(config.aliases, aliases(&[("ios", "ios-app")]));
+    }
+
+    #[test]
+    fn test_import_rejects_invalid_toml() {
+        let mut config = Config::default();
+        assert!(config.import_toml("[defaults\n", false).is_err());
+        assert!(config.import_toml("[cache]\nttl = \"soon\"\n", false).is_err());
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // File Permissions Tests (Unix only)
     // ─────────────────────────────────────────────────────────────────────────
@@ -0,0 +1,329 @@
+//! Undo for config changes
+//!
+//! Every save journals the keys it changed, with their old and new values,
+//! in `~/.reprise/undo.json`. `reprise config undo` puts the old values of
+//! the newest change back, so a typo'd `app set` or `alias --remove` is
+//! one command away from fixed. Only the changed keys are reverted: edits
+//! made to other keys since are kept, and a key changed again since is
+//! left alone rather than overwritten.
+//!
+//! The `[api]` section is not journaled, so the token is never copied
+//! into a second file. Other secrets (see [`SECRET_KEYS`]) are journaled so
+//! they can be undone, but are only ever described as changed.
+
+use std::fs;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::paths::{write_private, Paths};
+use super::settings::SECRET_KEYS;
+use crate::error::Result;
+
+/// Changes kept in the journal
+const MAX_STEPS: usize = 20;
+
+/// Sections left out of the journal
+const SKIPPED_SECTIONS: &[&str] = &["api"];
+
+/// Keys reverted along with the rest but not worth showing
+const BOOKKEEPING_KEYS: &[&str] = &["defaults.recent"];
+
+/// One key's value before and after a change (`None` when unset)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyChange {
+    /// Path to the key, e.g. `["aliases", "ios"]`
+    pub path: Vec<String>,
+    pub before: Option<toml::Value>,
+    pub after: Option<toml::Value>,
+}
+
+impl KeyChange {
+    /// Dotted key, e.g. `defaults.app_slug`
+    pub fn key(&self) -> String {
+        self.path.join(".")
+    }
+
+    /// e.g. `defaults.app_slug: "abc" -> "def"`, or `notify.webhook_url:
+    /// (changed)` for a secret
+    pub fn describe(&self) -> String {
+        if self.is_secret() {
+            return format!("{}: (changed)", self.key());
+        }
+        let show = |value: &Option<toml::Value>| match value {
+            Some(value) => value.to_string(),
+            None => "(unset)".to_string(),
+        };
+        format!("{}: {} -> {}", self.key(), show(&self.before), show(&self.after))
+    }
+
+    /// Whether the key holds a secret, whose values are never shown
+    pub fn is_secret(&self) -> bool {
+        SECRET_KEYS.contains(&self.key().as_str())
+    }
+
+    /// The change with a secret's values masked, for output
+    pub fn redacted(&self) -> KeyChange {
+        if !self.is_secret() {
+            return self.clone();
+        }
+        let mask = |value: &Option<toml::Value>| {
+            value.as_ref().map(|_| toml::Value::String("(redacted)".to_string()))
+        };
+        KeyChange { path: self.path.clone(), before: mask(&self.before), after: mask(&self.after) }
+    }
+
+    /// The change that undoes this one
+    pub fn reversed(&self) -> KeyChange {
+        KeyChange {
+            path: self.path.clone(),
+            before: self.after.clone(),
+            after: self.before.clone(),
+        }
+    }
+
+    /// Whether the key is kept up to date by reprise itself (the recent
+    /// default apps), rather than set by the user
+    pub fn is_bookkeeping(&self) -> bool {
+        BOOKKEEPING_KEYS.contains(&self.key().as_str())
+    }
+}
+
+/// The keys changed by one save
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UndoStep {
+    pub time: DateTime<Utc>,
+    pub changes: Vec<KeyChange>,
+}
+
+impl UndoStep {
+    /// The changes on one line, for the history
+    pub fn summary(&self) -> String {
+        let shown = self.changes.iter().filter(|change| !change.is_bookkeeping());
+        shown.map(KeyChange::describe).collect::<Vec<_>>().join(", ")
+    }
+}
+
+/// What `config undo` did
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Undone {
+    /// Keys put back to their old values
+    pub reverted: Vec<KeyChange>,
+    /// Keys changed again since, left as they are
+    pub skipped: Vec<KeyChange>,
+}
+
+/// Journal the keys a save changed; `None` when it changed nothing
+pub fn record(paths: &Paths, before: Option<&str>, after: &str) -> Result<Option<UndoStep>> {
+    let before: toml::Table = before.map(str::parse).transpose()?.unwrap_or_default();
+    let changes = diff(&before, &after.parse()?);
+    if changes.is_empty() {
+        return Ok(None);
+    }
+    let step = UndoStep { time: Utc::now(), changes };
+    let mut steps = read(paths);
+    steps.push(step.clone());
+    let excess = steps.len().saturating_sub(MAX_STEPS);
+    steps.drain(..excess);
+    write(paths, &steps)?;
+    Ok(Some(step))
+}
+
+/// Revert the newest journaled change; `None` when there is nothing to undo
+pub fn undo(paths: &Paths) -> Result<Option<Undone>> {
+    let mut steps = read(paths);
+    let Some(step) = steps.pop() else {
+        return Ok(None);
+    };
+    let mut config: toml::Table = match fs::read_to_string(&paths.config_file) {
+        Ok(contents) => contents.parse()?,
+        Err(_) => toml::Table::new(),
+    };
+
+    let mut undone = Undone { reverted: Vec::new(), skipped: Vec::new() };
+    for change in step.changes {
+        if lookup(&config, &change.path) != change.after.as_ref() {
+            undone.skipped.push(change);
+            continue;
+        }
+        assign(&mut config, &change.path, change.before.clone());
+        undone.reverted.push(change);
+    }
+
+    // Written directly, so undoing isn't itself journaled: a second undo
+    // reverts the change before
+    paths.ensure_dirs()?;
+    write_private(&paths.config_file, &toml::to_string_pretty(&config)?)?;
+    write(paths, &steps)?;
+    Ok(Some(undone))
+}
+
+/// The journal, oldest change first; empty when missing or unreadable
+pub fn read(paths: &Paths) -> Vec<UndoStep> {
+    fs::read_to_string(paths.undo_file())
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn write(paths: &Paths, steps: &[UndoStep]) -> Result<()> {
+    write_private(&paths.undo_file(), &serde_json::to_string_pretty(steps)?)
+}
+
+/// Keys that differ between two config files, down to the keys of each
+/// section (`[apps."<slug>"]` tables count as one key)
+fn diff(before: &toml::Table, after: &toml::Table) -> Vec<KeyChange> {
+    let mut sections: Vec<&String> = before.keys().chain(after.keys()).collect();
+    sections.sort();
+    sections.dedup();
+
+    let empty = toml::Table::new();
+    let mut changes = Vec::new();
+    for section in sections {
+        if SKIPPED_SECTIONS.contains(&section.as_str()) {
+            continue;
+        }
+        let (old, new) = (before.get(section), after.get(section));
+        let old_table = old.map_or(Some(&empty), toml::Value::as_table);
+        let new_table = new.map_or(Some(&empty), toml::Value::as_table);
+        let (Some(old_table), Some(new_table)) = (old_table, new_table) else {
+            if old != new {
+                changes.push(change(vec![section.clone()], old, new));
+            }
+            continue;
+        };
+        let mut keys: Vec<&String> = old_table.keys().chain(new_table.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            let (old, new) = (old_table.get(key), new_table.get(key));
+            if old != new {
+                changes.push(change(vec![section.clone(), key.clone()], old, new));
+            }
+        }
+    }
+    changes
+}
+
+fn change(
+    path: Vec<String>,
+    before: Option<&toml::Value>,
+    after: Option<&toml::Value>,
+) -> KeyChange {
+    KeyChange { path, before: before.cloned(), after: after.cloned() }
+}
+
+fn lookup<'a>(table: &'a toml::Table, path: &[String]) -> Option<&'a toml::Value> {
+    let (last, parents) = path.split_last()?;
+    let mut table = table;
+    for name in parents {
+        table = table.get(name)?.as_table()?;
+    }
+    table.get(last)
+}
+
+/// Set (or with `None`, remove) the value at `path`, creating sections
+fn assign(table: &mut toml::Table, path: &[String], value: Option<toml::Value>) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let mut table = table;
+    for name in parents {
+        let entry = table
+            .entry(name.clone())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        let Some(section) = entry.as_table_mut() else {
+            return;
+        };
+        table = section;
+    }
+    match value {
+        Some(value) => {
+            table.insert(last.clone(), value);
+        }
+        None => {
+            table.remove(last);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn paths(dir: &TempDir) -> Paths {
+        Paths {
+            root: dir.path().to_path_buf(),
+            config_file: dir.path().join("config.toml"),
+        }
+    }
+
+    fn save(paths: &Paths, contents: &str) {
+        let before = fs::read_to_string(&paths.config_file).ok();
+        fs::write(&paths.config_file, contents).unwrap();
+        record(paths, before.as_deref(), contents).unwrap();
+    }
+
+    #[test]
+    fn test_diff_skips_api_section() {
+        let before = "[api]\ntoken = \"a\"\n[defaults]\napp_slug = \"ios\"\n";
+        let after = "[api]\ntoken = \"b\"\n[defaults]\napp_slug = \"android\"\n\
+                     [aliases]\nx = \"1\"\n";
+        let changes = diff(&before.parse().unwrap(), &after.parse().unwrap());
+        let described: Vec<String> = changes.iter().map(KeyChange::describe).collect();
+        assert_eq!(
+            described,
+            ["aliases.x: (unset) -> \"1\"", "defaults.app_slug: \"ios\" -> \"android\""]
+        );
+    }
+
+    #[test]
+    fn test_describe_hides_secrets() {
+        let before = "[notify]\nmin_interval = 60\n";
+        let after = "[notify]\nmin_interval = 90\nwebhook_url = \"https://hooks.example.com/x\"\n";
+        let changes = diff(&before.parse().unwrap(), &after.parse().unwrap());
+        let described: Vec<String> = changes.iter().map(KeyChange::describe).collect();
+        assert_eq!(described, ["notify.min_interval: 60 -> 90", "notify.webhook_url: (changed)"]);
+        let redacted = changes[1].redacted();
+        assert_eq!(redacted.before, None);
+        assert_eq!(redacted.after, Some(toml::Value::String("(redacted)".to_string())));
+    }
+
+    #[test]
+    fn test_undo_reverts_newest_change_first() {
+        let dir = TempDir::new().unwrap();
+        let paths = paths(&dir);
+        save(&paths, "[defaults]\napp_slug = \"ios\"\n");
+        save(&paths, "[defaults]\napp_slug = \"ios\"\n[aliases]\nx = \"1\"\n");
+        save(&paths, "[defaults]\napp_slug = \"typo\"\n[aliases]\nx = \"1\"\n");
+
+        let undone = undo(&paths).unwrap().unwrap();
+        assert_eq!(undone.reverted.len(), 1);
+        assert_eq!(undone.reverted[0].key(), "defaults.app_slug");
+        let config = fs::read_to_string(&paths.config_file).unwrap();
+        assert!(config.contains("app_slug = \"ios\""));
+        assert!(config.contains("x = \"1\""));
+
+        undo(&paths).unwrap().unwrap();
+        assert!(!fs::read_to_string(&paths.config_file).unwrap().contains("x = "));
+        undo(&paths).unwrap().unwrap();
+        assert_eq!(undo(&paths).unwrap(), None);
+    }
+
+    #[test]
+    fn test_undo_keeps_keys_changed_since() {
+        let dir = TempDir::new().unwrap();
+        let paths = paths(&dir);
+        save(&paths, "[output]\nformat = \"pretty\"\n");
+        save(&paths, "[output]\nformat = \"json\"\n");
+        // Edited by hand after the change
+        let edited = "[output]\nformat = \"pretty\"\npager = \"less\"\n";
+        fs::write(&paths.config_file, edited).unwrap();
+
+        let undone = undo(&paths).unwrap().unwrap();
+        assert!(undone.reverted.is_empty());
+        assert_eq!(undone.skipped[0].key(), "output.format");
+        assert!(fs::read_to_string(&paths.config_file).unwrap().contains("pager"));
+    }
+}
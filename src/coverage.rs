@@ -0,0 +1,177 @@
+//! Code coverage reports
+//!
+//! Line coverage totals from the report formats builds commonly store as
+//! artifacts: LCOV tracefiles (`lcov.info`) and Cobertura XML
+//! (`coverage.xml`, `cobertura.xml`).
+
+use serde::Serialize;
+
+/// A coverage report format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoverageFormat {
+    Lcov,
+    Cobertura,
+}
+
+impl CoverageFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lcov => "lcov",
+            Self::Cobertura => "cobertura",
+        }
+    }
+}
+
+/// Line coverage totals of a report
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Coverage {
+    pub lines_covered: u64,
+    pub lines_valid: u64,
+}
+
+impl Coverage {
+    /// Covered share of lines, 0-100, or `None` for a report without lines
+    pub fn percent(&self) -> Option<f64> {
+        (self.lines_valid > 0)
+            .then(|| self.lines_covered as f64 * 100.0 / self.lines_valid as f64)
+    }
+
+    /// Totals of several reports together
+    pub fn combine(reports: impl IntoIterator<Item = Coverage>) -> Coverage {
+        reports.into_iter().fold(Coverage::default(), |total, c| Coverage {
+            lines_covered: total.lines_covered + c.lines_covered,
+            lines_valid: total.lines_valid + c.lines_valid,
+        })
+    }
+}
+
+/// Whether an artifact title looks like a coverage report
+pub fn is_coverage_report(title: &str) -> bool {
+    let title = title.to_lowercase();
+    title.ends_with(".lcov")
+        || (title.ends_with(".info") && title.contains("lcov"))
+        || (title.ends_with(".xml") && (title.contains("cobertura") || title.contains("coverage")))
+}
+
+/// Parse a report, detecting its format; `None` if it is neither
+pub fn parse(contents: &str) -> Option<(CoverageFormat, Coverage)> {
+    if contents.contains("<coverage") {
+        parse_cobertura(contents).map(|c| (CoverageFormat::Cobertura, c))
+    } else {
+        parse_lcov(contents).map(|c| (CoverageFormat::Lcov, c))
+    }
+}
+
+/// Totals of an LCOV tracefile: each record's `LF`/`LH` summary, or its
+/// `DA` lines when a record has no summary
+fn parse_lcov(contents: &str) -> Option<Coverage> {
+    let mut total = Coverage::default();
+    let mut summary: Option<Coverage> = None;
+    let mut lines = Coverage::default();
+    let mut seen = false;
+
+    for line in contents.lines().map(str::trim) {
+        if let Some(found) = line.strip_prefix("LF:") {
+            summary.get_or_insert_with(Coverage::default).lines_valid = found.parse().ok()?;
+        } else if let Some(hit) = line.strip_prefix("LH:") {
+            summary.get_or_insert_with(Coverage::default).lines_covered = hit.parse().ok()?;
+        } else if let Some(data) = line.strip_prefix("DA:") {
+            let hits = data.split(',').nth(1)?;
+            lines.lines_valid += 1;
+            if hits.parse::<u64>().is_ok_and(|h| h > 0) {
+                lines.lines_covered += 1;
+            }
+        } else if line == "end_of_record" {
+            total = Coverage::combine([total, summary.take().unwrap_or(lines)]);
+            lines = Coverage::default();
+        } else if !line.starts_with("SF:") && !line.starts_with("TN:") {
+            continue;
+        }
+        seen = true;
+    }
+    // A final record without `end_of_record`
+    total = Coverage::combine([total, summary.unwrap_or(lines)]);
+    seen.then_some(total)
+}
+
+/// Totals of a Cobertura report: the root element's `lines-covered` and
+/// `lines-valid`, or its `<line hits=..>` elements when those are missing
+fn parse_cobertura(contents: &str) -> Option<Coverage> {
+    let start = contents.find("<coverage")?;
+    let root = &contents[start..start + contents[start..].find('>')?];
+    if let (Some(covered), Some(valid)) =
+        (attribute(root, "lines-covered"), attribute(root, "lines-valid"))
+    {
+        return Some(Coverage {
+            lines_covered: covered.parse().ok()?,
+            lines_valid: valid.parse().ok()?,
+        });
+    }
+
+    let mut coverage = Coverage::default();
+    for element in contents.split("<line ").skip(1) {
+        let element = &element[..element.find('>').unwrap_or(element.len())];
+        if let Some(hits) = attribute(element, "hits") {
+            coverage.lines_valid += 1;
+            if hits.parse::<u64>().is_ok_and(|h| h > 0) {
+                coverage.lines_covered += 1;
+            }
+        }
+    }
+    Some(coverage)
+}
+
+/// Value of `name="..."` in an XML start tag
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let needle = format!(" {}=\"", name);
+    let start = tag.find(&needle)? + needle.len();
+    let end = tag[start..].find('"')?;
+    Some(&tag[start..start + end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lcov_summaries_and_lines() {
+        let report = "TN:\nSF:src/a.rs\nDA:1,3\nDA:2,0\nLF:2\nLH:1\nend_of_record\n\
+                      SF:src/b.rs\nDA:1,1\nDA:2,1\nDA:3,0\nend_of_record\n";
+        let (format, coverage) = parse(report).unwrap();
+        assert_eq!(format, CoverageFormat::Lcov);
+        assert_eq!(coverage, Coverage { lines_covered: 3, lines_valid: 5 });
+        assert_eq!(coverage.percent(), Some(60.0));
+    }
+
+    #[test]
+    fn test_parse_cobertura() {
+        let report = r#"<?xml version="1.0" ?>
+<coverage line-rate="0.75" lines-covered="300" lines-valid="400" version="7.2">
+</coverage>"#;
+        assert_eq!(
+            parse(report),
+            Some((CoverageFormat::Cobertura, Coverage { lines_covered: 300, lines_valid: 400 }))
+        );
+
+        let lines_only = r#"<coverage line-rate="0.5"><packages><classes><lines>
+<line number="1" hits="2"/><line number="2" hits="0"/></lines></classes></packages></coverage>"#;
+        assert_eq!(parse(lines_only).unwrap().1, Coverage { lines_covered: 1, lines_valid: 2 });
+    }
+
+    #[test]
+    fn test_parse_rejects_other_files() {
+        assert_eq!(parse("just some text\n"), None);
+        assert_eq!(Coverage::default().percent(), None);
+    }
+
+    #[test]
+    fn test_is_coverage_report() {
+        assert!(is_coverage_report("lcov.info"));
+        assert!(is_coverage_report("app.lcov"));
+        assert!(is_coverage_report("cobertura.xml"));
+        assert!(is_coverage_report("Coverage-Report.xml"));
+        assert!(!is_coverage_report("junit.xml"));
+        assert!(!is_coverage_report("App.ipa"));
+    }
+}
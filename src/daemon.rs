@@ -0,0 +1,183 @@
+//! State of the status daemon
+//!
+//! `reprise watch-daemon` polls a set of targets, each an app or an app's
+//! branch, and reports when the newest finished build of a target ends
+//! differently from the one before it (a branch breaks, or is fixed).
+//! [`DaemonState`] remembers the last finished build per target; it is
+//! kept in the cache so a restarted daemon picks up where it left off.
+//!
+//! This module fetches and compares builds; the polling loop,
+//! notifications and output belong to the caller (`watch-daemon`, `tray`).
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::bitrise::{BitriseClient, Build, BuildStatus};
+use crate::error::Result;
+
+/// Cache key holding the daemon state
+pub const STATE_KEY: &str = "daemon_state";
+
+/// Seconds between polls unless configured otherwise
+pub const DEFAULT_INTERVAL: u64 = 300;
+
+/// Builds fetched per poll to find the newest finished one
+const BUILDS_PER_POLL: u32 = 10;
+
+/// The newest build of an app, or of one of its branches, that is no
+/// longer running
+pub fn latest_finished(
+    client: &BitriseClient,
+    app_slug: &str,
+    branch: Option<&str>,
+) -> Result<Option<Build>> {
+    let builds = client.list_builds(app_slug, None, branch, None, BUILDS_PER_POLL)?;
+    Ok(builds.data.into_iter().find(|b| b.status.is_finished()))
+}
+
+/// An app, or one of its branches, as written in `daemon.targets` or on
+/// the command line: `APP` or `APP:BRANCH`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    /// App slug, alias or name
+    pub app: String,
+    pub branch: Option<String>,
+}
+
+impl Target {
+    /// Parse `APP` or `APP:BRANCH`
+    pub fn parse(spec: &str) -> std::result::Result<Self, String> {
+        let (app, branch) = match spec.split_once(':') {
+            Some((app, branch)) => (app.trim(), Some(branch.trim())),
+            None => (spec.trim(), None),
+        };
+        if app.is_empty() || branch.is_some_and(str::is_empty) {
+            return Err(format!("expected APP or APP:BRANCH, got '{}'", spec));
+        }
+        Ok(Self {
+            app: app.to_string(),
+            branch: branch.map(str::to_string),
+        })
+    }
+
+    /// The target as written, e.g. `ios:main`
+    pub fn label(&self) -> String {
+        match self.branch {
+            Some(ref branch) => format!("{}:{}", self.app, branch),
+            None => self.app.clone(),
+        }
+    }
+
+    /// State key for the target once its app is resolved to a slug
+    pub fn key(&self, app_slug: &str) -> String {
+        match self.branch {
+            Some(ref branch) => format!("{}:{}", app_slug, branch),
+            None => app_slug.to_string(),
+        }
+    }
+}
+
+/// The last finished build seen for a target
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Seen {
+    pub build_slug: String,
+    pub build_number: i64,
+    pub status: BuildStatus,
+}
+
+/// Last finished build per target key
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DaemonState {
+    #[serde(default)]
+    pub targets: HashMap<String, Seen>,
+}
+
+/// What a newly finished build means for its target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Observation {
+    /// The first build seen for the target; nothing to compare with
+    First,
+    /// Same outcome as the previous build
+    Unchanged,
+    /// A different outcome than the previous build
+    Changed { from: BuildStatus },
+}
+
+impl DaemonState {
+    /// Record the newest finished build of a target
+    ///
+    /// Returns `None` when the build was already seen, otherwise how it
+    /// compares to the previous one.
+    pub fn observe(&mut self, key: &str, build: &Build) -> Option<Observation> {
+        let seen = Seen {
+            build_slug: build.slug.clone(),
+            build_number: build.build_number,
+            status: build.status,
+        };
+        let previous = self.targets.insert(key.to_string(), seen);
+        match previous {
+            Some(previous) if previous.build_slug == build.slug => None,
+            // An older build finishing late doesn't replace the newer one
+            Some(previous) if previous.build_number > build.build_number => {
+                self.targets.insert(key.to_string(), previous);
+                None
+            }
+            Some(previous) if previous.status == build.status => Some(Observation::Unchanged),
+            Some(previous) => Some(Observation::Changed { from: previous.status }),
+            None => Some(Observation::First),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(number: i64, status: BuildStatus) -> Build {
+        Build {
+            slug: format!("build-{}", number),
+            build_number: number,
+            status,
+            status_text: status.as_str().to_string(),
+            ..Build::fixture()
+        }
+    }
+
+    #[test]
+    fn test_parse_target() {
+        let target = Target::parse("ios:release/1.2").unwrap();
+        assert_eq!(target.app, "ios");
+        assert_eq!(target.branch.as_deref(), Some("release/1.2"));
+        assert_eq!(target.label(), "ios:release/1.2");
+        assert_eq!(target.key("abc123"), "abc123:release/1.2");
+        assert_eq!(Target::parse("ios").unwrap().key("abc123"), "abc123");
+        assert!(Target::parse("ios:").is_err());
+        assert!(Target::parse(":main").is_err());
+    }
+
+    #[test]
+    fn test_observe_reports_status_changes() {
+        let mut state = DaemonState::default();
+        let first = state.observe("app", &build(1, BuildStatus::Success));
+        assert_eq!(first, Some(Observation::First));
+        assert_eq!(state.observe("app", &build(1, BuildStatus::Success)), None);
+        assert_eq!(
+            state.observe("app", &build(2, BuildStatus::Success)),
+            Some(Observation::Unchanged)
+        );
+        assert_eq!(
+            state.observe("app", &build(3, BuildStatus::Failed)),
+            Some(Observation::Changed { from: BuildStatus::Success })
+        );
+        assert_eq!(state.targets["app"].build_number, 3);
+    }
+
+    #[test]
+    fn test_observe_ignores_older_builds() {
+        let mut state = DaemonState::default();
+        state.observe("app", &build(5, BuildStatus::Failed));
+        assert_eq!(state.observe("app", &build(4, BuildStatus::Success)), None);
+        assert_eq!(state.targets["app"].build_number, 5);
+    }
+}
@@ -1,11 +1,16 @@
-//! Duration parsing for time-based filtering
+//! Duration parsing for time-based filtering and timeouts
 //!
 //! Supports formats:
 //! - Relative: 1h, 30m, 2d, 1w
 //! - Named: today, yesterday, this-week
 //! - ISO 8601: 2025-12-11T09:00:00
+//!
+//! `--since` values mark the start of a window; `--until` values mark its
+//! end, where whole days (`yesterday`, `2024-05-01`) include the entire day.
 
-use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono::{
+    DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+};
 
 use crate::error::{RepriseError, Result};
 
@@ -37,6 +42,107 @@ pub fn parse_since(s: &str) -> Result<DateTime<Utc>> {
     )))
 }
 
+/// Parse an `--until` value and return the (exclusive) UTC upper bound
+///
+/// Whole-day values cover the full day: `--until yesterday` and
+/// `--until 2024-05-01` include builds up to midnight at the end of that day.
+pub fn parse_until(s: &str) -> Result<DateTime<Utc>> {
+    let lower = s.trim().to_lowercase();
+    let whole_day = matches!(lower.as_str(), "today" | "yesterday")
+        || NaiveDate::parse_from_str(&lower, "%Y-%m-%d").is_ok();
+
+    let start = parse_since(&lower)?;
+    if whole_day {
+        Ok(start + Duration::days(1))
+    } else {
+        Ok(start)
+    }
+}
+
+/// A `--since`/`--until` time window for client-side filtering
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeWindow {
+    /// Inclusive lower bound
+    pub since: Option<DateTime<Utc>>,
+    /// Exclusive upper bound
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl TimeWindow {
+    /// Build a window from optional `--since`/`--until` arguments
+    ///
+    /// Returns `None` when neither bound is given.
+    pub fn from_args(since: Option<&str>, until: Option<&str>) -> Result<Option<Self>> {
+        let window = Self {
+            since: since.map(parse_since).transpose()?,
+            until: until.map(parse_until).transpose()?,
+        };
+
+        if let (Some(start), Some(end)) = (window.since, window.until) {
+            if start >= end {
+                return Err(RepriseError::InvalidArgument(format!(
+                    "--since ({}) must be before --until ({})",
+                    since.unwrap_or_default(),
+                    until.unwrap_or_default()
+                )));
+            }
+        }
+
+        Ok((window.since.is_some() || window.until.is_some()).then_some(window))
+    }
+
+    /// Whether a timestamp falls inside the window
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.since.is_none_or(|start| at >= start) && self.until.is_none_or(|end| at < end)
+    }
+
+    /// Whether a timestamp is older than the start of the window
+    ///
+    /// Listings are newest first, so paging can stop once this is true.
+    pub fn is_before(&self, at: DateTime<Utc>) -> bool {
+        self.since.is_some_and(|start| at < start)
+    }
+}
+
+/// Parse a span of time such as `90s`, `30m`, `2h`
+///
+/// Used for timeouts. Accepts the same units as relative `--since` values
+/// plus seconds.
+pub fn parse_duration(s: &str) -> Result<Duration> {
+    parse_span(&s.trim().to_lowercase()).ok_or_else(|| {
+        RepriseError::InvalidArgument(format!(
+            "Invalid duration: '{}'. Use formats like: 90s, 30m, 2h, 1d",
+            s
+        ))
+    })
+}
+
+/// Resolve a 24-hour time of day (`02:00`, `14:30:15`) to its next occurrence
+///
+/// A time that has already passed today means tomorrow. Used by `trigger --at`.
+pub fn next_time_of_day(s: &str, now: DateTime<Local>) -> Result<DateTime<Local>> {
+    let trimmed = s.trim();
+    let time = NaiveTime::parse_from_str(trimmed, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(trimmed, "%H:%M:%S"))
+        .map_err(|_| {
+            RepriseError::InvalidArgument(format!(
+                "Invalid time: '{}'. Use a 24-hour time like 02:00 or 18:30",
+                s
+            ))
+        })?;
+
+    // Look a few days ahead in case the time falls in a DST gap
+    let mut date = now.date_naive();
+    for _ in 0..3 {
+        let at = Local.from_local_datetime(&date.and_time(time)).earliest();
+        if let Some(at) = at.filter(|at| *at > now) {
+            return Ok(at);
+        }
+        date = date.succ_opt().unwrap_or(date);
+    }
+    Err(RepriseError::InvalidArgument(format!("Time '{}' never occurs", s)))
+}
+
 /// Parse named duration keywords
 fn parse_named_duration(s: &str, now: DateTime<Local>) -> Option<DateTime<Local>> {
     match s {
@@ -69,6 +175,11 @@ fn parse_named_duration(s: &str, now: DateTime<Local>) -> Option<DateTime<Local>
 
 /// Parse relative duration (e.g., 1h, 30m, 2d, 1w)
 fn parse_relative_duration(s: &str, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    parse_span(s).map(|duration| now - duration)
+}
+
+/// Parse a number followed by a unit (e.g., 90s, 30m, 2d) into a duration
+fn parse_span(s: &str) -> Option<Duration> {
     let s = s.trim();
     if s.is_empty() {
         return None;
@@ -88,6 +199,7 @@ fn parse_relative_duration(s: &str, now: DateTime<Local>) -> Option<DateTime<Loc
     }
 
     let duration = match unit.to_lowercase().as_str() {
+        "s" | "sec" | "secs" | "second" | "seconds" => Duration::seconds(num),
         "m" | "min" | "mins" | "minute" | "minutes" => Duration::minutes(num),
         "h" | "hr" | "hrs" | "hour" | "hours" => Duration::hours(num),
         "d" | "day" | "days" => Duration::days(num),
@@ -95,7 +207,7 @@ fn parse_relative_duration(s: &str, now: DateTime<Local>) -> Option<DateTime<Loc
         _ => return None,
     };
 
-    Some(now - duration)
+    Some(duration)
 }
 
 /// Parse datetime string (ISO 8601 or date-only)
@@ -207,4 +319,105 @@ mod tests {
         assert!(parse_since("1H").is_ok());
         assert!(parse_since("2D").is_ok());
     }
+
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("90s").unwrap(), Duration::seconds(90));
+        assert_eq!(parse_duration("30m").unwrap(), Duration::minutes(30));
+        assert_eq!(parse_duration("2H").unwrap(), Duration::hours(2));
+        assert_eq!(parse_duration("1d").unwrap(), Duration::days(1));
+    }
+
+    #[test]
+    fn test_parse_duration_invalid() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("30").is_err());
+        assert!(parse_duration("0m").is_err());
+        assert!(parse_duration("today").is_err());
+    }
+
+    #[test]
+    fn test_parse_until_date_covers_whole_day() {
+        let until = parse_until("2024-05-01").unwrap();
+        let since = parse_since("2024-05-01").unwrap();
+        assert_eq!(until - since, Duration::days(1));
+    }
+
+    #[test]
+    fn test_parse_until_yesterday_ends_at_midnight_today() {
+        assert_eq!(parse_until("yesterday").unwrap(), parse_since("today").unwrap());
+    }
+
+    #[test]
+    fn test_parse_until_relative() {
+        let result = parse_until("2h").unwrap();
+        let expected = Utc::now() - Duration::hours(2);
+        assert!((result - expected).num_seconds().abs() < 2);
+    }
+
+    #[test]
+    fn test_time_window_none_without_bounds() {
+        assert!(TimeWindow::from_args(None, None).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_time_window_contains() {
+        let window = TimeWindow::from_args(Some("2024-05-01"), Some("2024-05-01"))
+            .unwrap()
+            .unwrap();
+        let start = window.since.unwrap();
+        assert!(window.contains(start));
+        assert!(window.contains(start + Duration::hours(23)));
+        assert!(!window.contains(start + Duration::days(1)));
+        assert!(!window.contains(start - Duration::seconds(1)));
+        assert!(window.is_before(start - Duration::seconds(1)));
+        assert!(!window.is_before(start));
+    }
+
+    #[test]
+    fn test_time_window_rejects_inverted_bounds() {
+        let err = TimeWindow::from_args(Some("2024-05-02"), Some("2024-04-30")).unwrap_err();
+        assert!(err.to_string().contains("--since"));
+    }
+
+    #[test]
+    fn test_time_window_until_only() {
+        let window = TimeWindow::from_args(None, Some("1h")).unwrap().unwrap();
+        assert!(window.since.is_none());
+        assert!(!window.is_before(Utc::now() - Duration::weeks(52)));
+        assert!(!window.contains(Utc::now()));
+    }
+
+    #[test]
+    fn test_next_time_of_day_later_today() {
+        let now = Local.with_ymd_and_hms(2025, 3, 10, 9, 0, 0).unwrap();
+        let at = next_time_of_day("18:30", now).unwrap();
+        assert_eq!(at, Local.with_ymd_and_hms(2025, 3, 10, 18, 30, 0).unwrap());
+    }
+
+    #[test]
+    fn test_next_time_of_day_passed_means_tomorrow() {
+        let now = Local.with_ymd_and_hms(2025, 3, 10, 9, 0, 0).unwrap();
+        let at = next_time_of_day("02:00", now).unwrap();
+        assert_eq!(at, Local.with_ymd_and_hms(2025, 3, 11, 2, 0, 0).unwrap());
+
+        // The current minute itself is already past
+        let at = next_time_of_day("09:00", now).unwrap();
+        assert_eq!(at.date_naive(), NaiveDate::from_ymd_opt(2025, 3, 11).unwrap());
+    }
+
+    #[test]
+    fn test_next_time_of_day_with_seconds() {
+        let now = Local.with_ymd_and_hms(2025, 3, 10, 9, 0, 0).unwrap();
+        let at = next_time_of_day(" 14:30:15 ", now).unwrap();
+        assert_eq!(at, Local.with_ymd_and_hms(2025, 3, 10, 14, 30, 15).unwrap());
+    }
+
+    #[test]
+    fn test_next_time_of_day_invalid() {
+        let now = Local::now();
+        assert!(next_time_of_day("25:00", now).is_err());
+        assert!(next_time_of_day("2am", now).is_err());
+        assert!(next_time_of_day("", now).is_err());
+    }
 }
@@ -30,6 +30,14 @@ pub enum RepriseError {
     #[error("Failed to parse config file: {0}")]
     Toml(#[from] toml::de::Error),
 
+    /// bitrise.yml parsing error
+    #[error("Failed to parse bitrise.yml: {0}")]
+    Yml(#[from] crate::bitrise::yml::YmlError),
+
+    /// bitrise.yml failed validation
+    #[error("Invalid bitrise.yml: {0}")]
+    YmlInvalid(String),
+
     /// TOML serialization error
     #[error("Failed to write config file: {0}")]
     TomlSerialize(#[from] toml::ser::Error),
@@ -61,6 +69,22 @@ pub enum RepriseError {
     /// Environment variable error
     #[error("Environment error: {0}")]
     Env(#[from] std::env::VarError),
+
+    /// A waited-on build finished unsuccessfully
+    #[error("Build failed: {0}")]
+    BuildFailed(String),
+
+    /// A waited-on build finished without meeting the wait condition
+    #[error("Condition not met: {0}")]
+    ConditionUnmet(String),
+
+    /// Gave up waiting
+    #[error("Timed out: {0}")]
+    Timeout(String),
+
+    /// Interrupted by the user (Ctrl+C)
+    #[error("Interrupted")]
+    Interrupted,
 }
 
 impl RepriseError {
@@ -85,6 +109,10 @@ impl RepriseError {
     /// - 78: Configuration errors (EX_CONFIG from sysexits.h)
     /// - 69: Service unavailable (EX_UNAVAILABLE) for API errors
     /// - 66: Not found errors (EX_NOINPUT)
+    /// - 1: A waited-on build failed, or finished without meeting the
+    ///   wait condition (for merge gating scripts)
+    /// - 75: Timed out waiting (EX_TEMPFAIL)
+    /// - 130: Interrupted by Ctrl+C
     pub fn exit_code(&self) -> i32 {
         match self {
             // Configuration errors
@@ -112,7 +140,16 @@ impl RepriseError {
             Self::Io(_) | Self::Env(_) => 74, // EX_IOERR
 
             // Parsing errors
-            Self::Json(_) | Self::Toml(_) | Self::TomlSerialize(_) => 65, // EX_DATAERR
+            Self::Json(_)
+            | Self::Toml(_)
+            | Self::TomlSerialize(_)
+            | Self::Yml(_)
+            | Self::YmlInvalid(_) => 65, // EX_DATAERR
+
+            // Wait results
+            Self::BuildFailed(_) | Self::ConditionUnmet(_) => 1,
+            Self::Timeout(_) => 75, // EX_TEMPFAIL
+            Self::Interrupted => 130,
         }
     }
 }
@@ -254,6 +291,33 @@ mod tests {
         assert_eq!(err.exit_code(), 69); // EX_UNAVAILABLE
     }
 
+    // ─────────────────────────────────────────────────────────────────────────
+    // Exit Code Tests - Wait Results
+    // ─────────────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_exit_code_build_failed() {
+        let err = RepriseError::BuildFailed("#12 failed".to_string());
+        assert_eq!(err.exit_code(), 1);
+    }
+
+    #[test]
+    fn test_exit_code_condition_unmet() {
+        let err = RepriseError::ConditionUnmet("build #5 finished failed".to_string());
+        assert_eq!(err.exit_code(), 1);
+    }
+
+    #[test]
+    fn test_exit_code_timeout() {
+        let err = RepriseError::Timeout("30m".to_string());
+        assert_eq!(err.exit_code(), 75); // EX_TEMPFAIL
+    }
+
+    #[test]
+    fn test_exit_code_interrupted() {
+        assert_eq!(RepriseError::Interrupted.exit_code(), 130);
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // Exit Code Tests - IO Errors (74)
     // ─────────────────────────────────────────────────────────────────────────
@@ -290,6 +354,15 @@ mod tests {
         assert_eq!(err.exit_code(), 65); // EX_DATAERR
     }
 
+    #[test]
+    fn test_exit_code_yml_error() {
+        let yml_err = crate::bitrise::yml::parse("a: 1\na: 2\n").unwrap_err();
+        let err = RepriseError::Yml(yml_err);
+        assert_eq!(err.exit_code(), 65); // EX_DATAERR
+        assert_eq!(err.to_string(), "Failed to parse bitrise.yml: line 2: duplicate key 'a'");
+        assert_eq!(RepriseError::YmlInvalid("1 error(s)".into()).exit_code(), 65);
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // Error Display Tests
     // ─────────────────────────────────────────────────────────────────────────
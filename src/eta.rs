@@ -0,0 +1,143 @@
+//! Completion estimates for running builds
+//!
+//! A workflow's usual duration is the median of its most recent successful
+//! builds. It is kept in the cache for [`STATS_TTL`] under [`STATS_CACHE_KEY`],
+//! so watching a build costs at most one extra request per workflow, and is
+//! used to turn a build's elapsed time into a progress percentage and an
+//! expected finish.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::bitrise::{BitriseClient, BuildStatus};
+use crate::cache::Cache;
+use crate::config::Config;
+
+/// Cache key holding the usual duration of each app's workflows
+pub const STATS_CACHE_KEY: &str = "workflow-durations";
+
+/// How long a workflow's usual duration is trusted before it is refetched
+pub const STATS_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// How many recent successful builds the median is taken over
+const SAMPLE_SIZE: u32 = 10;
+
+/// A workflow's usual duration as cached
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WorkflowStats {
+    /// Median duration in seconds, `None` if the workflow never succeeded
+    median_secs: Option<u64>,
+    /// Number of builds the median was taken over
+    samples: usize,
+    checked_at: DateTime<Utc>,
+}
+
+static CACHE: OnceLock<Option<Cache>> = OnceLock::new();
+
+/// Lookups already made by this process, including failed ones
+static SEEN: Mutex<Option<HashMap<String, Option<Duration>>>> = Mutex::new(None);
+
+/// Apply the cache location to all estimates made by this process
+pub fn configure(config: &Config) {
+    let _ = CACHE.set(Cache::for_config(&config.cache).ok());
+}
+
+/// How long `workflow` usually takes on an app, if it has succeeded before
+///
+/// Each workflow is looked up at most once per process; a failed lookup
+/// only means no estimate is shown.
+pub fn expected_duration(
+    client: &BitriseClient,
+    app_slug: &str,
+    workflow: &str,
+) -> Option<Duration> {
+    let key = format!("{}/{}", app_slug, workflow);
+    let mut seen = SEEN.lock().ok()?;
+    let seen = seen.get_or_insert_with(HashMap::new);
+    if let Some(expected) = seen.get(&key) {
+        return *expected;
+    }
+    let expected = lookup(client, app_slug, workflow, &key);
+    seen.insert(key, expected);
+    expected
+}
+
+/// The usual duration from the cache, or from the API when missing or stale
+fn lookup(client: &BitriseClient, app_slug: &str, workflow: &str, key: &str) -> Option<Duration> {
+    let cache = CACHE.get_or_init(|| Cache::new().ok()).as_ref();
+    let max_age = chrono::Duration::from_std(STATS_TTL).unwrap_or(chrono::Duration::MAX);
+    let mut stats: HashMap<String, WorkflowStats> = cache
+        .and_then(|c| c.read(STATS_CACHE_KEY))
+        .map(|entry| entry.value)
+        .unwrap_or_default();
+    let now = Utc::now();
+    stats.retain(|_, s| now - s.checked_at < max_age);
+
+    if let Some(cached) = stats.get(key) {
+        return cached.median_secs.map(Duration::from_secs);
+    }
+
+    let success = Some(BuildStatus::Success);
+    let builds = client.list_builds(app_slug, success, None, Some(workflow), SAMPLE_SIZE).ok()?;
+    let secs: Vec<u64> = builds
+        .data
+        .iter()
+        .filter_map(|b| b.duration())
+        .map(|d| d.num_seconds().max(0) as u64)
+        .collect();
+    let median_secs = median(secs.clone());
+    stats.insert(
+        key.to_string(),
+        WorkflowStats { median_secs, samples: secs.len(), checked_at: now },
+    );
+    if let Some(cache) = cache {
+        // A cache write failure only costs a refetch next time
+        let _ = cache.put(STATS_CACHE_KEY, &stats);
+    }
+    median_secs.map(Duration::from_secs)
+}
+
+/// Median of some durations in seconds
+fn median(mut secs: Vec<u64>) -> Option<u64> {
+    if secs.is_empty() {
+        return None;
+    }
+    secs.sort_unstable();
+    let mid = secs.len() / 2;
+    Some(if secs.len().is_multiple_of(2) { (secs[mid - 1] + secs[mid]) / 2 } else { secs[mid] })
+}
+
+/// How far along a build is after `elapsed` of an `expected` run, capped at
+/// 99% until it actually finishes
+pub fn percent_done(elapsed: Duration, expected: Duration) -> u8 {
+    if expected.is_zero() {
+        return 99;
+    }
+    let percent = elapsed.as_secs_f64() / expected.as_secs_f64() * 100.0;
+    percent.clamp(0.0, 99.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(vec![300, 100, 200]), Some(200));
+        assert_eq!(median(vec![400, 100, 200, 300]), Some(250));
+        assert_eq!(median(vec![]), None);
+    }
+
+    #[test]
+    fn test_percent_done_caps_below_finished() {
+        let expected = Duration::from_secs(600);
+        assert_eq!(percent_done(Duration::ZERO, expected), 0);
+        assert_eq!(percent_done(Duration::from_secs(150), expected), 25);
+        assert_eq!(percent_done(Duration::from_secs(900), expected), 99);
+        assert_eq!(percent_done(Duration::from_secs(5), Duration::ZERO), 99);
+    }
+}
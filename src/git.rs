@@ -0,0 +1,210 @@
+//! Helpers for reading state from the local git repository
+//!
+//! All functions shell out to `git` and return `None` when git is not
+//! installed, the working directory is not a repository, or the query fails.
+
+use std::process::{Command, Stdio};
+
+use crate::error::{RepriseError, Result};
+
+/// Run a git command and return its trimmed stdout on success
+fn git(args: &[&str]) -> Option<String> {
+    Command::new("git")
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Get the current branch name (`None` when detached or outside a repository)
+pub fn current_branch() -> Option<String> {
+    git(&["symbolic-ref", "--quiet", "--short", "HEAD"])
+}
+
+/// Get the full commit hash of HEAD
+pub fn head_commit() -> Option<String> {
+    resolve_rev("HEAD")
+}
+
+/// Resolve a revision (e.g. `HEAD`, `main~1`, a short hash) to a full commit hash
+pub fn resolve_rev(rev: &str) -> Option<String> {
+    git(&["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", rev)])
+}
+
+/// Resolve a user-supplied commit argument to a hash.
+///
+/// Revisions such as `HEAD` or `main~1` are resolved through git. Hex
+/// strings are expanded when they exist locally and otherwise used as-is,
+/// so hashes of commits that were never fetched still work.
+pub fn resolve_commit_arg(input: &str) -> Result<String> {
+    let is_hex = input.len() >= 4 && input.chars().all(|c| c.is_ascii_hexdigit());
+    match resolve_rev(input) {
+        Some(sha) => Ok(sha),
+        None if is_hex => Ok(input.to_lowercase()),
+        None => Err(RepriseError::InvalidArgument(format!(
+            "Cannot resolve '{}' to a commit (not a git revision or commit hash)",
+            input
+        ))),
+    }
+}
+
+/// How a commit relates to the checked-out HEAD
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeadRelation {
+    /// The commit is HEAD
+    Head,
+    /// The commit descends from HEAD, so it includes the local changes
+    IncludesHead,
+    /// The commit is an ancestor of HEAD, so it predates the local changes
+    BehindHead,
+}
+
+/// Check whether `ancestor` is an ancestor of `descendant`
+///
+/// Returns `None` when either commit is unknown locally (e.g. never fetched).
+pub fn is_ancestor(ancestor: &str, descendant: &str) -> Option<bool> {
+    let status = Command::new("git")
+        .args(["merge-base", "--is-ancestor", ancestor, descendant])
+        .stderr(Stdio::null())
+        .status()
+        .ok()?;
+    match status.code() {
+        Some(0) => Some(true),
+        Some(1) => Some(false),
+        _ => None,
+    }
+}
+
+/// Relate `commit` to the full `head` hash; `None` when the two are unrelated
+/// or the commit is not in the local repository
+pub fn head_relation(commit: &str, head: &str) -> Option<HeadRelation> {
+    if commit.len() >= 7 && commit_matches(head, commit) {
+        return Some(HeadRelation::Head);
+    }
+    if is_ancestor(head, commit)? {
+        Some(HeadRelation::IncludesHead)
+    } else if is_ancestor(commit, head)? {
+        Some(HeadRelation::BehindHead)
+    } else {
+        None
+    }
+}
+
+/// Get the URL of the `origin` remote
+pub fn origin_url() -> Option<String> {
+    git(&["remote", "get-url", "origin"])
+}
+
+/// Get the branches of the `origin` remote, as last fetched
+pub fn remote_branches() -> Option<Vec<String>> {
+    let refs = git(&["for-each-ref", "--format=%(refname:lstrip=3)", "refs/remotes/origin"])?;
+    Some(
+        refs.lines()
+            .filter(|name| *name != "HEAD")
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+/// Normalize a repository URL to `host/owner/repo` (lowercase)
+///
+/// Handles HTTPS, `ssh://` and scp-style (`git@host:owner/repo.git`) URLs so
+/// that a local remote can be compared with the `repo_url` Bitrise reports.
+pub fn normalize_repo_url(url: &str) -> Option<String> {
+    let url = url.trim();
+    let rest = match url.split_once("://") {
+        Some((_, rest)) => rest.to_string(),
+        // scp-style: git@github.com:owner/repo.git
+        None => url.replacen(':', "/", 1),
+    };
+
+    // Strip credentials/user and trailing .git or slashes
+    let rest = rest.rsplit_once('@').map_or(rest.as_str(), |(_, host)| host);
+    let rest = rest.trim_end_matches('/');
+    let rest = rest.strip_suffix(".git").unwrap_or(rest);
+
+    // Drop an explicit port from the host
+    let (host, path) = rest.split_once('/')?;
+    let host = host.split(':').next().unwrap_or(host);
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+
+    Some(format!("{}/{}", host, path).to_lowercase())
+}
+
+/// Check whether `prefix` abbreviates `full` (case-insensitive)
+///
+/// Useful for matching a user-supplied short hash against the full
+/// `commit_hash` reported by Bitrise.
+pub fn commit_matches(full: &str, prefix: &str) -> bool {
+    !prefix.is_empty()
+        && full
+            .get(..prefix.len())
+            .is_some_and(|head| head.eq_ignore_ascii_case(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_matches_prefix() {
+        assert!(commit_matches("abc123def456", "abc123"));
+        assert!(commit_matches("abc123def456", "ABC123"));
+        assert!(commit_matches("abc123def456", "abc123def456"));
+    }
+
+    #[test]
+    fn test_resolve_commit_arg_passes_through_unknown_hash() {
+        let sha = "0123456789abcdef0123456789abcdef01234567";
+        assert_eq!(resolve_commit_arg(sha).unwrap(), sha);
+        assert_eq!(resolve_commit_arg("ABCDEF12").unwrap(), "abcdef12");
+    }
+
+    #[test]
+    fn test_resolve_commit_arg_rejects_garbage() {
+        assert!(resolve_commit_arg("definitely/not/a-rev").is_err());
+        assert!(resolve_commit_arg("abc").is_err());
+    }
+
+    #[test]
+    fn test_normalize_repo_url_variants() {
+        let expected = Some("github.com/dan-hart/reprise".to_string());
+        assert_eq!(normalize_repo_url("https://github.com/dan-hart/reprise.git"), expected);
+        assert_eq!(normalize_repo_url("https://github.com/dan-hart/reprise/"), expected);
+        assert_eq!(normalize_repo_url("git@github.com:dan-hart/reprise.git"), expected);
+        assert_eq!(normalize_repo_url("ssh://git@github.com:22/dan-hart/reprise"), expected);
+        assert_eq!(normalize_repo_url("https://user:pw@GitHub.com/Dan-Hart/Reprise"), expected);
+    }
+
+    #[test]
+    fn test_normalize_repo_url_invalid() {
+        assert_eq!(normalize_repo_url("not-a-url"), None);
+        assert_eq!(normalize_repo_url(""), None);
+    }
+
+    #[test]
+    fn test_head_relation_same_commit() {
+        let head = "0123456789abcdef0123456789abcdef01234567";
+        assert_eq!(head_relation(head, head), Some(HeadRelation::Head));
+        assert_eq!(head_relation("0123456789", head), Some(HeadRelation::Head));
+    }
+
+    #[test]
+    fn test_head_relation_unknown_commit() {
+        let head = "0123456789abcdef0123456789abcdef01234567";
+        assert_eq!(head_relation("fedcba9876543210fedcba9876543210fedcba98", head), None);
+        assert_eq!(is_ancestor("not-a-commit", head), None);
+    }
+
+    #[test]
+    fn test_commit_matches_rejects_mismatch() {
+        assert!(!commit_matches("abc123def456", "abd"));
+        assert!(!commit_matches("abc", "abc123"));
+        assert!(!commit_matches("abc123", ""));
+    }
+}
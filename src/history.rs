@@ -0,0 +1,265 @@
+//! Local history of actions that change something
+//!
+//! Triggers, aborts, rebuilds, downloads and config changes are appended to
+//! `~/.reprise/history.jsonl`, one JSON object per line, with the command
+//! line that ran them, the local user and whether they worked. On a shared
+//! automation box that answers "who aborted my build?"; `reprise history`
+//! reads it back.
+//!
+//! Recording is off until [`configure`] is called, so only the `reprise`
+//! binary writes history, not library users. It never fails the action:
+//! a history file that can't be written is skipped silently.
+
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Paths;
+use crate::error::Result;
+
+/// Name of the history file in the config directory
+pub const FILE_NAME: &str = "history.jsonl";
+
+/// Past this size the oldest half of the entries is dropped
+const MAX_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Flags whose value is a secret, left out of recorded command lines
+const SECRET_FLAGS: &[&str] = &["--token"];
+
+static RECORDER: OnceLock<Recorder> = OnceLock::new();
+
+/// Where entries go and what every entry of this process shares
+struct Recorder {
+    file: PathBuf,
+    command: String,
+    user: Option<String>,
+}
+
+/// One recorded action
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub time: DateTime<Utc>,
+    /// What was done: "trigger", "abort", "rebuild", "download", ...
+    pub action: String,
+    /// The reprise command line, with secrets masked
+    pub command: String,
+    /// Empty for actions that aren't about an app, like config changes
+    #[serde(default)]
+    pub app_slug: String,
+    /// The build (or pipeline) acted on or started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
+    /// "ok" or "failed"
+    pub result: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Local account that ran the command
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// What changed, for config changes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// The history file (~/.reprise/history.jsonl)
+pub fn file(paths: &Paths) -> PathBuf {
+    paths.root.join(FILE_NAME)
+}
+
+/// Record this process's actions in the history file
+pub fn configure(paths: &Paths) {
+    let args: Vec<String> = std::env::args().collect();
+    let _ = RECORDER.set(Recorder {
+        file: file(paths),
+        command: command_line(&args),
+        user: std::env::var("USER").or_else(|_| std::env::var("USERNAME")).ok(),
+    });
+}
+
+/// Record an action and its outcome
+///
+/// `slug` is the build or pipeline the action was about; for a trigger or
+/// a rebuild, the one it started.
+pub fn record<T>(action: &str, app_slug: &str, slug: Option<&str>, result: &Result<T>) {
+    let Some(recorder) = RECORDER.get() else {
+        return;
+    };
+    let entry = Entry {
+        time: Utc::now(),
+        action: action.to_string(),
+        command: recorder.command.clone(),
+        app_slug: app_slug.to_string(),
+        slug: slug.map(str::to_string),
+        result: if result.is_ok() { "ok" } else { "failed" }.to_string(),
+        error: result.as_ref().err().map(|e| e.to_string()),
+        user: recorder.user.clone(),
+        detail: None,
+    };
+    let _ = append(&recorder.file, &entry);
+}
+
+/// Record a saved config change, described by `detail`
+pub fn record_config(action: &str, detail: &str) {
+    let Some(recorder) = RECORDER.get() else {
+        return;
+    };
+    let entry = Entry {
+        time: Utc::now(),
+        action: action.to_string(),
+        command: recorder.command.clone(),
+        app_slug: String::new(),
+        slug: None,
+        result: "ok".to_string(),
+        error: None,
+        user: recorder.user.clone(),
+        detail: Some(detail.to_string()),
+    };
+    let _ = append(&recorder.file, &entry);
+}
+
+/// Append an entry to a history file, trimming the file when it grows large
+pub fn append(file: &Path, entry: &Entry) -> Result<()> {
+    if fs::metadata(file).is_ok_and(|m| m.len() > MAX_BYTES) {
+        let entries = read(file)?;
+        let kept = &entries[entries.len() / 2..];
+        let mut contents = String::new();
+        for entry in kept {
+            contents.push_str(&serde_json::to_string(entry)?);
+            contents.push('\n');
+        }
+        fs::write(file, contents)?;
+    }
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut out = open_private(file)?;
+    writeln!(out, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/// Open a history file for appending, readable by its owner only (like the
+/// config file), since entries hold command lines and config changes
+fn open_private(file: &Path) -> Result<fs::File> {
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let out = options.open(file)?;
+    // A file created before history was private is tightened too
+    #[cfg(unix)]
+    out.set_permissions(fs::Permissions::from_mode(0o600))?;
+    Ok(out)
+}
+
+/// All entries of a history file, oldest first
+///
+/// A missing file is an empty history; unreadable lines are skipped.
+pub fn read(file: &Path) -> Result<Vec<Entry>> {
+    let Ok(handle) = fs::File::open(file) else {
+        return Ok(Vec::new());
+    };
+    let mut entries = Vec::new();
+    for line in BufReader::new(handle).lines() {
+        if let Ok(entry) = serde_json::from_str(&line?) {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+/// The command line as typed, with secret flag values masked
+fn command_line(args: &[String]) -> String {
+    let mut words = vec!["reprise".to_string()];
+    let mut mask_next = false;
+    for arg in args.iter().skip(1) {
+        let secret_flag = SECRET_FLAGS.iter().find(|flag| arg.starts_with(&format!("{}=", flag)));
+        if mask_next {
+            words.push("****".to_string());
+        } else if let Some(flag) = secret_flag {
+            words.push(format!("{}=****", flag));
+        } else if arg.contains(char::is_whitespace) || arg.is_empty() {
+            words.push(format!("'{}'", arg.replace('\'', r"'\''")));
+        } else {
+            words.push(arg.clone());
+        }
+        mask_next = !mask_next && SECRET_FLAGS.contains(&arg.as_str());
+    }
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(action: &str) -> Entry {
+        Entry {
+            time: Utc::now(),
+            action: action.to_string(),
+            command: format!("reprise {} abc123", action),
+            app_slug: "app".to_string(),
+            slug: Some("abc123".to_string()),
+            result: "ok".to_string(),
+            error: None,
+            user: Some("ci".to_string()),
+            detail: None,
+        }
+    }
+
+    #[test]
+    fn test_append_and_read() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join(FILE_NAME);
+        assert!(read(&file).unwrap().is_empty());
+
+        append(&file, &entry("abort")).unwrap();
+        append(&file, &entry("rebuild")).unwrap();
+        let mut contents = fs::read_to_string(&file).unwrap();
+        contents.push_str("not json\n");
+        fs::write(&file, contents).unwrap();
+
+        let entries = read(&file).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].action, "abort");
+        assert_eq!(entries[1].action, "rebuild");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_append_keeps_file_private() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join(FILE_NAME);
+        fs::write(&file, "").unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o644)).unwrap();
+
+        append(&file, &entry("abort")).unwrap();
+        let mode = fs::metadata(&file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_command_line_masks_token() {
+        let args: Vec<String> = [
+            "/usr/bin/reprise",
+            "--token",
+            "secret",
+            "abort",
+            "abc",
+            "--reason",
+            "flaky test",
+            "--token=secret",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        assert_eq!(
+            command_line(&args),
+            "reprise --token **** abort abc --reason 'flaky test' --token=****"
+        );
+    }
+}
@@ -0,0 +1,194 @@
+//! Local commands run when a watched build finishes
+//!
+//! `[hooks]` in the config maps build outcomes to shell commands, e.g.
+//! `on_build_success = "./deploy.sh {slug} {status}"`. Placeholders are
+//! replaced with shell-quoted build values, and the same values are passed
+//! as `REPRISE_*` environment variables (see [`hook_vars`]). The CLI's
+//! polling loops call [`build_finished`] once a build stops running (through
+//! [`crate::watch::WatchOptions::on_build_finished`] when they use the watch
+//! engine), which also posts the build to `notify.webhook_url` and
+//! `notify.ntfy_topic` (see [`crate::webhook`] and [`crate::ntfy`]).
+
+use std::io;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+use crate::bitrise::Build;
+use crate::config::{Config, HooksConfig};
+
+static HOOKS: OnceLock<HooksConfig> = OnceLock::new();
+
+/// Apply the `[hooks]` config to all builds finished in this process
+pub fn configure(config: &Config) {
+    let _ = HOOKS.set(config.hooks.clone());
+}
+
+/// Run the configured hooks for a finished build's outcome, then post it to
+/// the webhook and ntfy
+///
+/// Hooks run one after another with their output sent to stderr, so
+/// reprise's own stdout stays parseable. A hook that can't start or exits
+/// non-zero is reported as a warning and never fails the command.
+pub fn build_finished(app_slug: &str, build: &Build) {
+    if let Some(hooks) = HOOKS.get() {
+        for (event, command) in hooks.for_status(build.status) {
+            if let Err(e) = run(event, command, app_slug, build) {
+                eprintln!("warning: {} hook failed: {}", event, e);
+            }
+        }
+    }
+    crate::webhook::build_finished(app_slug, build);
+    crate::ntfy::build_finished(app_slug, build);
+}
+
+/// Run one hook command through the shell and wait for it
+fn run(event: &str, command: &str, app_slug: &str, build: &Build) -> Result<(), String> {
+    let vars = hook_vars(event, app_slug, build);
+    let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+    let status = Command::new(shell)
+        .arg(flag)
+        .arg(expand(command, &vars))
+        .envs(vars.iter().map(|(name, _, value)| (*name, value)))
+        .stdin(Stdio::null())
+        .stdout(io::stderr())
+        .status()
+        .map_err(|e| format!("'{}' could not be started: {}", command, e))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("'{}' exited with {}", command, status))
+    }
+}
+
+/// Build values available to hooks, as (environment variable, placeholder, value)
+pub fn hook_vars(
+    event: &str,
+    app_slug: &str,
+    build: &Build,
+) -> Vec<(&'static str, &'static str, String)> {
+    vec![
+        ("REPRISE_HOOK_EVENT", "event", event.to_string()),
+        ("REPRISE_APP_SLUG", "app", app_slug.to_string()),
+        ("REPRISE_BUILD_SLUG", "slug", build.slug.clone()),
+        ("REPRISE_BUILD_NUMBER", "number", build.build_number.to_string()),
+        ("REPRISE_BUILD_STATUS", "status", build.status_display().to_string()),
+        ("REPRISE_BUILD_BRANCH", "branch", build.branch.clone()),
+        ("REPRISE_BUILD_WORKFLOW", "workflow", build.triggered_workflow.clone()),
+        ("REPRISE_BUILD_COMMIT", "commit", build.commit_hash.clone().unwrap_or_default()),
+        (
+            "REPRISE_BUILD_DURATION",
+            "duration",
+            build.duration().map(|d| d.num_seconds().to_string()).unwrap_or_default(),
+        ),
+        (
+            "REPRISE_BUILD_URL",
+            "url",
+            format!("https://app.bitrise.io/build/{}", build.slug),
+        ),
+    ]
+}
+
+/// Replace `{placeholder}`s with shell-quoted values; unknown ones are kept
+///
+/// The command is scanned once, so a value that itself contains
+/// `{placeholder}` is never expanded again.
+fn expand(command: &str, vars: &[(&str, &str, String)]) -> String {
+    let mut expanded = String::with_capacity(command.len());
+    let mut rest = command;
+    while let Some(open) = rest.find('{') {
+        expanded.push_str(&rest[..open]);
+        rest = &rest[open..];
+        let value = rest.find('}').and_then(|close| {
+            let name = &rest[1..close];
+            let value = vars.iter().find(|(_, n, _)| *n == name)?;
+            Some((close, &value.2))
+        });
+        match value {
+            Some((close, value)) => {
+                expanded.push_str(&shell_quote(value));
+                rest = &rest[close + 1..];
+            }
+            None => {
+                expanded.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Quote a value so the shell passes it through as one literal argument
+///
+/// Branch names and commit messages come from whoever pushed, so they must
+/// never be interpreted by the shell.
+fn shell_quote(value: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else if !value.is_empty()
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:@+=".contains(c))
+    {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitrise::BuildStatus;
+
+    fn build(status: i32, branch: &str) -> Build {
+        Build {
+            status: BuildStatus::from_code(status),
+            branch: branch.to_string(),
+            ..Build::fixture()
+        }
+    }
+
+    #[test]
+    fn test_expand_placeholders() {
+        let vars = hook_vars("on_build_success", "demo-app", &build(1, "main"));
+        let command = expand("./notify.sh {slug} {status} #{number} {duration}s {other}", &vars);
+        assert_eq!(command, "./notify.sh build-1 success #42 90s {other}");
+    }
+
+    #[test]
+    fn test_expand_quotes_untrusted_values() {
+        let vars = hook_vars("on_build_failure", "demo-app", &build(2, "x;rm -rf ~'"));
+        let command = expand("echo {branch}", &vars);
+        assert_eq!(command, r#"echo 'x;rm -rf ~'\'''"#);
+    }
+
+    #[test]
+    fn test_expand_does_not_reexpand_values() {
+        let vars = hook_vars("on_build_success", "demo-app", &build(1, "{status}"));
+        assert_eq!(expand("echo {branch} {status}", &vars), "echo '{status}' success");
+        assert_eq!(expand("echo {{slug}} {", &vars), "echo {build-1} {");
+    }
+
+    #[test]
+    fn test_expand_empty_value() {
+        let vars = hook_vars("on_build_finish", "demo-app", &build(3, "main"));
+        assert_eq!(expand("echo {commit}", &vars), "echo ''");
+    }
+
+    #[test]
+    fn test_hook_vars_env_names() {
+        let vars = hook_vars("on_build_success", "demo-app", &build(1, "main"));
+        let get = |name: &str| vars.iter().find(|(n, _, _)| *n == name).map(|v| v.2.as_str());
+        assert_eq!(get("REPRISE_BUILD_STATUS"), Some("success"));
+        assert_eq!(get("REPRISE_APP_SLUG"), Some("demo-app"));
+        assert_eq!(get("REPRISE_BUILD_URL"), Some("https://app.bitrise.io/build/build-1"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_reports_failure() {
+        let build = build(1, "main");
+        assert!(run("on_build_success", "true", "demo-app", &build).is_ok());
+        assert!(run("on_build_success", "exit 3", "demo-app", &build).is_err());
+    }
+}
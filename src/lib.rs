@@ -1,7 +1,54 @@
+//! reprise - a fast, ergonomic client for Bitrise CI
+//!
+//! Besides the `reprise` binary, this crate can be used as a library. The
+//! API client, types, configuration and polling engine are always
+//! available; the command-line layer (`cli`, `output`, `notify`, plus the
+//! `hooks`, `webhook`, `ntfy`, `history` and `update` modules, which read
+//! process-wide settings and print to the terminal) sits behind the default
+//! `cli` feature and can be disabled with `default-features = false`.
+//! Library users react to finished builds through
+//! [`WatchOptions::on_build_finished`](watch::WatchOptions::on_build_finished).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use reprise::bitrise::{BitriseClient, BuildStatus};
+//!
+//! # fn main() -> reprise::error::Result<()> {
+//! let client = BitriseClient::with_token("my-token")?;
+//! let builds = client.list_builds("app-slug", Some(BuildStatus::Failed), Some("main"), None, 10)?;
+//! for build in builds.data {
+//!     println!("#{} {}", build.build_number, build.status_display());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
 pub mod bitrise;
+pub mod cache;
+#[cfg(feature = "cli")]
 pub mod cli;
 pub mod config;
+pub mod coverage;
+pub mod daemon;
 pub mod duration;
 pub mod error;
+pub mod eta;
+pub mod git;
+#[cfg(feature = "cli")]
+pub mod history;
+#[cfg(feature = "cli")]
+pub mod hooks;
+pub mod logparse;
+#[cfg(feature = "cli")]
+pub mod ntfy;
+pub mod otel;
+#[cfg(feature = "cli")]
 pub mod notify;
+#[cfg(feature = "cli")]
 pub mod output;
+#[cfg(feature = "cli")]
+pub mod update;
+pub mod watch;
+#[cfg(feature = "cli")]
+pub mod webhook;
@@ -0,0 +1,280 @@
+//! Compiler and test diagnostics as code review annotations
+//!
+//! Diagnostics with a file and line are picked out of a log:
+//!
+//! ```text
+//! /Users/vagrant/git/App/Feed.swift:12:5: error: cannot find 'x' in scope
+//! /Users/vagrant/git/AppTests/FeedTests.swift:40: error: -[FeedTests testLoad] : failed
+//! e: file:///bitrise/src/app/src/main/Feed.kt:12:5 Unresolved reference: x
+//! ```
+//!
+//! and rendered as GitHub Actions workflow commands or a SARIF 2.1.0 log.
+
+use std::collections::HashSet;
+use std::io::{self, BufRead};
+use std::sync::OnceLock;
+
+use regex::{Captures, Regex};
+use serde::Serialize;
+
+use super::steps::{strip_sgr, StepTracker};
+
+/// Where Bitrise checks out sources; stripped so paths are repository-relative
+pub const DEFAULT_SOURCE_ROOTS: &[&str] = &["/Users/vagrant/git/", "/bitrise/src/"];
+
+/// Severity of a diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Error,
+    Warning,
+}
+
+/// A compiler or test diagnostic at a source location
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Diagnostic {
+    pub level: Level,
+    pub file: String,
+    pub line: u32,
+    pub column: Option<u32>,
+    pub message: String,
+    /// The step that printed it, e.g. `xcode-test@5`
+    pub step: Option<String>,
+}
+
+struct Patterns {
+    /// clang, swiftc, XCTest: `file:line[:col]: error: message`
+    clang: Regex,
+    /// Kotlin compiler: `e: file:///path:line:col message`
+    kotlin: Regex,
+}
+
+fn compile() -> Result<Patterns, regex::Error> {
+    Ok(Patterns {
+        clang: Regex::new(concat!(
+            r"^(?P<file>[^\s:][^:]*):(?P<line>\d+):(?:(?P<col>\d+):)?",
+            r"\s*(?P<level>fatal error|error|warning):\s*(?P<msg>.+)$",
+        ))?,
+        kotlin: Regex::new(concat!(
+            r"^(?P<level>[ew]): (?:file://)?(?P<file>[^\s:][^:]*):(?P<line>\d+):(?:(?P<col>\d+))?",
+            r"\s+(?P<msg>.+)$",
+        ))?,
+    })
+}
+
+/// The compiled patterns; `None` only if they fail to compile, in which
+/// case nothing is found
+fn patterns() -> Option<&'static Patterns> {
+    static PATTERNS: OnceLock<Option<Patterns>> = OnceLock::new();
+    PATTERNS.get_or_init(|| compile().ok()).as_ref()
+}
+
+/// The diagnostic on a log line, if any, with `roots` stripped from its path
+pub fn parse_line(line: &str, roots: &[&str]) -> Option<Diagnostic> {
+    let p = patterns()?;
+    let line = strip_sgr(line);
+    let line = line.trim();
+    let caps = p.clang.captures(line).or_else(|| p.kotlin.captures(line))?;
+    let level = match &caps["level"] {
+        "warning" | "w" => Level::Warning,
+        _ => Level::Error,
+    };
+    let number =
+        |caps: &Captures, name: &str| caps.name(name).and_then(|m| m.as_str().parse().ok());
+    let file = &caps["file"];
+    let file = roots.iter().find_map(|root| file.strip_prefix(root)).unwrap_or(file);
+    Some(Diagnostic {
+        level,
+        file: file.to_string(),
+        line: number(&caps, "line")?,
+        column: number(&caps, "col"),
+        message: caps["msg"].trim().to_string(),
+        step: None,
+    })
+}
+
+/// Diagnostics of a log in order, without repeats
+pub fn diagnostics(log: impl BufRead, roots: &[&str]) -> io::Result<Vec<Diagnostic>> {
+    let mut tracker = StepTracker::new();
+    let mut seen = HashSet::new();
+    let mut found = Vec::new();
+    for line in log.split(b'\n') {
+        let line = String::from_utf8_lossy(&line?).into_owned();
+        if tracker.feed(&line) {
+            continue;
+        }
+        if let Some(mut diagnostic) = parse_line(&line, roots) {
+            diagnostic.step = tracker.current().map(str::to_string);
+            let key = (
+                diagnostic.file.clone(),
+                diagnostic.line,
+                diagnostic.column,
+                diagnostic.message.clone(),
+            );
+            if seen.insert(key) {
+                found.push(diagnostic);
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// GitHub Actions workflow commands, one `::error file=..::message` per line
+pub fn github_commands(diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(|d| {
+            let mut properties = vec![
+                format!("file={}", escape_property(&d.file)),
+                format!("line={}", d.line),
+            ];
+            if let Some(column) = d.column {
+                properties.push(format!("col={}", column));
+            }
+            if let Some(ref step) = d.step {
+                properties.push(format!("title={}", escape_property(step)));
+            }
+            let command = match d.level {
+                Level::Error => "error",
+                Level::Warning => "warning",
+            };
+            format!("::{} {}::{}", command, properties.join(","), escape_data(&d.message))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A SARIF 2.1.0 log with one result per diagnostic
+pub fn sarif(diagnostics: &[Diagnostic]) -> serde_json::Value {
+    let results: Vec<serde_json::Value> = diagnostics
+        .iter()
+        .map(|d| {
+            let mut region = serde_json::json!({ "startLine": d.line });
+            if let Some(column) = d.column {
+                region["startColumn"] = column.into();
+            }
+            let mut result = serde_json::json!({
+                "ruleId": match d.level {
+                    Level::Error => "build-error",
+                    Level::Warning => "build-warning",
+                },
+                "level": d.level,
+                "message": { "text": d.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": d.file },
+                        "region": region,
+                    }
+                }],
+            });
+            if let Some(ref step) = d.step {
+                result["properties"] = serde_json::json!({ "step": step });
+            }
+            result
+        })
+        .collect();
+    serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "reprise",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/dan-hart/reprise",
+                }
+            },
+            "results": results,
+        }],
+    })
+}
+
+/// Escape a workflow command message
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Escape a workflow command property value
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns_compile() {
+        assert!(compile().is_ok());
+    }
+
+    #[test]
+    fn test_parse_clang_and_xctest() {
+        let d = parse_line(
+            "/Users/vagrant/git/App/Feed.swift:12:5: error: cannot find 'x' in scope",
+            DEFAULT_SOURCE_ROOTS,
+        )
+        .unwrap();
+        assert_eq!(d.file, "App/Feed.swift");
+        assert_eq!((d.line, d.column, d.level), (12, Some(5), Level::Error));
+        assert_eq!(d.message, "cannot find 'x' in scope");
+
+        let d = parse_line(
+            "/tmp/AppTests/FeedTests.swift:40: error: -[FeedTests testLoad] : XCTAssertTrue failed",
+            DEFAULT_SOURCE_ROOTS,
+        )
+        .unwrap();
+        assert_eq!(d.file, "/tmp/AppTests/FeedTests.swift");
+        assert_eq!((d.line, d.column), (40, None));
+
+        let d = parse_line("Feed.m:3:1: warning: unused variable 'y'", &[]).unwrap();
+        assert_eq!(d.level, Level::Warning);
+    }
+
+    #[test]
+    fn test_parse_kotlin() {
+        let d = parse_line(
+            "e: file:///bitrise/src/app/src/main/Feed.kt:12:5 Unresolved reference: x",
+            DEFAULT_SOURCE_ROOTS,
+        )
+        .unwrap();
+        assert_eq!(d.file, "app/src/main/Feed.kt");
+        assert_eq!((d.line, d.column), (12, Some(5)));
+        assert_eq!(d.message, "Unresolved reference: x");
+    }
+
+    #[test]
+    fn test_parse_ignores_other_lines() {
+        assert_eq!(parse_line("error: exit status 65", &[]), None);
+        assert_eq!(parse_line("Compiling Feed.swift", &[]), None);
+    }
+
+    #[test]
+    fn test_diagnostics_dedupe_and_track_steps() {
+        let log = "| (2) xcode-test@5 |\n\
+                   a.swift:1:1: error: boom\n\
+                   a.swift:1:1: error: boom\n";
+        let found = diagnostics(log.as_bytes(), &[]).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].step.as_deref(), Some("xcode-test@5"));
+    }
+
+    #[test]
+    fn test_github_commands_escape() {
+        let d = parse_line("a,b.swift:1:2: error: 100% broken", &[]).unwrap();
+        assert_eq!(github_commands(&[d]), "::error file=a%2Cb.swift,line=1,col=2::100%25 broken");
+    }
+
+    #[test]
+    fn test_sarif_result() {
+        let d = parse_line("a.swift:7: warning: deprecated", &[]).unwrap();
+        let log = sarif(&[d]);
+        let result = &log["runs"][0]["results"][0];
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(result["level"], "warning");
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "a.swift");
+        assert_eq!(location["region"]["startLine"], 7);
+        assert!(location["region"].get("startColumn").is_none());
+    }
+}
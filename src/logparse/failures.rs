@@ -0,0 +1,144 @@
+//! Error signatures
+//!
+//! Error lines that differ only in paths, line numbers, addresses or IDs
+//! describe the same failure. [`signature`] normalizes those parts away so
+//! failures can be counted across builds.
+
+use std::collections::HashSet;
+use std::io::{self, BufRead};
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use super::steps::{strip_sgr, StepTracker};
+
+/// Longest signature kept; the rest of a line rarely tells failures apart
+const MAX_SIGNATURE_CHARS: usize = 200;
+
+/// An error line of a log, with the step that printed it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorLine {
+    /// The step running when the line was printed, e.g. `xcode-test@5`
+    pub step: Option<String>,
+    /// The line, without color codes or surrounding whitespace
+    pub line: String,
+    pub signature: String,
+}
+
+struct Patterns {
+    error: Regex,
+    path: Regex,
+    uuid: Regex,
+    hex: Regex,
+    number: Regex,
+    space: Regex,
+}
+
+fn compile() -> Result<Patterns, regex::Error> {
+    Ok(Patterns {
+        // Same as the red highlight rules, without the generic "failed"
+        error: Regex::new(r"(?i)\b(error|fatal|exception|panic(ked)?)\b|^E |(?i)\[error\]")?,
+        path: Regex::new(r"(?:[\w.@+~-]*/)+([\w.@+-]+)")?,
+        uuid: Regex::new(
+            r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b",
+        )?,
+        hex: Regex::new(r"\b0x[0-9a-fA-F]+\b|\b[0-9a-f]{7,}\b")?,
+        number: Regex::new(r"\d+")?,
+        space: Regex::new(r"\s+")?,
+    })
+}
+
+/// The compiled patterns; `None` only if they fail to compile, in which
+/// case no line counts as an error
+fn patterns() -> Option<&'static Patterns> {
+    static PATTERNS: OnceLock<Option<Patterns>> = OnceLock::new();
+    PATTERNS.get_or_init(|| compile().ok()).as_ref()
+}
+
+/// Whether a log line reports an error
+pub fn is_error_line(line: &str) -> bool {
+    patterns().is_some_and(|p| p.error.is_match(line))
+}
+
+/// The line with paths cut to file names and IDs, addresses and numbers
+/// replaced by placeholders
+pub fn signature(line: &str) -> String {
+    let line = strip_sgr(line);
+    let Some(p) = patterns() else {
+        return line.trim().to_string();
+    };
+    let line = p.path.replace_all(line.trim(), "$1");
+    let line = p.uuid.replace_all(&line, "<id>");
+    let line = p.hex.replace_all(&line, "<hex>");
+    let line = p.number.replace_all(&line, "N");
+    let line = p.space.replace_all(&line, " ");
+    line.chars().take(MAX_SIGNATURE_CHARS).collect()
+}
+
+/// Error lines of a log, the first of each signature only
+pub fn error_lines(log: impl BufRead) -> io::Result<Vec<ErrorLine>> {
+    let mut tracker = StepTracker::new();
+    let mut seen = HashSet::new();
+    let mut found = Vec::new();
+    for line in log.split(b'\n') {
+        let line = String::from_utf8_lossy(&line?).into_owned();
+        if tracker.feed(&line) || !is_error_line(&line) {
+            continue;
+        }
+        let signature = signature(&line);
+        if seen.insert(signature.clone()) {
+            found.push(ErrorLine {
+                step: tracker.current().map(str::to_string),
+                line: strip_sgr(&line).trim().to_string(),
+                signature,
+            });
+        }
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns_compile() {
+        assert!(compile().is_ok());
+    }
+
+    #[test]
+    fn test_signature_normalizes_paths_and_numbers() {
+        let a = "/Users/vagrant/git/App/Feed/Feed.swift:12:5: error: cannot find 'x' in scope";
+        let b = "/tmp/build/App/Feed/Feed.swift:40:11: error: cannot find 'x' in scope";
+        assert_eq!(signature(a), "Feed.swift:N:N: error: cannot find 'x' in scope");
+        assert_eq!(signature(a), signature(b));
+        assert_eq!(
+            signature("\x1b[31mFatal error at 0x7ffee4b2 in 3f2c1d9e8a\x1b[0m"),
+            "Fatal error at <hex> in <hex>"
+        );
+    }
+
+    #[test]
+    fn test_is_error_line() {
+        assert!(is_error_line("error: build input file cannot be found"));
+        assert!(is_error_line("E/AndroidRuntime: FATAL EXCEPTION: main"));
+        assert!(!is_error_line("Compiling error_handler.swift"));
+        assert!(!is_error_line("Executed 42 tests, with 0 errors"));
+        assert!(!is_error_line("Step failed"));
+    }
+
+    #[test]
+    fn test_error_lines_tracks_steps_and_dedupes() {
+        let log = "| (1) git-clone@8                                    |\n\
+                   Cloning...\n\
+                   | (2) xcode-test@5                                   |\n\
+                   a.swift:1:1: error: boom\n\
+                   a.swift:9:9: error: boom\n\
+                   error: tests failed\n";
+        let lines = error_lines(log.as_bytes()).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].step.as_deref(), Some("xcode-test@5"));
+        assert_eq!(lines[0].line, "a.swift:1:1: error: boom");
+        assert_eq!(lines[1].signature, "error: tests failed");
+    }
+}
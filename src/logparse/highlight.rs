@@ -0,0 +1,216 @@
+//! Log line highlighting rules
+//!
+//! Each rule pairs a regular expression with a color. Rules are compiled once
+//! and checked in order; the first match colors the whole line. User rules
+//! from `[[log.highlight]]` in the config run before the built-in rules, which
+//! can be turned off with `log.default_rules = false`.
+//!
+//! ```toml
+//! [[log.highlight]]
+//! pattern = "^\\s*xcodebuild: error"
+//! color = "red"
+//! ```
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{RepriseError, Result};
+
+/// Colors available to highlight rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HighlightColor {
+    Red,
+    Yellow,
+    Green,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Dimmed,
+}
+
+/// A single pattern -> color rule as written in the config file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighlightRule {
+    /// Regular expression matched against each log line
+    pub pattern: String,
+    /// Color applied to matching lines
+    pub color: HighlightColor,
+}
+
+impl HighlightRule {
+    pub fn new(pattern: &str, color: HighlightColor) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            color,
+        }
+    }
+}
+
+/// Built-in rules, tuned to avoid matching identifiers like `error_handler`
+/// or summaries like "0 errors"
+fn default_rules() -> Vec<HighlightRule> {
+    use HighlightColor::*;
+    vec![
+        HighlightRule::new(r"(?i)\b(error|fatal|exception|panic(ked)?)\b", Red),
+        HighlightRule::new(r"(?i)\b(failed|failure)\b", Red),
+        HighlightRule::new(r"^E |(?i)\[error\]", Red),
+        HighlightRule::new(r"(?i)\bwarn(ing)?\b|^W |(?i)\[warn\]", Yellow),
+        HighlightRule::new(r"(?i)\b(success(ful|fully)?|passed|completed)\b", Green),
+        HighlightRule::new(r"\[OK\]|BUILD SUCCESSFUL", Green),
+    ]
+}
+
+/// Precompiled set of highlight rules
+#[derive(Debug, Clone)]
+pub struct Highlighter {
+    rules: Vec<(Regex, HighlightColor)>,
+}
+
+impl Default for Highlighter {
+    fn default() -> Self {
+        Self::new(&[], true).unwrap_or(Self { rules: Vec::new() })
+    }
+}
+
+impl Highlighter {
+    /// Compile user rules, optionally followed by the built-in rules
+    pub fn new(rules: &[HighlightRule], include_defaults: bool) -> Result<Self> {
+        let defaults = if include_defaults {
+            default_rules()
+        } else {
+            Vec::new()
+        };
+
+        let compiled = rules
+            .iter()
+            .chain(defaults.iter())
+            .map(|rule| {
+                Regex::new(&rule.pattern)
+                    .map(|re| (re, rule.color))
+                    .map_err(|e| {
+                        RepriseError::Config(format!(
+                            "Invalid log highlight pattern '{}': {}",
+                            rule.pattern, e
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { rules: compiled })
+    }
+
+    /// Color of the first rule matching the line, if any
+    pub fn classify(&self, line: &str) -> Option<HighlightColor> {
+        self.rules
+            .iter()
+            .find(|(re, _)| re.is_match(line))
+            .map(|(_, color)| *color)
+    }
+
+    /// Apply highlighting to a single line
+    #[cfg(feature = "cli")]
+    pub fn highlight_line(&self, line: &str) -> String {
+        use colored::Colorize;
+
+        match self.classify(line) {
+            Some(HighlightColor::Red) => line.red().to_string(),
+            Some(HighlightColor::Yellow) => line.yellow().to_string(),
+            Some(HighlightColor::Green) => line.green().to_string(),
+            Some(HighlightColor::Blue) => line.blue().to_string(),
+            Some(HighlightColor::Magenta) => line.magenta().to_string(),
+            Some(HighlightColor::Cyan) => line.cyan().to_string(),
+            Some(HighlightColor::White) => line.white().to_string(),
+            Some(HighlightColor::Dimmed) => line.dimmed().to_string(),
+            None => line.to_string(),
+        }
+    }
+
+    /// Apply highlighting to full log content
+    #[cfg(feature = "cli")]
+    pub fn highlight(&self, content: &str) -> String {
+        content
+            .lines()
+            .map(|line| self.highlight_line(line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_rules_compile() {
+        let highlighter = Highlighter::new(&[], true).unwrap();
+        assert_eq!(highlighter.rules.len(), default_rules().len());
+    }
+
+    #[test]
+    fn test_classify_errors() {
+        let h = Highlighter::default();
+        assert_eq!(h.classify("error: build input file cannot be found"), Some(HighlightColor::Red));
+        assert_eq!(h.classify("** BUILD FAILED **"), Some(HighlightColor::Red));
+        assert_eq!(h.classify("[ERROR] Step failed"), Some(HighlightColor::Red));
+        assert_eq!(h.classify("E Unable to locate device"), Some(HighlightColor::Red));
+    }
+
+    #[test]
+    fn test_classify_avoids_identifier_false_positives() {
+        let h = Highlighter::default();
+        assert_eq!(h.classify("Compiling error_handler.swift"), None);
+        assert_eq!(h.classify("Executed 42 tests, with 0 errors"), None);
+        assert_eq!(h.classify("import ErrorKit"), None);
+    }
+
+    #[test]
+    fn test_classify_warnings_and_success() {
+        let h = Highlighter::default();
+        assert_eq!(h.classify("warning: unused variable"), Some(HighlightColor::Yellow));
+        assert_eq!(h.classify("[WARN] deprecated"), Some(HighlightColor::Yellow));
+        assert_eq!(h.classify("BUILD SUCCESSFUL in 2m"), Some(HighlightColor::Green));
+        assert_eq!(h.classify("All tests passed"), Some(HighlightColor::Green));
+        assert_eq!(h.classify("Cloning repository"), None);
+    }
+
+    #[test]
+    fn test_user_rules_take_precedence() {
+        let rules = vec![HighlightRule::new("Retrying", HighlightColor::Cyan)];
+        let h = Highlighter::new(&rules, true).unwrap();
+        assert_eq!(h.classify("Retrying after error"), Some(HighlightColor::Cyan));
+        assert_eq!(h.classify("error: oops"), Some(HighlightColor::Red));
+    }
+
+    #[test]
+    fn test_without_default_rules() {
+        let rules = vec![HighlightRule::new("^xcodebuild: error", HighlightColor::Magenta)];
+        let h = Highlighter::new(&rules, false).unwrap();
+        assert_eq!(h.classify("xcodebuild: error: scheme missing"), Some(HighlightColor::Magenta));
+        assert_eq!(h.classify("error: something else"), None);
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_config_error() {
+        let rules = vec![HighlightRule::new("(unclosed", HighlightColor::Red)];
+        let err = Highlighter::new(&rules, true).unwrap_err();
+        assert!(matches!(err, RepriseError::Config(_)));
+        assert!(err.to_string().contains("(unclosed"));
+    }
+
+    #[test]
+    fn test_rule_deserialize_from_toml() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            highlight: Vec<HighlightRule>,
+        }
+        let toml = r#"
+            [[highlight]]
+            pattern = "timeout"
+            color = "magenta"
+        "#;
+        let wrapper: Wrapper = toml::from_str(toml).unwrap();
+        assert_eq!(wrapper.highlight[0].color, HighlightColor::Magenta);
+    }
+}
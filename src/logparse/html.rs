@@ -0,0 +1,351 @@
+//! Standalone HTML rendering of a build log
+//!
+//! Turns a raw log into one self-contained page for people who don't use
+//! the CLI: the Bitrise CLI's ANSI colors become CSS classes, lines without
+//! colors get the [`Highlighter`]'s, and every step is a collapsible
+//! `<details>` block, left open when the step failed. The log is streamed:
+//! only the step being rendered is held in memory.
+
+use std::io::{self, BufRead, Write};
+
+use super::steps::{box_row, parse_marker, parse_summary, strip_sgr, StepMarker, StepOutcome};
+use super::{HighlightColor, Highlighter};
+
+const STYLE: &str = "\
+body { background: #1e1e1e; color: #d4d4d4; font-family: sans-serif; margin: 1.5em; }
+h1 { font-size: 1.2em; }
+pre { font-family: ui-monospace, Menlo, Consolas, monospace; font-size: 0.85em; margin: 0; \
+white-space: pre-wrap; }
+details { border: 1px solid #3c3c3c; border-radius: 4px; margin: 0.4em 0; padding: 0 0.6em; }
+summary { cursor: pointer; padding: 0.4em 0; font-family: ui-monospace, Menlo, monospace; }
+details.failed { border-color: #f14c4c; }
+details.failed > summary { color: #f14c4c; }
+details.success > summary { color: #23d18b; }
+details.skipped > summary, details.failed_skippable > summary { color: #cca700; }
+.b { font-weight: bold; } .d { opacity: 0.6; }
+.c30, .c90 { color: #808080; } .c31, .c91 { color: #f14c4c; } .c32, .c92 { color: #23d18b; }
+.c33, .c93 { color: #e5e510; } .c34, .c94 { color: #3b8eea; } .c35, .c95 { color: #d670d6; }
+.c36, .c96 { color: #29b8db; } .c37, .c97 { color: #e5e5e5; }
+";
+
+/// What a rendered log contained
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HtmlStats {
+    pub lines: usize,
+    pub steps: usize,
+    pub failed_steps: usize,
+}
+
+/// Text style carried from one SGR code to the next, across lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Style {
+    color: Option<u8>,
+    bold: bool,
+    dim: bool,
+}
+
+impl Style {
+    /// Apply the parameters of one `ESC [ ... m` sequence
+    fn apply(&mut self, params: &str) {
+        let mut codes = params.split(';').map(|p| p.parse::<u8>().unwrap_or(0));
+        while let Some(code) = codes.next() {
+            match code {
+                0 => *self = Self::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                22 => (self.bold, self.dim) = (false, false),
+                30..=37 | 90..=97 => self.color = Some(code),
+                39 => self.color = None,
+                // 256-color and RGB colors aren't mapped; skip their values
+                38 | 48 => match codes.next() {
+                    Some(5) => {
+                        codes.next();
+                    }
+                    Some(2) => {
+                        codes.by_ref().take(3).for_each(drop);
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+    }
+
+    /// CSS classes, empty for plain text
+    fn classes(self) -> String {
+        let mut classes = Vec::new();
+        if let Some(color) = self.color {
+            classes.push(format!("c{}", color));
+        }
+        if self.bold {
+            classes.push("b".to_string());
+        }
+        if self.dim {
+            classes.push("d".to_string());
+        }
+        classes.join(" ")
+    }
+}
+
+/// The step whose lines are being collected
+struct Section {
+    /// Position and title, or `None` for lines outside any step
+    step: Option<(usize, String)>,
+    body: String,
+    /// Mark and time from the step's summary row
+    summary: Option<(StepOutcome, String)>,
+}
+
+impl Section {
+    fn new(step: Option<(usize, String)>) -> Self {
+        Self { step, body: String::new(), summary: None }
+    }
+}
+
+/// Render a log as a complete HTML page titled `title`
+pub fn write_html(
+    log: impl BufRead,
+    out: &mut impl Write,
+    title: &str,
+    highlighter: &Highlighter,
+) -> io::Result<HtmlStats> {
+    writeln!(out, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">")?;
+    writeln!(out, "<title>{}</title>\n<style>\n{}</style>\n</head>", escape(title), STYLE)?;
+    writeln!(out, "<body>\n<h1>{}</h1>", escape(title))?;
+
+    let mut stats = HtmlStats::default();
+    let mut style = Style::default();
+    let mut section = Section::new(None);
+    // A box border is held until the next line shows which step it frames
+    let mut border: Option<String> = None;
+
+    for line in log.lines() {
+        let line = line?;
+        stats.lines += 1;
+        let plain = strip_sgr(&line);
+        let html = line_to_html(&line, &plain, &mut style, highlighter);
+
+        // A step ends with its summary row and the border under it
+        if section.summary.is_some() {
+            let closing = is_border(&plain);
+            if closing {
+                section.body.push_str(&html);
+            }
+            flush(out, &section, &mut stats)?;
+            section = Section::new(None);
+            if closing {
+                continue;
+            }
+        }
+        if is_border(&plain) {
+            if let Some(held) = border.replace(html) {
+                section.body.push_str(&held);
+            }
+            continue;
+        }
+        match parse_marker(&line) {
+            Some(StepMarker::Started { index, name }) => {
+                flush(out, &section, &mut stats)?;
+                section = Section::new(Some((index, name)));
+            }
+            Some(StepMarker::Finished { .. }) if section.step.is_some() => {
+                let inner = box_row(&plain).unwrap_or_default();
+                if let Some((mark, _, _)) = parse_summary(inner) {
+                    let time = inner.rsplit('|').next().unwrap_or_default().trim();
+                    section.summary = Some((StepOutcome::from_mark(mark), time.to_string()));
+                }
+            }
+            _ => {}
+        }
+        if let Some(held) = border.take() {
+            section.body.push_str(&held);
+        }
+        section.body.push_str(&html);
+    }
+    if let Some(held) = border {
+        section.body.push_str(&held);
+    }
+    flush(out, &section, &mut stats)?;
+    writeln!(out, "</body>\n</html>")?;
+    Ok(stats)
+}
+
+/// Write a finished section: a step as `<details>`, other lines as-is
+fn flush(out: &mut impl Write, section: &Section, stats: &mut HtmlStats) -> io::Result<()> {
+    let Some((index, ref name)) = section.step else {
+        if !section.body.is_empty() {
+            writeln!(out, "<pre>{}</pre>", section.body)?;
+        }
+        return Ok(());
+    };
+    stats.steps += 1;
+    let (outcome, time) = match section.summary {
+        Some((outcome, ref time)) => (outcome.as_str(), format!(" · {}", escape(time))),
+        None => ("running", String::new()),
+    };
+    let failed = outcome == StepOutcome::Failed.as_str();
+    if failed {
+        stats.failed_steps += 1;
+    }
+    writeln!(
+        out,
+        "<details class=\"{}\"{}>\n<summary>({}) {} · {}{}</summary>",
+        outcome,
+        if failed { " open" } else { "" },
+        index,
+        escape(name),
+        outcome.replace('_', " "),
+        time
+    )?;
+    writeln!(out, "<pre>{}</pre>\n</details>", section.body)
+}
+
+/// `+-----+` lines framing step headers and summary rows
+fn is_border(plain: &str) -> bool {
+    let plain = plain.trim();
+    plain.starts_with("+-") && plain.chars().all(|c| c == '+' || c == '-')
+}
+
+/// One line of HTML: ANSI colors as spans, or the highlighter's color for a
+/// line without any
+fn line_to_html(line: &str, plain: &str, style: &mut Style, highlighter: &Highlighter) -> String {
+    if !line.contains('\x1b') && *style == Style::default() {
+        return match highlighter.classify(plain) {
+            Some(color) => {
+                format!("<span class=\"{}\">{}</span>\n", css_class(color), escape(plain))
+            }
+            None => format!("{}\n", escape(plain)),
+        };
+    }
+
+    let mut html = String::new();
+    let mut open = open_span(&mut html, *style);
+    let mut chars = line.chars();
+    let mut text = String::new();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            text.push(c);
+            continue;
+        }
+        html.push_str(&escape(&text));
+        text.clear();
+        if chars.next() != Some('[') {
+            continue;
+        }
+        let mut params = String::new();
+        let mut last = None;
+        for c in chars.by_ref() {
+            if ('@'..='~').contains(&c) {
+                last = Some(c);
+                break;
+            }
+            params.push(c);
+        }
+        if last == Some('m') {
+            if open {
+                html.push_str("</span>");
+            }
+            style.apply(&params);
+            open = open_span(&mut html, *style);
+        }
+    }
+    html.push_str(&escape(&text));
+    if open {
+        html.push_str("</span>");
+    }
+    html.push('\n');
+    html
+}
+
+/// Start a span for a non-plain style; returns whether one was started
+fn open_span(html: &mut String, style: Style) -> bool {
+    let classes = style.classes();
+    if classes.is_empty() {
+        return false;
+    }
+    html.push_str(&format!("<span class=\"{}\">", classes));
+    true
+}
+
+fn css_class(color: HighlightColor) -> &'static str {
+    match color {
+        HighlightColor::Red => "c31",
+        HighlightColor::Yellow => "c33",
+        HighlightColor::Green => "c32",
+        HighlightColor::Blue => "c34",
+        HighlightColor::Magenta => "c35",
+        HighlightColor::Cyan => "c36",
+        HighlightColor::White => "c37",
+        HighlightColor::Dimmed => "d",
+    }
+}
+
+/// Escape text for use in HTML content and attribute values
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "\
+Cloning <repo>
++------------------------------------------------------------------------------+
+| (0) git-clone@8                                                              |
++------------------------------------------------------------------------------+
+Checked out main
++---+---------------------------------------------------------------+----------+
+| \x1b[32;1m✓\x1b[0m | \x1b[32;1mgit-clone@8\x1b[0m | 12.5 sec |
++---+---------------------------------------------------------------+----------+
++------------------------------------------------------------------------------+
+| (1) xcode-test@5                                                             |
++------------------------------------------------------------------------------+
+\x1b[31;1merror: no such module\x1b[0m
+Test Suite failed
++---+---------------------------------------------------------------+----------+
+| x | xcode-test@5                                                  | 3.12 min |
++---+---------------------------------------------------------------+----------+
+Build finished
+";
+
+    fn render(log: &str) -> (String, HtmlStats) {
+        let mut out = Vec::new();
+        let stats =
+            write_html(log.as_bytes(), &mut out, "Build #7", &Highlighter::default()).unwrap();
+        (String::from_utf8(out).unwrap(), stats)
+    }
+
+    #[test]
+    fn test_steps_become_collapsible_sections() {
+        let (html, stats) = render(LOG);
+        assert_eq!(stats, HtmlStats { lines: 17, steps: 2, failed_steps: 1 });
+        assert!(html.contains("<details class=\"success\">\n<summary>(0) git-clone@8 · success"));
+        assert!(html.contains("<summary>(0) git-clone@8 · success · 12.5 sec</summary>"));
+        assert!(html.contains("<details class=\"failed\" open>\n<summary>(1) xcode-test@5"));
+        // Each step keeps its whole box, and lines around steps stay outside
+        let clone = html.split("<details").nth(1).unwrap();
+        let borders = clone.lines().filter(|l| l.trim_start_matches("<pre>").starts_with("+-"));
+        assert_eq!(borders.count(), 4);
+        assert!(html.contains("<pre>Cloning &lt;repo&gt;\n</pre>"));
+        assert!(html.contains("<pre>Build finished\n</pre>\n</body>"));
+    }
+
+    #[test]
+    fn test_ansi_colors_become_spans() {
+        let mut style = Style::default();
+        let html = line_to_html(
+            "\x1b[31;1merror\x1b[0m: a < b",
+            "error: a < b",
+            &mut style,
+            &Highlighter::default(),
+        );
+        assert_eq!(html, "<span class=\"c31 b\">error</span>: a &lt; b\n");
+
+        // A color left open carries on to the next line
+        line_to_html("\x1b[33mwarn", "warn", &mut style, &Highlighter::default());
+        let plain = Highlighter::new(&[], false).unwrap();
+        let next = line_to_html("still yellow", "still yellow", &mut style, &plain);
+        assert_eq!(next, "<span class=\"c33\">still yellow</span>\n");
+    }
+}
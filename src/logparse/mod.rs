@@ -0,0 +1,13 @@
+//! Build log processing
+//!
+//! Helpers for working with raw Bitrise build logs, independent of how they
+//! are fetched or printed.
+
+pub mod annotations;
+pub mod failures;
+pub mod highlight;
+pub mod html;
+pub mod steps;
+
+pub use highlight::{HighlightColor, HighlightRule, Highlighter};
+pub use steps::StepTracker;
@@ -0,0 +1,326 @@
+//! Step boundary detection
+//!
+//! The Bitrise CLI frames every step in its log with a box header and closes
+//! it with a summary row:
+//!
+//! ```text
+//! +------------------------------------------------------------------------------+
+//! | (2) xcode-test@5                                                             |
+//! +------------------------------------------------------------------------------+
+//! ...
+//! +---+---------------------------------------------------------------+----------+
+//! | ✓ | xcode-test@5                                                  | 3.12 min |
+//! +---+---------------------------------------------------------------+----------+
+//! ```
+//!
+//! [`StepTracker`] watches lines go by and remembers which step is running;
+//! [`step_timings`] reads a whole log for how each step ended and how long
+//! it took.
+
+use std::io::{self, BufRead};
+use std::time::Duration;
+
+/// A step boundary found in a log line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepMarker {
+    /// A step header: its position in the workflow and title
+    Started { index: usize, name: String },
+    /// A step summary row: the step is done
+    Finished { name: String },
+}
+
+/// Recognize a step header or summary row
+pub fn parse_marker(line: &str) -> Option<StepMarker> {
+    let line = strip_sgr(line);
+    let inner = box_row(&line)?;
+
+    // Header: "(2) xcode-test@5"
+    if let Some(rest) = inner.strip_prefix('(') {
+        let (index, name) = rest.split_once(')')?;
+        let index = index.parse().ok()?;
+        let name = name.trim();
+        if name.is_empty() || name.contains('|') {
+            return None;
+        }
+        return Some(StepMarker::Started {
+            index,
+            name: name.to_string(),
+        });
+    }
+
+    parse_summary(inner).map(|(_, name, _)| StepMarker::Finished {
+        name: name.to_string(),
+    })
+}
+
+/// The contents of a `| ... |` box row
+pub(super) fn box_row(line: &str) -> Option<&str> {
+    Some(line.trim().strip_prefix('|')?.strip_suffix('|')?.trim())
+}
+
+/// Split a summary row's cells, e.g. `✓ | xcode-test@5 | 3.12 min`
+pub(super) fn parse_summary(inner: &str) -> Option<(char, &str, Duration)> {
+    let cells: Vec<&str> = inner.split('|').map(str::trim).collect();
+    let [status, name, time] = cells[..] else {
+        return None;
+    };
+    let mut chars = status.chars();
+    let (Some(status), None) = (chars.next(), chars.next()) else {
+        return None;
+    };
+    if name.is_empty() {
+        return None;
+    }
+    Some((status, name, parse_step_time(time)?))
+}
+
+/// Parse a summary row's time, e.g. `12.5 sec`, `3.12 min` or `1.1 hour`
+fn parse_step_time(time: &str) -> Option<Duration> {
+    let (value, unit) = time.split_once(' ')?;
+    let value: f64 = value.parse().ok()?;
+    let seconds = if unit.starts_with('s') {
+        value
+    } else if unit.starts_with('m') {
+        value * 60.0
+    } else if unit.starts_with('h') {
+        value * 3600.0
+    } else {
+        return None;
+    };
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+/// How a step ended, from the mark in its summary row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepOutcome {
+    /// `✓`
+    Success,
+    /// `x`
+    Failed,
+    /// `!`: failed, but the step is allowed to fail
+    FailedSkippable,
+    /// `-` or `➜`: not run (e.g. its `run_if` was false)
+    Skipped,
+    /// Any other mark
+    Unknown,
+}
+
+impl StepOutcome {
+    pub(super) fn from_mark(mark: char) -> Self {
+        match mark {
+            '✓' => Self::Success,
+            'x' | '✗' => Self::Failed,
+            '!' => Self::FailedSkippable,
+            '-' | '➜' => Self::Skipped,
+            _ => Self::Unknown,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Failed => "failed",
+            Self::FailedSkippable => "failed_skippable",
+            Self::Skipped => "skipped",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// A finished step, as reported by its summary row
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepTiming {
+    /// Position in the workflow
+    pub index: usize,
+    /// Title from the step's header, e.g. `xcode-test@5`
+    pub name: String,
+    pub outcome: StepOutcome,
+    pub duration: Duration,
+}
+
+/// Read a log for the steps that ran, in order, and how long each took
+///
+/// Only summary rows that close a step header count: the `bitrise summary`
+/// table at the end of the log repeats every row and is skipped.
+pub fn step_timings(log: impl BufRead) -> io::Result<Vec<StepTiming>> {
+    let mut timings = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+    for line in log.lines() {
+        let line = strip_sgr(&line?);
+        let Some(inner) = box_row(&line) else {
+            continue;
+        };
+        match parse_marker(&line) {
+            Some(StepMarker::Started { index, name }) => current = Some((index, name)),
+            Some(StepMarker::Finished { .. }) => {
+                let Some((index, name)) = current.take() else {
+                    continue;
+                };
+                if let Some((mark, _, duration)) = parse_summary(inner) {
+                    timings.push(StepTiming {
+                        index,
+                        name,
+                        outcome: StepOutcome::from_mark(mark),
+                        duration,
+                    });
+                }
+            }
+            None => {}
+        }
+    }
+    Ok(timings)
+}
+
+/// The step currently running, according to the lines seen so far
+#[derive(Debug, Clone, Default)]
+pub struct StepTracker {
+    current: Option<(usize, String)>,
+}
+
+impl StepTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update from a log line; returns true when the current step changed
+    pub fn feed(&mut self, line: &str) -> bool {
+        match parse_marker(line) {
+            Some(StepMarker::Started { index, name }) => {
+                self.current = Some((index, name));
+                true
+            }
+            Some(StepMarker::Finished { .. }) => self.current.take().is_some(),
+            None => false,
+        }
+    }
+
+    /// Name of the running step, e.g. `xcode-test@5`
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_ref().map(|(_, name)| name.as_str())
+    }
+
+    /// Zero-based position of the running step in the workflow
+    pub fn current_index(&self) -> Option<usize> {
+        self.current.as_ref().map(|(index, _)| *index)
+    }
+}
+
+/// Remove SGR color codes (`ESC [ ... m`) that the CLI wraps around titles
+pub(super) fn strip_sgr(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str =
+        "| (2) xcode-test@5                                                             |";
+    const SUMMARY: &str =
+        "| ✓ | xcode-test@5                                                  | 3.12 min |";
+
+    #[test]
+    fn test_parse_step_header() {
+        assert_eq!(
+            parse_marker(HEADER),
+            Some(StepMarker::Started {
+                index: 2,
+                name: "xcode-test@5".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_step_summary() {
+        assert_eq!(
+            parse_marker(SUMMARY),
+            Some(StepMarker::Finished {
+                name: "xcode-test@5".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_colored_summary() {
+        let line = "| \x1b[32;1m✓\x1b[0m | \x1b[32;1mgit-clone@8\x1b[0m | 12.5 sec |";
+        assert!(matches!(parse_marker(line), Some(StepMarker::Finished { .. })));
+    }
+
+    #[test]
+    fn test_parse_ignores_other_box_lines() {
+        assert_eq!(parse_marker("| id: xcode-test                 |"), None);
+        assert_eq!(parse_marker("| Total runtime: 4.2 min         |"), None);
+        assert_eq!(parse_marker("+----------------+"), None);
+        assert_eq!(parse_marker("Compiling main.swift"), None);
+    }
+
+    #[test]
+    fn test_parse_step_time() {
+        assert_eq!(parse_step_time("12.5 sec"), Some(Duration::from_millis(12_500)));
+        assert_eq!(parse_step_time("3 min"), Some(Duration::from_secs(180)));
+        assert_eq!(parse_step_time("1.5 hour"), Some(Duration::from_secs(5400)));
+        assert_eq!(parse_step_time("3.12"), None);
+        assert_eq!(parse_step_time("3 days"), None);
+    }
+
+    #[test]
+    fn test_step_timings_skip_final_summary() {
+        let log = "\
+| (0) git-clone@8                                                              |
+Cloning...
+| \x1b[32;1m✓\x1b[0m | git-clone@8                                           | 12.5 sec |
+| (1) xcode-test@5                                                             |
+| x | xcode-test@5 (exit code: 65)                                  | 3 min    |
+|                               bitrise summary                                |
+| ✓ | git-clone@8                                                   | 12.5 sec |
+| x | xcode-test@5 (exit code: 65)                                  | 3 min    |
+| Total runtime: 3.2 min                                                       |
+";
+        let timings = step_timings(log.as_bytes()).unwrap();
+        assert_eq!(
+            timings,
+            vec![
+                StepTiming {
+                    index: 0,
+                    name: "git-clone@8".to_string(),
+                    outcome: StepOutcome::Success,
+                    duration: Duration::from_millis(12_500),
+                },
+                StepTiming {
+                    index: 1,
+                    name: "xcode-test@5".to_string(),
+                    outcome: StepOutcome::Failed,
+                    duration: Duration::from_secs(180),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tracker_follows_steps() {
+        let mut tracker = StepTracker::new();
+        assert!(tracker.feed(HEADER));
+        assert_eq!(tracker.current(), Some("xcode-test@5"));
+        assert_eq!(tracker.current_index(), Some(2));
+
+        assert!(!tracker.feed("Testing..."));
+        assert_eq!(tracker.current(), Some("xcode-test@5"));
+
+        assert!(tracker.feed(SUMMARY));
+        assert_eq!(tracker.current(), None);
+        assert!(!tracker.feed(SUMMARY));
+    }
+}
@@ -1,29 +1,51 @@
-use clap::Parser;
-use colored::{control::set_override, Colorize};
-use is_terminal::IsTerminal;
+use std::io::IsTerminal;
+
+use colored::Colorize;
 
 use reprise::bitrise::BitriseClient;
-use reprise::cli::args::{AppCommands, Cli, Commands, CompletionsArgs};
+use reprise::cli::args::{
+    AppArgs, AppCommands, Cli, Commands, CompletionsArgs, ConfigArgs, ConfigCommands, JsonCompat,
+    OutputFormat, TriggersArgs,
+};
 use reprise::cli::commands;
 use reprise::config::Config;
 use reprise::error::RepriseError;
+use reprise::notify::Notice;
+use reprise::output::{json, pager, table, terminal};
 
 fn main() {
-    // Respect NO_COLOR environment variable (https://no-color.org/)
-    // Also disable colors when stdout is not a terminal (for piping)
-    if std::env::var("NO_COLOR").is_ok() || !std::io::stdout().is_terminal() {
-        set_override(false);
-    }
+    // Respect NO_COLOR and disable colors when piped
+    terminal::configure_colors();
 
     if let Err(e) = run() {
         eprintln!("{}: {}", "error".red().bold(), e);
+        if matches!(e, RepriseError::Toml(_)) {
+            eprintln!("hint: run 'reprise config lint' for details");
+        }
         std::process::exit(e.exit_code());
     }
 }
 
 fn run() -> Result<(), RepriseError> {
-    let cli = Cli::parse();
-    let format = cli.output;
+    let (cli, kind) = Cli::parse_with_kind();
+    let format = cli.output.unwrap_or_default();
+    if let Some(columns) = cli.width {
+        terminal::set_width(columns.into());
+    }
+    if cli.no_header {
+        table::hide_headers();
+    }
+    if cli.quiet {
+        terminal::set_quiet();
+    }
+    if cli.non_interactive {
+        terminal::set_non_interactive();
+    }
+
+    // JSON output goes out in the versioned envelope unless --json-compat legacy
+    if cli.json_compat.unwrap_or_default() == JsonCompat::V1 {
+        json::use_envelope(&kind);
+    }
 
     // Handle completions command early (no config or client needed)
     if let Commands::Completions(CompletionsArgs { shell }) = &cli.command {
@@ -31,6 +53,22 @@ fn run() -> Result<(), RepriseError> {
         return Ok(());
     }
 
+    // Schemas are static, so they need no config either
+    if let Commands::Schema(args) = &cli.command {
+        println!("{}", commands::schema(args)?);
+        return Ok(());
+    }
+
+    // Help and man pages come from the command definitions alone
+    if let Commands::Help(args) = &cli.command {
+        print!("{}", commands::help(args)?);
+        return Ok(());
+    }
+    if let Commands::Man(args) = &cli.command {
+        println!("{}", commands::man(args, format)?);
+        return Ok(());
+    }
+
     // Handle URL generation early (no config or client needed)
     if let Commands::Url(args) = &cli.command {
         if commands::is_generation_mode(args) {
@@ -42,21 +80,103 @@ fn run() -> Result<(), RepriseError> {
         }
     }
 
+    // Detached notification handler started by notify::build_completed
+    if let Commands::NotifyHandler(args) = &cli.command {
+        reprise::notify::handle_actions(&Notice {
+            summary: args.summary.clone(),
+            body: args.body.clone(),
+            icon: args.icon.clone(),
+            url: args.url.clone(),
+            rebuild: args.rebuild,
+        });
+        return Ok(());
+    }
+
+    // Completion candidates are read on every Tab press: local data only, no errors
+    if let Commands::Complete(args) = &cli.command {
+        print!("{}", commands::complete(args));
+        return Ok(());
+    }
+
+    // Prompt status must be fast and must never print errors into a shell prompt
+    if let Commands::PromptStatus(args) = &cli.command {
+        let output = commands::prompt_status(cli.token.as_deref(), args, format).unwrap_or_default();
+        if !output.is_empty() {
+            println!("{output}");
+        }
+        return Ok(());
+    }
+
+    // Lint reads the raw file, so it must run before the config is parsed
+    if let Commands::Config(ConfigArgs { command: ConfigCommands::Lint }) = &cli.command {
+        println!("{}", commands::config_lint(format)?);
+        return Ok(());
+    }
+
     // Load configuration
     let mut config = Config::load()?;
+    reprise::notify::configure(&config);
+    reprise::hooks::configure(&config);
+    reprise::webhook::configure(&config);
+    reprise::ntfy::configure(&config);
+    reprise::eta::configure(&config);
+    if let Ok(paths) = reprise::config::Paths::new() {
+        reprise::history::configure(&paths);
+    }
+    reprise::output::theme::configure(&config);
+    let format = cli
+        .output
+        .unwrap_or_else(|| OutputFormat::from_config(&config.output.format));
+    if !matches!(cli.command, Commands::Config(_)) {
+        commands::warn_config_issues();
+    }
+
+    // Set when a command's output must still be shown before it fails
+    let mut failure = None;
 
     // Handle commands that don't need the API client
     let output = match &cli.command {
-        Commands::Completions(_) => unreachable!(), // Handled above
+        Commands::Completions(_)
+        | Commands::Complete(_)
+        | Commands::Help(_)
+        | Commands::Man(_)
+        | Commands::PromptStatus(_)
+        | Commands::NotifyHandler(_)
+        | Commands::Schema(_) => {
+            unreachable!() // Handled above
+        }
         Commands::Config(args) => commands::config(&mut config, args, format)?,
+        Commands::Cache(args) => commands::cache(&config, args, format)?,
+        Commands::History(args) => commands::history(args, format)?,
 
         // app show doesn't need API client
         Commands::App(args) if matches!(args.command, None | Some(AppCommands::Show)) => {
             commands::app_show(&config, format)?
         }
+        Commands::App(AppArgs { command: Some(AppCommands::Unset) }) => {
+            commands::app_unset(&mut config, format)?
+        }
+        Commands::App(AppArgs { command: Some(AppCommands::Recent { number }) }) => {
+            commands::app_recent(&mut config, *number, format)?
+        }
+
+        // A local bitrise.yml doesn't need the API client
+        Commands::Triggers(TriggersArgs { file: Some(file), .. }) => {
+            commands::triggers_file(file, format)?
+        }
+        Commands::ValidateYml(args) => {
+            let (report, invalid) = commands::validate_yml(args, format)?;
+            failure = invalid;
+            report
+        }
 
         // All other commands need the API client
         _ => {
+            // Report a missing trigger workflow before asking for a token
+            if let Commands::Trigger(args) = &cli.command {
+                commands::resolve_workflow(&config, args)?;
+            }
+
             // Create client with inline token (CLI/env) or config file
             let client = match &cli.token {
                 Some(token) => BitriseClient::with_token(token)?,
@@ -64,24 +184,99 @@ fn run() -> Result<(), RepriseError> {
             };
 
             match &cli.command {
-                Commands::Apps(args) => commands::apps(&client, args, format)?,
+                Commands::Apps(args) => commands::apps(&client, &config, args, format)?,
+                Commands::App(AppArgs { command: Some(AppCommands::Members { app }) }) => {
+                    commands::app_members(&client, &config, app.as_deref(), format)?
+                }
                 Commands::App(args) => commands::app_set(&client, &mut config, args, format)?,
+                Commands::Org(args) => commands::org(&client, args, format)?,
                 Commands::Builds(args) => commands::builds(&client, &config, args, format)?,
                 Commands::Build(args) => commands::build(&client, &config, args, format)?,
                 Commands::Log(args) => commands::log(&client, &config, args, format)?,
                 Commands::Trigger(args) => commands::trigger(&client, &config, args, format)?,
                 Commands::Artifacts(args) => commands::artifacts(&client, &config, args, format)?,
                 Commands::Abort(args) => commands::abort(&client, &config, args, format)?,
+                Commands::Rebuild(args) => commands::rebuild(&client, &config, args, format)?,
                 Commands::Url(args) => commands::url(&client, &mut config, args, format)?,
                 Commands::Pipelines(args) => commands::pipelines(&client, &config, args, format)?,
                 Commands::Pipeline(args) => commands::pipeline(&client, &config, args, format)?,
-                Commands::Config(_) | Commands::Completions(_) => unreachable!(),
+                Commands::Install(args) => commands::install(&client, &config, args, format)?,
+                Commands::Dsyms(args) => commands::dsyms(&client, &config, args, format)?,
+                Commands::Coverage(args) => commands::coverage(&client, &config, args, format)?,
+                Commands::Wait(args) => {
+                    let (report, failed) = commands::wait(&client, &config, args, format)?;
+                    failure = failed;
+                    report
+                }
+                Commands::WaitBuild(args) => {
+                    let (report, unmet) = commands::wait_build(&client, &config, args, format)?;
+                    failure = unmet;
+                    report
+                }
+                Commands::WatchDaemon(args) => {
+                    commands::watch_daemon(&client, &config, args, format)?
+                }
+                #[cfg(feature = "tray")]
+                Commands::Tray(args) => commands::tray(&client, &config, args, format)?,
+                Commands::Pr(args) => commands::pr(&client, &config, args, format)?,
+                Commands::Runs(args) => commands::runs(&client, &config, args, format)?,
+                Commands::Status(args) => commands::status(&client, &config, args, format)?,
+                Commands::Overview(args) => commands::overview(&client, &config, args, format)?,
+                Commands::Matrix(args) => commands::matrix(&client, &config, args, format)?,
+                Commands::Report(args) => commands::report(&client, &config, args, format)?,
+                Commands::Activity(args) => commands::activity(&client, &config, args, format)?,
+                Commands::Failures(args) => commands::failures(&client, &config, args, format)?,
+                Commands::Triggers(args) => commands::triggers(&client, &config, args, format)?,
+                Commands::Stacks(args) => commands::stacks(&client, &config, args, format)?,
+                Commands::Addons(args) => commands::addons(&client, &config, args, format)?,
+                Commands::Release(args) => commands::release(&client, &config, args, format)?,
+                Commands::Deploys(args) => commands::deploys(&client, &config, args, format)?,
+                Commands::Yml(args) => commands::yml(&client, &config, args, format)?,
+                Commands::Export(args) => commands::export(&client, &config, args, format)?,
+                Commands::Benchmark(args) => commands::benchmark(&client, &config, args, format)?,
+                Commands::Otel(args) => commands::otel(&client, &config, args, format)?,
+                Commands::Config(_)
+                | Commands::Cache(_)
+                | Commands::History(_)
+                | Commands::Completions(_)
+                | Commands::Complete(_)
+                | Commands::Help(_)
+                | Commands::Man(_)
+                | Commands::PromptStatus(_)
+                | Commands::NotifyHandler(_)
+                | Commands::Schema(_)
+                | Commands::ValidateYml(_) => {
+                    unreachable!()
+                }
             }
         }
     };
 
     if !output.is_empty() {
-        println!("{output}");
+        // Logs and build listings taller than the terminal go through the
+        // pager, like git; --watch prints its own refreshing listing
+        let pageable = match &cli.command {
+            Commands::Log(_) => true,
+            Commands::Builds(args) => !args.watch,
+            _ => false,
+        };
+        if pageable && format == OutputFormat::Pretty && !cli.no_pager && pager::is_long(&output)
+        {
+            pager::print_paged(&output, config.output.pager.as_deref());
+        } else {
+            println!("{output}");
+        }
+    }
+
+    if let Some(e) = failure {
+        return Err(e);
+    }
+
+    // After the output, and only for a person at a terminal
+    if !terminal::quiet() && std::io::stderr().is_terminal() {
+        if let Some(latest) = reprise::update::newer_version(&config) {
+            eprintln!("{}", reprise::update::hint(&latest).dimmed());
+        }
     }
 
     Ok(())
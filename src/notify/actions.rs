@@ -0,0 +1,203 @@
+//! Clickable notifications
+//!
+//! On macOS, clicking a build notification opens the build page; this needs
+//! `terminal-notifier` on the PATH (`brew install terminal-notifier`), as the
+//! system notification API offers no click callback to command-line tools.
+//!
+//! On Linux the notification gets "Open" and (for failed builds) "Rebuild"
+//! buttons. Waiting for a button press would keep the command from exiting,
+//! so reprise starts a detached `reprise notify-handler` that shows the
+//! notification and handles the action.
+
+#[cfg(unix)]
+use std::process::{Command, Stdio};
+#[cfg(all(unix, not(target_os = "macos")))]
+use std::sync::mpsc;
+#[cfg(all(unix, not(target_os = "macos")))]
+use std::time::Duration;
+
+use notify_rust::Notification;
+
+/// A notification with an attached build page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notice {
+    pub summary: String,
+    pub body: String,
+    pub icon: String,
+    /// Build page opened on click
+    pub url: String,
+    /// Offer a "Rebuild" action
+    pub rebuild: bool,
+}
+
+/// Show a notice, with click and button actions where the platform has them
+pub fn show(notice: &Notice) {
+    if !show_with_actions(notice) {
+        show_plain(notice);
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn show_with_actions(notice: &Notice) -> bool {
+    Command::new("terminal-notifier")
+        .args(["-title", &notice.summary, "-message", &notice.body])
+        .args(["-open", &notice.url, "-group", "reprise"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn show_with_actions(notice: &Notice) -> bool {
+    let Ok(exe) = std::env::current_exe() else {
+        return false;
+    };
+
+    Command::new(exe)
+        .args(handler_args(notice))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .is_ok()
+}
+
+#[cfg(not(unix))]
+fn show_with_actions(_notice: &Notice) -> bool {
+    false
+}
+
+fn show_plain(notice: &Notice) {
+    let _ = Notification::new()
+        .summary(&notice.summary)
+        .body(&notice.body)
+        .icon(&notice.icon)
+        .appname("reprise")
+        .timeout(5000) // 5 seconds
+        .show();
+}
+
+/// Arguments for the detached `notify-handler` process
+#[cfg_attr(any(target_os = "macos", not(unix)), allow(dead_code))]
+fn handler_args(notice: &Notice) -> Vec<String> {
+    let mut args = vec![
+        "notify-handler".to_string(),
+        "--summary".to_string(),
+        notice.summary.clone(),
+        "--body".to_string(),
+        notice.body.clone(),
+        "--icon".to_string(),
+        notice.icon.clone(),
+        "--url".to_string(),
+        notice.url.clone(),
+    ];
+    if notice.rebuild {
+        args.push("--rebuild".to_string());
+    }
+    args
+}
+
+/// How long the detached handler waits for a click before giving up, in case
+/// the notification server never reports the notification as closed
+#[cfg(all(unix, not(target_os = "macos")))]
+const HANDLER_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Show a notice with action buttons and act on the user's choice
+///
+/// Runs in the detached `notify-handler` process. Returns once an action was
+/// handled, the notification was dismissed, or after an hour.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn handle(notice: &Notice) {
+    let mut notification = Notification::new();
+    notification
+        .summary(&notice.summary)
+        .body(&notice.body)
+        .icon(&notice.icon)
+        .appname("reprise")
+        // "default" is sent when the notification body is clicked
+        .action("default", "Open")
+        .action("open", "Open");
+    if notice.rebuild {
+        notification.action("rebuild", "Rebuild");
+    }
+
+    let Ok(handle) = notification.show() else {
+        return;
+    };
+
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        handle.wait_for_action(|action| {
+            let _ = tx.send(action.to_string());
+        });
+    });
+
+    match rx.recv_timeout(HANDLER_TIMEOUT).as_deref() {
+        Ok("default" | "open") => {
+            let _ = Command::new("xdg-open").arg(&notice.url).status();
+        }
+        Ok("rebuild") => rebuild(&notice.url),
+        _ => {}
+    }
+}
+
+/// Without action support, show the notice as usual
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+pub fn handle(notice: &Notice) {
+    show_plain(notice);
+}
+
+/// Retry the build through `reprise url <url> --retry` and report the result
+#[cfg(all(unix, not(target_os = "macos")))]
+fn rebuild(url: &str) {
+    let Ok(exe) = std::env::current_exe() else {
+        return;
+    };
+
+    let result = Command::new(exe)
+        .args(["url", url, "--retry"])
+        .stdin(Stdio::null())
+        .output();
+    let (summary, body) = match result {
+        Ok(output) if output.status.success() => ("Rebuild started", url.to_string()),
+        Ok(output) => (
+            "Rebuild failed",
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ),
+        Err(e) => ("Rebuild failed", e.to_string()),
+    };
+    super::send(summary, &body);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handler_args_round_trip_notice_fields() {
+        let notice = Notice {
+            summary: "Build Failed: Bitrise - #42".to_string(),
+            body: "Workflow: ci".to_string(),
+            icon: "dialog-error".to_string(),
+            url: "https://app.bitrise.io/build/abc".to_string(),
+            rebuild: true,
+        };
+        let args = handler_args(&notice);
+        assert_eq!(args[0], "notify-handler");
+        assert!(args.windows(2).any(|w| w == ["--url", "https://app.bitrise.io/build/abc"]));
+        assert_eq!(args.last().map(String::as_str), Some("--rebuild"));
+    }
+
+    #[test]
+    fn test_handler_args_without_rebuild() {
+        let notice = Notice {
+            summary: "Build Succeeded".to_string(),
+            body: String::new(),
+            icon: "dialog-positive".to_string(),
+            url: "https://app.bitrise.io/build/abc".to_string(),
+            rebuild: false,
+        };
+        assert!(!handler_args(&notice).contains(&"--rebuild".to_string()));
+    }
+}
@@ -1,17 +1,29 @@
 //! Desktop notification support for build completion
 //!
-//! Provides cross-platform notifications for macOS and Linux.
+//! Provides cross-platform notifications for macOS and Linux. Build
+//! completion notifications open the build when clicked (see [`actions`]).
+//! All desktop notifications are subject to the `[notify]` throttling
+//! settings (see [`throttle`]). [`slack`] posts to a Slack channel instead.
+
+mod actions;
+pub mod slack;
+mod throttle;
 
 use notify_rust::Notification;
 
-use crate::bitrise::Build;
+pub use actions::{handle as handle_actions, Notice};
+pub use throttle::configure;
+
+use crate::bitrise::{Build, BuildStatus, Pipeline};
 
 /// Send a notification for build completion
 pub fn build_completed(build: &Build, app_name: Option<&str>) {
     let (title, icon) = match build.status {
-        1 => ("Build Succeeded", "dialog-positive"),
-        2 => ("Build Failed", "dialog-error"),
-        3 => ("Build Aborted", "dialog-warning"),
+        BuildStatus::Success => ("Build Succeeded", "dialog-positive"),
+        BuildStatus::Failed => ("Build Failed", "dialog-error"),
+        BuildStatus::Aborted | BuildStatus::AbortedWithSuccess => {
+            ("Build Aborted", "dialog-warning")
+        }
         _ => ("Build Completed", "dialog-information"),
     };
 
@@ -25,18 +37,53 @@ pub fn build_completed(build: &Build, app_name: Option<&str>) {
         build.duration_display()
     );
 
-    let _ = Notification::new()
-        .summary(&format!("{}: {}", title, summary))
-        .body(&body)
-        .icon(icon)
-        .appname("reprise")
-        .timeout(5000) // 5 seconds
-        .show();
+    let summary = format!("{}: {}", title, summary);
+    if !throttle::allow(&summary) {
+        return;
+    }
+    actions::show(&Notice {
+        summary,
+        body,
+        icon: icon.to_string(),
+        url: format!("https://app.bitrise.io/build/{}", build.slug),
+        rebuild: build.status == BuildStatus::Failed || build.status.is_aborted(),
+    });
+}
+
+/// Send a notification for pipeline completion
+pub fn pipeline_completed(pipeline: &Pipeline) {
+    let (title, icon, body) = match pipeline.status {
+        BuildStatus::Success => ("Pipeline Succeeded", "dialog-positive", "completed successfully"),
+        BuildStatus::Failed => ("Pipeline Failed", "dialog-error", "failed"),
+        BuildStatus::Aborted | BuildStatus::AbortedWithSuccess => {
+            ("Pipeline Aborted", "dialog-warning", "was aborted")
+        }
+        _ => ("Pipeline Finished", "dialog-information", "finished"),
+    };
+
+    if !throttle::allow(&format!("{}: {}", title, pipeline.pipeline_id)) {
+        return;
+    }
+    actions::show(&Notice {
+        summary: title.to_string(),
+        body: format!("Pipeline {} {}", pipeline.pipeline_id, body),
+        icon: icon.to_string(),
+        url: format!(
+            "https://app.bitrise.io/app/{}/pipelines/{}",
+            pipeline.get_app_slug(),
+            pipeline.id
+        ),
+        rebuild: false,
+    });
 }
 
 /// Send a notification for build triggered
 pub fn build_triggered(build: &Build, app_name: Option<&str>) {
     let app_display = app_name.unwrap_or("Bitrise");
+    let summary = format!("Build Triggered - {} #{}", app_display, build.build_number);
+    if !throttle::allow(&summary) {
+        return;
+    }
 
     let _ = Notification::new()
         .summary(&format!("Build Triggered - {}", app_display))
@@ -49,3 +96,17 @@ pub fn build_triggered(build: &Build, app_name: Option<&str>) {
         .timeout(3000) // 3 seconds
         .show();
 }
+
+/// Send a generic notification with a title and body
+pub fn send(title: &str, body: &str) {
+    if !throttle::allow(title) {
+        return;
+    }
+    let _ = Notification::new()
+        .summary(title)
+        .body(body)
+        .icon("dialog-information")
+        .appname("reprise")
+        .timeout(5000) // 5 seconds
+        .show();
+}
@@ -0,0 +1,71 @@
+//! Slack incoming webhook messages
+//!
+//! Unlike desktop notifications, Slack messages go to a shared channel, so
+//! they are not subject to the local quiet hours or throttling.
+
+use std::time::Duration;
+
+/// How long to wait for Slack before giving up on a message
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Post a message to a Slack incoming webhook
+///
+/// `text` may use Slack's mrkdwn, e.g. `<https://example.com|link text>`.
+pub fn post(webhook_url: &str, text: &str) -> Result<(), String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client
+        .post(webhook_url)
+        .json(&serde_json::json!({ "text": text }))
+        .send()
+        .map_err(|e| e.without_url().to_string())?;
+
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    // Slack explains rejections in a short plain-text body, e.g. "invalid_token"
+    let reason = response.text().unwrap_or_default();
+    Err(format!("Slack returned HTTP {} {}", status.as_u16(), reason.trim()))
+}
+
+/// Escape text for Slack mrkdwn
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+
+    #[test]
+    fn test_post_sends_text() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("POST", "/services/T/B/x")
+            .match_body(Matcher::Json(serde_json::json!({ "text": "Build fixed" })))
+            .with_status(200)
+            .with_body("ok")
+            .create();
+
+        post(&format!("{}/services/T/B/x", server.url()), "Build fixed").unwrap();
+        mock.assert();
+    }
+
+    #[test]
+    fn test_post_reports_rejection() {
+        let mut server = Server::new();
+        server.mock("POST", "/hook").with_status(403).with_body("invalid_token").create();
+
+        let err = post(&format!("{}/hook", server.url()), "hi").unwrap_err();
+        assert_eq!(err, "Slack returned HTTP 403 invalid_token");
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a <b> & c"), "a &lt;b&gt; &amp; c");
+    }
+}
@@ -0,0 +1,119 @@
+//! Notification throttling
+//!
+//! Every notification passes through [`allow`], which drops it during the
+//! configured quiet hours, when it repeats the previous notification, or
+//! when it follows the previous one within `notify.min_interval` seconds.
+//! The last notification is remembered in the cache, so the limits hold
+//! across separate `reprise` processes watching different builds.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use chrono::{Local, NaiveTime};
+
+use crate::cache::{Cache, CacheEntry};
+use crate::config::{Config, NotifyConfig};
+
+/// Cache key holding the summary of the last notification shown
+const LAST_KEY: &str = "notify_last";
+
+static SETTINGS: OnceLock<(NotifyConfig, Option<Cache>)> = OnceLock::new();
+
+/// Apply the `[notify]` config and cache location to all notifications sent
+/// by this process
+pub fn configure(config: &Config) {
+    let _ = SETTINGS.set((config.notify.clone(), Cache::for_config(&config.cache).ok()));
+}
+
+/// Whether a notification with this summary may be shown now; if so, it is
+/// recorded as the last one
+pub(super) fn allow(summary: &str) -> bool {
+    let (settings, cache) =
+        SETTINGS.get_or_init(|| (NotifyConfig::default(), Cache::new().ok()));
+    let last = cache.as_ref().and_then(|c| c.read::<String>(LAST_KEY));
+
+    if !allows(settings, Local::now().time(), last.as_ref(), summary) {
+        return false;
+    }
+    if let Some(cache) = cache {
+        let _ = cache.put(LAST_KEY, &summary);
+    }
+    true
+}
+
+/// The throttling decision, given the local time and the last notification
+fn allows(
+    settings: &NotifyConfig,
+    now: NaiveTime,
+    last: Option<&CacheEntry<String>>,
+    summary: &str,
+) -> bool {
+    if settings.quiet_hours().is_some_and(|quiet| quiet.contains(now)) {
+        return false;
+    }
+    let Some(last) = last else {
+        return true;
+    };
+    // The same event reported twice, e.g. by two watches on one build
+    if last.value == summary && last.age() < DUPLICATE_WINDOW {
+        return false;
+    }
+    settings
+        .min_interval
+        .is_none_or(|secs| last.age() >= Duration::from_secs(secs))
+}
+
+/// How long an identical notification is treated as a duplicate
+const DUPLICATE_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn at(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    fn last(summary: &str, secs_ago: i64) -> CacheEntry<String> {
+        CacheEntry {
+            stored_at: Utc::now() - chrono::Duration::seconds(secs_ago),
+            value: summary.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_allows_by_default() {
+        let settings = NotifyConfig::default();
+        assert!(allows(&settings, at(12, 0), None, "Build Failed"));
+        assert!(allows(&settings, at(12, 0), Some(&last("Build #1", 1)), "Build #2"));
+    }
+
+    #[test]
+    fn test_quiet_hours_drop_notifications() {
+        let settings = NotifyConfig {
+            quiet_hours: Some("22:00-08:00".to_string()),
+            ..Default::default()
+        };
+        assert!(!allows(&settings, at(23, 15), None, "Build #2"));
+        assert!(!allows(&settings, at(6, 0), None, "Build #2"));
+        assert!(allows(&settings, at(9, 0), None, "Build #2"));
+    }
+
+    #[test]
+    fn test_min_interval() {
+        let settings = NotifyConfig {
+            min_interval: Some(60),
+            ..Default::default()
+        };
+        assert!(!allows(&settings, at(12, 0), Some(&last("Build #1", 30)), "Build #2"));
+        assert!(allows(&settings, at(12, 0), Some(&last("Build #1", 90)), "Build #2"));
+    }
+
+    #[test]
+    fn test_duplicate_is_dropped() {
+        let settings = NotifyConfig::default();
+        assert!(!allows(&settings, at(12, 0), Some(&last("Build #1", 5)), "Build #1"));
+        assert!(allows(&settings, at(12, 0), Some(&last("Build #1", 3600)), "Build #1"));
+    }
+}
@@ -0,0 +1,89 @@
+//! Phone push notifications through ntfy
+//!
+//! With `notify.ntfy_topic` set, every build that [`crate::hooks`] sees
+//! finish is published to that topic on ntfy.sh, or on the server in
+//! `notify.ntfy_server`, so the ntfy app can push it to a phone without
+//! Slack. Pushes are personal like desktop notifications, so they are
+//! skipped during `notify.quiet_hours`.
+
+use std::sync::OnceLock;
+
+use chrono::Local;
+
+use crate::bitrise::{Build, BuildStatus};
+use crate::config::{Config, NotifyConfig};
+
+/// Server used when `notify.ntfy_server` isn't set
+pub const DEFAULT_SERVER: &str = "https://ntfy.sh";
+
+static SETTINGS: OnceLock<NotifyConfig> = OnceLock::new();
+
+/// Apply the `[notify]` config to all builds finished in this process
+pub fn configure(config: &Config) {
+    let _ = SETTINGS.set(config.notify.clone());
+}
+
+/// Publish a finished build to the configured topic, if any
+///
+/// A failed publish is reported as a warning and never fails the command.
+pub fn build_finished(app_slug: &str, build: &Build) {
+    let Some(settings) = SETTINGS.get() else {
+        return;
+    };
+    let Some(ref topic) = settings.ntfy_topic else {
+        return;
+    };
+    if settings.quiet_hours().is_some_and(|quiet| quiet.contains(Local::now().time())) {
+        return;
+    }
+    let server = settings.ntfy_server.as_deref().unwrap_or(DEFAULT_SERVER);
+    if let Err(e) = crate::webhook::post(server, &message(topic, app_slug, build)) {
+        eprintln!("warning: couldn't publish to ntfy: {}", e);
+    }
+}
+
+/// An ntfy JSON message: the outcome as title, tag and priority, and the
+/// build page to open when the push is tapped
+pub fn message(topic: &str, app_slug: &str, build: &Build) -> serde_json::Value {
+    let (title, tag, priority) = match build.status {
+        BuildStatus::Success => ("Build Succeeded", "white_check_mark", 3),
+        BuildStatus::Failed => ("Build Failed", "x", 4),
+        BuildStatus::Aborted | BuildStatus::AbortedWithSuccess => ("Build Aborted", "warning", 3),
+        _ => ("Build Finished", "information_source", 3),
+    };
+    serde_json::json!({
+        "topic": topic,
+        "title": format!("{}: {} #{}", title, app_slug, build.build_number),
+        "message": format!(
+            "{} on {} ({})",
+            build.triggered_workflow,
+            build.branch,
+            build.duration_display()
+        ),
+        "tags": [tag],
+        "priority": priority,
+        "click": format!("https://app.bitrise.io/build/{}", build.slug),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_for_failed_build() {
+        let build = Build {
+            status: BuildStatus::Failed,
+            status_text: "error".to_string(),
+            ..Build::fixture()
+        };
+
+        let message = message("my-builds", "ios", &build);
+        assert_eq!(message["topic"], "my-builds");
+        assert_eq!(message["title"], "Build Failed: ios #42");
+        assert_eq!(message["message"], format!("ci on main ({})", build.duration_display()));
+        assert_eq!(message["tags"][0], "x");
+        assert_eq!(message["priority"], 4);
+        assert_eq!(message["click"], "https://app.bitrise.io/build/build-1");
+    }
+}
@@ -0,0 +1,367 @@
+//! OpenTelemetry trace export
+//!
+//! Turns a finished build into an OTLP trace: a span for the build, one for
+//! the time it waited for a worker, and one per step, using the step timings
+//! read from its log. The log has no timestamps, so steps are laid end to
+//! end from the moment the build started on its worker.
+//!
+//! Trace and span IDs are derived from the build slug, so exporting the same
+//! build twice yields the same IDs instead of a duplicate trace.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+use crate::bitrise::{Build, BuildStatus};
+use crate::error::{RepriseError, Result};
+use crate::logparse::steps::{StepOutcome, StepTiming};
+
+/// `service.name` of exported traces unless set otherwise
+pub const DEFAULT_SERVICE_NAME: &str = "bitrise";
+
+/// How long to wait for the collector before giving up
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// OTLP span status codes
+const STATUS_UNSET: u8 = 0;
+const STATUS_OK: u8 = 1;
+const STATUS_ERROR: u8 = 2;
+
+/// OTLP span kind for work inside the process (as opposed to RPCs)
+const KIND_INTERNAL: u8 = 1;
+
+/// The OTLP/HTTP traces URL for an endpoint
+///
+/// Like `OTEL_EXPORTER_OTLP_ENDPOINT`, a base URL gets `/v1/traces`
+/// appended; a URL already ending in it is used as is.
+pub fn traces_url(endpoint: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    if endpoint.ends_with("/v1/traces") {
+        endpoint.to_string()
+    } else {
+        format!("{}/v1/traces", endpoint)
+    }
+}
+
+/// Parse headers written as `OTEL_EXPORTER_OTLP_HEADERS` expects them:
+/// `key=value` pairs separated by commas
+pub fn parse_headers(spec: &str) -> std::result::Result<Vec<(String, String)>, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                Ok((key.trim().to_string(), value.trim().to_string()))
+            }
+            _ => Err(format!("expected KEY=VALUE, got '{}'", pair)),
+        })
+        .collect()
+}
+
+/// Build an OTLP/JSON `ExportTraceServiceRequest` for a finished build
+pub fn trace_payload(
+    app_slug: &str,
+    build: &Build,
+    steps: &[StepTiming],
+    service_name: &str,
+) -> Result<Value> {
+    let Some(finished_at) = build.finished_at else {
+        return Err(RepriseError::InvalidArgument(format!(
+            "Build #{} hasn't finished yet",
+            build.build_number
+        )));
+    };
+    let trace_id = trace_id(&build.slug);
+    let root_id = span_id(&trace_id, "build");
+    let started_at = build.started_on_worker_at.unwrap_or(build.triggered_at);
+
+    let mut build_attributes = vec![
+        attribute("cicd.pipeline.name", json!(build.triggered_workflow)),
+        attribute("cicd.pipeline.run.id", json!(build.slug)),
+        attribute("bitrise.app.slug", json!(app_slug)),
+        int_attribute("bitrise.build.number", build.build_number),
+        attribute("bitrise.build.status", json!(build.status.as_str())),
+        attribute("vcs.ref.head.name", json!(build.branch)),
+    ];
+    if let Some(ref commit) = build.commit_hash {
+        build_attributes.push(attribute("vcs.ref.head.revision", json!(commit)));
+    }
+    if let Some(ref stack) = build.stack_identifier {
+        build_attributes.push(attribute("bitrise.stack", json!(stack)));
+    }
+    if let Some(ref machine) = build.machine_type_id {
+        build_attributes.push(attribute("bitrise.machine_type", json!(machine)));
+    }
+
+    let (code, message) = match build.status {
+        BuildStatus::Success | BuildStatus::AbortedWithSuccess => (STATUS_OK, None),
+        BuildStatus::Failed | BuildStatus::Aborted => (
+            STATUS_ERROR,
+            Some(build.abort_reason.clone().unwrap_or_else(|| build.status_text.clone())),
+        ),
+        _ => (STATUS_UNSET, None),
+    };
+    let mut spans = vec![span(
+        &trace_id,
+        &root_id,
+        None,
+        &format!("{} #{}", build.triggered_workflow, build.build_number),
+        (build.triggered_at, finished_at),
+        build_attributes,
+        status(code, message),
+    )];
+
+    if started_at > build.triggered_at {
+        spans.push(span(
+            &trace_id,
+            &span_id(&trace_id, "queue"),
+            Some(&root_id),
+            "queued",
+            (build.triggered_at, started_at),
+            Vec::new(),
+            status(STATUS_UNSET, None),
+        ));
+    }
+
+    let mut step_start = started_at;
+    for step in steps {
+        let elapsed = chrono::Duration::from_std(step.duration).unwrap_or_default();
+        // Summary times are rounded; don't let them run past the build
+        let step_end = (step_start + elapsed).min(finished_at).max(step_start);
+        let code = match step.outcome {
+            StepOutcome::Success => STATUS_OK,
+            StepOutcome::Failed => STATUS_ERROR,
+            _ => STATUS_UNSET,
+        };
+        spans.push(span(
+            &trace_id,
+            &span_id(&trace_id, &format!("step:{}", step.index)),
+            Some(&root_id),
+            &step.name,
+            (step_start, step_end),
+            vec![
+                attribute("cicd.pipeline.task.name", json!(step.name)),
+                int_attribute("bitrise.step.index", step.index as i64),
+                attribute("bitrise.step.outcome", json!(step.outcome.as_str())),
+            ],
+            status(code, None),
+        ));
+        step_start = step_end;
+    }
+
+    Ok(json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [attribute("service.name", json!(service_name))],
+            },
+            "scopeSpans": [{
+                "scope": { "name": "reprise", "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans,
+            }],
+        }],
+    }))
+}
+
+/// POST a payload from [`trace_payload`] to an OTLP/HTTP traces URL
+pub fn send(url: &str, headers: &[(String, String)], payload: &Value) -> Result<()> {
+    let client = reqwest::blocking::Client::builder().timeout(TIMEOUT).build()?;
+    let mut request = client.post(url).json(payload);
+    for (key, value) in headers {
+        request = request.header(key, value);
+    }
+    let response = request.send()?;
+
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let reason = response.text().unwrap_or_default();
+    Err(RepriseError::Io(std::io::Error::other(format!(
+        "OTLP endpoint returned HTTP {} {}",
+        status.as_u16(),
+        reason.trim()
+    ))))
+}
+
+fn span(
+    trace_id: &str,
+    span_id: &str,
+    parent_id: Option<&str>,
+    name: &str,
+    (start, end): (DateTime<Utc>, DateTime<Utc>),
+    attributes: Vec<Value>,
+    status: Value,
+) -> Value {
+    json!({
+        "traceId": trace_id,
+        "spanId": span_id,
+        "parentSpanId": parent_id.unwrap_or_default(),
+        "name": name,
+        "kind": KIND_INTERNAL,
+        "startTimeUnixNano": unix_nanos(start),
+        "endTimeUnixNano": unix_nanos(end),
+        "attributes": attributes,
+        "status": status,
+    })
+}
+
+fn status(code: u8, message: Option<String>) -> Value {
+    match message {
+        Some(message) => json!({ "code": code, "message": message }),
+        None => json!({ "code": code }),
+    }
+}
+
+fn attribute(key: &str, value: Value) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+/// 64-bit integers are strings in OTLP/JSON
+fn int_attribute(key: &str, value: i64) -> Value {
+    json!({ "key": key, "value": { "intValue": value.to_string() } })
+}
+
+fn unix_nanos(time: DateTime<Utc>) -> String {
+    time.timestamp_nanos_opt().unwrap_or_default().to_string()
+}
+
+/// The trace ID of a build: 32 hex digits, the slug itself when it is a
+/// UUID, as Bitrise's are
+pub fn trace_id(build_slug: &str) -> String {
+    let hex: String = build_slug.chars().filter(|&c| c != '-').collect();
+    if hex.len() == 32 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return hex.to_ascii_lowercase();
+    }
+    format!("{:016x}{:016x}", fnv1a(build_slug, 0), fnv1a(build_slug, 1))
+}
+
+/// 16 hex digits, unique to a part of a trace
+fn span_id(trace_id: &str, part: &str) -> String {
+    // An all-zero ID is invalid
+    format!("{:016x}", fnv1a(&format!("{}/{}", trace_id, part), 0).max(1))
+}
+
+/// FNV-1a, salted so one input can give several independent hashes
+fn fnv1a(text: &str, salt: u8) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in std::iter::once(salt).chain(text.bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build() -> Build {
+        let triggered_at = Build::fixture().triggered_at;
+        Build {
+            slug: "0F8FAD5B-D9CB-469F-A165-70867728950E".to_string(),
+            status: BuildStatus::Failed,
+            status_text: "error".to_string(),
+            started_on_worker_at: Some(triggered_at + chrono::Duration::seconds(30)),
+            finished_at: Some(triggered_at + chrono::Duration::minutes(5)),
+            commit_hash: Some("abc123".to_string()),
+            ..Build::fixture()
+        }
+    }
+
+    fn steps() -> Vec<StepTiming> {
+        vec![
+            StepTiming {
+                index: 0,
+                name: "git-clone@8".to_string(),
+                outcome: StepOutcome::Success,
+                duration: Duration::from_secs(30),
+            },
+            StepTiming {
+                index: 1,
+                name: "xcode-test@5".to_string(),
+                outcome: StepOutcome::Failed,
+                duration: Duration::from_secs(600),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_traces_url() {
+        assert_eq!(traces_url("http://localhost:4318"), "http://localhost:4318/v1/traces");
+        assert_eq!(traces_url("http://localhost:4318/"), "http://localhost:4318/v1/traces");
+        let full = "https://otel.example.com/v1/traces";
+        assert_eq!(traces_url(full), full);
+    }
+
+    #[test]
+    fn test_parse_headers() {
+        assert_eq!(
+            parse_headers("x-api-key=abc, x-team = ios").unwrap(),
+            vec![
+                ("x-api-key".to_string(), "abc".to_string()),
+                ("x-team".to_string(), "ios".to_string())
+            ]
+        );
+        assert!(parse_headers("").unwrap().is_empty());
+        assert!(parse_headers("novalue").is_err());
+    }
+
+    #[test]
+    fn test_trace_payload_spans() {
+        let payload = trace_payload("app-1", &build(), &steps(), "ci").unwrap();
+        let spans = payload["resourceSpans"][0]["scopeSpans"][0]["spans"].as_array().unwrap();
+        assert_eq!(spans.len(), 4);
+
+        let root = &spans[0];
+        assert_eq!(root["traceId"], "0f8fad5bd9cb469fa16570867728950e");
+        assert_eq!(root["name"], "ci #42");
+        assert_eq!(root["parentSpanId"], "");
+        assert_eq!(root["status"]["code"], STATUS_ERROR);
+        assert_eq!(root["startTimeUnixNano"], "1704110400000000000");
+        assert_eq!(root["endTimeUnixNano"], "1704110700000000000");
+
+        assert_eq!(spans[1]["name"], "queued");
+        assert_eq!(spans[1]["endTimeUnixNano"], "1704110430000000000");
+
+        let (clone, test) = (&spans[2], &spans[3]);
+        assert_eq!(clone["parentSpanId"], root["spanId"]);
+        assert_eq!(clone["startTimeUnixNano"], "1704110430000000000");
+        assert_eq!(clone["endTimeUnixNano"], test["startTimeUnixNano"]);
+        assert_eq!(test["status"]["code"], STATUS_ERROR);
+        // Clamped to the end of the build
+        assert_eq!(test["endTimeUnixNano"], root["endTimeUnixNano"]);
+    }
+
+    #[test]
+    fn test_ids_are_stable() {
+        assert_eq!(trace_id("build-1"), trace_id("build-1"));
+        assert_ne!(trace_id("build-1"), trace_id("build-2"));
+        assert_eq!(trace_id("build-1").len(), 32);
+        assert_ne!(span_id("t", "step:0"), span_id("t", "step:1"));
+        assert_eq!(span_id("t", "build").len(), 16);
+    }
+
+    #[test]
+    fn test_running_build_is_rejected() {
+        let mut build = build();
+        build.finished_at = None;
+        assert!(trace_payload("app-1", &build, &[], "ci").is_err());
+    }
+
+    #[test]
+    fn test_send_posts_payload() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("POST", "/v1/traces")
+            .match_header("x-api-key", "secret")
+            .match_body(mockito::Matcher::Json(json!({ "resourceSpans": [] })))
+            .with_status(200)
+            .create();
+
+        let headers = vec![("x-api-key".to_string(), "secret".to_string())];
+        let url = traces_url(&server.url());
+        send(&url, &headers, &json!({ "resourceSpans": [] })).unwrap();
+        mock.assert();
+    }
+}
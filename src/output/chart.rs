@@ -0,0 +1,117 @@
+//! Small terminal charts: sparklines and horizontal bars
+//!
+//! Charts use Unicode block elements, falling back to plain ASCII when the
+//! locale isn't UTF-8 or the terminal is `dumb`, so they stay legible in
+//! old consoles and CI logs.
+
+/// Which characters charts are drawn with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Charset {
+    Unicode,
+    Ascii,
+}
+
+impl Charset {
+    /// Unicode unless the environment says it won't render
+    ///
+    /// The first of `LC_ALL`, `LC_CTYPE` and `LANG` that is set decides; an
+    /// unset locale is assumed to be UTF-8, as on most terminals.
+    pub fn detect() -> Self {
+        if std::env::var("TERM").is_ok_and(|t| t == "dumb") {
+            return Self::Ascii;
+        }
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty());
+        match locale {
+            Some(locale) => {
+                let locale = locale.to_lowercase();
+                if locale.contains("utf-8") || locale.contains("utf8") {
+                    Self::Unicode
+                } else {
+                    Self::Ascii
+                }
+            }
+            None => Self::Unicode,
+        }
+    }
+
+    /// Sparkline levels, lowest first
+    fn levels(self) -> &'static [char] {
+        match self {
+            Self::Unicode => &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'],
+            Self::Ascii => &['_', '.', '-', '=', '+', '*', '#'],
+        }
+    }
+}
+
+/// A sparkline of `values`, one character each, scaled between their
+/// minimum and maximum
+pub fn sparkline(values: &[f64]) -> String {
+    render_sparkline(values, Charset::detect())
+}
+
+/// A horizontal bar `width` cells long at full, filled to `fraction`
+/// (0.0-1.0)
+pub fn bar(fraction: f64, width: usize) -> String {
+    render_bar(fraction, width, Charset::detect())
+}
+
+fn render_sparkline(values: &[f64], charset: Charset) -> String {
+    let levels = charset.levels();
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+    values
+        .iter()
+        .map(|value| {
+            if range <= f64::EPSILON {
+                // All equal: a flat line through the middle
+                return levels[levels.len() / 2];
+            }
+            let level = ((value - min) / range * (levels.len() - 1) as f64).round() as usize;
+            levels[level.min(levels.len() - 1)]
+        })
+        .collect()
+}
+
+fn render_bar(fraction: f64, width: usize, charset: Charset) -> String {
+    let cells = fraction.clamp(0.0, 1.0) * width as f64;
+    match charset {
+        Charset::Unicode => {
+            // Eighths of a cell
+            const PARTIAL: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+            let eighths = (cells * 8.0).round() as usize;
+            let mut bar = "█".repeat(eighths / 8);
+            let partial = PARTIAL[eighths % 8];
+            if partial != ' ' {
+                bar.push(partial);
+            }
+            bar
+        }
+        Charset::Ascii => "#".repeat(cells.round() as usize),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparkline_scales_between_min_and_max() {
+        assert_eq!(render_sparkline(&[1.0, 5.0, 3.0, 8.0], Charset::Unicode), "▁▅▃█");
+        assert_eq!(render_sparkline(&[1.0, 5.0, 3.0, 8.0], Charset::Ascii), "_=-#");
+        assert_eq!(render_sparkline(&[2.0, 2.0], Charset::Unicode), "▅▅");
+        assert_eq!(render_sparkline(&[], Charset::Unicode), "");
+    }
+
+    #[test]
+    fn test_bar() {
+        assert_eq!(render_bar(0.0, 10, Charset::Unicode), "");
+        assert_eq!(render_bar(0.5, 4, Charset::Unicode), "██");
+        assert_eq!(render_bar(0.55, 10, Charset::Unicode), "█████▌");
+        assert_eq!(render_bar(2.0, 3, Charset::Unicode), "███");
+        assert_eq!(render_bar(0.55, 10, Charset::Ascii), "######");
+    }
+}
@@ -0,0 +1,183 @@
+//! Line diffs in unified format
+//!
+//! Meant for config-sized text such as a bitrise.yml: the longest common
+//! subsequence is found with a plain table after trimming the common start
+//! and end, which is quick for files of a few thousand lines.
+
+use colored::Colorize;
+
+/// Lines of context around each change, as in `diff -u`
+pub const CONTEXT: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// A unified diff of `old` and `new` labelled with `from` and `to`, or
+/// `None` when the texts have the same lines
+pub fn unified(old: &str, new: &str, from: &str, to: &str) -> Option<String> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let ops = edit_script(&old, &new);
+    if ops.iter().all(|op| *op == Op::Equal) {
+        return None;
+    }
+
+    let mut output = format!("--- {}\n+++ {}\n", from, to);
+    // Position in `old` and `new` before each op
+    let mut positions = Vec::with_capacity(ops.len() + 1);
+    let (mut i, mut j) = (0, 0);
+    for op in &ops {
+        positions.push((i, j));
+        match op {
+            Op::Equal => (i, j) = (i + 1, j + 1),
+            Op::Delete => i += 1,
+            Op::Insert => j += 1,
+        }
+    }
+
+    let changed: Vec<usize> = (0..ops.len()).filter(|&k| ops[k] != Op::Equal).collect();
+    let mut k = 0;
+    while k < changed.len() {
+        // Extend the hunk while the next change is within twice the context
+        let start = changed[k].saturating_sub(CONTEXT);
+        let mut last = changed[k];
+        while k + 1 < changed.len() && changed[k + 1] - last <= 2 * CONTEXT + 1 {
+            k += 1;
+            last = changed[k];
+        }
+        let end = (last + CONTEXT + 1).min(ops.len());
+        k += 1;
+
+        let (old_start, new_start) = positions[start];
+        let slice = &ops[start..end];
+        let old_count = slice.iter().filter(|op| **op != Op::Insert).count();
+        let new_count = slice.iter().filter(|op| **op != Op::Delete).count();
+        output.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            range(old_start, old_count),
+            range(new_start, new_count)
+        ));
+        for (op, &(i, j)) in slice.iter().zip(&positions[start..end]) {
+            match op {
+                Op::Equal => output.push_str(&format!(" {}\n", old[i])),
+                Op::Delete => output.push_str(&format!("-{}\n", old[i])),
+                Op::Insert => output.push_str(&format!("+{}\n", new[j])),
+            }
+        }
+    }
+    Some(output)
+}
+
+/// A hunk header range: 1-based start and count, with the count left out
+/// when it's 1 and the start pointing before the hunk when it's 0
+fn range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, count),
+    }
+}
+
+/// The shortest sequence of kept, removed and added lines turning `old`
+/// into `new`
+fn edit_script(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+
+    // lcs[i][j]: longest common subsequence of a[i..] and b[j..]
+    let mut lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = vec![Op::Equal; prefix];
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            ops.push(Op::Equal);
+            (i, j) = (i + 1, j + 1);
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            // Removals come before the additions that replace them
+            ops.push(Op::Delete);
+            i += 1;
+        } else {
+            ops.push(Op::Insert);
+            j += 1;
+        }
+    }
+    ops.extend(std::iter::repeat_n(Op::Equal, suffix));
+    ops
+}
+
+/// Color a unified diff like `git diff`: removals red, additions green and
+/// hunk headers cyan
+pub fn paint(diff: &str) -> String {
+    diff.lines()
+        .map(|line| {
+            if line.starts_with("---") || line.starts_with("+++") {
+                line.bold().to_string()
+            } else if line.starts_with("@@") {
+                line.cyan().to_string()
+            } else if line.starts_with('-') {
+                line.red().to_string()
+            } else if line.starts_with('+') {
+                line.green().to_string()
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_text_has_no_diff() {
+        assert_eq!(unified("a\nb\n", "a\nb", "old", "new"), None);
+    }
+
+    #[test]
+    fn test_unified_diff_with_context() {
+        let old = "format_version: 11\nworkflows:\n  ci:\n    steps:\n    - git-clone@6\n    \
+                   - xcode-test@4\n    - deploy@2\n";
+        let new = "format_version: 11\nworkflows:\n  ci:\n    steps:\n    - git-clone@8\n    \
+                   - xcode-test@4\n    - deploy@2\n    - slack@3\n";
+        let diff = unified(old, new, "a/bitrise.yml", "b/bitrise.yml").unwrap();
+        assert_eq!(
+            diff,
+            "--- a/bitrise.yml\n+++ b/bitrise.yml\n@@ -2,6 +2,7 @@\n workflows:\n   ci:\n     \
+             steps:\n-    - git-clone@6\n+    - git-clone@8\n     - xcode-test@4\n     \
+             - deploy@2\n+    - slack@3\n"
+        );
+    }
+
+    #[test]
+    fn test_distant_changes_get_separate_hunks() {
+        let old: String = (1..=20).map(|n| format!("line {}\n", n)).collect();
+        let new = old.replace("line 2\n", "line two\n").replace("line 19\n", "");
+        let diff = unified(&old, &new, "old", "new").unwrap();
+        assert!(diff.contains("@@ -1,5 +1,5 @@\n line 1\n-line 2\n+line two\n"));
+        assert!(diff.contains("@@ -16,5 +16,4 @@\n"));
+        assert!(diff.ends_with(" line 18\n-line 19\n line 20\n"));
+    }
+}
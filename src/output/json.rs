@@ -1,3 +1,5 @@
+use std::sync::OnceLock;
+
 use serde::Serialize;
 
 use crate::bitrise::{App, Artifact, Build, Pipeline};
@@ -5,48 +7,88 @@ use crate::error::Result;
 
 /// Format apps as JSON
 pub fn format_apps(apps: &[App]) -> Result<String> {
-    Ok(serde_json::to_string_pretty(apps)?)
+    format_json(&apps)
 }
 
 /// Format a single app as JSON
 pub fn format_app(app: &App) -> Result<String> {
-    Ok(serde_json::to_string_pretty(app)?)
+    format_json(app)
 }
 
 /// Format builds as JSON
 pub fn format_builds(builds: &[Build]) -> Result<String> {
-    Ok(serde_json::to_string_pretty(builds)?)
+    format_json(&builds)
 }
 
 /// Format a single build as JSON
 pub fn format_build(build: &Build) -> Result<String> {
-    Ok(serde_json::to_string_pretty(build)?)
+    format_json(build)
 }
 
 /// Format pipelines as JSON
 pub fn format_pipelines(pipelines: &[Pipeline]) -> Result<String> {
-    Ok(serde_json::to_string_pretty(pipelines)?)
+    format_json(&pipelines)
 }
 
 /// Format a single pipeline as JSON
 pub fn format_pipeline(pipeline: &Pipeline) -> Result<String> {
-    Ok(serde_json::to_string_pretty(pipeline)?)
+    format_json(pipeline)
 }
 
-/// Format any serializable value as JSON
+/// Format any serializable value as JSON, in the envelope if one is in use
 pub fn format_json<T: Serialize>(value: &T) -> Result<String> {
-    Ok(serde_json::to_string_pretty(value)?)
+    match ENVELOPE_KIND.get() {
+        Some(kind) => Ok(serde_json::to_string_pretty(&Envelope::new(kind, value)?)?),
+        None => Ok(serde_json::to_string_pretty(value)?),
+    }
+}
+
+/// Version of the JSON output envelope, raised on breaking field changes
+pub const API_VERSION: u32 = 1;
+
+/// Kind of the running command, set when its JSON output is enveloped
+static ENVELOPE_KIND: OnceLock<String> = OnceLock::new();
+
+/// Wrap all JSON output of this process in the versioned envelope
+pub fn use_envelope(kind: &str) {
+    let _ = ENVELOPE_KIND.set(kind.to_string());
+}
+
+/// The versioned envelope around a command's JSON output
+///
+/// Arrays go under `items` and any other value under `item`. The value is
+/// serialized as is, so its fields keep their declared order.
+#[derive(Serialize)]
+struct Envelope<'a, T: Serialize> {
+    api_version: u32,
+    kind: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    item: Option<&'a T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    items: Option<&'a T>,
+}
+
+impl<'a, T: Serialize> Envelope<'a, T> {
+    fn new(kind: &'a str, value: &'a T) -> Result<Self> {
+        let is_array = serde_json::to_value(value)?.is_array();
+        Ok(Envelope {
+            api_version: API_VERSION,
+            kind,
+            item: (!is_array).then_some(value),
+            items: is_array.then_some(value),
+        })
+    }
 }
 
 /// Format artifacts as JSON
 pub fn format_artifacts(artifacts: &[Artifact]) -> Result<String> {
-    Ok(serde_json::to_string_pretty(artifacts)?)
+    format_json(&artifacts)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::bitrise::Owner;
+    use crate::bitrise::{BuildStatus, Owner};
     use chrono::{TimeZone, Utc};
 
     // ─────────────────────────────────────────────────────────────────────────
@@ -79,7 +121,7 @@ mod tests {
             triggered_at: Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
             started_on_worker_at: None,
             finished_at: None,
-            status: 1,
+            status: BuildStatus::Success,
             status_text: "success".to_string(),
             abort_reason: None,
             branch: "main".to_string(),
@@ -94,6 +136,7 @@ mod tests {
             pull_request_id: None,
             pull_request_target_branch: None,
             credit_cost: None,
+            original_build_params: None,
         }
     }
 
@@ -102,7 +145,7 @@ mod tests {
             id: id.to_string(),
             app_slug: "test-app".to_string(),
             app: None,
-            status: 1,
+            status: BuildStatus::Success,
             status_text: Some("success".to_string()),
             triggered_at: Some(Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap()),
             started_at: None,
@@ -125,6 +168,7 @@ mod tests {
             is_public_page_enabled: false,
             expiring_download_url: None,
             public_install_page_url: None,
+            artifact_meta: None,
         }
     }
 
@@ -317,4 +361,34 @@ mod tests {
         let result = format_json(&map).unwrap();
         assert!(result.contains("\"key\": \"value\""));
     }
+
+    fn envelope<T: Serialize>(kind: &str, value: &T) -> String {
+        serde_json::to_string_pretty(&Envelope::new(kind, value).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_envelope_wraps_arrays_as_items() {
+        let wrapped: serde_json::Value =
+            serde_json::from_str(&envelope("builds", &[1, 2])).unwrap();
+        assert_eq!(wrapped["api_version"], API_VERSION);
+        assert_eq!(wrapped["kind"], "builds");
+        assert_eq!(wrapped["items"], serde_json::json!([1, 2]));
+    }
+
+    #[test]
+    fn test_envelope_wraps_objects_as_item() {
+        let build = make_test_build("b", 1);
+        let wrapped: serde_json::Value = serde_json::from_str(&envelope("build", &build)).unwrap();
+        assert_eq!(wrapped["kind"], "build");
+        assert_eq!(wrapped["item"]["slug"], "b");
+        assert!(wrapped.get("items").is_none());
+    }
+
+    #[test]
+    fn test_envelope_keeps_field_order() {
+        let output = envelope("build", &make_test_build("b", 1));
+        let at = |field: &str| output.find(&format!("\"{}\"", field)).unwrap();
+        assert!(at("api_version") < at("kind") && at("kind") < at("item"));
+        assert!(at("slug") < at("triggered_at") && at("status") < at("branch"));
+    }
 }
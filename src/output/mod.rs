@@ -1,5 +1,12 @@
+pub mod chart;
+pub mod diff;
 pub mod json;
+pub mod pager;
 pub mod pretty;
+pub mod progress;
+pub mod table;
+pub mod terminal;
+pub mod theme;
 
 use crate::bitrise::{App, Artifact, Build, Pipeline};
 use crate::cli::OutputFormat;
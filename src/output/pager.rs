@@ -0,0 +1,124 @@
+//! Paging for long pretty output
+//!
+//! Like git, output is piped through a pager only when stdout is a terminal
+//! and the text doesn't fit on one screen. The pager is taken from the
+//! `output.pager` config key, then `$PAGER`, then `less -R`. An empty value
+//! or `cat` disables paging.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use terminal_size::{terminal_size, Height};
+
+use super::terminal::stdout_is_tty;
+
+/// Default pager when nothing is configured
+const DEFAULT_PAGER: &str = "less -R";
+
+/// Resolve the pager command, or `None` if paging is disabled
+pub fn pager_command(configured: Option<&str>) -> Option<String> {
+    let env = std::env::var("PAGER").ok();
+    resolve_pager(configured, env.as_deref())
+}
+
+fn resolve_pager(configured: Option<&str>, env: Option<&str>) -> Option<String> {
+    let cmd = configured.or(env).unwrap_or(DEFAULT_PAGER).trim();
+    if cmd.is_empty() || cmd == "cat" {
+        None
+    } else {
+        Some(cmd.to_string())
+    }
+}
+
+/// Whether output is long enough to page: stdout is a terminal and the
+/// text is taller than it
+pub fn is_long(text: &str) -> bool {
+    stdout_is_tty() && terminal_size().is_some_and(|(_, Height(rows))| exceeds(text, rows))
+}
+
+/// Whether the text has at least `rows` lines
+fn exceeds(text: &str, rows: u16) -> bool {
+    text.lines().nth(usize::from(rows).saturating_sub(1)).is_some()
+}
+
+/// Print output through the pager, or directly if paging is disabled
+///
+/// Falls back to printing directly if the pager can't be started. Callers
+/// check [`is_long`] first, so short output is never paged.
+pub fn print_paged(text: &str, configured: Option<&str>) {
+    if let Some(cmd) = pager_command(configured) {
+        if run_pager(&cmd, text) {
+            return;
+        }
+    }
+    println!("{text}");
+}
+
+/// Pipe text into the pager; returns false if it couldn't be spawned
+fn run_pager(cmd: &str, text: &str) -> bool {
+    let mut parts = cmd.split_whitespace();
+    let Some(program) = parts.next() else {
+        return false;
+    };
+
+    let mut command = Command::new(program);
+    command.args(parts).stdin(Stdio::piped());
+    // Same defaults git uses: quit if one screen, keep colors, don't clear
+    if std::env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+
+    let Ok(mut child) = command.spawn() else {
+        return false;
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // A broken pipe just means the user quit the pager early
+        let _ = writeln!(stdin, "{text}");
+    }
+    let _ = child.wait();
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_pager_default() {
+        assert_eq!(resolve_pager(None, None).as_deref(), Some("less -R"));
+    }
+
+    #[test]
+    fn test_resolve_pager_env() {
+        assert_eq!(resolve_pager(None, Some("most")).as_deref(), Some("most"));
+    }
+
+    #[test]
+    fn test_resolve_pager_config_overrides_env() {
+        assert_eq!(
+            resolve_pager(Some("bat --paging=always"), Some("most")).as_deref(),
+            Some("bat --paging=always")
+        );
+    }
+
+    #[test]
+    fn test_resolve_pager_disabled() {
+        assert_eq!(resolve_pager(Some(""), Some("less")), None);
+        assert_eq!(resolve_pager(None, Some("cat")), None);
+        assert_eq!(resolve_pager(Some("  "), None), None);
+    }
+
+    #[test]
+    fn test_exceeds_counts_lines() {
+        assert!(!exceeds("a\nb", 3));
+        assert!(exceeds("a\nb\nc", 3));
+        assert!(exceeds("a\nb\nc\nd", 3));
+        assert!(!exceeds("", 1));
+    }
+
+    #[test]
+    fn test_run_pager_missing_program() {
+        assert!(!run_pager("reprise-no-such-pager", "text"));
+    }
+}
@@ -1,13 +1,41 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
 use colored::Colorize;
-use terminal_size::{terminal_size, Width};
 
-use crate::bitrise::{App, Artifact, Build, Pipeline};
+use super::table::{self, Table};
+use super::{chart, terminal, theme};
+use crate::bitrise::{App, Artifact, Build, BuildStatus, Pipeline};
+use crate::git::HeadRelation;
+
+/// Below this many columns, list rows are stacked over two lines
+const NARROW_WIDTH: usize = 80;
+
+/// Longest separator under a list title, however wide the terminal
+pub const MAX_RULE_WIDTH: usize = 160;
+
+/// Indent of the detail lines under each list row
+const ROW_INDENT: usize = 8;
+
+/// A `─` separator as wide as the output, up to `max` columns
+pub fn separator(max: usize) -> String {
+    rule(terminal::width(), max)
+}
+
+fn rule(width: usize, max: usize) -> String {
+    "─".repeat(width.min(max))
+}
 
-/// Get terminal width, defaulting to 100 if detection fails
-fn get_terminal_width() -> usize {
-    terminal_size()
-        .map(|(Width(w), _)| w as usize)
-        .unwrap_or(100)
+/// A list's title and separator (unless `--no-header`), then its table
+fn listing(title: &str, table: &Table, width: usize) -> String {
+    let mut output = String::new();
+    if table::headers_shown() {
+        output.push_str(&format!("{}\n", title.bold()));
+        output.push_str(&rule(width, MAX_RULE_WIDTH));
+        output.push('\n');
+    }
+    output.push_str(&table.render());
+    output
 }
 
 /// Safely truncate a string to n characters, appending "..." if truncated.
@@ -28,7 +56,6 @@ fn get_terminal_width() -> usize {
 /// assert_eq!(truncate_str("hello world", 8), "hello...");
 /// assert_eq!(truncate_str("short", 10), "short");
 /// ```
-#[allow(dead_code)] // Used in tests
 fn truncate_str(s: &str, max_chars: usize) -> String {
     let chars: Vec<char> = s.chars().collect();
     if chars.len() > max_chars {
@@ -105,11 +132,7 @@ pub fn format_apps(apps: &[App]) -> String {
         return "No apps found.".to_string();
     }
 
-    let mut output = String::new();
-    output.push_str(&format!("{}\n", "Apps".bold()));
-    output.push_str(&"─".repeat(70));
-    output.push('\n');
-
+    let mut table = Table::new(&["APP", "STATUS", "SLUG", "OWNER"]);
     for app in apps {
         let status = if app.is_disabled {
             "disabled".red()
@@ -118,32 +141,22 @@ pub fn format_apps(apps: &[App]) -> String {
         };
 
         // Show slug prominently for easy copy-paste
-        output.push_str(&format!(
-            "{} [{}]\n",
-            app.title.bold(),
-            status
-        ));
-        output.push_str(&format!(
-            "  {} {}\n",
-            "Slug:".cyan(),
-            app.slug
-        ));
-        output.push_str(&format!(
-            "  {} {}\n",
-            "Owner:".cyan(),
-            app.owner.name
-        ));
+        table.row(vec![
+            app.title.bold().to_string(),
+            status.to_string(),
+            app.slug.clone(),
+            app.owner.name.clone(),
+        ]);
 
         if let Some(ref project_type) = app.project_type {
-            output.push_str(&format!("  {} {}\n", "Type:".cyan(), project_type));
+            table.text(format!("  {} {}", "Type:".cyan(), project_type));
         }
         if let Some(ref repo_url) = app.repo_url {
-            output.push_str(&format!("  {} {}\n", "Repo:".cyan(), repo_url.dimmed()));
+            table.text(format!("  {} {}", "Repo:".cyan(), repo_url.dimmed()));
         }
-        output.push('\n');
     }
 
-    output
+    listing("Apps", &table, terminal::width())
 }
 
 /// Format a single app for pretty output
@@ -157,7 +170,7 @@ pub fn format_app(app: &App) -> String {
     };
 
     output.push_str(&format!("{} [{}]\n", app.title.bold(), status_colored));
-    output.push_str(&"─".repeat(50));
+    output.push_str(&separator(50));
     output.push('\n');
 
     // Show slug prominently for easy copy-paste
@@ -188,88 +201,280 @@ pub fn format_app(app: &App) -> String {
     output
 }
 
+/// Number of older builds of the same workflow averaged for duration deltas
+const DELTA_WINDOW: usize = 5;
+
 /// Format a list of builds for pretty output
 pub fn format_builds(builds: &[Build]) -> String {
+    format_builds_annotated(builds, &BuildNotes::default())
+}
+
+/// Optional per-build annotations for listings, keyed by build slug
+#[derive(Debug, Default)]
+pub struct BuildNotes {
+    /// Duration deltas (see [`delta_map`])
+    pub deltas: HashMap<String, i64>,
+    /// How each build's commit relates to the local checkout
+    pub head: HashMap<String, HeadRelation>,
+}
+
+/// Duration deltas keyed by build slug (see [`duration_deltas`])
+///
+/// Compute this on the newest-first page before re-sorting builds.
+pub fn delta_map(builds: &[Build]) -> HashMap<String, i64> {
+    builds
+        .iter()
+        .zip(duration_deltas(builds, DELTA_WINDOW))
+        .filter_map(|(b, delta)| delta.map(|d| (b.slug.clone(), d)))
+        .collect()
+}
+
+/// Duration delta in seconds for each build versus the rolling average of up
+/// to `window` older successful builds of the same workflow
+///
+/// Builds are newest first. Running builds and builds without any older
+/// successful run to compare against get `None`.
+pub fn duration_deltas(builds: &[Build], window: usize) -> Vec<Option<i64>> {
+    builds
+        .iter()
+        .enumerate()
+        .map(|(i, build)| {
+            let secs = build.duration()?.num_seconds();
+            let previous: Vec<i64> = builds[i + 1..]
+                .iter()
+                .filter(|b| {
+                    b.status == BuildStatus::Success
+                        && b.triggered_workflow == build.triggered_workflow
+                })
+                .filter_map(|b| b.duration().map(|d| d.num_seconds()))
+                .take(window)
+                .collect();
+            if previous.is_empty() {
+                return None;
+            }
+            let average = previous.iter().sum::<i64>() / previous.len() as i64;
+            Some(secs - average)
+        })
+        .collect()
+}
+
+/// A sparkline of finished builds' durations, oldest to newest, with the
+/// shortest and longest; `None` with fewer than two finished builds
+pub fn format_duration_trend(builds: &[Build]) -> Option<String> {
+    let mut finished: Vec<&Build> = builds.iter().filter(|b| b.duration().is_some()).collect();
+    if finished.len() < 2 {
+        return None;
+    }
+    finished.sort_by_key(|b| b.triggered_at);
+    let shortest = finished.iter().min_by_key(|b| b.duration())?;
+    let longest = finished.iter().max_by_key(|b| b.duration())?;
+    let secs: Vec<f64> = finished
+        .iter()
+        .filter_map(|b| b.duration().map(|d| d.num_seconds() as f64))
+        .collect();
+    Some(format!(
+        "{} {} {}",
+        "Durations:".cyan(),
+        chart::sparkline(&secs),
+        format!(
+            "({} to {}, oldest to newest)",
+            shortest.duration_display(),
+            longest.duration_display()
+        )
+        .dimmed()
+    ))
+}
+
+/// Format a duration delta like "+2m 10s" or "-45s"
+fn format_delta(secs: i64) -> String {
+    let sign = if secs < 0 { '-' } else { '+' };
+    let abs = secs.abs();
+    if abs < 60 {
+        format!("{}{}s", sign, abs)
+    } else {
+        format!("{}{}m {}s", sign, abs / 60, abs % 60)
+    }
+}
+
+/// Format a list of builds with their annotations
+pub fn format_builds_annotated(builds: &[Build], notes: &BuildNotes) -> String {
+    builds_listing(builds, notes, terminal::width())
+}
+
+/// Builds listing laid out for `width` columns
+fn builds_listing(builds: &[Build], notes: &BuildNotes, width: usize) -> String {
     if builds.is_empty() {
         return "No builds found.".to_string();
     }
 
-    let term_width = get_terminal_width();
+    let mut table = build_table(width);
+    push_build_rows(&mut table, builds, notes, width);
+    listing("Builds", &table, width)
+}
 
-    let mut output = String::new();
-    output.push_str(&format!("{}\n", "Builds".bold()));
-    output.push_str(&"─".repeat(term_width.min(120)));
-    output.push('\n');
+/// Format builds in titled sections (e.g., one per branch)
+pub fn format_build_groups(groups: &[(String, Vec<Build>)], notes: &BuildNotes) -> String {
+    if groups.is_empty() {
+        return "No builds found.".to_string();
+    }
+
+    let width = terminal::width();
+
+    // One table across the sections keeps their columns aligned
+    let mut table = build_table(width);
+    for (key, builds) in groups {
+        table.text("");
+        table.text(format!("{} ({})", key.bold().underline(), builds.len()));
+        push_build_rows(&mut table, builds, notes, width);
+    }
+
+    listing("Builds", &table, width)
+}
 
+/// Columns of a builds listing; narrow terminals move branch and workflow
+/// to a line of their own
+fn build_table(width: usize) -> Table {
+    if width < NARROW_WIDTH {
+        Table::new(&["BUILD", "STATUS", "DURATION"])
+    } else {
+        Table::new(&["BUILD", "STATUS", "BRANCH", "WORKFLOW", "DURATION"])
+    }
+}
+
+/// Add the rows for each build to a listing
+///
+/// Each build gets one row, or two lines on narrow terminals: number,
+/// status and duration first, then branch and workflow cut to fit.
+fn push_build_rows(
+    table: &mut Table,
+    builds: &[Build],
+    notes: &BuildNotes,
+    width: usize,
+) {
+    let indent = " ".repeat(ROW_INDENT);
+    let detail_width = width.saturating_sub(ROW_INDENT).max(20);
     for build in builds {
-        let status_colored = match build.status {
-            0 => "running".yellow().bold(),
-            1 => "success".green(),
-            2 => "failed".red().bold(),
-            3 => "aborted".red(),
-            _ => "unknown".dimmed(),
+        let number = format!("#{}", build.build_number).bold().to_string();
+        let status_colored = theme::label(build.status).to_string();
+
+        // Slower than average is red, faster is green
+        let delta = match notes.deltas.get(&build.slug).copied() {
+            Some(secs) if secs > 0 => format!(" {}", format_delta(secs).red()),
+            Some(secs) if secs < 0 => format!(" {}", format_delta(secs).green()),
+            Some(_) => format!(" {}", "±0s".dimmed()),
+            None => String::new(),
         };
-
-        // Main build line with build number, status, branch, workflow, duration
-        // No truncation - show full branch and workflow names
-        output.push_str(&format!(
-            "#{:<6} {:12} {} {} {}\n",
-            build.build_number.to_string().bold(),
-            status_colored,
-            build.branch,
-            build.triggered_workflow.dimmed(),
-            build.duration_display().dimmed()
-        ));
+        let duration = format!("{}{}", build.duration_display().dimmed(), delta);
+
+        if width < NARROW_WIDTH {
+            table.row(vec![number, status_colored, duration]);
+            // Workflow names are short; the branch gives way
+            let branch_width = detail_width.saturating_sub(build.triggered_workflow.len() + 1);
+            table.text(format!(
+                "{}{} {}",
+                indent,
+                truncate_str(&build.branch, branch_width.max(10)),
+                build.triggered_workflow.dimmed()
+            ));
+        } else {
+            // Full branch and workflow names, however long
+            table.row(vec![
+                number,
+                status_colored,
+                build.branch.clone(),
+                build.triggered_workflow.dimmed().to_string(),
+                duration,
+            ]);
+        }
 
         // Show slug prominently for easy copy-paste
-        output.push_str(&format!("        {} {}", "Slug:".cyan(), build.slug));
+        let mut line = format!("{}{} {}", indent, "Slug:".cyan(), build.slug);
 
         // Show PR indicator if present
         if let Some(pr_id) = build.pull_request_id {
-            output.push_str(&format!("  {}#{}", "PR".magenta(), pr_id));
+            line.push_str(&format!("  {}#{}", "PR".magenta(), pr_id));
         }
 
         // Show tag if present
         if let Some(ref tag) = build.tag {
-            output.push_str(&format!("  {}{}", "Tag:".cyan(), tag));
+            line.push_str(&format!("  {}{}", "Tag:".cyan(), tag));
         }
 
-        output.push('\n');
+        // Show whether the local checkout is built yet
+        match notes.head.get(&build.slug) {
+            Some(HeadRelation::Head) => line.push_str(&format!("  {}", "● HEAD".green())),
+            Some(HeadRelation::IncludesHead) => {
+                line.push_str(&format!("  {}", "↑ includes HEAD".green()))
+            }
+            Some(HeadRelation::BehindHead) => {
+                line.push_str(&format!("  {}", "↓ behind HEAD".dimmed()))
+            }
+            None => {}
+        }
+
+        table.text(line);
 
         // Show triggered by
         if let Some(ref by) = build.triggered_by {
-            output.push_str(&format!("        {} {}\n", "By:".cyan(), by.dimmed()));
+            table.text(format!("{}{} {}", indent, "By:".cyan(), by.dimmed()));
         }
 
         // Show commit message preview for failed builds
         if build.is_failed() {
             if let Some(ref msg) = build.commit_message {
-                let preview: String = msg.lines().next().unwrap_or("").chars().take(60).collect();
-                output.push_str(&format!("        {}\n", preview.dimmed()));
+                let preview = truncate_str(msg.lines().next().unwrap_or(""), detail_width);
+                table.text(format!("{}{}", indent, preview.dimmed()));
             }
             if let Some(ref reason) = build.abort_reason {
-                output.push_str(&format!("        {} {}\n", "Reason:".red(), reason.red()));
+                table.text(format!("{}{} {}", indent, "Reason:".red(), reason.red()));
             }
         }
     }
+}
 
-    output
+/// Format a build as a single summary line (status, number, workflow, duration, commit)
+///
+/// Used by overview commands that list builds inline rather than as a table.
+pub fn format_build_line(build: &Build) -> String {
+    let status = theme::label(build.status);
+
+    let commit = build
+        .commit_hash
+        .as_deref()
+        .map(|h| first_n_chars(h, 7))
+        .unwrap_or_else(|| "-".to_string());
+
+    format!(
+        "#{:<6} {:12} {} {} {}",
+        build.build_number.to_string().bold(),
+        status,
+        build.triggered_workflow,
+        build.duration_display().dimmed(),
+        commit.dimmed()
+    )
+}
+
+/// Format a pipeline as a compact single line: short id, status, name, duration
+pub fn format_pipeline_line(pipeline: &Pipeline) -> String {
+    let status = theme::label(pipeline.status);
+
+    format!(
+        "{:<7} {:12} {} {}",
+        first_n_chars(&pipeline.id, 7).bold(),
+        status,
+        pipeline.pipeline_id,
+        pipeline.duration_display().dimmed()
+    )
 }
 
 /// Format a single build for pretty output
 pub fn format_build(build: &Build) -> String {
     let mut output = String::new();
 
-    let status_colored = match build.status {
-        0 => format!("{}", "RUNNING".yellow().bold()),
-        1 => format!("{}", "SUCCESS".green().bold()),
-        2 => format!("{}", "FAILED".red().bold()),
-        3 => format!("{}", "ABORTED".red()),
-        _ => format!("{}", "UNKNOWN".dimmed()),
-    };
+    let status_colored = theme::heading(build.status);
 
     output.push_str(&format!("Build #{} {}\n", build.build_number.to_string().bold(), status_colored));
-    output.push_str(&"─".repeat(60));
+    output.push_str(&separator(60));
     output.push('\n');
 
     // Show slug prominently for easy copy-paste
@@ -327,6 +532,11 @@ pub fn format_build(build: &Build) -> String {
         output.push_str(&format!("{} {}\n", "Credits:".cyan(), cost));
     }
 
+    // How the build was triggered
+    if let Some(params) = build.original_build_params.as_ref().and_then(|p| p.as_object()) {
+        output.push_str(&format_build_params(params));
+    }
+
     if let Some(ref reason) = build.abort_reason {
         output.push_str(&format!("\n{} {}\n", "Abort Reason:".red().bold(), reason));
     }
@@ -341,95 +551,181 @@ pub fn format_build(build: &Build) -> String {
     output
 }
 
+/// Name fragments of env vars whose values are never printed
+const SECRET_NAME_PARTS: [&str; 8] = [
+    "TOKEN", "SECRET", "PASSWORD", "PASSWD", "KEY", "AUTH", "CREDENTIAL", "PRIVATE",
+];
+
+/// Shown in place of a secret env var's value
+const MASKED_VALUE: &str = "****";
+
+/// The parameters a build was triggered with: scalar `build_params` such
+/// as tag, PR and stack, then the custom env vars with secrets masked
+fn format_build_params(params: &serde_json::Map<String, serde_json::Value>) -> String {
+    let rows: Vec<(String, String)> = params
+        .iter()
+        .filter(|(key, _)| key.as_str() != "environments")
+        .filter_map(|(key, value)| Some((key.clone(), param_value(value)?)))
+        .collect();
+    let envs: Vec<(String, String)> = params
+        .get("environments")
+        .and_then(|e| e.as_array())
+        .map(|envs| envs.iter().filter_map(env_var).collect())
+        .unwrap_or_default();
+    if rows.is_empty() && envs.is_empty() {
+        return String::new();
+    }
+
+    let width = rows.iter().chain(&envs).map(|(key, _)| key.chars().count()).max().unwrap_or(0);
+    let mut output = format!("\n{}\n", "Trigger Parameters:".cyan());
+    for (key, value) in rows {
+        output.push_str(&format!("  {:<width$}  {}\n", key, value, width = width));
+    }
+    if !envs.is_empty() {
+        output.push_str(&format!("{}\n", "Env Vars:".cyan()));
+        for (key, value) in envs {
+            output.push_str(&format!("  {:<width$}  {}\n", key, value, width = width));
+        }
+    }
+    output
+}
+
+/// A scalar build param as one line, or `None` for empty and nested values
+fn param_value(value: &serde_json::Value) -> Option<String> {
+    let text = match value {
+        serde_json::Value::String(s) if !s.trim().is_empty() => s.lines().next()?.to_string(),
+        serde_json::Value::Number(n) => n.to_string(),
+        serde_json::Value::Bool(b) => b.to_string(),
+        _ => return None,
+    };
+    Some(truncate_str(&text, 60))
+}
+
+/// Name and display value of an `environments` entry
+fn env_var(env: &serde_json::Value) -> Option<(String, String)> {
+    let name = env.get("mapped_to")?.as_str()?.to_string();
+    let value = match env.get("value") {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+        None => String::new(),
+    };
+    let flagged = ["is_sensitive", "is_secret"]
+        .iter()
+        .any(|flag| env.get(*flag).and_then(|f| f.as_bool()).unwrap_or(false));
+    if flagged || is_secret_name(&name) {
+        return Some((name, MASKED_VALUE.to_string()));
+    }
+    let value = truncate_str(value.lines().next().unwrap_or(""), 60);
+    Some((name, value))
+}
+
+/// Whether an env var name suggests its value is a credential
+fn is_secret_name(name: &str) -> bool {
+    let upper = name.to_uppercase();
+    SECRET_NAME_PARTS.iter().any(|part| upper.contains(part))
+}
+
 /// Format a list of pipelines for pretty output
 pub fn format_pipelines(pipelines: &[Pipeline]) -> String {
+    pipelines_listing(pipelines, terminal::width())
+}
+
+/// Pipelines listing laid out for `width` columns
+///
+/// Narrow terminals get the branch and pipeline name on a second line.
+fn pipelines_listing(pipelines: &[Pipeline], width: usize) -> String {
     if pipelines.is_empty() {
         return "No pipelines found.".to_string();
     }
 
-    let term_width = get_terminal_width();
-
-    let mut output = String::new();
-    output.push_str(&format!("{}\n", "Pipelines".bold()));
-    output.push_str(&"─".repeat(term_width.min(120)));
-    output.push('\n');
+    let narrow = width < NARROW_WIDTH;
+    let mut table = if narrow {
+        Table::new(&["ID", "STATUS", "PROGRESS", "DURATION"])
+    } else {
+        Table::new(&["ID", "STATUS", "PROGRESS", "BRANCH", "PIPELINE", "DURATION"])
+    };
 
     for pipeline in pipelines {
-        let status_colored = match pipeline.status {
-            0 => "running".yellow().bold(),
-            1 => "success".green(),
-            2 => "failed".red().bold(),
-            3 => "aborted".red(),
-            _ => "unknown".dimmed(),
-        };
+        let status_colored = theme::label(pipeline.status).to_string();
 
         // Use first 8 chars of ID for display in header
-        let id_display = first_n_chars(&pipeline.id, 8);
+        let id_display = first_n_chars(&pipeline.id, 8).bold().to_string();
+
+        // Workflow progress, e.g. "3/5 ✓", colored by how the pipeline is going
+        let (succeeded, total) = pipeline.workflow_progress();
+        let progress = if total > 0 {
+            let text = format!("{}/{} {}", succeeded, total, theme::symbol(BuildStatus::Success));
+            let colored = if pipeline.workflows.iter().any(|wf| wf.status == BuildStatus::Failed) {
+                text.red()
+            } else if succeeded == total {
+                text.green()
+            } else {
+                text.yellow()
+            };
+            colored.to_string()
+        } else {
+            String::new()
+        };
 
-        // No truncation - show full branch and pipeline names
         let branch = pipeline.get_branch();
-        if !pipeline.pipeline_id.is_empty() {
-            output.push_str(&format!(
-                "{:<10} {:12} {} {} {}\n",
-                id_display.bold(),
-                status_colored,
-                branch,
-                pipeline.pipeline_id.dimmed(),
-                pipeline.duration_display().dimmed()
+        let duration = pipeline.duration_display().dimmed().to_string();
+        if narrow {
+            table.row(vec![id_display, status_colored, progress, duration]);
+            let name_width = width.saturating_sub(11 + pipeline.pipeline_id.len() + 1);
+            table.text(format!(
+                "           {} {}",
+                truncate_str(branch, name_width.max(10)),
+                pipeline.pipeline_id.dimmed()
             ));
         } else {
-            output.push_str(&format!(
-                "{:<10} {:12} {} {}\n",
-                id_display.bold(),
+            // Full branch and pipeline names, however long
+            table.row(vec![
+                id_display,
                 status_colored,
-                branch,
-                pipeline.duration_display().dimmed()
-            ));
+                progress,
+                branch.to_string(),
+                pipeline.pipeline_id.dimmed().to_string(),
+                duration,
+            ]);
         }
 
         // Show full ID prominently for easy copy-paste
-        output.push_str(&format!("           {} {}", "ID:".cyan(), pipeline.id));
+        let mut line = format!("           {} {}", "ID:".cyan(), pipeline.id);
 
         // Show triggered by
         if let Some(ref by) = pipeline.triggered_by {
-            output.push_str(&format!("  {} {}", "By:".cyan(), by.dimmed()));
+            line.push_str(&format!("  {} {}", "By:".cyan(), by.dimmed()));
         }
-        output.push('\n');
+        table.text(line);
 
         // Show workflow statuses for running/failed pipelines
         if pipeline.is_running() || pipeline.is_failed() {
             for wf in &pipeline.workflows {
-                let wf_status = match wf.status {
-                    0 => "●".yellow(),
-                    1 => "✓".green(),
-                    2 => "✗".red(),
-                    3 => "○".dimmed(),
-                    _ => "?".dimmed(),
-                };
-                output.push_str(&format!("           {} {}\n", wf_status, wf.name));
+                let wf_status = theme::icon(wf.status);
+                table.text(format!("           {} {}", wf_status, wf.name));
             }
         }
     }
 
-    output
+    listing("Pipelines", &table, width)
 }
 
 /// Format a single pipeline for pretty output
 pub fn format_pipeline(pipeline: &Pipeline) -> String {
+    format_pipeline_with_builds(pipeline, &[])
+}
+
+/// Format a pipeline with the build behind each workflow (same order as
+/// `pipeline.workflows`), showing build numbers, durations and links
+pub fn format_pipeline_with_builds(pipeline: &Pipeline, builds: &[Option<Build>]) -> String {
     let mut output = String::new();
 
-    let status_colored = match pipeline.status {
-        0 => format!("{}", "RUNNING".yellow().bold()),
-        1 => format!("{}", "SUCCESS".green().bold()),
-        2 => format!("{}", "FAILED".red().bold()),
-        3 => format!("{}", "ABORTED".red()),
-        _ => format!("{}", "UNKNOWN".dimmed()),
-    };
+    let status_colored = theme::heading(pipeline.status);
 
     // Use short ID in header
     let short_id = first_n_chars(&pipeline.id, 8);
     output.push_str(&format!("Pipeline {} {}\n", short_id.bold(), status_colored));
-    output.push_str(&"─".repeat(60));
+    output.push_str(&separator(60));
     output.push('\n');
 
     // Show full ID prominently for easy copy-paste
@@ -468,18 +764,22 @@ pub fn format_pipeline(pipeline: &Pipeline) -> String {
     // Show workflow statuses
     if !pipeline.workflows.is_empty() {
         output.push_str(&format!("\n{}\n", "Workflows".bold()));
-        output.push_str(&"─".repeat(40));
+        output.push_str(&separator(40));
         output.push('\n');
 
-        for wf in &pipeline.workflows {
-            let wf_status_colored = match wf.status {
-                0 => "running".yellow().bold(),
-                1 => "success".green(),
-                2 => "failed".red().bold(),
-                3 => "aborted".red(),
-                _ => "unknown".dimmed(),
-            };
-            output.push_str(&format!("  {} {:12}\n", wf.name, wf_status_colored));
+        for (i, wf) in pipeline.workflows.iter().enumerate() {
+            let wf_status_colored = theme::label(wf.status);
+            match builds.get(i).and_then(|b| b.as_ref()) {
+                Some(build) => output.push_str(&format!(
+                    "  {:20} {:12} #{:<6} {:>9}  {}\n",
+                    wf.name,
+                    wf_status_colored,
+                    build.build_number,
+                    build.duration_display(),
+                    format!("https://app.bitrise.io/build/{}", build.slug).dimmed()
+                )),
+                None => output.push_str(&format!("  {} {:12}\n", wf.name, wf_status_colored)),
+            }
         }
     }
 
@@ -514,7 +814,7 @@ pub fn format_artifacts(artifacts: &[Artifact]) -> String {
         artifacts.len(),
         if artifacts.len() == 1 { "" } else { "s" }
     ));
-    output.push_str(&"─".repeat(60));
+    output.push_str(&separator(60));
     output.push_str("\n\n");
 
     for artifact in artifacts {
@@ -538,13 +838,8 @@ pub fn format_artifacts(artifacts: &[Artifact]) -> String {
             output.push_str(&format!("    {} {}\n", "Type:".cyan(), artifact_type));
         }
 
-        // Show public page indicator and URL
-        if artifact.is_public_page_enabled {
-            output.push_str(&format!("    {} {}\n", "Public:".cyan(), "yes".green()));
-            if let Some(ref url) = artifact.public_install_page_url {
-                output.push_str(&format!("    {} {}\n", "Install URL:".cyan(), url));
-            }
-        }
+        output.push_str(&format_artifact_meta(artifact));
+        output.push_str(&format_artifact_expiry(artifact, None, Utc::now()));
 
         output.push('\n');
     }
@@ -552,6 +847,88 @@ pub fn format_artifacts(artifacts: &[Artifact]) -> String {
     output.trim_end().to_string()
 }
 
+/// Format APK/IPA app metadata lines (empty when Bitrise has none)
+/// When a build's artifacts are deleted under the plan's retention
+#[derive(Debug, Clone, Copy)]
+pub struct ArtifactRetention {
+    pub until: DateTime<Utc>,
+    /// Whether that is soon enough to warn about (`--warn-expiring`)
+    pub expiring: bool,
+}
+
+/// Public page status, download link lifetime and retention of an artifact
+pub fn format_artifact_expiry(
+    artifact: &Artifact,
+    retention: Option<ArtifactRetention>,
+    now: DateTime<Utc>,
+) -> String {
+    let mut output = String::new();
+    if artifact.is_public_page_enabled {
+        output.push_str(&format!("    {} {}\n", "Public:".cyan(), "yes".green()));
+        if let Some(ref url) = artifact.public_install_page_url {
+            output.push_str(&format!("    {} {}\n", "Install URL:".cyan(), url));
+        }
+    } else {
+        output.push_str(&format!("    {} {}\n", "Public:".cyan(), "no".dimmed()));
+    }
+
+    if let Some(expires) = artifact.download_url_expires_at() {
+        let when = if expires > now {
+            format!("in {}", format_span(expires - now))
+        } else {
+            format!("expired {} ago", format_span(now - expires)).dimmed().to_string()
+        };
+        output.push_str(&format!("    {} {}\n", "Link expires:".cyan(), when));
+    }
+
+    if let Some(retention) = retention {
+        let when = if retention.until > now {
+            format!(
+                "{} (in {})",
+                retention.until.format("%Y-%m-%d"),
+                format_span(retention.until - now)
+            )
+        } else {
+            format!("{} (past)", retention.until.format("%Y-%m-%d"))
+        };
+        let when = if retention.expiring { when.yellow().to_string() } else { when };
+        output.push_str(&format!("    {} {}\n", "Kept until:".cyan(), when));
+    }
+    output
+}
+
+/// A span of time in its largest whole unit, e.g. "3d", "5h", "42m"
+pub fn format_span(span: chrono::Duration) -> String {
+    if span.num_days() > 0 {
+        format!("{}d", span.num_days())
+    } else if span.num_hours() > 0 {
+        format!("{}h", span.num_hours())
+    } else {
+        format!("{}m", span.num_minutes().max(0))
+    }
+}
+
+pub fn format_artifact_meta(artifact: &Artifact) -> String {
+    let Some(info) = artifact.app_info() else {
+        return String::new();
+    };
+
+    let mut output = String::new();
+    if let Some(ref name) = info.app_name {
+        output.push_str(&format!("    {} {}\n", "App:".cyan(), name));
+    }
+    if let Some(version) = info.version_display() {
+        output.push_str(&format!("    {} {}\n", "Version:".cyan(), version));
+    }
+    if let Some(ref id) = info.package_name {
+        output.push_str(&format!("    {} {}\n", "Bundle ID:".cyan(), id));
+    }
+    if let Some(ref min) = info.min_sdk_version {
+        output.push_str(&format!("    {} {}\n", "Min SDK:".cyan(), min));
+    }
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -585,24 +962,19 @@ mod tests {
     fn make_test_build(slug: &str, build_number: i64, status: i32) -> Build {
         Build {
             slug: slug.to_string(),
-            triggered_at: Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
             started_on_worker_at: Some(Utc.with_ymd_and_hms(2024, 1, 1, 12, 1, 0).unwrap()),
             finished_at: Some(Utc.with_ymd_and_hms(2024, 1, 1, 12, 6, 30).unwrap()),
-            status,
+            status: BuildStatus::from_code(status),
             status_text: "test".to_string(),
-            abort_reason: None,
-            branch: "main".to_string(),
             build_number,
             commit_hash: Some("abc1234567890".to_string()),
             commit_message: Some("Test commit message".to_string()),
-            tag: None,
             triggered_workflow: "primary".to_string(),
             triggered_by: Some("manual".to_string()),
             stack_identifier: Some("osx-xcode-14.3".to_string()),
             machine_type_id: Some("g2-m1.4core".to_string()),
-            pull_request_id: None,
-            pull_request_target_branch: None,
             credit_cost: Some(10),
+            ..Build::fixture()
         }
     }
 
@@ -611,7 +983,7 @@ mod tests {
             id: id.to_string(),
             app_slug: "test-app".to_string(),
             app: None,
-            status,
+            status: BuildStatus::from_code(status),
             status_text: Some("test".to_string()),
             triggered_at: Some(Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap()),
             started_at: Some(Utc.with_ymd_and_hms(2024, 1, 1, 12, 1, 0).unwrap()),
@@ -634,6 +1006,7 @@ mod tests {
             is_public_page_enabled: false,
             expiring_download_url: None,
             public_install_page_url: None,
+            artifact_meta: None,
         }
     }
 
@@ -711,6 +1084,15 @@ mod tests {
         assert!(result.contains("Test User"));
     }
 
+    #[test]
+    fn test_format_apps_header_row() {
+        let apps = vec![make_test_app("slug1", "My App", false)];
+        let result = format_apps(&apps);
+        let lines: Vec<&str> = result.lines().collect();
+        assert!(lines[2].starts_with("APP     STATUS  SLUG   OWNER"));
+        assert!(lines[3].starts_with("My App  active  slug1  Test User"));
+    }
+
     #[test]
     fn test_format_apps_multiple() {
         let apps = vec![
@@ -782,6 +1164,147 @@ mod tests {
         assert!(result.contains("123"));
     }
 
+    /// Build with the given workflow and duration in seconds
+    fn make_timed_build(number: i64, workflow: &str, secs: i64, status: i32) -> Build {
+        let mut build = make_test_build(&format!("slug{}", number), number, status);
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        build.started_on_worker_at = Some(start);
+        build.finished_at = Some(start + chrono::Duration::seconds(secs));
+        build.triggered_workflow = workflow.to_string();
+        build
+    }
+
+    #[test]
+    fn test_duration_deltas_against_workflow_average() {
+        // Newest first
+        let builds = vec![
+            make_timed_build(4, "primary", 400, 1),
+            make_timed_build(3, "deploy", 50, 1),
+            make_timed_build(2, "primary", 300, 1),
+            make_timed_build(1, "primary", 200, 1),
+        ];
+        let deltas = duration_deltas(&builds, 5);
+        assert_eq!(deltas, vec![Some(150), None, Some(100), None]);
+    }
+
+    #[test]
+    fn test_duration_deltas_ignore_failed_and_running() {
+        let mut running = make_timed_build(3, "primary", 0, 0);
+        running.finished_at = None;
+        let builds = vec![
+            running,
+            make_timed_build(2, "primary", 30, 2),
+            make_timed_build(1, "primary", 100, 1),
+        ];
+        let deltas = duration_deltas(&builds, 5);
+        assert_eq!(deltas, vec![None, Some(-70), None]);
+    }
+
+    #[test]
+    fn test_duration_deltas_window() {
+        let builds = vec![
+            make_timed_build(3, "primary", 100, 1),
+            make_timed_build(2, "primary", 100, 1),
+            make_timed_build(1, "primary", 1000, 1),
+        ];
+        assert_eq!(duration_deltas(&builds, 1)[0], Some(0));
+    }
+
+    #[test]
+    fn test_format_duration_trend() {
+        let mut running = make_timed_build(4, "primary", 0, 0);
+        running.finished_at = None;
+        let builds = vec![
+            running,
+            make_timed_build(3, "primary", 400, 1),
+            make_timed_build(2, "primary", 130, 2),
+            make_timed_build(1, "primary", 200, 1),
+        ];
+        let trend = terminal::strip_ansi(&format_duration_trend(&builds).unwrap()).into_owned();
+        assert!(trend.starts_with("Durations: "));
+        assert!(trend.ends_with("(2m 10s to 6m 40s, oldest to newest)"));
+        assert_eq!(format_duration_trend(&builds[..2]), None);
+    }
+
+    #[test]
+    fn test_format_delta() {
+        assert_eq!(format_delta(130), "+2m 10s");
+        assert_eq!(format_delta(-45), "-45s");
+        assert_eq!(format_delta(0), "+0s");
+    }
+
+    #[test]
+    fn test_format_builds_with_deltas_shows_delta() {
+        let builds = vec![
+            make_timed_build(2, "primary", 330, 1),
+            make_timed_build(1, "primary", 200, 1),
+        ];
+        let notes = BuildNotes { deltas: delta_map(&builds), ..Default::default() };
+        let result = format_builds_annotated(&builds, &notes);
+        assert!(result.contains("+2m 10s"));
+        assert!(!format_builds(&builds).contains("+2m 10s"));
+    }
+
+    #[test]
+    fn test_format_build_groups_sections() {
+        let mut feature = make_test_build("slug2", 2, 1);
+        feature.branch = "feature/x".to_string();
+        let groups = vec![
+            ("main".to_string(), vec![make_test_build("slug1", 1, 1)]),
+            ("feature/x".to_string(), vec![feature]),
+        ];
+        let result = format_build_groups(&groups, &BuildNotes::default());
+        assert!(result.contains("main (1)"));
+        assert!(result.contains("feature/x (1)"));
+        assert!(result.find("slug1") < result.find("slug2"));
+        assert_eq!(result.matches("BUILD").count(), 1);
+    }
+
+    #[test]
+    fn test_format_builds_marks_head_relation() {
+        let builds = vec![
+            make_test_build("slug3", 3, 1),
+            make_test_build("slug2", 2, 1),
+            make_test_build("slug1", 1, 1),
+        ];
+        let notes = BuildNotes {
+            head: HashMap::from([
+                ("slug3".to_string(), HeadRelation::IncludesHead),
+                ("slug2".to_string(), HeadRelation::Head),
+                ("slug1".to_string(), HeadRelation::BehindHead),
+            ]),
+            ..Default::default()
+        };
+        let rendered = format_builds_annotated(&builds, &notes);
+        let result = terminal::strip_ansi(&rendered);
+        assert!(result.contains("slug3  ↑ includes HEAD"));
+        assert!(result.contains("slug2  ● HEAD"));
+        assert!(result.contains("slug1  ↓ behind HEAD"));
+        assert!(!format_builds(&builds).contains("HEAD"));
+    }
+
+    #[test]
+    fn test_delta_map_keyed_by_slug() {
+        let builds = vec![
+            make_timed_build(2, "primary", 330, 1),
+            make_timed_build(1, "primary", 200, 1),
+        ];
+        let map = delta_map(&builds);
+        assert_eq!(map.get("slug2"), Some(&130));
+        assert!(!map.contains_key("slug1"));
+    }
+
+    #[test]
+    fn test_format_build_line() {
+        let build = make_test_build("slug1", 123, 2);
+        let result = format_build_line(&build);
+        assert!(result.contains("#123"));
+        assert!(result.contains("failed"));
+        assert!(result.contains("abc1234"));
+        assert!(!result.contains("abc12345"));
+        assert!(!result.contains('\n'));
+    }
+
     #[test]
     fn test_format_builds_contains_slug() {
         let builds = vec![make_test_build("build-slug-123", 1, 1)];
@@ -812,6 +1335,51 @@ mod tests {
         assert!(result.contains("42"));
     }
 
+    #[test]
+    fn test_builds_listing_wide_keeps_one_line_per_build() {
+        let mut build = make_test_build("slug1", 7, 1);
+        build.branch = "feature/a-very-long-branch-name-that-keeps-going".to_string();
+        let result = builds_listing(&[build], &BuildNotes::default(), 140);
+        let row = result.lines().find(|l| l.starts_with("#7")).unwrap();
+        assert!(row.contains("feature/a-very-long-branch-name-that-keeps-going  primary"));
+        assert_eq!(result.lines().nth(1).unwrap().chars().count(), 140);
+    }
+
+    #[test]
+    fn test_builds_listing_header_labels_columns() {
+        let result = builds_listing(&[make_test_build("slug1", 7, 1)], &BuildNotes::default(), 120);
+        let lines: Vec<&str> = result.lines().collect();
+        assert!(lines[2].starts_with("BUILD  STATUS   BRANCH  WORKFLOW  DURATION"));
+        // Columns line up under their headers
+        assert_eq!(lines[3].find("success"), lines[2].find("STATUS"));
+        assert_eq!(lines[3].find("main"), lines[2].find("BRANCH"));
+    }
+
+    #[test]
+    fn test_builds_listing_narrow_stacks_and_truncates() {
+        let mut build = make_test_build("slug1", 7, 1);
+        build.branch = "feature/a-very-long-branch-name-that-keeps-going".to_string();
+        let result = builds_listing(&[build], &BuildNotes::default(), 50);
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines[1].chars().count(), 50);
+        assert!(lines[2].starts_with("BUILD  STATUS   DURATION"));
+        assert!(lines[3].starts_with("#7"));
+        assert!(!lines[3].contains("feature"));
+        assert!(lines[4].starts_with("        feature/"));
+        assert!(lines[4].contains("... primary"));
+        assert!(lines.iter().all(|l| l.chars().count() <= 50));
+    }
+
+    #[test]
+    fn test_builds_listing_commit_preview_fits_width() {
+        let mut build = make_test_build("slug1", 7, 2);
+        build.commit_message = Some("x".repeat(200));
+        let narrow = builds_listing(&[build.clone()], &BuildNotes::default(), 60);
+        assert!(narrow.contains(&format!("{}...", "x".repeat(49))));
+        let wide = builds_listing(&[build], &BuildNotes::default(), 150);
+        assert!(wide.contains(&format!("{}...", "x".repeat(139))));
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // format_build Tests
     // ─────────────────────────────────────────────────────────────────────────
@@ -868,6 +1436,45 @@ mod tests {
         assert!(result.contains("develop"));
     }
 
+    #[test]
+    fn test_format_build_shows_trigger_params_with_secrets_masked() {
+        let mut build = make_test_build("slug1", 1, 1);
+        build.original_build_params = Some(serde_json::json!({
+            "branch": "main",
+            "workflow_id": "primary",
+            "tag": "v1.0",
+            "stack": "osx-xcode-15",
+            "environments": [
+                {"mapped_to": "FLAVOR", "value": "beta", "is_expand": true},
+                {"mapped_to": "API_TOKEN", "value": "tok-123", "is_expand": true},
+                {"mapped_to": "SIGNING", "value": "cert-pem", "is_sensitive": true}
+            ]
+        }));
+        let rendered = format_build(&build);
+        let result = terminal::strip_ansi(&rendered);
+        assert!(result.contains("Trigger Parameters:"));
+        assert!(result.contains("  stack        osx-xcode-15"));
+        assert!(result.contains("  FLAVOR       beta"));
+        assert!(result.contains("  API_TOKEN    ****"));
+        assert!(result.contains("  SIGNING      ****"));
+        assert!(!result.contains("tok-123"));
+        assert!(!result.contains("cert-pem"));
+    }
+
+    #[test]
+    fn test_format_build_without_trigger_params() {
+        let build = make_test_build("slug1", 1, 1);
+        assert!(!format_build(&build).contains("Trigger Parameters"));
+    }
+
+    #[test]
+    fn test_is_secret_name() {
+        assert!(is_secret_name("GITHUB_TOKEN"));
+        assert!(is_secret_name("keystore_password"));
+        assert!(is_secret_name("AWS_ACCESS_KEY_ID"));
+        assert!(!is_secret_name("FLAVOR"));
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // format_pipelines Tests
     // ─────────────────────────────────────────────────────────────────────────
@@ -906,13 +1513,13 @@ mod tests {
             PipelineWorkflow {
                 id: "wf1".to_string(),
                 name: "build".to_string(),
-                status: 1,
+                status: BuildStatus::Success,
                 status_text: Some("success".to_string()),
             },
             PipelineWorkflow {
                 id: "wf2".to_string(),
                 name: "test".to_string(),
-                status: 0,
+                status: BuildStatus::Running,
                 status_text: Some("running".to_string()),
             },
         ];
@@ -921,6 +1528,61 @@ mod tests {
         assert!(result.contains("test"));
     }
 
+    #[test]
+    fn test_format_pipelines_shows_workflow_progress() {
+        let mut pipeline = make_test_pipeline("id1", 0);
+        pipeline.workflows = ["build", "test", "deploy"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| PipelineWorkflow {
+                id: format!("wf{}", i),
+                name: name.to_string(),
+                status: if i == 0 { BuildStatus::Success } else { BuildStatus::Running },
+                status_text: None,
+            })
+            .collect();
+        let result = format_pipelines(&[pipeline]);
+        assert!(result.contains("1/3 ✓"));
+    }
+
+    #[test]
+    fn test_pipelines_listing_header_labels_columns() {
+        let wide = pipelines_listing(&[make_test_pipeline("id1", 1)], 120);
+        let header = wide.lines().nth(2).unwrap();
+        assert!(header.starts_with("ID   STATUS   PROGRESS  BRANCH"));
+        assert!(header.contains("PIPELINE"));
+        let narrow = pipelines_listing(&[make_test_pipeline("id1", 1)], 60);
+        assert!(!narrow.lines().nth(2).unwrap().contains("BRANCH"));
+    }
+
+    #[test]
+    fn test_format_pipelines_hides_progress_without_workflows() {
+        let result = format_pipelines(&[make_test_pipeline("id1", 1)]);
+        assert!(!result.contains("✓"));
+    }
+
+    #[test]
+    fn test_pipelines_listing_narrow_stacks_names() {
+        let mut pipeline = make_test_pipeline("abcdef123456", 1);
+        pipeline.branch = "release/2024-01-01-with-a-long-suffix".to_string();
+        let result = pipelines_listing(&[pipeline], 40);
+        let lines: Vec<&str> = result.lines().collect();
+        assert!(lines[3].starts_with("abcdef12"));
+        assert!(!lines[3].contains("release"));
+        assert!(lines[4].trim_start().starts_with("release/"));
+        assert!(lines[4].ends_with("build-and-test"));
+        assert!(lines.iter().take(5).all(|l| l.chars().count() <= 40));
+    }
+
+    #[test]
+    fn test_format_pipeline_line() {
+        let pipeline = make_test_pipeline("abcdef123456", 2);
+        let result = format_pipeline_line(&pipeline);
+        assert!(result.contains("abcdef1"));
+        assert!(!result.contains("abcdef12"));
+        assert!(result.contains("failed"));
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // format_pipeline Tests
     // ─────────────────────────────────────────────────────────────────────────
@@ -953,7 +1615,7 @@ mod tests {
             PipelineWorkflow {
                 id: "wf1".to_string(),
                 name: "build-workflow".to_string(),
-                status: 1,
+                status: BuildStatus::Success,
                 status_text: Some("success".to_string()),
             },
         ];
@@ -962,6 +1624,24 @@ mod tests {
         assert!(result.contains("Workflows"));
     }
 
+    #[test]
+    fn test_format_pipeline_with_builds_shows_build_details() {
+        let mut pipeline = make_test_pipeline("id1", 2);
+        let workflow = |id: &str, name: &str, status: i32| PipelineWorkflow {
+            id: id.to_string(),
+            name: name.to_string(),
+            status: BuildStatus::from_code(status),
+            status_text: None,
+        };
+        pipeline.workflows = vec![workflow("b1", "unit-tests", 2), workflow("", "deploy", 3)];
+
+        let builds = vec![Some(make_test_build("b1", 321, 2)), None];
+        let result = format_pipeline_with_builds(&pipeline, &builds);
+        assert!(result.contains("#321"));
+        assert!(result.contains("https://app.bitrise.io/build/b1"));
+        assert!(result.contains("deploy"));
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // format_artifacts Tests
     // ─────────────────────────────────────────────────────────────────────────
@@ -1003,6 +1683,51 @@ mod tests {
         assert!(result.contains("https://install.example.com"));
     }
 
+    #[test]
+    fn test_format_artifact_expiry() {
+        let now = Utc::now();
+        let mut artifact = make_test_artifact("art1", "app.ipa", Some(1024));
+        let stamp = (now - chrono::Duration::minutes(30)).format("%Y%m%dT%H%M%SZ");
+        artifact.expiring_download_url = Some(format!(
+            "https://example.com/app.ipa?X-Amz-Date={}&X-Amz-Expires=7200",
+            stamp
+        ));
+        let retention = ArtifactRetention {
+            until: now + chrono::Duration::days(3) + chrono::Duration::minutes(1),
+            expiring: true,
+        };
+
+        let rendered = format_artifact_expiry(&artifact, Some(retention), now);
+        let result = terminal::strip_ansi(&rendered);
+        assert!(result.contains("Public: no"), "{}", result);
+        assert!(result.contains("Link expires: in 1h"), "{}", result);
+        assert!(result.contains("(in 3d)"), "{}", result);
+    }
+
+    #[test]
+    fn test_format_artifacts_shows_app_meta() {
+        let mut artifact = make_test_artifact("art1", "app.apk", Some(1024));
+        artifact.artifact_meta = Some(crate::bitrise::ArtifactMeta {
+            app_info: Some(crate::bitrise::ArtifactAppInfo {
+                package_name: Some("com.example.demo".to_string()),
+                version_name: Some("1.4.0".to_string()),
+                version_code: Some("212".to_string()),
+                min_sdk_version: Some("24".to_string()),
+                ..Default::default()
+            }),
+        });
+        let result = format_artifacts(&[artifact]);
+        assert!(result.contains("com.example.demo"));
+        assert!(result.contains("1.4.0 (212)"));
+        assert!(result.contains("Min SDK:"));
+    }
+
+    #[test]
+    fn test_format_artifact_meta_empty_without_info() {
+        let artifact = make_test_artifact("art1", "logs.txt", None);
+        assert!(format_artifact_meta(&artifact).is_empty());
+    }
+
     #[test]
     fn test_format_artifacts_count_singular() {
         let artifacts = vec![make_test_artifact("art1", "app.ipa", Some(1024))];
@@ -0,0 +1,288 @@
+//! Live status line for long-running waits
+//!
+//! On a terminal the line is redrawn in place on stderr with a spinner,
+//! elapsed time and the current step, so watching a build doesn't scroll the
+//! screen. Given the workflow's usual duration, it also shows how far along
+//! the build is and when it should finish. When stderr is not a terminal,
+//! each distinct message and step is printed once as a plain line. With
+//! `--quiet` nothing is shown at all.
+
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local, Utc};
+use colored::Colorize;
+use is_terminal::IsTerminal;
+use terminal_size::{terminal_size_of, Width};
+
+use super::terminal;
+use crate::eta;
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+const TICK: Duration = Duration::from_millis(100);
+
+/// What the ticker thread draws
+struct State {
+    message: String,
+    /// Seconds already elapsed when `since` was taken
+    offset_secs: u64,
+    since: Instant,
+    /// Running step and when it was first seen
+    step: Option<(String, Instant)>,
+    /// How long the build usually takes
+    expected: Option<Duration>,
+}
+
+impl State {
+    fn elapsed_secs(&self) -> u64 {
+        self.offset_secs + self.since.elapsed().as_secs()
+    }
+
+    fn render(&self, frame: usize) -> String {
+        let step = self
+            .step
+            .as_ref()
+            .map(|(name, since)| (name.as_str(), since.elapsed().as_secs()));
+        let elapsed_secs = self.elapsed_secs();
+        let message = match self.expected {
+            Some(expected) => format!(
+                "{} {} ·{}",
+                self.message,
+                format_elapsed(elapsed_secs),
+                format_eta(elapsed_secs, expected, Local::now())
+            ),
+            None => format!("{} {}", self.message, format_elapsed(elapsed_secs)),
+        };
+        render(frame, &message, step)
+    }
+}
+
+/// A status line that updates in place on a TTY
+pub struct StatusLine {
+    state: Arc<Mutex<State>>,
+    running: Arc<AtomicBool>,
+    ticker: Option<JoinHandle<()>>,
+    last_plain: Option<String>,
+    /// `--quiet`: neither animate nor print plain lines
+    silent: bool,
+}
+
+impl StatusLine {
+    /// Start a status line, animating it when stderr is a terminal
+    pub fn start(message: &str) -> Self {
+        let silent = terminal::quiet();
+        let state = Arc::new(Mutex::new(State {
+            message: message.to_string(),
+            offset_secs: 0,
+            since: Instant::now(),
+            step: None,
+            expected: None,
+        }));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let ticker = (!silent && io::stderr().is_terminal()).then(|| {
+            let state = Arc::clone(&state);
+            let running = Arc::clone(&running);
+            thread::spawn(move || {
+                let mut frame = 0;
+                while running.load(Ordering::SeqCst) {
+                    if let Ok(state) = state.lock() {
+                        draw(&state.render(frame));
+                    }
+                    frame += 1;
+                    thread::sleep(TICK);
+                }
+            })
+        });
+
+        let mut line = Self {
+            state,
+            running,
+            ticker,
+            last_plain: None,
+            silent,
+        };
+        line.print_plain(message);
+        line
+    }
+
+    /// Replace the message shown after the spinner
+    pub fn set_message(&mut self, message: &str) {
+        if let Ok(mut state) = self.state.lock() {
+            state.message = message.to_string();
+        }
+        self.print_plain(message);
+    }
+
+    /// Count elapsed time from when the build or pipeline actually started
+    pub fn set_started_at(&mut self, started_at: DateTime<Utc>) {
+        let offset = (Utc::now() - started_at).num_seconds().max(0) as u64;
+        if let Ok(mut state) = self.state.lock() {
+            state.offset_secs = offset;
+            state.since = Instant::now();
+        }
+    }
+
+    /// Show progress and a finish time against how long the build usually takes
+    pub fn set_expected(&mut self, expected: Option<Duration>) {
+        if let Ok(mut state) = self.state.lock() {
+            state.expected = expected;
+        }
+    }
+
+    /// Show the step that is running now; its timer restarts when it changes
+    pub fn set_step(&mut self, step: Option<&str>) {
+        let changed = match self.state.lock() {
+            Ok(mut state) => {
+                let changed = state.step.as_ref().map(|(name, _)| name.as_str()) != step;
+                if changed {
+                    state.step = step.map(|name| (name.to_string(), Instant::now()));
+                }
+                changed
+            }
+            Err(_) => false,
+        };
+        if changed && self.ticker.is_none() && !self.silent {
+            if let Some(name) = step {
+                eprintln!("{} Step: {}", "->".cyan(), name);
+            }
+        }
+    }
+
+    /// Run `f` (e.g. printing log lines) with the status line erased, then redraw it
+    pub fn hide_while<T>(&self, f: impl FnOnce() -> T) -> T {
+        match self.state.lock() {
+            Ok(state) if self.ticker.is_some() => {
+                clear();
+                let result = f();
+                draw(&state.render(0));
+                result
+            }
+            _ => f(),
+        }
+    }
+
+    /// Stop animating and erase the line
+    pub fn finish(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(ticker) = self.ticker.take() {
+            ticker.join().ok();
+            clear();
+        }
+    }
+
+    /// Without a terminal, print each new message once
+    fn print_plain(&mut self, message: &str) {
+        if self.ticker.is_none() && !self.silent && self.last_plain.as_deref() != Some(message) {
+            eprintln!("{} {}", "->".cyan(), message);
+            self.last_plain = Some(message.to_string());
+        }
+    }
+}
+
+impl Drop for StatusLine {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Render one frame: spinner, message with elapsed time, and the running step
+/// with its own elapsed time, cut to the terminal width
+fn render(frame: usize, message: &str, step: Option<(&str, u64)>) -> String {
+    let width = terminal_size_of(io::stderr())
+        .map(|(Width(w), _)| w as usize)
+        .unwrap_or(100);
+    let mut text = message.to_string();
+    if let Some((name, step_secs)) = step {
+        text.push_str(&format!(" · step {} {}", name, format_elapsed(step_secs)));
+    }
+    let text: String = text.chars().take(width.saturating_sub(3)).collect();
+    format!("{} {}", SPINNER[frame % SPINNER.len()].to_string().cyan(), text)
+}
+
+/// Progress against the usual duration, e.g. ` ~40% · ETA 14:32`, or
+/// ` 2m 10s over usual` once the build runs longer than that
+fn format_eta(elapsed_secs: u64, expected: Duration, now: DateTime<Local>) -> String {
+    let elapsed = Duration::from_secs(elapsed_secs);
+    match expected.checked_sub(elapsed) {
+        Some(remaining) => {
+            let finish = now + chrono::Duration::seconds(remaining.as_secs() as i64);
+            format!(
+                " ~{}% · ETA {}",
+                eta::percent_done(elapsed, expected),
+                finish.format("%H:%M")
+            )
+        }
+        None => format!(" {} over usual", format_elapsed((elapsed - expected).as_secs())),
+    }
+}
+
+/// Format elapsed seconds as `45s`, `3m 05s` or `1h 02m`
+pub fn format_elapsed(secs: u64) -> String {
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
+
+fn draw(line: &str) {
+    let mut stderr = io::stderr();
+    write!(stderr, "\r\x1b[2K{}", line).ok();
+    stderr.flush().ok();
+}
+
+fn clear() {
+    let mut stderr = io::stderr();
+    write!(stderr, "\r\x1b[2K").ok();
+    stderr.flush().ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::terminal::strip_ansi;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(0), "0s");
+        assert_eq!(format_elapsed(45), "45s");
+        assert_eq!(format_elapsed(185), "3m 05s");
+        assert_eq!(format_elapsed(3720), "1h 02m");
+    }
+
+    #[test]
+    fn test_render_includes_message_and_elapsed() {
+        let line = render(1, "Build #12 running 1m 15s", None);
+        assert_eq!(strip_ansi(&line), "⠙ Build #12 running 1m 15s");
+    }
+
+    #[test]
+    fn test_render_includes_current_step() {
+        let line = render(0, "Build #12 running 1m 15s", Some(("xcode-test@5", 8)));
+        assert_eq!(
+            strip_ansi(&line),
+            "⠋ Build #12 running 1m 15s · step xcode-test@5 8s"
+        );
+    }
+
+    #[test]
+    fn test_format_eta() {
+        let now = Local.with_ymd_and_hms(2024, 1, 1, 14, 0, 0).unwrap();
+        let expected = Duration::from_secs(600);
+        assert_eq!(format_eta(240, expected, now), " ~40% · ETA 14:06");
+        assert_eq!(format_eta(730, expected, now), " 2m 10s over usual");
+    }
+
+    #[test]
+    fn test_render_cycles_spinner() {
+        let line = render(SPINNER.len(), "x", None);
+        assert!(strip_ansi(&line).starts_with(SPINNER[0]));
+    }
+}
@@ -0,0 +1,174 @@
+//! Column layout for pretty list outputs
+//!
+//! Builds, pipelines and apps listings lay out one row per item through
+//! [`Table`], which sizes each column to its widest cell, pads by visible
+//! width (color codes don't count) and starts with a header row naming the
+//! columns. `--no-header` drops the title and header rows, leaving only the
+//! items for `grep` and `awk`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use colored::Colorize;
+
+use super::terminal::strip_ansi;
+
+/// Space between two columns
+const GAP: &str = "  ";
+
+static HIDE_HEADERS: AtomicBool = AtomicBool::new(false);
+
+/// Leave out list titles and column headers (`--no-header`)
+pub fn hide_headers() {
+    HIDE_HEADERS.store(true, Ordering::Relaxed);
+}
+
+/// Whether list titles and column headers are printed
+pub fn headers_shown() -> bool {
+    !HIDE_HEADERS.load(Ordering::Relaxed)
+}
+
+/// A line of a table: a row of cells, or free text such as an item's
+/// detail lines or a group heading
+enum Line {
+    Cells(Vec<String>),
+    Text(String),
+}
+
+/// Rows of cells under column headers
+pub struct Table {
+    headers: Vec<String>,
+    lines: Vec<Line>,
+}
+
+impl Table {
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            lines: Vec::new(),
+        }
+    }
+
+    /// Add a row; cells may be colored
+    pub fn row(&mut self, cells: Vec<String>) {
+        self.lines.push(Line::Cells(cells));
+    }
+
+    /// Add a line printed as-is, e.g. details under the previous row
+    pub fn text(&mut self, line: impl Into<String>) {
+        self.lines.push(Line::Text(line.into()));
+    }
+
+    /// Render the header row (unless hidden) and all lines
+    pub fn render(&self) -> String {
+        self.render_with(headers_shown())
+    }
+
+    fn render_with(&self, header: bool) -> String {
+        let widths = self.column_widths(header);
+        let mut output = String::new();
+        if header {
+            output.push_str(&format!("{}\n", pad_row(&self.headers, &widths).dimmed()));
+        }
+        for line in &self.lines {
+            match line {
+                Line::Cells(cells) => output.push_str(&pad_row(cells, &widths)),
+                Line::Text(text) => output.push_str(text),
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    /// Widest cell of each column, or its header if printed and wider
+    fn column_widths(&self, header: bool) -> Vec<usize> {
+        let mut widths: Vec<usize> = self
+            .headers
+            .iter()
+            .map(|h| if header { h.chars().count() } else { 0 })
+            .collect();
+        for line in &self.lines {
+            if let Line::Cells(cells) = line {
+                for (width, cell) in widths.iter_mut().zip(cells) {
+                    *width = (*width).max(visible_width(cell));
+                }
+            }
+        }
+        widths
+    }
+}
+
+/// Cells padded to their column widths; the last cell isn't padded
+fn pad_row(cells: &[String], widths: &[usize]) -> String {
+    let last = cells.len().saturating_sub(1);
+    let padded: Vec<String> = cells
+        .iter()
+        .zip(widths)
+        .enumerate()
+        .map(|(i, (cell, &width))| {
+            let fill = if i == last { 0 } else { width.saturating_sub(visible_width(cell)) };
+            format!("{}{}", cell, " ".repeat(fill))
+        })
+        .collect();
+    padded.join(GAP).trim_end().to_string()
+}
+
+/// Columns a cell takes on screen, ignoring color codes
+fn visible_width(cell: &str) -> usize {
+    strip_ansi(cell).chars().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_columns_sized_to_widest_cell() {
+        let mut table = Table::new(&["ID", "NAME", "STATE"]);
+        table.row(cells(&["1", "primary", "ok"]));
+        table.row(cells(&["1234", "ci", "failed"]));
+        assert_eq!(
+            table.render_with(true),
+            "ID    NAME     STATE\n1     primary  ok\n1234  ci       failed\n"
+        );
+    }
+
+    #[test]
+    fn test_header_can_be_left_out() {
+        let mut table = Table::new(&["ID", "NAME"]);
+        table.row(cells(&["1", "primary"]));
+        assert_eq!(table.render_with(false), "1  primary\n");
+    }
+
+    #[test]
+    fn test_text_lines_kept_as_is() {
+        let mut table = Table::new(&["ID", "NAME"]);
+        table.row(cells(&["1", "primary"]));
+        table.text("    a detail line that is longer than the row");
+        table.row(cells(&["22", "ci"]));
+        let rendered = table.render_with(false);
+        assert_eq!(
+            rendered,
+            "1   primary\n    a detail line that is longer than the row\n22  ci\n"
+        );
+    }
+
+    #[test]
+    fn test_colored_cells_padded_by_visible_width() {
+        let mut table = Table::new(&["STATUS", "ID"]);
+        table.row(vec!["\x1b[32mok\x1b[0m".to_string(), "1".to_string()]);
+        table.row(cells(&["failed", "2"]));
+        let rendered = table.render_with(false);
+        assert_eq!(strip_ansi(&rendered), "ok      1\nfailed  2\n");
+    }
+
+    #[test]
+    fn test_trailing_empty_cells_trimmed() {
+        let mut table = Table::new(&["ID", "NOTE"]);
+        table.row(cells(&["1", ""]));
+        assert_eq!(table.render_with(false), "1\n");
+    }
+}
@@ -0,0 +1,238 @@
+//! Terminal detection and ANSI escape handling
+//!
+//! Everything that depends on whether stdout is a terminal lives here, so
+//! commands ask one place instead of probing the TTY themselves.
+
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use is_terminal::IsTerminal;
+use terminal_size::{terminal_size, Width};
+
+/// Columns assumed when stdout isn't a terminal and no width is set
+const DEFAULT_WIDTH: usize = 100;
+
+static WIDTH: OnceLock<usize> = OnceLock::new();
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+static NON_INTERACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Whether stdout is attached to a terminal
+pub fn stdout_is_tty() -> bool {
+    std::io::stdout().is_terminal()
+}
+
+/// Whether stdin is attached to a terminal, i.e. someone can answer a prompt
+pub fn stdin_is_tty() -> bool {
+    std::io::stdin().is_terminal()
+}
+
+/// Whether reprise should emit its own colors
+///
+/// Respects `NO_COLOR` (https://no-color.org/) and disables colors when
+/// stdout is piped.
+pub fn colors_enabled() -> bool {
+    std::env::var("NO_COLOR").is_err() && stdout_is_tty()
+}
+
+/// Lay out pretty output for a fixed number of columns (`--width`)
+pub fn set_width(columns: usize) {
+    let _ = WIDTH.set(columns);
+}
+
+/// Columns pretty output is laid out for: `--width` if given, else the
+/// terminal's width, else 100 (e.g. when piped)
+pub fn width() -> usize {
+    WIDTH
+        .get()
+        .copied()
+        .or_else(|| terminal_size().map(|(Width(w), _)| w as usize))
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+/// Keep stderr to final results and errors (`--quiet`)
+pub fn set_quiet() {
+    QUIET.store(true, Ordering::Relaxed);
+}
+
+/// Whether spinners, progress lines and hints are left out (`--quiet`)
+pub fn quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Never wait on stdin for an answer (`--non-interactive`)
+pub fn set_non_interactive() {
+    NON_INTERACTIVE.store(true, Ordering::Relaxed);
+}
+
+/// Whether reprise may prompt on stdin: not with `--non-interactive`, nor
+/// in CI (`CI` set to anything but `false` or `0`)
+pub fn interactive() -> bool {
+    !NON_INTERACTIVE.load(Ordering::Relaxed) && !is_ci(std::env::var("CI").ok().as_deref())
+}
+
+fn is_ci(value: Option<&str>) -> bool {
+    value.is_some_and(|v| !v.is_empty() && v != "0" && !v.eq_ignore_ascii_case("false"))
+}
+
+/// Apply the global color setting to the `colored` crate
+pub fn configure_colors() {
+    if !colors_enabled() {
+        colored::control::set_override(false);
+    }
+}
+
+/// How ANSI escape codes embedded in build logs are handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnsiMode {
+    /// Pass escape codes through untouched
+    Preserve,
+    /// Remove escape codes, leaving plain text
+    Strip,
+}
+
+impl AnsiMode {
+    /// Pick the mode for log output
+    ///
+    /// `raw` always preserves; `strip` always strips; otherwise codes are
+    /// stripped only when stdout isn't a terminal.
+    pub fn for_log(strip: bool, raw: bool) -> Self {
+        Self::resolve(strip, raw, stdout_is_tty())
+    }
+
+    fn resolve(strip: bool, raw: bool, is_tty: bool) -> Self {
+        if raw {
+            Self::Preserve
+        } else if strip || !is_tty {
+            Self::Strip
+        } else {
+            Self::Preserve
+        }
+    }
+
+    /// Apply the mode to a piece of text
+    pub fn apply(self, text: &str) -> Cow<'_, str> {
+        match self {
+            Self::Preserve => Cow::Borrowed(text),
+            Self::Strip => strip_ansi(text),
+        }
+    }
+}
+
+/// Remove ANSI escape sequences (CSI, OSC and two-byte escapes)
+pub fn strip_ansi(text: &str) -> Cow<'_, str> {
+    if !text.contains('\x1b') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            // CSI: ESC [ params... final byte in @..~
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: ESC ] ... terminated by BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Two-byte escape (or a lone trailing ESC)
+            _ => {}
+        }
+    }
+
+    Cow::Owned(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_ci() {
+        assert!(is_ci(Some("true")));
+        assert!(is_ci(Some("1")));
+        assert!(!is_ci(Some("false")));
+        assert!(!is_ci(Some("0")));
+        assert!(!is_ci(Some("")));
+        assert!(!is_ci(None));
+    }
+
+    #[test]
+    fn test_strip_ansi_plain_text_borrowed() {
+        let text = "no escapes here";
+        assert!(matches!(strip_ansi(text), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_strip_ansi_sgr_codes() {
+        let text = "\x1b[31;1merror\x1b[0m: build failed";
+        assert_eq!(strip_ansi(text), "error: build failed");
+    }
+
+    #[test]
+    fn test_strip_ansi_cursor_and_erase() {
+        assert_eq!(strip_ansi("\x1b[2K\x1b[1Gprogress 50%"), "progress 50%");
+    }
+
+    #[test]
+    fn test_strip_ansi_osc_hyperlink() {
+        let text = "\x1b]8;;https://bitrise.io\x07link\x1b]8;;\x1b\\ done";
+        assert_eq!(strip_ansi(text), "link done");
+    }
+
+    #[test]
+    fn test_strip_ansi_keeps_unicode() {
+        assert_eq!(strip_ansi("\x1b[32m✓ passed\x1b[0m"), "✓ passed");
+    }
+
+    #[test]
+    fn test_strip_ansi_trailing_escape() {
+        assert_eq!(strip_ansi("text\x1b"), "text");
+    }
+
+    #[test]
+    fn test_ansi_mode_raw_always_preserves() {
+        assert_eq!(AnsiMode::resolve(false, true, false), AnsiMode::Preserve);
+        assert_eq!(AnsiMode::resolve(true, true, true), AnsiMode::Preserve);
+    }
+
+    #[test]
+    fn test_ansi_mode_strips_when_piped() {
+        assert_eq!(AnsiMode::resolve(false, false, false), AnsiMode::Strip);
+        assert_eq!(AnsiMode::resolve(false, false, true), AnsiMode::Preserve);
+    }
+
+    #[test]
+    fn test_ansi_mode_strip_flag_on_tty() {
+        assert_eq!(AnsiMode::resolve(true, false, true), AnsiMode::Strip);
+    }
+
+    #[test]
+    fn test_ansi_mode_apply() {
+        let text = "\x1b[33mwarn\x1b[0m";
+        assert_eq!(AnsiMode::Preserve.apply(text), text);
+        assert_eq!(AnsiMode::Strip.apply(text), "warn");
+    }
+}
@@ -0,0 +1,161 @@
+//! Status icons and colors for pretty output
+//!
+//! Pretty formatters and watch loops style build, pipeline and workflow
+//! statuses through this module, so the `[theme]` config applies
+//! everywhere: per-status colors (`success = "bright green"`) and ASCII
+//! icons for terminals that can't show Unicode symbols (`ascii = true`).
+
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use colored::{Color, ColoredString, Colorize};
+
+use crate::bitrise::BuildStatus;
+use crate::config::{Config, ThemeConfig};
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Resolved `[theme]` settings
+#[derive(Debug, Clone, Default)]
+struct Theme {
+    running: Option<Color>,
+    success: Option<Color>,
+    failed: Option<Color>,
+    aborted: Option<Color>,
+    ascii: bool,
+}
+
+impl Theme {
+    /// Resolve the configured color names; unknown names keep the default
+    fn from_config(config: &ThemeConfig) -> Self {
+        let color = |name: &Option<String>| name.as_deref().and_then(|n| Color::from_str(n).ok());
+        Self {
+            running: color(&config.running),
+            success: color(&config.success),
+            failed: color(&config.failed),
+            aborted: color(&config.aborted),
+            ascii: config.ascii,
+        }
+    }
+
+    fn color(&self, status: BuildStatus) -> Option<Color> {
+        match status {
+            BuildStatus::Running => self.running,
+            BuildStatus::Success => self.success,
+            BuildStatus::Failed => self.failed,
+            BuildStatus::Aborted | BuildStatus::AbortedWithSuccess => self.aborted,
+            BuildStatus::Unknown(_) => None,
+        }
+    }
+
+    fn paint(&self, status: BuildStatus, text: &str) -> ColoredString {
+        match self.color(status) {
+            // Running and failed stand out in bold whatever their color
+            Some(color) if matches!(status, BuildStatus::Running | BuildStatus::Failed) => {
+                text.color(color).bold()
+            }
+            Some(color) => text.color(color),
+            None => status.colorize(text),
+        }
+    }
+
+    fn symbol(&self, status: BuildStatus) -> &'static str {
+        if !self.ascii {
+            return status.icon();
+        }
+        match status {
+            BuildStatus::Running => "*",
+            BuildStatus::Success => "+",
+            BuildStatus::Failed => "x",
+            BuildStatus::Aborted | BuildStatus::AbortedWithSuccess => "-",
+            BuildStatus::Unknown(_) => "?",
+        }
+    }
+}
+
+/// Apply the `[theme]` config to all pretty output of this process
+pub fn configure(config: &Config) {
+    let _ = THEME.set(Theme::from_config(&config.theme));
+}
+
+fn theme() -> &'static Theme {
+    THEME.get_or_init(Theme::default)
+}
+
+/// Style text in a status's color
+pub fn paint(status: BuildStatus, text: &str) -> ColoredString {
+    theme().paint(status, text)
+}
+
+/// Status in lowercase, colored by outcome, e.g. for table rows
+pub fn label(status: BuildStatus) -> ColoredString {
+    paint(status, status.as_str())
+}
+
+/// Status in bold capitals, colored by outcome, for detail headers
+pub fn heading(status: BuildStatus) -> ColoredString {
+    paint(status, &status.as_str().to_uppercase()).bold()
+}
+
+/// Colored status icon, e.g. `✓` (or `+` in ASCII mode)
+pub fn icon(status: BuildStatus) -> ColoredString {
+    paint(status, symbol(status))
+}
+
+/// Uncolored status icon, for text that is colored as a whole
+pub fn symbol(status: BuildStatus) -> &'static str {
+    theme().symbol(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn theme(config: ThemeConfig) -> Theme {
+        Theme::from_config(&config)
+    }
+
+    #[test]
+    fn test_default_theme_uses_status_colors() {
+        let theme = Theme::default();
+        assert_eq!(theme.paint(BuildStatus::Success, "ok").fgcolor, Some(Color::Green));
+        assert_eq!(theme.paint(BuildStatus::Running, "..").fgcolor, Some(Color::Yellow));
+        assert_eq!(theme.symbol(BuildStatus::Failed), "✗");
+    }
+
+    #[test]
+    fn test_configured_colors_override_defaults() {
+        let theme = theme(ThemeConfig {
+            success: Some("bright blue".to_string()),
+            aborted: Some("Magenta".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(theme.paint(BuildStatus::Success, "ok").fgcolor, Some(Color::BrightBlue));
+        let aborted = theme.paint(BuildStatus::AbortedWithSuccess, "aborted");
+        assert_eq!(aborted.fgcolor, Some(Color::Magenta));
+        assert_eq!(theme.paint(BuildStatus::Failed, "no").fgcolor, Some(Color::Red));
+    }
+
+    #[test]
+    fn test_unknown_color_keeps_default() {
+        let theme = theme(ThemeConfig {
+            failed: Some("crimson".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(theme.paint(BuildStatus::Failed, "no").fgcolor, Some(Color::Red));
+    }
+
+    #[test]
+    fn test_ascii_icons() {
+        let theme = theme(ThemeConfig {
+            ascii: true,
+            ..Default::default()
+        });
+        let icons: Vec<&str> = [0, 1, 2, 3, 4, 9]
+            .into_iter()
+            .map(|code| theme.symbol(BuildStatus::from_code(code)))
+            .collect();
+        assert_eq!(icons, ["*", "+", "x", "-", "-", "?"]);
+        assert!(icons.iter().all(|icon| icon.is_ascii()));
+    }
+}
@@ -0,0 +1,131 @@
+//! Check for newer reprise releases
+//!
+//! Opt-in with `update.check = true`. The newest release on GitHub is
+//! looked up at most once a day and remembered in the cache, so the check
+//! costs a request a day and nothing on the other runs. A failed lookup is
+//! remembered too: an offline laptop isn't asked again until tomorrow.
+
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::cache::Cache;
+use crate::config::Config;
+use crate::error::Result;
+
+/// Latest release of reprise on GitHub
+pub const RELEASES_URL: &str = "https://api.github.com/repos/dan-hart/reprise/releases/latest";
+
+/// Cache key holding the latest release version (or null when unknown)
+pub const CACHE_KEY: &str = "update_check";
+
+/// How long a looked-up version is trusted
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Give up quickly: the check must never hold up a command
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The running version
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+}
+
+/// A newer release than the running one, if the check is enabled and
+/// one exists
+pub fn newer_version(config: &Config) -> Option<String> {
+    if !config.update.check {
+        return None;
+    }
+    let cache = Cache::for_config(&config.cache).ok()?;
+    let latest = match cache.get::<Option<String>>(CACHE_KEY, CHECK_INTERVAL) {
+        Some(latest) => latest,
+        None => {
+            let latest = latest_release(RELEASES_URL).ok();
+            // Without the cache every run would check, so skip it entirely
+            cache.put(CACHE_KEY, &latest).ok()?;
+            latest
+        }
+    };
+    latest.filter(|version| is_newer(version, CURRENT_VERSION))
+}
+
+/// Version of the latest release at `url`, without the tag's `v`
+pub fn latest_release(url: &str) -> Result<String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(TIMEOUT)
+        .user_agent(concat!("reprise/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    let release: Release = client.get(url).send()?.error_for_status()?.json()?;
+    Ok(release.tag_name.trim_start_matches('v').to_string())
+}
+
+/// Whether `candidate` is a later stable version than `current`
+///
+/// Pre-releases (`1.2.0-beta.1`) are never offered.
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+    fn parse(version: &str) -> Option<Vec<u64>> {
+        version.split('.').map(|part| part.parse().ok()).collect()
+    }
+    let current = current.split('-').next().unwrap_or_default();
+    match (parse(candidate), parse(current)) {
+        (Some(candidate), Some(current)) => candidate > current,
+        _ => false,
+    }
+}
+
+/// The one-line hint shown when a newer release exists
+pub fn hint(latest: &str) -> String {
+    format!(
+        "reprise {} is available (you have {}): brew upgrade reprise, or cargo install reprise",
+        latest, CURRENT_VERSION
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("0.2.0", "0.1.9"));
+        assert!(is_newer("1.10.0", "1.9.3"));
+        assert!(is_newer("1.0.1", "1.0.0-beta.2"));
+        assert!(!is_newer("0.1.0", "0.1.0"));
+        assert!(!is_newer("0.0.9", "0.1.0"));
+        assert!(!is_newer("0.3.0-rc.1", "0.2.0"));
+        assert!(!is_newer("nightly", "0.2.0"));
+    }
+
+    #[test]
+    fn test_latest_release_strips_tag_prefix() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/releases/latest")
+            .with_status(200)
+            .with_body(r#"{"tag_name": "v0.9.1", "name": "0.9.1"}"#)
+            .create();
+
+        let url = format!("{}/releases/latest", server.url());
+        assert_eq!(latest_release(&url).unwrap(), "0.9.1");
+        mock.assert();
+    }
+
+    #[test]
+    fn test_newer_version_uses_cached_release() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.cache.dir = Some(dir.path().to_string_lossy().into_owned());
+        let cache = Cache::for_config(&config.cache).unwrap();
+        cache.put(CACHE_KEY, &Some("999.0.0")).unwrap();
+
+        assert_eq!(newer_version(&config), None);
+        config.update.check = true;
+        assert_eq!(newer_version(&config).as_deref(), Some("999.0.0"));
+        cache.put(CACHE_KEY, &None::<String>).unwrap();
+        assert_eq!(newer_version(&config), None);
+    }
+}
@@ -0,0 +1,300 @@
+//! Polling engine for waiting on builds and pipelines
+//!
+//! This module is independent of the CLI: it only knows how to poll the
+//! Bitrise API until a build or pipeline finishes. Presentation (progress
+//! output, notifications, Ctrl+C handling) is left to the caller through
+//! the `on_poll` callback.
+//!
+//! Polls are adaptive (see [`PollSchedule`]): quick while a build has just
+//! started, then further apart, so an hour-long build costs a fraction of
+//! the requests.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::bitrise::{BitriseClient, Build, Pipeline};
+use crate::error::{RepriseError, Result};
+
+/// Delay between polls while watching has just started
+pub const FAST_INTERVAL: Duration = Duration::from_secs(3);
+
+/// How long polls stay at [`FAST_INTERVAL`]
+pub const FAST_PERIOD: Duration = Duration::from_secs(120);
+
+/// How long polls then take to slow down to the maximum delay
+pub const BACKOFF_PERIOD: Duration = Duration::from_secs(480);
+
+/// Maximum delay between polls unless `--interval` says otherwise
+pub const DEFAULT_MAX_INTERVAL_SECS: u64 = 30;
+
+/// Delays between polls that start fast and back off
+///
+/// Polls come every [`FAST_INTERVAL`] for the first [`FAST_PERIOD`] after
+/// watching starts, then the delay grows steadily to `max` over
+/// [`BACKOFF_PERIOD`] and stays there. A `max` below the fast interval is
+/// used from the start.
+#[derive(Debug, Clone, Copy)]
+pub struct PollSchedule {
+    started: Instant,
+    max: Duration,
+}
+
+impl PollSchedule {
+    /// Start a schedule now, never waiting longer than `max`
+    pub fn start(max: Duration) -> Self {
+        Self { started: Instant::now(), max }
+    }
+
+    /// Start a schedule capped at `max_secs` seconds
+    pub fn start_secs(max_secs: u64) -> Self {
+        Self::start(Duration::from_secs(max_secs))
+    }
+
+    /// Delay before the next poll
+    pub fn next_delay(&self) -> Duration {
+        delay_after(self.started.elapsed(), self.max)
+    }
+
+    /// Wait until the next poll is due
+    pub fn sleep(&self) {
+        thread::sleep(self.next_delay());
+    }
+}
+
+/// The delay `elapsed` after watching started, never above `max`
+fn delay_after(elapsed: Duration, max: Duration) -> Duration {
+    let fast = FAST_INTERVAL.min(max);
+    if elapsed <= FAST_PERIOD {
+        return fast;
+    }
+    let progress = (elapsed - FAST_PERIOD).as_secs_f64() / BACKOFF_PERIOD.as_secs_f64();
+    fast + (max - fast).mul_f64(progress.min(1.0))
+}
+
+/// Options controlling how often and how persistently to poll
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// Longest delay between polls (see [`PollSchedule`])
+    pub interval: Duration,
+    /// Retries for transient 5xx errors before giving up
+    pub max_retries: u32,
+    /// Called with the app slug and final state once a watched build finishes
+    pub on_build_finished: Option<fn(&str, &Build)>,
+}
+
+impl WatchOptions {
+    /// Create options with the given maximum polling interval in seconds
+    pub fn new(interval_secs: u64) -> Self {
+        Self {
+            interval: Duration::from_secs(interval_secs),
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(DEFAULT_MAX_INTERVAL_SECS),
+            max_retries: 5,
+            on_build_finished: None,
+        }
+    }
+}
+
+/// Poll a build until it finishes.
+///
+/// `on_poll` is invoked after every fetch with the latest build state.
+/// Returning `false` stops watching early (e.g. after Ctrl+C). Once the
+/// build finishes, `options.on_build_finished` is called with it.
+///
+/// # Returns
+/// - `Some(build)` with the final state once the build is no longer running
+/// - `None` if `on_poll` asked to stop before the build finished
+pub fn watch_build<F>(
+    client: &BitriseClient,
+    app_slug: &str,
+    build_slug: &str,
+    options: &WatchOptions,
+    mut on_poll: F,
+) -> Result<Option<Build>>
+where
+    F: FnMut(&Build) -> bool,
+{
+    let schedule = PollSchedule::start(options.interval);
+    loop {
+        let build = with_retry(options.max_retries, || {
+            client.get_build(app_slug, build_slug).map(|r| r.data)
+        })?;
+
+        let keep_going = on_poll(&build);
+        if !build.is_running() {
+            if let Some(on_build_finished) = options.on_build_finished {
+                on_build_finished(app_slug, &build);
+            }
+            return Ok(Some(build));
+        }
+        if !keep_going {
+            return Ok(None);
+        }
+
+        schedule.sleep();
+    }
+}
+
+/// Poll a pipeline until it finishes.
+///
+/// Behaves like [`watch_build`] but for pipelines.
+pub fn watch_pipeline<F>(
+    client: &BitriseClient,
+    app_slug: &str,
+    pipeline_id: &str,
+    options: &WatchOptions,
+    mut on_poll: F,
+) -> Result<Option<Pipeline>>
+where
+    F: FnMut(&Pipeline) -> bool,
+{
+    let schedule = PollSchedule::start(options.interval);
+    loop {
+        let pipeline = with_retry(options.max_retries, || {
+            client
+                .get_pipeline(app_slug, pipeline_id)
+                .map(|r| r.into_pipeline())
+        })?;
+
+        let keep_going = on_poll(&pipeline);
+        if !pipeline.is_running() {
+            return Ok(Some(pipeline));
+        }
+        if !keep_going {
+            return Ok(None);
+        }
+
+        schedule.sleep();
+    }
+}
+
+/// Run an API call, retrying transient server errors with exponential backoff
+pub fn with_retry<T, F>(max_retries: u32, mut call: F) -> Result<T>
+where
+    F: FnMut() -> Result<T>,
+{
+    let mut attempt = 0;
+    loop {
+        match call() {
+            Ok(value) => return Ok(value),
+            Err(e) => {
+                // Only retry on 5xx server errors
+                let should_retry = matches!(&e, RepriseError::Api { status, .. } if *status >= 500);
+
+                if should_retry && attempt < max_retries {
+                    attempt += 1;
+                    let backoff = Duration::from_secs(1 << (attempt - 1)); // 1, 2, 4, 8, 16s
+                    thread::sleep(backoff);
+                    continue;
+                }
+                return Err(e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+
+    fn build_body(status: i32) -> String {
+        format!(
+            r#"{{"data": {{
+                "slug": "build-slug",
+                "build_number": 7,
+                "status": {},
+                "status_text": "x",
+                "triggered_at": "2024-01-01T12:00:00Z",
+                "branch": "main",
+                "triggered_workflow": "primary"
+            }}}}"#,
+            status
+        )
+    }
+
+    fn fast_options() -> WatchOptions {
+        WatchOptions {
+            interval: Duration::from_millis(1),
+            max_retries: 0,
+            on_build_finished: None,
+        }
+    }
+
+    #[test]
+    fn test_poll_delay_backs_off_to_max() {
+        let max = Duration::from_secs(30);
+        let secs = |s: u64| delay_after(Duration::from_secs(s), max);
+        assert_eq!(secs(0), FAST_INTERVAL);
+        assert_eq!(secs(120), FAST_INTERVAL);
+        assert_eq!(secs(360), Duration::from_secs_f64(16.5));
+        assert_eq!(secs(600), max);
+        assert_eq!(secs(3600), max);
+
+        // A cap below the fast interval applies throughout
+        let one = Duration::from_secs(1);
+        assert_eq!(delay_after(Duration::ZERO, one), one);
+        assert_eq!(delay_after(Duration::from_secs(900), one), one);
+    }
+
+    #[test]
+    fn test_watch_options_new() {
+        let options = WatchOptions::new(10);
+        assert_eq!(options.interval, Duration::from_secs(10));
+        assert_eq!(options.max_retries, 5);
+    }
+
+    #[test]
+    fn test_watch_build_returns_finished_build() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/apps/app/builds/build-slug")
+            .with_status(200)
+            .with_body(build_body(1))
+            .create();
+
+        let client = BitriseClient::with_base_url("token", server.url()).unwrap();
+        let mut polls = 0;
+        let result = watch_build(&client, "app", "build-slug", &fast_options(), |_| {
+            polls += 1;
+            true
+        })
+        .unwrap();
+
+        mock.assert();
+        assert_eq!(polls, 1);
+        assert_eq!(result.unwrap().build_number, 7);
+    }
+
+    #[test]
+    fn test_watch_build_stops_when_callback_returns_false() {
+        let mut server = Server::new();
+        let _mock = server
+            .mock("GET", "/apps/app/builds/build-slug")
+            .with_status(200)
+            .with_body(build_body(0))
+            .create();
+
+        let client = BitriseClient::with_base_url("token", server.url()).unwrap();
+        let result = watch_build(&client, "app", "build-slug", &fast_options(), |_| false).unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_with_retry_does_not_retry_client_errors() {
+        let mut calls = 0;
+        let result: Result<()> = with_retry(3, || {
+            calls += 1;
+            Err(RepriseError::api(404, "nope"))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+}
@@ -0,0 +1,109 @@
+//! JSON webhook posted when a watched build finishes
+//!
+//! With `notify.webhook_url` set, every build that [`crate::hooks`] sees
+//! finish is also POSTed to that URL as JSON (see [`payload`]), for
+//! dashboards and bots that don't speak Slack. Like Slack messages, posts
+//! ignore the desktop quiet hours and throttling.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::bitrise::Build;
+use crate::config::Config;
+
+/// How long to wait for the endpoint before giving up on a post
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+static WEBHOOK_URL: OnceLock<Option<String>> = OnceLock::new();
+
+/// Apply `notify.webhook_url` to all builds finished in this process
+pub fn configure(config: &Config) {
+    let _ = WEBHOOK_URL.set(config.notify.webhook_url.clone());
+}
+
+/// Post a finished build to the configured webhook, if any
+///
+/// A failed post is reported as a warning and never fails the command.
+pub fn build_finished(app_slug: &str, build: &Build) {
+    let Some(Some(url)) = WEBHOOK_URL.get() else {
+        return;
+    };
+    if let Err(e) = post(url, &payload(app_slug, build)) {
+        eprintln!("warning: couldn't post to the webhook: {}", e);
+    }
+}
+
+/// The JSON body: the event, the outcome and link up front, and the build
+/// as the API returned it
+pub fn payload(app_slug: &str, build: &Build) -> serde_json::Value {
+    serde_json::json!({
+        "event": "build.finished",
+        "app_slug": app_slug,
+        "status": build.status.as_str(),
+        "url": format!("https://app.bitrise.io/build/{}", build.slug),
+        "build": build,
+    })
+}
+
+/// POST a JSON body to a URL
+pub fn post(url: &str, body: &serde_json::Value) -> Result<(), String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(TIMEOUT)
+        .user_agent(concat!("reprise/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client.post(url).json(body).send().map_err(|e| e.without_url().to_string())?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(format!("HTTP {}", status.as_u16()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitrise::BuildStatus;
+    use mockito::{Matcher, Server};
+
+    fn build() -> Build {
+        Build {
+            status: BuildStatus::Failed,
+            status_text: "error".to_string(),
+            started_on_worker_at: None,
+            finished_at: None,
+            ..Build::fixture()
+        }
+    }
+
+    #[test]
+    fn test_post_sends_build_payload() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("POST", "/hooks/ci")
+            .match_header("content-type", "application/json")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "event": "build.finished",
+                "app_slug": "my-app",
+                "status": "failed",
+                "url": "https://app.bitrise.io/build/build-1",
+                "build": { "build_number": 42, "branch": "main" },
+            })))
+            .with_status(204)
+            .create();
+
+        post(&format!("{}/hooks/ci", server.url()), &payload("my-app", &build())).unwrap();
+        mock.assert();
+    }
+
+    #[test]
+    fn test_post_reports_rejection() {
+        let mut server = Server::new();
+        server.mock("POST", "/hook").with_status(500).create();
+
+        let err = post(&format!("{}/hook", server.url()), &serde_json::json!({})).unwrap_err();
+        assert_eq!(err, "HTTP 500");
+    }
+}
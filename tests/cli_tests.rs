@@ -60,9 +60,38 @@ fn test_trigger_help() {
         .stdout(predicate::str::contains("Trigger a new build"))
         .stdout(predicate::str::contains("--workflow"))
         .stdout(predicate::str::contains("--branch"))
+        .stdout(predicate::str::contains("--tag"))
         .stdout(predicate::str::contains("--env"));
 }
 
+#[test]
+fn test_trigger_schedule_help() {
+    reprise()
+        .args(["trigger", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--at <TIME>"))
+        .stdout(predicate::str::contains("--in <DURATION>"));
+}
+
+#[test]
+fn test_trigger_at_conflicts_with_in() {
+    reprise()
+        .args(["trigger", "-w", "ci", "--at", "02:00", "--in", "45m"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_trigger_when_requires_then_pipeline() {
+    reprise()
+        .args(["trigger", "-w", "ci", "--when", "failure"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--then-pipeline"));
+}
+
 #[test]
 fn test_config_help() {
     reprise()
@@ -73,7 +102,18 @@ fn test_config_help() {
         .stdout(predicate::str::contains("init"))
         .stdout(predicate::str::contains("show"))
         .stdout(predicate::str::contains("set"))
-        .stdout(predicate::str::contains("path"));
+        .stdout(predicate::str::contains("path"))
+        .stdout(predicate::str::contains("lint"));
+}
+
+#[test]
+fn test_cache_help() {
+    reprise()
+        .args(["cache", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("status"))
+        .stdout(predicate::str::contains("clear"));
 }
 
 #[test]
@@ -119,6 +159,16 @@ fn test_trigger_requires_workflow() {
         .stderr(predicate::str::contains("--workflow"));
 }
 
+#[test]
+fn test_config_init_help_describes_wizard() {
+    reprise()
+        .args(["config", "init", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("checked against your account"))
+        .stdout(predicate::str::contains("picked from your apps"));
+}
+
 #[test]
 fn test_env_var_parsing() {
     // Valid env var format
@@ -184,6 +234,142 @@ fn test_abort_help() {
         .stdout(predicate::str::contains("--yes"));
 }
 
+#[test]
+fn test_rebuild_help() {
+    reprise()
+        .args(["rebuild", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("original parameters"))
+        .stdout(predicate::str::contains("--wait"));
+}
+
+#[test]
+fn test_triggers_help() {
+    reprise()
+        .args(["triggers", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("trigger_map"))
+        .stdout(predicate::str::contains("--file"));
+}
+
+#[test]
+fn test_triggers_reads_local_file_without_token() {
+    let home = tempfile::TempDir::new().unwrap();
+    let yml = home.path().join("bitrise.yml");
+    std::fs::write(
+        &yml,
+        "trigger_map:\n- push_branch: main\n  workflow: deploy\n- tag: 'v*'\n  workflow: release\n",
+    )
+    .unwrap();
+
+    reprise()
+        .env("HOME", home.path())
+        .env_remove("BITRISE_TOKEN")
+        .args(["triggers", "--file"])
+        .arg(&yml)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("branch=main"))
+        .stdout(predicate::str::contains("workflow deploy"))
+        .stdout(predicate::str::contains("tag=v*"));
+}
+
+#[test]
+fn test_triggers_reports_yml_line() {
+    let home = tempfile::TempDir::new().unwrap();
+    let yml = home.path().join("bitrise.yml");
+    std::fs::write(&yml, "workflows:\n  a: {}\n  a: {}\n").unwrap();
+
+    reprise()
+        .env("HOME", home.path())
+        .args(["triggers", "--file"])
+        .arg(&yml)
+        .assert()
+        .code(65)
+        .stderr(predicate::str::contains("line 3: duplicate key 'a'"));
+}
+
+#[test]
+fn test_validate_yml_reports_errors_with_lines() {
+    let home = tempfile::TempDir::new().unwrap();
+    let yml = home.path().join("bitrise.yml");
+    std::fs::write(
+        &yml,
+        concat!(
+            "format_version: '13'\nworkflows:\n  primary:\n",
+            "    before_run:\n    - setup\n    steps: []\n"
+        ),
+    )
+    .unwrap();
+
+    reprise()
+        .env("HOME", home.path())
+        .env_remove("BITRISE_TOKEN")
+        .arg("validate-yml")
+        .arg(&yml)
+        .assert()
+        .code(65)
+        .stdout(predicate::str::contains("line 5: before_run names unknown workflow 'setup'"))
+        .stderr(predicate::str::contains("1 error(s), 0 warning(s)"));
+}
+
+#[test]
+fn test_validate_yml_reads_stdin_and_strict_fails_on_warnings() {
+    let home = tempfile::TempDir::new().unwrap();
+    let yml = "format_version: '13'\nworkflows:\n  primary: {}\n";
+
+    reprise()
+        .env("HOME", home.path())
+        .args(["validate-yml", "-"])
+        .write_stdin(yml)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("workflow 'primary' has no steps"));
+
+    reprise()
+        .env("HOME", home.path())
+        .args(["validate-yml", "--strict", "-"])
+        .write_stdin(yml)
+        .assert()
+        .code(65);
+
+    reprise()
+        .env("HOME", home.path())
+        .args(["validate-yml", "-"])
+        .write_stdin("format_version: '13'\nworkflows:\n  primary:\n    steps: []\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("stdin looks good"));
+}
+
+#[test]
+fn test_validate_yml_json_failure_and_merge_keys() {
+    let home = tempfile::TempDir::new().unwrap();
+
+    let output = reprise()
+        .env("HOME", home.path())
+        .args(["validate-yml", "-o", "json", "-"])
+        .write_stdin("format_version: '13'\nworkflows:\n  primary:\n    before_run: [setup]\n")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(65));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["kind"], "validate-yml");
+
+    reprise()
+        .env("HOME", home.path())
+        .args(["validate-yml", "-"])
+        .write_stdin(concat!(
+            "format_version: '13'\nworkflows:\n  _base: &base\n    steps:\n    - script@1: {}\n",
+            "  primary:\n    <<: *base\n    title: CI\n"
+        ))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("stdin looks good"));
+}
+
 #[test]
 fn test_artifacts_help() {
     reprise()
@@ -212,7 +398,17 @@ fn test_log_help() {
         .stdout(predicate::str::contains("logs"))
         .stdout(predicate::str::contains("--tail"))
         .stdout(predicate::str::contains("--follow"))
-        .stdout(predicate::str::contains("--save"));
+        .stdout(predicate::str::contains("--save"))
+        .stdout(predicate::str::contains("--save-dir"));
+}
+
+#[test]
+fn test_log_requires_slug_without_save_dir() {
+    reprise()
+        .args(["log", "--branch", "main"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--save-dir"));
 }
 
 #[test]
@@ -368,6 +564,26 @@ fn test_pipeline_trigger_requires_name() {
         .stderr(predicate::str::contains("NAME"));
 }
 
+#[test]
+fn test_pipeline_trigger_help_shows_tag_and_pr_options() {
+    reprise()
+        .args(["pipeline", "trigger", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--tag"))
+        .stdout(predicate::str::contains("--commit"))
+        .stdout(predicate::str::contains("--pr-target"));
+}
+
+#[test]
+fn test_pipeline_trigger_pr_target_requires_pr() {
+    reprise()
+        .args(["pipeline", "trigger", "ci", "--pr-target", "main"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--pr"));
+}
+
 #[test]
 fn test_pipeline_abort_requires_id() {
     reprise()
@@ -528,6 +744,77 @@ fn test_completions_help() {
         .stdout(predicate::str::contains("fish"));
 }
 
+#[test]
+fn test_schema_builds() {
+    let output = reprise().args(["schema", "builds"]).output().unwrap();
+    assert!(output.status.success());
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(schema["properties"]["api_version"]["const"], 1);
+    assert_eq!(schema["properties"]["items"]["items"]["$ref"], "#/$defs/Build");
+    assert!(schema["$defs"]["Build"]["properties"]["build_number"].is_object());
+}
+
+#[test]
+fn test_schema_rejects_unknown_kind() {
+    reprise()
+        .args(["schema", "logs"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value 'logs'"));
+}
+
+#[test]
+fn test_help_topic() {
+    reprise()
+        .args(["help", "exit-codes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("78   Configuration error"));
+}
+
+#[test]
+fn test_help_lists_topics() {
+    reprise()
+        .arg("help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Usage: reprise"))
+        .stdout(predicate::str::contains("Help topics"))
+        .stdout(predicate::str::contains("authentication"));
+}
+
+#[test]
+fn test_help_for_nested_command() {
+    reprise()
+        .args(["help", "config", "alias"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Usage: reprise config alias"));
+}
+
+#[test]
+fn test_help_unknown_topic() {
+    reprise()
+        .args(["help", "nope"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("exit-codes"));
+}
+
+#[test]
+fn test_man_writes_pages() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let out = dir.path().join("man1");
+    reprise()
+        .args(["man", out.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("man pages"));
+    assert!(out.join("reprise.1").exists());
+    assert!(out.join("reprise-config-alias-import.1").exists());
+    assert!(!out.join("reprise-notify-handler.1").exists());
+}
+
 #[test]
 fn test_completions_bash() {
     reprise()
@@ -555,6 +842,62 @@ fn test_completions_fish() {
         .stdout(predicate::str::contains("complete"));
 }
 
+#[test]
+fn test_completions_call_back_for_app_slugs() {
+    reprise()
+        .args(["completions", "bash"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("reprise __complete app-slugs --shell bash"));
+}
+
+#[test]
+fn test_complete_lists_aliases_and_local_workflows() {
+    let home = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir(home.path().join(".reprise")).unwrap();
+    std::fs::write(
+        home.path().join(".reprise/config.toml"),
+        "[aliases]\nios = \"abc123\"\nandroid = \"def456\"\n",
+    )
+    .unwrap();
+    std::fs::write(home.path().join("bitrise.yml"), "workflows:\n  deploy: {}\n").unwrap();
+
+    reprise()
+        .env("HOME", home.path())
+        .args(["__complete", "aliases", "--shell", "bash"])
+        .assert()
+        .success()
+        .stdout("android\nios\n");
+    reprise()
+        .env("HOME", home.path())
+        .args(["__complete", "app-slugs", "--shell", "zsh"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ios:abc123\n"));
+    reprise()
+        .env("HOME", home.path())
+        .current_dir(home.path())
+        .args(["__complete", "workflows", "--shell", "fish"])
+        .assert()
+        .success()
+        .stdout("deploy\tbitrise.yml\n");
+}
+
+#[test]
+fn test_complete_never_fails() {
+    let home = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir(home.path().join(".reprise")).unwrap();
+    std::fs::write(home.path().join(".reprise/config.toml"), "not = [valid").unwrap();
+
+    reprise()
+        .env("HOME", home.path())
+        .args(["__complete", "app-slugs"])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+}
+
 #[test]
 fn test_url_generate_build() {
     reprise()
@@ -631,6 +974,32 @@ fn test_pipelines_since_option() {
         .success();
 }
 
+#[test]
+fn test_builds_until_option() {
+    reprise()
+        .args(["builds", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--until"));
+}
+
+#[test]
+fn test_pipelines_until_option() {
+    reprise()
+        .args(["pipelines", "--since", "yesterday", "--until", "today", "--help"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_pipelines_workflow_option() {
+    reprise()
+        .args(["pipelines", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--workflow"));
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // v0.1.8 Feature Tests: App Aliases, PR Filter, Artifact Filter, URL Actions
 // ─────────────────────────────────────────────────────────────────────────────
@@ -645,6 +1014,16 @@ fn test_config_alias_help() {
         .stdout(predicate::str::contains("--remove"));
 }
 
+#[test]
+fn test_config_alias_import_help() {
+    reprise()
+        .args(["config", "alias", "import", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--strategy"))
+        .stdout(predicate::str::contains("overwrite"));
+}
+
 #[test]
 fn test_config_alias_subcommand_exists() {
     // Config alias without args should list aliases (or show empty message)
@@ -654,6 +1033,20 @@ fn test_config_alias_subcommand_exists() {
         .success();
 }
 
+#[test]
+fn test_config_undo_restores_removed_alias() {
+    let home = tempfile::TempDir::new().unwrap();
+    let run = |args: &[&str]| reprise().env("HOME", home.path()).args(args).assert().success();
+
+    run(&["config", "undo"]).stdout(predicate::str::contains("Nothing to undo"));
+    run(&["config", "alias", "ios", "abc123"]);
+    run(&["config", "alias", "ios", "--remove"]);
+    run(&["config", "undo"]).stdout(predicate::str::contains("aliases.ios"));
+    run(&["config", "alias", "ios"]).stdout(predicate::str::contains("abc123"));
+    run(&["config", "undo"]);
+    run(&["config", "alias"]).stdout(predicate::str::contains("ios").not());
+}
+
 #[test]
 fn test_builds_pr_filter_option() {
     reprise()
@@ -710,6 +1103,24 @@ fn test_url_retry_option() {
         .stdout(predicate::str::contains("--retry"));
 }
 
+#[test]
+fn test_url_clipboard_option() {
+    reprise()
+        .args(["url", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--clipboard"));
+}
+
+#[test]
+fn test_url_clipboard_conflicts_with_url() {
+    reprise()
+        .args(["url", "https://app.bitrise.io/build/abc123", "--clipboard"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
 #[test]
 fn test_url_download_option() {
     reprise()
@@ -748,3 +1159,246 @@ fn test_url_retry_wait_requires_retry() {
         .failure()
         .stderr(predicate::str::contains("retry"));
 }
+
+#[test]
+fn test_prompt_status_help() {
+    reprise()
+        .args(["prompt-status", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--ttl"))
+        .stdout(predicate::str::contains("starship").or(predicate::str::contains("Starship")));
+}
+
+#[test]
+fn test_status_help() {
+    reprise()
+        .args(["status", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--branch"))
+        .stdout(predicate::str::contains("origin"));
+}
+
+#[test]
+fn test_runs_help() {
+    reprise()
+        .args(["runs", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("builds and pipelines"))
+        .stdout(predicate::str::contains("--status"));
+}
+
+#[test]
+fn test_builds_commit_option() {
+    reprise()
+        .args(["builds", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--commit"));
+}
+
+#[test]
+fn test_pr_help() {
+    reprise()
+        .args(["pr", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("<NUMBER>"));
+}
+
+#[test]
+fn test_pr_requires_number() {
+    reprise()
+        .arg("pr")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("NUMBER"));
+}
+
+#[test]
+fn test_wait_requires_commit() {
+    reprise()
+        .arg("wait")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--commit"));
+}
+
+#[test]
+fn test_wait_help_documents_exit_codes() {
+    reprise()
+        .args(["wait", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--timeout"))
+        .stdout(predicate::str::contains("Exit codes"));
+}
+
+#[test]
+fn test_install_help() {
+    reprise()
+        .args(["install", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--device"))
+        .stdout(predicate::str::contains("adb install"));
+}
+
+#[test]
+fn test_artifacts_latest_options() {
+    reprise()
+        .args(["artifacts", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("latest"))
+        .stdout(predicate::str::contains("--branch"))
+        .stdout(predicate::str::contains("--workflow"));
+}
+
+#[test]
+fn test_artifacts_sort_option() {
+    reprise()
+        .args(["artifacts", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--sort"))
+        .stdout(predicate::str::contains("size"));
+}
+
+#[test]
+fn test_log_ansi_options() {
+    reprise()
+        .args(["log", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--strip-ansi"))
+        .stdout(predicate::str::contains("--raw"));
+}
+
+#[test]
+fn test_log_strip_ansi_conflicts_with_raw() {
+    reprise()
+        .args(["log", "abc123", "--strip-ansi", "--raw"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_no_pager_flag() {
+    reprise()
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--no-pager"));
+}
+
+#[test]
+fn test_no_header_flag() {
+    reprise()
+        .args(["builds", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--no-header"));
+}
+
+#[test]
+fn test_limit_zero_rejected() {
+    reprise()
+        .args(["builds", "--limit", "0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("must be at least 1"));
+}
+
+#[test]
+fn test_width_must_fit_a_row() {
+    reprise()
+        .args(["--width", "10", "builds"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("10 is not in 20.."));
+}
+
+#[test]
+fn test_config_set_output_pager() {
+    let home = tempfile::TempDir::new().unwrap();
+    reprise()
+        .env("HOME", home.path())
+        .args(["config", "set", "output.pager", ""])
+        .assert()
+        .success();
+
+    reprise()
+        .env("HOME", home.path())
+        .args(["config", "show"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pager ="))
+        .stdout(predicate::str::contains("pager = (not set)").not());
+}
+
+#[test]
+fn test_builds_deltas_option() {
+    reprise()
+        .args(["builds", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--deltas"));
+}
+
+#[test]
+fn test_builds_sort_and_group_options() {
+    reprise()
+        .args(["builds", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--sort"))
+        .stdout(predicate::str::contains("--group-by"));
+}
+
+#[test]
+fn test_overview_help() {
+    reprise()
+        .args(["overview", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--running"))
+        .stdout(predicate::str::contains("--refresh"));
+}
+
+#[test]
+fn test_overview_alias() {
+    reprise().args(["ov", "--help"]).assert().success();
+}
+
+#[test]
+fn test_notify_handler_hidden_from_help() {
+    reprise()
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("notify-handler").not());
+}
+
+#[test]
+fn test_export_builds_help() {
+    reprise()
+        .args(["export", "builds", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--since"))
+        .stdout(predicate::str::contains("--format"))
+        .stdout(predicate::str::contains("--out"));
+}
+
+#[test]
+fn test_app_recent_help() {
+    reprise()
+        .args(["app", "recent", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("switch back"));
+}
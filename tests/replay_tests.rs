@@ -0,0 +1,3522 @@
+//! Replay integration tests
+//!
+//! Run commands against recorded fixtures (BITRISE_REPLAY) so their output can
+//! be checked deterministically without network access.
+
+use std::path::Path;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use reprise::bitrise::transport::{Fixture, Transport};
+use reprise::config::ENV_OVERRIDES;
+use tempfile::TempDir;
+
+/// Get a command for the reprise binary, isolated from the user's config
+fn reprise(home: &Path, fixtures: &Path) -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_reprise"));
+    cmd.env("HOME", home)
+        .env("BITRISE_REPLAY", fixtures)
+        .env_remove("BITRISE_TOKEN")
+        .env_remove("BITRISE_RECORD")
+        .env_remove("REPRISE_JSON_COMPAT")
+        .env_remove("CI");
+    for (var, _) in ENV_OVERRIDES {
+        cmd.env_remove(var);
+    }
+    cmd
+}
+
+fn record(dir: &Path, method: &str, key: &str, status: u16, body: &str) -> reprise::error::Result<()> {
+    Transport::save(
+        dir,
+        &Fixture {
+            method: method.to_string(),
+            key: key.to_string(),
+            status,
+            body: body.to_string(),
+        },
+    )
+}
+
+/// The payload of `-o json` output, unwrapped from the versioned envelope
+fn payload(stdout: &[u8]) -> serde_json::Result<serde_json::Value> {
+    let mut envelope: serde_json::Value = serde_json::from_slice(stdout)?;
+    assert_eq!(envelope["api_version"], 1);
+    Ok(match envelope.get("items") {
+        Some(_) => envelope["items"].take(),
+        None => envelope["item"].take(),
+    })
+}
+
+fn write_config(home: &Path, contents: &str) -> std::io::Result<()> {
+    std::fs::create_dir_all(home.join(".reprise"))?;
+    std::fs::write(home.join(".reprise/config.toml"), contents)
+}
+
+#[test]
+fn test_replay_builds_json() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=25",
+        200,
+        r#"{"data": [{
+            "slug": "build-1",
+            "build_number": 4211,
+            "status": 1,
+            "status_text": "success",
+            "triggered_at": "2024-01-01T12:00:00Z",
+            "branch": "main",
+            "triggered_workflow": "primary"
+        }], "paging": {"total_item_count": 1, "page_item_limit": 25}}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["builds", "--app", "demo-app", "-o", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("4211"));
+}
+
+#[test]
+fn test_replay_json_compat_legacy_prints_bare_payload() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=25",
+        200,
+        r#"{"data": [{
+            "slug": "build-1", "build_number": 4211, "status": 1, "status_text": "success",
+            "triggered_at": "2024-01-01T12:00:00Z", "branch": "main", "triggered_workflow": "primary"
+        }], "paging": {"total_item_count": 1, "page_item_limit": 25}}"#,
+    )
+    .unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["builds", "--app", "demo-app", "-o", "json"])
+        .output()
+        .unwrap();
+    let envelope: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(envelope["kind"], "builds");
+    assert_eq!(envelope["items"][0]["build_number"], 4211);
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["builds", "--app", "demo-app", "-o", "json", "--json-compat", "legacy"])
+        .output()
+        .unwrap();
+    let legacy: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(legacy[0]["build_number"], 4211);
+}
+
+#[test]
+fn test_replay_builds_width_stacks_narrow_rows() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=25",
+        200,
+        r#"{"data": [{
+            "slug": "build-1", "build_number": 4211, "status": 1, "status_text": "success",
+            "triggered_at": "2024-01-01T12:00:00Z", "branch": "feature/ticket-1234-long-name",
+            "triggered_workflow": "primary"
+        }], "paging": {"total_item_count": 1, "page_item_limit": 25}}"#,
+    )
+    .unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["builds", "--app", "demo-app", "--width", "40", "--no-pager"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines[1], "─".repeat(40));
+    assert!(lines[2].starts_with("BUILD"));
+    assert!(lines[3].starts_with("#4211"));
+    assert!(lines[4].trim_start().starts_with("feature/ticket"));
+    assert!(lines[4].ends_with("... primary"));
+}
+
+#[test]
+fn test_replay_builds_no_header_prints_only_rows() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=25",
+        200,
+        r#"{"data": [{
+            "slug": "build-1", "build_number": 4211, "status": 1, "status_text": "success",
+            "triggered_at": "2024-01-01T12:00:00Z", "branch": "main",
+            "triggered_workflow": "primary"
+        }], "paging": {"total_item_count": 1, "page_item_limit": 25}}"#,
+    )
+    .unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["builds", "--app", "demo-app", "--width", "120", "--no-pager"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.lines().any(|l| l.starts_with("BUILD  STATUS")));
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["builds", "--app", "demo-app", "--width", "120", "--no-pager", "--no-header"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let first = stdout.lines().next().unwrap();
+    assert!(first.starts_with("#4211  success  main  primary"));
+    assert!(!stdout.contains("BUILD"));
+    assert!(!stdout.contains("Builds"));
+}
+
+#[test]
+fn test_replay_builds_limit_above_page_size_paginates() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let page = |range: std::ops::Range<u32>, next: &str| {
+        let builds: Vec<String> = range
+            .map(|n| {
+                format!(
+                    r#"{{"slug": "build-{n}", "build_number": {n}, "status": 1,
+                    "status_text": "success", "triggered_at": "2024-01-01T12:00:00Z",
+                    "branch": "main", "triggered_workflow": "primary"}}"#
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"data": [{}], "paging": {{"total_item_count": 90, "page_item_limit": 50,
+            "next": {}}}}}"#,
+            builds.join(","),
+            next
+        )
+    };
+    record(fixtures.path(), "GET", "/apps/demo-app/builds?limit=50", 200, &page(0..50, "\"c2\""))
+        .unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=10&next=c2",
+        200,
+        &page(50..60, "null"),
+    )
+    .unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["builds", "--app", "demo-app", "--limit", "60", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("warning: --limit 60 is above the API's maximum of 50 per page"));
+    let items = payload(&output.stdout).unwrap();
+    assert_eq!(items.as_array().unwrap().len(), 60);
+    assert_eq!(items[59]["slug"], "build-59");
+}
+
+#[test]
+fn test_replay_abort_in_ci_needs_yes() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds/build-1",
+        200,
+        r#"{"data": {"slug": "build-1", "build_number": 12, "status": 0,
+            "status_text": "in-progress", "triggered_at": "2024-01-01T12:00:00Z",
+            "branch": "main", "triggered_workflow": "ci"}}"#,
+    )
+    .unwrap();
+    let abort = "/apps/demo-app/builds/build-1/abort";
+    record(fixtures.path(), "POST", abort, 200, r#"{"status": "ok"}"#).unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["abort", "build-1", "--app", "demo-app"])
+        .env("CI", "true")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("running non-interactively"))
+        .stderr(predicate::str::contains("pass --yes to confirm"));
+
+    reprise(home.path(), fixtures.path())
+        .args(["abort", "build-1", "--app", "demo-app", "--non-interactive", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Build #12 aborted"));
+}
+
+#[test]
+fn test_replay_actions_are_recorded_in_history() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds/build-1",
+        200,
+        r#"{"data": {"slug": "build-1", "build_number": 12, "status": 0,
+            "status_text": "in-progress", "triggered_at": "2024-01-01T12:00:00Z",
+            "branch": "main", "triggered_workflow": "ci"}}"#,
+    )
+    .unwrap();
+    let abort = "/apps/demo-app/builds/build-1/abort";
+    record(fixtures.path(), "POST", abort, 200, r#"{"status": "ok"}"#).unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["history"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No recorded actions yet"));
+    reprise(home.path(), fixtures.path())
+        .args(["--token", "secret-token", "abort", "build-1", "--app", "demo-app", "--yes"])
+        .assert()
+        .success();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["history", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let entries = payload(&output.stdout).unwrap();
+    assert_eq!(entries.as_array().unwrap().len(), 1);
+    let entry = &entries[0];
+    assert_eq!(entry["action"], "abort");
+    assert_eq!(entry["app_slug"], "demo-app");
+    assert_eq!(entry["slug"], "build-1");
+    assert_eq!(entry["result"], "ok");
+    assert_eq!(entry["command"], "reprise --token **** abort build-1 --app demo-app --yes");
+}
+
+#[test]
+fn test_replay_rebuild_reuses_original_build() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds/build-1",
+        200,
+        r#"{"data": {"slug": "build-1", "build_number": 12, "status": 2,
+            "status_text": "error", "triggered_at": "2024-01-01T12:00:00Z",
+            "branch": "feature", "triggered_workflow": "ci", "pull_request_id": 7,
+            "original_build_params": {"branch": "feature", "workflow_id": "ci",
+                "pull_request_id": 7,
+                "environments": [{"mapped_to": "FLAVOR", "value": "beta", "is_expand": true}]}}}"#,
+    )
+    .unwrap();
+    record(
+        fixtures.path(),
+        "POST",
+        "/apps/demo-app/builds",
+        201,
+        r#"{"status": "ok", "message": "triggered", "build_slug": "build-2"}"#,
+    )
+    .unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds/build-2",
+        200,
+        r#"{"data": {"slug": "build-2", "build_number": 13, "status": 0,
+            "status_text": "in-progress", "triggered_at": "2024-01-01T12:05:00Z",
+            "branch": "feature", "triggered_workflow": "ci"}}"#,
+    )
+    .unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["rebuild", "build-1", "--app", "demo-app", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json = payload(&output.stdout).unwrap();
+    assert_eq!(json["original_build_number"], 12);
+    assert_eq!(json["new_build_slug"], "build-2");
+    assert_eq!(json["branch"], "feature");
+}
+
+#[test]
+fn test_replay_pipeline_abort_piped_stdin_needs_yes() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+
+    // A piped answer isn't read; the prompt fails instead of hanging
+    reprise(home.path(), fixtures.path())
+        .args(["pipeline", "abort", "pipe-1", "--app", "demo-app"])
+        .write_stdin("y\n")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("Abort pipeline pipe-1 needs confirmation"))
+        .stderr(predicate::str::contains("stdin is not a terminal; pass --yes"));
+}
+
+#[test]
+fn test_replay_missing_fixture_fails() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["builds", "--app", "demo-app"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No recorded response"));
+}
+
+#[test]
+fn test_replay_recorded_api_error() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(fixtures.path(), "GET", "/apps/demo-app/builds/nope", 404, "Not found").unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["build", "nope", "--app", "demo-app"])
+        .assert()
+        .code(66);
+}
+
+#[test]
+fn test_replay_prompt_status_refresh_then_read() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=1&branch=main",
+        200,
+        r#"{"data": [{
+            "slug": "build-1",
+            "build_number": 4211,
+            "status": 1,
+            "status_text": "success",
+            "triggered_at": "2024-01-01T12:00:00Z",
+            "started_on_worker_at": "2024-01-01T12:00:00Z",
+            "finished_at": "2024-01-01T12:03:10Z",
+            "branch": "main",
+            "triggered_workflow": "primary"
+        }], "paging": {"total_item_count": 1, "page_item_limit": 1}}"#,
+    )
+    .unwrap();
+
+    let args = ["prompt-status", "--app", "demo-app", "--branch", "main"];
+
+    reprise(home.path(), fixtures.path())
+        .args(args)
+        .arg("--refresh")
+        .assert()
+        .success()
+        .stdout("");
+
+    reprise(home.path(), fixtures.path())
+        .args(args)
+        .assert()
+        .success()
+        .stdout("✓ #4211 3m\n");
+}
+
+#[test]
+fn test_prompt_status_silent_without_app() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["prompt-status", "--branch", "main"])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+}
+
+#[test]
+fn test_replay_status_json() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=20&branch=main",
+        200,
+        r#"{"data": [{
+            "slug": "build-1",
+            "build_number": 4211,
+            "status": 2,
+            "status_text": "error",
+            "triggered_at": "2024-01-01T12:00:00Z",
+            "branch": "main",
+            "triggered_workflow": "primary"
+        }], "paging": {"total_item_count": 1, "page_item_limit": 20}}"#,
+    )
+    .unwrap();
+
+    // No pipelines fixture: the overview still succeeds without them
+    reprise(home.path(), fixtures.path())
+        .args(["status", "--app", "demo-app", "--branch", "main", "-o", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"recent_builds\""))
+        .stdout(predicate::str::contains("4211"))
+        .stdout(predicate::str::contains("\"running_pipelines\": []"));
+}
+
+#[test]
+fn test_replay_builds_by_commit_pages_through_history() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let build = |slug: &str, number: i64, sha: &str| {
+        format!(
+            r#"{{"slug": "{}", "build_number": {}, "status": 1, "status_text": "success",
+                "triggered_at": "2024-01-01T12:00:00Z", "branch": "main",
+                "triggered_workflow": "primary", "commit_hash": "{}"}}"#,
+            slug, number, sha
+        )
+    };
+
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=50",
+        200,
+        &format!(
+            r#"{{"data": [{}], "paging": {{"total_item_count": 2, "page_item_limit": 50, "next": "cursor-2"}}}}"#,
+            build("b2", 2, "ffff000011112222")
+        ),
+    )
+    .unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=50&next=cursor-2",
+        200,
+        &format!(
+            r#"{{"data": [{}], "paging": {{"total_item_count": 2, "page_item_limit": 50}}}}"#,
+            build("b1", 1, "abcdef0123456789")
+        ),
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["builds", "--app", "demo-app", "--commit", "abcdef01", "-o", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"b1\""))
+        .stdout(predicate::str::contains("\"b2\"").not());
+}
+
+#[test]
+fn test_replay_pr_summary() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=50",
+        200,
+        r#"{"data": [
+            {"slug": "b3", "build_number": 3, "status": 1, "status_text": "success",
+             "triggered_at": "2024-01-01T12:00:00Z", "branch": "feature/x",
+             "triggered_workflow": "primary", "pull_request_id": 42,
+             "pull_request_target_branch": "main"},
+            {"slug": "b2", "build_number": 2, "status": 2, "status_text": "error",
+             "triggered_at": "2024-01-01T11:00:00Z", "branch": "other",
+             "triggered_workflow": "primary", "pull_request_id": 7}
+        ], "paging": {"total_item_count": 2, "page_item_limit": 50}}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["pr", "42", "--app", "demo-app", "-o", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"source_branch\": \"feature/x\""))
+        .stdout(predicate::str::contains("\"target_branch\": \"main\""))
+        .stdout(predicate::str::contains("\"b3\""))
+        .stdout(predicate::str::contains("\"b2\"").not());
+}
+
+#[test]
+fn test_replay_wait_exits_nonzero_on_failed_build() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=50",
+        200,
+        r#"{"data": [
+            {"slug": "b2", "build_number": 2, "status": 2, "status_text": "error",
+             "triggered_at": "2024-01-01T12:00:00Z", "branch": "main",
+             "triggered_workflow": "ci", "commit_hash": "abcdef0123456789"},
+            {"slug": "b1", "build_number": 1, "status": 1, "status_text": "success",
+             "triggered_at": "2024-01-01T11:00:00Z", "branch": "main",
+             "triggered_workflow": "deploy", "commit_hash": "abcdef0123456789"}
+        ], "paging": {"total_item_count": 2, "page_item_limit": 50}}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["wait", "--app", "demo-app", "--commit", "abcdef01", "--interval", "1"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("#2 ci (failed)"));
+
+}
+
+#[test]
+fn test_replay_wait_succeeds_for_passing_workflow() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=50&workflow=deploy",
+        200,
+        r#"{"data": [
+            {"slug": "b1", "build_number": 1, "status": 1, "status_text": "success",
+             "triggered_at": "2024-01-01T11:00:00Z", "branch": "main",
+             "triggered_workflow": "deploy", "commit_hash": "abcdef0123456789"}
+        ], "paging": {"total_item_count": 1, "page_item_limit": 50}}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["wait", "--app", "demo-app", "--commit", "abcdef01", "--workflow", "deploy"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("All builds for abcdef0 passed"));
+}
+
+#[test]
+fn test_replay_wait_posts_finished_builds_to_webhook() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=50&workflow=deploy",
+        200,
+        r#"{"data": [
+            {"slug": "b1", "build_number": 1, "status": 1, "status_text": "success",
+             "triggered_at": "2024-01-01T11:00:00Z", "branch": "main",
+             "triggered_workflow": "deploy", "commit_hash": "abcdef0123456789"}
+        ], "paging": {"total_item_count": 1, "page_item_limit": 50}}"#,
+    )
+    .unwrap();
+    let mut server = mockito::Server::new();
+    let hook = server
+        .mock("POST", "/ci")
+        .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+            "event": "build.finished",
+            "status": "success",
+            "build": { "slug": "b1", "triggered_workflow": "deploy" },
+        })))
+        .with_status(200)
+        .create();
+    write_config(home.path(), &format!("[notify]\nwebhook_url = \"{}/ci\"\n", server.url()))
+        .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["wait", "--app", "demo-app", "--commit", "abcdef01", "--workflow", "deploy"])
+        .assert()
+        .success();
+    hook.assert();
+}
+
+#[test]
+fn test_replay_wait_publishes_finished_builds_to_ntfy() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=50&workflow=deploy",
+        200,
+        r#"{"data": [
+            {"slug": "b1", "build_number": 1, "status": 2, "status_text": "error",
+             "triggered_at": "2024-01-01T11:00:00Z", "branch": "main",
+             "triggered_workflow": "deploy", "commit_hash": "abcdef0123456789"}
+        ], "paging": {"total_item_count": 1, "page_item_limit": 50}}"#,
+    )
+    .unwrap();
+    let mut server = mockito::Server::new();
+    let push = server
+        .mock("POST", "/")
+        .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+            "topic": "demo-builds",
+            "title": "Build Failed: demo-app #1",
+            "click": "https://app.bitrise.io/build/b1",
+        })))
+        .with_status(200)
+        .create();
+    let config =
+        format!("[notify]\nntfy_topic = \"demo-builds\"\nntfy_server = \"{}\"\n", server.url());
+    write_config(home.path(), &config).unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["wait", "--app", "demo-app", "--commit", "abcdef01", "--workflow", "deploy"])
+        .assert()
+        .code(1);
+    push.assert();
+}
+
+#[test]
+fn test_replay_wait_build_until_status() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds/b7",
+        200,
+        r#"{"data": {"slug": "b7", "build_number": 7, "status": 2, "status_text": "error",
+             "triggered_at": "2024-01-01T12:00:00Z", "branch": "main",
+             "triggered_workflow": "ci"}}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["wait-build", "b7", "--app", "demo-app", "--until", "finished"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Build #7 failed · ci"));
+    reprise(home.path(), fixtures.path())
+        .args(["wait-build", "b7", "--app", "demo-app", "--until", "status=failed", "-o", "json"])
+        .assert()
+        .success();
+    reprise(home.path(), fixtures.path())
+        .args(["wait-build", "b7", "--app", "demo-app", "--until", "status=success"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("build #7 finished failed, waited for status=success"));
+}
+
+#[test]
+fn test_wait_build_rejects_unknown_condition() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    reprise(home.path(), fixtures.path())
+        .args(["wait-build", "b7", "--until", "status=running"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("status=success|failed|aborted"));
+}
+
+#[test]
+fn test_replay_matrix_grid() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=50",
+        200,
+        r#"{"data": [
+            {"slug": "b4", "build_number": 4, "status": 2, "status_text": "error",
+             "triggered_at": "2024-01-01T13:00:00Z", "branch": "release/2.0",
+             "triggered_workflow": "ui"},
+            {"slug": "b3", "build_number": 3, "status": 1, "status_text": "success",
+             "triggered_at": "2024-01-01T12:00:00Z", "branch": "main",
+             "triggered_workflow": "unit"},
+            {"slug": "b2", "build_number": 2, "status": 2, "status_text": "error",
+             "triggered_at": "2024-01-01T11:00:00Z", "branch": "main",
+             "triggered_workflow": "unit"},
+            {"slug": "b1", "build_number": 1, "status": 1, "status_text": "success",
+             "triggered_at": "2024-01-01T10:00:00Z", "branch": "feature/x",
+             "triggered_workflow": "ui"}
+        ], "paging": {"total_item_count": 4, "page_item_limit": 50}}"#,
+    )
+    .unwrap();
+    // main's ui build is older than the recent builds
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=1&branch=main&workflow=ui",
+        200,
+        r#"{"data": [
+            {"slug": "b0", "build_number": 0, "status": 3, "status_text": "aborted",
+             "triggered_at": "2023-12-01T10:00:00Z", "branch": "main",
+             "triggered_workflow": "ui"}
+        ], "paging": {"total_item_count": 1, "page_item_limit": 1}}"#,
+    )
+    .unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["matrix", "--app", "demo-app", "-b", "main,release/*", "-w", "unit,ui"])
+        .args(["-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let matrix = payload(&output.stdout).unwrap();
+    assert_eq!(matrix["branches"], serde_json::json!(["main", "release/2.0"]));
+    let slugs: Vec<&str> = matrix["cells"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|cell| cell["build"]["slug"].as_str().unwrap_or("-"))
+        .collect();
+    assert_eq!(slugs, ["b3", "b0", "-", "b4"]);
+
+    reprise(home.path(), fixtures.path())
+        .args(["matrix", "--app", "demo-app", "-b", "main,release/*", "-w", "unit,ui"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("release/2.0"))
+        .stdout(predicate::str::contains("failed"));
+}
+
+#[test]
+fn test_replay_report_writes_markdown_and_html() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app",
+        200,
+        r#"{"data": {"slug": "demo-app", "title": "Demo", "is_disabled": false, "status": 1,
+            "owner": {"account_type": "organization", "name": "acme", "slug": "acme"}}}"#,
+    )
+    .unwrap();
+    // One failure in the last day, and a build from two days ago as baseline
+    let at = |hours: i64| (chrono::Utc::now() - chrono::Duration::hours(hours)).to_rfc3339();
+    let builds = serde_json::json!({
+        "data": [
+            {"slug": "b2", "build_number": 2, "status": 2, "status_text": "error",
+             "triggered_at": at(2), "branch": "main", "triggered_workflow": "ci",
+             "commit_message": "Break the build", "credit_cost": 4},
+            {"slug": "b1", "build_number": 1, "status": 1, "status_text": "success",
+             "triggered_at": at(48), "branch": "main", "triggered_workflow": "ci",
+             "credit_cost": 2}
+        ],
+        "paging": {"total_item_count": 2, "page_item_limit": 50}
+    });
+    record(fixtures.path(), "GET", "/apps/demo-app/builds?limit=50", 200, &builds.to_string())
+        .unwrap();
+
+    let out = home.path().join("reports/nightly.md");
+    reprise(home.path(), fixtures.path())
+        .args(["report", "--app", "demo-app", "--since", "24h", "--out"])
+        .arg(&out)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Wrote report of 1 build"));
+    let md = std::fs::read_to_string(&out).unwrap();
+    assert!(md.starts_with("# Bitrise report: Demo\n"));
+    assert!(md.contains("- Credits: 4\n"));
+    assert!(md.contains("[#2](https://app.bitrise.io/build/b2) ci on main: Break the build"));
+
+    reprise(home.path(), fixtures.path())
+        .args(["report", "--app", "demo-app", "--format", "html"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("<a href=\"https://app.bitrise.io/build/b2\">#2</a>"));
+}
+
+#[test]
+fn test_replay_install_requires_single_artifact() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds/b1/artifacts",
+        200,
+        r#"{"data": [
+            {"title": "app-debug.apk", "slug": "a1", "is_public_page_enabled": false},
+            {"title": "app-release.apk", "slug": "a2", "is_public_page_enabled": false},
+            {"title": "mapping.txt", "slug": "a3", "is_public_page_enabled": false}
+        ], "paging": {"total_item_count": 3, "page_item_limit": 50}}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["install", "b1", "--app", "demo-app"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("app-debug.apk, app-release.apk"));
+
+    reprise(home.path(), fixtures.path())
+        .args(["install", "b1", "--app", "demo-app", "--filter", "*.ipa"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("No installable artifact"));
+}
+
+#[test]
+fn test_replay_artifacts_latest_resolves_build() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=1&status=1&branch=main",
+        200,
+        r#"{"data": [{"slug": "newest-ok", "build_number": 9, "status": 1,
+             "status_text": "success", "triggered_at": "2024-01-01T12:00:00Z",
+             "branch": "main", "triggered_workflow": "deploy"}],
+           "paging": {"total_item_count": 1, "page_item_limit": 1}}"#,
+    )
+    .unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds/newest-ok/artifacts",
+        200,
+        r#"{"data": [{"title": "App.ipa", "slug": "a1", "is_public_page_enabled": false}],
+           "paging": {"total_item_count": 1, "page_item_limit": 50}}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["artifacts", "latest", "--app", "demo-app", "--branch", "main", "-o", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("App.ipa"));
+}
+
+#[test]
+fn test_replay_build_artifacts_json_is_enveloped() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds/b1/artifacts",
+        200,
+        r#"{"data": [{"title": "App.ipa", "slug": "a1", "is_public_page_enabled": false}],
+           "paging": {"total_item_count": 1, "page_item_limit": 50}}"#,
+    )
+    .unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["build", "b1", "--app", "demo-app", "--artifacts", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let envelope: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(envelope["kind"], "build");
+    assert_eq!(envelope["items"][0]["slug"], "a1");
+}
+
+#[test]
+fn test_replay_artifacts_warn_expiring() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds/old-build",
+        200,
+        r#"{"data": {"slug": "old-build", "build_number": 9, "status": 1,
+            "status_text": "success", "triggered_at": "2024-01-01T12:00:00Z",
+            "finished_at": "2024-01-01T12:10:00Z", "branch": "main",
+            "triggered_workflow": "deploy"}}"#,
+    )
+    .unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds/old-build/artifacts",
+        200,
+        r#"{"data": [{"title": "App.ipa", "slug": "a1", "is_public_page_enabled": false}],
+           "paging": {"total_item_count": 1, "page_item_limit": 50}}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["artifacts", "old-build", "--app", "demo-app", "--warn-expiring", "7d"])
+        .args(["--retention", "90d"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "warning: artifacts of build #9 expire already (2024-03-31 under a 90d retention)",
+        ))
+        .stdout(predicate::str::contains("Public: no"))
+        .stdout(predicate::str::contains("Kept until: 2024-03-31 (past)"));
+}
+
+#[test]
+fn test_replay_artifacts_publish_prints_install_url() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds/build-1/artifacts",
+        200,
+        r#"{"data": [{"title": "App.ipa", "slug": "a1", "is_public_page_enabled": false},
+                     {"title": "App.dSYM.zip", "slug": "a2", "is_public_page_enabled": false}],
+           "paging": {"total_item_count": 2, "page_item_limit": 50}}"#,
+    )
+    .unwrap();
+    record(
+        fixtures.path(),
+        "PATCH",
+        "/apps/demo-app/builds/build-1/artifacts/a1",
+        200,
+        r#"{"data": {"title": "App.ipa", "slug": "a1", "is_public_page_enabled": true,
+            "public_install_page_url": "https://i.bitrise.io/app.ipa"}}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["artifacts", "publish", "build-1", "*.ipa", "--on", "--app", "demo-app"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Public install page on for App.ipa"))
+        .stdout(predicate::str::contains("Install URL: https://i.bitrise.io/app.ipa"));
+}
+
+#[test]
+fn test_artifacts_publish_needs_one_of_on_off() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["artifacts", "publish", "build-1", "a1", "--app", "demo-app"])
+        .assert()
+        .code(2);
+    reprise(home.path(), fixtures.path())
+        .args(["artifacts", "publish", "build-1", "a1", "--on", "--off", "--app", "demo-app"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_replay_artifacts_upload_reports_refusal() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let file = fixtures.path().join("coverage.xml");
+    std::fs::write(&file, "<coverage/>").unwrap();
+    record(
+        fixtures.path(),
+        "POST",
+        "/apps/demo-app/builds/build-1/artifacts",
+        403,
+        r#"{"message": "Forbidden"}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["artifacts", "upload", file.to_str().unwrap(), "--build", "build-1"])
+        .args(["--app", "demo-app"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "Bitrise doesn't allow artifact uploads to build build-1 with this token (HTTP 403)",
+        ));
+}
+
+#[test]
+fn test_artifacts_upload_missing_file() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["artifacts", "upload", "no-such-file.xml", "--build", "build-1"])
+        .args(["--app", "demo-app"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Cannot read no-such-file.xml"));
+}
+
+#[test]
+fn test_replay_coverage_trend_skips_builds_without_reports() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=50&status=1",
+        200,
+        r#"{"data": [{"slug": "build-1", "build_number": 1, "status": 1,
+            "status_text": "success", "triggered_at": "2099-01-01T12:00:00Z",
+            "branch": "main", "triggered_workflow": "primary"}],
+           "paging": {"total_item_count": 1, "page_item_limit": 50}}"#,
+    )
+    .unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds/build-1/artifacts",
+        200,
+        r#"{"data": [{"title": "junit.xml", "slug": "a1", "is_public_page_enabled": false}],
+           "paging": {"total_item_count": 1, "page_item_limit": 50}}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["coverage", "--trend", "--since", "30d", "--app", "demo-app"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No coverage reports in 1 successful builds since 30d."));
+
+    reprise(home.path(), fixtures.path())
+        .args(["coverage", "build-1", "--app", "demo-app"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("No coverage reports in build build-1 (1 artifacts)"));
+}
+
+#[test]
+fn test_artifacts_branch_requires_latest() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["artifacts", "abc123", "--app", "demo-app", "--branch", "main"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("only be used with 'latest'"));
+}
+
+#[test]
+fn test_replay_failures_groups_error_signatures() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let build = |n: i64| {
+        format!(
+            r#"{{"slug": "build-{n}", "build_number": {n}, "status": 2,
+                "status_text": "error", "triggered_at": "2099-01-0{n}T12:00:00Z",
+                "branch": "main", "triggered_workflow": "primary"}}"#
+        )
+    };
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=50&status=2",
+        200,
+        &format!(
+            r#"{{"data": [{}, {}, {}],
+                "paging": {{"total_item_count": 3, "page_item_limit": 50}}}}"#,
+            build(3),
+            build(2),
+            build(1)
+        ),
+    )
+    .unwrap();
+    let logs = [
+        (3, r"| (2) xcode-test@5 |\n/tmp/a/Feed.swift:12:5: error: no such module 'Kit'\n"),
+        (2, r"| (2) xcode-test@5 |\n/Users/b/Feed.swift:40:1: error: no such module 'Kit'\n"),
+        (1, r"Step failed\n"),
+    ];
+    for (n, log) in logs {
+        record(
+            fixtures.path(),
+            "GET",
+            &format!("/apps/demo-app/builds/build-{n}/log"),
+            200,
+            &format!(
+                r#"{{"log_chunks": [{{"chunk": "{log}", "position": 0}}],
+                    "expiring_raw_log_url": null, "is_archived": false}}"#
+            ),
+        )
+        .unwrap();
+    }
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["failures", "--app", "demo-app", "--since", "2099-01-01", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let report = payload(&output.stdout).unwrap();
+    assert_eq!(report["failed_builds"], 3);
+    assert_eq!(report["without_errors"], 1);
+    let cause = &report["causes"][0];
+    assert_eq!(cause["signature"], "Feed.swift:N:N: error: no such module 'Kit'");
+    assert_eq!(cause["builds"], 2);
+    assert_eq!(cause["steps"][0], "xcode-test@5");
+    assert_eq!(cause["examples"][0]["build_number"], 3);
+}
+
+/// Record a log response whose chunks carry ANSI color codes
+fn record_ansi_log(dir: &Path) -> reprise::error::Result<()> {
+    record(
+        dir,
+        "GET",
+        "/apps/demo-app/builds/build-1/log",
+        200,
+        r#"{"log_chunks": [{"chunk": "\u001b[31;1mxcodebuild failed\u001b[0m\n", "position": 0}],
+           "expiring_raw_log_url": null, "is_archived": false}"#,
+    )
+}
+
+#[test]
+fn test_replay_log_strips_ansi_when_piped() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_ansi_log(fixtures.path()).unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["log", "build-1", "--app", "demo-app"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("xcodebuild failed"))
+        .stdout(predicate::str::contains("\u{1b}[").not());
+}
+
+/// Record a log with a compiler error and a warning inside a step
+fn record_diagnostics_log(dir: &Path) -> reprise::error::Result<()> {
+    let log = concat!(
+        r"| (3) xcode-test@5 |\n",
+        r"/Users/vagrant/git/App/Feed.swift:12:5: error: cannot find 'x' in scope\n",
+        r"Feed.m:3:1: warning: unused variable\n",
+    );
+    record(
+        dir,
+        "GET",
+        "/apps/demo-app/builds/build-1/log",
+        200,
+        &format!(
+            r#"{{"log_chunks": [{{"chunk": "{log}", "position": 0}}],
+                "expiring_raw_log_url": null, "is_archived": false}}"#
+        ),
+    )
+}
+
+#[test]
+fn test_replay_log_annotations_github() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_diagnostics_log(fixtures.path()).unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["log", "build-1", "--app", "demo-app", "--annotations", "github"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "::error file=App/Feed.swift,line=12,col=5,title=xcode-test@5::cannot find 'x'",
+        ))
+        .stdout(predicate::str::contains("::warning file=Feed.m,line=3,col=1"))
+        .stderr(predicate::str::contains("1 errors and 1 warnings in build build-1"));
+}
+
+#[test]
+fn test_replay_log_annotations_sarif() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_diagnostics_log(fixtures.path()).unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["log", "build-1", "--app", "demo-app", "--annotations", "sarif"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let sarif: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let results = &sarif["runs"][0]["results"];
+    assert_eq!(results.as_array().map(Vec::len), Some(2));
+    assert_eq!(results[0]["level"], "error");
+    assert_eq!(
+        results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        "App/Feed.swift"
+    );
+}
+
+#[test]
+fn test_replay_log_html_export() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_diagnostics_log(fixtures.path()).unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds/build-1",
+        200,
+        r#"{"data": {"slug": "build-1", "build_number": 12, "status": 2,
+            "status_text": "error", "triggered_at": "2024-01-01T12:00:00Z",
+            "branch": "main", "triggered_workflow": "ci"}}"#,
+    )
+    .unwrap();
+    let path = home.path().join("build.html");
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["log", "build-1", "--app", "demo-app", "-o", "json", "--html"])
+        .arg(&path)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let result = payload(&output.stdout).unwrap();
+    assert_eq!(result["lines"], 3);
+    assert_eq!(result["steps"], 1);
+
+    let html = std::fs::read_to_string(&path).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>Build #12 · ci · main</title>"));
+    assert!(html.contains("<summary>(3) xcode-test@5 · running</summary>"));
+    assert!(html.contains("<span class=\"c31\">/Users/vagrant/git/App/Feed.swift:12:5: error"));
+}
+
+#[test]
+fn test_replay_log_raw_keeps_ansi() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_ansi_log(fixtures.path()).unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["log", "build-1", "--app", "demo-app", "--raw"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\u{1b}[31;1mxcodebuild failed"));
+}
+
+#[test]
+fn test_replay_builds_group_by_branch_json() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=25",
+        200,
+        r#"{"data": [
+            {"slug": "b3", "build_number": 3, "status": 1, "status_text": "success",
+             "triggered_at": "2024-01-01T12:00:00Z", "branch": "main", "triggered_workflow": "primary",
+             "started_on_worker_at": "2024-01-01T12:00:00Z", "finished_at": "2024-01-01T12:02:00Z"},
+            {"slug": "b2", "build_number": 2, "status": 2, "status_text": "error",
+             "triggered_at": "2024-01-01T11:00:00Z", "branch": "feature", "triggered_workflow": "primary",
+             "started_on_worker_at": "2024-01-01T11:00:00Z", "finished_at": "2024-01-01T11:09:00Z"},
+            {"slug": "b1", "build_number": 1, "status": 1, "status_text": "success",
+             "triggered_at": "2024-01-01T10:00:00Z", "branch": "main", "triggered_workflow": "primary",
+             "started_on_worker_at": "2024-01-01T10:00:00Z", "finished_at": "2024-01-01T10:05:00Z"}
+        ], "paging": {"total_item_count": 3, "page_item_limit": 25}}"#,
+    )
+    .unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["builds", "--app", "demo-app", "--sort", "duration", "--group-by", "branch", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json = payload(&output.stdout).unwrap();
+    assert_eq!(json["group_by"], "branch");
+    // Sorted by duration first, so the 9 minute feature build leads
+    assert_eq!(json["groups"][0]["key"], "feature");
+    assert_eq!(json["groups"][1]["key"], "main");
+    assert_eq!(json["groups"][1]["count"], 2);
+    assert_eq!(json["groups"][1]["builds"][0]["slug"], "b1");
+}
+
+#[test]
+fn test_replay_builds_time_window_pages_until_covered() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=50",
+        200,
+        r#"{"data": [
+            {"slug": "too-new", "build_number": 30, "status": 1, "status_text": "success",
+             "triggered_at": "2024-01-10T12:00:00Z", "branch": "main", "triggered_workflow": "primary"}
+        ], "paging": {"total_item_count": 3, "page_item_limit": 50, "next": "cursor1"}}"#,
+    )
+    .unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=50&next=cursor1",
+        200,
+        r#"{"data": [
+            {"slug": "in-window", "build_number": 20, "status": 1, "status_text": "success",
+             "triggered_at": "2024-01-01T12:00:00Z", "branch": "main", "triggered_workflow": "primary"},
+            {"slug": "too-old", "build_number": 10, "status": 1, "status_text": "success",
+             "triggered_at": "2023-12-20T12:00:00Z", "branch": "main", "triggered_workflow": "primary"}
+        ], "paging": {"total_item_count": 3, "page_item_limit": 50, "next": "cursor2"}}"#,
+    )
+    .unwrap();
+
+    // No fixture for cursor2: paging must stop once builds predate --since
+    reprise(home.path(), fixtures.path())
+        .args([
+            "builds", "--app", "demo-app", "--since", "2023-12-31", "--until", "2024-01-02", "-o", "json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("in-window"))
+        .stdout(predicate::str::contains("too-new").not())
+        .stdout(predicate::str::contains("too-old").not());
+}
+
+#[test]
+fn test_builds_since_after_until_is_rejected() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["builds", "--app", "demo-app", "--since", "2024-05-02", "--until", "2024-04-30"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("must be before --until"));
+}
+
+#[test]
+fn test_replay_overview_across_apps() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let owner = r#""owner": {"account_type": "organization", "name": "acme", "slug": "acme"}"#;
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps?limit=50",
+        200,
+        &format!(
+            r#"{{"data": [
+                {{"slug": "ios", "title": "iOS App", "is_disabled": false, "status": 1, {owner}}},
+                {{"slug": "android", "title": "Android App", "is_disabled": false, "status": 1, {owner}}},
+                {{"slug": "old", "title": "Retired", "is_disabled": true, "status": 1, {owner}}}
+            ], "paging": {{"total_item_count": 3, "page_item_limit": 100}}}}"#
+        ),
+    )
+    .unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/ios/builds?limit=10",
+        200,
+        r#"{"data": [
+            {"slug": "ios-run", "build_number": 8, "status": 0, "status_text": "in-progress",
+             "triggered_at": "2024-01-02T12:00:00Z", "branch": "main", "triggered_workflow": "primary"},
+            {"slug": "ios-done", "build_number": 7, "status": 1, "status_text": "success",
+             "triggered_at": "2024-01-01T12:00:00Z", "branch": "main", "triggered_workflow": "primary"}
+        ], "paging": {"total_item_count": 2, "page_item_limit": 10}}"#,
+    )
+    .unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/android/builds?limit=10",
+        500,
+        r#"{"message": "boom"}"#,
+    )
+    .unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["overview", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json = payload(&output.stdout).unwrap();
+    let rows = json.as_array().unwrap();
+    // Disabled apps are skipped; the busy app sorts first
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["app_slug"], "ios");
+    assert_eq!(rows[0]["running"][0]["slug"], "ios-run");
+    assert_eq!(rows[0]["latest"]["slug"], "ios-done");
+    // One app failing doesn't fail the overview
+    assert_eq!(rows[1]["app_slug"], "android");
+    assert!(rows[1]["error"].is_string());
+}
+
+#[test]
+fn test_replay_builds_uses_app_config_branch() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    write_config(
+        home.path(),
+        "[aliases]\nios = \"demo-app\"\n\n[apps.\"demo-app\"]\nbranch = \"develop\"\n",
+    )
+    .unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=25&branch=develop",
+        200,
+        r#"{"data": [{
+            "slug": "build-1", "build_number": 7, "status": 1, "status_text": "success",
+            "triggered_at": "2024-01-01T12:00:00Z", "branch": "develop",
+            "triggered_workflow": "primary"
+        }], "paging": {"total_item_count": 1, "page_item_limit": 25}}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["builds", "--app", "ios", "-o", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"branch\": \"develop\""));
+}
+
+#[test]
+fn test_trigger_without_workflow_points_at_app_config() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["trigger", "--app", "demo-app"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("[apps.\"demo-app\"]"));
+}
+
+#[test]
+fn test_config_lint_reports_problems_with_lines() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    write_config(home.path(), "[output]\nformat = \"yaml\"\npagr = \"less\"\n").unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["config", "lint"])
+        .assert()
+        .code(78)
+        .stdout(predicate::str::contains("line 2: output.format must be 'pretty' or 'json'"))
+        .stdout(predicate::str::contains("line 3: unknown key 'output.pagr'"))
+        .stdout(predicate::str::contains("1 error(s), 1 warning(s)"));
+}
+
+#[test]
+fn test_config_lint_runs_when_config_does_not_parse() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    write_config(home.path(), "[apps.\"demo-app\"]\ninterval = \"fast\"\n").unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["config", "lint", "-o", "json"])
+        .assert()
+        .code(78)
+        .stdout(predicate::str::contains("\"valid\": false"))
+        .stdout(predicate::str::contains("\"line\": 2"));
+}
+
+#[test]
+fn test_unknown_config_key_warns_before_command() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    write_config(home.path(), "[defaults]\napp_slug = \"demo-app\"\napp_slgu = \"typo\"\n").unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["app", "show"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("warning: config line 3: unknown key 'defaults.app_slgu'"));
+}
+
+#[test]
+fn test_env_overrides_default_app_and_output_format() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    write_config(home.path(), "[defaults]\napp_slug = \"other-app\"\n").unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=25",
+        200,
+        r#"{"data": [{
+            "slug": "build-1", "build_number": 7, "status": 1, "status_text": "success",
+            "triggered_at": "2024-01-01T12:00:00Z", "branch": "main",
+            "triggered_workflow": "primary"
+        }], "paging": {"total_item_count": 1, "page_item_limit": 25}}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .env("REPRISE_DEFAULT_APP", "demo-app")
+        .env("REPRISE_OUTPUT_FORMAT", "json")
+        .arg("builds")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"slug\": \"build-1\""));
+}
+
+#[test]
+fn test_env_override_with_invalid_value_fails() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .env("REPRISE_CACHE_TTL", "soon")
+        .args(["app", "show"])
+        .assert()
+        .code(78)
+        .stderr(predicate::str::contains("Invalid REPRISE_CACHE_TTL"));
+}
+
+#[test]
+fn test_config_export_no_secrets_omits_token() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    write_config(
+        home.path(),
+        "[api]\ntoken = \"secret-token\"\n\n[aliases]\nios = \"demo-app\"\n",
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["config", "export", "--no-secrets"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ios = \"demo-app\""))
+        .stdout(predicate::str::contains("secret-token").not());
+
+    reprise(home.path(), fixtures.path())
+        .args(["config", "export"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("token = \"secret-token\""));
+}
+
+#[test]
+fn test_config_import_merges_aliases_and_keeps_token() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    write_config(
+        home.path(),
+        "[api]\ntoken = \"secret-token\"\n\n[aliases]\nweb = \"web-app\"\n",
+    )
+    .unwrap();
+    let import = home.path().join("team.toml");
+    std::fs::write(
+        &import,
+        "[aliases]\nios = \"demo-app\"\n\n[apps.\"demo-app\"]\nworkflow = \"ci\"\n",
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["config", "import", import.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported [aliases], [apps]"));
+
+    let saved = std::fs::read_to_string(home.path().join(".reprise/config.toml")).unwrap();
+    assert!(saved.contains("secret-token"));
+    assert!(saved.contains("web = \"web-app\""));
+    assert!(saved.contains("ios = \"demo-app\""));
+    assert!(saved.contains("workflow = \"ci\""));
+}
+
+#[test]
+fn test_config_alias_export_then_import_overwrite() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    write_config(home.path(), "[aliases]\nios = \"ios-app\"\nweb = \"web-app\"\n").unwrap();
+    let shared = home.path().join("aliases.toml");
+
+    reprise(home.path(), fixtures.path())
+        .args(["config", "alias", "export", shared.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Exported 2 aliases"));
+    let exported = std::fs::read_to_string(&shared).unwrap();
+    assert!(exported.starts_with("[aliases]"));
+    assert!(exported.contains("ios = \"ios-app\""));
+
+    std::fs::write(&shared, "[aliases]\nios = \"new-ios\"\nandroid = \"android-app\"\n").unwrap();
+    reprise(home.path(), fixtures.path())
+        .args(["config", "alias", "import", shared.to_str().unwrap()])
+        .args(["--strategy", "overwrite"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Added:   android"))
+        .stdout(predicate::str::contains("Updated: ios"))
+        .stdout(predicate::str::contains("Removed: web"));
+
+    let saved = std::fs::read_to_string(home.path().join(".reprise/config.toml")).unwrap();
+    assert!(saved.contains("ios = \"new-ios\""));
+    assert!(saved.contains("android = \"android-app\""));
+    assert!(!saved.contains("web-app"));
+}
+
+#[test]
+fn test_config_alias_import_merge_keeps_existing() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    write_config(home.path(), "[aliases]\nweb = \"web-app\"\n").unwrap();
+    let shared = home.path().join("aliases.toml");
+    std::fs::write(&shared, "ios = \"ios-app\"\n").unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["config", "alias", "import", shared.to_str().unwrap(), "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(payload(&output.stdout).unwrap()["added"], serde_json::json!(["ios"]));
+
+    let saved = std::fs::read_to_string(home.path().join(".reprise/config.toml")).unwrap();
+    assert!(saved.contains("web = \"web-app\""));
+    assert!(saved.contains("ios = \"ios-app\""));
+}
+
+#[test]
+fn test_config_import_rejects_invalid_file() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let import = home.path().join("bad.toml");
+    std::fs::write(&import, "[output]\nformat = \"yaml\"\n").unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["config", "import", import.to_str().unwrap()])
+        .assert()
+        .code(78)
+        .stderr(predicate::str::contains("line 2: output.format"));
+    assert!(!home.path().join(".reprise/config.toml").exists());
+}
+
+#[test]
+fn test_config_set_token_reports_account() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/me",
+        200,
+        r#"{"data": {"username": "alice", "slug": "u1", "email": null}}"#,
+    )
+    .unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/organizations",
+        200,
+        r#"{"data": [{"slug": "o1", "name": "Acme"}]}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["config", "set", "api.token", "good-token-1234"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("alice"))
+        .stdout(predicate::str::contains("Acme"))
+        .stdout(predicate::str::contains("good-token-1234").not());
+
+    let saved = std::fs::read_to_string(home.path().join(".reprise/config.toml")).unwrap();
+    assert!(saved.contains("good-token-1234"));
+}
+
+/// Two pages of builds; two of the five were triggered by alice
+fn record_builds_by_user(dir: &Path) -> reprise::error::Result<()> {
+    let build = |n: i64, by: &str| {
+        format!(
+            r#"{{"slug": "build-{n}", "build_number": {n}, "status": 1, "status_text": "success",
+                "triggered_at": "2024-01-01T12:00:00Z", "branch": "main",
+                "triggered_workflow": "primary", "triggered_by": "{by}"}}"#
+        )
+    };
+    let page = |builds: Vec<String>, next: &str| {
+        format!(
+            r#"{{"data": [{}], "paging": {{"total_item_count": 5, "page_item_limit": 50{}}}}}"#,
+            builds.join(","),
+            next
+        )
+    };
+    record(
+        dir,
+        "GET",
+        "/apps/demo-app/builds?limit=50",
+        200,
+        &page(
+            vec![build(5, "manual-Alice"), build(4, "bob"), build(3, "bob")],
+            r#", "next": "p2""#,
+        ),
+    )?;
+    record(
+        dir,
+        "GET",
+        "/apps/demo-app/builds?limit=50&next=p2",
+        200,
+        &page(vec![build(2, "webhook"), build(1, "manual-alice")], ""),
+    )
+}
+
+#[test]
+fn test_replay_builds_me_pages_until_limit_and_caches_user() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_builds_by_user(fixtures.path()).unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/me",
+        200,
+        r#"{"data": {"username": "alice", "slug": "u1", "email": null}}"#,
+    )
+    .unwrap();
+
+    let run = |fixtures: &Path| {
+        let output = reprise(home.path(), fixtures)
+            .args(["builds", "--me", "--app", "demo-app", "--limit", "2", "-o", "json"])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let builds = payload(&output.stdout).unwrap();
+        let numbers: Vec<i64> = builds
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|b| b["build_number"].as_i64().unwrap())
+            .collect();
+        assert_eq!(numbers, vec![5, 1]);
+    };
+    run(fixtures.path());
+
+    // The username is cached, so /me isn't asked again
+    let without_me = TempDir::new().unwrap();
+    record_builds_by_user(without_me.path()).unwrap();
+    run(without_me.path());
+}
+
+/// Run git in `dir` and return its trimmed stdout
+fn git(dir: &Path, args: &[&str]) -> std::io::Result<String> {
+    let output = std::process::Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()?;
+    if !output.status.success() {
+        return Err(std::io::Error::other(String::from_utf8_lossy(&output.stderr)));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[test]
+fn test_replay_builds_marks_local_head() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let repo = TempDir::new().unwrap();
+    git(repo.path(), &["init", "--quiet"]).unwrap();
+    git(repo.path(), &["commit", "--quiet", "--allow-empty", "-m", "first"]).unwrap();
+    let first = git(repo.path(), &["rev-parse", "HEAD"]).unwrap();
+    git(repo.path(), &["commit", "--quiet", "--allow-empty", "-m", "second"]).unwrap();
+    let second = git(repo.path(), &["rev-parse", "HEAD"]).unwrap();
+    git(repo.path(), &["commit", "--quiet", "--allow-empty", "-m", "third"]).unwrap();
+    let third = git(repo.path(), &["rev-parse", "HEAD"]).unwrap();
+    git(repo.path(), &["checkout", "--quiet", &second]).unwrap();
+
+    let build = |n: i64, commit: &str| {
+        format!(
+            r#"{{"slug": "build-{n}", "build_number": {n}, "status": 1, "status_text": "success",
+                "triggered_at": "2024-01-01T12:00:00Z", "branch": "main",
+                "triggered_workflow": "primary", "commit_hash": "{commit}"}}"#
+        )
+    };
+    let builds = [
+        build(4, &"f".repeat(40)),
+        build(3, &third),
+        build(2, &second[..10]),
+        build(1, &first),
+    ];
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=25",
+        200,
+        &format!(
+            r#"{{"data": [{}], "paging": {{"total_item_count": 4, "page_item_limit": 25}}}}"#,
+            builds.join(",")
+        ),
+    )
+    .unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .current_dir(repo.path())
+        .args(["builds", "--app", "demo-app", "--no-pager"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("build-4\n"), "{stdout}");
+    assert!(stdout.contains("build-3  ↑ includes HEAD"), "{stdout}");
+    assert!(stdout.contains("build-2  ● HEAD"), "{stdout}");
+    assert!(stdout.contains("build-1  ↓ behind HEAD"), "{stdout}");
+
+    reprise(home.path(), fixtures.path())
+        .current_dir(repo.path())
+        .args(["builds", "--app", "demo-app", "--no-pager", "--no-git"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("HEAD").not());
+}
+
+/// Builds of main, a deleted branch and a tag
+fn record_branch_builds(dir: &Path) -> reprise::error::Result<()> {
+    let build = |n: i64, branch: &str, tag: &str| {
+        format!(
+            r#"{{"slug": "build-{n}", "build_number": {n}, "status": 1, "status_text": "success",
+                "triggered_at": "2024-01-01T12:00:00Z", "branch": "{branch}",
+                "triggered_workflow": "primary", "tag": {tag}}}"#
+        )
+    };
+    let builds = [
+        build(4, "main", "null"),
+        build(3, "feature/old", "null"),
+        build(2, "", r#""v1.0""#),
+        build(1, "feature/new", "null"),
+    ];
+    record(
+        dir,
+        "GET",
+        "/apps/demo-app/builds?limit=50",
+        200,
+        &format!(
+            r#"{{"data": [{}], "paging": {{"total_item_count": 4, "page_item_limit": 50}}}}"#,
+            builds.join(",")
+        ),
+    )
+}
+
+/// Build numbers in `-o json` output of `reprise builds`
+fn build_numbers(stdout: &[u8]) -> Vec<i64> {
+    payload(stdout)
+        .ok()
+        .and_then(|builds| {
+            builds
+                .as_array()?
+                .iter()
+                .map(|b| b["build_number"].as_i64())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_replay_builds_stale_branches_from_bitrise() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let outside_repo = TempDir::new().unwrap();
+    record_branch_builds(fixtures.path()).unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/branches",
+        200,
+        r#"{"data": ["main", "feature/new"]}"#,
+    )
+    .unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .current_dir(outside_repo.path())
+        .args(["builds", "--stale-branches", "--app", "demo-app", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(build_numbers(&output.stdout), vec![3]);
+}
+
+#[test]
+fn test_replay_builds_stale_branches_from_origin() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let repo = TempDir::new().unwrap();
+    git(repo.path(), &["init", "--quiet"]).unwrap();
+    git(repo.path(), &["commit", "--quiet", "--allow-empty", "-m", "first"]).unwrap();
+    git(repo.path(), &["remote", "add", "origin", "git@github.com:acme/demo.git"]).unwrap();
+    git(repo.path(), &["update-ref", "refs/remotes/origin/main", "HEAD"]).unwrap();
+
+    record_branch_builds(fixtures.path()).unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app",
+        200,
+        r#"{"data": {"slug": "demo-app", "title": "Demo", "is_disabled": false, "status": 1,
+            "repo_url": "https://github.com/acme/demo",
+            "owner": {"account_type": "organization", "name": "acme", "slug": "acme"}}}"#,
+    )
+    .unwrap();
+
+    // Only main exists on origin; Bitrise's branch list isn't asked
+    let output = reprise(home.path(), fixtures.path())
+        .current_dir(repo.path())
+        .args(["builds", "--stale-branches", "--app", "demo-app", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(build_numbers(&output.stdout), vec![3, 1]);
+}
+
+#[test]
+fn test_replay_triggers_from_bitrise() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/bitrise.yml",
+        200,
+        &[
+            "format_version: '13'",
+            "trigger_map:",
+            "- pull_request_target_branch: main",
+            "  pipeline: ci",
+            "workflows:",
+            "  nightly:",
+            "    triggers:",
+            "      push:",
+            "      - branch: {regex: '^release/'}",
+        ]
+        .join("\n"),
+    )
+    .unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["triggers", "--app", "demo-app", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let triggers = payload(&output.stdout).unwrap();
+    assert_eq!(triggers[0]["event"], "pull_request");
+    assert_eq!(triggers[0]["target"], "ci");
+    assert_eq!(triggers[0]["target_kind"], "pipeline");
+    assert_eq!(triggers[0]["trigger_map"], true);
+    assert_eq!(triggers[0]["line"], 3);
+    assert_eq!(triggers[1]["target"], "nightly");
+    assert_eq!(triggers[1]["conditions"][0]["pattern"], "^release/");
+    assert_eq!(triggers[1]["conditions"][0]["regex"], true);
+}
+
+#[test]
+fn test_replay_yml_diff_between_builds() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let dir = fixtures.path();
+    for (slug, number, clone) in [("build-1", 11, "git-clone@6"), ("build-2", 12, "git-clone@8")] {
+        record(
+            dir,
+            "GET",
+            &format!("/apps/demo-app/builds/{slug}"),
+            200,
+            &format!(
+                r#"{{"data": {{"slug": "{slug}", "build_number": {number}, "status": 1,
+                    "status_text": "success", "triggered_at": "2024-01-01T12:00:00Z",
+                    "branch": "main", "triggered_workflow": "ci"}}}}"#
+            ),
+        )
+        .unwrap();
+        let yml = format!("workflows:\n  ci:\n    steps:\n    - {clone}: {{}}\n");
+        let path = format!("/apps/demo-app/builds/{slug}/bitrise.yml");
+        record(dir, "GET", &path, 200, &yml).unwrap();
+    }
+
+    reprise(home.path(), dir)
+        .args(["yml", "diff", "build-1", "build-2", "--app", "demo-app"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--- bitrise.yml (build #11, ci on main)"))
+        .stdout(predicate::str::contains("-    - git-clone@6: {}\n+    - git-clone@8: {}"));
+
+    let output = reprise(home.path(), dir)
+        .args(["yml", "diff", "build-2", "build-2", "--app", "demo-app", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let result = payload(&output.stdout).unwrap();
+    assert_eq!(result["changed"], false);
+    assert_eq!(result["to"]["build_number"], 12);
+}
+
+/// Two stacks and the machine types that run them
+fn record_stacks(dir: &Path) -> reprise::error::Result<()> {
+    record(
+        dir,
+        "GET",
+        "/apps/demo-app/stacks",
+        200,
+        r#"{"data": [
+            {"id": "osx-xcode-16.0.x", "title": "Xcode 16.0.x", "status": "stable",
+             "machine_types": ["g2.mac.medium", "g2.mac.large"]},
+            {"id": "linux-docker-android-22.04", "status": "edge",
+             "machine_types": ["standard"]}]}"#,
+    )?;
+    record(
+        dir,
+        "GET",
+        "/apps/demo-app/machine_types",
+        200,
+        r#"{"data": [
+            {"id": "g2.mac.medium", "cpu_count": 4, "ram": "12 GB", "credit_per_minute": 2},
+            {"id": "g2.mac.large", "cpu_count": 8},
+            {"id": "standard", "cpu_count": "4 vCPU"}]}"#,
+    )
+}
+
+#[test]
+fn test_replay_stacks_lists_stacks_and_machine_types() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_stacks(fixtures.path()).unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["stacks", "--app", "demo-app"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"osx-xcode-16\.0\.x\s+16\.0\s+stable").unwrap())
+        .stdout(predicate::str::contains("4 vCPU"));
+
+    // Checking one stack narrows the machine types to those that run it
+    let output = reprise(home.path(), fixtures.path())
+        .args(["stacks", "osx-xcode-16.0.x", "--app", "demo-app", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let json = payload(&output.stdout).unwrap();
+    assert_eq!(json["stacks"].as_array().map(Vec::len), Some(1));
+    assert_eq!(json["machine_types"].as_array().map(Vec::len), Some(2));
+
+    reprise(home.path(), fixtures.path())
+        .args(["stacks", "xcode-16", "--app", "demo-app"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("did you mean osx-xcode-16.0.x?"));
+}
+
+#[test]
+fn test_replay_app_and_org_members() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let dir = fixtures.path();
+    let members = r#"{"data": [
+        {"user_slug": "u1", "username": "ana", "email": "ana@example.com", "role": "owner"},
+        {"user_slug": "u2", "username": "ben", "email": "ben@example.com", "role": "developer"}]}"#;
+    record(dir, "GET", "/apps/demo-app/members", 200, members).unwrap();
+    record(dir, "GET", "/organizations/org-1/members", 200, members).unwrap();
+    let orgs = r#"{"data": [{"slug": "org-1", "name": "Acme Mobile"}]}"#;
+    record(dir, "GET", "/organizations", 200, orgs).unwrap();
+
+    reprise(home.path(), dir)
+        .args(["app", "members", "--app", "demo-app"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(1 owner, 1 developer)"))
+        .stdout(predicate::str::is_match(r"ben\s+developer\s+ben@example\.com").unwrap());
+
+    let output = reprise(home.path(), dir)
+        .args(["org", "members", "acme", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let members = payload(&output.stdout).unwrap();
+    assert_eq!(members[0]["username"], "ana");
+    assert_eq!(members[0]["role"], "owner");
+}
+
+#[test]
+fn test_replay_addons_lists_enabled_addons() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/addons",
+        200,
+        r#"{"data": [
+            {"id": "addons-ship", "title": "Release Management"},
+            {"id": "addons-testing", "title": "Test Reports", "is_enabled": true,
+             "plan": {"id": "free", "name": "Free"}}]}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["addons", "--app", "demo-app"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Test Reports"))
+        .stdout(predicate::str::contains("app/demo-app/addon/addons-testing/login"))
+        .stdout(predicate::str::contains("Release Management").not())
+        .stdout(predicate::str::contains("1 more available but not enabled"));
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["addons", "--all", "--app", "demo-app", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let json = payload(&output.stdout).unwrap();
+    assert_eq!(json[0]["id"], "addons-testing");
+    assert_eq!(json[1]["is_enabled"], false);
+    assert_eq!(
+        json[0]["url"],
+        "https://app.bitrise.io/app/demo-app/addon/addons-testing/login"
+    );
+}
+
+#[test]
+fn test_replay_release_add_build_replaces_candidate_with_yes() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let dir = fixtures.path();
+    let connected = "/release-management/v1/connected-apps?project_id=demo-app";
+    record(dir, "GET", connected, 200, r#"{"items": [{"id": "ca-1", "platform": "ios"}]}"#)
+        .unwrap();
+    record(
+        dir,
+        "GET",
+        "/release-management/v1/connected-apps/ca-1/releases",
+        200,
+        r#"{"items": [{"id": "rel-1", "version": "2.4.0", "status": "in_progress",
+            "release_candidate": {"build_slug": "build-0", "build_number": 11}}]}"#,
+    )
+    .unwrap();
+    record(
+        dir,
+        "GET",
+        "/apps/demo-app/builds/build-1",
+        200,
+        r#"{"data": {"slug": "build-1", "build_number": 12, "status": 1,
+            "status_text": "success", "triggered_at": "2024-01-01T12:00:00Z",
+            "branch": "release/2.4", "triggered_workflow": "deploy"}}"#,
+    )
+    .unwrap();
+    record(
+        dir,
+        "POST",
+        "/release-management/v1/connected-apps/ca-1/releases/rel-1/build",
+        200,
+        r#"{"id": "rel-1", "version": "2.4.0",
+            "release_candidate": {"build_slug": "build-1", "build_number": 12}}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), dir)
+        .args(["release", "list", "--app", "demo-app"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"2\.4\.0\s+in_progress\s+#11").unwrap());
+
+    // Replacing a candidate needs confirmation
+    reprise(home.path(), dir)
+        .args(["release", "add-build", "2.4.0", "build-1", "--app", "demo-app"])
+        .env("CI", "true")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("pass --yes to confirm"));
+
+    reprise(home.path(), dir)
+        .args(["release", "add-build", "2.4.0", "build-1", "--app", "demo-app", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Build #12 is the release candidate of 2.4.0"));
+}
+
+#[test]
+fn test_replay_release_needs_connected_app_choice() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/release-management/v1/connected-apps?project_id=demo-app",
+        200,
+        r#"{"items": [{"id": "ca-ios", "platform": "ios"},
+            {"id": "ca-android", "store_app_name": "Demo"}]}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["release", "list", "--app", "demo-app"])
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("ca-ios (ios), ca-android (Demo)"));
+}
+
+#[test]
+fn test_replay_deploys_reads_deploy_steps_from_bitrise_yml() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let dir = fixtures.path();
+    record(
+        dir,
+        "GET",
+        "/apps/demo-app/bitrise.yml",
+        200,
+        "format_version: '13'\nworkflows:\n  ship:\n    steps:\n    - google-play-deploy@3: {}\n  \
+         primary:\n    steps:\n    - script@1: {}\n",
+    )
+    .unwrap();
+    let build = |n: i64, workflow: &str| {
+        format!(
+            r#"{{"slug": "build-{n}", "build_number": {n}, "status": 1, "status_text": "success",
+                "triggered_at": "2024-03-01T12:00:00Z", "finished_at": "2024-03-01T12:20:00Z",
+                "branch": "main", "triggered_workflow": "{workflow}"}}"#
+        )
+    };
+    record(
+        dir,
+        "GET",
+        "/apps/demo-app/builds?limit=50&status=1",
+        200,
+        &format!(
+            r#"{{"data": [{}, {}], "paging": {{"total_item_count": 2, "page_item_limit": 50}}}}"#,
+            build(8, "ship"),
+            build(7, "primary")
+        ),
+    )
+    .unwrap();
+    record(
+        dir,
+        "GET",
+        "/apps/demo-app/builds/build-8/artifacts",
+        200,
+        r#"{"data": [{"title": "app-release.aab", "slug": "art-1", "artifact_type": "android-aab",
+            "is_public_page_enabled": false,
+            "artifact_meta": {"app_info": {"version_name": "3.1.0", "version_code": "310"}}}],
+            "paging": {"total_item_count": 1, "page_item_limit": 50}}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), dir)
+        .args(["deploys", "--app", "demo-app"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"#8\s+3\.1\.0 \(310\)\s+Google Play\s+ship").unwrap())
+        .stdout(predicate::str::contains("#7").not());
+
+    let output = reprise(home.path(), dir)
+        .args(["deploys", "--app", "demo-app", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let json = payload(&output.stdout).unwrap();
+    assert_eq!(json[0]["destinations"][0], "google_play");
+    assert_eq!(json.as_array().map(Vec::len), Some(1));
+}
+
+#[test]
+fn test_replay_dsyms_picks_dsym_artifacts() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let dir = fixtures.path();
+    let storage = "https://bitrise-prod-build-storage.s3.amazonaws.com";
+    let artifact = |slug: &str, title: &str| {
+        format!(
+            r#"{{"title": "{title}", "slug": "{slug}", "artifact_type": "file",
+                "is_public_page_enabled": false, "expiring_download_url": "{storage}/{slug}"}}"#
+        )
+    };
+    record(
+        dir,
+        "GET",
+        "/apps/demo-app/builds/build-1/artifacts",
+        200,
+        &format!(
+            r#"{{"data": [{}, {}], "paging": {{"total_item_count": 2, "page_item_limit": 50}}}}"#,
+            artifact("art-ipa", "App.ipa"),
+            artifact("art-dsym", "App.app.dSYM.zip")
+        ),
+    )
+    .unwrap();
+    record(
+        dir,
+        "GET",
+        "/apps/demo-app/builds/build-1/artifacts/art-dsym",
+        200,
+        &format!(r#"{{"data": {}}}"#, artifact("art-dsym", "App.app.dSYM.zip")),
+    )
+    .unwrap();
+
+    // Downloads aren't replayed, so the download itself fails after picking the dSYM
+    let symbols = home.path().join("symbols");
+    reprise(home.path(), dir)
+        .args(["dsyms", "build-1", "--app", "demo-app", "-d"])
+        .arg(&symbols)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Downloading App.app.dSYM.zip"))
+        .stderr(predicate::str::contains("App.ipa").not());
+
+    reprise(home.path(), dir)
+        .args(["dsyms", "build-1", "--app", "demo-app", "--filter", "*.txt"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No dSYM artifacts matching '*.txt'"));
+}
+
+#[test]
+fn test_replay_stacks_find_xcode() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_stacks(fixtures.path()).unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["stacks", "--find-xcode", "16", "--app", "demo-app", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let json = payload(&output.stdout).unwrap();
+    assert_eq!(json["stacks"][0]["id"], "osx-xcode-16.0.x");
+    assert_eq!(json["stacks"].as_array().map(Vec::len), Some(1));
+    assert_eq!(json["machine_types"].as_array().map(Vec::len), Some(2));
+
+    reprise(home.path(), fixtures.path())
+        .args(["stacks", "--find-xcode", "15.4", "--app", "demo-app"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No stack provides Xcode 15.4. Available: 16.0"));
+}
+
+#[test]
+fn test_replay_builds_triggered_by_partial_match() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_builds_by_user(fixtures.path()).unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["builds", "--triggered-by", "ALI", "--app", "demo-app", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(payload(&output.stdout).unwrap().as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn test_replay_activity_groups_builds_by_user() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let build = |n: i64, status: i32, by: &str, at: &str| {
+        format!(
+            r#"{{"slug": "build-{n}", "build_number": {n}, "status": {status},
+                "status_text": "done", "triggered_at": "{at}", "branch": "main",
+                "triggered_workflow": "primary", "commit_hash": "abc{}",
+                "credit_cost": 10, "triggered_by": "{by}"}}"#,
+            if n == 4 { 3 } else { n }
+        )
+    };
+    let builds = [
+        build(5, 1, "bob", "2024-01-20T12:00:00Z"),
+        build(4, 1, "alice", "2024-01-15T12:00:00Z"),
+        build(3, 2, "alice", "2024-01-10T12:00:00Z"),
+        build(2, 2, "alice", "2024-01-05T12:00:00Z"),
+        build(1, 1, "alice", "2023-12-20T12:00:00Z"),
+    ];
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=50",
+        200,
+        &format!(
+            r#"{{"data": [{}], "paging": {{"total_item_count": 5, "page_item_limit": 50,
+                "next": "p2"}}}}"#,
+            builds.join(",")
+        ),
+    )
+    .unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["activity", "--app", "demo-app", "--since", "2024-01-01", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let users = payload(&output.stdout).unwrap();
+    assert_eq!(users[0]["user"], "alice");
+    assert_eq!(users[0]["builds"], 3);
+    assert_eq!(users[0]["failed"], 2);
+    assert_eq!(users[0]["reruns"], 1);
+    assert_eq!(users[0]["credits"], 30);
+    assert_eq!(users[1]["user"], "bob");
+    assert_eq!(users[1]["failure_rate"], 0.0);
+
+    reprise(home.path(), fixtures.path())
+        .args(["activity", "--app", "demo-app", "--since", "2024-01-01"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Activity since 2024-01-01 (4 builds, 2 users)"))
+        .stdout(predicate::str::contains("FAIL %"));
+}
+
+#[test]
+fn test_config_set_rejected_token_is_not_saved() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(fixtures.path(), "GET", "/me", 401, r#"{"message": "Unauthorized"}"#).unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["config", "set", "api.token", "bad-token"])
+        .assert()
+        .code(78)
+        .stderr(predicate::str::contains("rejected this API token"));
+    assert!(!home.path().join(".reprise/config.toml").exists());
+}
+
+fn record_failed_pipeline(dir: &Path) -> reprise::error::Result<()> {
+    record(
+        dir,
+        "GET",
+        "/apps/demo-app/pipelines/pipe-1",
+        200,
+        r#"{"data": {"id": "pipe-1", "app_slug": "demo-app", "status": 2, "branch": "main",
+            "pipeline_id": "ci", "triggered_at": "2024-01-01T12:00:00Z",
+            "workflows": [
+                {"id": "wf-build-1", "name": "unit-tests", "status": 2},
+                {"id": "", "name": "deploy", "status": 3}
+            ]}}"#,
+    )?;
+    record(
+        dir,
+        "GET",
+        "/apps/demo-app/builds/wf-build-1",
+        200,
+        r#"{"data": {"slug": "wf-build-1", "build_number": 42, "status": 2,
+            "status_text": "error", "triggered_at": "2024-01-01T12:00:05Z",
+            "started_on_worker_at": "2024-01-01T12:00:10Z",
+            "finished_at": "2024-01-01T12:05:10Z",
+            "branch": "main", "triggered_workflow": "unit-tests"}}"#,
+    )
+}
+
+#[test]
+fn test_replay_pipeline_show_resolves_workflow_builds() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_failed_pipeline(fixtures.path()).unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["pipeline", "show", "pipe-1", "--app", "demo-app", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json = payload(&output.stdout).unwrap();
+    let workflows = json["workflow_builds"].as_array().unwrap();
+    assert_eq!(workflows[0]["build_number"], 42);
+    assert_eq!(workflows[0]["duration_secs"], 300);
+    assert_eq!(workflows[0]["url"], "https://app.bitrise.io/build/wf-build-1");
+    assert!(workflows[1]["build_slug"].is_null());
+}
+
+#[test]
+fn test_replay_pipeline_show_follow_failed_prints_log() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_failed_pipeline(fixtures.path()).unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds/wf-build-1/log",
+        200,
+        r#"{"log_chunks": [{"chunk": "Test Suite failed\n", "position": 0}],
+           "expiring_raw_log_url": null, "is_archived": false}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["pipeline", "show", "pipe-1", "--app", "demo-app", "--follow-failed"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Test Suite failed"))
+        .stderr(predicate::str::contains("unit-tests"));
+}
+
+#[test]
+fn test_replay_runs_merges_builds_and_pipelines() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=25",
+        200,
+        r#"{"data": [
+            {"slug": "build-new", "build_number": 12, "status": 0, "status_text": "running",
+             "triggered_at": "2024-01-01T14:00:00Z", "branch": "main", "triggered_workflow": "ci"},
+            {"slug": "build-old", "build_number": 11, "status": 1, "status_text": "success",
+             "triggered_at": "2024-01-01T10:00:00Z", "branch": "main", "triggered_workflow": "ci"}
+        ], "paging": {"total_item_count": 2, "page_item_limit": 25}}"#,
+    )
+    .unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/pipelines?limit=25",
+        200,
+        r#"{"data": [{"id": "pipe-1", "status": 1, "triggered_at": "2024-01-01T12:00:00Z",
+            "branch": "main", "pipeline_id": "release", "workflows": []}],
+            "paging": {"total_item_count": 1, "page_item_limit": 25}}"#,
+    )
+    .unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["runs", "--app", "demo-app", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let runs = payload(&output.stdout).unwrap();
+    let types: Vec<_> = runs
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["type"].as_str().unwrap())
+        .collect();
+    assert_eq!(types, ["build", "pipeline", "build"]);
+    assert_eq!(runs[1]["pipeline"]["id"], "pipe-1");
+}
+
+#[test]
+fn test_replay_runs_without_pipelines() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=25",
+        200,
+        r#"{"data": [{"slug": "build-1", "build_number": 7, "status": 1, "status_text": "success",
+            "triggered_at": "2024-01-01T10:00:00Z", "branch": "main", "triggered_workflow": "ci"}],
+            "paging": {"total_item_count": 1, "page_item_limit": 25}}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["runs", "--app", "demo-app"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("build"))
+        .stdout(predicate::str::contains("#7"));
+}
+
+#[test]
+fn test_replay_pipeline_watch_prints_plain_progress_when_piped() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_failed_pipeline(fixtures.path()).unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["pipeline", "watch", "pipe-1", "--app", "demo-app"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("-> Pipeline ci failed · 0/2 workflows"))
+        .stderr(predicate::str::contains("\x1b[2K").not());
+}
+
+#[test]
+fn test_replay_cache_status_and_clear() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let cache = reprise::cache::Cache::with_dir(home.path().join(".reprise/cache"));
+    cache.put("apps", &vec!["ios", "android"]).unwrap();
+    cache.put("prompt-status-ios-main", &()).unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["cache", "status", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json = payload(&output.stdout).unwrap();
+    let entries = json["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["key"], "apps");
+    assert_eq!(entries[0]["items"], 2);
+    assert!(json["total_bytes"].as_u64().unwrap() > 0);
+
+    reprise(home.path(), fixtures.path())
+        .args(["cache", "clear", "apps"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed apps"));
+    assert_eq!(cache.entries().len(), 1);
+
+    reprise(home.path(), fixtures.path())
+        .args(["cache", "clear"])
+        .assert()
+        .success();
+    assert!(cache.entries().is_empty());
+
+    reprise(home.path(), fixtures.path())
+        .args(["cache", "status"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Entries:  0"));
+}
+
+#[test]
+fn test_replay_cache_dir_from_env() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let dir = TempDir::new().unwrap();
+    reprise::cache::Cache::with_dir(dir.path()).put("apps", &vec!["ios"]).unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .env("REPRISE_CACHE_DIR", dir.path())
+        .args(["cache", "status", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json = payload(&output.stdout).unwrap();
+    assert_eq!(json["location"], dir.path().display().to_string());
+    assert_eq!(json["entries"][0]["key"], "apps");
+}
+
+#[test]
+fn test_replay_finished_log_is_cached() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let empty = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds/build-1/log",
+        200,
+        r#"{"log_chunks": [{"chunk": "Build succeeded\n", "position": 0}],
+           "expiring_raw_log_url": null, "is_archived": true}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["log", "build-1", "--app", "demo-app"])
+        .assert()
+        .success();
+
+    // Served from the log cache without a recorded response
+    reprise(home.path(), empty.path())
+        .args(["log", "build-1", "--app", "demo-app"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Build succeeded"));
+
+    reprise(home.path(), empty.path())
+        .args(["cache", "clear", "logs"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("logs (1 build)"));
+
+    reprise(home.path(), empty.path())
+        .args(["log", "build-1", "--app", "demo-app"])
+        .assert()
+        .failure();
+}
+
+/// Record a finished build whose log is served from a raw log URL
+fn record_raw_log(dir: &Path, body: &str) -> reprise::error::Result<()> {
+    let url = "https://bitrise-build-log-archives.s3.amazonaws.com/build-1.log";
+    record(
+        dir,
+        "GET",
+        "/apps/demo-app/builds/build-1/log",
+        200,
+        &format!(r#"{{"log_chunks": [], "expiring_raw_log_url": "{url}", "is_archived": false}}"#),
+    )?;
+    record(dir, "GET", url, 200, body)
+}
+
+#[test]
+fn test_replay_log_save_streams_to_file() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_raw_log(fixtures.path(), "line 1\nline 2\n\u{1b}[31mline 3\u{1b}[0m\n").unwrap();
+    let path = home.path().join("build.log");
+
+    reprise(home.path(), fixtures.path())
+        .args(["log", "build-1", "--app", "demo-app", "--save"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("line 1").and(predicate::str::contains("line 3")))
+        .stderr(predicate::str::contains("Log saved to"));
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "line 1\nline 2\n\u{1b}[31mline 3\u{1b}[0m\n"
+    );
+
+    reprise(home.path(), fixtures.path())
+        .args(["log", "build-1", "--app", "demo-app", "--strip-ansi", "--tail", "2", "--save"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::diff("line 2\nline 3\n"));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "line 1\nline 2\nline 3\n");
+}
+
+#[test]
+fn test_replay_log_save_dir_filtered_builds() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=2&status=2&branch=main",
+        200,
+        r#"{"data": [{
+            "slug": "build-1",
+            "build_number": 42,
+            "status": 2,
+            "status_text": "error",
+            "triggered_at": "2024-01-01T12:00:00Z",
+            "branch": "main",
+            "triggered_workflow": "primary"
+        }], "paging": {"total_item_count": 1, "page_item_limit": 2}}"#,
+    )
+    .unwrap();
+    record_raw_log(fixtures.path(), "failed step\n").unwrap();
+    let dir = home.path().join("logs");
+
+    reprise(home.path(), fixtures.path())
+        .args(["log", "--app", "demo-app", "--branch", "main", "--status", "failed"])
+        .args(["--limit", "2", "--save-dir"])
+        .arg(&dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Saved 1 log to"));
+    assert_eq!(
+        std::fs::read_to_string(dir.join("app-demo-app-build-42.log")).unwrap(),
+        "failed step\n"
+    );
+}
+
+#[test]
+fn test_replay_log_tail() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_raw_log(fixtures.path(), "a\nb\nc\nd").unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["log", "build-1", "--app", "demo-app", "--tail", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::diff("c\nd\n"));
+}
+
+#[test]
+fn test_replay_export_builds_pages_through_history() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let build = |slug: &str, number: u32, message: &str| {
+        format!(
+            r#"{{"slug": "{slug}", "build_number": {number}, "status": 1,
+                "status_text": "success", "triggered_at": "2024-01-0{number}T12:00:00Z",
+                "branch": "main", "triggered_workflow": "primary",
+                "commit_message": "{message}"}}"#
+        )
+    };
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=50",
+        200,
+        &format!(
+            r#"{{"data": [{}], "paging": {{"total_item_count": 2, "page_item_limit": 50, "next": "cursor-2"}}}}"#,
+            build("build-2", 2, "Fix, then ship")
+        ),
+    )
+    .unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=50&next=cursor-2",
+        200,
+        &format!(
+            r#"{{"data": [{}], "paging": {{"total_item_count": 2, "page_item_limit": 50}}}}"#,
+            build("build-1", 1, "Initial")
+        ),
+    )
+    .unwrap();
+    let out = home.path().join("builds.csv");
+
+    reprise(home.path(), fixtures.path())
+        .args(["export", "builds", "--app", "demo-app", "--out"])
+        .arg(&out)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Exported 2 builds"));
+
+    let csv = std::fs::read_to_string(&out).unwrap();
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows.len(), 3);
+    assert!(rows[0].starts_with("build_number,slug,status"));
+    assert!(rows[1].starts_with("2,build-2,"));
+    assert!(rows[1].contains(",\"Fix, then ship\","));
+    assert!(rows[2].starts_with("1,build-1,"));
+}
+
+fn record_two_apps(dir: &Path) -> reprise::error::Result<()> {
+    let owner = r#""owner": {"account_type": "organization", "name": "acme", "slug": "acme"}"#;
+    record(
+        dir,
+        "GET",
+        "/apps?limit=50",
+        200,
+        &format!(
+            r#"{{"data": [
+                {{"slug": "ios-slug", "title": "Ignite iOS", "is_disabled": false, "status": 1, {owner}}},
+                {{"slug": "android-slug", "title": "Ignite Android", "is_disabled": false, "status": 1, {owner}}}
+            ], "paging": {{"total_item_count": 2, "page_item_limit": 100}}}}"#
+        ),
+    )
+}
+
+#[test]
+fn test_replay_url_reads_link_from_stdin() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/ios-slug",
+        200,
+        r#"{"data": {"slug": "ios-slug", "title": "Ignite iOS", "is_disabled": false, "status": 1,
+            "owner": {"account_type": "organization", "name": "acme", "slug": "acme"}}}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["url", "-"])
+        .write_stdin("Opened <https://app.bitrise.io/app/ios-slug> from chat\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Ignite iOS"));
+
+    reprise(home.path(), fixtures.path())
+        .args(["url", "-"])
+        .write_stdin("nothing to see\n")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("No URL found on stdin"));
+}
+
+#[test]
+fn test_replay_url_workflow_editor_lists_workflow_builds() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/ios-slug/builds?limit=10&workflow=deploy",
+        200,
+        r#"{"data": [{
+            "slug": "build-9", "build_number": 9, "status": 1, "status_text": "success",
+            "triggered_at": "2024-01-01T12:00:00Z", "branch": "main", "triggered_workflow": "deploy"
+        }], "paging": {"total_item_count": 1, "page_item_limit": 10}}"#,
+    )
+    .unwrap();
+
+    let link = "https://www.bitrise.io/app/ios-slug/workflow_editor#!/workflows?workflow_id=deploy";
+    let output = reprise(home.path(), fixtures.path())
+        .args(["url", link, "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let builds = payload(&output.stdout).unwrap();
+    assert_eq!(builds[0]["slug"], "build-9");
+}
+
+#[test]
+fn test_replay_url_build_app_is_indexed() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_two_apps(fixtures.path()).unwrap();
+    let build = r#"{"data": {"slug": "build-7", "build_number": 7, "status": 1,
+        "status_text": "success", "triggered_at": "2024-01-01T12:00:00Z",
+        "branch": "main", "triggered_workflow": "primary"}}"#;
+    record(fixtures.path(), "GET", "/apps/android-slug/builds/build-7", 200, build).unwrap();
+
+    // Cold lookup: the build is found by probing every app
+    reprise(home.path(), fixtures.path())
+        .args(["url", "https://app.bitrise.io/build/build-7"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Build #7"));
+    let cache = reprise::cache::Cache::with_dir(home.path().join(".reprise/cache"));
+    let index: serde_json::Value = cache.read("build-apps").unwrap().value;
+    assert_eq!(index["build-7"]["app_slug"], "android-slug");
+
+    // Warm lookup: only the indexed app is asked, so no app list is needed
+    let warm = TempDir::new().unwrap();
+    record(warm.path(), "GET", "/apps/android-slug/builds/build-7", 200, build).unwrap();
+    cache.remove("apps").unwrap();
+    reprise(home.path(), warm.path())
+        .args(["url", "https://app.bitrise.io/build/build-7"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Build #7"));
+}
+
+#[test]
+fn test_replay_app_resolved_by_partial_name() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_two_apps(fixtures.path()).unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/ios-slug/builds?limit=25",
+        200,
+        r#"{"data": [{
+            "slug": "build-1", "build_number": 77, "status": 1, "status_text": "success",
+            "triggered_at": "2024-01-01T12:00:00Z", "branch": "main", "triggered_workflow": "primary"
+        }], "paging": {"total_item_count": 1, "page_item_limit": 25}}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["builds", "--app", "ios", "-o", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"build_number\": 77"));
+}
+
+#[test]
+fn test_replay_ambiguous_app_name_is_an_error() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_two_apps(fixtures.path()).unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["builds", "--app", "ignite"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("'ignite' matches 2 apps"))
+        .stderr(predicate::str::contains("Ignite iOS (ios-slug)"));
+}
+
+#[test]
+fn test_replay_apps_filters_by_owner_type_and_enabled() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let app = |slug: &str, kind: &str, owner: &str, disabled: bool| {
+        format!(
+            r#"{{"slug": "{slug}", "title": "{slug}", "project_type": "{kind}", "is_disabled": {disabled},
+                "status": 1, "owner": {{"account_type": "organization", "name": "{owner}", "slug": "{owner}"}}}}"#
+        )
+    };
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps?limit=50",
+        200,
+        &format!(
+            r#"{{"data": [{}, {}], "paging": {{"total_item_count": 4, "page_item_limit": 50, "next": "cursor-2"}}}}"#,
+            app("acme-ios", "ios", "Acme", false),
+            app("acme-flutter-old", "flutter", "Acme", true),
+        ),
+    )
+    .unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps?limit=50&next=cursor-2",
+        200,
+        &format!(
+            r#"{{"data": [{}, {}], "paging": {{"total_item_count": 4, "page_item_limit": 50}}}}"#,
+            app("acme-flutter", "flutter", "Acme", false),
+            app("other-flutter", "flutter", "Other", false),
+        ),
+    )
+    .unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["apps", "--owner", "acme", "--type", "flutter", "--enabled", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json = payload(&output.stdout).unwrap();
+    let slugs: Vec<&str> = json.as_array().unwrap().iter().map(|a| a["slug"].as_str().unwrap()).collect();
+    assert_eq!(slugs, vec!["acme-flutter"]);
+}
+
+#[test]
+fn test_replay_apps_sort_by_activity_uses_cache() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let owner = r#""owner": {"account_type": "organization", "name": "acme", "slug": "acme"}"#;
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps?limit=50",
+        200,
+        &format!(
+            r#"{{"data": [
+                {{"slug": "idle", "title": "Idle", "is_disabled": false, "status": 1, {owner}}},
+                {{"slug": "old", "title": "Old", "is_disabled": false, "status": 1, {owner}}},
+                {{"slug": "busy", "title": "Busy", "is_disabled": false, "status": 1, {owner}}}
+            ], "paging": {{"total_item_count": 3, "page_item_limit": 50}}}}"#
+        ),
+    )
+    .unwrap();
+    for (slug, day) in [("old", "01"), ("busy", "09")] {
+        record(
+            fixtures.path(),
+            "GET",
+            &format!("/apps/{slug}/builds?limit=1"),
+            200,
+            &format!(
+                r#"{{"data": [{{"slug": "b-{slug}", "build_number": 1, "status": 1, "status_text": "success",
+                    "triggered_at": "2024-01-{day}T12:00:00Z", "branch": "main", "triggered_workflow": "primary"}}],
+                    "paging": {{"total_item_count": 1, "page_item_limit": 1}}}}"#
+            ),
+        )
+        .unwrap();
+    }
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/idle/builds?limit=1",
+        200,
+        r#"{"data": [], "paging": {"total_item_count": 0, "page_item_limit": 1}}"#,
+    )
+    .unwrap();
+
+    let slugs = |fixtures: &Path| -> Vec<String> {
+        let output = reprise(home.path(), fixtures)
+            .args(["apps", "--sort", "activity", "-o", "json"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json = payload(&output.stdout).unwrap();
+        json.as_array().unwrap().iter().map(|a| a["slug"].as_str().unwrap().to_string()).collect()
+    };
+    assert_eq!(slugs(fixtures.path()), vec!["busy", "old", "idle"]);
+
+    // Build times now come from the cache
+    for slug in ["old", "busy", "idle"] {
+        std::fs::remove_file(reprise::bitrise::transport::fixture_path(
+            fixtures.path(),
+            "GET",
+            &format!("/apps/{slug}/builds?limit=1"),
+        ))
+        .unwrap();
+    }
+    assert_eq!(slugs(fixtures.path()), vec!["busy", "old", "idle"]);
+}
+
+#[test]
+fn test_app_unset_and_recent_reselect() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    write_config(
+        home.path(),
+        r#"[defaults]
+app_slug = "ios-slug"
+app_name = "iOS"
+
+[[defaults.recent]]
+slug = "ios-slug"
+name = "iOS"
+
+[[defaults.recent]]
+slug = "android-slug"
+name = "Android"
+"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["app", "unset"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed default app: iOS"));
+    reprise(home.path(), fixtures.path())
+        .args(["app"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No default app set"));
+
+    reprise(home.path(), fixtures.path())
+        .args(["app", "recent"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2. Android"));
+    reprise(home.path(), fixtures.path())
+        .args(["app", "recent", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Default app set to: Android"));
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["app", "recent", "-o", "json"])
+        .output()
+        .unwrap();
+    let json = payload(&output.stdout).unwrap();
+    assert_eq!(json[0]["app_slug"], "android-slug");
+    assert_eq!(json[0]["current"], true);
+    assert_eq!(json[1]["app_slug"], "ios-slug");
+}
+
+#[test]
+fn test_replay_trigger_skip_if_running_reuses_build() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=50&status=0&branch=main&workflow=ci",
+        200,
+        r#"{"data": [{
+            "slug": "running-1", "build_number": 512, "status": 0, "status_text": "in-progress",
+            "triggered_at": "2024-01-01T12:00:00Z", "branch": "main", "triggered_workflow": "ci",
+            "commit_hash": "abc123def456"
+        }], "paging": {"total_item_count": 1, "page_item_limit": 50}}"#,
+    )
+    .unwrap();
+
+    // No trigger fixture is recorded, so a POST would fail the command
+    reprise(home.path(), fixtures.path())
+        .args(["trigger", "--app", "demo-app", "-w", "ci", "-b", "main", "--skip-if-running"])
+        .args(["--commit", "abc123"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Build #512 is already running"))
+        .stderr(predicate::str::contains("running-1"));
+}
+
+#[test]
+fn test_replay_trigger_invalid_at_fails_before_waiting() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["trigger", "--app", "demo-app", "-w", "ci", "--at", "25:00"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid time: '25:00'"));
+}
+
+/// Record a trigger of `ci` on main that returns `build-9`, already finished
+/// with `status`
+fn record_finished_trigger(dir: &Path, status: i32) -> reprise::error::Result<()> {
+    record(
+        dir,
+        "POST",
+        "/apps/demo-app/builds",
+        201,
+        r#"{"status": "ok", "message": "webhook processed", "build_slug": "build-9"}"#,
+    )?;
+    record(
+        dir,
+        "GET",
+        "/apps/demo-app/builds/build-9",
+        200,
+        &format!(
+            r#"{{"data": {{"slug": "build-9", "build_number": 9, "status": {status},
+                "status_text": "done", "triggered_at": "2024-01-01T12:00:00Z",
+                "started_on_worker_at": "2024-01-01T12:00:10Z",
+                "finished_at": "2024-01-01T12:05:10Z", "commit_hash": "abc123def456",
+                "branch": "main", "triggered_workflow": "ci"}}}}"#
+        ),
+    )
+}
+
+#[test]
+fn test_replay_trigger_then_pipeline_after_success() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_finished_trigger(fixtures.path(), 1).unwrap();
+    record(
+        fixtures.path(),
+        "POST",
+        "/apps/demo-app/pipelines",
+        201,
+        r#"{"status": "ok", "message": "pipeline started", "id": "pipe-7"}"#,
+    )
+    .unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/pipelines/pipe-7",
+        200,
+        r#"{"data": {"id": "pipe-7", "app_slug": "demo-app", "status": 0, "branch": "main",
+            "pipeline_id": "release", "triggered_at": "2024-01-01T12:06:00Z"}}"#,
+    )
+    .unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["trigger", "--app", "demo-app", "-w", "ci", "-b", "main"])
+        .args(["--then-pipeline", "release", "--interval", "1", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let json = payload(&output.stdout).unwrap();
+    assert_eq!(json["build"]["build_number"], 9);
+    assert_eq!(json["pipeline"]["id"], "pipe-7");
+}
+
+#[test]
+fn test_replay_trigger_then_pipeline_skipped_when_build_fails() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    // No pipeline fixture is recorded, so triggering one would fail the command
+    record_finished_trigger(fixtures.path(), 2).unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["trigger", "--app", "demo-app", "-w", "ci", "-b", "main"])
+        .args(["--then-pipeline", "release", "--interval", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Build failed"))
+        .stdout(predicate::str::contains("Pipeline release not triggered (--when success)"));
+}
+
+#[test]
+fn test_replay_trigger_wait_quiet_keeps_only_result() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_finished_trigger(fixtures.path(), 1).unwrap();
+    let trigger = ["trigger", "--app", "demo-app", "-w", "ci", "-b", "main", "--wait"];
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(trigger)
+        .args(["--interval", "1"])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("-> Waiting for build"), "{}", stderr);
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(trigger)
+        .args(["--interval", "1", "--quiet"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stderr.contains("->"), "{}", stderr);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Build completed successfully"), "{}", stdout);
+}
+
+#[test]
+fn test_replay_trigger_wait_runs_build_hooks() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_finished_trigger(fixtures.path(), 1).unwrap();
+    let out = home.path().join("hook.txt");
+    write_config(
+        home.path(),
+        &format!(
+            "[hooks]\n\
+             on_build_success = \"echo {{number}} {{status}} $REPRISE_BUILD_BRANCH > '{}'\"\n\
+             on_build_failure = \"exit 1\"\n",
+            out.display()
+        ),
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["trigger", "--app", "demo-app", "-w", "ci", "-b", "main"])
+        .args(["--wait", "--interval", "1"])
+        .assert()
+        .success();
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), "9 success main\n");
+}
+
+#[test]
+fn test_replay_failing_hook_only_warns() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record_finished_trigger(fixtures.path(), 2).unwrap();
+    write_config(home.path(), "[hooks]\non_build_failure = \"exit 3\"\n").unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["trigger", "--app", "demo-app", "-w", "ci", "-b", "main"])
+        .args(["--wait", "--interval", "1"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("warning: on_build_failure hook failed"));
+}
+
+#[test]
+fn test_replay_watch_daemon_reports_status_change() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let builds = |newest: i64, status: i32| {
+        format!(
+            r#"{{"data": [
+                {{"slug": "build-{running}", "build_number": {running}, "status": 0,
+                  "status_text": "in-progress", "triggered_at": "2024-01-01T13:00:00Z",
+                  "branch": "main", "triggered_workflow": "ci"}},
+                {{"slug": "build-{newest}", "build_number": {newest}, "status": {status},
+                  "status_text": "done", "triggered_at": "2024-01-01T12:00:00Z",
+                  "branch": "main", "triggered_workflow": "ci"}}
+            ], "paging": {{"total_item_count": 2, "page_item_limit": 10}}}}"#,
+            running = newest + 1
+        )
+    };
+    let key = "/apps/demo-app/builds?limit=10&branch=main";
+    record(fixtures.path(), "GET", key, 200, &builds(1, 1)).unwrap();
+    let watch = ["watch-daemon", "demo-app:main", "--once", "--no-desktop"];
+
+    reprise(home.path(), fixtures.path())
+        .args(watch)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("#1 success · ci (now watching)"));
+
+    // The state survives between runs: the same build isn't reported twice
+    reprise(home.path(), fixtures.path()).args(watch).assert().success().stdout("");
+
+    record(fixtures.path(), "GET", key, 200, &builds(2, 2)).unwrap();
+    let output = reprise(home.path(), fixtures.path())
+        .args(watch)
+        .args(["-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let event: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(event["target"], "demo-app:main");
+    assert_eq!(event["event"], "changed");
+    assert_eq!(event["previous_status"], "success");
+    assert_eq!(event["build"]["build_number"], 2);
+}
+
+#[test]
+fn test_replay_watch_daemon_needs_targets() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["watch-daemon", "--once"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("daemon.targets"));
+}
+
+#[test]
+fn test_replay_otel_export_dry_run() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds/build-1",
+        200,
+        r#"{"data": {"slug": "build-1", "build_number": 12, "status": 2,
+            "status_text": "error", "triggered_at": "2024-01-01T12:00:00Z",
+            "started_on_worker_at": "2024-01-01T12:01:00Z",
+            "finished_at": "2024-01-01T12:06:00Z",
+            "branch": "main", "triggered_workflow": "ci"}}"#,
+    )
+    .unwrap();
+    let log = concat!(
+        r"| (0) git-clone@8 |\n",
+        r"| ✓ | git-clone@8 | 30.0 sec |\n",
+        r"| (1) xcode-test@5 |\n",
+        r"| x | xcode-test@5 | 4.5 min |\n",
+    );
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds/build-1/log",
+        200,
+        &format!(
+            r#"{{"log_chunks": [{{"chunk": "{log}", "position": 0}}],
+                "expiring_raw_log_url": null, "is_archived": false}}"#
+        ),
+    )
+    .unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["otel", "export", "build-1", "--app", "demo-app", "--dry-run"])
+        .env_remove("OTEL_EXPORTER_OTLP_ENDPOINT")
+        .env_remove("OTEL_SERVICE_NAME")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let payload: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let resource = &payload["resourceSpans"][0];
+    assert_eq!(resource["resource"]["attributes"][0]["value"]["stringValue"], "bitrise");
+    let spans = resource["scopeSpans"][0]["spans"].as_array().unwrap();
+    let names: Vec<&str> = spans.iter().filter_map(|s| s["name"].as_str()).collect();
+    assert_eq!(names, ["ci #12", "queued", "git-clone@8", "xcode-test@5"]);
+    assert_eq!(spans[3]["status"]["code"], 2);
+}
+
+#[test]
+fn test_replay_otel_export_needs_endpoint() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["otel", "export", "build-1", "--app", "demo-app"])
+        .env_remove("OTEL_EXPORTER_OTLP_ENDPOINT")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--endpoint"));
+}
+
+#[test]
+fn test_replay_benchmark_reports_percentiles() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    let owner = r#""owner": {"account_type": "organization", "name": "acme", "slug": "acme"}"#;
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps?limit=10",
+        200,
+        &format!(
+            r#"{{"data": [{{"slug": "demo-app", "title": "Demo", "is_disabled": false,
+                "status": 1, {owner}}}],
+                "paging": {{"total_item_count": 1, "page_item_limit": 10}}}}"#
+        ),
+    )
+    .unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds?limit=10",
+        200,
+        r#"{"data": [{"slug": "build-1", "build_number": 1, "status": 1,
+            "status_text": "success", "triggered_at": "2024-01-01T12:00:00Z",
+            "branch": "main", "triggered_workflow": "ci"}],
+            "paging": {"total_item_count": 1, "page_item_limit": 10}}"#,
+    )
+    .unwrap();
+    record(
+        fixtures.path(),
+        "GET",
+        "/apps/demo-app/builds/build-1/log",
+        200,
+        r#"{"log_chunks": [], "expiring_raw_log_url": null, "is_archived": true}"#,
+    )
+    .unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["benchmark", "--app", "demo-app", "-n", "3"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("API Latency for demo-app (3 requests each)"))
+        .stdout(predicate::str::contains("log metadata"));
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["benchmark", "--app", "demo-app", "-n", "3", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let report = payload(&output.stdout).unwrap();
+    let measurements = report["measurements"].as_array().unwrap();
+    let endpoints: Vec<&str> =
+        measurements.iter().filter_map(|m| m["endpoint"].as_str()).collect();
+    assert_eq!(endpoints, ["list apps", "list builds", "log metadata"]);
+    for m in measurements {
+        assert_eq!(m["requests"], 3);
+        assert_eq!(m["errors"], 0);
+        assert!(m["p50_ms"].as_f64().is_some() && m["p95_ms"].as_f64().is_some());
+    }
+}
+
+#[test]
+fn test_config_export_no_secrets_then_import_keeps_token() {
+    let home = TempDir::new().unwrap();
+    let fixtures = TempDir::new().unwrap();
+    write_config(
+        home.path(),
+        "[api]\ntoken = \"secret-token\"\n\n[defaults]\napp_slug = \"demo-app\"\n\n\
+         [hooks]\non_build_failure = \"say failed\"\n",
+    )
+    .unwrap();
+
+    let output = reprise(home.path(), fixtures.path())
+        .args(["config", "export", "--no-secrets", "-o", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let exported = home.path().join("team.toml");
+    std::fs::write(&exported, &output.stdout).unwrap();
+
+    reprise(home.path(), fixtures.path())
+        .args(["config", "import", exported.to_str().unwrap()])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Skipped [hooks]"));
+
+    let saved = std::fs::read_to_string(home.path().join(".reprise/config.toml")).unwrap();
+    assert!(saved.contains("token = \"secret-token\""), "{}", saved);
+    assert!(saved.contains("app_slug = \"demo-app\""), "{}", saved);
+}